@@ -14,6 +14,46 @@ fn main() {
         }
     }
 
+    // Generate the gRPC service definitions
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/simulation.proto");
+        tonic_build::compile_protos("proto/simulation.proto").expect("Failed to compile proto/simulation.proto");
+    }
+
+    // Spout sender shim; SPOUT_SDK_DIR points at the Spout2 SDK's SpoutDX folder
+    #[cfg(feature = "spout")]
+    if env::var("CARGO_CFG_TARGET_OS").unwrap_or_default() == "windows" {
+        let sdk = env::var("SPOUT_SDK_DIR").expect("SPOUT_SDK_DIR must point at the SpoutDX SDK for the spout feature");
+        println!("cargo:rerun-if-changed=native/spout_sender.cpp");
+        println!("cargo:rerun-if-env-changed=SPOUT_SDK_DIR");
+        cc::Build::new()
+            .cpp(true)
+            .include(&sdk)
+            .file("native/spout_sender.cpp")
+            .compile("inochi_spout");
+        println!("cargo:rustc-link-search=native={}", sdk);
+        println!("cargo:rustc-link-lib=SpoutDX");
+        println!("cargo:rustc-link-lib=d3d11");
+    }
+
+    // Syphon server shim; SYPHON_FRAMEWORK_DIR is the folder holding Syphon.framework
+    #[cfg(feature = "syphon")]
+    if env::var("CARGO_CFG_TARGET_OS").unwrap_or_default() == "macos" {
+        let frameworks = env::var("SYPHON_FRAMEWORK_DIR").unwrap_or_else(|_| "/Library/Frameworks".to_string());
+        println!("cargo:rerun-if-changed=native/syphon_server.m");
+        println!("cargo:rerun-if-env-changed=SYPHON_FRAMEWORK_DIR");
+        cc::Build::new()
+            .file("native/syphon_server.m")
+            .flag("-fobjc-arc")
+            .flag(&format!("-F{}", frameworks))
+            .compile("inochi_syphon");
+        println!("cargo:rustc-link-search=framework={}", frameworks);
+        println!("cargo:rustc-link-lib=framework=Syphon");
+        println!("cargo:rustc-link-lib=framework=Metal");
+        println!("cargo:rustc-link-lib=framework=Foundation");
+    }
+
     // Set version info
     println!("cargo:rustc-env=CARGO_PKG_VERSION_BUILD={}", env::var("GITHUB_SHA").unwrap_or_else(|_| "dev".to_string()));
 }
\ No newline at end of file
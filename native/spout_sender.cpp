@@ -0,0 +1,29 @@
+// C entry points for src/spout.rs around the SpoutDX sender from the Spout2 SDK
+#include "SpoutDX.h"
+
+extern "C" {
+
+void* inochi_spout_create(const char* name) {
+    spoutDX* sender = new spoutDX();
+    if (!sender->OpenDirectX11()) {
+        delete sender;
+        return nullptr;
+    }
+    sender->SetSenderName(name);
+    // Frames arrive as RGBA8, rows top to bottom
+    sender->SetSenderFormat(DXGI_FORMAT_R8G8B8A8_UNORM);
+    return sender;
+}
+
+bool inochi_spout_send(void* handle, const unsigned char* pixels, unsigned int width, unsigned int height) {
+    return static_cast<spoutDX*>(handle)->SendImage(pixels, width, height);
+}
+
+void inochi_spout_destroy(void* handle) {
+    spoutDX* sender = static_cast<spoutDX*>(handle);
+    sender->ReleaseSender();
+    sender->CloseDirectX11();
+    delete sender;
+}
+
+}
@@ -0,0 +1,65 @@
+// C entry points for src/syphon.rs around SyphonMetalServer. Built with ARC.
+#import <Foundation/Foundation.h>
+#import <Metal/Metal.h>
+#import <Syphon/Syphon.h>
+
+@interface InochiSyphonServer : NSObject
+@property(nonatomic, strong) id<MTLDevice> device;
+@property(nonatomic, strong) id<MTLCommandQueue> queue;
+@property(nonatomic, strong) id<MTLTexture> texture;
+@property(nonatomic, strong) SyphonMetalServer *server;
+@end
+
+@implementation InochiSyphonServer
+@end
+
+void *inochi_syphon_create(const char *name) {
+    @autoreleasepool {
+        id<MTLDevice> device = MTLCreateSystemDefaultDevice();
+        if (device == nil) {
+            return NULL;
+        }
+        InochiSyphonServer *handle = [InochiSyphonServer new];
+        handle.device = device;
+        handle.queue = [device newCommandQueue];
+        handle.server = [[SyphonMetalServer alloc] initWithName:[NSString stringWithUTF8String:name] device:device options:nil];
+        if (handle.server == nil) {
+            return NULL;
+        }
+        return (__bridge_retained void *)handle;
+    }
+}
+
+bool inochi_syphon_publish(void *pointer, const uint8_t *pixels, uint32_t width, uint32_t height) {
+    @autoreleasepool {
+        InochiSyphonServer *handle = (__bridge InochiSyphonServer *)pointer;
+
+        // The upload texture is remade whenever the output resolution changes
+        if (handle.texture == nil || handle.texture.width != width || handle.texture.height != height) {
+            MTLTextureDescriptor *descriptor = [MTLTextureDescriptor texture2DDescriptorWithPixelFormat:MTLPixelFormatRGBA8Unorm
+                                                                                                  width:width
+                                                                                                 height:height
+                                                                                              mipmapped:NO];
+            descriptor.usage = MTLTextureUsageShaderRead;
+            handle.texture = [handle.device newTextureWithDescriptor:descriptor];
+            if (handle.texture == nil) {
+                return false;
+            }
+        }
+
+        [handle.texture replaceRegion:MTLRegionMake2D(0, 0, width, height) mipmapLevel:0 withBytes:pixels bytesPerRow:width * 4];
+
+        id<MTLCommandBuffer> commands = [handle.queue commandBuffer];
+        // Rows arrive top to bottom, which is already Metal's texture origin
+        [handle.server publishFrameTexture:handle.texture onCommandBuffer:commands imageRegion:NSMakeRect(0, 0, width, height) flipped:NO];
+        [commands commit];
+        // The next frame overwrites the same texture, so wait for Syphon's copy of this one
+        [commands waitUntilCompleted];
+        return true;
+    }
+}
+
+void inochi_syphon_destroy(void *pointer) {
+    InochiSyphonServer *handle = (__bridge_transfer InochiSyphonServer *)pointer;
+    [handle.server stop];
+}
@@ -0,0 +1,156 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::config::SpeciesRegistry;
+use crate::particle::Particle;
+use crate::spatial::SpatialPartitioning;
+
+// Particles as agents with a small state machine: the state is the species, so each state has its
+// own colour and force rules, and particles switch species depending on their neighbours. All
+// agents decide from the same snapshot, like the cells of a cellular automaton.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentConfig {
+    pub enabled: bool,
+    // Particles closer than this count as neighbours
+    pub radius: f32,
+    // Checked in order; the first rule that fires decides the particle's next state
+    pub rules: Vec<StateRule>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StateRule {
+    pub from: u32,
+    pub to: u32,
+    // State the neighbours are counted in; None makes the rule depend on chance alone
+    pub neighbor: Option<u32>,
+    pub min_neighbors: usize,
+    // Probability per step that a particle meeting the condition switches
+    pub chance: f32,
+}
+
+impl Default for AgentConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            radius: 25.0,
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl StateRule {
+    pub fn new(from: u32, to: u32) -> Self {
+        Self { from, to, neighbor: None, min_neighbors: 0, chance: 1.0 }
+    }
+
+    pub fn when_neighbors(mut self, neighbor: u32, min_neighbors: usize) -> Self {
+        self.neighbor = Some(neighbor);
+        self.min_neighbors = min_neighbors;
+        self
+    }
+
+    pub fn with_chance(mut self, chance: f32) -> Self {
+        self.chance = chance;
+        self
+    }
+}
+
+impl AgentConfig {
+    // Moves every particle one step through its state machine, returning how many switched. With
+    // a spatial index only nearby particles are checked as neighbours.
+    pub fn step<R: Rng>(&self, particles: &mut [Particle], species: &SpeciesRegistry, spatial: Option<&SpatialPartitioning>, rng: &mut R) -> usize {
+        if !self.enabled || self.rules.is_empty() {
+            return 0;
+        }
+        let radius_sq = self.radius * self.radius;
+        let next: Vec<Option<u32>> = particles
+            .iter()
+            .enumerate()
+            .map(|(index, particle)| {
+                let mut rules = self.rules.iter().filter(|rule| rule.from == particle.species_id).peekable();
+                rules.peek()?;
+                let candidates = match spatial {
+                    Some(spatial) => spatial.query_neighbors(particle.position, self.radius),
+                    None => (0..particles.len()).collect(),
+                };
+                let neighbors: Vec<u32> = candidates
+                    .into_iter()
+                    .filter(|&other| other != index)
+                    .filter_map(|other| particles.get(other))
+                    .filter(|other| other.position.distance_squared(particle.position) < radius_sq)
+                    .map(|other| other.species_id)
+                    .collect();
+                rules
+                    .find(|rule| {
+                        let count = rule.neighbor.map_or(0, |neighbor| neighbors.iter().filter(|&&state| state == neighbor).count());
+                        count >= rule.min_neighbors && rng.gen::<f32>() < rule.chance
+                    })
+                    .map(|rule| rule.to)
+            })
+            .collect();
+
+        let mut switched = 0;
+        for (particle, state) in particles.iter_mut().zip(next) {
+            if let Some(state) = state.filter(|&state| state != particle.species_id) {
+                particle.species_id = state;
+                particle.color = species.color(state);
+                switched += 1;
+            }
+        }
+        switched
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec2;
+
+    #[test]
+    fn test_rules_count_neighbors_within_radius() {
+        let agents = AgentConfig {
+            enabled: true,
+            radius: 10.0,
+            rules: vec![StateRule::new(0, 1).when_neighbors(1, 2)],
+        };
+        let registry = SpeciesRegistry::default();
+        let mut rng = crate::rng::fork();
+        let mut particles = vec![
+            Particle::new(Vec2::ZERO),
+            Particle::new(Vec2::new(5.0, 0.0)).with_species(1),
+            Particle::new(Vec2::new(15.0, 0.0)).with_species(1),
+        ];
+        // Only one neighbour is close enough
+        assert_eq!(agents.step(&mut particles, &registry, None, &mut rng), 0);
+
+        particles[2].position = Vec2::new(0.0, 8.0);
+        assert_eq!(agents.step(&mut particles, &registry, None, &mut rng), 1);
+        assert_eq!(particles[0].species_id, 1);
+        assert_eq!(particles[0].color, registry.color(1));
+    }
+
+    #[test]
+    fn test_agents_decide_from_the_same_snapshot() {
+        // A chain 0 -> 1 -> 2 advances one link per step rather than cascading
+        let agents = AgentConfig {
+            enabled: true,
+            radius: 10.0,
+            rules: vec![
+                StateRule::new(0, 1).when_neighbors(1, 1),
+                StateRule::new(1, 2),
+            ],
+        };
+        let registry = SpeciesRegistry::default();
+        let mut rng = crate::rng::fork();
+        let mut particles: Vec<Particle> = (0..3)
+            .map(|i| Particle::new(Vec2::new(i as f32 * 8.0, 0.0)))
+            .collect();
+        particles[0].species_id = 1;
+
+        agents.step(&mut particles, &registry, None, &mut rng);
+        let states: Vec<u32> = particles.iter().map(|p| p.species_id).collect();
+        assert_eq!(states, vec![2, 1, 0]);
+        agents.step(&mut particles, &registry, None, &mut rng);
+        let states: Vec<u32> = particles.iter().map(|p| p.species_id).collect();
+        assert_eq!(states, vec![2, 2, 1]);
+    }
+}
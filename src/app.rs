@@ -0,0 +1,4812 @@
+use nannou::prelude::*;
+use nannou_egui::{self, egui, Egui};
+use glam::Vec2;
+use rand::Rng;
+use crate::particle::{Particle, ParticleEvent, ParticleSystem};
+use crate::pool::ParticleId;
+use crate::forces::{PhysicsEngine, IntegrationMethod, ForceType, CurvePoint, DragModel};
+use crate::renderer::ParticleRenderer;
+use crate::config::{Background, ConfigManager, InitialVelocity, Palette, ParticleRenderMode, SimulationConfig, SpawnArea, Preset, UiTheme};
+use crate::presets::{ConfigFile, PresetManager, PresetThumbnail, CustomPresetData};
+use crate::spatial::SpatialPartitioning;
+use crate::chunk::ChunkStreamer;
+use crate::transition::{MorphSettings, PresetTransition};
+use crate::audio::{AudioReactor, AudioSource};
+use crate::forces::{ForceKind, INVERSE_SQUARE_FORCES, SOFTENED_FORCES};
+use crate::control::ControlTarget;
+use crate::midi::{MidiBindings, MidiController};
+use crate::osc::{OscCommand, OscListener};
+use crate::metrics::{MetricsServer, SimulationMetrics, METRICS_PATH};
+use crate::autosave::{Autosave, SessionSnapshot};
+use crate::crash::{self, CrashNotice};
+use crate::output::{FrameCapture, OutputManager};
+use crate::ndi::NdiSink;
+use crate::spout::SpoutSink;
+use crate::syphon::SyphonSink;
+use crate::history::SimulationHistory;
+use crate::picking::PickingBuffer;
+use crate::keymap::{Action, KeyMap};
+use crate::notifications::{Notifications, NotificationLevel};
+use crate::i18n::{tr, Language};
+use crate::layout::{WindowLayouts, DEFAULT_LAYOUT_FILE};
+use crate::display::{DisplayConfig, DEFAULT_DISPLAY_FILE};
+use crate::perf::{self, FrameHistory, FrameSample, HISTORY_SECONDS};
+use crate::profiling::profile_span;
+use crate::field::ForceField;
+use crate::noise::CurlNoiseVelocity;
+use crate::cloth::{update_cloth, Cloth};
+use crate::rigid::{update_rigid_groups, RigidGroup};
+use crate::pheromone::{update_pheromones, PheromoneField};
+use crate::reaction::{update_reaction, ReactionField};
+use crate::rulecode::RuleCode;
+use crate::heatmap::{InteractionHeatmap, DEFAULT_HEATMAP_FILE};
+use crate::demo::{DemoMode, DemoStep};
+use crate::community::{CommunityClient, CommunityEntry, CommunityReply, EntryKind, EntrySummary, Submission};
+use crate::scene::{CameraState, Scene, SceneAction, SceneEvent, SceneScript};
+use crate::image_import::{ImageImportOptions, ThresholdChannel};
+use crate::stamp::{ShapeHold, StampMode, StampOptions, TextStampOptions};
+use crate::brush::{BrushOptions, BrushShape, ShapeSampler};
+use crate::measure::Measurement;
+use crate::probe::{Probe, ProbeDrag, ProbeShape};
+use crate::grab::{Grab, GrabOptions};
+use crate::selection::{Selection, SelectionMode, SelectionShape};
+use crate::clipboard::ParticleClipboard;
+use crate::zone::{Zone, ZoneEffect, ZoneShape};
+use crate::well::{well_at, GravityWell};
+use crate::units;
+use crate::comparison::IntegratorComparison;
+use crate::formation::{FormationOptions, FormationSource};
+use crate::steering::{SteeringBehavior, SteeringPath, WeightedBehavior};
+use crate::embed::EmbedOptions;
+use crate::evolution::{Evolution, EvolutionConfig, EvolutionRunner, Fitness, FitnessKind};
+use crate::{audio, config, image_import, midi, osc, rng, stamp, transition};
+use std::cell::Cell;
+use std::collections::HashMap;
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+
+const THUMBNAIL_SIZE: [usize; 2] = [160, 100];
+const THUMBNAIL_PREVIEW_STEPS: usize = 60;
+const MIN_TIME_SCALE: f32 = 0.1;
+const MAX_TIME_SCALE: f32 = 10.0;
+// Wall-clock time spent on warm-up steps per frame, so the progress bar keeps moving
+const WARM_UP_FRAME_BUDGET: std::time::Duration = std::time::Duration::from_millis(30);
+// Points in a Profile converted from another pair force
+const PROFILE_POINTS: usize = 8;
+// Stable ids for windows whose layout is persisted; titles change with the UI language
+const PERFORMANCE_WINDOW: &str = "performance";
+const SETTINGS_WINDOW: &str = "settings";
+const FORCE_EDITOR_WINDOW: &str = "force_editor";
+const HOTKEYS_WINDOW: &str = "hotkeys";
+const PRESET_GALLERY_WINDOW: &str = "preset_gallery";
+const SCENES_WINDOW: &str = "scenes";
+const EVOLUTION_WINDOW: &str = "evolution";
+const PROBE_WINDOW: &str = "probe";
+const COMMUNITY_WINDOW: &str = "community";
+
+pub struct App {
+    pub particle_system: ParticleSystem,
+    pub physics_engine: PhysicsEngine,
+    pub renderer: ParticleRenderer,
+    pub config_manager: ConfigManager,
+    pub spatial: Option<SpatialPartitioning>,
+    pub pheromones: Option<PheromoneField>,
+    pub reaction: Option<ReactionField>,
+    pub cloth: Option<Cloth>,
+    // Taken out while the UI is built, since building it needs the rest of the app mutably
+    pub egui: Option<Egui>,
+    // The simulation window, and the window the control panels move to once it has been opened
+    pub window: nannou::window::Id,
+    pub controls_window: Option<nannou::window::Id>,
+    // Whether egui currently lives in controls_window rather than the simulation window
+    pub controls_separate: bool,
+    pub ui_state: UiState,
+    pub performance_stats: PerformanceStats,
+    pub time_accumulator: f32,
+    pub frame_count: u64,
+    pub paused: bool,
+    pub time_scale: f32,
+    pub step_requested: bool,
+    pub history: SimulationHistory,
+    pub sim_time: f32,
+    pub current_preset: Option<Preset>,
+    pub transition: Option<PresetTransition>,
+    pub audio: AudioReactor,
+    pub midi: MidiController,
+    pub osc: OscListener,
+    // Optional Prometheus endpoint and the figures it last published
+    pub metrics_server: MetricsServer,
+    pub metrics: SimulationMetrics,
+    pub autosave: Autosave,
+    // Session left behind by a crash, waiting on the restore prompt
+    pub pending_restore: Option<SessionSnapshot>,
+    // Crash report shown in a dialog until dismissed
+    pub crash_notice: Option<CrashNotice>,
+    pub output: OutputManager,
+    // Offscreen render target for the video outputs, made on first use at the output resolution
+    output_capture: Option<FrameCapture>,
+    pub notifications: Notifications,
+    pub scene_events: Vec<SceneEvent>,
+    pub scene_script: Option<SceneScript>,
+    pub shape_hold: Option<ShapeHold>,
+    // Stamps locked into rigid bodies
+    pub rigid_groups: Vec<RigidGroup>,
+    pub evolution: Option<EvolutionRunner>,
+    pub picking: PickingBuffer,
+    // Ghost run under a second integrator, stepped alongside the live one
+    pub comparison: Option<IntegratorComparison>,
+    // Warm-up steps done and total while a rebuilt preset is being pre-run
+    pub warm_up: Option<(usize, usize)>,
+    // Unattended preset cycling with the UI hidden
+    pub demo: Option<DemoMode>,
+    // Told about every particle event once per frame, e.g. the WASM bindings' JS callback
+    pub particle_listeners: Vec<Box<dyn FnMut(&ParticleEvent)>>,
+    // Splits physics between chunks near the camera and dormant ones, see PerformanceConfig::streaming
+    pub chunk_streamer: ChunkStreamer,
+}
+
+#[derive(Default)]
+pub struct UiState {
+    pub show_settings: bool,
+    pub show_performance: bool,
+    pub show_force_editor: bool,
+    pub selected_preset: usize,
+    pub selected_interaction: Option<(u32, u32)>,
+    pub selected_interaction_force: usize,
+    pub force_strength_slider: f32,
+    pub spawn_rate_slider: f32,
+    pub particle_count_slider: usize,
+    pub species_count_slider: usize,
+    pub show_preset_gallery: bool,
+    pub preset_thumbnails: HashMap<String, egui::TextureHandle>,
+    pub custom_presets: Option<Vec<CustomPresetData>>,
+    pub show_hotkeys: bool,
+    pub rebinding_action: Option<Action>,
+    pub native_pixels_per_point: f32,
+    pub applied_style: Option<(UiTheme, [u8; 3])>,
+    // Window settings last applied, and the names of the connected monitors
+    pub applied_display: Option<DisplayConfig>,
+    pub monitors: Vec<String>,
+    pub show_scenes: bool,
+    pub scene_list: Option<Vec<String>>,
+    pub scene_name: String,
+    pub scene_notes: String,
+    pub pending_scene_delete: Option<String>,
+    pub image_import_path: String,
+    pub image_import: ImageImportOptions,
+    pub image_import_replace: bool,
+    pub text_stamp: TextStampOptions,
+    pub brush: BrushOptions,
+    pub brush_tool: bool,
+    // Left-drag draws a ruler instead of painting
+    pub measure_tool: bool,
+    pub measurement: Measurement,
+    pub measure_dragging: bool,
+    pub show_probe: bool,
+    pub probe: Probe,
+    // Left-drag moves the probe, or draws a new one when started outside it
+    pub probe_tool: bool,
+    pub probe_drag: Option<ProbeDrag>,
+    // Left-click places or drags gravity wells, right-click deletes them
+    pub well_tool: bool,
+    pub well_drag: Option<usize>,
+    // Left-drag pulls the particle nearest the click along on a spring
+    pub grab_tool: bool,
+    pub grab_options: GrabOptions,
+    pub grab: Option<Grab>,
+    // Left-drag rubber-bands a selection for bulk edits
+    pub select_tool: bool,
+    pub selection_mode: SelectionMode,
+    pub selection_drag: Option<SelectionShape>,
+    pub selection: Selection,
+    pub selection_species: u32,
+    pub selection_impulse: Vec2,
+    // Particles added at once by the Burst button
+    pub burst_count: usize,
+    // Particles copied from a selection, and the saved stamps they can be loaded from
+    pub clipboard: Option<ParticleClipboard>,
+    pub stamp_name: String,
+    pub stamp_list: Option<Vec<String>>,
+    // Integrator for the next comparison ghost run
+    pub comparison_method: IntegrationMethod,
+    pub formation: FormationOptions,
+    pub steering_species: u32,
+    // Index of the steering path that mouse clicks currently extend
+    pub drawing_path: Option<usize>,
+    pub show_evolution: bool,
+    pub evolution_config: EvolutionConfig,
+    pub evolution_thumbnails: Vec<egui::TextureHandle>,
+    // Rule code pasted into the Force Editor
+    pub rule_code: String,
+    pub show_community: bool,
+    pub community: CommunityClient,
+    // Listing from the community server; None until fetched
+    pub community_entries: Option<Vec<EntrySummary>>,
+    pub community_kind: Option<EntryKind>,
+    pub community_name: String,
+    pub community_author: String,
+    // Cursor in points from the top-left of the simulation window, while it is over the window
+    pub cursor_position: Option<Vec2>,
+}
+
+#[derive(Default)]
+pub struct PerformanceStats {
+    pub fps: f32,
+    pub frame_time_ms: f32,
+    pub update_time_ms: f32,
+    pub render_time_ms: f32,
+    pub particle_count: usize,
+    // Force terms evaluated, particle pairs considered and spatial index queries in the last frame
+    pub active_forces: usize,
+    pub candidate_pairs: usize,
+    pub spatial_queries: usize,
+    // Running total of particles whose net force was limited to max_force
+    pub clamped_forces: usize,
+    // Per-frame timings behind the performance plots
+    pub history: FrameHistory,
+    // Smoothed time per frame in the force/integration step, the spatial index rebuild and trails
+    pub physics_time_ms: f32,
+    pub spatial_time_ms: f32,
+    pub trail_time_ms: f32,
+    // Physics and spatial time of this frame's substeps so far, before smoothing
+    pub frame_physics_ms: f32,
+    pub frame_spatial_ms: f32,
+    // Written by render, which only sees &self, and folded into the stats on the next update
+    pub last_render_ms: Cell<Option<f32>>,
+}
+
+impl App {
+    pub fn new(_app: &nannou::App, window: nannou::window::Id) -> Self {
+        let mut config_manager = ConfigManager::new();
+        if let Ok(layouts) = WindowLayouts::from_file(DEFAULT_LAYOUT_FILE) {
+            config_manager.config_mut().ui.window_layouts = layouts;
+        }
+        if let Ok(display) = DisplayConfig::from_file(DEFAULT_DISPLAY_FILE) {
+            config_manager.config_mut().ui.display = display;
+        }
+        let config = config_manager.config();
+        let layouts = &config.ui.window_layouts;
+        
+        let particle_system = PresetManager::create_particle_system_from_preset(
+            &Preset::ParticleLife, 
+            config
+        );
+        
+        let mut physics_engine = PhysicsEngine::new(config.physics.clone());
+        physics_engine.force_calculator.interaction_matrix = config.forces.interaction_matrix();
+        let mut renderer = ParticleRenderer::new(config.rendering.clone());
+        renderer.set_species_registry(config.species.clone());
+        
+        let mut notifications = Notifications::new();
+        let mut osc = OscListener::new();
+        if config.osc.enabled {
+            if let Err(e) = osc.start(config.osc.port) {
+                notifications.error(format!("Failed to start OSC listener: {}", e));
+            }
+        }
+        let mut metrics_server = MetricsServer::new();
+        if config.metrics.enabled {
+            if let Err(e) = metrics_server.start(config.metrics.port) {
+                notifications.error(format!("Failed to start metrics endpoint: {}", e));
+            }
+        }
+        let autosave = Autosave::new(Autosave::default_path());
+        let pending_restore = if autosave.exists() {
+            match autosave.load() {
+                Ok(snapshot) => Some(snapshot),
+                Err(e) => {
+                    notifications.warn(format!("Ignoring unreadable autosave: {}", e));
+                    None
+                },
+            }
+        } else {
+            None
+        };
+        
+        let spatial = if config.performance.enable_spatial_partitioning {
+            Some(SpatialPartitioning::new_quadtree(
+                (Vec2::new(-500.0, -500.0), Vec2::new(500.0, 500.0)),
+                10,
+                8
+            ))
+        } else {
+            None
+        };
+
+        let window_ref = _app.window(window).unwrap();
+        let egui = Egui::from_window(&window_ref);
+
+        Self {
+            particle_system,
+            physics_engine,
+            renderer,
+            config_manager,
+            spatial,
+            pheromones: None,
+            reaction: None,
+            cloth: None,
+            egui: Some(egui),
+            window,
+            controls_window: None,
+            controls_separate: false,
+            ui_state: UiState {
+                species_count_slider: 3,
+                native_pixels_per_point: window_ref.scale_factor(),
+                show_performance: layouts.is_open(PERFORMANCE_WINDOW).unwrap_or(false),
+                show_settings: layouts.is_open(SETTINGS_WINDOW).unwrap_or(false),
+                show_force_editor: layouts.is_open(FORCE_EDITOR_WINDOW).unwrap_or(false),
+                show_hotkeys: layouts.is_open(HOTKEYS_WINDOW).unwrap_or(false),
+                show_preset_gallery: layouts.is_open(PRESET_GALLERY_WINDOW).unwrap_or(false),
+                show_scenes: layouts.is_open(SCENES_WINDOW).unwrap_or(false),
+                show_evolution: layouts.is_open(EVOLUTION_WINDOW).unwrap_or(false),
+                show_probe: layouts.is_open(PROBE_WINDOW).unwrap_or(false),
+                show_community: layouts.is_open(COMMUNITY_WINDOW).unwrap_or(false),
+                comparison_method: IntegrationMethod::Euler,
+                burst_count: 100,
+                ..UiState::default()
+            },
+            performance_stats: PerformanceStats::default(),
+            time_accumulator: 0.0,
+            frame_count: 0,
+            paused: false,
+            time_scale: 1.0,
+            step_requested: false,
+            history: SimulationHistory::new(),
+            sim_time: 0.0,
+            current_preset: Some(Preset::ParticleLife),
+            transition: None,
+            audio: AudioReactor::new(),
+            midi: MidiController::load_default(),
+            osc,
+            metrics_server,
+            metrics: SimulationMetrics::default(),
+            autosave,
+            pending_restore,
+            crash_notice: crash::unseen_report(&crash::default_dir()),
+            output: OutputManager::new(),
+            output_capture: None,
+            notifications,
+            scene_events: Vec::new(),
+            scene_script: None,
+            shape_hold: None,
+            rigid_groups: Vec::new(),
+            evolution: None,
+            picking: PickingBuffer::default(),
+            comparison: None,
+            warm_up: None,
+            demo: None,
+            particle_listeners: Vec::new(),
+            chunk_streamer: ChunkStreamer::default(),
+        }
+    }
+
+    pub fn update(&mut self, _app: &nannou::App, _update: &nannou::event::Update) {
+        let dt = _update.since_last.as_secs_f32();
+        self.time_accumulator += dt;
+        self.frame_count += 1;
+        
+        let stats = &mut self.performance_stats;
+        if let Some(render_ms) = stats.last_render_ms.take() {
+            stats.render_time_ms = perf::smooth(stats.render_time_ms, render_ms);
+            stats.history.set_render_time(render_ms);
+        }
+
+        // Update FPS every second
+        if self.time_accumulator >= 1.0 {
+            self.performance_stats.fps = self.frame_count as f32 / self.time_accumulator;
+            self.performance_stats.frame_time_ms = self.time_accumulator * 1000.0 / self.frame_count as f32;
+            self.time_accumulator = 0.0;
+            self.frame_count = 0;
+        }
+
+        self.apply_display(_app);
+        self.apply_controls_window(_app);
+        
+        // Apply external control surfaces to the live config
+        if self.midi.process(self.config_manager.config_mut()) {
+            self.sync_live_parameters();
+        }
+        for command in self.osc.poll() {
+            self.handle_osc_command(command);
+        }
+        if let Some(runner) = self.evolution.as_mut() {
+            if runner.poll() {
+                self.ui_state.evolution_thumbnails.clear();
+            }
+        }
+        for reply in self.ui_state.community.poll() {
+            self.handle_community_reply(reply);
+        }
+        // Esc leaves demo mode rather than quitting
+        _app.set_exit_on_escape(self.demo.is_none());
+        if let Some(demo) = self.demo.as_mut() {
+            if let Some(step) = demo.update(&self.config_manager.config().ui.demo, dt, &mut rand::thread_rng()) {
+                self.advance_demo(step);
+            }
+        }
+
+        let update_start = std::time::Instant::now();
+        // Steps and simulated seconds this frame, for the metrics endpoint
+        let mut simulated = (0, 0.0);
+        if self.warm_up.is_some() {
+            self.advance_warm_up();
+        } else if !self.paused {
+            let start_time = std::time::Instant::now();
+            
+            // Playing from a scrubbed point discards the buffered future
+            self.history.resume();
+            self.apply_force_modulation();
+            
+            // Split scaled time into substeps no longer than the configured physics step
+            let time_scale = self.time_scale.clamp(MIN_TIME_SCALE, MAX_TIME_SCALE);
+            let steps = time_scale.ceil().max(1.0) as usize;
+            let step_scale = time_scale / steps as f32;
+            let physics_dt = self.config_manager.config().physics.dt;
+            for _ in 0..steps {
+                self.simulation_step(physics_dt * step_scale, dt * step_scale);
+            }
+            simulated = (steps as u64, physics_dt * time_scale);
+            self.advance_scene_script(dt * time_scale);
+            
+            let stats = &mut self.performance_stats;
+            stats.update_time_ms = perf::smooth(stats.update_time_ms, perf::millis(start_time.elapsed()));
+            self.performance_stats.particle_count = self.particle_system.particle_count();
+        } else if self.step_requested {
+            self.step_requested = false;
+            self.apply_force_modulation();
+            let physics_dt = self.config_manager.config().physics.dt;
+            self.simulation_step(physics_dt, physics_dt);
+            simulated = (1, physics_dt);
+            self.performance_stats.particle_count = self.particle_system.particle_count();
+        }
+        self.dispatch_particle_events();
+        let stats = &mut self.performance_stats;
+        stats.history.record(dt, perf::millis(update_start.elapsed()));
+        stats.physics_time_ms = perf::smooth(stats.physics_time_ms, std::mem::take(&mut stats.frame_physics_ms));
+        stats.spatial_time_ms = perf::smooth(stats.spatial_time_ms, std::mem::take(&mut stats.frame_spatial_ms));
+        let forces = self.physics_engine.force_calculator.take_counts();
+        stats.active_forces = forces.force_terms;
+        stats.candidate_pairs = forces.candidate_pairs;
+        stats.spatial_queries = self.spatial.as_ref().map_or(0, SpatialPartitioning::take_queries);
+        if self.metrics_server.is_serving() {
+            self.publish_metrics(simulated);
+        }
+        if let Some(notice) = crash::take_last_notice() {
+            self.crash_notice = Some(notice);
+        }
+        if let Some(Err(e)) = self.autosave.poll() {
+            self.notifications.error(format!("Autosave failed: {}", e));
+        }
+        // Holding off while the restore prompt is up keeps the crashed session's autosave intact
+        if self.pending_restore.is_none() && self.autosave.due(&self.config_manager.config().autosave, dt) {
+            let snapshot = self.session_snapshot();
+            self.autosave.save_in_background(snapshot);
+        }
+
+        // Update renderer
+        let trail_start = std::time::Instant::now();
+        {
+            profile_span!("trails");
+            self.renderer.update(&self.particle_system, dt);
+        }
+        let stats = &mut self.performance_stats;
+        stats.trail_time_ms = perf::smooth(stats.trail_time_ms, perf::millis(trail_start.elapsed()));
+        let steering = &self.config_manager.config().forces.steering;
+        let paths = if steering.enabled || self.ui_state.drawing_path.is_some() {
+            steering.paths.clone()
+        } else {
+            Vec::new()
+        };
+        self.renderer.set_steering_paths(paths);
+        let measurement = &self.ui_state.measurement;
+        let ruler = (self.ui_state.measure_tool && measurement.distance() > 0.0).then_some((measurement.start, measurement.end));
+        self.renderer.set_measurement(ruler);
+        let outline = self.ui_state.selection_drag.as_ref().map(SelectionShape::outline);
+        self.renderer.set_selection(outline, self.ui_state.selection.positions(&self.particle_system));
+        self.renderer.set_grab(self.ui_state.grab.and_then(|grab| grab.segment(&self.particle_system)));
+        self.renderer.set_probe(self.ui_state.show_probe.then_some(self.ui_state.probe));
+        self.renderer.set_zones(self.config_manager.config().forces.zones.clone());
+        self.renderer.set_wells(self.config_manager.config().forces.wells.clone());
+        let streaming = &self.config_manager.config().performance.streaming;
+        let chunks = (streaming.enabled && streaming.show_chunks).then(|| {
+            self.chunk_streamer.chunks.iter().map(|&(chunk, active)| (streaming.chunk_bounds(chunk), active)).collect()
+        });
+        self.renderer.set_chunks(chunks.unwrap_or_default());
+        let pheromones = &self.config_manager.config().pheromones;
+        self.renderer.set_pheromones(self.pheromones.clone().filter(|_| pheromones.show), pheromones.color);
+        let cloth = &self.config_manager.config().cloth;
+        let system = &self.particle_system;
+        let links = self.cloth.as_ref().filter(|_| cloth.show_links).map(|woven| {
+            woven.links().iter()
+                .filter_map(|link| Some((system.get(link.a)?.position, system.get(link.b)?.position)))
+                .collect()
+        });
+        self.renderer.set_cloth(links, cloth.color);
+        self.renderer.set_units(self.config_manager.config().units.clone());
+        self.renderer.set_ghosts(self.comparison.as_ref().map(|comparison| comparison.ghost.particles.clone()).unwrap_or_default());
+        self.update_force_field();
+        
+        // Publish the rendered view to any external video outputs
+        if self.output.is_active() {
+            let output_config = &self.config_manager.config().output;
+            let size = (output_config.width, output_config.height);
+            let draw = nannou::Draw::new();
+            self.draw_view(&draw);
+            
+            let window = _app.main_window();
+            if self.output_capture.as_ref().map(FrameCapture::size) != Some(size) {
+                if let Some(capture) = &self.output_capture {
+                    capture.finish(window.device());
+                }
+                self.output_capture = Some(FrameCapture::new(window.device(), size.0, size.1));
+            }
+            if let Some(capture) = self.output_capture.as_mut() {
+                if let Some(frame) = capture.latest() {
+                    self.output.publish(&frame);
+                }
+                if let Err(e) = capture.capture(window.device(), window.queue(), &draw) {
+                    self.output.last_error = Some(e);
+                }
+            }
+            if let Some(e) = self.output.last_error.take() {
+                self.notifications.error(format!("Video output failed: {}", e));
+            }
+        }
+        
+        self.notifications.update(dt);
+        self.prepare_frame(_app, _update.since_start);
+    }
+
+    // Everything the next frame needs mutable access for: GPU textures, the picking buffer and
+    // the egui UI. Runs at the end of update so render can draw with &self.
+    fn prepare_frame(&mut self, app: &nannou::App, elapsed: std::time::Duration) {
+        if let Err(e) = self.renderer.load_background(app) {
+            self.notifications.error(e);
+        }
+        let reaction = &self.config_manager.config().reaction;
+        self.renderer.load_reaction(app, self.reaction.as_ref().filter(|_| reaction.show), reaction.colors);
+        self.picking.invalidate();
+        self.update_interaction_radii();
+        
+        if let Some(mut egui) = self.egui.take() {
+            egui.set_elapsed_time(elapsed);
+            let frame = egui.begin_frame();
+            self.draw_ui(&frame.context());
+            frame.end();
+            self.egui = Some(egui);
+        }
+    }
+
+    // Samples the net force a virtual particle of the probe species would feel across the view
+    fn update_force_field(&mut self) {
+        let config = self.config_manager.config();
+        let field_config = &config.rendering.force_field;
+        if !field_config.enabled {
+            self.renderer.set_force_field(None);
+            return;
+        }
+        
+        let mut probe = Particle::default().with_species(field_config.probe_species);
+        if let Some(species) = config.species.get(field_config.probe_species) {
+            probe = probe.with_mass(species.mass).with_charge(species.charge).with_size(species.size);
+        }
+        
+        let (min, max) = self.renderer.view_bounds();
+        let field = ForceField::sample(
+            &self.physics_engine.force_calculator,
+            &self.particle_system.particles,
+            probe,
+            min,
+            max,
+            field_config.resolution,
+        );
+        self.renderer.set_force_field(Some(field));
+    }
+
+    // Base force scales from config, then live modulation on top
+    fn apply_force_modulation(&mut self) {
+        let config = self.config_manager.config();
+        self.physics_engine.force_calculator.force_multipliers = config.forces.force_scales.clone();
+        self.physics_engine.force_calculator.steering = config.forces.steering.clone();
+        self.physics_engine.force_calculator.species_drag = config.species.drag_coefficients();
+        let global_forces = &mut self.physics_engine.force_calculator.global_forces;
+        global_forces.retain(|force| !force.is_environmental() && !matches!(force, ForceType::Brownian { .. }));
+        global_forces.extend(config.forces.environment_forces());
+        let audio_config = &config.audio;
+        self.audio.update(audio_config);
+        self.audio.apply(audio_config, &mut self.physics_engine.force_calculator);
+    }
+
+    // Advances the simulation by one physics step
+    fn simulation_step(&mut self, physics_dt: f32, frame_dt: f32) {
+        profile_span!("simulation_step");
+        // Update spatial partitioning
+        let spatial_start = std::time::Instant::now();
+        if let Some(ref mut spatial) = self.spatial {
+            profile_span!("spatial_update");
+            spatial.update(&self.particle_system.particles);
+        }
+        self.performance_stats.frame_spatial_ms += perf::millis(spatial_start.elapsed());
+        
+        // Agents switch state while the spatial index still matches their positions
+        let config = self.config_manager.config();
+        config.agents.step(&mut self.particle_system.particles, &config.species, self.spatial.as_ref(), &mut rng::fork());
+        update_cloth(&mut self.cloth, &config.cloth, &mut self.particle_system);
+        update_pheromones(&mut self.pheromones, &config.pheromones, &mut self.particle_system, physics_dt);
+        update_reaction(&mut self.reaction, &config.reaction, &mut self.particle_system, physics_dt);
+        
+        if let Some(grab) = self.ui_state.grab {
+            if !grab.apply(&self.ui_state.grab_options, &mut self.particle_system) {
+                self.ui_state.grab = None;
+            }
+        }
+        
+        // Keep held stamps in shape
+        if let Some(ref hold) = self.shape_hold {
+            if !hold.apply(&mut self.particle_system) {
+                self.shape_hold = None;
+            }
+        }
+        
+        // Update physics
+        self.physics_engine.config.dt = physics_dt;
+        let physics = &self.config_manager.config().physics;
+        self.physics_engine.config.integration_method = physics.integration_method;
+        self.physics_engine.config.newtons_third_law = physics.newtons_third_law;
+        self.physics_engine.config.spawn_ramp_time = physics.spawn_ramp_time;
+        self.physics_engine.config.max_force = physics.max_force;
+        self.physics_engine.config.softening.clone_from(&physics.softening);
+        self.physics_engine.force_calculator.fast_math.clone_from(&self.config_manager.config().performance.fast_math);
+        self.physics_engine.zones = self.config_manager.config().forces.zones.clone();
+        self.physics_engine.wells = self.config_manager.config().forces.wells.clone();
+        self.physics_engine.metabolism = self.config_manager.config().metabolism.clone();
+        // Advance any in-progress preset morph, over the settings just taken from the config
+        self.update_transition(frame_dt);
+        if let Some(comparison) = self.comparison.as_mut() {
+            comparison.step(&self.physics_engine, frame_dt);
+        }
+        let physics_start = std::time::Instant::now();
+        let streaming = &self.config_manager.config().performance.streaming;
+        if streaming.enabled {
+            // The spatial index covers every particle, so the split steps go without it
+            let focus = self.renderer.camera_state().0;
+            let (engine, stats) = (&mut self.physics_engine, &mut self.performance_stats);
+            self.chunk_streamer.step(streaming, focus, &mut self.particle_system, physics_dt, |system, dt| {
+                engine.config.dt = dt;
+                engine.update(system);
+                stats.clamped_forces += engine.clamped_forces;
+            });
+            self.physics_engine.config.dt = physics_dt;
+        } else {
+            self.physics_engine.update_with_spatial(&mut self.particle_system, self.spatial.as_ref());
+            self.performance_stats.clamped_forces += self.physics_engine.clamped_forces;
+        }
+        
+        // Update particle system
+        self.particle_system.update(frame_dt);
+        update_rigid_groups(&mut self.rigid_groups, &mut self.particle_system, physics_dt);
+        self.performance_stats.frame_physics_ms += perf::millis(physics_start.elapsed());
+        let config = self.config_manager.config();
+        config.particles.bursts.update(&mut self.particle_system, config, self.sim_time, self.sim_time + frame_dt);
+        // The spatial index is stale after the update, so feeding checks every pair
+        config.metabolism.feed(&mut self.particle_system, &config.species, &config.forces.zones, None, (self.sim_time, self.sim_time + frame_dt), &mut rng::fork());
+        
+        self.sim_time += frame_dt;
+        self.history.record(&self.particle_system, self.sim_time, &self.config_manager.config().history);
+    }
+
+    // Runs warm-up steps until this frame's budget is spent. When they are done the history is
+    // cleared, so scrubbing starts from the developed state.
+    fn advance_warm_up(&mut self) {
+        let Some((mut done, total)) = self.warm_up else {
+            return;
+        };
+        let start_time = std::time::Instant::now();
+        self.apply_force_modulation();
+        let physics_dt = self.config_manager.config().physics.dt;
+        while done < total && start_time.elapsed() < WARM_UP_FRAME_BUDGET {
+            self.simulation_step(physics_dt, physics_dt);
+            done += 1;
+        }
+        self.performance_stats.particle_count = self.particle_system.particle_count();
+        
+        if done < total {
+            self.warm_up = Some((done, total));
+        } else {
+            self.finish_warm_up();
+        }
+    }
+
+    fn finish_warm_up(&mut self) {
+        self.warm_up = None;
+        self.history.clear();
+        self.restart_comparison();
+    }
+
+    // Replays buffered frames while scrubbing, otherwise simulates one new step
+    pub fn step_forward(&mut self) {
+        self.paused = true;
+        if let Some(state) = self.history.step_forward() {
+            self.particle_system = state.clone();
+            return;
+        }
+        self.history.resume();
+        self.step_requested = true;
+    }
+
+    pub fn step_backward(&mut self) -> bool {
+        self.paused = true;
+        match self.history.step_back() {
+            Some(state) => {
+                self.particle_system = state.clone();
+                true
+            },
+            None => {
+                if !self.config_manager.config().history.enabled {
+                    self.notifications.info("Enable Record History to step backwards");
+                }
+                false
+            },
+        }
+    }
+
+    pub fn seek_history(&mut self, index: usize) -> bool {
+        self.paused = true;
+        match self.history.seek(index) {
+            Some(state) => {
+                self.particle_system = state.clone();
+                true
+            },
+            None => false,
+        }
+    }
+
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.time_scale = time_scale.clamp(MIN_TIME_SCALE, MAX_TIME_SCALE);
+    }
+
+    // Pushes live-controllable config values into the running systems
+    fn advance_scene_script(&mut self, dt: f32) {
+        let Some(script) = self.scene_script.as_mut() else {
+            return;
+        };
+        let actions = script.advance(dt);
+        if script.is_finished() {
+            self.scene_script = None;
+        }
+        
+        for action in actions {
+            match action {
+                SceneAction::Set { target, value } => {
+                    target.apply(self.config_manager.config_mut(), value);
+                    self.sync_live_parameters();
+                },
+                SceneAction::Pause(paused) => {
+                    self.paused = paused;
+                },
+                SceneAction::Note(text) => {
+                    self.notifications.info(text);
+                },
+                SceneAction::Burst { count, area } => {
+                    self.burst(count, &area);
+                },
+                SceneAction::Stamp { shape, center, density, species_id } => {
+                    let positions = ShapeSampler::new(shape, center).sample_density(density, &mut rand::thread_rng());
+                    let options = StampOptions {
+                        species_id,
+                        mode: StampMode::Free,
+                        ..StampOptions::default()
+                    };
+                    self.stamp(positions, center, &options);
+                },
+            }
+        }
+    }
+
+    // Spawns `count` particles at once in `area`, as the spawner would over time
+    pub fn burst(&mut self, count: usize, area: &SpawnArea) -> usize {
+        let added = self.particle_system.burst(count, area);
+        if added > 0 {
+            self.current_preset = None;
+        }
+        added
+    }
+
+    fn sync_live_parameters(&mut self) {
+        let config = self.config_manager.config();
+        self.particle_system.spawn_rate = config.particles.spawn_rate;
+        self.renderer.set_trail_length(config.rendering.trail_length);
+    }
+
+    fn handle_osc_command(&mut self, command: OscCommand) {
+        match command {
+            OscCommand::Set(target, value) => {
+                target.apply(self.config_manager.config_mut(), value);
+                self.sync_live_parameters();
+            },
+            OscCommand::Preset(name) => {
+                match Preset::from_key(&name) {
+                    Some(preset) => self.apply_preset(preset),
+                    None => self.notifications.warn(format!("Unknown preset from OSC: {}", name)),
+                }
+            },
+            OscCommand::Pause(paused) => {
+                self.paused = paused;
+            },
+            OscCommand::Reset => {
+                self.reset_simulation();
+            },
+        }
+    }
+
+    // Draws the frame prepared by update; mutable per-frame work belongs in prepare_frame
+    pub fn render(&self, app: &nannou::App, frame: &nannou::Frame) {
+        profile_span!("render");
+        let start_time = std::time::Instant::now();
+        
+        let draw = app.draw();
+        self.draw_view(&draw);
+        draw.to_frame(app, frame).unwrap();
+        
+        self.performance_stats.last_render_ms.set(Some(perf::millis(start_time.elapsed())));
+        
+        // The UI goes on top and is not counted as render time
+        if !self.controls_separate {
+            if let Some(egui) = &self.egui {
+                egui.draw_to_frame(frame).unwrap();
+            }
+        }
+    }
+
+    // The simulation view without the UI, for the window and the video outputs alike. A warming-up
+    // preset stays hidden until it has developed.
+    fn draw_view(&self, draw: &nannou::Draw) {
+        if self.warm_up.is_some() {
+            let [r, g, b, a] = self.config_manager.config().rendering.background_color;
+            draw.background().color(rgba(r, g, b, a));
+        } else {
+            self.renderer.render(draw, &self.particle_system);
+        }
+    }
+
+    // Waits for video output readbacks still in flight; called before the window goes away
+    pub fn finish_output(&self, app: &nannou::App) {
+        if let Some(capture) = &self.output_capture {
+            capture.finish(app.main_window().device());
+        }
+    }
+
+    // Draws the control panels when they have a window of their own
+    pub fn render_controls(&self, frame: &nannou::Frame) {
+        frame.clear(BLACK);
+        if self.controls_separate {
+            if let Some(egui) = &self.egui {
+                egui.draw_to_frame(frame).unwrap();
+            }
+        }
+    }
+
+    // Input to the control panel window only feeds egui
+    pub fn controls_window_event(&mut self, event: &nannou::winit::event::WindowEvent) {
+        if self.controls_separate {
+            if let Some(egui) = self.egui.as_mut() {
+                egui.handle_raw_event(event);
+            }
+        }
+    }
+
+    pub fn raw_window_event(&mut self, _app: &nannou::App, event: &nannou::winit::event::WindowEvent) {
+        if !self.controls_separate {
+            if let Some(egui) = self.egui.as_mut() {
+                egui.handle_raw_event(event);
+            }
+        }
+        
+        if let Some(demo) = self.demo.as_mut() {
+            use nannou::winit::event::WindowEvent;
+            if matches!(event, WindowEvent::CursorMoved { .. } | WindowEvent::MouseInput { .. } | WindowEvent::MouseWheel { .. } | WindowEvent::KeyboardInput { .. } | WindowEvent::Touch(_)) {
+                demo.interact();
+            }
+        }
+        
+        // Clicks and scrolls over egui panels belong to the UI. Cursor tracking and releases
+        // still go through, so drags already under way in the view end cleanly.
+        let pointer_captured = self.ui_wants_pointer();
+        
+        // Handle mouse events for camera control
+        match event {
+            nannou::winit::event::WindowEvent::CursorMoved { position, .. } => {
+                let Some(window) = _app.window(self.window) else {
+                    return;
+                };
+                let screen_size = Vec2::new(
+                    window.inner_size_points().0,
+                    window.inner_size_points().1,
+                );
+                let position = position.to_logical::<f32>(window.scale_factor() as f64);
+                let mouse_pos = Vec2::new(position.x, position.y);
+                self.renderer.handle_mouse_input(
+                    nannou::geom::Vec2::new(mouse_pos.x, mouse_pos.y), 
+                    nannou::geom::Vec2::new(screen_size.x, screen_size.y)
+                );
+                self.ui_state.cursor_position = Some(mouse_pos);
+                let cursor = self.renderer.cursor_world();
+                if self.ui_state.measure_dragging {
+                    self.ui_state.measurement.end = cursor;
+                }
+                match self.ui_state.probe_drag {
+                    Some(ProbeDrag::Move(grab)) => self.ui_state.probe.center = cursor - grab,
+                    Some(ProbeDrag::Resize) => self.ui_state.probe.resize_to(cursor),
+                    None => {},
+                }
+                if let Some(grab) = self.ui_state.grab.as_mut() {
+                    grab.target = cursor;
+                }
+                if let Some(shape) = self.ui_state.selection_drag.as_mut() {
+                    shape.extend(cursor);
+                }
+                if let Some(index) = self.ui_state.well_drag {
+                    if let Some(well) = self.config_manager.config_mut().forces.wells.get_mut(index) {
+                        well.position = cursor;
+                    }
+                }
+            },
+            nannou::winit::event::WindowEvent::MouseWheel { delta, .. } => {
+                if pointer_captured {
+                    return;
+                }
+                if let nannou::winit::event::MouseScrollDelta::LineDelta(_, y) = delta {
+                    self.renderer.handle_zoom(*y);
+                }
+            },
+            nannou::winit::event::WindowEvent::MouseInput {
+                state: nannou::winit::event::ElementState::Pressed,
+                button: nannou::winit::event::MouseButton::Left,
+                ..
+            } => {
+                if pointer_captured {
+                    return;
+                }
+                let cursor = self.renderer.cursor_world();
+                if let Some(index) = self.ui_state.drawing_path {
+                    if let Some(path) = self.config_manager.config_mut().forces.steering.paths.get_mut(index) {
+                        path.points.push(cursor);
+                    }
+                } else if self.ui_state.grab_tool {
+                    if let Some(position) = self.ui_state.cursor_position {
+                        self.start_grab(position);
+                    }
+                } else if self.ui_state.select_tool {
+                    self.ui_state.selection_drag = Some(SelectionShape::new(self.ui_state.selection_mode, cursor));
+                } else if self.ui_state.well_tool {
+                    let wells = &mut self.config_manager.config_mut().forces.wells;
+                    let index = well_at(wells, cursor).unwrap_or_else(|| {
+                        wells.push(GravityWell::new(cursor));
+                        wells.len() - 1
+                    });
+                    self.ui_state.well_drag = Some(index);
+                } else if self.ui_state.show_probe && self.ui_state.probe_tool {
+                    let probe = &mut self.ui_state.probe;
+                    if probe.contains(cursor) {
+                        self.ui_state.probe_drag = Some(ProbeDrag::Move(cursor - probe.center));
+                    } else {
+                        probe.center = cursor;
+                        probe.resize_to(cursor);
+                        self.ui_state.probe_drag = Some(ProbeDrag::Resize);
+                    }
+                } else if self.ui_state.measure_tool {
+                    self.ui_state.measurement.start = cursor;
+                    self.ui_state.measurement.end = cursor;
+                    self.ui_state.measure_dragging = true;
+                } else if self.ui_state.brush_tool {
+                    self.stamp_brush(cursor);
+                }
+            },
+            nannou::winit::event::WindowEvent::MouseInput {
+                state: nannou::winit::event::ElementState::Released,
+                button: nannou::winit::event::MouseButton::Left,
+                ..
+            } => {
+                self.ui_state.measure_dragging = false;
+                self.ui_state.probe_drag = None;
+                self.ui_state.well_drag = None;
+                self.release_grab();
+                if let Some(shape) = self.ui_state.selection_drag.take() {
+                    self.ui_state.selection = Selection::from_shape(&shape, &self.particle_system, self.spatial.as_ref());
+                }
+            },
+            nannou::winit::event::WindowEvent::MouseInput {
+                state: nannou::winit::event::ElementState::Pressed,
+                button: nannou::winit::event::MouseButton::Right,
+                ..
+            } => {
+                if !self.ui_state.well_tool || pointer_captured {
+                    return;
+                }
+                let cursor = self.renderer.cursor_world();
+                let wells = &mut self.config_manager.config_mut().forces.wells;
+                if let Some(index) = well_at(wells, cursor) {
+                    wells.remove(index);
+                    self.ui_state.well_drag = None;
+                }
+            },
+            nannou::winit::event::WindowEvent::CursorLeft { .. } => {
+                self.ui_state.cursor_position = None;
+            },
+            _ => {}
+        }
+    }
+
+    // Called once per key press (not per frame while held)
+    pub fn key_pressed(&mut self, key: nannou::event::Key) {
+        if self.demo.is_some() && key == nannou::event::Key::Escape {
+            self.stop_demo();
+            return;
+        }
+        let key_name = format!("{:?}", key);
+        
+        if let Some(action) = self.ui_state.rebinding_action.take() {
+            self.config_manager.config_mut().ui.keymap.rebind(action, &key_name);
+            return;
+        }
+        
+        let ui_config = &self.config_manager.config().ui;
+        if !ui_config.enable_keyboard_shortcuts || self.ui_wants_keyboard() {
+            return;
+        }
+        
+        if let Some(action) = ui_config.keymap.action_for(&key_name) {
+            self.perform_action(action);
+        }
+    }
+
+    pub fn perform_action(&mut self, action: Action) {
+        match action {
+            Action::TogglePause => {
+                self.paused = !self.paused;
+            },
+            Action::Reset => {
+                self.reset_simulation();
+            },
+            Action::ResetCamera => {
+                self.renderer.reset_camera();
+            },
+            Action::FocusCamera => {
+                self.renderer.focus_on_particles(&self.particle_system);
+            },
+            Action::Preset(index) => {
+                if let Some(preset) = Preset::all().into_iter().nth(index) {
+                    self.apply_preset(preset);
+                }
+            },
+            Action::ToggleSettings => {
+                self.ui_state.show_settings = !self.ui_state.show_settings;
+            },
+            Action::TogglePerformance => {
+                self.ui_state.show_performance = !self.ui_state.show_performance;
+            },
+            Action::StepForward => {
+                self.step_forward();
+            },
+            Action::StepBackward => {
+                self.step_backward();
+            },
+            Action::SlowDown => {
+                self.set_time_scale(self.time_scale * 0.5);
+            },
+            Action::SpeedUp => {
+                self.set_time_scale(self.time_scale * 2.0);
+            },
+            Action::ToggleHotkeys => {
+                self.ui_state.show_hotkeys = !self.ui_state.show_hotkeys;
+            },
+            Action::ToggleFullscreen => {
+                let display = &mut self.config_manager.config_mut().ui.display;
+                display.fullscreen = !display.fullscreen;
+            },
+            Action::ToggleDemo => {
+                if self.demo.is_some() {
+                    self.stop_demo();
+                } else {
+                    self.start_demo();
+                }
+            },
+            Action::CopySelection => {
+                self.copy_selection();
+            },
+            Action::PasteClipboard => {
+                let cursor = self.renderer.cursor_world();
+                self.paste_clipboard(cursor);
+            },
+            Action::DuplicateSelection => {
+                self.duplicate_selection();
+            },
+            Action::ToggleChunkPin => {
+                let streaming = &mut self.config_manager.config_mut().performance.streaming;
+                let chunk = streaming.chunk_of(self.renderer.cursor_world());
+                let pinned = streaming.toggle_pin(chunk);
+                self.notifications.info(format!("Chunk ({}, {}) {}", chunk.0, chunk.1, if pinned { "pinned" } else { "unpinned" }));
+            },
+        }
+    }
+
+    fn draw_ui(&mut self, ctx: &egui::Context) {
+        // Shown even with the UI hidden, since autosaving waits on the answer. After a crash the
+        // report comes first.
+        if self.crash_notice.is_some() {
+            self.draw_crash_report(ctx);
+        } else {
+            self.draw_restore_prompt(ctx);
+        }
+        if !self.config_manager.config().ui.show_ui || self.demo.is_some() {
+            return;
+        }
+        self.apply_ui_style(ctx);
+        
+        let lang = self.config_manager.config().ui.language;
+        // Main control panel
+        egui::SidePanel::left("control_panel")
+            .default_width(250.0)
+            .show(ctx, |ui| {
+                ui.heading(tr(lang, "🎆 Inochi Particle Life"));
+                ui.separator();
+                
+                self.draw_simulation_controls(ui);
+                ui.separator();
+                
+                self.draw_preset_selector(ui);
+                ui.separator();
+                
+                self.draw_particle_controls(ui);
+                ui.separator();
+                
+                self.draw_force_controls(ui);
+                ui.separator();
+                
+                self.draw_rendering_controls(ui);
+            });
+
+        // Floating windows remember where the user left them
+        if self.ui_state.show_performance {
+            self.show_persistent_window(ctx, PERFORMANCE_WINDOW, tr(lang, "📊 Performance Stats"), [300.0, 200.0], |app, ui| {
+                app.draw_performance_stats(ui);
+            });
+        }
+
+        if self.ui_state.show_settings {
+            self.show_persistent_window(ctx, SETTINGS_WINDOW, tr(lang, "⚙️ Advanced Settings"), [400.0, 300.0], |app, ui| {
+                app.draw_advanced_settings(ui);
+            });
+        }
+
+        if self.ui_state.show_force_editor {
+            self.show_persistent_window(ctx, FORCE_EDITOR_WINDOW, tr(lang, "🔧 Force Editor"), [350.0, 400.0], |app, ui| {
+                app.draw_force_editor(ui);
+            });
+        }
+
+        self.draw_notifications(ctx);
+        self.draw_warm_up_progress(ctx);
+        
+        if self.ui_state.show_hotkeys {
+            self.show_persistent_window(ctx, HOTKEYS_WINDOW, tr(lang, "⌨️ Hotkeys"), [300.0, 360.0], |app, ui| {
+                app.draw_hotkey_cheat_sheet(ui);
+            });
+        }
+        
+        if self.ui_state.show_preset_gallery {
+            self.show_persistent_window(ctx, PRESET_GALLERY_WINDOW, tr(lang, "🖼️ Preset Gallery"), [480.0, 520.0], |app, ui| {
+                app.draw_preset_gallery(ui);
+            });
+        }
+        
+        if self.ui_state.show_scenes {
+            self.show_persistent_window(ctx, SCENES_WINDOW, tr(lang, "🎬 Scenes"), [360.0, 420.0], |app, ui| {
+                app.draw_scene_browser(ui);
+            });
+        }
+        
+        if self.ui_state.show_evolution {
+            self.show_persistent_window(ctx, EVOLUTION_WINDOW, tr(lang, "🧬 Evolution"), [420.0, 520.0], |app, ui| {
+                app.draw_evolution(ui);
+            });
+        }
+        
+        if self.ui_state.show_probe {
+            self.show_persistent_window(ctx, PROBE_WINDOW, tr(lang, "🔍 Region Probe"), [260.0, 320.0], |app, ui| {
+                app.draw_probe(ui);
+            });
+        }
+        
+        if self.ui_state.show_community {
+            self.show_persistent_window(ctx, COMMUNITY_WINDOW, tr(lang, "🌐 Community"), [380.0, 480.0], |app, ui| {
+                app.draw_community(ui);
+            });
+        }
+        
+        let open_windows = [
+            (PERFORMANCE_WINDOW, self.ui_state.show_performance),
+            (SETTINGS_WINDOW, self.ui_state.show_settings),
+            (FORCE_EDITOR_WINDOW, self.ui_state.show_force_editor),
+            (HOTKEYS_WINDOW, self.ui_state.show_hotkeys),
+            (PRESET_GALLERY_WINDOW, self.ui_state.show_preset_gallery),
+            (SCENES_WINDOW, self.ui_state.show_scenes),
+            (EVOLUTION_WINDOW, self.ui_state.show_evolution),
+            (PROBE_WINDOW, self.ui_state.show_probe),
+            (COMMUNITY_WINDOW, self.ui_state.show_community),
+        ];
+        let layouts = &mut self.config_manager.config_mut().ui.window_layouts;
+        for (id, open) in open_windows {
+            layouts.set_open(id, open);
+        }
+    }
+
+    fn show_persistent_window(
+        &mut self,
+        ctx: &egui::Context,
+        id: &str,
+        title: &str,
+        default_size: [f32; 2],
+        add_contents: impl FnOnce(&mut Self, &mut egui::Ui),
+    ) {
+        let mut window = egui::Window::new(title)
+            .id(egui::Id::new(id))
+            .default_size(default_size);
+        if let Some(rect) = self.config_manager.config().ui.window_layouts.get(id) {
+            window = window.default_pos(rect.position).default_size(rect.size);
+        }
+        
+        // The content size is stored rather than the frame, so restoring doesn't grow the window
+        let response = window.show(ctx, |ui| {
+            let content_size = ui.max_rect().size();
+            add_contents(self, ui);
+            content_size
+        });
+        
+        if let Some(response) = response {
+            if let Some(content_size) = response.inner {
+                self.config_manager.config_mut().ui.window_layouts.update(
+                    id,
+                    response.response.rect.min.into(),
+                    content_size.into(),
+                );
+            }
+        }
+    }
+
+    pub fn save_layout(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.config_manager.config().ui.window_layouts.save_to_file(DEFAULT_LAYOUT_FILE)
+    }
+
+    pub fn save_display(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.config_manager.config().ui.display.save_to_file(DEFAULT_DISPLAY_FILE)
+    }
+
+    // Brings the window in line with the display settings whenever they change
+    fn apply_display(&mut self, app: &nannou::App) {
+        use nannou::winit::window::Fullscreen;
+        
+        let display = &self.config_manager.config().ui.display;
+        let applied = self.ui_state.applied_display.take();
+        if applied.as_ref() == Some(display) {
+            self.ui_state.applied_display = applied;
+            return;
+        }
+        
+        let monitors = app.available_monitors();
+        self.ui_state.monitors = monitors
+            .iter()
+            .enumerate()
+            .map(|(i, monitor)| {
+                let size = monitor.size();
+                let name = monitor.name().unwrap_or_else(|| format!("{} {}", tr(self.config_manager.config().ui.language, "Monitor"), i + 1));
+                format!("{} ({}×{})", name, size.width, size.height)
+            })
+            .collect();
+        let monitor = display.monitor_index(monitors.len()).map(|index| monitors[index].clone());
+        
+        let Some(window) = app.window(self.window) else {
+            return;
+        };
+        if let Some(monitor) = monitor.as_ref().filter(|_| applied.as_ref().map(|a| a.monitor) != Some(display.monitor)) {
+            window.winit_window().set_outer_position(monitor.position());
+        }
+        window.set_fullscreen_with(display.fullscreen.then(|| Fullscreen::Borderless(monitor)));
+        window.set_decorations(!display.borderless);
+        window.set_always_on_top(display.always_on_top);
+        self.ui_state.applied_display = Some(display.clone());
+    }
+
+    // Moves the control panels between the simulation window and a window of their own when
+    // the display setting changes, or back when that window is closed
+    fn apply_controls_window(&mut self, app: &nannou::App) {
+        if self.controls_window.is_some_and(|id| app.window(id).is_none()) {
+            self.controls_window = None;
+            self.config_manager.config_mut().ui.display.separate_controls = false;
+        }
+        let separate = self.config_manager.config().ui.display.separate_controls;
+        if separate == self.controls_separate {
+            return;
+        }
+        
+        let target = if separate {
+            match self.controls_window.map_or_else(|| build_controls_window(app), Ok) {
+                Ok(id) => {
+                    self.controls_window = Some(id);
+                    id
+                },
+                Err(e) => {
+                    self.notifications.error(format!("Failed to open the controls window: {}", e));
+                    self.config_manager.config_mut().ui.display.separate_controls = false;
+                    return;
+                },
+            }
+        } else {
+            self.window
+        };
+        if let Some(controls) = self.controls_window.and_then(|id| app.window(id)) {
+            controls.set_visible(separate);
+        }
+        // egui is tied to the window it was made for, so the panels get a fresh one. Textures
+        // belong to the old context, so the thumbnails are loaded again into the new one.
+        if let Some(window) = app.window(target) {
+            self.egui = Some(Egui::from_window(&window));
+            self.ui_state.native_pixels_per_point = window.scale_factor();
+            self.ui_state.applied_style = None;
+            self.ui_state.preset_thumbnails.clear();
+            self.ui_state.evolution_thumbnails.clear();
+        }
+        self.controls_separate = separate;
+    }
+
+    fn apply_ui_style(&mut self, ctx: &egui::Context) {
+        let ui_config = &self.config_manager.config().ui;
+        
+        let pixels_per_point = self.ui_state.native_pixels_per_point * ui_config.ui_scale.clamp(0.5, 3.0);
+        if (ctx.pixels_per_point() - pixels_per_point).abs() > f32::EPSILON {
+            ctx.set_pixels_per_point(pixels_per_point);
+        }
+        
+        // Visuals are only rebuilt when the theme or accent changes
+        let style = (ui_config.theme, ui_config.accent_color);
+        if self.ui_state.applied_style == Some(style) {
+            return;
+        }
+        ctx.set_visuals(build_visuals(ui_config.theme, ui_config.accent_color));
+        self.ui_state.applied_style = Some(style);
+    }
+
+    fn draw_warm_up_progress(&mut self, ctx: &egui::Context) {
+        let Some((done, total)) = self.warm_up else {
+            return;
+        };
+        let lang = self.config_manager.config().ui.language;
+        let mut skip = false;
+        egui::Area::new("warm_up")
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(tr(lang, "Warming Up"));
+                    ui.add(egui::ProgressBar::new(done as f32 / total.max(1) as f32)
+                        .desired_width(240.0)
+                        .text(format!("{} / {}", done, total)));
+                    skip = ui.button(tr(lang, "Skip")).clicked();
+                });
+            });
+        
+        if skip {
+            self.finish_warm_up();
+        }
+    }
+
+    fn draw_notifications(&mut self, ctx: &egui::Context) {
+        if self.notifications.is_empty() {
+            return;
+        }
+        
+        let mut dismissed = None;
+        egui::Area::new("notifications")
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-10.0, -10.0))
+            .show(ctx, |ui| {
+                for (index, toast) in self.notifications.toasts().enumerate() {
+                    let color = match toast.level {
+                        NotificationLevel::Info => egui::Color32::LIGHT_BLUE,
+                        NotificationLevel::Warning => egui::Color32::YELLOW,
+                        NotificationLevel::Error => egui::Color32::LIGHT_RED,
+                    };
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.colored_label(color, format!("{} {}", toast.level.icon(), toast.message));
+                            if ui.small_button("✖").clicked() {
+                                dismissed = Some(index);
+                            }
+                        });
+                    });
+                }
+            });
+        
+        if let Some(index) = dismissed {
+            self.notifications.dismiss(index);
+        }
+    }
+
+    fn draw_hotkey_cheat_sheet(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        let keymap = &self.config_manager.config().ui.keymap;
+        
+        egui::Grid::new("hotkeys").striped(true).show(ui, |ui| {
+            for action in Action::all() {
+                let key = keymap.key_for(action).map(KeyMap::display_key).unwrap_or_else(|| "—".to_string());
+                ui.monospace(key);
+                ui.label(tr(lang, &action.description()));
+                
+                if self.ui_state.rebinding_action == Some(action) {
+                    ui.label(tr(lang, "Press a key..."));
+                } else if ui.small_button(tr(lang, "Rebind")).clicked() {
+                    self.ui_state.rebinding_action = Some(action);
+                }
+                ui.end_row();
+            }
+        });
+        
+        if ui.button(tr(lang, "Restore Defaults")).clicked() {
+            self.config_manager.config_mut().ui.keymap = KeyMap::default();
+        }
+    }
+
+    fn draw_simulation_controls(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        ui.heading(tr(lang, "Simulation"));
+        
+        ui.horizontal(|ui| {
+            if ui.button(tr(lang, if self.paused { "▶️ Play" } else { "⏸️ Pause" })).clicked() {
+                self.paused = !self.paused;
+            }
+            
+            if ui.button(tr(lang, "🔄 Reset")).clicked() {
+                self.reset_simulation();
+            }
+        });
+        
+        ui.horizontal(|ui| {
+            if ui.add_enabled(self.history.position() > 0, egui::Button::new(tr(lang, "⏮ Step Back"))).clicked() {
+                self.step_backward();
+            }
+            if ui.button(tr(lang, "⏭ Step")).clicked() {
+                self.step_forward();
+            }
+        });
+        
+        ui.add(egui::Slider::new(&mut self.time_scale, MIN_TIME_SCALE..=MAX_TIME_SCALE)
+            .logarithmic(true)
+            .text(tr(lang, "Speed ×")));
+        
+        self.draw_history_timeline(ui);
+        
+        ui.label(format!("{}: {}", tr(lang, "Particles"), self.particle_system.particle_count()));
+        ui.label(format!("FPS: {:.1}", self.performance_stats.fps));
+        
+        ui.horizontal(|ui| {
+            if ui.button(tr(lang, "📊 Performance")).clicked() {
+                self.ui_state.show_performance = !self.ui_state.show_performance;
+            }
+            if ui.button(tr(lang, "🔍 Region Probe")).clicked() {
+                self.ui_state.show_probe = !self.ui_state.show_probe;
+            }
+        });
+    }
+
+    fn draw_probe(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        let species = &self.config_manager.config().species;
+        let units = &self.config_manager.config().units;
+        let probe = &mut self.ui_state.probe;
+        
+        ui.checkbox(&mut self.ui_state.probe_tool, tr(lang, "Drag to place probe"));
+        ui.horizontal(|ui| {
+            let (half_extents, radius) = match probe.shape {
+                ProbeShape::Rect { half_extents } => (half_extents, half_extents.max_element()),
+                ProbeShape::Circle { radius } => (Vec2::splat(radius), radius),
+            };
+            if ui.selectable_label(matches!(probe.shape, ProbeShape::Circle { .. }), tr(lang, "Circle")).clicked() {
+                probe.shape = ProbeShape::Circle { radius };
+            }
+            if ui.selectable_label(matches!(probe.shape, ProbeShape::Rect { .. }), tr(lang, "Rectangle")).clicked() {
+                probe.shape = ProbeShape::Rect { half_extents };
+            }
+        });
+        match &mut probe.shape {
+            ProbeShape::Circle { radius } => {
+                ui.add(egui::Slider::new(radius, 1.0..=1000.0).text(tr(lang, "Radius")));
+            },
+            ProbeShape::Rect { half_extents } => {
+                ui.add(egui::Slider::new(&mut half_extents.x, 1.0..=1000.0).text(tr(lang, "Half Width")));
+                ui.add(egui::Slider::new(&mut half_extents.y, 1.0..=1000.0).text(tr(lang, "Half Height")));
+            },
+        }
+        ui.separator();
+        
+        let stats = probe.stats(&self.particle_system.particles, self.spatial.as_ref());
+        ui.label(format!("{}: {}", tr(lang, "Particles"), stats.count));
+        ui.label(format!("{}: {:.2}", tr(lang, "Density (per 100×100)"), stats.density));
+        ui.label(format!("{}: ({}, {})", tr(lang, "Mean Velocity"), units.format_speed(stats.mean_velocity.x), units.format_speed(stats.mean_velocity.y)));
+        ui.label(format!("{}: {}", tr(lang, "Mean Speed"), units.format_speed(stats.mean_speed)));
+        for (species_id, count) in &stats.species {
+            ui.label(format!("{}: {}", species.name(*species_id), count));
+        }
+    }
+
+    fn draw_history_timeline(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        let history_config = &mut self.config_manager.config_mut().history;
+        if ui.checkbox(&mut history_config.enabled, tr(lang, "Record History")).changed() && !history_config.enabled {
+            self.history.clear();
+        }
+        
+        if !history_config.enabled {
+            return;
+        }
+        
+        ui.add(egui::Slider::new(&mut history_config.duration_seconds, 1.0..=60.0)
+            .text(tr(lang, "Buffer (s)")));
+        ui.add(egui::Slider::new(&mut history_config.memory_cap_mb, 16..=2048)
+            .logarithmic(true)
+            .text(tr(lang, "Memory Cap (MB)")));
+        
+        if self.history.len() > 1 {
+            let mut position = self.history.position();
+            let last = self.history.len() - 1;
+            let offset = self.history.offset_seconds(position);
+            let response = ui.add(egui::Slider::new(&mut position, 0..=last)
+                .show_value(false)
+                .text(format!("{:+.2}s", offset)));
+            if response.changed() {
+                self.seek_history(position);
+            }
+        }
+        
+        ui.label(format!(
+            "{:.1}s {} • {:.1} MB{}",
+            self.history.buffered_seconds(),
+            tr(lang, "buffered"),
+            self.history.memory_bytes() as f32 / (1024.0 * 1024.0),
+            if self.history.is_scrubbing() { tr(lang, " • press Play to resume here") } else { "" },
+        ));
+    }
+
+    fn draw_preset_selector(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        ui.heading(tr(lang, "Presets"));
+        
+        let current_name = self.current_preset.as_ref().map(|p| p.name()).unwrap_or(tr(lang, "Custom"));
+        ui.label(format!("{}: {}", tr(lang, "Current"), current_name));
+        
+        ui.horizontal(|ui| {
+            if ui.button(tr(lang, "🖼️ Preset Gallery")).clicked() {
+                self.ui_state.show_preset_gallery = !self.ui_state.show_preset_gallery;
+            }
+            if ui.button(tr(lang, "🎬 Scenes")).clicked() {
+                self.ui_state.show_scenes = !self.ui_state.show_scenes;
+            }
+            if ui.button(tr(lang, "🌐 Community")).clicked() {
+                self.ui_state.show_community = !self.ui_state.show_community;
+            }
+        });
+        
+        ui.add(egui::Slider::new(&mut self.ui_state.species_count_slider, 1..=32)
+            .text(tr(lang, "Species")));
+        ui.horizontal(|ui| {
+            if ui.button(tr(lang, "🎲 Random Particle Life")).clicked() {
+                self.randomize_particle_life();
+            }
+            if ui.button(tr(lang, "🧬 Evolution")).clicked() {
+                self.ui_state.show_evolution = !self.ui_state.show_evolution;
+            }
+        });
+    }
+
+    fn draw_evolution(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        let running = self.evolution.is_some();
+        let options = &mut self.ui_state.evolution_config;
+        
+        ui.add_enabled_ui(!running, |ui| {
+            egui::ComboBox::from_label(tr(lang, "Fitness"))
+                .selected_text(tr(lang, options.fitness.name()))
+                .show_ui(ui, |ui| {
+                    for fitness in FitnessKind::all() {
+                        ui.selectable_value(&mut options.fitness, fitness, tr(lang, fitness.name()));
+                    }
+                });
+            ui.add(egui::Slider::new(&mut options.species_count, 2..=8).text(tr(lang, "Species")));
+            ui.add(egui::Slider::new(&mut options.population, 4..=32).text(tr(lang, "Population")));
+            ui.add(egui::Slider::new(&mut options.elite, 1..=8).text(tr(lang, "Elite")));
+            ui.add(egui::Slider::new(&mut options.mutation_rate, 0.0..=1.0).text(tr(lang, "Mutation Rate")));
+            ui.add(egui::Slider::new(&mut options.mutation_scale, 0.0..=1.0).text(tr(lang, "Mutation Scale")));
+            ui.add(egui::Slider::new(&mut options.particle_count, 20..=400).text(tr(lang, "Particles")));
+            ui.add(egui::Slider::new(&mut options.steps, 30..=1000).text(tr(lang, "Steps per Run")));
+        });
+        
+        ui.horizontal(|ui| {
+            if running {
+                if ui.button(tr(lang, "⏹ Stop")).clicked() {
+                    self.evolution = None;
+                }
+            } else if ui.button(tr(lang, "▶ Start Search")).clicked() {
+                self.start_evolution();
+            }
+        });
+        
+        let Some(runner) = &self.evolution else {
+            return;
+        };
+        ui.label(format!("{}: {}", tr(lang, "Generation"), runner.generation));
+        ui.separator();
+        
+        let ctx = ui.ctx().clone();
+        if self.ui_state.evolution_thumbnails.len() != runner.best.len() {
+            self.ui_state.evolution_thumbnails = runner
+                .best
+                .iter()
+                .enumerate()
+                .map(|(i, candidate)| load_thumbnail_texture(&ctx, &format!("evolution:{}", i), &candidate.thumbnail))
+                .collect();
+        }
+        
+        let mut selected = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (i, (candidate, texture)) in runner.best.iter().zip(&self.ui_state.evolution_thumbnails).enumerate() {
+                let name = format!("#{} — {:.2}", i + 1, candidate.score);
+                let description = format!("{} {}", tr(lang, "Generation"), candidate.generation);
+                if draw_gallery_entry(ui, lang, texture, &name, &description) {
+                    selected = Some(candidate.genome.clone());
+                }
+            }
+        });
+        
+        if let Some(genome) = selected {
+            let species_count = self.ui_state.evolution_config.species_count;
+            self.config_manager.apply_particle_life_rules(species_count, genome.interactions);
+            self.ui_state.species_count_slider = species_count;
+            self.rebuild_simulation(Preset::ParticleLife);
+        }
+    }
+
+    // Searches for Particle Life rules in the background; candidates run with the stock preset settings
+    pub fn start_evolution(&mut self) {
+        let options = self.ui_state.evolution_config.clone();
+        let fitness = Box::new(options.fitness);
+        let mut base = ConfigManager::new();
+        base.apply_preset(Preset::ParticleLife);
+        let evolution = Evolution::new(options, base.config().clone(), fitness);
+        match EvolutionRunner::start(evolution) {
+            Ok(runner) => {
+                self.ui_state.evolution_thumbnails.clear();
+                self.evolution = Some(runner);
+            },
+            Err(e) => self.notifications.error(e),
+        }
+    }
+
+    fn draw_community(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        let busy = self.ui_state.community.is_busy();
+        ui.horizontal(|ui| {
+            ui.label(tr(lang, "Server"));
+            ui.text_edit_singleline(&mut self.config_manager.config_mut().ui.community_server);
+        });
+        
+        let kind = self.ui_state.community_kind;
+        let kind_name = |kind: Option<EntryKind>| kind.map(|kind| tr(lang, kind.name())).unwrap_or(tr(lang, "All"));
+        let mut refresh = ui.horizontal(|ui| {
+            egui::ComboBox::from_label(tr(lang, "Show"))
+                .selected_text(kind_name(kind))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.ui_state.community_kind, None, kind_name(None));
+                    for kind in EntryKind::all() {
+                        ui.selectable_value(&mut self.ui_state.community_kind, Some(kind), kind_name(Some(kind)));
+                    }
+                });
+            let clicked = ui.add_enabled(!busy, egui::Button::new(tr(lang, "🔄 Refresh"))).clicked();
+            if busy {
+                ui.spinner();
+            }
+            clicked
+        }).inner;
+        refresh |= self.ui_state.community_kind != kind || (self.ui_state.community_entries.is_none() && !busy);
+        if refresh {
+            self.browse_community();
+        }
+        ui.separator();
+        
+        let mut to_load = None;
+        let entries = self.ui_state.community_entries.as_deref().unwrap_or_default();
+        if entries.is_empty() && !busy {
+            ui.label(tr(lang, "Nothing shared yet"));
+        }
+        egui::ScrollArea::vertical().max_height(280.0).show(ui, |ui| {
+            for entry in entries {
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!busy, egui::Button::new(tr(lang, "📥 Load"))).clicked() {
+                        to_load = Some(entry.id);
+                    }
+                    ui.strong(entry.name.as_str());
+                    ui.weak(tr(lang, entry.kind.name()));
+                    if !entry.author.is_empty() {
+                        ui.label(format!("{} {}", tr(lang, "by"), entry.author));
+                    }
+                });
+            }
+        });
+        if let Some(id) = to_load {
+            let server = self.config_manager.config().ui.community_server.clone();
+            self.ui_state.community.fetch(&server, id);
+        }
+        
+        ui.separator();
+        ui.heading(tr(lang, "Share"));
+        ui.horizontal(|ui| {
+            ui.label(tr(lang, "Name"));
+            ui.text_edit_singleline(&mut self.ui_state.community_name);
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr(lang, "Author"));
+            ui.text_edit_singleline(&mut self.ui_state.community_author);
+        });
+        let share = ui.horizontal(|ui| {
+            let rules = ui.add_enabled(!busy, egui::Button::new(tr(lang, "📤 Share Rule Code"))).clicked().then_some(EntryKind::RuleCode);
+            let preset = ui.add_enabled(!busy, egui::Button::new(tr(lang, "📤 Share Preset"))).clicked().then_some(EntryKind::Preset);
+            rules.or(preset)
+        }).inner;
+        if let Some(kind) = share {
+            self.share_to_community(kind);
+        }
+    }
+    
+    fn browse_community(&mut self) {
+        let server = self.config_manager.config().ui.community_server.clone();
+        self.ui_state.community.browse(&server, self.ui_state.community_kind);
+    }
+    
+    // Uploads the current rules, or the current config and particles as a custom preset
+    pub fn share_to_community(&mut self, kind: EntryKind) {
+        let name = self.ui_state.community_name.trim().to_string();
+        let content = match kind {
+            EntryKind::RuleCode => self.rule_code(),
+            EntryKind::Preset => serde_json::to_string(&CustomPresetData {
+                name: name.clone(),
+                description: format!("Shared preset with {} particles", self.particle_system.particle_count()),
+                config: self.config_manager.config().clone(),
+                initial_particles: self.particle_system.particles.clone(),
+            }).unwrap_or_default(),
+        };
+        let submission = Submission { name, author: self.ui_state.community_author.clone(), kind, content };
+        match submission.validate() {
+            Ok(()) => {
+                let server = self.config_manager.config().ui.community_server.clone();
+                self.ui_state.community.share(&server, &submission);
+            },
+            Err(e) => self.notifications.warn(e),
+        }
+    }
+    
+    fn handle_community_reply(&mut self, reply: CommunityReply) {
+        match reply {
+            CommunityReply::Listing(entries) => self.ui_state.community_entries = Some(entries),
+            CommunityReply::Entry(entry) => match self.load_community_entry(&entry) {
+                Ok(()) => self.notifications.info(format!("Loaded {}", entry.name)),
+                Err(e) => self.notifications.error(format!("Failed to load {}: {}", entry.name, e)),
+            },
+            CommunityReply::Shared(id) => {
+                self.notifications.info(format!("Shared as #{}", id));
+                self.browse_community();
+            },
+            CommunityReply::Failed(e) => {
+                self.notifications.error(format!("Community server: {}", e));
+                // Stop the window from retrying on every frame
+                self.ui_state.community_entries.get_or_insert_with(Vec::new);
+            },
+        }
+    }
+    
+    pub fn load_community_entry(&mut self, entry: &CommunityEntry) -> Result<(), String> {
+        match entry.kind {
+            EntryKind::RuleCode => self.load_rule_code(&entry.content).map(|_| ()),
+            EntryKind::Preset => {
+                self.apply_config_file(ConfigFile::parse(&entry.content)?);
+                Ok(())
+            },
+        }
+    }
+
+    fn draw_preset_gallery(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        if self.ui_state.custom_presets.is_none() {
+            self.refresh_custom_presets();
+        }
+        
+        if ui.button(tr(lang, "🔄 Refresh Custom Presets")).clicked() {
+            self.refresh_custom_presets();
+        }
+        ui.separator();
+        
+        let ctx = ui.ctx().clone();
+        let mut selected_builtin = None;
+        let mut selected_custom = None;
+        
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (i, preset) in Preset::all().iter().enumerate() {
+                let key = format!("builtin:{}", preset.name());
+                let texture = self.ui_state.preset_thumbnails
+                    .entry(key.clone())
+                    .or_insert_with(|| {
+                        let thumbnail = PresetManager::generate_preview(
+                            preset,
+                            THUMBNAIL_PREVIEW_STEPS,
+                            THUMBNAIL_SIZE[0],
+                            THUMBNAIL_SIZE[1],
+                        );
+                        load_thumbnail_texture(&ctx, &key, &thumbnail)
+                    });
+                
+                if draw_gallery_entry(ui, lang, texture, preset.name(), preset.description()) {
+                    selected_builtin = Some((i, preset.clone()));
+                }
+            }
+            
+            if let Some(custom_presets) = &self.ui_state.custom_presets {
+                if !custom_presets.is_empty() {
+                    ui.heading(tr(lang, "Custom Presets"));
+                }
+                
+                for (i, data) in custom_presets.iter().enumerate() {
+                    let key = format!("custom:{}", data.name);
+                    let texture = self.ui_state.preset_thumbnails
+                        .entry(key.clone())
+                        .or_insert_with(|| {
+                            let thumbnail = PresetManager::render_thumbnail(
+                                &data.initial_particles,
+                                None,
+                                THUMBNAIL_SIZE[0],
+                                THUMBNAIL_SIZE[1],
+                            );
+                            load_thumbnail_texture(&ctx, &key, &thumbnail)
+                        });
+                    
+                    if draw_gallery_entry(ui, lang, texture, &data.name, &data.description) {
+                        selected_custom = Some(i);
+                    }
+                }
+            }
+        });
+        
+        if let Some((i, preset)) = selected_builtin {
+            self.ui_state.selected_preset = i;
+            self.apply_preset(preset);
+        } else if let Some(i) = selected_custom {
+            let data = self.ui_state.custom_presets.as_ref().and_then(|presets| presets.get(i).cloned());
+            if let Some(data) = data {
+                self.apply_custom_preset(data);
+            }
+        }
+    }
+
+    fn refresh_custom_presets(&mut self) {
+        let names = match PresetManager::list_custom_presets() {
+            Ok(names) => names,
+            Err(e) => {
+                self.notifications.warn(format!("Could not list custom presets: {}", e));
+                Vec::new()
+            }
+        };
+        
+        // Files in presets/ that are not CustomPresetData (e.g. raw configs) are skipped
+        let custom_presets: Vec<CustomPresetData> = names
+            .iter()
+            .filter_map(|name| PresetManager::load_custom_preset(name).ok())
+            .collect();
+        
+        let skipped = names.len() - custom_presets.len();
+        if skipped > 0 {
+            self.notifications.warn(format!("Skipped {} unreadable preset file(s)", skipped));
+        }
+        
+        self.ui_state.custom_presets = Some(custom_presets);
+        self.ui_state.preset_thumbnails.retain(|key, _| !key.starts_with("custom:"));
+    }
+
+    fn draw_particle_controls(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        ui.heading(tr(lang, "Particles"));
+        
+        let config = self.config_manager.config_mut();
+        
+        ui.add(egui::Slider::new(&mut config.particles.spawn_rate, 0.0..=1000.0)
+            .logarithmic(true)
+            .text(tr(lang, "Spawn Rate")));
+        
+        let mut burst = None;
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::new(&mut self.ui_state.burst_count).clamp_range(1..=2000));
+            if ui.button(tr(lang, "💥 Burst")).on_hover_text(tr(lang, "Spawn this many particles at once in the spawn area")).clicked() {
+                burst = Some(self.ui_state.burst_count);
+            }
+        });
+        
+        ui.add(egui::Slider::new(&mut config.particles.max_particles, 10..=2000)
+            .text(tr(lang, "Max Particles")));
+        
+        ui.add(egui::Slider::new(&mut config.particles.default_size, 0.5..=10.0)
+            .text(tr(lang, "Default Size")));
+        
+        ui.add(egui::Slider::new(&mut config.particles.warm_up_steps, 0..=2000)
+            .text(tr(lang, "Warm-Up Steps")));
+        
+        let initial = &mut config.particles.initial_velocity;
+        egui::ComboBox::from_label(tr(lang, "Initial Velocity"))
+            .selected_text(tr(lang, initial.name()))
+            .show_ui(ui, |ui| {
+                if ui.selectable_label(*initial == InitialVelocity::Uniform, tr(lang, "Uniform")).clicked() {
+                    *initial = InitialVelocity::Uniform;
+                }
+                let curl = matches!(initial, InitialVelocity::CurlNoise(_));
+                if ui.selectable_label(curl, tr(lang, "Curl Noise")).clicked() && !curl {
+                    *initial = InitialVelocity::CurlNoise(CurlNoiseVelocity::default());
+                }
+            });
+        if let InitialVelocity::CurlNoise(field) = initial {
+            edit_curl_noise(ui, lang, field);
+            ui.label(tr(lang, "Applies when the simulation is reset"));
+        }
+        
+        ui.collapsing(tr(lang, "⏳ Lifespan"), |ui| {
+            let particles = &mut config.particles;
+            let mut finite = particles.default_lifespan.is_finite();
+            if ui.checkbox(&mut finite, tr(lang, "Finite Lifespan")).changed() {
+                particles.default_lifespan = if finite { 5.0 } else { f32::INFINITY };
+            }
+            if finite {
+                ui.add(egui::Slider::new(&mut particles.default_lifespan, 0.5..=60.0).text(tr(lang, "Lifespan")));
+                ui.add(egui::Slider::new(&mut particles.lifespan_variation, 0.0..=1.0).text(tr(lang, "Lifespan Variation")));
+            }
+            ui.label(tr(lang, "Applies to particles created from now on"));
+        });
+        
+        ui.collapsing(tr(lang, "🎆 Bursts"), |ui| {
+            let bursts = &mut config.particles.bursts;
+            ui.checkbox(&mut bursts.enabled, tr(lang, "Enabled"));
+            ui.add(egui::Slider::new(&mut bursts.interval, 0.1..=10.0).text(tr(lang, "Interval")));
+            ui.add(egui::Slider::new(&mut bursts.particles_per_burst, 1..=500).text(tr(lang, "Particles per Burst")));
+            ui.add(egui::Slider::new(&mut bursts.speed_range.0, 0.0..=500.0).text(tr(lang, "Min Speed")));
+            ui.add(egui::Slider::new(&mut bursts.speed_range.1, 0.0..=500.0).text(tr(lang, "Max Speed")));
+            ui.add(egui::Slider::new(&mut bursts.lifespan, 0.1..=10.0).text(tr(lang, "Lifespan")));
+            let mut swirl = bursts.swirl.is_some();
+            if ui.checkbox(&mut swirl, tr(lang, "Swirl")).on_hover_text(tr(lang, "Curl the sparks with a noise flow")).changed() {
+                bursts.swirl = swirl.then(CurlNoiseVelocity::default);
+            }
+            if let Some(field) = bursts.swirl.as_mut() {
+                edit_curl_noise(ui, lang, field);
+            }
+        });
+        
+        ui.collapsing(tr(lang, "🐜 Agents"), |ui| {
+            let agents = &mut config.agents;
+            ui.checkbox(&mut agents.enabled, tr(lang, "Enabled"));
+            ui.add(egui::Slider::new(&mut agents.radius, 1.0..=100.0).text(tr(lang, "Neighbor Radius")));
+            for rule in agents.rules.iter_mut() {
+                let mut label = format!("{} → {}", config.species.name(rule.from), config.species.name(rule.to));
+                if let Some(neighbor) = rule.neighbor {
+                    label += &format!(" ({} ≥ {})", config.species.name(neighbor), rule.min_neighbors);
+                }
+                ui.add(egui::Slider::new(&mut rule.chance, 0.0..=1.0).logarithmic(true).text(label));
+            }
+        });
+        
+        ui.collapsing(tr(lang, "🍄 Pheromones"), |ui| {
+            let pheromones = &mut config.pheromones;
+            ui.checkbox(&mut pheromones.enabled, tr(lang, "Enabled"));
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut pheromones.show, tr(lang, "Show Trails"));
+                ui.color_edit_button_rgba_unmultiplied(&mut pheromones.color);
+            });
+            ui.add(egui::Slider::new(&mut pheromones.cell_size, 2.0..=20.0).text(tr(lang, "Cell Size")));
+            ui.add(egui::Slider::new(&mut pheromones.deposit, 0.0..=50.0).text(tr(lang, "Deposit")));
+            ui.add(egui::Slider::new(&mut pheromones.diffusion, 0.0..=20.0).text(tr(lang, "Diffusion")));
+            ui.add(egui::Slider::new(&mut pheromones.evaporation, 0.0..=5.0).text(tr(lang, "Evaporation")));
+            ui.add(egui::Slider::new(&mut pheromones.sensor_distance, 1.0..=60.0).text(tr(lang, "Sensor Distance")));
+            ui.add(egui::Slider::new(&mut pheromones.sensor_angle, 5.0..=90.0).text(tr(lang, "Sensor Angle")));
+            ui.add(egui::Slider::new(&mut pheromones.turn_rate, 0.0..=1800.0).text(tr(lang, "Turn Rate")));
+            ui.add(egui::Slider::new(&mut pheromones.speed, 1.0..=200.0).text(tr(lang, "Speed")));
+        });
+        
+        ui.collapsing(tr(lang, "🧪 Gray-Scott"), |ui| {
+            let reaction = &mut config.reaction;
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut reaction.enabled, tr(lang, "Enabled"));
+                if ui.button(tr(lang, "Reseed")).clicked() {
+                    self.reaction = None;
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut reaction.show, tr(lang, "Show Field"));
+                ui.color_edit_button_rgba_unmultiplied(&mut reaction.colors[0]);
+                ui.color_edit_button_rgba_unmultiplied(&mut reaction.colors[1]);
+            });
+            ui.add(egui::Slider::new(&mut reaction.feed, 0.0..=0.1).text(tr(lang, "Feed")));
+            ui.add(egui::Slider::new(&mut reaction.kill, 0.0..=0.1).text(tr(lang, "Kill")));
+            ui.add(egui::Slider::new(&mut reaction.diffusion_u, 0.0..=1.0).text(tr(lang, "Diffusion U")));
+            ui.add(egui::Slider::new(&mut reaction.diffusion_v, 0.0..=1.0).text(tr(lang, "Diffusion V")));
+            ui.add(egui::Slider::new(&mut reaction.cell_size, 2.0..=20.0).text(tr(lang, "Cell Size")));
+            ui.add(egui::Slider::new(&mut reaction.iterations, 0..=32).text(tr(lang, "Reaction Steps")));
+            ui.add(egui::Slider::new(&mut reaction.deposit, 0.0..=5.0).text(tr(lang, "Deposit")));
+            ui.add(egui::Slider::new(&mut reaction.push, -50_000.0..=50_000.0).text(tr(lang, "Push")));
+        });
+        
+        ui.collapsing(tr(lang, "🍽 Metabolism"), |ui| {
+            let metabolism = &mut config.metabolism;
+            ui.checkbox(&mut metabolism.enabled, tr(lang, "Enabled"));
+            ui.add(egui::Slider::new(&mut metabolism.initial_energy, 0.1..=100.0).text(tr(lang, "Initial Energy")));
+            ui.add(egui::Slider::new(&mut metabolism.max_energy, 0.1..=200.0).text(tr(lang, "Max Energy")));
+            ui.add(egui::Slider::new(&mut metabolism.basal_cost, 0.0..=5.0).text(tr(lang, "Basal Cost")));
+            ui.add(egui::Slider::new(&mut metabolism.movement_cost, 0.0..=0.1).text(tr(lang, "Movement Cost")));
+            ui.add(egui::Slider::new(&mut metabolism.force_cost, 0.0..=0.01).text(tr(lang, "Force Cost")));
+            ui.add(egui::Slider::new(&mut metabolism.eat_radius, 0.5..=30.0).text(tr(lang, "Eat Radius")));
+            ui.add(egui::Slider::new(&mut metabolism.food_energy, 0.0..=50.0).text(tr(lang, "Food Energy")));
+            ui.add(egui::Slider::new(&mut metabolism.food_spawn_rate, 0.0..=100.0).text(tr(lang, "Food per Second")));
+        });
+        
+        ui.collapsing(tr(lang, "🧵 Cloth"), |ui| {
+            let cloth = &mut config.cloth;
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut cloth.enabled, tr(lang, "Enabled"));
+                if ui.button(tr(lang, "Reweave")).clicked() {
+                    self.cloth = None;
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut cloth.show_links, tr(lang, "Show Links"));
+                ui.color_edit_button_rgba_unmultiplied(&mut cloth.color);
+            });
+            ui.add(egui::Slider::new(&mut cloth.stiffness, 0.0..=600.0).text(tr(lang, "Stiffness")));
+            ui.add(egui::Slider::new(&mut cloth.damping, 0.0..=20.0).text(tr(lang, "Damping")));
+            ui.add(egui::Slider::new(&mut cloth.tear_stretch, 0.0..=4.0).text(tr(lang, "Tear Stretch")));
+            ui.checkbox(&mut cloth.shear, tr(lang, "Shear Links"));
+            ui.checkbox(&mut cloth.pin_top, tr(lang, "Pin Top Row"));
+            ui.label(tr(lang, "Reweaving links the particles where they are now"));
+        });
+        
+        ui.collapsing(tr(lang, "🖼 Import Image"), |ui| {
+            self.draw_image_import(ui);
+        });
+        
+        ui.collapsing(tr(lang, "✏️ Stamp Text"), |ui| {
+            self.draw_text_stamp(ui);
+        });
+        
+        ui.collapsing(tr(lang, "🖌 Brush"), |ui| {
+            self.draw_brush(ui);
+        });
+        
+        ui.collapsing(tr(lang, "📏 Measure"), |ui| {
+            self.draw_measure(ui);
+        });
+        
+        ui.collapsing(tr(lang, "⬚ Select"), |ui| {
+            self.draw_selection(ui);
+        });
+        
+        ui.collapsing(tr(lang, "✋ Grab"), |ui| {
+            let options = &mut self.ui_state.grab_options;
+            ui.checkbox(&mut self.ui_state.grab_tool, tr(lang, "Drag to grab particles"));
+            ui.add(egui::Slider::new(&mut options.stiffness, 1.0..=200.0).logarithmic(true).text(tr(lang, "Stiffness")));
+            ui.add(egui::Slider::new(&mut options.damping, 0.0..=30.0).text(tr(lang, "Damping")));
+            ui.add(egui::Slider::new(&mut options.reach, 2.0..=60.0).text(tr(lang, "Reach")));
+        });
+        
+        ui.collapsing(tr(lang, "🎯 Formation"), |ui| {
+            self.draw_formation(ui);
+        });
+        
+        if let Some(count) = burst {
+            let area = self.config_manager.config().particles.spawn_area.clone();
+            self.burst(count, &area);
+        }
+    }
+
+    fn draw_formation(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        let options = &mut self.ui_state.formation;
+        
+        ui.label(tr(lang, "Steer the existing particles into a shape"));
+        egui::ComboBox::from_label(tr(lang, "Targets From"))
+            .selected_text(tr(lang, options.source.name()))
+            .show_ui(ui, |ui| {
+                for source in FormationSource::all() {
+                    ui.selectable_value(&mut options.source, source, tr(lang, source.name()));
+                }
+            });
+        ui.add(egui::Slider::new(&mut options.max_speed, 1.0..=500.0).text(tr(lang, "Max Speed")));
+        ui.add(egui::Slider::new(&mut options.max_force, 1.0..=1000.0).text(tr(lang, "Max Force")));
+        ui.add(egui::Slider::new(&mut options.arrival_radius, 1.0..=200.0).text(tr(lang, "Arrival Radius")));
+        
+        ui.horizontal(|ui| {
+            if ui.button(tr(lang, "🎯 Seek")).clicked() {
+                self.seek_formation();
+            }
+            if self.has_formation() && ui.button(tr(lang, "Release")).clicked() {
+                self.release_formation();
+            }
+        });
+    }
+
+    // Builds target points from the selected source and steers the particles onto them
+    pub fn seek_formation(&mut self) {
+        let center = self.renderer.camera_state().0;
+        let count = self.particle_system.particle_count();
+        let targets = match self.ui_state.formation.source {
+            FormationSource::Brush => {
+                Ok(ShapeSampler::new(self.ui_state.brush.shape, center).sample(count, &mut rand::thread_rng()))
+            },
+            FormationSource::Text => stamp::text_positions(&self.ui_state.text_stamp, center),
+            FormationSource::Image => {
+                let mut options = self.ui_state.image_import.clone();
+                options.center = center;
+                options.max_particles = count.max(1);
+                image_import::particles_from_image(&self.ui_state.image_import_path, &options, &self.config_manager.config().species)
+                    .map(|particles| particles.iter().map(|p| p.position).collect())
+                    .map_err(|e| format!("Failed to import {}: {}", self.ui_state.image_import_path, e))
+            },
+        };
+        
+        match targets {
+            Ok(targets) if !targets.is_empty() => self.set_formation(&targets),
+            Ok(_) => self.notifications.warn("The formation has no target points"),
+            Err(e) => self.notifications.error(e),
+        }
+    }
+
+    // Replaces any current formation; calling this repeatedly morphs the swarm between shapes
+    pub fn set_formation(&mut self, targets: &[Vec2]) {
+        let positions: Vec<Vec2> = self.particle_system.particles.iter().map(|p| p.position).collect();
+        let force = self.ui_state.formation.seek_force(&positions, targets);
+        self.release_formation();
+        self.physics_engine.force_calculator.add_global_force(force);
+    }
+
+    pub fn has_formation(&self) -> bool {
+        self.physics_engine.force_calculator.global_forces
+            .iter()
+            .any(|force| matches!(force, ForceType::SeekTargets { .. }))
+    }
+
+    pub fn release_formation(&mut self) {
+        self.physics_engine.force_calculator.global_forces
+            .retain(|force| !matches!(force, ForceType::SeekTargets { .. }));
+    }
+
+    fn draw_measure(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        let species = &self.config_manager.config().species;
+        let units = &self.config_manager.config().units;
+        let measurement = &mut self.ui_state.measurement;
+        
+        ui.checkbox(&mut self.ui_state.measure_tool, tr(lang, "Drag to measure"));
+        for (label, species_id) in [("Species A", &mut measurement.species_a), ("Species B", &mut measurement.species_b)] {
+            egui::ComboBox::from_label(tr(lang, label))
+                .selected_text(species.name(*species_id))
+                .show_ui(ui, |ui| {
+                    for definition in &species.species {
+                        ui.selectable_value(species_id, definition.id, &definition.name);
+                    }
+                });
+        }
+        
+        ui.label(format!("{}: {}", tr(lang, "Distance"), units.format_length(measurement.distance())));
+        let force = measurement.force(&self.physics_engine.force_calculator);
+        ui.label(format!("{}: {:.3}", tr(lang, "Force (+ repulsive)"), force));
+    }
+
+    fn draw_selection(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        let state = &mut self.ui_state;
+        
+        ui.checkbox(&mut state.select_tool, tr(lang, "Drag to select"));
+        ui.horizontal(|ui| {
+            for mode in SelectionMode::all() {
+                ui.selectable_value(&mut state.selection_mode, mode, tr(lang, mode.name()));
+            }
+        });
+        ui.label(format!("{}: {}", tr(lang, "Selected"), state.selection.len()));
+        
+        let system = &mut self.particle_system;
+        ui.add_enabled_ui(!state.selection.is_empty(), |ui| {
+            ui.horizontal(|ui| {
+                if ui.button(tr(lang, "⏹ Stop")).clicked() {
+                    state.selection.stop(system);
+                }
+                if ui.button(tr(lang, "📋 Copy")).clicked() {
+                    state.clipboard = ParticleClipboard::copy(system, &state.selection.ids);
+                }
+            });
+            
+            let species = &self.config_manager.config().species;
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_source("selection_species")
+                    .selected_text(species.name(state.selection_species))
+                    .show_ui(ui, |ui| {
+                        for definition in &species.species {
+                            ui.selectable_value(&mut state.selection_species, definition.id, &definition.name);
+                        }
+                    });
+                if ui.button(tr(lang, "Set Species")).clicked() {
+                    state.selection.set_species(system, species, state.selection_species);
+                }
+            });
+            
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut state.selection_impulse.x).speed(1.0).prefix("x: "));
+                ui.add(egui::DragValue::new(&mut state.selection_impulse.y).speed(1.0).prefix("y: "));
+                if ui.button(tr(lang, "Apply Impulse")).clicked() {
+                    state.selection.apply_impulse(system, state.selection_impulse);
+                }
+            });
+        });
+        
+        ui.horizontal(|ui| {
+            if !self.ui_state.selection.is_empty() {
+                if ui.button(tr(lang, "Duplicate")).clicked() {
+                    self.duplicate_selection();
+                }
+                if ui.button(tr(lang, "🗑 Delete")).clicked() {
+                    self.delete_selection();
+                }
+            }
+            if self.ui_state.clipboard.is_some() && ui.button(tr(lang, "📌 Paste")).clicked() {
+                let center = self.renderer.camera_state().0;
+                self.paste_clipboard(center);
+            }
+        });
+        
+        ui.separator();
+        self.draw_stamp_library(ui);
+    }
+    
+    // Saving the clipboard as a named stamp, and loading saved stamps back into it
+    fn draw_stamp_library(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        if self.ui_state.stamp_list.is_none() {
+            self.ui_state.stamp_list = Some(ParticleClipboard::list());
+        }
+        
+        if let Some(clipboard) = &self.ui_state.clipboard {
+            ui.label(format!("{}: {}", tr(lang, "Clipboard"), clipboard.len()));
+            ui.horizontal(|ui| {
+                ui.label(tr(lang, "Name"));
+                ui.text_edit_singleline(&mut self.ui_state.stamp_name);
+            });
+            if ui.button(tr(lang, "💾 Save Stamp")).clicked() {
+                let name = self.ui_state.stamp_name.trim().to_string();
+                if name.is_empty() {
+                    self.notifications.warn("Enter a stamp name before saving");
+                } else {
+                    match clipboard.save(&name) {
+                        Ok(()) => self.notifications.info(format!("Saved stamp {}", name)),
+                        Err(e) => self.notifications.error(e),
+                    }
+                    self.ui_state.stamp_list = None;
+                }
+            }
+        }
+        
+        let names = self.ui_state.stamp_list.clone().unwrap_or_default();
+        if names.is_empty() {
+            ui.label(tr(lang, "No saved stamps"));
+        }
+        let mut to_load = None;
+        let mut to_delete = None;
+        for name in &names {
+            ui.horizontal(|ui| {
+                ui.label(name);
+                if ui.button(tr(lang, "Load")).clicked() {
+                    to_load = Some(name.clone());
+                }
+                if ui.button(tr(lang, "Delete")).clicked() {
+                    to_delete = Some(name.clone());
+                }
+            });
+        }
+        
+        if let Some(name) = to_load {
+            match ParticleClipboard::load(&name) {
+                Ok(clipboard) => {
+                    self.ui_state.stamp_name = name;
+                    self.ui_state.clipboard = Some(clipboard);
+                },
+                Err(e) => self.notifications.error(e),
+            }
+        }
+        if let Some(name) = to_delete {
+            if let Err(e) = ParticleClipboard::delete(&name) {
+                self.notifications.error(e);
+            }
+            self.ui_state.stamp_list = None;
+        }
+    }
+
+    // Removes the selected particles; holds, groups and grabs on them let go on their next step
+    pub fn delete_selection(&mut self) -> usize {
+        self.ui_state.selection.delete(&mut self.particle_system)
+    }
+
+    pub fn copy_selection(&mut self) -> usize {
+        self.ui_state.clipboard = ParticleClipboard::copy(&self.particle_system, &self.ui_state.selection.ids);
+        self.ui_state.clipboard.as_ref().map_or(0, ParticleClipboard::len)
+    }
+
+    // Adds the clipboard's particles centred on `center` and selects them, so they can be moved
+    // or edited straight away; returns how many fit under the particle limit
+    pub fn paste_clipboard(&mut self, center: Vec2) -> usize {
+        let Some(clipboard) = &self.ui_state.clipboard else {
+            return 0;
+        };
+        let start = self.particle_system.particle_count();
+        for particle in clipboard.paste_at(center) {
+            self.particle_system.add_particle(particle);
+        }
+        let end = self.particle_system.particle_count();
+        self.ui_state.selection = Selection { ids: self.particle_system.particles[start..end].iter().map(|particle| particle.id).collect() };
+        if end > start {
+            self.current_preset = None;
+        }
+        end - start
+    }
+
+    // Copies the selection and pastes it slightly offset from the original
+    pub fn duplicate_selection(&mut self) -> usize {
+        let Some(centroid) = self.ui_state.selection.centroid(&self.particle_system) else {
+            return 0;
+        };
+        self.copy_selection();
+        let zoom = self.renderer.camera_state().1;
+        self.paste_clipboard(centroid + Vec2::new(20.0, -20.0) / zoom)
+    }
+
+    fn draw_brush(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        let species = &self.config_manager.config().species;
+        let brush = &mut self.ui_state.brush;
+        
+        ui.checkbox(&mut self.ui_state.brush_tool, tr(lang, "Click to paint"));
+        
+        egui::ComboBox::from_label(tr(lang, "Shape"))
+            .selected_text(tr(lang, brush.shape.name()))
+            .show_ui(ui, |ui| {
+                for shape in BrushShape::all(100.0) {
+                    if ui.selectable_label(shape.name() == brush.shape.name(), tr(lang, shape.name())).clicked() {
+                        brush.shape = shape;
+                    }
+                }
+            });
+        
+        match &mut brush.shape {
+            BrushShape::Rect { half_extents } | BrushShape::Grid { half_extents } => {
+                ui.add(egui::Slider::new(&mut half_extents.x, 1.0..=500.0).text(tr(lang, "Half Width")));
+                ui.add(egui::Slider::new(&mut half_extents.y, 1.0..=500.0).text(tr(lang, "Half Height")));
+            },
+            BrushShape::Circle { radius } => {
+                ui.add(egui::Slider::new(radius, 1.0..=500.0).text(tr(lang, "Radius")));
+            },
+            BrushShape::Ring { inner_radius, outer_radius } => {
+                ui.add(egui::Slider::new(outer_radius, 1.0..=500.0).text(tr(lang, "Radius")));
+                ui.add(egui::Slider::new(inner_radius, 0.0..=*outer_radius).text(tr(lang, "Inner Radius")));
+            },
+            BrushShape::Spiral { radius, turns, arms } => {
+                ui.add(egui::Slider::new(radius, 1.0..=500.0).text(tr(lang, "Radius")));
+                ui.add(egui::Slider::new(turns, 0.25..=10.0).text(tr(lang, "Turns")));
+                ui.add(egui::Slider::new(arms, 1..=8).text(tr(lang, "Arms")));
+            },
+            BrushShape::GaussianBlob { sigma } => {
+                ui.add(egui::Slider::new(sigma, 1.0..=200.0).text(tr(lang, "Spread")));
+            },
+        }
+        ui.add(egui::Slider::new(&mut brush.density, 1.0..=200.0)
+            .logarithmic(true)
+            .text(tr(lang, "Density")));
+        draw_stamp_options(ui, lang, species, &mut brush.stamp);
+        
+        if ui.button(tr(lang, "🖌 Stamp at View Center")).clicked() {
+            let center = self.renderer.camera_state().0;
+            self.stamp_brush(center);
+        }
+    }
+
+    // Paints the current brush at `center`; returns how many particles were added
+    pub fn stamp_brush(&mut self, center: Vec2) -> usize {
+        let brush = self.ui_state.brush.clone();
+        let positions = ShapeSampler::new(brush.shape, center).sample_density(brush.density, &mut rand::thread_rng());
+        self.stamp(positions, center, &brush.stamp)
+    }
+
+    fn draw_text_stamp(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        let species = &self.config_manager.config().species;
+        let options = &mut self.ui_state.text_stamp;
+        
+        ui.text_edit_multiline(&mut options.text);
+        ui.horizontal(|ui| {
+            ui.label(tr(lang, "Font"));
+            ui.text_edit_singleline(&mut options.font);
+            ui.menu_button("▾", |ui| {
+                for font in stamp::builtin_fonts() {
+                    if ui.button(&font).clicked() {
+                        options.font = font;
+                        ui.close_menu();
+                    }
+                }
+            });
+        });
+        ui.add(egui::Slider::new(&mut options.font_size, 20.0..=600.0).text(tr(lang, "Size")));
+        ui.add(egui::Slider::new(&mut options.spacing, 1.0..=20.0).text(tr(lang, "Particle Spacing")));
+        draw_stamp_options(ui, lang, species, &mut options.stamp);
+        
+        ui.horizontal(|ui| {
+            if ui.button(tr(lang, "✏️ Stamp")).clicked() {
+                self.stamp_text();
+            }
+            if (self.shape_hold.is_some() || !self.rigid_groups.is_empty()) && ui.button(tr(lang, "Release")).clicked() {
+                self.shape_hold = None;
+                self.rigid_groups.clear();
+            }
+        });
+    }
+
+    pub fn stamp_text(&mut self) {
+        let options = self.ui_state.text_stamp.clone();
+        let center = self.renderer.camera_state().0;
+        match stamp::text_positions(&options, center) {
+            Ok(positions) => {
+                let count = self.stamp(positions, center, &options.stamp);
+                self.notifications.info(format!("Stamped {} particles", count));
+            },
+            Err(e) => self.notifications.error(e),
+        }
+    }
+
+    // Spawns particles at the given positions and applies the stamp mode; returns how many were added
+    pub fn stamp(&mut self, positions: Vec<Vec2>, center: Vec2, options: &StampOptions) -> usize {
+        let config = self.config_manager.config();
+        let start = self.particle_system.particle_count();
+        let mut rng = rand::thread_rng();
+        
+        for &position in &positions {
+            let mut particle = config.species
+                .particle(options.species_id, position)
+                .with_lifespan(config.particles.sample_lifespan(&mut rng));
+            if config.metabolism.enabled && config.metabolism.metabolizes(options.species_id) {
+                particle.energy = config.metabolism.initial_energy;
+            }
+            if options.mode == StampMode::Disperse {
+                let outward = (position - center).normalize_or_zero();
+                particle.velocity = outward * options.strength * rng.gen_range(0.5..1.0);
+            }
+            self.particle_system.add_particle(particle);
+        }
+        
+        let added = self.particle_system.particle_count() - start;
+        let ids: Vec<ParticleId> = self.particle_system.particles[start..].iter().map(|particle| particle.id).collect();
+        if options.mode == StampMode::Hold && added > 0 {
+            let homes = ids.into_iter().zip(positions).collect();
+            self.shape_hold = Some(ShapeHold::new(homes, options.strength));
+        } else if options.mode == StampMode::Rigid && added > 0 {
+            self.rigid_groups.push(RigidGroup::new(ids, &self.particle_system));
+        }
+        self.current_preset = None;
+        added
+    }
+
+    fn draw_image_import(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        let options = &mut self.ui_state.image_import;
+        
+        ui.horizontal(|ui| {
+            ui.label(tr(lang, "File"));
+            ui.text_edit_singleline(&mut self.ui_state.image_import_path);
+        });
+        egui::ComboBox::from_label(tr(lang, "Threshold On"))
+            .selected_text(tr(lang, options.channel.name()))
+            .show_ui(ui, |ui| {
+                for channel in ThresholdChannel::all() {
+                    ui.selectable_value(&mut options.channel, channel, tr(lang, channel.name()));
+                }
+            });
+        ui.add(egui::Slider::new(&mut options.threshold, 0.0..=1.0).text(tr(lang, "Threshold")));
+        ui.add(egui::Slider::new(&mut options.spacing, 1..=16).text(tr(lang, "Pixel Spacing")));
+        ui.add(egui::Slider::new(&mut options.world_width, 50.0..=2000.0).text(tr(lang, "Width")));
+        ui.checkbox(&mut self.ui_state.image_import_replace, tr(lang, "Replace Existing Particles"));
+        
+        if ui.button(tr(lang, "📥 Import")).clicked() {
+            let path = self.ui_state.image_import_path.clone();
+            self.import_image(&path);
+        }
+    }
+
+    pub fn import_image(&mut self, path: &str) {
+        let mut options = self.ui_state.image_import.clone();
+        options.center = self.renderer.camera_state().0;
+        options.max_particles = self.config_manager.config().particles.max_particles;
+        
+        match image_import::particles_from_image(path, &options, &self.config_manager.config().species) {
+            Ok(particles) => {
+                let count = particles.len();
+                self.import_particles(particles, self.ui_state.image_import_replace);
+                self.notifications.info(format!("Imported {} particles from {}", count, path));
+            },
+            Err(e) => {
+                self.notifications.error(format!("Failed to import {}: {}", path, e));
+            }
+        }
+    }
+
+    pub fn import_particles(&mut self, particles: Vec<Particle>, replace: bool) {
+        if replace {
+            self.particle_system.clear();
+            self.history.clear();
+            self.shape_hold = None;
+            self.rigid_groups.clear();
+            self.ui_state.grab = None;
+        }
+        for particle in particles {
+            self.particle_system.add_particle(particle);
+        }
+        self.current_preset = None;
+    }
+
+    fn draw_force_controls(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        ui.heading(tr(lang, "Forces"));
+        
+        let config = self.config_manager.config_mut();
+        
+        ui.checkbox(&mut config.forces.enable_gravity, tr(lang, "Gravity"));
+        if config.forces.enable_gravity {
+            ui.add(egui::Slider::new(&mut config.forces.gravity_strength, 0.0..=1000.0)
+                .text(tr(lang, "Gravity Strength")));
+        }
+        
+        ui.checkbox(&mut config.forces.enable_damping, tr(lang, "Damping"));
+        if config.forces.enable_damping {
+            ui.add(egui::Slider::new(&mut config.forces.damping_coefficient, 0.0..=0.1)
+                .text(tr(lang, "Damping")));
+        }
+        
+        ui.checkbox(&mut config.forces.enable_brownian, tr(lang, "Brownian Motion"));
+        if config.forces.enable_brownian {
+            ui.add(egui::Slider::new(&mut config.forces.brownian_intensity, 0.0..=10.0)
+                .text(tr(lang, "Brownian Intensity")));
+        }
+        
+        let default_wind = ForceType::Wind { direction: Vec2::X, strength: 20.0, gustiness: 0.5 };
+        if let Some(ForceType::Wind { direction, strength, gustiness }) =
+            global_force_toggle(ui, tr(lang, "Wind"), &mut config.forces.global_forces, default_wind)
+        {
+            let mut angle = direction.y.atan2(direction.x).to_degrees();
+            if ui.add(egui::Slider::new(&mut angle, -180.0..=180.0).text(tr(lang, "Direction (°)"))).changed() {
+                *direction = Vec2::from_angle(angle.to_radians());
+            }
+            ui.add(egui::Slider::new(strength, 0.0..=200.0).text(tr(lang, "Wind Strength")));
+            ui.add(egui::Slider::new(gustiness, 0.0..=2.0).text(tr(lang, "Gustiness")));
+        }
+        
+        let default_turbulence = ForceType::Turbulence { strength: 20.0, scale: 200.0, octaves: 3, speed: 0.1 };
+        if let Some(ForceType::Turbulence { strength, scale, octaves, speed }) =
+            global_force_toggle(ui, tr(lang, "Turbulence"), &mut config.forces.global_forces, default_turbulence)
+        {
+            ui.add(egui::Slider::new(strength, 0.0..=200.0).text(tr(lang, "Turbulence Strength")));
+            ui.add(egui::Slider::new(scale, 10.0..=1000.0).text(tr(lang, "Eddy Size")));
+            ui.add(egui::Slider::new(octaves, 1..=6).text(tr(lang, "Octaves")));
+            ui.add(egui::Slider::new(speed, 0.0..=2.0).text(tr(lang, "Drift Speed")));
+        }
+        
+        let default_drag = ForceType::Drag { model: DragModel::Linear, coefficient: 0.05, size_exponent: 1.0 };
+        if let Some(ForceType::Drag { model, coefficient, size_exponent }) =
+            global_force_toggle(ui, tr(lang, "Drag"), &mut config.forces.global_forces, default_drag)
+        {
+            ui.horizontal(|ui| {
+                for option in DragModel::all() {
+                    if ui.selectable_label(*model == option, tr(lang, option.name())).clicked() && *model != option {
+                        *model = option;
+                        *size_exponent = option.size_exponent();
+                    }
+                }
+            });
+            let max = match model {
+                DragModel::Linear => 1.0,
+                DragModel::Quadratic => 0.01,
+            };
+            ui.add(egui::Slider::new(coefficient, 0.0..=max).text(tr(lang, "Drag Coefficient")));
+            ui.add(egui::Slider::new(size_exponent, 0.0..=3.0).text(tr(lang, "Size Exponent")));
+        }
+        
+        ui.collapsing(tr(lang, "🧭 Steering"), |ui| {
+            self.draw_steering_editor(ui);
+        });
+        
+        ui.collapsing(tr(lang, "🌡 Zones"), |ui| {
+            self.draw_zone_editor(ui);
+        });
+        
+        ui.collapsing(tr(lang, "🪐 Gravity Wells"), |ui| {
+            self.draw_well_editor(ui);
+        });
+        
+        if ui.button(tr(lang, "🔧 Force Editor")).clicked() {
+            self.ui_state.show_force_editor = !self.ui_state.show_force_editor;
+        }
+    }
+
+    fn draw_steering_editor(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        let species = self.config_manager.config().species.clone();
+        let steering = &mut self.config_manager.config_mut().forces.steering;
+        
+        ui.checkbox(&mut steering.enabled, tr(lang, "Enabled"));
+        ui.add(egui::Slider::new(&mut steering.max_speed, 1.0..=300.0).text(tr(lang, "Max Speed")));
+        ui.add(egui::Slider::new(&mut steering.max_force, 1.0..=1000.0).text(tr(lang, "Max Force")));
+        
+        let selected = &mut self.ui_state.steering_species;
+        egui::ComboBox::from_label(tr(lang, "Species"))
+            .selected_text(species.name(*selected))
+            .show_ui(ui, |ui| {
+                for definition in &species.species {
+                    ui.selectable_value(selected, definition.id, &definition.name);
+                }
+            });
+        
+        let path_count = steering.paths.len();
+        let behaviors = steering.species.entry(*selected).or_default();
+        let mut remove_index = None;
+        for (index, weighted) in behaviors.iter_mut().enumerate() {
+            ui.push_id(("steering_behavior", index), |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(tr(lang, weighted.behavior.name()));
+                    ui.add(egui::Slider::new(&mut weighted.weight, 0.0..=5.0).text(tr(lang, "Weight")));
+                    if ui.button("🗑").clicked() {
+                        remove_index = Some(index);
+                    }
+                });
+                edit_steering_behavior(ui, lang, &species, path_count, &mut weighted.behavior);
+            });
+        }
+        if let Some(index) = remove_index {
+            behaviors.remove(index);
+        }
+        ui.menu_button(tr(lang, "➕ Add Behavior"), |ui| {
+            for behavior in SteeringBehavior::all() {
+                if ui.button(tr(lang, behavior.name())).clicked() {
+                    behaviors.push(WeightedBehavior { behavior, weight: 1.0 });
+                    ui.close_menu();
+                }
+            }
+        });
+        
+        ui.separator();
+        ui.label(tr(lang, "Paths"));
+        let mut remove_path = None;
+        for (index, path) in steering.paths.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("#{} ({} {})", index, path.points.len(), tr(lang, "points")));
+                ui.checkbox(&mut path.closed, tr(lang, "Closed"));
+                if ui.button("🗑").clicked() {
+                    remove_path = Some(index);
+                }
+            });
+        }
+        if let Some(index) = remove_path {
+            steering.paths.remove(index);
+            self.ui_state.drawing_path = None;
+        }
+        
+        match self.ui_state.drawing_path {
+            Some(_) => {
+                ui.label(tr(lang, "Click in the world to add points"));
+                if ui.button(tr(lang, "✔ Finish Path")).clicked() {
+                    self.ui_state.drawing_path = None;
+                }
+            },
+            None => {
+                if ui.button(tr(lang, "✏️ Draw New Path")).clicked() {
+                    steering.paths.push(SteeringPath::default());
+                    self.ui_state.drawing_path = Some(steering.paths.len() - 1);
+                }
+            },
+        }
+    }
+
+    fn draw_zone_editor(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        let view_center = Vec2::new(self.renderer.camera.position.x, self.renderer.camera.position.y);
+        let zones = &mut self.config_manager.config_mut().forces.zones;
+        
+        let mut remove_index = None;
+        for (index, zone) in zones.iter_mut().enumerate() {
+            ui.push_id(("zone", index), |ui| {
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut zone.enabled, "");
+                    ui.add(egui::TextEdit::singleline(&mut zone.name).desired_width(90.0));
+                    ui.label(tr(lang, zone.effect.name()));
+                    if ui.button("🗑").clicked() {
+                        remove_index = Some(index);
+                    }
+                });
+                match &mut zone.effect {
+                    ZoneEffect::Damping { amount } => {
+                        ui.add(egui::Slider::new(amount, 0.0..=10.0).text(tr(lang, "Amount")));
+                    },
+                    ZoneEffect::GravityWell { strength } => {
+                        ui.add(egui::Slider::new(strength, -1000.0..=1000.0).text(tr(lang, "Strength")));
+                    },
+                    ZoneEffect::Temperature { temperature } => {
+                        ui.add(egui::Slider::new(temperature, -5.0..=5.0).text(tr(lang, "Temperature")));
+                    },
+                    ZoneEffect::ForceMultiplier { factor } => {
+                        ui.add(egui::Slider::new(factor, 0.0..=5.0).text(tr(lang, "Factor")));
+                    },
+                    ZoneEffect::Food { rate } => {
+                        ui.add(egui::Slider::new(rate, 0.0..=10.0).text(tr(lang, "Energy per Second")));
+                    },
+                }
+                
+                let (min, max) = zone.shape.bounds();
+                ui.horizontal(|ui| {
+                    let is_circle = matches!(zone.shape, ZoneShape::Circle { .. });
+                    if ui.selectable_label(is_circle, tr(lang, "Circle")).clicked() && !is_circle {
+                        zone.shape = ZoneShape::Circle { center: zone.shape.center(), radius: (max - min).max_element() * 0.5 };
+                    }
+                    if ui.selectable_label(!is_circle, tr(lang, "Rectangle")).clicked() && is_circle {
+                        zone.shape = ZoneShape::Rect { min, max };
+                    }
+                });
+                match &mut zone.shape {
+                    ZoneShape::Circle { center, radius } => {
+                        ui.horizontal(|ui| {
+                            ui.label(tr(lang, "Center"));
+                            ui.add(egui::DragValue::new(&mut center.x));
+                            ui.add(egui::DragValue::new(&mut center.y));
+                        });
+                        ui.add(egui::Slider::new(radius, 1.0..=1000.0).text(tr(lang, "Radius")));
+                    },
+                    ZoneShape::Rect { min, max } => {
+                        for (label, corner) in [("Min", min), ("Max", max)] {
+                            ui.horizontal(|ui| {
+                                ui.label(tr(lang, label));
+                                ui.add(egui::DragValue::new(&mut corner.x));
+                                ui.add(egui::DragValue::new(&mut corner.y));
+                            });
+                        }
+                    },
+                }
+            });
+            ui.separator();
+        }
+        if let Some(index) = remove_index {
+            zones.remove(index);
+        }
+        
+        // New zones start as circles in the middle of the view
+        ui.menu_button(tr(lang, "➕ Add Zone"), |ui| {
+            for effect in ZoneEffect::all() {
+                if ui.button(tr(lang, effect.name())).clicked() {
+                    let shape = ZoneShape::Circle { center: view_center, radius: 150.0 };
+                    zones.push(Zone::new(effect.name(), shape, effect));
+                    ui.close_menu();
+                }
+            }
+        });
+    }
+
+    fn draw_well_editor(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        ui.checkbox(&mut self.ui_state.well_tool, tr(lang, "Click to place wells"));
+        if self.ui_state.well_tool {
+            ui.small(tr(lang, "Drag to move, right-click to delete"));
+        }
+        
+        let wells = &mut self.config_manager.config_mut().forces.wells;
+        let mut remove_index = None;
+        for (index, well) in wells.iter_mut().enumerate() {
+            ui.push_id(("well", index), |ui| {
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut well.enabled, format!("#{}", index + 1));
+                    ui.add(egui::DragValue::new(&mut well.position.x));
+                    ui.add(egui::DragValue::new(&mut well.position.y));
+                    if ui.button("🗑").clicked() {
+                        remove_index = Some(index);
+                    }
+                });
+                ui.add(egui::Slider::new(&mut well.strength, -200_000.0..=200_000.0).text(tr(lang, "Strength")));
+                ui.add(egui::Slider::new(&mut well.softening, 0.0..=100.0).text(tr(lang, "Softening")));
+            });
+        }
+        if let Some(index) = remove_index {
+            wells.remove(index);
+            self.ui_state.well_drag = None;
+        }
+        
+        if !wells.is_empty() && ui.button(tr(lang, "🗑️ Clear Wells")).clicked() {
+            wells.clear();
+            self.ui_state.well_drag = None;
+        }
+    }
+
+    fn draw_rendering_controls(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        ui.heading(tr(lang, "Rendering"));
+        
+        let config = self.config_manager.config_mut();
+        
+        ui.checkbox(&mut config.rendering.enable_trails, tr(lang, "Particle Trails"));
+        if config.rendering.enable_trails {
+            ui.add(egui::Slider::new(&mut config.rendering.trail_length, 5..=200)
+                .text(tr(lang, "Trail Length")));
+        }
+        
+        ui.checkbox(&mut config.rendering.auto_fit_camera, tr(lang, "Auto-fit Camera on Preset"));
+        ui.checkbox(&mut config.rendering.show_velocity_vectors, tr(lang, "Velocity Vectors"));
+        ui.checkbox(&mut config.rendering.show_force_vectors, tr(lang, "Force Vectors"));
+        ui.checkbox(&mut config.rendering.show_interaction_radii, tr(lang, "Interaction Radii"))
+            .on_hover_text(tr(lang, "Hover over a particle to see how far each of its pair rules reaches"));
+        ui.checkbox(&mut config.rendering.force_field.enabled, tr(lang, "Force Field Overlay"));
+        if config.rendering.force_field.enabled {
+            let field = &mut config.rendering.force_field;
+            let species = &config.species;
+            egui::ComboBox::from_label(tr(lang, "Probe Species"))
+                .selected_text(species.name(field.probe_species))
+                .show_ui(ui, |ui| {
+                    for definition in &species.species {
+                        ui.selectable_value(&mut field.probe_species, definition.id, &definition.name);
+                    }
+                });
+            ui.add(egui::Slider::new(&mut field.resolution, 8..=64)
+                .text(tr(lang, "Field Resolution")));
+            ui.add(egui::Slider::new(&mut field.arrow_scale, 5.0..=80.0)
+                .text(tr(lang, "Arrow Length")));
+            ui.checkbox(&mut field.show_streamlines, tr(lang, "Streamlines"));
+        }
+        ui.checkbox(&mut config.rendering.color_by_velocity, tr(lang, "Color by Velocity"));
+        ui.checkbox(&mut config.rendering.color_by_energy, tr(lang, "Color by Energy"));
+        ui.checkbox(&mut config.rendering.species_shapes, tr(lang, "Shape per Species"));
+        ui.collapsing(tr(lang, "⏳ Age Ramp"), |ui| {
+            let ramp = &mut config.rendering.age_ramp;
+            ui.checkbox(&mut ramp.fade, tr(lang, "Fade with Age"));
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut ramp.recolor, tr(lang, "Recolor with Age"));
+                ui.color_edit_button_rgba_unmultiplied(&mut ramp.old_color);
+            });
+            ui.add(egui::Slider::new(&mut ramp.end_size, 0.0..=3.0).text(tr(lang, "Size at End of Life")));
+        });
+        
+        let mut palette = None;
+        egui::ComboBox::from_label(tr(lang, "Species Palette"))
+            .selected_text(tr(lang, "Choose..."))
+            .show_ui(ui, |ui| {
+                for option in Palette::all() {
+                    if ui.selectable_label(false, tr(lang, option.name())).clicked() {
+                        palette = Some(option);
+                    }
+                }
+            });
+        if let Some(palette) = palette {
+            config.species.apply_palette(palette);
+            for particle in &mut self.particle_system.particles {
+                particle.color = config.species.color(particle.species_id);
+            }
+            self.renderer.set_species_registry(config.species.clone());
+        }
+        
+        ui.add(egui::Slider::new(&mut config.rendering.point_size, 0.1..=10.0)
+            .text(tr(lang, "Point Size")));
+        
+        egui::ComboBox::from_label(tr(lang, "Render Mode"))
+            .selected_text(tr(lang, config.rendering.particle_render_mode.name()))
+            .show_ui(ui, |ui| {
+                for mode in ParticleRenderMode::all() {
+                    let name = tr(lang, mode.name());
+                    ui.selectable_value(&mut config.rendering.particle_render_mode, mode, name);
+                }
+            });
+        let mut additive = config.rendering.is_additive();
+        if ui.checkbox(&mut additive, tr(lang, "Additive Blending (Glow)")).changed() {
+            config.rendering.set_additive(additive);
+        }
+        if config.rendering.particle_render_mode == ParticleRenderMode::Stretched {
+            ui.add(egui::Slider::new(&mut config.rendering.stretch_factor, 0.0..=0.2)
+                .text(tr(lang, "Stretch per Speed")));
+            ui.add(egui::Slider::new(&mut config.rendering.max_elongation, 1.0..=10.0)
+                .text(tr(lang, "Max Elongation")));
+        }
+
+        let background_color = config.rendering.background_color;
+        egui::ComboBox::from_label(tr(lang, "Background"))
+            .selected_text(tr(lang, config.rendering.background.name()))
+            .show_ui(ui, |ui| {
+                for option in Background::options(background_color) {
+                    let selected = config.rendering.background.name() == option.name();
+                    if ui.selectable_label(selected, tr(lang, option.name())).clicked() && !selected {
+                        config.rendering.background = option;
+                    }
+                }
+            });
+        match &mut config.rendering.background {
+            Background::Solid => {
+                ui.color_edit_button_rgba_unmultiplied(&mut config.rendering.background_color);
+            },
+            Background::VerticalGradient { top: first, bottom: second }
+            | Background::RadialGradient { inner: first, outer: second } => {
+                ui.horizontal(|ui| {
+                    ui.color_edit_button_rgba_unmultiplied(first);
+                    ui.color_edit_button_rgba_unmultiplied(second);
+                });
+            },
+            Background::Image { path } => {
+                ui.horizontal(|ui| {
+                    ui.label(tr(lang, "Image Path"));
+                    ui.text_edit_singleline(path);
+                });
+            },
+        }
+        if config.rendering.background != Background::Solid {
+            ui.add(egui::Slider::new(&mut config.rendering.background_parallax, 0.0..=1.0)
+                .text(tr(lang, "Parallax")));
+        }
+        
+        // Update renderer config when changed
+        self.renderer.update_config(config.rendering.clone());
+    }
+
+    fn draw_performance_stats(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        ui.label(format!("FPS: {:.1}", self.performance_stats.fps));
+        
+        // Rolling timings over the last few seconds, with percentiles to catch stutter
+        let history = &self.performance_stats.history;
+        let timings: [(&str, fn(&FrameSample) -> f32); 3] = [
+            ("Frame Time", |sample| sample.frame_ms),
+            ("Update Time", |sample| sample.update_ms),
+            ("Render Time", |sample| sample.render_ms),
+        ];
+        let now = history.now() as f64;
+        Plot::new("frame_timings")
+            .height(120.0)
+            .legend(Legend::default())
+            .include_x(-HISTORY_SECONDS as f64)
+            .include_x(0.0)
+            .include_y(0.0)
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .show(ui, |plot_ui| {
+                for (name, timing) in timings {
+                    let points: PlotPoints = history.samples()
+                        .iter()
+                        .map(|sample| [sample.time as f64 - now, timing(sample) as f64])
+                        .collect();
+                    plot_ui.line(Line::new(points).name(tr(lang, name)));
+                }
+            });
+        egui::Grid::new("frame_percentiles").striped(true).show(ui, |ui| {
+            ui.label("");
+            ui.strong(tr(lang, "Average"));
+            ui.strong("p95");
+            ui.strong("p99");
+            ui.strong(tr(lang, "Max"));
+            ui.end_row();
+            for (name, timing) in timings {
+                ui.label(tr(lang, name));
+                ui.label(format!("{:.2} ms", history.mean(timing)));
+                for percentile in [95.0, 99.0, 100.0] {
+                    ui.label(format!("{:.2} ms", history.percentile(timing, percentile)));
+                }
+                ui.end_row();
+            }
+        });
+        let stats = &self.performance_stats;
+        ui.label(format!("{}: {:.2} ms • {}: {:.2} ms • {}: {:.2} ms",
+            tr(lang, "Physics"), stats.physics_time_ms,
+            tr(lang, "Spatial Index"), stats.spatial_time_ms,
+            tr(lang, "Trails"), stats.trail_time_ms));
+        ui.separator();
+        ui.label(format!("{}: {}", tr(lang, "Particles"), self.performance_stats.particle_count));
+        ui.label(format!("{}: {}", tr(lang, "Active Forces"), self.performance_stats.active_forces));
+        ui.label(format!("{}: {}", tr(lang, "Candidate Pairs"), self.performance_stats.candidate_pairs));
+        ui.label(format!("{}: {}", tr(lang, "Spatial Queries"), self.performance_stats.spatial_queries));
+        ui.horizontal(|ui| {
+            ui.label(format!("{}: {}", tr(lang, "Clamped Forces"), self.performance_stats.clamped_forces));
+            if ui.small_button(tr(lang, "Reset")).clicked() {
+                self.performance_stats.clamped_forces = 0;
+            }
+        });
+        if self.physics_engine.clamped_forces > 0 {
+            ui.colored_label(egui::Color32::YELLOW, tr(lang, "Forces are hitting Max Force; try more softening or a smaller time step"));
+        }
+        
+        if let Some(ref spatial) = self.spatial {
+            match spatial {
+                SpatialPartitioning::Grid(grid) => {
+                    ui.label(format!("{}: {}", tr(lang, "Grid Cells"), grid.get_cell_count()));
+                    ui.label(format!("{}: {}", tr(lang, "Max Particles/Cell"), grid.get_max_particles_per_cell()));
+                },
+                SpatialPartitioning::QuadTree(qt) => {
+                    let stats = qt.get_statistics();
+                    ui.label(format!("{}: {}", tr(lang, "QuadTree Nodes"), stats.node_count));
+                    ui.label(format!("{}: {}", tr(lang, "Max Depth"), stats.max_depth));
+                }
+            }
+        }
+    }
+
+    fn draw_advanced_settings(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        let config = self.config_manager.config_mut();
+        
+        ui.collapsing(tr(lang, "Appearance"), |ui| {
+            egui::ComboBox::from_label(tr(lang, "Language"))
+                .selected_text(config.ui.language.native_name())
+                .show_ui(ui, |ui| {
+                    for language in Language::all() {
+                        ui.selectable_value(&mut config.ui.language, language, language.native_name());
+                    }
+                });
+            
+            egui::ComboBox::from_label(tr(lang, "Theme"))
+                .selected_text(tr(lang, config.ui.theme.name()))
+                .show_ui(ui, |ui| {
+                    for theme in UiTheme::all() {
+                        ui.selectable_value(&mut config.ui.theme, theme, tr(lang, theme.name()));
+                    }
+                });
+            
+            ui.horizontal(|ui| {
+                ui.label(tr(lang, "Accent Color"));
+                ui.color_edit_button_srgb(&mut config.ui.accent_color);
+            });
+            
+            ui.add(egui::Slider::new(&mut config.ui.ui_scale, 0.5..=3.0)
+                .text(tr(lang, "UI Scale")));
+        });
+        
+        ui.collapsing(tr(lang, "Physics"), |ui| {
+            egui::ComboBox::from_label(tr(lang, "Integrator"))
+                .selected_text(tr(lang, config.physics.integration_method.name()))
+                .show_ui(ui, |ui| {
+                    for method in IntegrationMethod::all() {
+                        ui.selectable_value(&mut config.physics.integration_method, method, tr(lang, method.name()));
+                    }
+                });
+            ui.add(egui::Slider::new(&mut config.physics.dt, 0.001..=0.1)
+                .text(tr(lang, "Time Step")));
+            ui.add(egui::Slider::new(&mut config.physics.max_velocity, 10.0..=1000.0)
+                .text(tr(lang, "Max Velocity")));
+            ui.checkbox(&mut config.physics.enable_collisions, tr(lang, "Enable Collisions"));
+            ui.checkbox(&mut config.physics.newtons_third_law, tr(lang, "Equal and Opposite Pair Forces"));
+            ui.add(egui::Slider::new(&mut config.physics.spawn_ramp_time, 0.0..=5.0)
+                .text(tr(lang, "Spawn Ramp-In (s)")));
+            ui.add(egui::Slider::new(&mut config.physics.max_force, 10.0..=100_000.0)
+                .logarithmic(true)
+                .text(tr(lang, "Max Force")));
+            ui.label(tr(lang, "Softening"));
+            for kind in SOFTENED_FORCES {
+                let softening = config.physics.softening.entry(kind).or_insert(0.0);
+                ui.add(egui::Slider::new(softening, 0.0..=50.0).text(tr(lang, kind.name())));
+            }
+        });
+        
+        ui.collapsing(tr(lang, "Preset Transitions"), |ui| {
+            ui.checkbox(&mut config.ui.morph_preset_transitions, tr(lang, "Morph Between Presets"));
+            ui.add(egui::Slider::new(&mut config.ui.preset_transition_duration, 0.0..=20.0)
+                .text(tr(lang, "Transition Duration (s)")));
+        });
+
+        let notifications = &mut self.notifications;
+        let audio = &mut self.audio;
+        ui.collapsing(tr(lang, "Audio"), |ui| {
+            ui.checkbox(&mut config.audio.enabled, tr(lang, "Audio-Reactive Forces"));
+
+            if audio.is_listening() {
+                ui.label(tr(lang, "🎤 Listening"));
+            } else if ui.button(tr(lang, "🎤 Start Microphone")).clicked() {
+                match audio.start_input() {
+                    Ok(()) => notifications.info("Microphone input started"),
+                    Err(e) => notifications.error(format!("Failed to start audio input: {}", e)),
+                }
+            }
+
+            ui.add(egui::Slider::new(&mut config.audio.gain, 0.0..=10.0)
+                .text(tr(lang, "Gain")));
+            ui.add(egui::Slider::new(&mut config.audio.smoothing, 0.0..=0.99)
+                .text(tr(lang, "Smoothing")));
+
+            for source in AudioSource::all() {
+                ui.horizontal(|ui| {
+                    ui.label(source.name());
+                    ui.add(egui::ProgressBar::new(audio.bands.value(source).clamp(0.0, 1.0)));
+                });
+            }
+
+            ui.separator();
+            ui.label(tr(lang, "Mappings"));
+
+            let mut remove_index = None;
+            for (index, mapping) in config.audio.mappings.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_id_source(("audio_source", index))
+                        .selected_text(mapping.source.name())
+                        .show_ui(ui, |ui| {
+                            for source in AudioSource::all() {
+                                ui.selectable_value(&mut mapping.source, source, source.name());
+                            }
+                        });
+                    ui.label("→");
+                    egui::ComboBox::from_id_source(("audio_target", index))
+                        .selected_text(mapping.target.name())
+                        .show_ui(ui, |ui| {
+                            for kind in ForceKind::all() {
+                                ui.selectable_value(&mut mapping.target, kind, kind.name());
+                            }
+                        });
+                    if ui.button("🗑").clicked() {
+                        remove_index = Some(index);
+                    }
+                });
+                ui.add(egui::Slider::new(&mut mapping.base, 0.0..=2.0).text(tr(lang, "Base")));
+                ui.add(egui::Slider::new(&mut mapping.depth, 0.0..=10.0).text(tr(lang, "Depth")));
+            }
+
+            if let Some(index) = remove_index {
+                config.audio.mappings.remove(index);
+            }
+
+            if ui.button(tr(lang, "➕ Add Mapping")).clicked() {
+                config.audio.mappings.push(audio::AudioMapping {
+                    source: AudioSource::Amplitude,
+                    target: ForceKind::Attraction,
+                    base: 1.0,
+                    depth: 1.0,
+                });
+            }
+        });
+
+        let controller = &mut self.midi;
+        ui.collapsing(tr(lang, "MIDI"), |ui| {
+            match controller.port_name() {
+                Some(name) => {
+                    ui.label(format!("🎹 {}: {}", tr(lang, "Connected"), name));
+                },
+                None => {
+                    if ui.button(tr(lang, "🎹 Connect MIDI Input")).clicked() {
+                        if let Err(e) = controller.connect() {
+                            notifications.error(format!("Failed to connect MIDI input: {}", e));
+                        }
+                    }
+                },
+            }
+
+            if let Some(change) = controller.last_change {
+                ui.label(format!("{}: ch {} / cc {} = {}", tr(lang, "Last CC"), change.channel + 1, change.controller, change.value));
+            }
+
+            ui.separator();
+            ui.label(tr(lang, "Bindings"));
+
+            let mut remove_index = None;
+            for (index, binding) in controller.bindings.bindings.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("ch {} / cc {} → {}", binding.channel + 1, binding.controller, binding.target.label()));
+                    if ui.button("🗑").clicked() {
+                        remove_index = Some(index);
+                    }
+                });
+                let (low, high) = binding.target.default_range();
+                ui.add(egui::Slider::new(&mut binding.min, low..=high).text(tr(lang, "Min")));
+                ui.add(egui::Slider::new(&mut binding.max, low..=high).text(tr(lang, "Max")));
+            }
+
+            if let Some(index) = remove_index {
+                controller.bindings.bindings.remove(index);
+            }
+
+            ui.separator();
+            ui.label(tr(lang, "Learn"));
+
+            match controller.learn_target {
+                Some(target) => {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{}: {}", tr(lang, "Move a control to bind"), target.label()));
+                        if ui.button(tr(lang, "Cancel")).clicked() {
+                            controller.learn_target = None;
+                        }
+                    });
+                },
+                None => {
+                    for target in ControlTarget::all() {
+                        if ui.button(format!("🎛 {}", target.label())).clicked() {
+                            controller.start_learn(target);
+                        }
+                    }
+                },
+            }
+
+            ui.horizontal(|ui| {
+                if ui.button(tr(lang, "💾 Save Bindings")).clicked() {
+                    match controller.bindings.save_to_file(midi::DEFAULT_BINDINGS_FILE) {
+                        Ok(()) => notifications.info(format!("Saved MIDI bindings to {}", midi::DEFAULT_BINDINGS_FILE)),
+                        Err(e) => notifications.error(format!("Failed to save MIDI bindings: {}", e)),
+                    }
+                }
+                if ui.button(tr(lang, "📁 Load Bindings")).clicked() {
+                    match MidiBindings::from_file(midi::DEFAULT_BINDINGS_FILE) {
+                        Ok(bindings) => controller.bindings = bindings,
+                        Err(e) => notifications.error(format!("Failed to load MIDI bindings: {}", e)),
+                    }
+                }
+            });
+        });
+        
+        let listener = &mut self.osc;
+        ui.collapsing(tr(lang, "OSC"), |ui| {
+            ui.add(egui::DragValue::new(&mut config.osc.port).prefix(tr(lang, "UDP Port: ")));
+            
+            if listener.is_listening() {
+                ui.label(format!("📡 {} {}/...", tr(lang, "Listening on"), osc::ADDRESS_PREFIX));
+                if ui.button(tr(lang, "Stop")).clicked() {
+                    listener.stop();
+                    config.osc.enabled = false;
+                }
+            } else if ui.button(tr(lang, "📡 Start OSC Listener")).clicked() {
+                match listener.start(config.osc.port) {
+                    Ok(()) => config.osc.enabled = true,
+                    Err(e) => notifications.error(format!("Failed to start OSC listener: {}", e)),
+                }
+            }
+        });
+        
+        let metrics_server = &mut self.metrics_server;
+        ui.collapsing(tr(lang, "Prometheus Metrics"), |ui| {
+            ui.add(egui::DragValue::new(&mut config.metrics.port).prefix(tr(lang, "HTTP Port: ")));
+            
+            if metrics_server.is_serving() {
+                ui.label(format!("📈 {} http://0.0.0.0:{}{}", tr(lang, "Serving on"), config.metrics.port, METRICS_PATH));
+                if ui.button(tr(lang, "Stop")).clicked() {
+                    metrics_server.stop();
+                    config.metrics.enabled = false;
+                }
+            } else if ui.button(tr(lang, "📈 Start Metrics Endpoint")).clicked() {
+                match metrics_server.start(config.metrics.port) {
+                    Ok(()) => config.metrics.enabled = true,
+                    Err(e) => notifications.error(format!("Failed to start metrics endpoint: {}", e)),
+                }
+            }
+        });
+        
+        let autosave_path = self.autosave.path().display().to_string();
+        ui.collapsing(tr(lang, "Autosave"), |ui| {
+            ui.checkbox(&mut config.autosave.enabled, tr(lang, "Autosave Session"));
+            ui.add(egui::Slider::new(&mut config.autosave.interval_seconds, 10.0..=600.0).text(tr(lang, "Autosave Interval (s)")));
+            ui.label(format!("💾 {}", autosave_path));
+        });
+        
+        let output = &mut self.output;
+        ui.collapsing(tr(lang, "Video Output"), |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr(lang, "Resolution"));
+                ui.add(egui::DragValue::new(&mut config.output.width).clamp_range(16..=3840));
+                ui.label("×");
+                ui.add(egui::DragValue::new(&mut config.output.height).clamp_range(16..=2160));
+            });
+            
+            ui.horizontal(|ui| {
+                ui.label(tr(lang, "Server Name"));
+                // Sinks are looked up by name, so it can't change while publishing
+                let publishing = config.output.ndi_enabled || config.output.spout_enabled || config.output.syphon_enabled;
+                ui.add_enabled(!publishing, egui::TextEdit::singleline(&mut config.output.server_name));
+            });
+            
+            // Shared textures only exist on their own platform
+            if cfg!(target_os = "windows") && ui.checkbox(&mut config.output.spout_enabled, tr(lang, "Spout Sender")).changed() {
+                if config.output.spout_enabled {
+                    match SpoutSink::new(&config.output.server_name) {
+                        Ok(sink) => output.add_sink(Box::new(sink)),
+                        Err(e) => {
+                            notifications.error(e);
+                            config.output.spout_enabled = false;
+                        }
+                    }
+                } else {
+                    output.remove_sink(&SpoutSink::sink_name(&config.output.server_name));
+                }
+            }
+            
+            if cfg!(target_os = "macos") && ui.checkbox(&mut config.output.syphon_enabled, tr(lang, "Syphon Server")).changed() {
+                if config.output.syphon_enabled {
+                    match SyphonSink::new(&config.output.server_name) {
+                        Ok(sink) => output.add_sink(Box::new(sink)),
+                        Err(e) => {
+                            notifications.error(e);
+                            config.output.syphon_enabled = false;
+                        }
+                    }
+                } else {
+                    output.remove_sink(&SyphonSink::sink_name(&config.output.server_name));
+                }
+            }
+            
+            if ui.checkbox(&mut config.output.ndi_enabled, tr(lang, "NDI Source")).changed() {
+                if config.output.ndi_enabled {
+                    match NdiSink::new(&config.output.server_name, config.output.frame_rate) {
+                        Ok(sink) => output.add_sink(Box::new(sink)),
+                        Err(e) => {
+                            notifications.error(e);
+                            config.output.ndi_enabled = false;
+                        }
+                    }
+                } else {
+                    output.remove_sink(&NdiSink::sink_name(&config.output.server_name));
+                }
+            }
+        });
+        
+        ui.collapsing(tr(lang, "Performance"), |ui| {
+            ui.checkbox(&mut config.performance.enable_spatial_partitioning, tr(lang, "Spatial Partitioning"));
+            ui.checkbox(&mut config.performance.enable_multithreading, tr(lang, "Multithreading"));
+            ui.add(egui::Slider::new(&mut config.performance.target_fps, 30.0..=120.0)
+                .text(tr(lang, "Target FPS")));
+            ui.checkbox(&mut config.performance.fast_math.enabled, tr(lang, "Fast Math"))
+                .on_hover_text(tr(lang, "Approximate 1/r² forces for higher particle counts, at a small cost in accuracy"));
+            if config.performance.fast_math.enabled {
+                ui.label(tr(lang, "Cutoff Distance (0 = none)"));
+                for kind in INVERSE_SQUARE_FORCES {
+                    let cutoff = config.performance.fast_math.cutoffs.entry(kind).or_insert(0.0);
+                    ui.add(egui::Slider::new(cutoff, 0.0..=2000.0).text(tr(lang, kind.name())));
+                }
+            }
+            ui.checkbox(&mut config.performance.streaming.enabled, tr(lang, "World Streaming"))
+                .on_hover_text(tr(lang, "Fully simulate only the chunks near the camera or pinned; the rest freeze or tick slowly"));
+            let streaming = &mut config.performance.streaming;
+            if streaming.enabled {
+                ui.add(egui::Slider::new(&mut streaming.chunk_size, 50.0..=5000.0)
+                    .logarithmic(true)
+                    .text(tr(lang, "Chunk Size")));
+                ui.add(egui::Slider::new(&mut streaming.activation_radius, 0.0..=10_000.0)
+                    .text(tr(lang, "Activation Radius")));
+                ui.add(egui::Slider::new(&mut streaming.dormant_tick_interval, 0..=60)
+                    .text(tr(lang, "Dormant Tick Interval (0 = frozen)")));
+                ui.checkbox(&mut streaming.show_chunks, tr(lang, "Show Chunks"));
+                ui.label(format!("{}: {}", tr(lang, "Dormant Particles"), self.chunk_streamer.dormant_particles));
+                if !streaming.pinned.is_empty() && ui.button(tr(lang, "Unpin All Chunks")).clicked() {
+                    streaming.pinned.clear();
+                }
+            }
+        });
+        
+        ui.collapsing(tr(lang, "Units"), |ui| {
+            self.draw_unit_settings(ui);
+        });
+        
+        ui.collapsing(tr(lang, "Integrator Comparison"), |ui| {
+            self.draw_integrator_comparison(ui);
+        });
+        
+        ui.collapsing(tr(lang, "🖥 Display"), |ui| {
+            self.draw_display_settings(ui);
+        });
+        
+        ui.collapsing(tr(lang, "🎞 Demo Mode"), |ui| {
+            self.draw_demo_settings(ui);
+        });
+        
+        ui.separator();
+        
+        if ui.button(tr(lang, "💾 Save Config")).clicked() {
+            match self.config_manager.save_to_file("config.json") {
+                Ok(()) => self.notifications.info("Saved config.json"),
+                Err(e) => self.notifications.error(format!("Failed to save config: {}", e)),
+            }
+            if let Err(e) = self.save_layout() {
+                self.notifications.error(format!("Failed to save window layout: {}", e));
+            }
+        }
+        
+        if ui.button(tr(lang, "📁 Load Config")).clicked() {
+            match ConfigManager::from_file("config.json") {
+                Ok(manager) => {
+                    self.config_manager = manager;
+                    self.apply_current_config();
+                    self.notifications.info("Loaded config.json");
+                },
+                Err(e) => {
+                    self.notifications.error(format!("Failed to load config: {}", e));
+                }
+            }
+        }
+        
+        if ui.button(tr(lang, "🔄 Reset to Defaults")).clicked() {
+            self.config_manager.reset_to_defaults();
+            self.apply_current_config();
+        }
+    }
+
+    fn draw_display_settings(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        let monitors = &self.ui_state.monitors;
+        let display = &mut self.config_manager.config_mut().ui.display;
+        ui.checkbox(&mut display.fullscreen, tr(lang, "Fullscreen (F11)"));
+        ui.checkbox(&mut display.borderless, tr(lang, "Borderless Window"));
+        ui.checkbox(&mut display.always_on_top, tr(lang, "Always on Top"));
+        ui.checkbox(&mut display.separate_controls, tr(lang, "Controls in Separate Window"));
+        
+        let monitor_name = |monitor: Option<usize>| match monitor.and_then(|index| monitors.get(index)) {
+            Some(name) => name.clone(),
+            None => tr(lang, "Current").to_string(),
+        };
+        egui::ComboBox::from_label(tr(lang, "Monitor"))
+            .selected_text(monitor_name(display.monitor))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut display.monitor, None, monitor_name(None));
+                for index in 0..monitors.len() {
+                    ui.selectable_value(&mut display.monitor, Some(index), monitor_name(Some(index)));
+                }
+            });
+    }
+    
+    fn draw_demo_settings(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        let demo = &mut self.config_manager.config_mut().ui.demo;
+        ui.add(egui::Slider::new(&mut demo.preset_duration, 5.0..=600.0)
+            .logarithmic(true)
+            .text(tr(lang, "Seconds per Preset")));
+        ui.add(egui::Slider::new(&mut demo.transition_duration, 0.0..=20.0)
+            .text(tr(lang, "Transition Duration (s)")));
+        ui.add(egui::Slider::new(&mut demo.randomize_chance, 0.0..=1.0)
+            .text(tr(lang, "Random Rules Chance")));
+        ui.add(egui::Slider::new(&mut demo.idle_timeout, 5.0..=600.0)
+            .logarithmic(true)
+            .text(tr(lang, "Idle Reset (s)")));
+        ui.checkbox(&mut demo.shuffle, tr(lang, "Shuffle"));
+        
+        ui.label(tr(lang, "Presets (none ticked plays them all)"));
+        for preset in Preset::all() {
+            let mut included = demo.presets.contains(&preset);
+            if ui.checkbox(&mut included, preset.name()).changed() {
+                if included {
+                    demo.presets.push(preset);
+                } else {
+                    demo.presets.retain(|p| *p != preset);
+                }
+            }
+        }
+        
+        if ui.button(tr(lang, "▶ Start Demo")).clicked() {
+            self.start_demo();
+        }
+        ui.label(tr(lang, "Hides the UI; press Esc to leave"));
+    }
+    
+    // Hides the UI and starts cycling presets from the top of the playlist
+    pub fn start_demo(&mut self) {
+        let (demo, preset) = DemoMode::start(&self.config_manager.config().ui.demo, &mut rand::thread_rng());
+        self.demo = Some(demo);
+        self.paused = false;
+        self.config_manager.apply_preset(preset.clone());
+        self.rebuild_simulation(preset);
+    }
+    
+    pub fn stop_demo(&mut self) {
+        self.demo = None;
+    }
+    
+    fn advance_demo(&mut self, step: DemoStep) {
+        let duration = self.config_manager.config().ui.demo.transition_duration;
+        let morph = duration > 0.0 && self.particle_system.particle_count() > 0;
+        match step {
+            DemoStep::Preset(preset) if morph => self.begin_preset_transition(preset, duration),
+            DemoStep::Preset(preset) | DemoStep::Restart(preset) => {
+                self.config_manager.apply_preset(preset.clone());
+                self.rebuild_simulation(preset);
+            },
+            DemoStep::Randomize if morph => {
+                let config = self.config_manager.config();
+                let (from_interactions, from_settings) = (config.forces.species_interactions.clone(), MorphSettings::current(config, &self.particle_system));
+                self.config_manager.randomize_particle_life(self.ui_state.species_count_slider);
+                self.morph_into_config(Preset::ParticleLife, from_interactions, from_settings, duration);
+            },
+            DemoStep::Randomize => self.randomize_particle_life(),
+        }
+    }
+
+    fn draw_unit_settings(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        let config = self.config_manager.config_mut();
+        let unit_config = &mut config.units;
+        
+        ui.checkbox(&mut unit_config.enabled, tr(lang, "Show Physical Units"));
+        ui.add(egui::Slider::new(&mut unit_config.pixels_per_meter, 1e-6..=1e6)
+            .logarithmic(true)
+            .text(tr(lang, "Pixels per Meter")));
+        ui.add(egui::Slider::new(&mut unit_config.time_scale, 1e-3..=1e7)
+            .logarithmic(true)
+            .text(tr(lang, "Seconds per Simulated Second")));
+        ui.horizontal(|ui| {
+            ui.label(tr(lang, "kg per Mass Unit"));
+            ui.add(egui::DragValue::new(&mut unit_config.kilograms_per_mass).speed(0.1).clamp_range(1e-30..=1e30));
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr(lang, "C per Charge Unit"));
+            ui.add(egui::DragValue::new(&mut unit_config.coulombs_per_charge).speed(0.1).clamp_range(1e-30..=1e30));
+        });
+        ui.label(format!("G = {:.4e}, k = {:.4e}", unit_config.gravity_strength(), unit_config.coulomb_strength()));
+        
+        if ui.button(tr(lang, "Apply Physical Constants")).clicked() {
+            units::apply_physical_constants(config);
+            self.physics_engine.force_calculator.interaction_matrix = config.forces.interaction_matrix();
+        }
+        
+        // Rescaling keeps the scene's size in metres, so it only changes how fine the world grid is
+        ui.horizontal(|ui| {
+            ui.label(tr(lang, "Rescale Scene"));
+            for (label, factor) in [("×½", 0.5), ("×2", 2.0), ("×10", 10.0)] {
+                if ui.button(label).clicked() {
+                    self.rescale_scene(factor);
+                }
+            }
+        });
+    }
+
+    // Runs a ghost copy of the current state under another integrator, drawn as magenta outlines
+    fn draw_integrator_comparison(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        let live_method = self.config_manager.config().physics.integration_method;
+        ui.label(format!("{}: {}", tr(lang, "Integrator"), tr(lang, live_method.name())));
+        egui::ComboBox::from_label(tr(lang, "Compare With"))
+            .selected_text(tr(lang, self.ui_state.comparison_method.name()))
+            .show_ui(ui, |ui| {
+                for method in IntegrationMethod::all() {
+                    ui.selectable_value(&mut self.ui_state.comparison_method, method, tr(lang, method.name()));
+                }
+            });
+        
+        ui.horizontal(|ui| {
+            let label = if self.comparison.is_some() { "Restart" } else { "Start" };
+            if ui.button(tr(lang, label)).clicked() {
+                self.comparison = Some(IntegratorComparison::new(&self.particle_system, &self.physics_engine, self.ui_state.comparison_method));
+            }
+            if self.comparison.is_some() && ui.button(tr(lang, "Stop")).clicked() {
+                self.comparison = None;
+            }
+        });
+        
+        if let Some(comparison) = &self.comparison {
+            let units = &self.config_manager.config().units;
+            ui.label(format!("{} ({}): {}", tr(lang, "Divergence"), tr(lang, comparison.method.name()), units.format_length(comparison.divergence(&self.particle_system))));
+        }
+    }
+
+    // Starts any running comparison over from the current state, e.g. after the scene is rebuilt
+    fn restart_comparison(&mut self) {
+        if let Some(comparison) = &self.comparison {
+            self.comparison = Some(IntegratorComparison::new(&self.particle_system, &self.physics_engine, comparison.method));
+        }
+    }
+
+    // Stretches config, particles and camera together, so the view and the motion stay the same
+    fn rescale_scene(&mut self, factor: f32) {
+        units::rescale_config(self.config_manager.config_mut(), factor);
+        units::rescale_particles(&mut self.particle_system.particles, factor);
+        
+        let config = self.config_manager.config();
+        self.physics_engine.config.max_velocity = config.physics.max_velocity;
+        self.physics_engine.force_calculator.interaction_matrix = config.forces.interaction_matrix();
+        self.renderer.set_species_registry(config.species.clone());
+        self.renderer.camera.position *= factor;
+        self.renderer.camera.zoom /= factor;
+        self.history.clear();
+        self.restart_comparison();
+    }
+
+    fn draw_force_editor(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        ui.heading(tr(lang, "Species"));
+        
+        let mut species_changed = false;
+        let mut species_to_remove = None;
+        
+        {
+            let config = self.config_manager.config_mut();
+            
+            for species in config.species.species.iter_mut() {
+                ui.collapsing(format!("{} (#{})", species.name, species.id), |ui| {
+                    species_changed |= ui.text_edit_singleline(&mut species.name).changed();
+                    species_changed |= ui.color_edit_button_rgba_unmultiplied(&mut species.color).changed();
+                    species_changed |= ui.add(egui::Slider::new(&mut species.mass, 0.1..=10.0)
+                        .text(tr(lang, "Mass"))).changed();
+                    species_changed |= ui.add(egui::Slider::new(&mut species.size, 0.5..=10.0)
+                        .text(tr(lang, "Size"))).changed();
+                    species_changed |= ui.add(egui::Slider::new(&mut species.charge, -5.0..=5.0)
+                        .text(tr(lang, "Charge"))).changed();
+                    ui.add(egui::Slider::new(&mut species.drag, 0.0..=5.0).text(tr(lang, "Drag")));
+                    
+                    if ui.button(tr(lang, "🗑️ Remove Species")).clicked() {
+                        species_to_remove = Some(species.id);
+                    }
+                });
+            }
+        }
+        
+        if ui.button(tr(lang, "➕ Add Species")).clicked() {
+            self.add_species(tr(lang, "New Species"));
+        }
+        
+        if let Some(species_id) = species_to_remove {
+            self.remove_species(species_id);
+        } else if species_changed {
+            self.renderer.set_species_registry(self.config_manager.config().species.clone());
+        }
+        
+        ui.separator();
+        self.draw_interaction_editor(ui);
+        
+        ui.separator();
+        self.draw_rule_code(ui);
+    }
+
+    fn draw_rule_code(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        ui.heading(tr(lang, "Rule Code"));
+        if ui.button(tr(lang, "📋 Copy Rule Code")).clicked() {
+            let code = self.rule_code();
+            ui.output_mut(|output| output.copied_text = code);
+            self.notifications.info("Rule code copied to the clipboard");
+        }
+        let load = ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.ui_state.rule_code).hint_text(tr(lang, "Paste a rule code")));
+            ui.button(tr(lang, "📥 Load")).clicked()
+        }).inner;
+        if load {
+            let code = std::mem::take(&mut self.ui_state.rule_code);
+            match self.load_rule_code(&code) {
+                Ok(species_count) => self.notifications.info(format!("Loaded rules for {} species", species_count)),
+                Err(e) => {
+                    self.notifications.error(e);
+                    self.ui_state.rule_code = code;
+                },
+            }
+        }
+    }
+
+    // Shareable code for the current pair rules and species colours
+    pub fn rule_code(&self) -> String {
+        RuleCode::from_config(self.config_manager.config()).encode()
+    }
+
+    // Switches to Particle Life with the rules from a rule code, returning its species count
+    pub fn load_rule_code(&mut self, code: &str) -> Result<usize, String> {
+        let rules = RuleCode::decode(code)?;
+        rules.apply(&mut self.config_manager);
+        self.ui_state.species_count_slider = rules.species_count();
+        self.rebuild_simulation(Preset::ParticleLife);
+        Ok(rules.species_count())
+    }
+
+    fn draw_interaction_editor(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        ui.heading(tr(lang, "Interactions"));
+        self.draw_interaction_heatmap(ui);
+        
+        let config = self.config_manager.config_mut();
+        let mut asymmetric = config.forces.asymmetric_interactions;
+        if ui.checkbox(&mut asymmetric, tr(lang, "One-Sided Rules"))
+            .on_hover_text(tr(lang, "Each species reacts to the other by its own rule, so A can chase B while B flees A"))
+            .changed()
+        {
+            config.forces.set_asymmetric_interactions(asymmetric);
+            // Equal and opposite forces would average the two sides back together
+            if asymmetric {
+                config.physics.newtons_third_law = false;
+            }
+            self.physics_engine.force_calculator.interaction_matrix = config.forces.interaction_matrix();
+        }
+        
+        // One-sided pairs without rules of their own follow their mirror until edited
+        let mut pairs: Vec<(u32, u32)> = config.forces.species_interactions
+            .iter()
+            .filter(|(_, forces)| forces.iter().any(ForceType::is_pair_force))
+            .flat_map(|(&(a, b), _)| [(a, b), (b, a)].into_iter().take(if asymmetric { 2 } else { 1 }))
+            .collect();
+        pairs.sort();
+        pairs.dedup();
+        
+        let Some(&first_pair) = pairs.first() else {
+            ui.label(tr(lang, "No pair forces in this preset"));
+            return;
+        };
+        
+        let current_pair = self.ui_state.selected_interaction
+            .map(|(a, b)| if asymmetric || a <= b { (a, b) } else { (b, a) })
+            .filter(|pair| pairs.contains(pair))
+            .unwrap_or(first_pair);
+        let arrow = if asymmetric { "→" } else { "↔" };
+        let pair_label = |(a, b): (u32, u32)| format!("{} {} {}", config.species.name(a), arrow, config.species.name(b));
+        let mut selected_pair = current_pair;
+        egui::ComboBox::from_label(tr(lang, "Species Pair"))
+            .selected_text(pair_label(current_pair))
+            .show_ui(ui, |ui| {
+                for pair in &pairs {
+                    ui.selectable_value(&mut selected_pair, *pair, pair_label(*pair));
+                }
+            });
+        if selected_pair != current_pair {
+            self.ui_state.selected_interaction_force = 0;
+        }
+        self.ui_state.selected_interaction = Some(selected_pair);
+        
+        let (a, b) = selected_pair;
+        if let Some(mirror) = config.forces.species_interactions.get(&(b, a)).filter(|_| asymmetric).cloned() {
+            config.forces.species_interactions.entry(selected_pair).or_insert(mirror);
+        }
+        let Some(forces) = config.forces.species_interactions.get_mut(&selected_pair) else {
+            return;
+        };
+        let pair_force_indices: Vec<usize> = forces
+            .iter()
+            .enumerate()
+            .filter(|(_, force)| force.is_pair_force())
+            .map(|(index, _)| index)
+            .collect();
+        let mut force_index = self.ui_state.selected_interaction_force;
+        if !pair_force_indices.contains(&force_index) {
+            force_index = pair_force_indices[0];
+        }
+        egui::ComboBox::from_label(tr(lang, "Force"))
+            .selected_text(forces[force_index].kind().name())
+            .show_ui(ui, |ui| {
+                for index in &pair_force_indices {
+                    let label = format!("{} #{}", forces[*index].kind().name(), index + 1);
+                    ui.selectable_value(&mut force_index, *index, label);
+                }
+            });
+        self.ui_state.selected_interaction_force = force_index;
+        
+        let force = &mut forces[force_index];
+        let mut changed = edit_pair_force(ui, lang, force);
+        if !matches!(force, ForceType::Profile { .. })
+            && ui.button(tr(lang, "➰ Convert to Profile"))
+                .on_hover_text(tr(lang, "Replace this force with an editable curve of the same shape"))
+                .clicked()
+        {
+            *force = self.physics_engine.force_calculator.profile_of(force, PROFILE_POINTS);
+            changed = true;
+        }
+        let curve = self.physics_engine.force_calculator.pair_curve(force, force.plot_range(), 120);
+        draw_force_curve(ui, lang, &curve);
+        
+        let caps = &mut config.forces.interaction_max_forces;
+        let mut max_force = caps.get(&selected_pair).copied();
+        ui.horizontal(|ui| {
+            let mut capped = max_force.is_some();
+            if ui.checkbox(&mut capped, tr(lang, "Max Pair Force"))
+                .on_hover_text(tr(lang, "Limit the summed pair forces between these species"))
+                .changed()
+            {
+                max_force = capped.then_some(100.0);
+            }
+            if let Some(value) = max_force.as_mut() {
+                ui.add(egui::DragValue::new(value).speed(0.5).clamp_range(0.0..=f32::MAX));
+            }
+        });
+        if max_force != caps.get(&selected_pair).copied() {
+            match max_force {
+                Some(value) => caps.insert(selected_pair, value),
+                None => caps.remove(&selected_pair),
+            };
+            changed = true;
+        }
+        
+        if changed {
+            self.physics_engine.force_calculator.interaction_matrix = config.forces.interaction_matrix();
+        }
+    }
+
+    // Species × species grid coloured by net attraction; clicking a cell selects that pair below
+    fn draw_interaction_heatmap(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        let heatmap = InteractionHeatmap::from_config(self.config_manager.config());
+        if heatmap.is_empty() {
+            return;
+        }
+        let n = heatmap.len();
+        let cell = (ui.available_width() / (n + 1) as f32).clamp(8.0, 28.0);
+        let (rect, response) = ui.allocate_exact_size(egui::Vec2::splat(cell * (n + 1) as f32), egui::Sense::click());
+        let painter = ui.painter_at(rect);
+        let cell_rect = |row: usize, column: usize| {
+            egui::Rect::from_min_size(rect.min + egui::vec2(column as f32, row as f32) * cell, egui::Vec2::splat(cell)).shrink(1.0)
+        };
+        
+        for (index, color) in heatmap.colors.iter().enumerate() {
+            let [r, g, b, _] = color.map(|channel| (channel.clamp(0.0, 1.0) * 255.0) as u8);
+            let color = egui::Color32::from_rgb(r, g, b);
+            painter.rect_filled(cell_rect(0, index + 1), 2.0, color);
+            painter.rect_filled(cell_rect(index + 1, 0), 2.0, color);
+        }
+        for row in 0..n {
+            for column in 0..n {
+                let [r, g, b] = heatmap.color(row, column);
+                painter.rect_filled(cell_rect(row + 1, column + 1), 0.0, egui::Color32::from_rgb(r, g, b));
+            }
+        }
+        if let Some((a, b)) = self.ui_state.selected_interaction {
+            let position = |id| heatmap.species.iter().position(|&species| species == id);
+            if let (Some(row), Some(column)) = (position(a), position(b)) {
+                painter.rect_stroke(cell_rect(row + 1, column + 1), 0.0, egui::Stroke::new(2.0, egui::Color32::YELLOW));
+            }
+        }
+        
+        let hovered = response.hover_pos().and_then(|pos| {
+            let offset = (pos - rect.min) / cell;
+            let (row, column) = (offset.y as usize, offset.x as usize);
+            let inside = (1..=n).contains(&row) && (1..=n).contains(&column);
+            inside.then(|| (row - 1, column - 1))
+        });
+        if let Some((row, column)) = hovered {
+            painter.rect_stroke(cell_rect(row + 1, column + 1), 0.0, egui::Stroke::new(1.5, egui::Color32::WHITE));
+            let pair = (heatmap.species[row], heatmap.species[column]);
+            if response.clicked() {
+                self.ui_state.selected_interaction = Some(pair);
+                self.ui_state.selected_interaction_force = 0;
+            }
+            let species = &self.config_manager.config().species;
+            response.on_hover_text(format!("{} → {}: {:+.2}", species.name(pair.0), species.name(pair.1), heatmap.value(row, column)));
+        }
+        ui.label(tr(lang, "Green attracts, red repels. Click a cell to edit that pair."));
+        
+        if ui.button(tr(lang, "🖼 Export Heatmap")).clicked() {
+            match heatmap.save_png(DEFAULT_HEATMAP_FILE) {
+                Ok(()) => self.notifications.info(format!("Saved interaction heatmap to {}", DEFAULT_HEATMAP_FILE)),
+                Err(e) => self.notifications.error(e),
+            }
+        }
+    }
+
+    pub fn add_species(&mut self, name: &str) -> u32 {
+        let species_id = self.config_manager.config_mut().species.add_species(name);
+        self.renderer.set_species_registry(self.config_manager.config().species.clone());
+        species_id
+    }
+
+    pub fn on_particle_event(&mut self, listener: impl FnMut(&ParticleEvent) + 'static) {
+        self.particle_listeners.push(Box::new(listener));
+    }
+
+    // Hands the endpoint this frame's stats; `simulated` is the steps and seconds just simulated
+    fn publish_metrics(&mut self, simulated: (u64, f32)) {
+        let stats = &self.performance_stats;
+        let metrics = &mut self.metrics;
+        metrics.fps = stats.fps;
+        metrics.frame_time_ms = stats.frame_time_ms;
+        metrics.update_time_ms = stats.update_time_ms;
+        metrics.physics_time_ms = stats.physics_time_ms;
+        metrics.render_time_ms = stats.render_time_ms;
+        let preset = self.current_preset.as_ref().map_or("Custom", |preset| preset.name());
+        metrics.record(preset, &self.particle_system.particles, simulated.0, simulated.1);
+        self.metrics_server.publish(metrics);
+    }
+
+    // Everything the autosave keeps: config with the current camera, particles and field state
+    pub fn session_snapshot(&self) -> SessionSnapshot {
+        let (position, zoom) = self.renderer.camera_state();
+        let mut config = self.config_manager.config().clone();
+        config.rendering.camera_position = position;
+        config.rendering.camera_zoom = zoom;
+        SessionSnapshot {
+            pheromones: self.pheromones.clone(),
+            reaction: self.reaction.clone(),
+            cloth: self.cloth.clone(),
+            ..SessionSnapshot::new(self.current_preset.clone(), config, self.particle_system.clone(), self.sim_time, rng::state())
+        }
+    }
+
+    pub fn restore_session(&mut self, snapshot: SessionSnapshot) {
+        self.shape_hold = None;
+        self.rigid_groups.clear();
+        self.ui_state.grab = None;
+        self.ui_state.selection.clear();
+        self.transition = None;
+        self.history.clear();
+        *self.config_manager.config_mut() = snapshot.config;
+        self.apply_current_config();
+        
+        // The world bounds follow this window, not the one the session was saved from
+        let bounds = self.particle_system.bounds;
+        self.particle_system = snapshot.system;
+        self.particle_system.bounds = bounds;
+        self.pheromones = snapshot.pheromones;
+        self.reaction = snapshot.reaction;
+        self.cloth = snapshot.cloth;
+        self.sim_time = snapshot.sim_time;
+        rng::set_state(snapshot.rng);
+        self.current_preset = snapshot.preset;
+        self.restart_comparison();
+    }
+
+    // Called on a clean exit, so the next launch doesn't offer to restore
+    pub fn discard_autosave(&mut self) -> Result<(), String> {
+        self.autosave.discard()
+    }
+
+    fn draw_crash_report(&mut self, ctx: &egui::Context) {
+        let Some(notice) = &self.crash_notice else {
+            return;
+        };
+        let lang = self.config_manager.config().ui.language;
+        let mut dismiss = false;
+        egui::Window::new(tr(lang, "Crash Report"))
+            .collapsible(false)
+            .default_width(560.0)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(tr(lang, "Inochi hit an unexpected error. Please include this report when filing an issue."));
+                if let Some(path) = &notice.path {
+                    ui.label(format!("📄 {}", path.display()));
+                }
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    // Read-only, but still selectable
+                    ui.add(egui::TextEdit::multiline(&mut notice.text.as_str()).code_editor().desired_width(f32::INFINITY));
+                });
+                ui.horizontal(|ui| {
+                    if ui.button(tr(lang, "📋 Copy Report")).clicked() {
+                        ui.output_mut(|output| output.copied_text = notice.text.clone());
+                    }
+                    dismiss = ui.button(tr(lang, "Dismiss")).clicked();
+                });
+            });
+        
+        if dismiss {
+            if let Some(path) = self.crash_notice.take().and_then(|notice| notice.path) {
+                if let Err(e) = crash::acknowledge(&path) {
+                    self.notifications.error(e);
+                }
+            }
+        }
+    }
+
+    fn draw_restore_prompt(&mut self, ctx: &egui::Context) {
+        let Some(snapshot) = &self.pending_restore else {
+            return;
+        };
+        let lang = self.config_manager.config().ui.language;
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+        let (mut restore, mut discard) = (false, false);
+        egui::Window::new(tr(lang, "Restore Previous Session?"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(tr(lang, "Inochi didn't close cleanly last time. Its rules and particles were autosaved."));
+                ui.label(format!(
+                    "{} {} · {} {}",
+                    snapshot.system.particle_count(),
+                    tr(lang, "particles"),
+                    snapshot.age_minutes(now),
+                    tr(lang, "minutes ago"),
+                ));
+                ui.horizontal(|ui| {
+                    restore = ui.button(tr(lang, "Restore")).clicked();
+                    discard = ui.button(tr(lang, "Discard")).clicked();
+                });
+            });
+        
+        if restore {
+            if let Some(snapshot) = self.pending_restore.take() {
+                self.restore_session(snapshot);
+                self.notifications.info("Restored the autosaved session");
+            }
+        } else if discard {
+            self.pending_restore = None;
+            if let Err(e) = self.autosave.discard() {
+                self.notifications.error(e);
+            }
+        }
+    }
+
+    // Hands queued particle events to the listeners
+    pub fn dispatch_particle_events(&mut self) {
+        let events = self.particle_system.drain_events();
+        for event in &events {
+            for listener in &mut self.particle_listeners {
+                listener(event);
+            }
+        }
+    }
+
+    pub fn remove_species(&mut self, species_id: u32) -> bool {
+        let removed = self.config_manager.config_mut().species.remove_species(species_id);
+        
+        if removed {
+            self.particle_system.kill_species(species_id);
+            self.renderer.set_species_registry(self.config_manager.config().species.clone());
+        }
+        
+        removed
+    }
+
+    fn apply_preset(&mut self, preset: Preset) {
+        let ui_config = &self.config_manager.config().ui;
+        if ui_config.morph_preset_transitions && self.particle_system.particle_count() > 0 {
+            let duration = ui_config.preset_transition_duration;
+            self.begin_preset_transition(preset, duration);
+        } else {
+            self.config_manager.apply_preset(preset.clone());
+            self.rebuild_simulation(preset);
+        }
+    }
+
+    fn begin_preset_transition(&mut self, preset: Preset, duration: f32) {
+        let config = self.config_manager.config();
+        let (from_interactions, from_settings) = (config.forces.species_interactions.clone(), MorphSettings::current(config, &self.particle_system));
+        self.config_manager.apply_preset(preset.clone());
+        self.morph_into_config(preset, from_interactions, from_settings, duration);
+    }
+
+    // Reassigns the running particles to the config's species and eases from the old pair rules
+    // and settings into its own
+    fn morph_into_config(&mut self, preset: Preset, from_interactions: HashMap<(u32, u32), Vec<ForceType>>, from_settings: MorphSettings, duration: f32) {
+        let config = self.config_manager.config();
+        transition::reassign_species(&mut self.particle_system, config);
+        self.particle_system.spawn_rate = config.particles.spawn_rate;
+        self.renderer.update_config(config.rendering.clone());
+        self.renderer.set_species_registry(config.species.clone());
+        
+        self.transition = Some(PresetTransition::new(
+            preset.clone(),
+            from_interactions,
+            config.forces.species_interactions.clone(),
+            duration,
+        )
+        .with_asymmetric(config.forces.asymmetric_interactions)
+        .with_settings(from_settings, MorphSettings::from_config(config)));
+        self.current_preset = Some(preset);
+    }
+
+    fn update_transition(&mut self, dt: f32) {
+        if let Some(ref mut transition) = self.transition {
+            transition.advance(dt);
+            if let Some(settings) = transition.current_settings() {
+                settings.apply(&mut self.physics_engine, &mut self.particle_system);
+            }
+            
+            if transition.is_finished() {
+                self.physics_engine.force_calculator.interaction_matrix = transition.target_matrix();
+                self.transition = None;
+            } else {
+                self.physics_engine.force_calculator.interaction_matrix = transition.current_matrix();
+            }
+        }
+    }
+
+    fn apply_custom_preset(&mut self, data: CustomPresetData) {
+        self.shape_hold = None;
+        self.rigid_groups.clear();
+        self.ui_state.grab = None;
+        *self.config_manager.config_mut() = data.config;
+        self.apply_current_config();
+        
+        let particles_config = &self.config_manager.config().particles;
+        let mut particle_system = ParticleSystem::new(particles_config.max_particles);
+        particle_system.spawn_rate = particles_config.spawn_rate;
+        particle_system.bounds = self.particle_system.bounds;
+        
+        for particle in data.initial_particles {
+            particle_system.add_particle(particle);
+        }
+        
+        self.particle_system = particle_system;
+        self.pheromones = None;
+        self.reaction = None;
+        self.cloth = None;
+        self.current_preset = None;
+        self.restart_comparison();
+    }
+
+    fn draw_scene_browser(&mut self, ui: &mut egui::Ui) {
+        let lang = self.config_manager.config().ui.language;
+        if self.ui_state.scene_list.is_none() {
+            self.ui_state.scene_list = Some(Scene::list());
+        }
+        
+        ui.horizontal(|ui| {
+            ui.label(tr(lang, "Name"));
+            ui.text_edit_singleline(&mut self.ui_state.scene_name);
+        });
+        ui.label(tr(lang, "Notes"));
+        ui.text_edit_multiline(&mut self.ui_state.scene_notes);
+        
+        if ui.button(tr(lang, "💾 Save Current Scene")).clicked() {
+            let name = self.ui_state.scene_name.trim().to_string();
+            if name.is_empty() {
+                self.notifications.warn("Enter a scene name before saving");
+            } else {
+                match self.capture_scene(&name).save() {
+                    Ok(()) => self.notifications.info(format!("Saved scene {}", name)),
+                    Err(e) => self.notifications.error(format!("Failed to save scene: {}", e)),
+                }
+                self.ui_state.scene_list = None;
+            }
+        }
+        
+        ui.separator();
+        if ui.button(tr(lang, "🔄 Refresh")).clicked() {
+            self.ui_state.scene_list = None;
+        }
+        
+        let names = self.ui_state.scene_list.clone().unwrap_or_default();
+        if names.is_empty() {
+            ui.label(tr(lang, "No saved scenes"));
+        }
+        
+        let mut to_load = None;
+        let mut to_duplicate = None;
+        let mut delete_confirmed = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for name in &names {
+                ui.horizontal(|ui| {
+                    ui.label(name);
+                    if self.ui_state.pending_scene_delete.as_ref() == Some(name) {
+                        if ui.button(tr(lang, "Confirm")).clicked() {
+                            delete_confirmed = Some(name.clone());
+                        }
+                        if ui.button(tr(lang, "Cancel")).clicked() {
+                            self.ui_state.pending_scene_delete = None;
+                        }
+                        return;
+                    }
+                    if ui.button(tr(lang, "Load")).clicked() {
+                        to_load = Some(name.clone());
+                    }
+                    if ui.button(tr(lang, "Duplicate")).clicked() {
+                        to_duplicate = Some(name.clone());
+                    }
+                    if ui.button(tr(lang, "Delete")).clicked() {
+                        self.ui_state.pending_scene_delete = Some(name.clone());
+                    }
+                });
+            }
+        });
+        
+        if let Some(name) = to_load {
+            self.open_scene_file(Scene::path_for(&name));
+        }
+        if let Some(name) = to_duplicate {
+            match Scene::duplicate(&name) {
+                Ok(copy) => self.notifications.info(format!("Duplicated {} as {}", name, copy)),
+                Err(e) => self.notifications.error(format!("Failed to duplicate scene: {}", e)),
+            }
+            self.ui_state.scene_list = None;
+        }
+        if let Some(name) = delete_confirmed {
+            if let Err(e) = Scene::delete(&name) {
+                self.notifications.error(format!("Failed to delete scene: {}", e));
+            }
+            self.ui_state.pending_scene_delete = None;
+            self.ui_state.scene_list = None;
+        }
+    }
+
+    pub fn capture_scene(&self, name: &str) -> Scene {
+        let (position, zoom) = self.renderer.camera_state();
+        let mut config = self.config_manager.config().clone();
+        config.rendering.camera_position = position;
+        config.rendering.camera_zoom = zoom;
+        
+        Scene {
+            preset: CustomPresetData {
+                name: name.to_string(),
+                description: format!("Scene with {} particles", self.particle_system.particle_count()),
+                config,
+                initial_particles: self.particle_system.particles.clone(),
+            },
+            camera: Some(CameraState { position, zoom }),
+            events: self.scene_events.clone(),
+            notes: self.ui_state.scene_notes.clone(),
+        }
+    }
+
+    pub fn load_scene(&mut self, scene: Scene) {
+        let mut preset = scene.preset;
+        if let Some(camera) = scene.camera {
+            preset.config.rendering.camera_position = camera.position;
+            preset.config.rendering.camera_zoom = camera.zoom;
+        }
+        
+        self.ui_state.scene_name = preset.name.clone();
+        self.ui_state.scene_notes = scene.notes;
+        self.scene_events = scene.events.clone();
+        self.scene_script = Some(SceneScript::new(scene.events));
+        self.transition = None;
+        self.history.clear();
+        self.apply_custom_preset(preset);
+    }
+
+    // Also used for .inochi files passed on the command line (e.g. through a file association)
+    pub fn open_scene_file(&mut self, path: impl AsRef<std::path::Path>) {
+        let path = path.as_ref();
+        match Scene::from_file(path) {
+            Ok(scene) => {
+                self.notifications.info(format!("Loaded scene {}", scene.name()));
+                self.load_scene(scene);
+            },
+            Err(e) => {
+                self.notifications.error(format!("Failed to open {}: {}", path.display(), e));
+            }
+        }
+    }
+
+    fn randomize_particle_life(&mut self) {
+        self.config_manager.randomize_particle_life(self.ui_state.species_count_slider);
+        self.rebuild_simulation(Preset::ParticleLife);
+    }
+
+    fn rebuild_simulation(&mut self, preset: Preset) {
+        self.current_preset = Some(preset.clone());
+        self.transition = None;
+        self.history.clear();
+        self.shape_hold = None;
+        self.rigid_groups.clear();
+        self.ui_state.grab = None;
+        self.pheromones = None;
+        self.reaction = None;
+        self.cloth = None;
+        
+        // Recreate particle system with new preset
+        self.particle_system = PresetManager::create_particle_system_from_preset(
+            &preset,
+            self.config_manager.config()
+        );
+        
+        // Update physics engine
+        self.physics_engine = PhysicsEngine::new(self.config_manager.config().physics.clone());
+        self.physics_engine.force_calculator.interaction_matrix = self.config_manager.config().forces.interaction_matrix();
+        
+        // Update renderer
+        self.renderer.update_config(self.config_manager.config().rendering.clone());
+        self.renderer.set_species_registry(self.config_manager.config().species.clone());
+        
+        if self.config_manager.config().rendering.auto_fit_camera {
+            self.fit_camera_to_particles();
+        }
+        
+        let warm_up_steps = self.config_manager.config().particles.warm_up_steps;
+        self.warm_up = (warm_up_steps > 0).then_some((0, warm_up_steps));
+        
+        // Update spatial partitioning
+        if self.config_manager.config().performance.enable_spatial_partitioning {
+            self.spatial = Some(SpatialPartitioning::new_quadtree(
+                (Vec2::new(-500.0, -500.0), Vec2::new(500.0, 500.0)),
+                10,
+                8
+            ));
+        } else {
+            self.spatial = None;
+        }
+        
+        self.restart_comparison();
+    }
+
+    fn fit_camera_to_particles(&mut self) {
+        self.renderer.fit_to_particles(&self.particle_system);
+        
+        // Keep the config in sync so the next update_config() doesn't undo the fit
+        let (camera_position, camera_zoom) = self.renderer.camera_state();
+        let rendering = &mut self.config_manager.config_mut().rendering;
+        rendering.camera_position = camera_position;
+        rendering.camera_zoom = camera_zoom;
+    }
+
+    fn reset_simulation(&mut self) {
+        if let Some(ref preset) = self.current_preset.clone() {
+            // A reset is always a hard rebuild, even when morphing is enabled;
+            // rebuild_simulation positions the camera (recommended view or auto-fit)
+            self.config_manager.apply_preset(preset.clone());
+            self.rebuild_simulation(preset.clone());
+        } else {
+            self.particle_system.clear();
+            self.renderer.reset_camera();
+        }
+    }
+
+    fn apply_current_config(&mut self) {
+        let config = self.config_manager.config().clone();
+        
+        // Update physics engine
+        self.physics_engine = PhysicsEngine::new(config.physics);
+        self.physics_engine.force_calculator.interaction_matrix = config.forces.interaction_matrix();
+        
+        // Update renderer
+        self.renderer.update_config(config.rendering);
+        self.renderer.set_species_registry(config.species);
+        
+        // Update spatial partitioning
+        if config.performance.enable_spatial_partitioning {
+            self.spatial = Some(SpatialPartitioning::new_quadtree(
+                (Vec2::new(-500.0, -500.0), Vec2::new(500.0, 500.0)),
+                10,
+                8
+            ));
+        } else {
+            self.spatial = None;
+        }
+    }
+
+    // Rebuilds the simulation with settings from an embedding page's URL
+    pub fn apply_embed_options(&mut self, options: &EmbedOptions) {
+        if let Some(seed) = options.seed {
+            rng::seed(seed);
+        }
+        let preset = options
+            .preset
+            .clone()
+            .or_else(|| self.current_preset.clone())
+            .unwrap_or(Preset::ParticleLife);
+        self.config_manager.apply_preset(preset.clone());
+        options.apply(self.config_manager.config_mut());
+        self.rebuild_simulation(preset);
+    }
+
+    // Whether egui is using the mouse or keyboard, so the simulation should leave it alone
+    fn ui_wants_pointer(&self) -> bool {
+        !self.controls_separate && self.egui.as_ref().is_some_and(|egui| egui.ctx().wants_pointer_input())
+    }
+
+    fn ui_wants_keyboard(&self) -> bool {
+        self.egui.as_ref().is_some_and(|egui| egui.ctx().wants_keyboard_input())
+    }
+
+    // Redraws the picking buffer at the current view. Picks do this themselves when the buffer
+    // is stale, so each frame that nobody picks in skips the redraw.
+    pub fn refresh_picking(&mut self) {
+        self.renderer.draw_picking(&self.particle_system, &mut self.picking);
+    }
+
+    // Circles at the reach of each pair rule of the particle under the cursor, coloured by the
+    // species the rule applies to. Forces without a cutoff reach everything and get no circle.
+    fn update_interaction_radii(&mut self) {
+        let hovered = self.ui_state.cursor_position
+            .filter(|_| self.config_manager.config().rendering.show_interaction_radii && !self.ui_wants_pointer())
+            .and_then(|position| self.pick_particle(position));
+        let config = self.config_manager.config();
+        let hovered = hovered.and_then(|index| self.particle_system.particles.get(index));
+        let matrix = &self.physics_engine.force_calculator.interaction_matrix;
+        let radii = hovered.map(|particle| {
+            let radii = config.species.ids()
+                .into_iter()
+                .flat_map(move |species| {
+                    matrix.get_forces(particle.species_id, species)
+                        .iter()
+                        .filter(|force| force.is_pair_force())
+                        .filter_map(ForceType::cutoff)
+                        .map(move |radius| (radius, config.species.color(species)))
+                })
+                .collect();
+            (particle.position, radii)
+        });
+        self.renderer.set_interaction_radii(radii);
+    }
+
+    // Index of the particle drawn at `position`, in points from the top-left of the view
+    pub fn pick_particle(&mut self, position: Vec2) -> Option<usize> {
+        if self.picking.is_stale() {
+            self.refresh_picking();
+        }
+        self.picking.pick(position.x, position.y)
+    }
+
+    // World position under `position`, in points from the top-left of the view, which also
+    // becomes the cursor position
+    fn point_to_world(&mut self, position: Vec2) -> Vec2 {
+        let rendering = &self.config_manager.config().rendering;
+        let screen_size = nannou::geom::Vec2::new(rendering.window_width as f32, rendering.window_height as f32);
+        self.renderer.handle_mouse_input(nannou::geom::Vec2::new(position.x, position.y), screen_size);
+        self.renderer.cursor_world()
+    }
+
+    // Hooks the particle under or nearest `position` (in points from the top-left of the view)
+    // onto a spring to that point; returns its index
+    pub fn start_grab(&mut self, position: Vec2) -> Option<usize> {
+        let target = self.point_to_world(position);
+        let reach = self.ui_state.grab_options.reach / self.renderer.camera.zoom;
+        self.ui_state.grab = match self.pick_particle(position).and_then(|index| self.particle_system.particles.get(index)) {
+            Some(particle) => Some(Grab { id: particle.id, target }),
+            None => Grab::nearest(&self.particle_system, target, reach),
+        };
+        self.ui_state.grab.and_then(|grab| self.particle_system.index_of(grab.id))
+    }
+
+    // Moves the grabbed particle's end of the spring to `position`, in points from the top-left
+    pub fn move_grab(&mut self, position: Vec2) {
+        if self.ui_state.grab.is_some() {
+            let target = self.point_to_world(position);
+            if let Some(grab) = self.ui_state.grab.as_mut() {
+                grab.target = target;
+            }
+        }
+    }
+
+    pub fn release_grab(&mut self) {
+        self.ui_state.grab = None;
+    }
+
+    // Keeps the view in step with the window or canvas size, in logical points
+    pub fn resize_viewport(&mut self, width: u32, height: u32) {
+        let rendering = &mut self.config_manager.config_mut().rendering;
+        rendering.window_width = width.max(1);
+        rendering.window_height = height.max(1);
+        self.renderer.resize(width, height, self.particle_system.bounds);
+        
+        let (camera_position, camera_zoom) = self.renderer.camera_state();
+        let rendering = &mut self.config_manager.config_mut().rendering;
+        rendering.camera_position = camera_position;
+        rendering.camera_zoom = camera_zoom;
+    }
+
+    // Applies a config or custom preset file loaded from outside the app
+    pub fn apply_config_file(&mut self, file: ConfigFile) {
+        match file {
+            ConfigFile::Preset(data) => self.apply_custom_preset(data),
+            ConfigFile::Config(config) => {
+                *self.config_manager.config_mut() = config;
+                self.apply_current_config();
+            },
+        }
+    }
+
+    // Back to default settings, keeping the current preset
+    pub fn reset_settings(&mut self) {
+        *self.config_manager.config_mut() = SimulationConfig::default();
+        let preset = self.current_preset.clone().unwrap_or(Preset::ParticleLife);
+        self.config_manager.apply_preset(preset.clone());
+        self.rebuild_simulation(preset);
+    }
+
+    pub fn get_particle_count(&self) -> usize {
+        self.particle_system.particle_count()
+    }
+
+    pub fn get_fps(&self) -> f32 {
+        self.performance_stats.fps
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+}
+
+fn build_controls_window(app: &nannou::App) -> Result<nannou::window::Id, String> {
+    app.new_window()
+        .title("Inochi - Controls")
+        .size(480, 800)
+        .view(controls_view)
+        .raw_event(controls_window_event)
+        .key_pressed(controls_key_pressed)
+        .build()
+        .map_err(|e| format!("{:?}", e))
+}
+
+fn controls_view(_app: &nannou::App, model: &App, frame: nannou::Frame) {
+    model.render_controls(&frame);
+}
+
+fn controls_window_event(_app: &nannou::App, model: &mut App, event: &nannou::winit::event::WindowEvent) {
+    model.controls_window_event(event);
+}
+
+// Shortcuts work from either window
+fn controls_key_pressed(_app: &nannou::App, model: &mut App, key: nannou::event::Key) {
+    model.key_pressed(key);
+}
+
+fn load_thumbnail_texture(ctx: &egui::Context, name: &str, thumbnail: &PresetThumbnail) -> egui::TextureHandle {
+    let image = egui::ColorImage::from_rgba_unmultiplied(
+        [thumbnail.width, thumbnail.height],
+        &thumbnail.pixels,
+    );
+    ctx.load_texture(name, image, egui::TextureOptions::NEAREST)
+}
+
+fn build_visuals(theme: UiTheme, accent: [u8; 3]) -> egui::Visuals {
+    let accent = egui::Color32::from_rgb(accent[0], accent[1], accent[2]);
+    
+    let mut visuals = match theme {
+        UiTheme::Dark => egui::Visuals::dark(),
+        UiTheme::Light => egui::Visuals::light(),
+        UiTheme::HighContrast => {
+            let mut visuals = egui::Visuals::dark();
+            visuals.override_text_color = Some(egui::Color32::WHITE);
+            visuals.panel_fill = egui::Color32::BLACK;
+            visuals.window_fill = egui::Color32::BLACK;
+            visuals.extreme_bg_color = egui::Color32::BLACK;
+            visuals.window_stroke = egui::Stroke::new(2.0, egui::Color32::WHITE);
+            visuals.widgets.noninteractive.bg_stroke = egui::Stroke::new(1.5, egui::Color32::WHITE);
+            visuals.widgets.inactive.bg_stroke = egui::Stroke::new(1.5, egui::Color32::WHITE);
+            visuals.widgets.inactive.bg_fill = egui::Color32::BLACK;
+            visuals.widgets.inactive.weak_bg_fill = egui::Color32::BLACK;
+            visuals
+        }
+    };
+    
+    visuals.selection.bg_fill = accent;
+    visuals.selection.stroke = egui::Stroke::new(1.0, egui::Color32::WHITE);
+    visuals.hyperlink_color = accent;
+    visuals.widgets.hovered.bg_stroke = egui::Stroke::new(1.5, accent);
+    visuals.widgets.active.bg_fill = accent;
+    visuals
+}
+
+// Checkbox that adds or removes a global force of `default`'s kind; returns the force while enabled
+fn global_force_toggle<'a>(ui: &mut egui::Ui, label: &str, forces: &'a mut Vec<ForceType>, default: ForceType) -> Option<&'a mut ForceType> {
+    let kind = default.kind();
+    let mut enabled = forces.iter().any(|force| force.kind() == kind);
+    if ui.checkbox(&mut enabled, label).changed() {
+        if enabled {
+            forces.push(default);
+        } else {
+            forces.retain(|force| force.kind() != kind);
+        }
+    }
+    forces.iter_mut().find(|force| force.kind() == kind)
+}
+
+fn draw_stamp_options(ui: &mut egui::Ui, lang: Language, species: &config::SpeciesRegistry, options: &mut StampOptions) {
+    egui::ComboBox::from_label(tr(lang, "Species"))
+        .selected_text(species.name(options.species_id))
+        .show_ui(ui, |ui| {
+            for definition in &species.species {
+                ui.selectable_value(&mut options.species_id, definition.id, &definition.name);
+            }
+        });
+    
+    ui.horizontal(|ui| {
+        for mode in StampMode::all() {
+            ui.selectable_value(&mut options.mode, mode, tr(lang, mode.name()));
+        }
+    });
+    match options.mode {
+        StampMode::Disperse => {
+            ui.add(egui::Slider::new(&mut options.strength, 0.0..=50.0).text(tr(lang, "Speed")));
+        },
+        StampMode::Hold => {
+            ui.add(egui::Slider::new(&mut options.strength, 0.1..=20.0).text(tr(lang, "Stiffness")));
+        },
+        StampMode::Free | StampMode::Rigid => {},
+    }
+}
+
+fn edit_steering_behavior(
+    ui: &mut egui::Ui,
+    lang: Language,
+    species: &config::SpeciesRegistry,
+    path_count: usize,
+    behavior: &mut SteeringBehavior,
+) {
+    match behavior {
+        SteeringBehavior::Wander { radius, distance, rate } => {
+            ui.add(egui::Slider::new(radius, 1.0..=100.0).text(tr(lang, "Radius")));
+            ui.add(egui::Slider::new(distance, 0.0..=200.0).text(tr(lang, "Distance")));
+            ui.add(egui::Slider::new(rate, 0.0..=5.0).text(tr(lang, "Rate")));
+        },
+        SteeringBehavior::Pursue { species: target, radius } | SteeringBehavior::Evade { species: target, radius } => {
+            egui::ComboBox::from_label(tr(lang, "Target Species"))
+                .selected_text(species.name(*target))
+                .show_ui(ui, |ui| {
+                    for definition in &species.species {
+                        ui.selectable_value(target, definition.id, &definition.name);
+                    }
+                });
+            ui.add(egui::Slider::new(radius, 1.0..=500.0).text(tr(lang, "Radius")));
+        },
+        SteeringBehavior::FollowPath { path, lookahead } => {
+            if path_count == 0 {
+                ui.label(tr(lang, "Draw a path below first"));
+            } else {
+                ui.add(egui::Slider::new(path, 0..=path_count - 1).text(tr(lang, "Path")));
+            }
+            ui.add(egui::Slider::new(lookahead, 1.0..=200.0).text(tr(lang, "Lookahead")));
+        },
+        SteeringBehavior::Separate { radius } | SteeringBehavior::Align { radius } | SteeringBehavior::Cohere { radius } => {
+            ui.add(egui::Slider::new(radius, 1.0..=200.0).text(tr(lang, "Radius")));
+        },
+    }
+}
+
+fn edit_pair_force(ui: &mut egui::Ui, lang: Language, force: &mut ForceType) -> bool {
+    let mut changed = false;
+    let mut parameter = |ui: &mut egui::Ui, label: &str, value: &mut f32, min: f32| {
+        ui.horizontal(|ui| {
+            ui.label(tr(lang, label));
+            changed |= ui.add(egui::DragValue::new(value)
+                .speed(0.05)
+                .clamp_range(min..=f32::MAX)).changed();
+        });
+    };
+    
+    match force {
+        ForceType::Gravity { strength, min_distance } | ForceType::ElectroMagnetic { strength, min_distance } => {
+            parameter(ui, "Strength", strength, f32::MIN);
+            parameter(ui, "Min Distance", min_distance, 0.01);
+        },
+        ForceType::LennardJones { epsilon, sigma } => {
+            parameter(ui, "Epsilon", epsilon, 0.0);
+            parameter(ui, "Sigma", sigma, 0.01);
+        },
+        ForceType::Attraction { strength, max_distance } | ForceType::Repulsion { strength, max_distance } => {
+            parameter(ui, "Strength", strength, f32::MIN);
+            parameter(ui, "Max Distance", max_distance, 0.01);
+        },
+        ForceType::Spring { rest_length, stiffness, damping } => {
+            parameter(ui, "Rest Length", rest_length, 0.0);
+            parameter(ui, "Stiffness", stiffness, 0.0);
+            parameter(ui, "Damping", damping, 0.0);
+        },
+        ForceType::Profile { points } => changed |= edit_profile(ui, lang, points),
+        _ => {},
+    }
+    
+    changed
+}
+
+fn edit_curl_noise(ui: &mut egui::Ui, lang: Language, field: &mut CurlNoiseVelocity) {
+    ui.add(egui::Slider::new(&mut field.scale, 10.0..=1000.0).logarithmic(true).text(tr(lang, "Swirl Size")));
+    ui.add(egui::Slider::new(&mut field.speed, 0.0..=300.0).text(tr(lang, "Speed")));
+    ui.add(egui::Slider::new(&mut field.octaves, 1..=6).text(tr(lang, "Octaves")));
+}
+
+// Points of a Profile over force against distance. Dragging moves a point between its
+// neighbours, double-clicking adds one and right-clicking removes one.
+fn edit_profile(ui: &mut egui::Ui, lang: Language, points: &mut Vec<(f32, f32)>) -> bool {
+    let mut changed = false;
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(ui.available_width(), 160.0), egui::Sense::click());
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals().clone();
+    painter.rect_filled(rect, 2.0, visuals.extreme_bg_color);
+    
+    let max_distance = points.last().map_or(0.0, |&(distance, _)| distance).max(10.0) * 1.25;
+    let max_force = points.iter().fold(1.0, |max: f32, &(_, force)| max.max(force.abs())) * 1.25;
+    let axis_y = rect.center().y;
+    let half_height = rect.height() * 0.5;
+    let to_screen = |(distance, force): (f32, f32)| {
+        egui::pos2(rect.left() + distance / max_distance * rect.width(), axis_y - force / max_force * half_height)
+    };
+    painter.line_segment(
+        [egui::pos2(rect.left(), axis_y), egui::pos2(rect.right(), axis_y)],
+        egui::Stroke::new(1.0, visuals.weak_text_color()),
+    );
+    
+    // The curve holds its first force down to zero distance and drops to zero past its last point
+    let line_color = egui::Color32::from_rgb(255, 140, 60);
+    if let (Some(&first), Some(&last)) = (points.first(), points.last()) {
+        let mut line = vec![to_screen((0.0, first.1))];
+        line.extend(points.iter().map(|&point| to_screen(point)));
+        line.extend([to_screen((last.0, 0.0)), to_screen((max_distance, 0.0))]);
+        painter.add(egui::Shape::line(line, egui::Stroke::new(1.5, line_color)));
+    }
+    
+    let mut remove = None;
+    for index in 0..points.len() {
+        let center = to_screen(points[index]);
+        let handle = ui.interact(egui::Rect::from_center_size(center, egui::Vec2::splat(12.0)), response.id.with(index), egui::Sense::click_and_drag());
+        if handle.dragged() {
+            let delta = handle.drag_delta();
+            let low = if index == 0 { 0.0 } else { points[index - 1].0 };
+            let high = points.get(index + 1).map_or(f32::MAX, |&(distance, _)| distance);
+            let (distance, force) = &mut points[index];
+            *distance = (*distance + delta.x / rect.width() * max_distance).clamp(low, high);
+            *force -= delta.y / half_height * max_force;
+            changed = true;
+        }
+        if handle.secondary_clicked() && points.len() > 2 {
+            remove = Some(index);
+        }
+        let radius = if handle.hovered() || handle.dragged() { 5.0 } else { 3.5 };
+        painter.circle_filled(center, radius, visuals.text_color());
+        let (distance, force) = points[index];
+        handle.on_hover_text(format!("r = {:.1}, F = {:+.2}", distance, force));
+    }
+    if let Some(index) = remove {
+        points.remove(index);
+        changed = true;
+    }
+    if response.double_clicked() {
+        if let Some(pos) = response.interact_pointer_pos() {
+            let distance = ((pos.x - rect.left()) / rect.width() * max_distance).max(0.0);
+            let force = (axis_y - pos.y) / half_height * max_force;
+            let index = points.partition_point(|&(d, _)| d < distance);
+            points.insert(index, (distance, force));
+            changed = true;
+        }
+    }
+    
+    let font = egui::FontId::proportional(11.0);
+    painter.text(rect.left_top() + egui::vec2(4.0, 4.0), egui::Align2::LEFT_TOP, tr(lang, "Force (+ repulsive)"), font.clone(), line_color);
+    painter.text(rect.right_bottom() - egui::vec2(4.0, 4.0), egui::Align2::RIGHT_BOTTOM, format!("r = {:.1}", max_distance), font, visuals.text_color());
+    ui.label(tr(lang, "Drag points to reshape the curve; double-click to add a point, right-click to remove one."));
+    
+    changed
+}
+
+// Plots force and potential against distance, each scaled to its own range
+fn draw_force_curve(ui: &mut egui::Ui, lang: Language, curve: &[CurvePoint]) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width(), 160.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals();
+    painter.rect_filled(rect, 2.0, visuals.extreme_bg_color);
+    
+    let Some(last) = curve.last() else {
+        return;
+    };
+    
+    // Curves like Lennard-Jones diverge near zero, so scale to the 90th percentile instead of the peak
+    let robust_scale = |values: &mut dyn Iterator<Item = f32>| {
+        let mut magnitudes: Vec<f32> = values.map(f32::abs).filter(|v| v.is_finite()).collect();
+        magnitudes.sort_by(|a, b| a.total_cmp(b));
+        magnitudes.get(magnitudes.len() * 9 / 10).copied().unwrap_or(0.0).max(1e-6)
+    };
+    let force_scale = robust_scale(&mut curve.iter().map(|p| p.force));
+    let potential_scale = robust_scale(&mut curve.iter().map(|p| p.potential));
+    
+    let axis_y = rect.center().y;
+    let to_screen = |distance: f32, value: f32, scale: f32| {
+        egui::pos2(
+            rect.left() + distance / last.distance * rect.width(),
+            axis_y - (value / scale).clamp(-1.0, 1.0) * rect.height() * 0.45,
+        )
+    };
+    
+    painter.line_segment(
+        [egui::pos2(rect.left(), axis_y), egui::pos2(rect.right(), axis_y)],
+        egui::Stroke::new(1.0, visuals.weak_text_color()),
+    );
+    
+    let force_color = egui::Color32::from_rgb(255, 140, 60);
+    let potential_color = visuals.selection.bg_fill;
+    let force_points: Vec<egui::Pos2> = curve
+        .iter()
+        .filter(|p| p.force.is_finite())
+        .map(|p| to_screen(p.distance, p.force, force_scale))
+        .collect();
+    let potential_points: Vec<egui::Pos2> = curve
+        .iter()
+        .filter(|p| p.potential.is_finite())
+        .map(|p| to_screen(p.distance, p.potential, potential_scale))
+        .collect();
+    painter.add(egui::Shape::line(force_points, egui::Stroke::new(1.5, force_color)));
+    painter.add(egui::Shape::line(potential_points, egui::Stroke::new(1.5, potential_color)));
+    
+    let font = egui::FontId::proportional(11.0);
+    painter.text(rect.left_top() + egui::vec2(4.0, 4.0), egui::Align2::LEFT_TOP, tr(lang, "Force (+ repulsive)"), font.clone(), force_color);
+    painter.text(rect.left_top() + egui::vec2(4.0, 18.0), egui::Align2::LEFT_TOP, tr(lang, "Potential"), font.clone(), potential_color);
+    painter.text(rect.right_bottom() - egui::vec2(4.0, 4.0), egui::Align2::RIGHT_BOTTOM, format!("r = {:.1}", last.distance), font, visuals.text_color());
+}
+
+fn draw_gallery_entry(ui: &mut egui::Ui, lang: Language, texture: &egui::TextureHandle, name: &str, description: &str) -> bool {
+    let mut clicked = false;
+    
+    ui.horizontal(|ui| {
+        clicked |= ui.add(egui::ImageButton::new((texture.id(), texture.size_vec2()))).clicked();
+        ui.vertical(|ui| {
+            ui.strong(name);
+            ui.label(description);
+            clicked |= ui.button(tr(lang, "Apply")).clicked();
+        });
+    });
+    ui.separator();
+    
+    clicked
+}
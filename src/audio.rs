@@ -0,0 +1,321 @@
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+use crate::forces::{ForceCalculator, ForceKind};
+
+#[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+use std::sync::{Arc, Mutex};
+#[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+use std::collections::VecDeque;
+
+const ANALYSIS_WINDOW: usize = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AudioSource {
+    Amplitude,
+    Bass,
+    Mid,
+    Treble,
+}
+
+impl AudioSource {
+    pub fn all() -> Vec<AudioSource> {
+        vec![AudioSource::Amplitude, AudioSource::Bass, AudioSource::Mid, AudioSource::Treble]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            AudioSource::Amplitude => "Amplitude",
+            AudioSource::Bass => "Bass",
+            AudioSource::Mid => "Mid",
+            AudioSource::Treble => "Treble",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioMapping {
+    pub source: AudioSource,
+    pub target: ForceKind,
+    pub base: f32,
+    pub depth: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioConfig {
+    pub enabled: bool,
+    pub gain: f32,
+    pub smoothing: f32,
+    pub mappings: Vec<AudioMapping>,
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            gain: 1.0,
+            smoothing: 0.8,
+            mappings: vec![
+                AudioMapping { source: AudioSource::Bass, target: ForceKind::Vortex, base: 1.0, depth: 4.0 },
+                AudioMapping { source: AudioSource::Treble, target: ForceKind::Brownian, base: 1.0, depth: 4.0 },
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AudioBands {
+    pub amplitude: f32,
+    pub bass: f32,
+    pub mid: f32,
+    pub treble: f32,
+}
+
+impl AudioBands {
+    pub fn value(&self, source: AudioSource) -> f32 {
+        match source {
+            AudioSource::Amplitude => self.amplitude,
+            AudioSource::Bass => self.bass,
+            AudioSource::Mid => self.mid,
+            AudioSource::Treble => self.treble,
+        }
+    }
+
+    pub fn analyze(samples: &[f32], sample_rate: f32) -> Self {
+        let window = &samples[samples.len().saturating_sub(ANALYSIS_WINDOW)..];
+        let n = window.len();
+        if n == 0 || sample_rate <= 0.0 {
+            return Self::default();
+        }
+
+        let amplitude = (window.iter().map(|s| s * s).sum::<f32>() / n as f32).sqrt();
+
+        // Hann-windowed DFT; the window is small enough that a naive transform is fine per frame
+        let windowed: Vec<f32> = window
+            .iter()
+            .enumerate()
+            .map(|(i, s)| s * (0.5 - 0.5 * (2.0 * PI * i as f32 / n as f32).cos()))
+            .collect();
+
+        let bin_hz = sample_rate / n as f32;
+        let mut bands = [0.0f32; 3];
+        let mut counts = [0usize; 3];
+
+        for k in 1..n / 2 {
+            let frequency = k as f32 * bin_hz;
+            let band = if frequency < 250.0 {
+                0
+            } else if frequency < 2000.0 {
+                1
+            } else if frequency < 8000.0 {
+                2
+            } else {
+                continue;
+            };
+
+            let (mut re, mut im) = (0.0f32, 0.0f32);
+            for (i, s) in windowed.iter().enumerate() {
+                let phase = 2.0 * PI * k as f32 * i as f32 / n as f32;
+                re += s * phase.cos();
+                im -= s * phase.sin();
+            }
+
+            bands[band] += (re * re + im * im).sqrt() * 2.0 / n as f32;
+            counts[band] += 1;
+        }
+
+        let average = |band: usize| if counts[band] > 0 { bands[band] / counts[band] as f32 } else { 0.0 };
+
+        Self {
+            amplitude,
+            bass: average(0),
+            mid: average(1),
+            treble: average(2),
+        }
+    }
+
+    fn smoothed(&self, next: &AudioBands, smoothing: f32) -> AudioBands {
+        let mix = |a: f32, b: f32| a * smoothing + b * (1.0 - smoothing);
+        AudioBands {
+            amplitude: mix(self.amplitude, next.amplitude),
+            bass: mix(self.bass, next.bass),
+            mid: mix(self.mid, next.mid),
+            treble: mix(self.treble, next.treble),
+        }
+    }
+}
+
+#[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+pub struct AudioInput {
+    _stream: cpal::Stream,
+    buffer: Arc<Mutex<VecDeque<f32>>>,
+    sample_rate: f32,
+}
+
+#[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+impl AudioInput {
+    pub fn start() -> Result<Self, String> {
+        use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+        let host = cpal::default_host();
+        let device = host
+            .default_input_device()
+            .ok_or_else(|| "No audio input device available".to_string())?;
+        let config = device.default_input_config().map_err(|e| e.to_string())?;
+        let channels = config.channels() as usize;
+        let sample_rate = config.sample_rate().0 as f32;
+
+        let buffer = Arc::new(Mutex::new(VecDeque::with_capacity(ANALYSIS_WINDOW * 2)));
+        let stream_buffer = Arc::clone(&buffer);
+
+        let stream = device
+            .build_input_stream(
+                &config.into(),
+                move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                    if let Ok(mut buffer) = stream_buffer.lock() {
+                        // Downmix to mono
+                        for frame in data.chunks(channels) {
+                            buffer.push_back(frame.iter().sum::<f32>() / channels as f32);
+                        }
+                        while buffer.len() > ANALYSIS_WINDOW {
+                            buffer.pop_front();
+                        }
+                    }
+                },
+                |err| eprintln!("Audio input error: {}", err),
+                None,
+            )
+            .map_err(|e| e.to_string())?;
+        stream.play().map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            _stream: stream,
+            buffer,
+            sample_rate,
+        })
+    }
+
+    pub fn latest_samples(&self) -> Vec<f32> {
+        self.buffer
+            .lock()
+            .map(|buffer| buffer.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+}
+
+#[derive(Default)]
+pub struct AudioReactor {
+    pub bands: AudioBands,
+    #[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+    input: Option<AudioInput>,
+}
+
+impl AudioReactor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+    pub fn start_input(&mut self) -> Result<(), String> {
+        if self.input.is_none() {
+            self.input = Some(AudioInput::start()?);
+        }
+        Ok(())
+    }
+
+    #[cfg(not(all(feature = "audio", not(target_arch = "wasm32"))))]
+    pub fn start_input(&mut self) -> Result<(), String> {
+        Err("Audio input requires the `audio` feature on desktop; in the browser samples are pushed from Web Audio".to_string())
+    }
+
+    pub fn is_listening(&self) -> bool {
+        #[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+        {
+            self.input.is_some()
+        }
+        #[cfg(not(all(feature = "audio", not(target_arch = "wasm32"))))]
+        {
+            false
+        }
+    }
+
+    // Feeds externally captured samples (e.g. a Web Audio AnalyserNode in the browser)
+    pub fn push_samples(&mut self, samples: &[f32], sample_rate: f32, config: &AudioConfig) {
+        let scaled: Vec<f32> = samples.iter().map(|s| s * config.gain).collect();
+        let next = AudioBands::analyze(&scaled, sample_rate);
+        self.bands = self.bands.smoothed(&next, config.smoothing.clamp(0.0, 0.99));
+    }
+
+    pub fn update(&mut self, config: &AudioConfig) {
+        #[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+        {
+            let captured = self
+                .input
+                .as_ref()
+                .map(|input| (input.latest_samples(), input.sample_rate()));
+            if let Some((samples, sample_rate)) = captured {
+                self.push_samples(&samples, sample_rate, config);
+            }
+        }
+        #[cfg(not(all(feature = "audio", not(target_arch = "wasm32"))))]
+        {
+            let _ = config;
+        }
+    }
+
+    // Scales on top of the multipliers already set for this frame
+    pub fn apply(&self, config: &AudioConfig, calculator: &mut ForceCalculator) {
+        if !config.enabled {
+            return;
+        }
+
+        for mapping in &config.mappings {
+            let factor = mapping.base + mapping.depth * self.bands.value(mapping.source);
+            let current = calculator.force_multiplier(mapping.target);
+            calculator.set_force_multiplier(mapping.target, current * factor.max(0.0));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f32, sample_rate: f32) -> Vec<f32> {
+        (0..ANALYSIS_WINDOW)
+            .map(|i| (2.0 * PI * frequency * i as f32 / sample_rate).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_band_analysis() {
+        let bass = AudioBands::analyze(&sine(100.0, 44100.0), 44100.0);
+        assert!(bass.bass > bass.treble);
+
+        let treble = AudioBands::analyze(&sine(5000.0, 44100.0), 44100.0);
+        assert!(treble.treble > treble.bass);
+
+        let silence = AudioBands::analyze(&[0.0; ANALYSIS_WINDOW], 44100.0);
+        assert_eq!(silence, AudioBands::default());
+    }
+
+    #[test]
+    fn test_mapping_applies_multiplier() {
+        let mut reactor = AudioReactor::new();
+        reactor.bands.bass = 0.5;
+
+        let mut config = AudioConfig { enabled: true, ..Default::default() };
+
+        let mut calculator = ForceCalculator::new();
+        reactor.apply(&config, &mut calculator);
+        assert_eq!(calculator.force_multiplier(ForceKind::Vortex), 3.0);
+
+        config.enabled = false;
+        let mut calculator = ForceCalculator::new();
+        reactor.apply(&config, &mut calculator);
+        assert_eq!(calculator.force_multiplier(ForceKind::Vortex), 1.0);
+    }
+}
@@ -0,0 +1,221 @@
+#[cfg(feature = "checkpoint")]
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "checkpoint")]
+use std::fs::File;
+#[cfg(feature = "checkpoint")]
+use std::io::{BufReader, BufWriter};
+#[cfg(feature = "checkpoint")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "checkpoint")]
+use std::thread::JoinHandle;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::cloth::Cloth;
+use crate::config::{Preset, SimulationConfig};
+use crate::particle::ParticleSystem;
+use crate::pheromone::PheromoneField;
+use crate::reaction::ReactionField;
+use crate::rng::SimRng;
+
+pub const AUTOSAVE_FILE: &str = "session.autosave.gz";
+const AUTOSAVE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutosaveConfig {
+    pub enabled: bool,
+    pub interval_seconds: f32,
+}
+
+impl Default for AutosaveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_seconds: 60.0,
+        }
+    }
+}
+
+// The live session as it was at the last autosave, rules and particles alike, so a crash or power
+// cut loses at most one interval of tuning
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    pub version: u32,
+    // Seconds since the Unix epoch
+    pub saved_at: u64,
+    // None for a custom or hand-tuned setup
+    pub preset: Option<Preset>,
+    pub config: SimulationConfig,
+    pub system: ParticleSystem,
+    pub pheromones: Option<PheromoneField>,
+    pub reaction: Option<ReactionField>,
+    pub cloth: Option<Cloth>,
+    pub sim_time: f32,
+    pub rng: SimRng,
+}
+
+impl SessionSnapshot {
+    // Stamped with the current time
+    pub fn new(preset: Option<Preset>, config: SimulationConfig, system: ParticleSystem, sim_time: f32, rng: SimRng) -> Self {
+        Self {
+            version: AUTOSAVE_VERSION,
+            saved_at: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs()),
+            preset,
+            config,
+            system,
+            pheromones: None,
+            reaction: None,
+            cloth: None,
+            sim_time,
+            rng,
+        }
+    }
+
+    // Gzipped bincode like a checkpoint, and likewise written to a temporary file first so a crash
+    // mid-write leaves the previous autosave intact
+    #[cfg(feature = "checkpoint")]
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let temporary = path.with_extension("tmp");
+        let file = File::create(&temporary).map_err(|e| format!("Failed to create {}: {}", temporary.display(), e))?;
+        let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::fast());
+        bincode::serialize_into(&mut encoder, self).map_err(|e| format!("Failed to write autosave: {}", e))?;
+        encoder.finish().map_err(|e| format!("Failed to write autosave: {}", e))?;
+        std::fs::rename(&temporary, path).map_err(|e| format!("Failed to save {}: {}", path.display(), e))
+    }
+
+    #[cfg(feature = "checkpoint")]
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        let snapshot: SessionSnapshot = bincode::deserialize_from(GzDecoder::new(BufReader::new(file)))
+            .map_err(|e| format!("Invalid autosave {}: {}", path.display(), e))?;
+        if snapshot.version != AUTOSAVE_VERSION {
+            return Err(format!("Unsupported autosave version {} in {}", snapshot.version, path.display()));
+        }
+        Ok(snapshot)
+    }
+
+    // Whole minutes between the save and `now` (seconds since the Unix epoch)
+    pub fn age_minutes(&self, now: u64) -> u64 {
+        now.saturating_sub(self.saved_at) / 60
+    }
+}
+
+// Periodic autosave to a temporary location. Snapshots are written on a background thread so a
+// large system doesn't stall a frame, and the file is removed on a clean exit: finding one at
+// launch means the last session ended in a crash.
+#[cfg(feature = "checkpoint")]
+#[derive(Debug)]
+pub struct Autosave {
+    path: PathBuf,
+    since_save: f32,
+    saving: Option<JoinHandle<Result<(), String>>>,
+}
+
+#[cfg(feature = "checkpoint")]
+impl Autosave {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, since_save: 0.0, saving: None }
+    }
+
+    // Under the OS temp directory; the web build has no filesystem and never autosaves
+    pub fn default_path() -> PathBuf {
+        if cfg!(target_arch = "wasm32") {
+            PathBuf::from(AUTOSAVE_FILE)
+        } else {
+            std::env::temp_dir().join("inochi").join(AUTOSAVE_FILE)
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Advances the timer by `dt` seconds; true when a save is due and the last one has finished
+    pub fn due(&mut self, config: &AutosaveConfig, dt: f32) -> bool {
+        if !config.enabled || cfg!(target_arch = "wasm32") {
+            return false;
+        }
+        self.since_save += dt;
+        self.since_save >= config.interval_seconds.max(1.0) && self.saving.is_none()
+    }
+
+    pub fn save_in_background(&mut self, snapshot: SessionSnapshot) {
+        self.since_save = 0.0;
+        let path = self.path.clone();
+        self.saving = Some(std::thread::spawn(move || snapshot.save(&path)));
+    }
+
+    // The outcome of a background save once it has finished
+    pub fn poll(&mut self) -> Option<Result<(), String>> {
+        if !self.saving.as_ref()?.is_finished() {
+            return None;
+        }
+        self.saving.take().map(|handle| handle.join().unwrap_or_else(|_| Err("Autosave thread panicked".to_string())))
+    }
+
+    // Whether an earlier session left an autosave behind
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    pub fn load(&self) -> Result<SessionSnapshot, String> {
+        SessionSnapshot::load(&self.path)
+    }
+
+    // Waits out any save in progress, then deletes the autosave
+    pub fn discard(&mut self) -> Result<(), String> {
+        if let Some(handle) = self.saving.take() {
+            let _ = handle.join();
+        }
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to remove {}: {}", self.path.display(), e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "checkpoint"))]
+mod tests {
+    use super::*;
+    use crate::particle::Particle;
+    use crate::rng;
+    use glam::Vec2;
+
+    #[test]
+    fn test_background_autosave_round_trips_and_discards() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut autosave = Autosave::new(dir.path().join("nested").join(AUTOSAVE_FILE));
+        let config = AutosaveConfig { enabled: true, interval_seconds: 10.0 };
+        assert!(!autosave.due(&config, 6.0));
+        assert!(autosave.due(&config, 6.0));
+        assert!(!autosave.due(&AutosaveConfig { enabled: false, ..config.clone() }, 60.0));
+
+        let mut system = ParticleSystem::new(10);
+        system.add_particle(Particle::new(Vec2::new(3.0, 4.0)).with_species(2));
+        let mut simulation = SimulationConfig::default();
+        simulation.physics.dt = 0.005;
+        autosave.save_in_background(SessionSnapshot::new(Some(Preset::Gravity), simulation, system, 12.5, rng::state()));
+        assert!(!autosave.due(&config, 1.0));
+        let saved = loop {
+            match autosave.poll() {
+                Some(result) => break result,
+                None => std::thread::sleep(std::time::Duration::from_millis(5)),
+            }
+        };
+        saved.unwrap();
+
+        assert!(autosave.exists());
+        let restored = autosave.load().unwrap();
+        assert_eq!(restored.system.particles[0].position, Vec2::new(3.0, 4.0));
+        assert_eq!(restored.system.particles[0].species_id, 2);
+        assert_eq!(restored.config.physics.dt, 0.005);
+        assert_eq!((restored.preset.clone(), restored.sim_time), (Some(Preset::Gravity), 12.5));
+        assert_eq!(restored.age_minutes(restored.saved_at + 150), 2);
+
+        autosave.discard().unwrap();
+        assert!(!autosave.exists());
+        autosave.discard().unwrap();
+    }
+}
@@ -1,6 +1,71 @@
 use actix_files as fs;
-use actix_web::{middleware, web, App, HttpResponse, HttpServer};
+use actix_web::{middleware, web, App, HttpRequest, HttpResponse, HttpServer};
+use actix_ws::Message;
+use inochi::checkpoint::HeadlessRun;
+use inochi::community::{CommunityStore, EntryKind, Submission, UploadLimiter, MAX_CONTENT_BYTES, UPLOAD_INTERVAL_SECONDS};
+use inochi::config::{ConfigManager, Preset};
+use inochi::multiplayer::{ClientId, ClientMessage, SharedSimulation};
 use std::env;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+// The collaborative sandbox every connected browser shares
+struct Multiplayer {
+    simulation: Mutex<SharedSimulation>,
+    // Serialized deltas, fanned out to every socket
+    deltas: broadcast::Sender<String>,
+    next_client: AtomicU64,
+}
+
+impl Multiplayer {
+    fn snapshot(&self) -> String {
+        serde_json::to_string(&self.simulation.lock().unwrap().snapshot()).unwrap_or_default()
+    }
+}
+
+// Rule codes and presets shared by players, browsed from the app's Community window
+struct Community {
+    store: Mutex<CommunityStore>,
+    uploads: Mutex<UploadLimiter>,
+}
+
+#[derive(serde::Deserialize)]
+struct CommunityQuery {
+    kind: Option<EntryKind>,
+    limit: Option<usize>,
+}
+
+async fn list_community(query: web::Query<CommunityQuery>, community: web::Data<Community>) -> HttpResponse {
+    let limit = query.limit.unwrap_or(100).min(500);
+    HttpResponse::Ok().json(community.store.lock().unwrap().list(query.kind, limit))
+}
+
+async fn get_community(id: web::Path<u64>, community: web::Data<Community>) -> HttpResponse {
+    match community.store.lock().unwrap().get(id.into_inner()) {
+        Some(entry) => HttpResponse::Ok().json(entry),
+        None => HttpResponse::NotFound().body("No such entry"),
+    }
+}
+
+async fn submit_community(req: HttpRequest, submission: web::Json<Submission>, community: web::Data<Community>) -> HttpResponse {
+    let created = SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or_default();
+    let client = req.peer_addr().map(|address| address.ip().to_string()).unwrap_or_default();
+    if !community.uploads.lock().unwrap().allow(&client, created) {
+        return HttpResponse::TooManyRequests().body(format!("Please wait {} seconds between uploads", UPLOAD_INTERVAL_SECONDS));
+    }
+    // The store is only locked to add the entry; its file is written on the blocking pool
+    let write = match community.store.lock().unwrap().submit(submission.into_inner(), created) {
+        Ok(write) => write,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+    match web::block(move || write.write()).await {
+        Ok(Ok(id)) => HttpResponse::Created().json(serde_json::json!({ "id": id })),
+        Ok(Err(e)) => HttpResponse::InternalServerError().body(e),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+    }
+}
 
 async fn index() -> HttpResponse {
     HttpResponse::Ok()
@@ -8,6 +73,68 @@ async fn index() -> HttpResponse {
         .body(include_str!("../../www/index.html"))
 }
 
+async fn ws(req: HttpRequest, body: web::Payload, multiplayer: web::Data<Multiplayer>) -> Result<HttpResponse, actix_web::Error> {
+    let (response, mut session, mut messages) = actix_ws::handle(&req, body)?;
+    let client: ClientId = multiplayer.next_client.fetch_add(1, Ordering::Relaxed);
+
+    // Subscribe under the lock so the first delta received comes after the snapshot
+    let (snapshot, mut deltas) = {
+        let simulation = multiplayer.simulation.lock().unwrap();
+        (serde_json::to_string(&simulation.snapshot()).unwrap_or_default(), multiplayer.deltas.subscribe())
+    };
+
+    actix_web::rt::spawn(async move {
+        let mut outgoing = Some(snapshot);
+        loop {
+            if let Some(text) = outgoing.take() {
+                if session.text(text).await.is_err() {
+                    break;
+                }
+            }
+            tokio::select! {
+                delta = deltas.recv() => match delta {
+                    Ok(delta) => outgoing = Some(delta),
+                    // Too slow to keep up: skip the missed deltas and resync
+                    Err(broadcast::error::RecvError::Lagged(_)) => outgoing = Some(multiplayer.snapshot()),
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                message = messages.recv() => match message {
+                    Some(Ok(Message::Text(text))) => match serde_json::from_str::<ClientMessage>(&text) {
+                        Ok(message) => multiplayer.simulation.lock().unwrap().apply(client, message),
+                        Err(e) => eprintln!("Ignoring message from client {}: {}", client, e),
+                    },
+                    Some(Ok(Message::Ping(bytes))) => {
+                        if session.pong(&bytes).await.is_err() {
+                            break;
+                        }
+                    },
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {},
+                },
+            }
+        }
+
+        multiplayer.simulation.lock().unwrap().disconnect(client);
+        let _ = session.close(None).await;
+    });
+
+    Ok(response)
+}
+
+// Steps the authoritative simulation on its own thread and broadcasts what changed
+fn run_simulation(multiplayer: web::Data<Multiplayer>, tick: Duration) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(tick);
+        let mut simulation = multiplayer.simulation.lock().unwrap();
+        simulation.step(1);
+        if multiplayer.deltas.receiver_count() > 0 {
+            if let Ok(delta) = serde_json::to_string(&simulation.delta()) {
+                let _ = multiplayer.deltas.send(delta);
+            }
+        }
+    });
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
@@ -27,8 +154,42 @@ async fn main() -> std::io::Result<()> {
     println!("  HOST={} (default: 0.0.0.0)", host);
     println!("  PORT={} (default: 3000)", port);
 
-    HttpServer::new(|| {
+    // Shared sandbox at /multiplayer.html, simulated here and streamed over /ws
+    let preset_key = env::var("MULTIPLAYER_PRESET").unwrap_or_else(|_| "particle_life".to_string());
+    let preset = Preset::from_key(&preset_key).unwrap_or(Preset::ParticleLife);
+    let tick_rate: u64 = env::var("TICK_RATE")
+        .ok()
+        .and_then(|rate| rate.parse().ok())
+        .unwrap_or(30);
+    println!("  MULTIPLAYER_PRESET={} (default: particle_life)", preset_key);
+    println!("  TICK_RATE={} (default: 30)", tick_rate);
+
+    let mut config = ConfigManager::new();
+    config.apply_preset(preset.clone());
+    let multiplayer = web::Data::new(Multiplayer {
+        simulation: Mutex::new(SharedSimulation::new(HeadlessRun::new(preset, config.config().clone(), rand::random()))),
+        deltas: broadcast::channel(16).0,
+        next_client: AtomicU64::new(0),
+    });
+    run_simulation(multiplayer.clone(), Duration::from_millis(1000 / tick_rate.max(1)));
+
+    // Shared rule codes and presets under /api/community
+    let store_path = env::var("COMMUNITY_STORE").unwrap_or_else(|_| "community".to_string());
+    println!("  COMMUNITY_STORE={} (default: community)", store_path);
+    let store = CommunityStore::open(&store_path).unwrap_or_else(|e| {
+        eprintln!("{}; community uploads will not be saved", e);
+        CommunityStore::in_memory()
+    });
+    let community = web::Data::new(Community {
+        store: Mutex::new(store),
+        uploads: Mutex::new(UploadLimiter::new(UPLOAD_INTERVAL_SECONDS)),
+    });
+
+    HttpServer::new(move || {
         App::new()
+            .app_data(multiplayer.clone())
+            .app_data(community.clone())
+            .app_data(web::JsonConfig::default().limit(MAX_CONTENT_BYTES + 4096))
             .wrap(middleware::Logger::default())
             .wrap(
                 middleware::DefaultHeaders::new()
@@ -36,6 +197,10 @@ async fn main() -> std::io::Result<()> {
                     .add(("Cross-Origin-Opener-Policy", "same-origin")),
             )
             .route("/", web::get().to(index))
+            .route("/ws", web::get().to(ws))
+            .route("/api/community", web::get().to(list_community))
+            .route("/api/community", web::post().to(submit_community))
+            .route("/api/community/{id}", web::get().to(get_community))
             .service(fs::Files::new("/", "./www").index_file("index.html"))
     })
     .bind((host.as_str(), port))?
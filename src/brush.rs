@@ -0,0 +1,222 @@
+use glam::Vec2;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+use crate::stamp::StampOptions;
+
+// Densities are given in particles per 100x100 world units
+const DENSITY_AREA: f32 = 100.0 * 100.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BrushShape {
+    Rect { half_extents: Vec2 },
+    Circle { radius: f32 },
+    Ring { inner_radius: f32, outer_radius: f32 },
+    Spiral { radius: f32, turns: f32, arms: u32 },
+    // Regular lattice filling a rectangle
+    Grid { half_extents: Vec2 },
+    // Normally distributed around the center; `sigma` is the standard deviation
+    GaussianBlob { sigma: f32 },
+}
+
+impl BrushShape {
+    // One of each brush shape sized to roughly `size` world units across
+    pub fn all(size: f32) -> Vec<BrushShape> {
+        let radius = size * 0.5;
+        vec![
+            BrushShape::Circle { radius },
+            BrushShape::Ring { inner_radius: radius * 0.7, outer_radius: radius },
+            BrushShape::Spiral { radius, turns: 2.0, arms: 2 },
+            BrushShape::Grid { half_extents: Vec2::splat(radius) },
+            BrushShape::GaussianBlob { sigma: radius * 0.4 },
+            BrushShape::Rect { half_extents: Vec2::splat(radius) },
+        ]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            BrushShape::Rect { .. } => "Rectangle",
+            BrushShape::Circle { .. } => "Circle",
+            BrushShape::Ring { .. } => "Ring",
+            BrushShape::Spiral { .. } => "Spiral",
+            BrushShape::Grid { .. } => "Grid",
+            BrushShape::GaussianBlob { .. } => "Gaussian Blob",
+        }
+    }
+
+    // Area the particles are spread over; spirals count the disk they sweep
+    pub fn area(&self) -> f32 {
+        match *self {
+            BrushShape::Rect { half_extents } | BrushShape::Grid { half_extents } => {
+                4.0 * half_extents.x * half_extents.y
+            },
+            BrushShape::Circle { radius } | BrushShape::Spiral { radius, .. } => TAU * 0.5 * radius * radius,
+            BrushShape::Ring { inner_radius, outer_radius } => {
+                TAU * 0.5 * (outer_radius * outer_radius - inner_radius * inner_radius).abs()
+            },
+            // Area within one standard deviation
+            BrushShape::GaussianBlob { sigma } => TAU * 0.5 * sigma * sigma,
+        }
+    }
+}
+
+// Generates spawn positions for a brush shape placed at `center`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShapeSampler {
+    pub shape: BrushShape,
+    pub center: Vec2,
+}
+
+impl ShapeSampler {
+    pub fn new(shape: BrushShape, center: Vec2) -> Self {
+        Self { shape, center }
+    }
+
+    pub fn rect(min: Vec2, max: Vec2) -> Self {
+        Self::new(BrushShape::Rect { half_extents: (max - min) * 0.5 }, (min + max) * 0.5)
+    }
+
+    pub fn count_for_density(&self, density: f32) -> usize {
+        (self.shape.area() / DENSITY_AREA * density.max(0.0)).round() as usize
+    }
+
+    pub fn sample_density<R: Rng>(&self, density: f32, rng: &mut R) -> Vec<Vec2> {
+        self.sample(self.count_for_density(density), rng)
+    }
+
+    // Grids and spirals place points evenly; the other shapes scatter them at random
+    pub fn sample<R: Rng>(&self, count: usize, rng: &mut R) -> Vec<Vec2> {
+        match self.shape {
+            BrushShape::Grid { half_extents } => self.grid(half_extents, count),
+            BrushShape::Spiral { radius, turns, arms } => self.spiral(radius, turns, arms, count),
+            _ => (0..count).map(|_| self.sample_one(rng)).collect(),
+        }
+    }
+
+    // A single random point inside the shape; grids and spirals pick a random spot along their layout
+    pub fn sample_one<R: Rng>(&self, rng: &mut R) -> Vec2 {
+        let offset = match self.shape {
+            BrushShape::Rect { half_extents } | BrushShape::Grid { half_extents } => Vec2::new(
+                rng.gen_range(-1.0..=1.0) * half_extents.x,
+                rng.gen_range(-1.0..=1.0) * half_extents.y,
+            ),
+            BrushShape::Circle { radius } => {
+                Vec2::from_angle(rng.gen::<f32>() * TAU) * radius * rng.gen::<f32>().sqrt()
+            },
+            BrushShape::Ring { inner_radius, outer_radius } => {
+                // Uniform over the annulus area rather than over the radius
+                let inner = inner_radius * inner_radius;
+                let outer = outer_radius * outer_radius;
+                let distance = (inner + rng.gen::<f32>() * (outer - inner)).sqrt();
+                Vec2::from_angle(rng.gen::<f32>() * TAU) * distance
+            },
+            BrushShape::Spiral { radius, turns, arms } => {
+                let arm = rng.gen_range(0..arms.max(1));
+                spiral_point(radius, turns, arms, arm, rng.gen())
+            },
+            BrushShape::GaussianBlob { sigma } => {
+                // Box-Muller transform
+                let distance = sigma * (-2.0 * (1.0 - rng.gen::<f32>()).ln()).sqrt();
+                Vec2::from_angle(rng.gen::<f32>() * TAU) * distance
+            },
+        };
+        self.center + offset
+    }
+
+    fn grid(&self, half_extents: Vec2, count: usize) -> Vec<Vec2> {
+        if count == 0 {
+            return Vec::new();
+        }
+
+        // Square cells sized so the lattice holds about `count` points
+        let extent = (half_extents * 2.0).max(Vec2::splat(f32::EPSILON));
+        let cell = (extent.x * extent.y / count as f32).sqrt();
+        let columns = ((extent.x / cell).round() as usize).max(1);
+        let rows = ((extent.y / cell).round() as usize).max(1);
+        let spacing = extent / Vec2::new(columns as f32, rows as f32);
+        let origin = self.center - half_extents + spacing * 0.5;
+
+        (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| origin + Vec2::new(column as f32, row as f32) * spacing)
+            .collect()
+    }
+
+    fn spiral(&self, radius: f32, turns: f32, arms: u32, count: usize) -> Vec<Vec2> {
+        let arms = arms.max(1);
+        (0..count)
+            .map(|i| {
+                let arm = i as u32 % arms;
+                let t = (i as u32 / arms) as f32 / (count as f32 / arms as f32).ceil().max(1.0);
+                self.center + spiral_point(radius, turns, arms, arm, t)
+            })
+            .collect()
+    }
+}
+
+// Point at fraction `t` (0 = center, 1 = rim) along one arm of an Archimedean spiral
+fn spiral_point(radius: f32, turns: f32, arms: u32, arm: u32, t: f32) -> Vec2 {
+    let angle = t * turns * TAU + arm as f32 / arms.max(1) as f32 * TAU;
+    Vec2::from_angle(angle) * t * radius
+}
+
+#[derive(Debug, Clone)]
+pub struct BrushOptions {
+    pub shape: BrushShape,
+    pub density: f32,
+    pub stamp: StampOptions,
+}
+
+impl Default for BrushOptions {
+    fn default() -> Self {
+        Self {
+            shape: BrushShape::Circle { radius: 50.0 },
+            density: 20.0,
+            stamp: StampOptions::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_samples_stay_inside_shape() {
+        let mut rng = rand::thread_rng();
+        let center = Vec2::new(100.0, -50.0);
+
+        let circle = ShapeSampler::new(BrushShape::Circle { radius: 10.0 }, center);
+        assert!(circle.sample(200, &mut rng).iter().all(|p| p.distance(center) <= 10.0 + 1e-3));
+
+        let ring = ShapeSampler::new(BrushShape::Ring { inner_radius: 5.0, outer_radius: 10.0 }, center);
+        assert!(ring.sample(200, &mut rng).iter().all(|p| {
+            let distance = p.distance(center);
+            (5.0 - 1e-3..=10.0 + 1e-3).contains(&distance)
+        }));
+
+        let rect = ShapeSampler::rect(Vec2::new(-10.0, 0.0), Vec2::new(10.0, 4.0));
+        assert!(rect.sample(200, &mut rng).iter().all(|p| p.x.abs() <= 10.0 && (0.0..=4.0).contains(&p.y)));
+
+        let spiral = ShapeSampler::new(BrushShape::Spiral { radius: 20.0, turns: 3.0, arms: 3 }, center);
+        let points = spiral.sample(30, &mut rng);
+        assert_eq!(points.len(), 30);
+        assert!(points.iter().all(|p| p.distance(center) <= 20.0 + 1e-3));
+    }
+
+    #[test]
+    fn test_density_controls_count() {
+        let mut rng = rand::thread_rng();
+        let grid = ShapeSampler::new(BrushShape::Grid { half_extents: Vec2::splat(50.0) }, Vec2::ZERO);
+        assert_eq!(grid.count_for_density(25.0), 25);
+
+        // A 100x100 grid at 25 per 100x100 is a 5x5 lattice spaced 20 apart
+        let points = grid.sample_density(25.0, &mut rng);
+        assert_eq!(points.len(), 25);
+        assert_eq!(points[0], Vec2::new(-40.0, -40.0));
+        assert_eq!(points[1] - points[0], Vec2::new(20.0, 0.0));
+
+        let blob = ShapeSampler::new(BrushShape::GaussianBlob { sigma: 10.0 }, Vec2::ZERO);
+        assert!(blob.count_for_density(100.0) > blob.count_for_density(10.0));
+    }
+}
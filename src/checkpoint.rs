@@ -0,0 +1,286 @@
+#[cfg(feature = "checkpoint")]
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "checkpoint")]
+use std::fs::File;
+#[cfg(feature = "checkpoint")]
+use std::io::{BufReader, BufWriter};
+#[cfg(feature = "checkpoint")]
+use std::path::Path;
+use std::path::PathBuf;
+use crate::config::{Preset, SimulationConfig};
+use crate::forces::PhysicsEngine;
+use crate::particle::ParticleSystem;
+use crate::cloth::{update_cloth, Cloth};
+use crate::pheromone::{update_pheromones, PheromoneField};
+use crate::reaction::{update_reaction, ReactionField};
+use crate::presets::PresetManager;
+use crate::rng::{self, SimRng};
+
+pub const CHECKPOINT_EXTENSION: &str = "ckpt.gz";
+const CHECKPOINT_PREFIX: &str = "checkpoint-";
+const CHECKPOINT_VERSION: u32 = 2;
+
+// Everything needed to continue a headless run exactly where it stopped.
+// Stored as gzipped bincode: configs hold tuple-keyed maps and infinite lifespans that JSON can't.
+// Reading and writing the files needs the "checkpoint" feature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub version: u32,
+    pub step: u64,
+    pub rng: SimRng,
+    pub preset: Preset,
+    pub config: SimulationConfig,
+    pub system: ParticleSystem,
+    pub pheromones: Option<PheromoneField>,
+    pub reaction: Option<ReactionField>,
+    pub cloth: Option<Cloth>,
+}
+
+#[cfg(feature = "checkpoint")]
+impl Checkpoint {
+    // Written to a temporary file first so an interrupted write never replaces a good checkpoint
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let temporary = path.with_extension("tmp");
+        let file = File::create(&temporary).map_err(|e| format!("Failed to create {}: {}", temporary.display(), e))?;
+        let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+        bincode::serialize_into(&mut encoder, self).map_err(|e| format!("Failed to write checkpoint: {}", e))?;
+        encoder.finish().map_err(|e| format!("Failed to write checkpoint: {}", e))?;
+        std::fs::rename(&temporary, path).map_err(|e| format!("Failed to save {}: {}", path.display(), e))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        let checkpoint: Checkpoint = bincode::deserialize_from(GzDecoder::new(BufReader::new(file)))
+            .map_err(|e| format!("Invalid checkpoint {}: {}", path.display(), e))?;
+        if checkpoint.version != CHECKPOINT_VERSION {
+            return Err(format!("Unsupported checkpoint version {} in {}", checkpoint.version, path.display()));
+        }
+        Ok(checkpoint)
+    }
+}
+
+// A preset simulated without a window. It keeps its own copy of the simulation random stream,
+// so results don't depend on which thread steps it.
+pub struct HeadlessRun {
+    pub preset: Preset,
+    pub config: SimulationConfig,
+    pub system: ParticleSystem,
+    pub engine: PhysicsEngine,
+    pub pheromones: Option<PheromoneField>,
+    pub reaction: Option<ReactionField>,
+    pub cloth: Option<Cloth>,
+    pub step: u64,
+    rng: SimRng,
+}
+
+impl HeadlessRun {
+    pub fn new(preset: Preset, config: SimulationConfig, seed: u64) -> Self {
+        rng::seed(seed);
+        let system = PresetManager::create_particle_system_from_preset(&preset, &config);
+        let engine = PresetManager::create_physics_engine(&config);
+        Self { preset, config, system, engine, pheromones: None, reaction: None, cloth: None, step: 0, rng: rng::state() }
+    }
+
+    pub fn resume(checkpoint: Checkpoint) -> Self {
+        let engine = PresetManager::create_physics_engine(&checkpoint.config);
+        Self {
+            preset: checkpoint.preset,
+            config: checkpoint.config,
+            system: checkpoint.system,
+            engine,
+            pheromones: checkpoint.pheromones,
+            reaction: checkpoint.reaction,
+            cloth: checkpoint.cloth,
+            step: checkpoint.step,
+            rng: checkpoint.rng,
+        }
+    }
+
+    pub fn step(&mut self) {
+        self.advance(1);
+    }
+
+    pub fn advance(&mut self, steps: u64) {
+        rng::set_state(self.rng);
+        for _ in 0..steps {
+            // Derived from the step count so resumed runs see the same gusts and turbulence
+            let time = self.step as f32 * self.config.physics.dt;
+            self.engine.force_calculator.time = time;
+            self.config.agents.step(&mut self.system.particles, &self.config.species, None, &mut rng::fork());
+            update_cloth(&mut self.cloth, &self.config.cloth, &mut self.system);
+            update_pheromones(&mut self.pheromones, &self.config.pheromones, &mut self.system, self.config.physics.dt);
+            update_reaction(&mut self.reaction, &self.config.reaction, &mut self.system, self.config.physics.dt);
+            self.engine.update(&mut self.system);
+            self.system.update(self.config.physics.dt);
+            self.config.particles.bursts.update(&mut self.system, &self.config, time, time + self.config.physics.dt);
+            self.config.metabolism.feed(&mut self.system, &self.config.species, &self.config.forces.zones, None, (time, time + self.config.physics.dt), &mut rng::fork());
+            self.step += 1;
+        }
+        self.rng = rng::state();
+    }
+
+    // Rebuilds the physics from `config` after it was edited, keeping the particles in motion
+    pub fn apply_config(&mut self) {
+        self.engine = PresetManager::create_physics_engine(&self.config);
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            version: CHECKPOINT_VERSION,
+            step: self.step,
+            rng: self.rng,
+            preset: self.preset.clone(),
+            config: self.config.clone(),
+            system: self.system.clone(),
+            pheromones: self.pheromones.clone(),
+            reaction: self.reaction.clone(),
+            cloth: self.cloth.clone(),
+        }
+    }
+}
+
+// Where and how often to checkpoint, and which checkpoints to keep
+#[derive(Debug, Clone)]
+pub struct CheckpointPolicy {
+    pub dir: PathBuf,
+    // Steps between checkpoints
+    pub interval: u64,
+    // Number of most recent checkpoints kept; older ones are deleted
+    pub keep_last: usize,
+    // Checkpoints at multiples of this step count are kept as milestones regardless of age
+    pub keep_every: Option<u64>,
+}
+
+impl Default for CheckpointPolicy {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("checkpoints"),
+            interval: 1000,
+            keep_last: 3,
+            keep_every: None,
+        }
+    }
+}
+
+impl CheckpointPolicy {
+    pub fn path_for(&self, step: u64) -> PathBuf {
+        self.dir.join(format!("{}{:012}.{}", CHECKPOINT_PREFIX, step, CHECKPOINT_EXTENSION))
+    }
+
+    // Checkpoints in `dir`, oldest first
+    pub fn list(&self) -> Vec<(u64, PathBuf)> {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut checkpoints: Vec<(u64, PathBuf)> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                let step = name
+                    .strip_prefix(CHECKPOINT_PREFIX)?
+                    .strip_suffix(CHECKPOINT_EXTENSION)?
+                    .strip_suffix('.')?
+                    .parse()
+                    .ok()?;
+                Some((step, entry.path()))
+            })
+            .collect();
+        checkpoints.sort_by_key(|(step, _)| *step);
+        checkpoints
+    }
+
+    pub fn latest(&self) -> Option<PathBuf> {
+        self.list().pop().map(|(_, path)| path)
+    }
+
+    #[cfg(feature = "checkpoint")]
+    pub fn save(&self, run: &HeadlessRun) -> Result<PathBuf, String> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| format!("Failed to create {}: {}", self.dir.display(), e))?;
+        let path = self.path_for(run.step);
+        run.checkpoint().save(&path)?;
+        self.prune()?;
+        Ok(path)
+    }
+
+    pub fn prune(&self) -> Result<(), String> {
+        let checkpoints = self.list();
+        let recent = checkpoints.len().saturating_sub(self.keep_last.max(1));
+        for (step, path) in &checkpoints[..recent] {
+            let milestone = self.keep_every.is_some_and(|every| every > 0 && step % every == 0);
+            if !milestone {
+                std::fs::remove_file(path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+            }
+        }
+        Ok(())
+    }
+
+    // Steps `run` up to `total_steps`, checkpointing every `interval` steps and at the end
+    #[cfg(feature = "checkpoint")]
+    pub fn run(&self, run: &mut HeadlessRun, total_steps: u64) -> Result<(), String> {
+        self.run_with(run, total_steps, |_| {})
+    }
+
+    // Same, showing `observe` the run after every step, e.g. to publish metrics
+    #[cfg(feature = "checkpoint")]
+    pub fn run_with(&self, run: &mut HeadlessRun, total_steps: u64, mut observe: impl FnMut(&HeadlessRun)) -> Result<(), String> {
+        while run.step < total_steps {
+            run.step();
+            observe(run);
+            if run.step.is_multiple_of(self.interval.max(1)) || run.step == total_steps {
+                self.save(run)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "checkpoint"))]
+mod tests {
+    use super::*;
+    use crate::config::ConfigManager;
+
+    fn run(seed: u64) -> HeadlessRun {
+        let mut manager = ConfigManager::new();
+        manager.apply_preset(Preset::Brownian);
+        let mut config = manager.config().clone();
+        config.particles.initial_particle_count = 40;
+        HeadlessRun::new(Preset::Brownian, config, seed)
+    }
+
+    #[test]
+    fn test_resume_matches_uninterrupted_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let policy = CheckpointPolicy { dir: dir.path().to_path_buf(), interval: 10, ..CheckpointPolicy::default() };
+
+        let mut uninterrupted = run(7);
+        for _ in 0..30 {
+            uninterrupted.step();
+        }
+
+        let mut interrupted = run(7);
+        policy.run(&mut interrupted, 20).unwrap();
+        let mut resumed = HeadlessRun::resume(Checkpoint::load(&policy.latest().unwrap()).unwrap());
+        assert_eq!(resumed.step, 20);
+        policy.run(&mut resumed, 30).unwrap();
+
+        assert_eq!(resumed.system.particles, uninterrupted.system.particles);
+    }
+
+    #[test]
+    fn test_retention_keeps_recent_and_milestones() {
+        let dir = tempfile::tempdir().unwrap();
+        let policy = CheckpointPolicy {
+            dir: dir.path().to_path_buf(),
+            interval: 5,
+            keep_last: 2,
+            keep_every: Some(20),
+        };
+
+        let mut headless = run(1);
+        policy.run(&mut headless, 50).unwrap();
+
+        let steps: Vec<u64> = policy.list().into_iter().map(|(step, _)| step).collect();
+        assert_eq!(steps, vec![20, 40, 45, 50]);
+    }
+}
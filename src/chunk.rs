@@ -0,0 +1,195 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::particle::{Particle, ParticleSystem};
+
+// Grid cell of the world, counted in chunk sizes from the origin
+pub type ChunkCoord = (i32, i32);
+
+// Streaming for worlds far larger than the view: the world is cut into square chunks, and only
+// chunks near the camera (or pinned) get the full simulation. Particles in the rest are dormant,
+// either frozen or stepped every few frames with a longer time step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkConfig {
+    pub enabled: bool,
+    pub chunk_size: f32,
+    // A chunk is active while any part of it lies within this distance of the camera centre
+    pub activation_radius: f32,
+    // Dormant chunks take one step of this many frames' time every that many frames; 0 freezes them
+    pub dormant_tick_interval: u32,
+    // Chunks kept active wherever the camera is
+    #[serde(default)]
+    pub pinned: Vec<ChunkCoord>,
+    #[serde(default)]
+    pub show_chunks: bool,
+}
+
+impl Default for ChunkConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            chunk_size: 500.0,
+            activation_radius: 1000.0,
+            dormant_tick_interval: 10,
+            pinned: Vec::new(),
+            show_chunks: false,
+        }
+    }
+}
+
+impl ChunkConfig {
+    pub fn chunk_of(&self, position: Vec2) -> ChunkCoord {
+        let cell = (position / self.chunk_size.max(1.0)).floor();
+        (cell.x as i32, cell.y as i32)
+    }
+
+    // Corners of a chunk in world space
+    pub fn chunk_bounds(&self, chunk: ChunkCoord) -> (Vec2, Vec2) {
+        let size = self.chunk_size.max(1.0);
+        let min = Vec2::new(chunk.0 as f32, chunk.1 as f32) * size;
+        (min, min + Vec2::splat(size))
+    }
+
+    pub fn is_active(&self, chunk: ChunkCoord, focus: Vec2) -> bool {
+        let (min, max) = self.chunk_bounds(chunk);
+        self.pinned.contains(&chunk) || focus.clamp(min, max).distance(focus) <= self.activation_radius
+    }
+
+    // Pins the chunk, or unpins it if it already was; returns whether it is pinned now
+    pub fn toggle_pin(&mut self, chunk: ChunkCoord) -> bool {
+        match self.pinned.iter().position(|&pinned| pinned == chunk) {
+            Some(index) => {
+                self.pinned.remove(index);
+                false
+            },
+            None => {
+                self.pinned.push(chunk);
+                true
+            },
+        }
+    }
+}
+
+// Splits each physics step between active and dormant particles. Dormant particles are taken out
+// of the system for the step, so active ones neither feel them nor pay for them.
+#[derive(Debug, Clone, Default)]
+pub struct ChunkStreamer {
+    frame: u64,
+    // Chunks holding particles as of the last step, sorted, with whether each was active
+    pub chunks: Vec<(ChunkCoord, bool)>,
+    pub dormant_particles: usize,
+}
+
+impl ChunkStreamer {
+    // Runs `physics` with the step's time on the particles of active chunks, and on a dormant tick
+    // once more on the dormant ones with the longer time step. Particle order is kept, so indices
+    // held elsewhere stay valid.
+    pub fn step(
+        &mut self,
+        config: &ChunkConfig,
+        focus: Vec2,
+        system: &mut ParticleSystem,
+        dt: f32,
+        mut physics: impl FnMut(&mut ParticleSystem, f32),
+    ) {
+        self.frame += 1;
+        let mut chunks: HashMap<ChunkCoord, bool> = HashMap::new();
+        let mask: Vec<bool> = system
+            .particles
+            .iter()
+            .map(|particle| {
+                let chunk = config.chunk_of(particle.position);
+                *chunks.entry(chunk).or_insert_with(|| config.is_active(chunk, focus))
+            })
+            .collect();
+        self.chunks = chunks.into_iter().collect();
+        self.chunks.sort_unstable();
+
+        let (active, mut dormant): (Vec<Particle>, Vec<Particle>) = {
+            let mut index = 0;
+            std::mem::take(&mut system.particles).into_iter().partition(|_| {
+                index += 1;
+                mask[index - 1]
+            })
+        };
+        self.dormant_particles = dormant.len();
+
+        system.particles = active;
+        physics(system, dt);
+        let active = std::mem::take(&mut system.particles);
+
+        let interval = config.dormant_tick_interval;
+        if interval > 0 && self.frame.is_multiple_of(interval as u64) && !dormant.is_empty() {
+            // Integrator history is in units of the normal step, so dormant particles seed theirs
+            // from their velocity on each side of the long step
+            for particle in &mut dormant {
+                particle.previous_position = Vec2::NAN;
+            }
+            system.particles = dormant;
+            physics(system, dt * interval as f32);
+            dormant = std::mem::take(&mut system.particles);
+            for particle in &mut dormant {
+                particle.previous_position = Vec2::NAN;
+            }
+        }
+
+        let (mut active, mut dormant) = (active.into_iter(), dormant.into_iter());
+        system.particles = mask
+            .iter()
+            .filter_map(|&is_active| if is_active { active.next() } else { dormant.next() })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_activation_and_pins() {
+        let mut config = ChunkConfig { chunk_size: 100.0, activation_radius: 150.0, ..ChunkConfig::default() };
+        assert_eq!(config.chunk_of(Vec2::new(250.0, -10.0)), (2, -1));
+        assert_eq!(config.chunk_bounds((2, -1)), (Vec2::new(200.0, -100.0), Vec2::new(300.0, 0.0)));
+
+        assert!(config.is_active((0, 0), Vec2::new(50.0, 50.0)));
+        // Its near edge is 150 away
+        assert!(config.is_active((2, 0), Vec2::new(50.0, 50.0)));
+        assert!(!config.is_active((3, 0), Vec2::new(50.0, 50.0)));
+
+        assert!(config.toggle_pin((3, 0)));
+        assert!(config.is_active((3, 0), Vec2::new(50.0, 50.0)));
+        assert!(!config.toggle_pin((3, 0)));
+        assert!(config.pinned.is_empty());
+    }
+
+    #[test]
+    fn test_dormant_particles_freeze_or_slow_tick_in_place() {
+        let mut system = ParticleSystem::new(10);
+        for x in [0.0, 1000.0, 20.0] {
+            system.add_particle(Particle::new(Vec2::new(x, 0.0)).with_velocity(Vec2::new(1.0, 0.0)));
+        }
+        let drift = |system: &mut ParticleSystem, dt: f32| {
+            for particle in &mut system.particles {
+                particle.position += particle.velocity * dt;
+            }
+        };
+
+        let mut config = ChunkConfig { enabled: true, chunk_size: 100.0, activation_radius: 50.0, dormant_tick_interval: 0, ..ChunkConfig::default() };
+        let mut streamer = ChunkStreamer::default();
+        streamer.step(&config, Vec2::ZERO, &mut system, 1.0, drift);
+        let xs: Vec<f32> = system.particles.iter().map(|particle| particle.position.x).collect();
+        assert_eq!(xs, vec![1.0, 1000.0, 21.0]);
+        assert_eq!(streamer.dormant_particles, 1);
+        assert_eq!(streamer.chunks, vec![((0, 0), true), ((10, 0), false)]);
+
+        // Every third frame the far particle catches up on three frames at once
+        config.dormant_tick_interval = 3;
+        let mut streamer = ChunkStreamer::default();
+        for _ in 0..3 {
+            streamer.step(&config, Vec2::ZERO, &mut system, 1.0, drift);
+        }
+        assert_eq!(system.particles[1].position.x, 1003.0);
+        assert_eq!(system.particles[0].position.x, 4.0);
+        assert!(system.particles[1].previous_position.is_nan());
+    }
+}
@@ -0,0 +1,222 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use crate::particle::{Particle, ParticleSystem};
+use crate::pool::ParticleId;
+
+// A sheet of cloth: a grid of particles held together by springs. Structural links join each
+// particle to its right and lower neighbours, shear links cross every square, and the top row can
+// be pinned in place. A link stretched past `tear_stretch` times its rest length snaps for good.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClothConfig {
+    pub enabled: bool,
+    // Particles across and down the sheet
+    pub columns: usize,
+    pub rows: usize,
+    // Rest distance between neighbouring particles
+    pub spacing: f32,
+    // Spring force per unit of stretch, and damping of the stretching speed
+    pub stiffness: f32,
+    pub damping: f32,
+    pub shear: bool,
+    pub pin_top: bool,
+    // Stretch ratio at which a link tears; 0 never tears
+    pub tear_stretch: f32,
+    // Draw the links between particles
+    pub show_links: bool,
+    pub color: [f32; 4],
+}
+
+impl Default for ClothConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            columns: 30,
+            rows: 20,
+            spacing: 12.0,
+            stiffness: 1500.0,
+            damping: 8.0,
+            shear: true,
+            pin_top: true,
+            tear_stretch: 2.0,
+            show_links: true,
+            color: [0.85, 0.85, 0.95, 0.8],
+        }
+    }
+}
+
+impl ClothConfig {
+    pub fn particle_count(&self) -> usize {
+        self.columns * self.rows
+    }
+
+    // Rest positions of the grid, row by row from the top, with the top edge centred on `top`
+    pub fn positions(&self, top: Vec2) -> Vec<Vec2> {
+        let left = top.x - self.columns.saturating_sub(1) as f32 * self.spacing * 0.5;
+        (0..self.rows)
+            .flat_map(|row| (0..self.columns).map(move |column| (row, column)))
+            .map(|(row, column)| Vec2::new(left + column as f32 * self.spacing, top.y - row as f32 * self.spacing))
+            .collect()
+    }
+}
+
+// A spring between two particles, which are tracked by id so deaths elsewhere don't rewire it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Link {
+    pub a: ParticleId,
+    pub b: ParticleId,
+    pub rest_length: f32,
+}
+
+// The links and pins holding a woven sheet together
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cloth {
+    links: Vec<Link>,
+    // Pinned particles and where they are held
+    pins: Vec<(ParticleId, Vec2)>,
+}
+
+impl Cloth {
+    // Links the first `columns × rows` particles as a grid laid out by `ClothConfig::positions`,
+    // with rest lengths and pins taken from where they are now
+    pub fn weave(config: &ClothConfig, particles: &[Particle]) -> Self {
+        let (columns, rows) = (config.columns, config.rows.min(particles.len() / config.columns.max(1)));
+        let mut links = Vec::new();
+        let mut link = |a: usize, b: usize| {
+            let (a, b) = (&particles[a], &particles[b]);
+            links.push(Link { a: a.id, b: b.id, rest_length: a.position.distance(b.position) });
+        };
+        for row in 0..rows {
+            for column in 0..columns {
+                let index = row * columns + column;
+                if column + 1 < columns {
+                    link(index, index + 1);
+                }
+                if row + 1 < rows {
+                    link(index, index + columns);
+                    if config.shear && column + 1 < columns {
+                        link(index, index + columns + 1);
+                        link(index + 1, index + columns);
+                    }
+                }
+            }
+        }
+        let pins = if config.pin_top && rows > 0 {
+            particles[..columns].iter().map(|particle| (particle.id, particle.position)).collect()
+        } else {
+            Vec::new()
+        };
+        Self { links, pins }
+    }
+
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    // Pulls linked particles together with damped springs and tears overstretched links, then
+    // holds the pins in place. Returns how many links tore.
+    pub fn apply(&mut self, config: &ClothConfig, system: &mut ParticleSystem) -> usize {
+        let before = self.links.len();
+        self.links.retain(|link| {
+            let (Some(a), Some(b)) = (system.get(link.a), system.get(link.b)) else {
+                return false;
+            };
+            let offset = b.position - a.position;
+            let length = offset.length();
+            config.tear_stretch <= 0.0 || length <= link.rest_length * config.tear_stretch
+        });
+        let torn = before - self.links.len();
+
+        for link in &self.links {
+            let (Some(index_a), Some(index_b)) = (system.index_of(link.a), system.index_of(link.b)) else {
+                continue;
+            };
+            let (a, b) = (&system.particles[index_a], &system.particles[index_b]);
+            let offset = b.position - a.position;
+            let Some(direction) = offset.try_normalize() else {
+                continue;
+            };
+            let stretch = offset.length() - link.rest_length;
+            let closing = (b.velocity - a.velocity).dot(direction);
+            let force = direction * (config.stiffness * stretch + config.damping * closing);
+            system.particles[index_a].apply_force(force);
+            system.particles[index_b].apply_force(-force);
+        }
+
+        for &(id, position) in &self.pins {
+            if let Some(particle) = system.get_mut(id) {
+                particle.position = position;
+                particle.velocity = Vec2::ZERO;
+                particle.acceleration = Vec2::ZERO;
+            }
+        }
+        torn
+    }
+}
+
+// Steps the cloth for one physics step, before the forces. The sheet is woven from the current
+// particles on first use and dropped when cloth is off.
+pub fn update_cloth(cloth: &mut Option<Cloth>, config: &ClothConfig, system: &mut ParticleSystem) -> usize {
+    if !config.enabled {
+        *cloth = None;
+        return 0;
+    }
+    cloth
+        .get_or_insert_with(|| Cloth::weave(config, &system.particles))
+        .apply(config, system)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sheet(config: &ClothConfig) -> ParticleSystem {
+        let mut system = ParticleSystem::new(config.particle_count());
+        for position in config.positions(Vec2::ZERO) {
+            system.add_particle(Particle::new(position));
+        }
+        system
+    }
+
+    #[test]
+    fn test_weave_links_grid_neighbours() {
+        let config = ClothConfig { enabled: true, columns: 3, rows: 2, ..ClothConfig::default() };
+        let mut system = sheet(&config);
+        let mut cloth = Cloth::weave(&config, &system.particles);
+        // Two across in each row, three down, and two crossing each square
+        assert_eq!(cloth.links().len(), 2 * 2 + 3 + 2 * 2);
+        assert!(cloth.links().iter().all(|link| link.rest_length >= config.spacing - 1e-4));
+
+        // At rest nothing pulls, and the pinned top row stays put
+        system.particles[0].velocity = Vec2::new(5.0, 0.0);
+        assert_eq!(cloth.apply(&config, &mut system), 0);
+        assert_eq!(system.particles[5].acceleration, Vec2::ZERO);
+        assert_eq!(system.particles[0].velocity, Vec2::ZERO);
+    }
+
+    #[test]
+    fn test_links_pull_back_and_tear_when_overstretched() {
+        let config = ClothConfig { enabled: true, columns: 2, rows: 2, shear: false, ..ClothConfig::default() };
+        let mut system = sheet(&config);
+        let mut cloth = Cloth::weave(&config, &system.particles);
+
+        // Stretched a little: the bottom-left particle is pulled back up
+        system.particles[2].position.y -= 5.0;
+        assert_eq!(cloth.apply(&config, &mut system), 0);
+        assert!(system.particles[2].acceleration.y > 0.0);
+
+        // Stretched past the limit: its links to the pinned row and its neighbour tear
+        system.particles[2].position.y -= config.spacing * 3.0;
+        assert_eq!(cloth.apply(&config, &mut system), 2);
+        assert_eq!(cloth.links().len(), 2);
+
+        // The torn-off particle falling away moves the last one into its slot; the remaining
+        // links and pins stay on the same particles
+        let (pinned, corner) = (system.particles[1].id, system.particles[3].id);
+        system.remove_particle(system.particles[2].id);
+        system.particles[0].position.x -= 5.0;
+        assert_eq!(cloth.apply(&config, &mut system), 0);
+        assert_eq!(system.particles[0].position, Vec2::new(-config.spacing * 0.5, 0.0));
+        assert!(cloth.links().iter().any(|link| (link.a, link.b) == (pinned, corner)));
+        assert_eq!(system.index_of(corner), Some(2));
+    }
+}
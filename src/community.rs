@@ -0,0 +1,437 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use crate::presets::ConfigFile;
+use crate::rulecode::RuleCode;
+
+// Longest name or author accepted, in characters
+pub const MAX_NAME_LENGTH: usize = 60;
+// Largest rule code or preset accepted, so one upload can't fill the store
+pub const MAX_CONTENT_BYTES: usize = 256 * 1024;
+// Oldest entries are dropped once the store holds this many, or this much content in total
+const MAX_ENTRIES: usize = 1000;
+const MAX_STORE_BYTES: usize = 32 * 1024 * 1024;
+// Shortest time between two uploads from the same client
+pub const UPLOAD_INTERVAL_SECONDS: u64 = 30;
+const ENTRY_EXTENSION: &str = "json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    // A rule code from `RuleCode::encode`
+    RuleCode,
+    // A custom preset or config file, as JSON
+    Preset,
+}
+
+impl EntryKind {
+    pub fn all() -> Vec<EntryKind> {
+        vec![EntryKind::RuleCode, EntryKind::Preset]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EntryKind::RuleCode => "Rule Code",
+            EntryKind::Preset => "Preset",
+        }
+    }
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            EntryKind::RuleCode => "rule_code",
+            EntryKind::Preset => "preset",
+        }
+    }
+}
+
+// What a client uploads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Submission {
+    pub name: String,
+    #[serde(default)]
+    pub author: String,
+    pub kind: EntryKind,
+    pub content: String,
+}
+
+impl Submission {
+    // Rejects anything the app couldn't load back
+    pub fn validate(&self) -> Result<(), String> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err("Name is empty".to_string());
+        }
+        if name.chars().count() > MAX_NAME_LENGTH || self.author.trim().chars().count() > MAX_NAME_LENGTH {
+            return Err(format!("Names are limited to {} characters", MAX_NAME_LENGTH));
+        }
+        if self.content.len() > MAX_CONTENT_BYTES {
+            return Err(format!("Content is larger than {} KiB", MAX_CONTENT_BYTES / 1024));
+        }
+        match self.kind {
+            EntryKind::RuleCode => RuleCode::decode(&self.content).map(|_| ()),
+            EntryKind::Preset => ConfigFile::parse(&self.content).map(|_| ()),
+        }
+    }
+}
+
+// A stored upload; `created` is in seconds since the Unix epoch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommunityEntry {
+    pub id: u64,
+    pub name: String,
+    pub author: String,
+    pub kind: EntryKind,
+    pub content: String,
+    pub created: u64,
+}
+
+// An entry without its content, as listed when browsing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntrySummary {
+    pub id: u64,
+    pub name: String,
+    pub author: String,
+    pub kind: EntryKind,
+    pub created: u64,
+}
+
+impl From<&CommunityEntry> for EntrySummary {
+    fn from(entry: &CommunityEntry) -> Self {
+        Self {
+            id: entry.id,
+            name: entry.name.clone(),
+            author: entry.author.clone(),
+            kind: entry.kind,
+            created: entry.created,
+        }
+    }
+}
+
+// The server's uploads, kept in memory and also written to a directory with one JSON file per
+// entry, so an upload writes only its own file
+#[derive(Debug, Default)]
+pub struct CommunityStore {
+    dir: Option<PathBuf>,
+    entries: Vec<CommunityEntry>,
+    // Total content size of `entries`
+    bytes: usize,
+}
+
+impl CommunityStore {
+    // Opens the store in `dir`, creating the directory when it doesn't exist yet
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, String> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        let files = std::fs::read_dir(&dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+        let mut entries = Vec::new();
+        for path in files.flatten().map(|file| file.path()) {
+            if path.extension().is_some_and(|extension| extension == ENTRY_EXTENSION) {
+                let json = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+                let entry: CommunityEntry = serde_json::from_str(&json).map_err(|e| format!("Invalid community entry {}: {}", path.display(), e))?;
+                entries.push(entry);
+            }
+        }
+        entries.sort_by_key(|entry| entry.id);
+        let bytes = entries.iter().map(|entry| entry.content.len()).sum();
+        Ok(Self { dir: Some(dir), entries, bytes })
+    }
+
+    // A store that is never written to disk
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Validates an upload and adds it, dropping the oldest entries to make room. Nothing is
+    // written yet: the returned write does that, so the caller can run it without holding the
+    // store.
+    pub fn submit(&mut self, submission: Submission, created: u64) -> Result<EntryWrite, String> {
+        submission.validate()?;
+        let id = self.entries.last().map_or(1, |entry| entry.id + 1);
+        let entry = CommunityEntry {
+            id,
+            name: submission.name.trim().to_string(),
+            author: submission.author.trim().to_string(),
+            kind: submission.kind,
+            content: submission.content,
+            created,
+        };
+        self.bytes += entry.content.len();
+        self.entries.push(entry.clone());
+
+        let mut evicted = Vec::new();
+        while self.entries.len() > MAX_ENTRIES || self.bytes > MAX_STORE_BYTES {
+            let oldest = self.entries.remove(0);
+            self.bytes -= oldest.content.len();
+            evicted.push(oldest.id);
+        }
+        Ok(EntryWrite { dir: self.dir.clone(), entry, evicted })
+    }
+
+    // Newest first, optionally of one kind
+    pub fn list(&self, kind: Option<EntryKind>, limit: usize) -> Vec<EntrySummary> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| kind.is_none() || kind == Some(entry.kind))
+            .take(limit)
+            .map(EntrySummary::from)
+            .collect()
+    }
+
+    pub fn get(&self, id: u64) -> Option<&CommunityEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+}
+
+// An accepted upload waiting to be written to disk, with the files of the entries it pushed out
+#[derive(Debug)]
+pub struct EntryWrite {
+    dir: Option<PathBuf>,
+    entry: CommunityEntry,
+    evicted: Vec<u64>,
+}
+
+impl EntryWrite {
+    // Writes beside the entry's file and renames over it, so a crash never leaves half a file;
+    // returns the entry's id
+    pub fn write(self) -> Result<u64, String> {
+        let Some(dir) = &self.dir else {
+            return Ok(self.entry.id);
+        };
+        let path = entry_path(dir, self.entry.id);
+        let json = serde_json::to_string(&self.entry).map_err(|e| e.to_string())?;
+        let temp = path.with_extension("tmp");
+        std::fs::write(&temp, json)
+            .and_then(|_| std::fs::rename(&temp, &path))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        // An evicted entry's own write may not have landed yet when uploads race
+        for id in self.evicted {
+            let path = entry_path(dir, id);
+            match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(format!("Failed to remove {}: {}", path.display(), e)),
+                _ => {},
+            }
+        }
+        Ok(self.entry.id)
+    }
+}
+
+fn entry_path(dir: &Path, id: u64) -> PathBuf {
+    dir.join(format!("{}.{}", id, ENTRY_EXTENSION))
+}
+
+// Allows each client (by address) one upload per interval
+#[derive(Debug)]
+pub struct UploadLimiter {
+    interval: u64,
+    // When each client last uploaded, in seconds since the Unix epoch
+    last: HashMap<String, u64>,
+}
+
+impl UploadLimiter {
+    pub fn new(interval_seconds: u64) -> Self {
+        Self { interval: interval_seconds, last: HashMap::new() }
+    }
+
+    // Records an upload from `client` at `now` unless its last one was too recent
+    pub fn allow(&mut self, client: &str, now: u64) -> bool {
+        // Clients past their interval are forgotten, so the map only holds recent uploaders
+        let interval = self.interval;
+        self.last.retain(|_, &mut time| now < time.saturating_add(interval));
+        if self.last.contains_key(client) {
+            return false;
+        }
+        self.last.insert(client.to_string(), now);
+        true
+    }
+}
+
+// A reply from a community server
+#[cfg(feature = "desktop")]
+#[derive(Debug, Clone)]
+pub enum CommunityReply {
+    Listing(Vec<EntrySummary>),
+    Entry(CommunityEntry),
+    Shared(u64),
+    Failed(String),
+}
+
+// Talks to a community server in the background so the UI never waits on the network; replies
+// are collected with `poll`
+#[cfg(feature = "desktop")]
+pub struct CommunityClient {
+    sender: std::sync::mpsc::Sender<CommunityReply>,
+    receiver: std::sync::mpsc::Receiver<CommunityReply>,
+    pending: usize,
+}
+
+#[cfg(feature = "desktop")]
+impl Default for CommunityClient {
+    fn default() -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        Self { sender, receiver, pending: 0 }
+    }
+}
+
+#[cfg(feature = "desktop")]
+impl CommunityClient {
+    pub fn is_busy(&self) -> bool {
+        self.pending > 0
+    }
+
+    pub fn browse(&mut self, server: &str, kind: Option<EntryKind>) {
+        let mut url = format!("{}/api/community", server.trim_end_matches('/'));
+        if let Some(kind) = kind {
+            url = format!("{}?kind={}", url, kind.key());
+        }
+        self.send(url, None, |json| serde_json::from_str(&json).map(CommunityReply::Listing));
+    }
+
+    pub fn fetch(&mut self, server: &str, id: u64) {
+        let url = format!("{}/api/community/{}", server.trim_end_matches('/'), id);
+        self.send(url, None, |json| serde_json::from_str(&json).map(CommunityReply::Entry));
+    }
+
+    pub fn share(&mut self, server: &str, submission: &Submission) {
+        let url = format!("{}/api/community", server.trim_end_matches('/'));
+        let body = serde_json::to_string(submission).unwrap_or_default();
+        self.send(url, Some(body), |json| {
+            serde_json::from_str::<serde_json::Value>(&json).map(|reply| CommunityReply::Shared(reply["id"].as_u64().unwrap_or_default()))
+        });
+    }
+
+    pub fn poll(&mut self) -> Vec<CommunityReply> {
+        let replies: Vec<_> = self.receiver.try_iter().collect();
+        self.pending = self.pending.saturating_sub(replies.len());
+        replies
+    }
+
+    fn send(&mut self, url: String, body: Option<String>, parse: fn(String) -> serde_json::Result<CommunityReply>) {
+        self.pending += 1;
+        let sender = self.sender.clone();
+        let finish = move |response: Result<String, String>| {
+            let reply = response
+                .and_then(|json| parse(json).map_err(|e| format!("Unexpected reply from the server: {}", e)))
+                .unwrap_or_else(CommunityReply::Failed);
+            let _ = sender.send(reply);
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        std::thread::spawn(move || finish(request(&url, body.as_deref())));
+
+        #[cfg(all(target_arch = "wasm32", feature = "web"))]
+        wasm_bindgen_futures::spawn_local(async move { finish(fetch(&url, body.as_deref()).await) });
+
+        #[cfg(all(target_arch = "wasm32", not(feature = "web")))]
+        finish(Err(format!("Can't reach {} without the web feature", url)));
+    }
+}
+
+#[cfg(all(feature = "desktop", not(target_arch = "wasm32")))]
+fn request(url: &str, body: Option<&str>) -> Result<String, String> {
+    let response = match body {
+        Some(body) => ureq::post(url).set("Content-Type", "application/json").send_string(body),
+        None => ureq::get(url).call(),
+    };
+    match response {
+        Ok(response) => response.into_string().map_err(|e| e.to_string()),
+        // The server explains rejected uploads in the body
+        Err(ureq::Error::Status(status, response)) => Err(response.into_string().unwrap_or_else(|_| format!("Server replied {}", status))),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[cfg(all(feature = "desktop", target_arch = "wasm32", feature = "web"))]
+async fn fetch(url: &str, body: Option<&str>) -> Result<String, String> {
+    use wasm_bindgen::{JsCast, JsValue};
+    use wasm_bindgen_futures::JsFuture;
+
+    let describe = |e: JsValue| e.as_string().unwrap_or_else(|| format!("{:?}", e));
+    let init = web_sys::RequestInit::new();
+    if let Some(body) = body {
+        init.set_method("POST");
+        init.set_body(&JsValue::from_str(body));
+    }
+    let request = web_sys::Request::new_with_str_and_init(url, &init).map_err(describe)?;
+    if body.is_some() {
+        request.headers().set("Content-Type", "application/json").map_err(describe)?;
+    }
+    let window = web_sys::window().ok_or("No browser window")?;
+    let response: web_sys::Response = JsFuture::from(window.fetch_with_request(&request))
+        .await
+        .map_err(describe)?
+        .dyn_into()
+        .map_err(describe)?;
+    let text = JsFuture::from(response.text().map_err(describe)?)
+        .await
+        .map_err(describe)?
+        .as_string()
+        .unwrap_or_default();
+    if response.ok() {
+        Ok(text)
+    } else if text.is_empty() {
+        Err(format!("Server replied {}", response.status()))
+    } else {
+        Err(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigManager;
+
+    fn rule_code() -> String {
+        let mut manager = ConfigManager::new();
+        manager.randomize_particle_life(3);
+        RuleCode::from_config(manager.config()).encode()
+    }
+
+    #[test]
+    fn test_submissions_are_validated() {
+        let submission = Submission { name: "Swirls".to_string(), author: String::new(), kind: EntryKind::RuleCode, content: rule_code() };
+        assert!(submission.validate().is_ok());
+        assert!(Submission { name: "  ".to_string(), ..submission.clone() }.validate().is_err());
+        assert!(Submission { content: "inochi1:!!".to_string(), ..submission.clone() }.validate().is_err());
+        // A rule code isn't a preset
+        assert!(Submission { kind: EntryKind::Preset, ..submission }.validate().is_err());
+    }
+
+    #[test]
+    fn test_store_lists_newest_first_and_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("community");
+        let mut store = CommunityStore::open(&path).unwrap();
+        let config = serde_json::to_string(ConfigManager::new().config()).unwrap();
+        let first = store.submit(Submission { name: "Rules".to_string(), author: "ana".to_string(), kind: EntryKind::RuleCode, content: rule_code() }, 10).unwrap();
+        let second = store.submit(Submission { name: "Setup".to_string(), author: String::new(), kind: EntryKind::Preset, content: config }, 20).unwrap();
+        assert!(store.submit(Submission { name: "Broken".to_string(), author: String::new(), kind: EntryKind::Preset, content: "{".to_string() }, 30).is_err());
+        let (first, second) = (first.write().unwrap(), second.write().unwrap());
+        assert_eq!(std::fs::read_dir(&path).unwrap().count(), 2);
+
+        let reopened = CommunityStore::open(&path).unwrap();
+        assert_eq!(reopened.len(), 2);
+        let ids: Vec<u64> = reopened.list(None, 10).iter().map(|entry| entry.id).collect();
+        assert_eq!(ids, vec![second, first]);
+        assert_eq!(reopened.list(Some(EntryKind::RuleCode), 10)[0].author, "ana");
+        assert_eq!(reopened.get(second).unwrap().kind, EntryKind::Preset);
+        assert!(reopened.get(99).is_none());
+    }
+
+    #[test]
+    fn test_uploads_are_rate_limited_per_client() {
+        let mut limiter = UploadLimiter::new(30);
+        assert!(limiter.allow("10.0.0.1", 100));
+        assert!(!limiter.allow("10.0.0.1", 110));
+        assert!(limiter.allow("10.0.0.2", 110));
+        assert!(limiter.allow("10.0.0.1", 130));
+    }
+}
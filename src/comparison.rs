@@ -0,0 +1,78 @@
+use crate::forces::{IntegrationMethod, PhysicsEngine};
+use crate::particle::ParticleSystem;
+
+// A ghost copy of the simulation stepped under a second integrator, drawn over the live one so
+// the two methods can be watched drifting apart from the same starting state
+pub struct IntegratorComparison {
+    pub method: IntegrationMethod,
+    pub ghost: ParticleSystem,
+    engine: PhysicsEngine,
+}
+
+impl IntegratorComparison {
+    pub fn new(system: &ParticleSystem, live: &PhysicsEngine, method: IntegrationMethod) -> Self {
+        let mut ghost = system.clone();
+        // Never spawn, so ghost particles keep pairing by index with the live ones they started as
+        ghost.spawn_rate = 0.0;
+        let mut engine = PhysicsEngine::new(live.config.clone());
+        engine.config.integration_method = method;
+        Self { method, ghost, engine }
+    }
+
+    // Call before the live engine's step, with its config already synced, so both see the same
+    // forces and time
+    pub fn step(&mut self, live: &PhysicsEngine, frame_dt: f32) {
+        self.engine.config.clone_from(&live.config);
+        self.engine.config.integration_method = self.method;
+        self.engine.force_calculator.clone_from(&live.force_calculator);
+        self.engine.zones.clone_from(&live.zones);
+        self.engine.wells.clone_from(&live.wells);
+        self.engine.update(&mut self.ghost);
+        self.ghost.update(frame_dt);
+    }
+
+    // Root-mean-square distance between each ghost and its live particle
+    pub fn divergence(&self, live: &ParticleSystem) -> f32 {
+        let pairs = live.particles.len().min(self.ghost.particles.len());
+        if pairs == 0 {
+            return 0.0;
+        }
+        let total: f32 = live.particles
+            .iter()
+            .zip(&self.ghost.particles)
+            .map(|(a, b)| a.position.distance_squared(b.position))
+            .sum();
+        (total / pairs as f32).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forces::PhysicsConfig;
+    use crate::particle::Particle;
+    use crate::well::GravityWell;
+    use glam::Vec2;
+
+    #[test]
+    fn test_ghost_tracks_live_under_the_same_integrator() {
+        let mut system = ParticleSystem::new(10);
+        system.spawn_rate = 0.0;
+        system.add_particle(Particle::new(Vec2::new(100.0, 0.0)).with_velocity(Vec2::new(0.0, 22.0)));
+        let mut live = PhysicsEngine::new(PhysicsConfig::default());
+        live.force_calculator.interaction_matrix.default_forces.clear();
+        live.wells = vec![GravityWell::new(Vec2::ZERO)];
+
+        let mut same = IntegratorComparison::new(&system, &live, IntegrationMethod::Verlet);
+        let mut euler = IntegratorComparison::new(&system, &live, IntegrationMethod::Euler);
+        for _ in 0..120 {
+            same.step(&live, live.config.dt);
+            euler.step(&live, live.config.dt);
+            live.update(&mut system);
+            system.update(live.config.dt);
+        }
+        assert!(same.divergence(&system) < 1e-3);
+        assert!(euler.divergence(&system) > 1.0);
+        assert_eq!(euler.ghost.particle_count(), 1);
+    }
+}
@@ -1,7 +1,33 @@
 use glam::Vec2;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use crate::forces::{ForceType, PhysicsConfig, IntegrationMethod};
+use crate::forces::{ForceType, ForceKind, InteractionMatrix, DragModel, PhysicsConfig, IntegrationMethod, FastMath, normalize_key};
+use crate::audio::AudioConfig;
+use crate::osc::OscConfig;
+use crate::metrics::MetricsConfig;
+use crate::autosave::AutosaveConfig;
+use crate::output::OutputConfig;
+use crate::history::HistoryConfig;
+use crate::keymap::KeyMap;
+use crate::i18n::Language;
+use crate::layout::WindowLayouts;
+use crate::field::ForceFieldConfig;
+use crate::steering::SteeringConfig;
+use crate::zone::{Zone, ZoneEffect, ZoneShape};
+use crate::well::GravityWell;
+use crate::units::UnitConfig;
+use crate::emitter::BurstEmitter;
+use crate::agents::{AgentConfig, StateRule};
+use crate::pheromone::PheromoneConfig;
+use crate::reaction::ReactionConfig;
+use crate::metabolism::MetabolismConfig;
+use crate::cloth::ClothConfig;
+use crate::demo::DemoConfig;
+use crate::display::DisplayConfig;
+use crate::particle::Particle;
+use crate::noise::CurlNoiseVelocity;
+use crate::chunk::ChunkConfig;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationConfig {
@@ -11,6 +37,32 @@ pub struct SimulationConfig {
     pub forces: ForceConfig,
     pub ui: UiConfig,
     pub performance: PerformanceConfig,
+    #[serde(default)]
+    pub species: SpeciesRegistry,
+    #[serde(default)]
+    pub audio: AudioConfig,
+    #[serde(default)]
+    pub osc: OscConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    #[serde(default)]
+    pub autosave: AutosaveConfig,
+    #[serde(default)]
+    pub output: OutputConfig,
+    #[serde(default)]
+    pub history: HistoryConfig,
+    #[serde(default)]
+    pub units: UnitConfig,
+    #[serde(default)]
+    pub agents: AgentConfig,
+    #[serde(default)]
+    pub pheromones: PheromoneConfig,
+    #[serde(default)]
+    pub reaction: ReactionConfig,
+    #[serde(default)]
+    pub metabolism: MetabolismConfig,
+    #[serde(default)]
+    pub cloth: ClothConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,9 +89,137 @@ pub struct RenderConfig {
     pub color_by_velocity: bool,
     pub color_by_energy: bool,
     pub hdr_exposure: f32,
+    #[serde(default)]
+    pub auto_fit_camera: bool,
+    #[serde(default)]
+    pub force_field: ForceFieldConfig,
+    // Draws each species with its own shape so species don't rely on colour alone
+    #[serde(default)]
+    pub species_shapes: bool,
+    // Stretched mode: extra length per unit of speed, relative to particle size
+    #[serde(default = "default_stretch_factor")]
+    pub stretch_factor: f32,
+    // Stretched mode: cap on length / width
+    #[serde(default = "default_max_elongation")]
+    pub max_elongation: f32,
+    // Render modes drawn with additive blending, so overlapping particles brighten into a glow
+    #[serde(default)]
+    pub additive_modes: Vec<ParticleRenderMode>,
+    #[serde(default)]
+    pub background: Background,
+    // How far the backdrop scrolls with the camera: 0 stays fixed, 1 moves with the world
+    #[serde(default = "default_background_parallax")]
+    pub background_parallax: f32,
+    #[serde(default)]
+    pub age_ramp: AgeRamp,
+    // Circles around the particle under the cursor at the distances its pair rules reach
+    #[serde(default)]
+    pub show_interaction_radii: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl RenderConfig {
+    pub fn is_additive(&self) -> bool {
+        self.additive_modes.contains(&self.particle_render_mode)
+    }
+
+    pub fn set_additive(&mut self, additive: bool) {
+        let mode = self.particle_render_mode.clone();
+        self.additive_modes.retain(|m| *m != mode);
+        if additive {
+            self.additive_modes.push(mode);
+        }
+    }
+}
+
+fn default_stretch_factor() -> f32 {
+    0.02
+}
+
+fn default_max_elongation() -> f32 {
+    4.0
+}
+
+fn default_background_parallax() -> f32 {
+    0.2
+}
+
+// Backdrop drawn behind the simulation; `Solid` uses `background_color`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum Background {
+    #[default]
+    Solid,
+    VerticalGradient { top: [f32; 4], bottom: [f32; 4] },
+    RadialGradient { inner: [f32; 4], outer: [f32; 4] },
+    // Image file tiled across the screen
+    Image { path: String },
+}
+
+impl Background {
+    pub fn name(&self) -> &str {
+        match self {
+            Background::Solid => "Solid",
+            Background::VerticalGradient { .. } => "Vertical Gradient",
+            Background::RadialGradient { .. } => "Radial Gradient",
+            Background::Image { .. } => "Image",
+        }
+    }
+
+    // One of each kind, starting from `color` so switching kinds keeps the current tone
+    pub fn options(color: [f32; 4]) -> Vec<Background> {
+        let lighter = [color[0] + 0.12, color[1] + 0.1, color[2] + 0.18, color[3]].map(|c| c.min(1.0));
+        vec![
+            Background::Solid,
+            Background::VerticalGradient { top: lighter, bottom: color },
+            Background::RadialGradient { inner: lighter, outer: color },
+            Background::Image { path: String::new() },
+        ]
+    }
+}
+
+// How particles with a finite lifespan change as they age; immortal particles never do
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgeRamp {
+    // Alpha follows the share of life left
+    pub fade: bool,
+    // Blends the particle's colour toward `old_color` over its life
+    pub recolor: bool,
+    pub old_color: [f32; 4],
+    // Size multiplier reached at the end of life, from 1 at birth
+    pub end_size: f32,
+}
+
+impl Default for AgeRamp {
+    fn default() -> Self {
+        Self {
+            fade: true,
+            recolor: false,
+            old_color: [0.9, 0.3, 0.1, 1.0],
+            end_size: 1.0,
+        }
+    }
+}
+
+impl AgeRamp {
+    // `life_ratio` runs from 1 at birth to 0 at death, as in Particle::life_ratio
+    pub fn color(&self, color: [f32; 4], life_ratio: f32) -> [f32; 4] {
+        let age = 1.0 - life_ratio;
+        let mut color = if self.recolor {
+            std::array::from_fn(|i| color[i] + (self.old_color[i] - color[i]) * age)
+        } else {
+            color
+        };
+        if self.fade {
+            color[3] *= life_ratio;
+        }
+        color
+    }
+
+    pub fn size_scale(&self, life_ratio: f32) -> f32 {
+        1.0 + (self.end_size - 1.0) * (1.0 - life_ratio)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ParticleRenderMode {
     Points,
     Circles,
@@ -47,6 +227,34 @@ pub enum ParticleRenderMode {
     Metaballs,
     Lines,
     Trails,
+    // Ellipses elongated along the velocity, for a motion-blur look
+    Stretched,
+}
+
+impl ParticleRenderMode {
+    pub fn all() -> Vec<ParticleRenderMode> {
+        vec![
+            ParticleRenderMode::Points,
+            ParticleRenderMode::Circles,
+            ParticleRenderMode::Sprites,
+            ParticleRenderMode::Metaballs,
+            ParticleRenderMode::Lines,
+            ParticleRenderMode::Trails,
+            ParticleRenderMode::Stretched,
+        ]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            ParticleRenderMode::Points => "Points",
+            ParticleRenderMode::Circles => "Circles",
+            ParticleRenderMode::Sprites => "Sprites",
+            ParticleRenderMode::Metaballs => "Metaballs",
+            ParticleRenderMode::Lines => "Lines",
+            ParticleRenderMode::Trails => "Trails",
+            ParticleRenderMode::Stretched => "Motion Stretch",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,17 +265,60 @@ pub struct ParticleConfig {
     pub default_mass: f32,
     pub default_charge: f32,
     pub default_size: f32,
+    #[serde(default = "infinite_lifespan", with = "lifespan")]
     pub default_lifespan: f32,
+    // Each particle's lifespan is varied by up to ± this fraction of default_lifespan
+    #[serde(default)]
+    pub lifespan_variation: f32,
     pub default_color: [f32; 4],
     pub spawn_area: SpawnArea,
     pub initial_velocity_range: (Vec2, Vec2),
+    #[serde(default)]
+    pub initial_velocity: InitialVelocity,
     pub mass_variation: f32,
     pub size_variation: f32,
     pub color_variation: f32,
+    #[serde(default)]
+    pub charge_variation: f32,
     pub species_weights: HashMap<u32, f32>,
+    #[serde(default)]
+    pub species_charges: HashMap<u32, f32>,
+    // Physics steps run out of sight when a preset is (re)built, so it opens already developed
+    #[serde(default)]
+    pub warm_up_steps: usize,
+    #[serde(default)]
+    pub bursts: BurstEmitter,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// How the particles a preset starts with get their velocities
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum InitialVelocity {
+    // Uniform in the initial_velocity_range box
+    #[default]
+    Uniform,
+    // From a curl-noise flow, so the particles start out swirling together
+    CurlNoise(CurlNoiseVelocity),
+}
+
+impl InitialVelocity {
+    pub fn name(&self) -> &str {
+        match self {
+            InitialVelocity::Uniform => "Uniform",
+            InitialVelocity::CurlNoise(_) => "Curl Noise",
+        }
+    }
+
+    // Where in the noise a batch of particles samples from; Uniform leaves the rng untouched so
+    // seeded runs spawn exactly as before
+    pub fn field_offset<R: Rng>(&self, rng: &mut R) -> Vec2 {
+        match self {
+            InitialVelocity::Uniform => Vec2::ZERO,
+            InitialVelocity::CurlNoise(_) => CurlNoiseVelocity::random_offset(rng),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SpawnArea {
     Point(Vec2),
     Circle { center: Vec2, radius: f32 },
@@ -75,9 +326,32 @@ pub enum SpawnArea {
     Ring { center: Vec2, inner_radius: f32, outer_radius: f32 },
 }
 
+impl SpawnArea {
+    // A uniformly random point in the area
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Vec2 {
+        match *self {
+            SpawnArea::Point(point) => point,
+            SpawnArea::Circle { center, radius } => {
+                let angle = rng.gen::<f32>() * std::f32::consts::TAU;
+                center + Vec2::from_angle(angle) * radius * rng.gen::<f32>().sqrt()
+            },
+            SpawnArea::Rectangle { min, max } => {
+                Vec2::new(rng.gen::<f32>() * (max.x - min.x) + min.x, rng.gen::<f32>() * (max.y - min.y) + min.y)
+            },
+            // Radius drawn so the ring is evenly covered rather than crowded at its inner edge
+            SpawnArea::Ring { center, inner_radius, outer_radius } => {
+                let angle = rng.gen::<f32>() * std::f32::consts::TAU;
+                let (inner, outer) = (inner_radius * inner_radius, outer_radius * outer_radius);
+                center + Vec2::from_angle(angle) * (inner + (outer - inner) * rng.gen::<f32>()).sqrt()
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ForceConfig {
     pub global_forces: Vec<ForceType>,
+    #[serde(with = "interaction_keys")]
     pub species_interactions: HashMap<(u32, u32), Vec<ForceType>>,
     pub enable_gravity: bool,
     pub gravity_strength: f32,
@@ -88,6 +362,24 @@ pub struct ForceConfig {
     pub enable_brownian: bool,
     pub brownian_intensity: f32,
     pub boundary_forces: BoundaryForces,
+    // User-facing strength scale per force kind, applied on top of the interaction matrix
+    #[serde(default)]
+    pub force_scales: HashMap<ForceKind, f32>,
+    // Per-species steering behaviors (wander, pursue, path following, ...)
+    #[serde(default)]
+    pub steering: SteeringConfig,
+    // Regions with their own local physics, applied in order
+    #[serde(default)]
+    pub zones: Vec<Zone>,
+    // Fixed attractors placed with the mouse
+    #[serde(default)]
+    pub wells: Vec<GravityWell>,
+    // Pair rules are one-sided: (a, b) is how a reacts to b, and may differ from (b, a)
+    #[serde(default)]
+    pub asymmetric_interactions: bool,
+    // Optional cap on the summed pair forces of a species pair, keyed like species_interactions
+    #[serde(default, with = "interaction_keys")]
+    pub interaction_max_forces: HashMap<(u32, u32), f32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -118,6 +410,106 @@ pub struct UiConfig {
     pub show_physics_controls: bool,
     pub ui_scale: f32,
     pub enable_keyboard_shortcuts: bool,
+    #[serde(default)]
+    pub morph_preset_transitions: bool,
+    #[serde(default)]
+    pub preset_transition_duration: f32,
+    #[serde(default)]
+    pub keymap: KeyMap,
+    #[serde(default)]
+    pub language: Language,
+    #[serde(default)]
+    pub theme: UiTheme,
+    #[serde(default = "default_accent_color")]
+    pub accent_color: [u8; 3],
+    #[serde(default)]
+    pub window_layouts: WindowLayouts,
+    // Base URL of the server browsed in the Community window
+    #[serde(default = "default_community_server")]
+    pub community_server: String,
+    #[serde(default)]
+    pub demo: DemoConfig,
+    #[serde(default)]
+    pub display: DisplayConfig,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum UiTheme {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl UiTheme {
+    pub fn all() -> Vec<UiTheme> {
+        vec![UiTheme::Dark, UiTheme::Light, UiTheme::HighContrast]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            UiTheme::Dark => "Dark",
+            UiTheme::Light => "Light",
+            UiTheme::HighContrast => "High Contrast",
+        }
+    }
+}
+
+fn infinite_lifespan() -> f32 {
+    f32::INFINITY
+}
+
+// JSON has no infinity, so "lives forever" is stored as null (or left out in TOML)
+pub(crate) mod lifespan {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &f32, serializer: S) -> Result<S::Ok, S::Error> {
+        Some(*value).filter(|v| v.is_finite()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+        Ok(Option::<f32>::deserialize(deserializer)?.unwrap_or(f32::INFINITY))
+    }
+}
+
+// JSON and TOML only allow string keys, so species pairs are stored as "a,b"
+mod interaction_keys {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::HashMap;
+
+    pub fn serialize<S: Serializer, V: Serialize>(map: &HashMap<(u32, u32), V>, serializer: S) -> Result<S::Ok, S::Error> {
+        map.iter()
+            .map(|((a, b), value)| (format!("{},{}", a, b), value))
+            .collect::<HashMap<_, _>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, V: Deserialize<'de>>(deserializer: D) -> Result<HashMap<(u32, u32), V>, D::Error> {
+        HashMap::<String, V>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(key, value)| {
+                let pair = key
+                    .split_once(',')
+                    .and_then(|(a, b)| Some((a.trim().parse().ok()?, b.trim().parse().ok()?)));
+                pair.map(|pair| (pair, value))
+                    .ok_or_else(|| D::Error::custom(format!("invalid species pair \"{}\", expected \"a,b\"", key)))
+            })
+            .collect()
+    }
+}
+
+fn default_accent_color() -> [u8; 3] {
+    [90, 170, 255]
+}
+
+fn default_community_server() -> String {
+    // The web build is served by the community server itself
+    if cfg!(target_arch = "wasm32") {
+        String::new()
+    } else {
+        "http://localhost:3000".to_string()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -133,6 +525,12 @@ pub struct PerformanceConfig {
     pub enable_frustum_culling: bool,
     pub max_gpu_particles: usize,
     pub enable_gpu_compute: bool,
+    // Approximate rsqrt and cutoff distances for the 1/r² forces
+    #[serde(default)]
+    pub fast_math: FastMath,
+    // Full simulation only near the camera, for worlds much larger than the view
+    #[serde(default)]
+    pub streaming: ChunkConfig,
 }
 
 impl Default for SimulationConfig {
@@ -144,6 +542,19 @@ impl Default for SimulationConfig {
             forces: ForceConfig::default(),
             ui: UiConfig::default(),
             performance: PerformanceConfig::default(),
+            species: SpeciesRegistry::default(),
+            audio: AudioConfig::default(),
+            osc: OscConfig::default(),
+            metrics: MetricsConfig::default(),
+            autosave: AutosaveConfig::default(),
+            output: OutputConfig::default(),
+            history: HistoryConfig::default(),
+            units: UnitConfig::default(),
+            agents: AgentConfig::default(),
+            pheromones: PheromoneConfig::default(),
+            reaction: ReactionConfig::default(),
+            metabolism: MetabolismConfig::default(),
+            cloth: ClothConfig::default(),
         }
     }
 }
@@ -173,6 +584,16 @@ impl Default for RenderConfig {
             color_by_velocity: false,
             color_by_energy: false,
             hdr_exposure: 1.0,
+            auto_fit_camera: true,
+            force_field: ForceFieldConfig::default(),
+            species_shapes: false,
+            stretch_factor: default_stretch_factor(),
+            max_elongation: default_max_elongation(),
+            additive_modes: Vec::new(),
+            background: Background::Solid,
+            background_parallax: default_background_parallax(),
+            age_ramp: AgeRamp::default(),
+            show_interaction_radii: false,
         }
     }
 }
@@ -187,18 +608,55 @@ impl Default for ParticleConfig {
             default_charge: 0.0,
             default_size: 2.0,
             default_lifespan: f32::INFINITY,
+            lifespan_variation: 0.0,
             default_color: [1.0, 1.0, 1.0, 1.0],
             spawn_area: SpawnArea::Circle { center: Vec2::ZERO, radius: 100.0 },
             initial_velocity_range: (Vec2::new(-10.0, -10.0), Vec2::new(10.0, 10.0)),
             mass_variation: 0.1,
             size_variation: 0.2,
             color_variation: 0.1,
+            charge_variation: 0.0,
             species_weights: {
                 let mut weights = HashMap::new();
                 weights.insert(0, 1.0);
                 weights
             },
+            species_charges: HashMap::new(),
+            warm_up_steps: 0,
+            bursts: BurstEmitter::default(),
+            initial_velocity: InitialVelocity::default(),
+        }
+    }
+}
+
+impl ParticleConfig {
+    // Starting velocity for a particle at `position`. Particles created together should share a
+    // `field_offset` (see InitialVelocity::field_offset) so they follow the same flow.
+    pub fn sample_velocity<R: Rng>(&self, position: Vec2, field_offset: Vec2, rng: &mut R) -> Vec2 {
+        match self.initial_velocity {
+            InitialVelocity::Uniform => {
+                let (min, max) = self.initial_velocity_range;
+                Vec2::new(rng.gen_range(min.x..max.x), rng.gen_range(min.y..max.y))
+            },
+            InitialVelocity::CurlNoise(field) => field.sample(position, field_offset),
+        }
+    }
+
+    // A lifespan for a new particle: default_lifespan with its variation, or forever
+    pub fn sample_lifespan<R: Rng>(&self, rng: &mut R) -> f32 {
+        if !self.default_lifespan.is_finite() {
+            return f32::INFINITY;
         }
+        let variation = (rng.gen::<f32>() * 2.0 - 1.0) * self.lifespan_variation;
+        (self.default_lifespan * (1.0 + variation)).max(0.0)
+    }
+
+    // Base charge for a species, falling back to default_charge when unmapped
+    pub fn species_charge(&self, species_id: u32) -> f32 {
+        self.species_charges
+            .get(&species_id)
+            .copied()
+            .unwrap_or(self.default_charge)
     }
 }
 
@@ -216,9 +674,70 @@ impl Default for ForceConfig {
             electromagnetic_strength: 100.0,
             enable_damping: true,
             damping_coefficient: 0.01,
-            enable_brownian: true,
+            enable_brownian: false,
             brownian_intensity: 0.1,
             boundary_forces: BoundaryForces::default(),
+            force_scales: HashMap::new(),
+            steering: SteeringConfig::default(),
+            zones: Vec::new(),
+            wells: Vec::new(),
+            asymmetric_interactions: false,
+            interaction_max_forces: HashMap::new(),
+        }
+    }
+}
+
+impl ForceConfig {
+    pub fn interaction_matrix(&self) -> InteractionMatrix {
+        let mut matrix = InteractionMatrix::from_interactions_with_mode(&self.species_interactions, self.asymmetric_interactions);
+        // In key order, so which of (a, b) and (b, a) wins a shared cap doesn't vary between runs
+        let mut caps: Vec<_> = self.interaction_max_forces.iter().collect();
+        caps.sort_unstable_by_key(|(&pair, _)| pair);
+        for (&(a, b), &max_force) in caps {
+            matrix.set_max_force(a, b, Some(max_force));
+        }
+        matrix
+    }
+
+    // Switching to one-sided rules copies each pair's rules to its other direction so both can
+    // be edited apart; switching back keeps the lower species' side of each pair
+    pub fn set_asymmetric_interactions(&mut self, asymmetric: bool) {
+        if asymmetric == self.asymmetric_interactions {
+            return;
+        }
+        self.asymmetric_interactions = asymmetric;
+        set_pairs_directed(&mut self.species_interactions, asymmetric);
+        set_pairs_directed(&mut self.interaction_max_forces, asymmetric);
+    }
+
+    pub fn force_scale(&self, kind: ForceKind) -> f32 {
+        self.force_scales.get(&kind).copied().unwrap_or(1.0)
+    }
+
+    // Global forces the engine takes straight from the config: the environmental ones, plus a
+    // Brownian heat bath when the Forces panel's Brownian Motion is on
+    pub fn environment_forces(&self) -> Vec<ForceType> {
+        let mut forces: Vec<ForceType> = self.global_forces.iter().filter(|force| force.is_environmental()).cloned().collect();
+        if self.enable_brownian && self.brownian_intensity > 0.0 {
+            forces.push(ForceType::Brownian { intensity: self.brownian_intensity });
+        }
+        forces
+    }
+}
+
+// Gives every pair of a per-pair map an entry for each direction, or folds the two back into one
+fn set_pairs_directed<V: Clone>(map: &mut HashMap<(u32, u32), V>, directed: bool) {
+    let pairs: Vec<(u32, u32)> = map.keys().copied().filter(|&(a, b)| a != b).collect();
+    for (a, b) in pairs {
+        if directed {
+            if !map.contains_key(&(b, a)) {
+                let value = map[&(a, b)].clone();
+                map.insert((b, a), value);
+            }
+        } else if a > b {
+            if let Some(value) = map.remove(&(a, b)) {
+                map.entry((b, a)).or_insert(value);
+            }
         }
     }
 }
@@ -247,6 +766,16 @@ impl Default for UiConfig {
             show_physics_controls: true,
             ui_scale: 1.0,
             enable_keyboard_shortcuts: true,
+            morph_preset_transitions: false,
+            preset_transition_duration: 3.0,
+            keymap: KeyMap::default(),
+            language: Language::default(),
+            theme: UiTheme::default(),
+            accent_color: default_accent_color(),
+            window_layouts: WindowLayouts::default(),
+            community_server: default_community_server(),
+            demo: DemoConfig::default(),
+            display: DisplayConfig::default(),
         }
     }
 }
@@ -265,10 +794,335 @@ impl Default for PerformanceConfig {
             enable_frustum_culling: true,
             max_gpu_particles: 10000,
             enable_gpu_compute: false, // Disabled by default for compatibility
+            fast_math: FastMath::default(),
+            streaming: ChunkConfig::default(),
         }
     }
 }
 
+impl SimulationConfig {
+    // Per-species charge override first, then the species registry, then the global default
+    pub fn species_charge(&self, species_id: u32) -> f32 {
+        if let Some(&charge) = self.particles.species_charges.get(&species_id) {
+            return charge;
+        }
+        
+        self.species
+            .get(species_id)
+            .map(|species| species.charge)
+            .unwrap_or(self.particles.default_charge)
+    }
+}
+
+// Species colour schemes; Okabe-Ito and Tol Bright stay distinguishable with colour-blindness
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Palette {
+    OkabeIto,
+    TolBright,
+    Neon,
+    Pastel,
+    Sunset,
+    Ocean,
+    Mono,
+}
+
+impl Palette {
+    pub fn all() -> Vec<Palette> {
+        vec![
+            Palette::OkabeIto,
+            Palette::TolBright,
+            Palette::Neon,
+            Palette::Pastel,
+            Palette::Sunset,
+            Palette::Ocean,
+            Palette::Mono,
+        ]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Palette::OkabeIto => "Okabe-Ito (color-blind safe)",
+            Palette::TolBright => "Tol Bright (color-blind safe)",
+            Palette::Neon => "Neon",
+            Palette::Pastel => "Pastel",
+            Palette::Sunset => "Sunset",
+            Palette::Ocean => "Ocean",
+            Palette::Mono => "Mono",
+        }
+    }
+
+    // Case-insensitive, ignoring separators, e.g. "okabe_ito" or "OkabeIto"
+    pub fn from_key(key: &str) -> Option<Palette> {
+        let key = normalize_key(key);
+        Palette::all()
+            .into_iter()
+            .find(|palette| normalize_key(&format!("{:?}", palette)) == key)
+    }
+
+    pub fn colors(&self) -> &'static [[f32; 4]] {
+        match self {
+            // Okabe & Ito (2008), with white in place of black for dark backgrounds
+            Palette::OkabeIto => &[
+                [0.902, 0.624, 0.0, 1.0],
+                [0.337, 0.706, 0.914, 1.0],
+                [0.0, 0.620, 0.451, 1.0],
+                [0.941, 0.894, 0.259, 1.0],
+                [0.0, 0.447, 0.698, 1.0],
+                [0.835, 0.369, 0.0, 1.0],
+                [0.800, 0.475, 0.655, 1.0],
+                [1.0, 1.0, 1.0, 1.0],
+            ],
+            // Paul Tol's bright qualitative scheme
+            Palette::TolBright => &[
+                [0.267, 0.467, 0.667, 1.0],
+                [0.933, 0.400, 0.467, 1.0],
+                [0.133, 0.533, 0.200, 1.0],
+                [0.800, 0.733, 0.267, 1.0],
+                [0.400, 0.800, 0.933, 1.0],
+                [0.667, 0.200, 0.467, 1.0],
+                [0.733, 0.733, 0.733, 1.0],
+            ],
+            Palette::Neon => &[
+                [1.0, 0.1, 0.6, 1.0],
+                [0.1, 1.0, 0.9, 1.0],
+                [0.7, 1.0, 0.1, 1.0],
+                [1.0, 0.9, 0.1, 1.0],
+                [0.6, 0.2, 1.0, 1.0],
+                [1.0, 0.4, 0.1, 1.0],
+            ],
+            Palette::Pastel => &[
+                [1.0, 0.7, 0.75, 1.0],
+                [0.7, 0.85, 1.0, 1.0],
+                [0.75, 1.0, 0.8, 1.0],
+                [1.0, 0.95, 0.7, 1.0],
+                [0.85, 0.75, 1.0, 1.0],
+                [1.0, 0.85, 0.7, 1.0],
+            ],
+            Palette::Sunset => &[
+                [1.0, 0.35, 0.2, 1.0],
+                [1.0, 0.6, 0.2, 1.0],
+                [1.0, 0.85, 0.4, 1.0],
+                [0.85, 0.25, 0.45, 1.0],
+                [0.55, 0.2, 0.5, 1.0],
+                [0.3, 0.15, 0.4, 1.0],
+            ],
+            Palette::Ocean => &[
+                [0.0, 0.45, 0.75, 1.0],
+                [0.0, 0.75, 0.8, 1.0],
+                [0.5, 0.9, 0.9, 1.0],
+                [0.1, 0.3, 0.55, 1.0],
+                [0.3, 0.6, 1.0, 1.0],
+                [0.85, 0.95, 1.0, 1.0],
+            ],
+            Palette::Mono => &[
+                [1.0, 1.0, 1.0, 1.0],
+                [0.75, 0.75, 0.75, 1.0],
+                [0.5, 0.5, 0.5, 1.0],
+                [0.9, 0.9, 0.9, 1.0],
+                [0.6, 0.6, 0.6, 1.0],
+                [0.4, 0.4, 0.4, 1.0],
+            ],
+        }
+    }
+
+    // Cycles through the palette when there are more species than colours
+    pub fn color(&self, species_id: u32) -> [f32; 4] {
+        let colors = self.colors();
+        colors[species_id as usize % colors.len()]
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeciesDefinition {
+    pub id: u32,
+    pub name: String,
+    pub color: [f32; 4],
+    pub mass: f32,
+    pub size: f32,
+    pub charge: f32,
+    pub max_count: Option<usize>,
+    // Scales the coefficient of Drag forces for this species
+    #[serde(default = "default_drag")]
+    pub drag: f32,
+}
+
+fn default_drag() -> f32 {
+    1.0
+}
+
+impl SpeciesDefinition {
+    pub fn new(id: u32, name: &str) -> Self {
+        Self {
+            id,
+            name: name.to_string(),
+            color: SpeciesRegistry::default_color(id),
+            mass: 1.0,
+            size: 2.0,
+            charge: 0.0,
+            max_count: None,
+            drag: default_drag(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeciesRegistry {
+    pub species: Vec<SpeciesDefinition>,
+}
+
+impl Default for SpeciesRegistry {
+    fn default() -> Self {
+        let names = ["Red", "Blue", "Green", "Yellow", "Magenta", "Cyan", "Orange", "Purple"];
+        
+        Self {
+            species: names
+                .iter()
+                .enumerate()
+                .map(|(id, name)| SpeciesDefinition::new(id as u32, name))
+                .collect(),
+        }
+    }
+}
+
+impl SpeciesRegistry {
+    pub fn new() -> Self {
+        Self { species: Vec::new() }
+    }
+
+    pub fn default_color(species_id: u32) -> [f32; 4] {
+        match species_id {
+            0 => [1.0, 0.3, 0.3, 1.0], // Red
+            1 => [0.3, 0.3, 1.0, 1.0], // Blue
+            2 => [0.3, 1.0, 0.3, 1.0], // Green
+            3 => [1.0, 1.0, 0.3, 1.0], // Yellow
+            4 => [1.0, 0.3, 1.0, 1.0], // Magenta
+            5 => [0.3, 1.0, 1.0, 1.0], // Cyan
+            6 => [1.0, 0.6, 0.2, 1.0], // Orange
+            7 => [0.6, 0.2, 1.0, 1.0], // Purple
+            _ => Self::generated_color(species_id),
+        }
+    }
+
+    // Golden-angle hue stepping keeps neighbouring IDs visually distinct for any species count
+    pub fn generated_color(species_id: u32) -> [f32; 4] {
+        const GOLDEN_ANGLE: f32 = 137.507_77;
+        let hue = (species_id as f32 * GOLDEN_ANGLE) % 360.0;
+        let saturation = if species_id.is_multiple_of(2) { 0.75 } else { 0.55 };
+        let [r, g, b] = hsv_to_rgb(hue, saturation, 1.0);
+        [r, g, b, 1.0]
+    }
+
+    pub fn with_count(species_count: usize) -> Self {
+        let mut registry = Self::default();
+        registry.species.truncate(species_count);
+        
+        while registry.len() < species_count {
+            let id = registry.len() as u32;
+            registry.add_species(&format!("Species {}", id));
+        }
+        
+        registry
+    }
+
+    // Drag multiplier by species ID, as used by the force calculator
+    pub fn drag_coefficients(&self) -> HashMap<u32, f32> {
+        self.species.iter().map(|s| (s.id, s.drag)).collect()
+    }
+
+    pub fn get(&self, species_id: u32) -> Option<&SpeciesDefinition> {
+        self.species.iter().find(|s| s.id == species_id)
+    }
+
+    pub fn get_mut(&mut self, species_id: u32) -> Option<&mut SpeciesDefinition> {
+        self.species.iter_mut().find(|s| s.id == species_id)
+    }
+
+    pub fn apply_palette(&mut self, palette: Palette) {
+        for species in &mut self.species {
+            species.color = palette.color(species.id);
+        }
+    }
+
+    pub fn color(&self, species_id: u32) -> [f32; 4] {
+        self.get(species_id)
+            .map(|s| s.color)
+            .unwrap_or_else(|| Self::default_color(species_id))
+    }
+
+    pub fn name(&self, species_id: u32) -> &str {
+        self.get(species_id).map(|s| s.name.as_str()).unwrap_or("Unknown")
+    }
+
+    pub fn rename(&mut self, species_id: u32, name: &str) {
+        if let Some(species) = self.get_mut(species_id) {
+            species.name = name.to_string();
+        }
+    }
+
+    pub fn add_species(&mut self, name: &str) -> u32 {
+        let id = self.species.iter().map(|s| s.id + 1).max().unwrap_or(0);
+        self.species.push(SpeciesDefinition::new(id, name));
+        id
+    }
+
+    pub fn remove_species(&mut self, species_id: u32) -> bool {
+        let len_before = self.species.len();
+        self.species.retain(|s| s.id != species_id);
+        self.species.len() != len_before
+    }
+
+    pub fn ids(&self) -> Vec<u32> {
+        self.species.iter().map(|s| s.id).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.species.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.species.is_empty()
+    }
+
+    pub fn can_spawn(&self, species_id: u32, current_count: usize) -> bool {
+        match self.get(species_id).and_then(|s| s.max_count) {
+            Some(max_count) => current_count < max_count,
+            None => true,
+        }
+    }
+
+    // A particle at rest carrying the species' color, mass, size and charge
+    pub fn particle(&self, species_id: u32, position: Vec2) -> Particle {
+        let mut particle = Particle::new(position)
+            .with_species(species_id)
+            .with_color(self.color(species_id));
+        if let Some(definition) = self.get(species_id) {
+            particle = particle
+                .with_mass(definition.mass)
+                .with_size(definition.size)
+                .with_charge(definition.charge);
+        }
+        particle
+    }
+}
+
+fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> [f32; 3] {
+    let c = value * saturation;
+    let h = hue / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let m = value - c;
+    
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    
+    [r + m, g + m, b + m]
+}
+
 pub struct ConfigManager {
     config: SimulationConfig,
     config_path: Option<String>,
@@ -332,6 +1186,34 @@ impl ConfigManager {
     }
 
     pub fn apply_preset(&mut self, preset: Preset) {
+        self.config.species = SpeciesRegistry::default();
+        self.config.forces.species_interactions.clear();
+        self.config.forces.asymmetric_interactions = false;
+        self.config.forces.interaction_max_forces.clear();
+        let physics = PhysicsConfig::default();
+        self.config.physics.newtons_third_law = true;
+        self.config.physics.spawn_ramp_time = physics.spawn_ramp_time;
+        self.config.physics.integration_method = physics.integration_method;
+        self.config.physics.enable_collisions = false;
+        self.config.physics.collision_restitution = physics.collision_restitution;
+        self.config.physics.max_force = physics.max_force;
+        self.config.particles.warm_up_steps = 0;
+        self.config.particles.spawn_rate = ParticleConfig::default().spawn_rate;
+        self.config.forces.enable_brownian = false;
+        self.config.forces.enable_damping = true;
+        self.config.particles.bursts = BurstEmitter::default();
+        self.config.agents = AgentConfig::default();
+        self.config.pheromones = PheromoneConfig::default();
+        self.config.reaction = ReactionConfig::default();
+        self.config.metabolism = MetabolismConfig::default();
+        self.config.cloth = ClothConfig::default();
+        // Food zones only feed organisms, so they go with the metabolism they belonged to
+        self.config.forces.zones.retain(|zone| !matches!(zone.effect, ZoneEffect::Food { .. }));
+        
+        let (camera_position, camera_zoom) = preset.recommended_camera();
+        self.config.rendering.camera_position = camera_position;
+        self.config.rendering.camera_zoom = camera_zoom;
+        
         match preset {
             Preset::ParticleLife => self.apply_particle_life_preset(),
             Preset::Flocking => self.apply_flocking_preset(),
@@ -339,12 +1221,25 @@ impl ConfigManager {
             Preset::Electromagnetic => self.apply_electromagnetic_preset(),
             Preset::Brownian => self.apply_brownian_preset(),
             Preset::ReactionDiffusion => self.apply_reaction_diffusion_preset(),
+            Preset::HexCrystal | Preset::SquareCrystal => self.apply_crystal_preset(),
+            Preset::Granular => self.apply_granular_preset(),
+            Preset::Fireworks => self.apply_fireworks_preset(),
+            Preset::CyclicAgents => self.apply_cyclic_agents_preset(),
+            Preset::ExcitableAgents => self.apply_excitable_agents_preset(),
+            Preset::Physarum => self.apply_physarum_preset(),
+            Preset::Metabolism => self.apply_metabolism_preset(),
+            Preset::Cloth => self.apply_cloth_preset(),
         }
     }
 
     fn apply_particle_life_preset(&mut self) {
         self.config.particles.max_particles = 500;
         self.config.particles.initial_particle_count = 300;
+        // Clusters take a few seconds to form from the uniform start
+        self.config.particles.warm_up_steps = 300;
+        // Particle Life rules are meant to be one-sided
+        self.config.physics.newtons_third_law = false;
+        self.config.forces.asymmetric_interactions = true;
         
         // Species 0: Red particles
         // Species 1: Blue particles  
@@ -391,14 +1286,56 @@ impl ConfigManager {
             (2, 2), 
             vec![ForceType::Repulsion { strength: 30.0, max_distance: 40.0 }]
         );
+        
+        // The other direction of each pair differs, so species chase and flee one another
+        // Blue-Red: flees the red that chases it
+        self.config.forces.species_interactions.insert(
+            (1, 0), 
+            vec![ForceType::Repulsion { strength: 25.0, max_distance: 60.0 }]
+        );
+        
+        // Green-Red: chases the red that avoids it
+        self.config.forces.species_interactions.insert(
+            (2, 0), 
+            vec![ForceType::Attraction { strength: 12.0, max_distance: 70.0 }]
+        );
+        
+        // Green-Blue: mild repulsion
+        self.config.forces.species_interactions.insert(
+            (2, 1), 
+            vec![ForceType::Repulsion { strength: 10.0, max_distance: 50.0 }]
+        );
 
         self.config.rendering.enable_trails = true;
         self.config.rendering.trail_length = 30;
     }
 
+    pub fn randomize_particle_life(&mut self, species_count: usize) {
+        let species_count = species_count.max(1);
+        let interactions = random_particle_life_rules(species_count, &mut rand::thread_rng());
+        self.apply_particle_life_rules(species_count, interactions);
+    }
+
+    // Particle Life with evenly weighted species and the given pair rules
+    pub fn apply_particle_life_rules(&mut self, species_count: usize, interactions: HashMap<(u32, u32), Vec<ForceType>>) {
+        let species_count = species_count.max(1);
+        
+        self.apply_preset(Preset::ParticleLife);
+        self.config.species = SpeciesRegistry::with_count(species_count);
+        
+        self.config.particles.species_weights.clear();
+        for species_id in 0..species_count as u32 {
+            self.config.particles.species_weights.insert(species_id, 1.0 / species_count as f32);
+        }
+        
+        self.config.forces.species_interactions = interactions;
+    }
+
     fn apply_flocking_preset(&mut self) {
         self.config.particles.max_particles = 200;
         self.config.particles.initial_particle_count = 150;
+        // Let the random headings settle into flocks
+        self.config.particles.warm_up_steps = 180;
         
         self.config.forces.global_forces = vec![
             ForceType::Flocking {
@@ -421,10 +1358,19 @@ impl ConfigManager {
         self.config.particles.initial_particle_count = 50;
         
         self.config.forces.global_forces = vec![
-            ForceType::Gravity { strength: 500.0, min_distance: 5.0 },
             ForceType::Damping { coefficient: 0.001 },
         ];
         
+        // Gravity acts between every pair: the sun (species 0) and the orbiting bodies (species 1)
+        let gravity = ForceType::Gravity { strength: 50.0, min_distance: 5.0 };
+        self.config.forces.species_interactions.clear();
+        self.config.forces.species_interactions.insert((0, 1), vec![gravity.clone()]);
+        self.config.forces.species_interactions.insert((1, 1), vec![gravity]);
+        
+        // Orbits are set up for full-strength gravity from the first step under Verlet
+        self.config.physics.integration_method = IntegrationMethod::Verlet;
+        self.config.physics.spawn_ramp_time = 0.0;
+        
         self.config.particles.mass_variation = 0.5;
         self.config.rendering.color_by_energy = true;
         self.config.rendering.enable_trails = true;
@@ -444,6 +1390,14 @@ impl ConfigManager {
         self.config.particles.species_weights.insert(0, 0.5); // Positive
         self.config.particles.species_weights.insert(1, 0.5); // Negative
         
+        self.config.particles.species_charges.clear();
+        self.config.particles.species_charges.insert(0, 1.0);
+        self.config.particles.species_charges.insert(1, -1.0);
+        self.config.particles.charge_variation = 0.2;
+        
+        self.config.species.rename(0, "Positive");
+        self.config.species.rename(1, "Negative");
+        
         self.config.rendering.show_force_vectors = true;
     }
 
@@ -473,6 +1427,9 @@ impl ConfigManager {
         self.config.particles.species_weights.insert(0, 0.6);
         self.config.particles.species_weights.insert(1, 0.4);
         
+        self.config.species.rename(0, "Activator");
+        self.config.species.rename(1, "Inhibitor");
+        
         // Activator-Activator: self-reinforcement
         self.config.forces.species_interactions.insert(
             (0, 0), 
@@ -495,10 +1452,274 @@ impl ConfigManager {
             ForceType::Brownian { intensity: 2.0 },
             ForceType::Damping { coefficient: 0.03 },
         ];
+        
+        // A Gray-Scott grid underneath: activators feed it and drift up the slope of its V
+        self.config.reaction = ReactionConfig {
+            enabled: true,
+            deposit: 0.5,
+            push: 10_000.0,
+            species: vec![0],
+            ..ReactionConfig::default()
+        };
+    }
+
+    // Light Lennard-Jones atoms packed at their equilibrium spacing. The Forces panel's Brownian
+    // Motion is the heat bath: around intensity 1 the lattice only jiggles, from about 5 it melts.
+    fn apply_crystal_preset(&mut self) {
+        self.config.particles.max_particles = 300;
+        self.config.particles.initial_particle_count = 196;
+        self.config.particles.spawn_rate = 0.0;
+        
+        self.config.particles.species_weights.clear();
+        self.config.particles.species_weights.insert(0, 1.0);
+        if let Some(atom) = self.config.species.get_mut(0) {
+            atom.name = "Atom".to_string();
+            atom.mass = 0.005;
+            atom.size = 8.0;
+        }
+        
+        self.config.forces.species_interactions.insert(
+            (0, 0),
+            vec![ForceType::LennardJones { epsilon: 0.005, sigma: 10.0 }]
+        );
+        self.config.forces.global_forces = Vec::new();
+        self.config.forces.enable_brownian = true;
+        self.config.forces.brownian_intensity = 0.5;
+        
+        // The lattice is built at rest, so it needs no ramp, and the app's per-frame velocity
+        // damping leaves Symplectic Euler overdamped, which keeps the heat bath from running away
+        self.config.physics.integration_method = IntegrationMethod::SymplecticEuler;
+        self.config.physics.spawn_ramp_time = 0.0;
+    }
+
+    // Grains dropped onto the floor of the bounds, held apart by stiff short-range repulsion
+    fn apply_granular_preset(&mut self) {
+        self.config.particles.max_particles = 400;
+        self.config.particles.initial_particle_count = 300;
+        self.config.particles.spawn_rate = 0.0;
+        
+        self.config.particles.species_weights.clear();
+        self.config.particles.species_weights.insert(0, 1.0);
+        if let Some(grain) = self.config.species.get_mut(0) {
+            grain.name = "Grain".to_string();
+            grain.color = [0.9, 0.75, 0.45, 1.0];
+            grain.mass = 1.0;
+            grain.size = 8.0;
+        }
+        
+        self.config.forces.species_interactions.insert(
+            (0, 0),
+            vec![ForceType::Repulsion { strength: 10_000.0, max_distance: 12.0 }]
+        );
+        // Uniform wind straight down stands in for surface gravity
+        self.config.forces.global_forces = vec![
+            ForceType::Wind { direction: Vec2::NEG_Y, strength: 1800.0, gustiness: 0.0 },
+        ];
+        
+        // A settled pile carries many grains' weight on the bottom layer
+        self.config.physics.max_force = 20_000.0;
+        self.config.physics.enable_collisions = true;
+        self.config.physics.collision_restitution = 0.2;
+        self.config.physics.integration_method = IntegrationMethod::SymplecticEuler;
+        self.config.physics.spawn_ramp_time = 0.0;
+    }
+
+    // Timed bursts of short-lived sparks that fall under gravity, slow in the air and fade out
+    fn apply_fireworks_preset(&mut self) {
+        self.config.particles.max_particles = 2000;
+        self.config.particles.initial_particle_count = 0;
+        self.config.particles.spawn_rate = 0.0;
+        
+        // The first six default species give each burst one of their colours
+        let sparks: Vec<u32> = (0..6).collect();
+        self.config.particles.species_weights = sparks.iter().map(|&id| (id, 1.0)).collect();
+        for &id in &sparks {
+            if let Some(spark) = self.config.species.get_mut(id) {
+                spark.mass = 1.0;
+                spark.size = 3.0;
+            }
+        }
+        // Sparks pass through each other; empty rule lists keep the default pair forces off too
+        for &a in &sparks {
+            for &b in sparks.iter().filter(|&&b| b >= a) {
+                self.config.forces.species_interactions.insert((a, b), Vec::new());
+            }
+        }
+        self.config.particles.bursts = BurstEmitter { enabled: true, species: sparks, ..BurstEmitter::default() };
+        
+        self.config.forces.global_forces = vec![
+            ForceType::Wind { direction: Vec2::NEG_Y, strength: 60.0, gustiness: 0.0 },
+            ForceType::Drag { model: DragModel::Linear, coefficient: 1.5, size_exponent: 0.0 },
+        ];
+        // Drag does the slowing; the per-frame velocity damping would stop sparks dead
+        self.config.forces.enable_damping = false;
+        self.config.physics.integration_method = IntegrationMethod::SymplecticEuler;
+        self.config.physics.spawn_ramp_time = 0.0;
+    }
+
+    // Rock beats scissors beats paper beats rock: an agent switches to the state that beats it once
+    // enough of those are around, and the three states chase each other in spreading domains
+    fn apply_cyclic_agents_preset(&mut self) {
+        let states = [("Rock", [0.9, 0.35, 0.3, 1.0]), ("Paper", [0.35, 0.5, 0.95, 1.0]), ("Scissors", [0.4, 0.85, 0.4, 1.0])];
+        self.apply_agent_states(&states, &[0, 1, 2]);
+        self.config.agents = AgentConfig {
+            enabled: true,
+            radius: 25.0,
+            rules: (0..3)
+                .map(|state| StateRule::new(state, (state + 1) % 3).when_neighbors((state + 1) % 3, 3).with_chance(0.2))
+                .collect(),
+        };
+    }
+
+    // Greenberg-Hastings excitable medium: resting agents fire when a neighbour fires, then need
+    // a while to recover, so rare spontaneous sparks set off rings and spirals of activity
+    fn apply_excitable_agents_preset(&mut self) {
+        let states = [("Resting", [0.2, 0.3, 0.6, 1.0]), ("Excited", [1.0, 0.9, 0.3, 1.0]), ("Refractory", [0.8, 0.25, 0.2, 1.0])];
+        self.apply_agent_states(&states, &[0]);
+        self.config.agents = AgentConfig {
+            enabled: true,
+            radius: 14.0,
+            rules: vec![
+                StateRule::new(0, 1).when_neighbors(1, 1),
+                StateRule::new(0, 1).with_chance(0.0002),
+                StateRule::new(1, 2).with_chance(0.5),
+                StateRule::new(2, 0).with_chance(0.03),
+            ],
+        };
+    }
+
+    // Shared setup for the agent presets: one species per state, all spread out on a jiggling
+    // lattice, starting in the `initial` states
+    fn apply_agent_states(&mut self, states: &[(&str, [f32; 4])], initial: &[u32]) {
+        self.config.particles.max_particles = 600;
+        self.config.particles.initial_particle_count = 500;
+        self.config.particles.spawn_rate = 0.0;
+        self.config.particles.species_weights = initial.iter().map(|&id| (id, 1.0)).collect();
+        
+        for (id, (name, color)) in states.iter().enumerate() {
+            let id = id as u32;
+            if let Some(species) = self.config.species.get_mut(id) {
+                species.name = name.to_string();
+                species.color = *color;
+                species.size = 5.0;
+            }
+            // Agents of every state keep the same spacing
+            for other in id..states.len() as u32 {
+                self.config.forces.species_interactions.insert(
+                    (id, other),
+                    vec![ForceType::Repulsion { strength: 200.0, max_distance: 12.0 }]
+                );
+            }
+        }
+        self.config.forces.global_forces = Vec::new();
+        self.config.forces.enable_brownian = true;
+        self.config.forces.brownian_intensity = 20.0;
+        self.config.physics.integration_method = IntegrationMethod::SymplecticEuler;
+        self.config.physics.spawn_ramp_time = 0.0;
+    }
+
+    // Slime mould agents that only follow each other's trails; the trails thicken into a network
+    // of veins that slowly contracts
+    fn apply_physarum_preset(&mut self) {
+        self.config.particles.max_particles = 1500;
+        self.config.particles.initial_particle_count = 1000;
+        self.config.particles.spawn_rate = 0.0;
+        self.config.particles.species_weights.clear();
+        self.config.particles.species_weights.insert(0, 1.0);
+        if let Some(slime) = self.config.species.get_mut(0) {
+            slime.name = "Slime".to_string();
+            slime.color = [1.0, 0.9, 0.35, 1.0];
+            slime.mass = 1.0;
+            slime.size = 2.0;
+        }
+        // No pair forces at all, not even the default ones: agents only meet through the field
+        self.config.forces.species_interactions.insert((0, 0), Vec::new());
+        self.config.forces.global_forces = Vec::new();
+        self.config.forces.enable_damping = false;
+        self.config.pheromones = PheromoneConfig { enabled: true, ..PheromoneConfig::default() };
+        self.config.physics.integration_method = IntegrationMethod::SymplecticEuler;
+        self.config.physics.spawn_ramp_time = 0.0;
+    }
+
+    // Foragers that sniff out food and burn energy doing it. Food keeps appearing and a meadow in
+    // the middle feeds anything resting there; foragers that find neither starve.
+    fn apply_metabolism_preset(&mut self) {
+        self.config.particles.max_particles = 800;
+        self.config.particles.initial_particle_count = 320;
+        self.config.particles.spawn_rate = 0.0;
+        self.config.particles.species_weights = HashMap::from([(0, 0.4), (1, 0.6)]);
+        if let Some(forager) = self.config.species.get_mut(0) {
+            forager.name = "Forager".to_string();
+            forager.color = [0.95, 0.5, 0.3, 1.0];
+            forager.mass = 1.0;
+            forager.size = 4.0;
+        }
+        // Heavy enough that foragers go to the food rather than the other way round
+        if let Some(food) = self.config.species.get_mut(1) {
+            food.name = "Food".to_string();
+            food.color = [0.45, 0.85, 0.35, 1.0];
+            food.mass = 50.0;
+            food.size = 2.0;
+        }
+        
+        self.config.forces.species_interactions.insert(
+            (0, 0),
+            vec![ForceType::Repulsion { strength: 60.0, max_distance: 12.0 }]
+        );
+        self.config.forces.species_interactions.insert(
+            (0, 1),
+            vec![ForceType::Attraction { strength: 40.0, max_distance: 120.0 }]
+        );
+        self.config.forces.species_interactions.insert((1, 1), Vec::new());
+        self.config.forces.global_forces = vec![
+            ForceType::Drag { model: DragModel::Linear, coefficient: 1.0, size_exponent: 0.0 },
+        ];
+        self.config.forces.enable_brownian = true;
+        self.config.forces.brownian_intensity = 30.0;
+        self.config.forces.enable_damping = false;
+        self.config.forces.zones.push(
+            Zone::new("Meadow", ZoneShape::Circle { center: Vec2::ZERO, radius: 60.0 }, ZoneEffect::Food { rate: 1.0 })
+        );
+        
+        self.config.metabolism = MetabolismConfig {
+            enabled: true,
+            food_species: vec![1],
+            food_spawn_rate: 6.0,
+            ..MetabolismConfig::default()
+        };
+        self.config.physics.integration_method = IntegrationMethod::SymplecticEuler;
+        self.config.physics.spawn_ramp_time = 0.0;
+    }
+
+    // A sheet hung from its top row in a gusty breeze. The springs are explicit, so stiffness is
+    // kept below what a 60 Hz step can integrate without the sheet shaking itself apart.
+    fn apply_cloth_preset(&mut self) {
+        self.config.cloth = ClothConfig { enabled: true, stiffness: 500.0, damping: 3.0, ..ClothConfig::default() };
+        self.config.particles.max_particles = self.config.cloth.particle_count();
+        self.config.particles.initial_particle_count = self.config.cloth.particle_count();
+        self.config.particles.spawn_rate = 0.0;
+        self.config.particles.species_weights.clear();
+        self.config.particles.species_weights.insert(0, 1.0);
+        if let Some(thread) = self.config.species.get_mut(0) {
+            thread.name = "Cloth".to_string();
+            thread.color = [0.6, 0.7, 1.0, 1.0];
+            thread.mass = 1.0;
+            thread.size = 3.0;
+        }
+        // Only the links hold the sheet together
+        self.config.forces.species_interactions.insert((0, 0), Vec::new());
+        // The breeze comes first so the Wind control adjusts it; the steady downward wind is gravity
+        self.config.forces.global_forces = vec![
+            ForceType::Wind { direction: Vec2::X, strength: 40.0, gustiness: 1.0 },
+            ForceType::Wind { direction: Vec2::NEG_Y, strength: 40.0, gustiness: 0.0 },
+        ];
+        self.config.physics.max_force = 20_000.0;
+        self.config.physics.integration_method = IntegrationMethod::SymplecticEuler;
+        self.config.physics.spawn_ramp_time = 0.0;
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Preset {
     ParticleLife,
     Flocking,
@@ -506,6 +1727,15 @@ pub enum Preset {
     Electromagnetic,
     Brownian,
     ReactionDiffusion,
+    HexCrystal,
+    SquareCrystal,
+    Granular,
+    Fireworks,
+    CyclicAgents,
+    ExcitableAgents,
+    Physarum,
+    Metabolism,
+    Cloth,
 }
 
 impl Preset {
@@ -517,6 +1747,15 @@ impl Preset {
             Preset::Electromagnetic,
             Preset::Brownian,
             Preset::ReactionDiffusion,
+            Preset::HexCrystal,
+            Preset::SquareCrystal,
+            Preset::Granular,
+            Preset::Fireworks,
+            Preset::CyclicAgents,
+            Preset::ExcitableAgents,
+            Preset::Physarum,
+            Preset::Metabolism,
+            Preset::Cloth,
         ]
     }
 
@@ -528,6 +1767,44 @@ impl Preset {
             Preset::Electromagnetic => "Electromagnetic",
             Preset::Brownian => "Brownian Motion",
             Preset::ReactionDiffusion => "Reaction-Diffusion",
+            Preset::HexCrystal => "Hexagonal Crystal",
+            Preset::SquareCrystal => "Square Crystal",
+            Preset::Granular => "Granular Pile",
+            Preset::Fireworks => "Fireworks",
+            Preset::CyclicAgents => "Rock-Paper-Scissors Agents",
+            Preset::ExcitableAgents => "Excitable Medium",
+            Preset::Physarum => "Physarum Slime Mold",
+            Preset::Metabolism => "Metabolism",
+            Preset::Cloth => "Cloth",
+        }
+    }
+
+    // Case-insensitive lookup ignoring separators, e.g. "particle_life" or "ParticleLife"
+    pub fn from_key(key: &str) -> Option<Preset> {
+        let key = normalize_key(key);
+        Preset::all()
+            .into_iter()
+            .find(|preset| normalize_key(&format!("{:?}", preset)) == key)
+    }
+
+    // Camera position and zoom used when auto-fit is disabled
+    pub fn recommended_camera(&self) -> (Vec2, f32) {
+        match self {
+            Preset::ParticleLife => (Vec2::ZERO, 1.0),
+            Preset::Flocking => (Vec2::ZERO, 0.9),
+            Preset::Gravity => (Vec2::ZERO, 0.8),
+            Preset::Electromagnetic => (Vec2::ZERO, 1.0),
+            Preset::Brownian => (Vec2::ZERO, 1.0),
+            Preset::ReactionDiffusion => (Vec2::ZERO, 1.0),
+            Preset::HexCrystal => (Vec2::ZERO, 2.5),
+            Preset::SquareCrystal => (Vec2::ZERO, 2.5),
+            Preset::Granular => (Vec2::new(0.0, -150.0), 1.0),
+            Preset::Fireworks => (Vec2::new(0.0, 50.0), 1.0),
+            Preset::CyclicAgents => (Vec2::ZERO, 1.5),
+            Preset::ExcitableAgents => (Vec2::ZERO, 1.5),
+            Preset::Physarum => (Vec2::ZERO, 1.0),
+            Preset::Metabolism => (Vec2::ZERO, 1.0),
+            Preset::Cloth => (Vec2::new(0.0, 100.0), 1.0),
         }
     }
 
@@ -539,8 +1816,38 @@ impl Preset {
             Preset::Electromagnetic => "Charged particles with electromagnetic forces",
             Preset::Brownian => "Random walk particles demonstrating Brownian motion",
             Preset::ReactionDiffusion => "Reaction-diffusion patterns with activator-inhibitor dynamics",
+            Preset::HexCrystal => "Lennard-Jones atoms in a hexagonal lattice; raise Brownian intensity to melt it",
+            Preset::SquareCrystal => "Lennard-Jones atoms on a square grid, a packing that only holds while cold",
+            Preset::Granular => "A column of grains collapsing into a heap under gravity, kept apart by collisions and short-range repulsion",
+            Preset::Fireworks => "Bursts of sparks with finite lifespans that arc under gravity, slow in the air and fade out",
+            Preset::CyclicAgents => "Agents that switch state when outnumbered by the state that beats them, growing rock-paper-scissors domains",
+            Preset::ExcitableAgents => "Agents that fire, recover and rest, passing waves of activity to their neighbours like an excitable medium",
+            Preset::Physarum => "Agents that lay and follow pheromone trails, weaving a transport network like a slime mold",
+            Preset::Metabolism => "Foragers that spend energy moving and pushing, refuel on food particles and a food zone, and starve when they run dry",
+            Preset::Cloth => "A sheet of cloth pinned along its top edge, sagging under gravity and billowing in gusty wind; overstretched links tear",
+        }
+    }
+}
+
+// One attraction or repulsion for each direction of each species pair, drawn independently, as
+// used by the Particle Life randomizer. Meant for one-sided (asymmetric) rules.
+pub fn random_particle_life_rules<R: Rng>(species_count: usize, rng: &mut R) -> HashMap<(u32, u32), Vec<ForceType>> {
+    let mut interactions = HashMap::new();
+    for species_a in 0..species_count as u32 {
+        for species_b in 0..species_count as u32 {
+            let strength: f32 = rng.gen_range(-50.0..50.0);
+            let max_distance = rng.gen_range(30.0..100.0);
+            
+            let force = if strength >= 0.0 {
+                ForceType::Attraction { strength, max_distance }
+            } else {
+                ForceType::Repulsion { strength: -strength, max_distance }
+            };
+            
+            interactions.insert((species_a, species_b), vec![force]);
         }
     }
+    interactions
 }
 
 #[cfg(test)]
@@ -558,6 +1865,33 @@ mod tests {
         assert_eq!(config.particles.max_particles, deserialized.particles.max_particles);
     }
 
+    #[test]
+    fn test_preset_config_json_round_trip() {
+        let mut manager = ConfigManager::new();
+        manager.apply_preset(Preset::ParticleLife);
+        let config = manager.config();
+        let json = serde_json::to_string(config).unwrap();
+        let deserialized: SimulationConfig = serde_json::from_str(&json).unwrap();
+        
+        assert_eq!(deserialized.forces.species_interactions.len(), config.forces.species_interactions.len());
+        assert!(deserialized.particles.default_lifespan.is_infinite());
+    }
+
+    #[test]
+    fn test_additive_blending_per_render_mode() {
+        let mut rendering = RenderConfig { particle_render_mode: ParticleRenderMode::Sprites, ..Default::default() };
+        rendering.set_additive(true);
+        rendering.set_additive(true);
+        assert!(rendering.is_additive());
+        assert_eq!(rendering.additive_modes, vec![ParticleRenderMode::Sprites]);
+
+        rendering.particle_render_mode = ParticleRenderMode::Points;
+        assert!(!rendering.is_additive());
+        rendering.particle_render_mode = ParticleRenderMode::Sprites;
+        rendering.set_additive(false);
+        assert!(!rendering.is_additive());
+    }
+
     #[test]
     fn test_config_manager_save_load() {
         let mut temp_file = NamedTempFile::new().unwrap();
@@ -579,4 +1913,103 @@ mod tests {
             assert!(manager.config().particles.max_particles > 0);
         }
     }
+
+    #[test]
+    fn test_lifespan_and_age_ramp() {
+        let mut config = ParticleConfig::default();
+        let mut rng = crate::rng::fork();
+        assert!(config.sample_lifespan(&mut rng).is_infinite());
+        config.default_lifespan = 10.0;
+        config.lifespan_variation = 0.2;
+        for _ in 0..50 {
+            let lifespan = config.sample_lifespan(&mut rng);
+            assert!((8.0..=12.0).contains(&lifespan));
+        }
+
+        let ramp = AgeRamp { recolor: true, old_color: [0.0, 0.0, 0.0, 1.0], end_size: 3.0, ..AgeRamp::default() };
+        assert_eq!(ramp.color([1.0, 1.0, 1.0, 1.0], 1.0), [1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(ramp.color([1.0, 1.0, 1.0, 1.0], 0.5), [0.5, 0.5, 0.5, 0.5]);
+        assert_eq!(ramp.size_scale(0.5), 2.0);
+        assert_eq!(AgeRamp::default().size_scale(0.0), 1.0);
+    }
+
+    #[test]
+    fn test_species_charge_fallback() {
+        let mut config = ParticleConfig { default_charge: 0.5, ..Default::default() };
+        config.species_charges.insert(1, -2.0);
+        
+        assert_eq!(config.species_charge(0), 0.5);
+        assert_eq!(config.species_charge(1), -2.0);
+    }
+
+    #[test]
+    fn test_species_registry() {
+        let mut registry = SpeciesRegistry::default();
+        assert_eq!(registry.len(), 8);
+        assert_eq!(registry.name(0), "Red");
+        
+        let id = registry.add_species("Custom");
+        assert_eq!(id, 8);
+        assert_eq!(registry.name(id), "Custom");
+        
+        registry.get_mut(id).unwrap().max_count = Some(2);
+        assert!(registry.can_spawn(id, 1));
+        assert!(!registry.can_spawn(id, 2));
+        
+        assert!(registry.remove_species(id));
+        assert!(registry.get(id).is_none());
+        assert!(!registry.remove_species(id));
+    }
+
+    #[test]
+    fn test_generated_species_colors() {
+        let registry = SpeciesRegistry::with_count(24);
+        assert_eq!(registry.len(), 24);
+        assert_eq!(registry.color(0), [1.0, 0.3, 0.3, 1.0]);
+        
+        for id in 8..24 {
+            let color = registry.color(id);
+            assert_ne!(color, [0.8, 0.8, 0.8, 1.0]);
+            assert_ne!(color, registry.color(id + 1));
+        }
+    }
+
+    #[test]
+    fn test_randomize_particle_life() {
+        let mut manager = ConfigManager::new();
+        manager.randomize_particle_life(12);
+        
+        assert_eq!(manager.config().species.len(), 12);
+        assert_eq!(manager.config().particles.species_weights.len(), 12);
+        let forces = &manager.config().forces;
+        assert!(forces.asymmetric_interactions);
+        assert_eq!(forces.species_interactions.len(), 12 * 12);
+        // Each direction is drawn on its own, so the rules aren't mirrored
+        let rule = |pair: (u32, u32)| format!("{:?}", forces.species_interactions[&pair]);
+        assert!((0..12).any(|a| (0..12).any(|b| rule((a, b)) != rule((b, a)))));
+    }
+
+    #[test]
+    fn test_one_sided_rules_migrate_both_ways() {
+        let mut forces = ForceConfig::default();
+        let attract = ForceType::Attraction { strength: 10.0, max_distance: 50.0 };
+        forces.species_interactions.insert((0, 1), vec![attract.clone()]);
+        forces.species_interactions.insert((1, 1), vec![attract]);
+        forces.interaction_max_forces.insert((0, 1), 20.0);
+
+        forces.set_asymmetric_interactions(true);
+        assert_eq!(forces.species_interactions.len(), 3);
+        assert_eq!(forces.interaction_max_forces.get(&(1, 0)), Some(&20.0));
+        forces.interaction_max_forces.insert((1, 0), 5.0);
+        assert_eq!(forces.interaction_matrix().max_force(1, 0), Some(5.0));
+        forces.species_interactions.insert((1, 0), vec![ForceType::Repulsion { strength: 5.0, max_distance: 50.0 }]);
+        let matrix = forces.interaction_matrix();
+        assert!(matches!(matrix.get_forces(0, 1), [ForceType::Attraction { .. }]));
+        assert!(matches!(matrix.get_forces(1, 0), [ForceType::Repulsion { .. }]));
+
+        forces.set_asymmetric_interactions(false);
+        assert_eq!(forces.species_interactions.len(), 2);
+        assert!(matches!(forces.interaction_matrix().get_forces(1, 0), [ForceType::Attraction { .. }]));
+        assert_eq!(forces.interaction_matrix().max_force(1, 0), Some(20.0));
+    }
 }
\ No newline at end of file
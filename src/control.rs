@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use crate::config::SimulationConfig;
+use crate::forces::ForceKind;
+
+// Live-controllable parameters shared by the external control surfaces (MIDI, OSC)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ControlTarget {
+    ForceStrength(ForceKind),
+    SpawnRate,
+    TrailLength,
+}
+
+impl ControlTarget {
+    pub fn all() -> Vec<ControlTarget> {
+        let mut targets: Vec<ControlTarget> = ForceKind::all()
+            .into_iter()
+            .map(ControlTarget::ForceStrength)
+            .collect();
+        targets.push(ControlTarget::SpawnRate);
+        targets.push(ControlTarget::TrailLength);
+        targets
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            ControlTarget::ForceStrength(kind) => format!("{} Strength", kind.name()),
+            ControlTarget::SpawnRate => "Spawn Rate".to_string(),
+            ControlTarget::TrailLength => "Trail Length".to_string(),
+        }
+    }
+
+    pub fn default_range(&self) -> (f32, f32) {
+        match self {
+            ControlTarget::ForceStrength(_) => (0.0, 4.0),
+            ControlTarget::SpawnRate => (0.0, 100.0),
+            ControlTarget::TrailLength => (5.0, 200.0),
+        }
+    }
+
+    pub fn apply(&self, config: &mut SimulationConfig, value: f32) {
+        match self {
+            ControlTarget::ForceStrength(kind) => {
+                config.forces.force_scales.insert(*kind, value.max(0.0));
+            },
+            ControlTarget::SpawnRate => {
+                config.particles.spawn_rate = value.max(0.0);
+            },
+            ControlTarget::TrailLength => {
+                config.rendering.trail_length = value.round().max(1.0) as usize;
+            },
+        }
+    }
+}
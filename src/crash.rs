@@ -0,0 +1,176 @@
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const CRASH_REPORT_PREFIX: &str = "crash-";
+const REPORT_EXTENSION: &str = "txt";
+// Reports already shown to the user are renamed to this extension, so they come up only once
+const SEEN_EXTENSION: &str = "seen";
+
+// The last panic caught this session, waiting to be shown
+static LAST_NOTICE: Mutex<Option<CrashNotice>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrashReport {
+    pub message: String,
+    // file:line:column of the panic
+    pub location: String,
+    pub thread: String,
+    pub backtrace: String,
+    // Seconds since the Unix epoch
+    pub time: u64,
+}
+
+impl CrashReport {
+    // For a panic on the current thread, stamped with the current time
+    pub fn new(message: String, location: String, backtrace: String) -> Self {
+        Self {
+            message,
+            location,
+            thread: std::thread::current().name().unwrap_or("<unnamed>").to_string(),
+            backtrace,
+            time: unix_time(),
+        }
+    }
+
+    // Plain text meant to be pasted into an issue as is
+    pub fn to_text(&self) -> String {
+        format!(
+            "Inochi {} crashed ({} {})\n\nPanic on thread '{}' at {}:\n{}\n\nTime: {} (Unix)\n\nBacktrace:\n{}\n",
+            env!("CARGO_PKG_VERSION"),
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            self.thread,
+            self.location,
+            self.message,
+            self.time,
+            self.backtrace.trim_end(),
+        )
+    }
+
+    pub fn file_name(&self) -> String {
+        format!("{}{}.{}", CRASH_REPORT_PREFIX, self.time, REPORT_EXTENSION)
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<PathBuf, String> {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        let path = dir.join(self.file_name());
+        std::fs::write(&path, self.to_text()).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Ok(path)
+    }
+}
+
+// A report for the crash dialog, with the file it was saved to, if any
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrashNotice {
+    pub text: String,
+    pub path: Option<PathBuf>,
+}
+
+// Next to the autosave under the OS temp directory; the web build has no filesystem
+pub fn default_dir() -> PathBuf {
+    if cfg!(target_arch = "wasm32") {
+        PathBuf::from("crashes")
+    } else {
+        std::env::temp_dir().join("inochi").join("crashes")
+    }
+}
+
+// The newest report in `dir` not yet acknowledged. Release builds abort on panic, so a crash can
+// only be shown on the launch after it.
+pub fn unseen_report(dir: &Path) -> Option<CrashNotice> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+            name.starts_with(CRASH_REPORT_PREFIX) && path.extension().is_some_and(|extension| extension == REPORT_EXTENSION)
+        })
+        .collect();
+    paths.sort();
+    let path = paths.pop()?;
+    let text = std::fs::read_to_string(&path).ok()?;
+    Some(CrashNotice { text, path: Some(path) })
+}
+
+pub fn acknowledge(path: &Path) -> Result<(), String> {
+    std::fs::rename(path, path.with_extension(SEEN_EXTENSION)).map_err(|e| format!("Failed to update {}: {}", path.display(), e))
+}
+
+// SystemTime::now panics on wasm32-unknown-unknown, which inside the panic hook would lose the report
+fn unix_time() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        (js_sys::Date::now() / 1000.0) as u64
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs())
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "<non-string panic payload>".to_string()
+    }
+}
+
+// Replaces the panic hook with one that builds a report with a backtrace, hands it to `on_crash`
+// and keeps it for take_last_notice, then runs the previous hook (by default, printing to stderr).
+// `on_crash` returns the file it saved the report to, if any, so showing the report in this
+// session can acknowledge it.
+pub fn install(on_crash: impl Fn(&CrashReport) -> Option<PathBuf> + Send + Sync + 'static) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let location = info.location().map_or_else(|| "<unknown>".to_string(), |location| location.to_string());
+        let report = CrashReport::new(panic_message(info.payload()), location, Backtrace::force_capture().to_string());
+        let path = on_crash(&report);
+        if let Ok(mut last) = LAST_NOTICE.lock() {
+            *last = Some(CrashNotice { text: report.to_text(), path });
+        }
+        previous(info);
+    }));
+}
+
+// A panic caught since the last call, e.g. on a worker thread the app outlived
+pub fn take_last_notice() -> Option<CrashNotice> {
+    LAST_NOTICE.lock().ok()?.take()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reports_are_saved_and_shown_once() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(unseen_report(dir.path()), None);
+
+        let mut report = CrashReport::new("index out of bounds".to_string(), "src/app.rs:10:5".to_string(), "0: main".to_string());
+        report.time = 100;
+        report.save(dir.path()).unwrap();
+        report.time = 200;
+        let newest = report.save(dir.path()).unwrap();
+
+        let notice = unseen_report(dir.path()).unwrap();
+        assert_eq!(notice.path.as_deref(), Some(newest.as_path()));
+        assert!(notice.text.contains("at src/app.rs:10:5:\nindex out of bounds"));
+        assert!(notice.text.contains("Backtrace:\n0: main"));
+
+        acknowledge(&newest).unwrap();
+        assert_eq!(unseen_report(dir.path()).unwrap().path, Some(dir.path().join("crash-100.txt")));
+    }
+
+    #[test]
+    fn test_panic_messages() {
+        assert_eq!(panic_message(&"boom"), "boom");
+        assert_eq!(panic_message(&format!("boom {}", 2)), "boom 2");
+        assert_eq!(panic_message(&7), "<non-string panic payload>");
+    }
+}
@@ -0,0 +1,162 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::config::Preset;
+
+// Unattended demo mode for gallery installations and screensaver-style use: the UI is hidden,
+// presets cycle on a timer, rules are randomized now and then, and the show starts over once
+// visitors stop interacting.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DemoConfig {
+    // Presets to cycle through, in order; empty cycles every preset
+    pub presets: Vec<Preset>,
+    // Seconds each preset runs before the next
+    pub preset_duration: f32,
+    // Morph into the next preset over this many seconds; 0 cuts straight to a fresh one
+    pub transition_duration: f32,
+    // Chance that a switch morphs into random Particle Life rules instead of the next preset
+    pub randomize_chance: f32,
+    // Seconds without input after a visitor interacts before the show restarts
+    pub idle_timeout: f32,
+    pub shuffle: bool,
+}
+
+impl Default for DemoConfig {
+    fn default() -> Self {
+        Self {
+            // Presets that read well when morphed into from whatever came before
+            presets: vec![
+                Preset::ParticleLife,
+                Preset::Flocking,
+                Preset::Gravity,
+                Preset::Electromagnetic,
+                Preset::CyclicAgents,
+                Preset::Physarum,
+            ],
+            preset_duration: 45.0,
+            transition_duration: 4.0,
+            randomize_chance: 0.25,
+            idle_timeout: 30.0,
+            shuffle: false,
+        }
+    }
+}
+
+impl DemoConfig {
+    pub fn playlist(&self) -> Vec<Preset> {
+        if self.presets.is_empty() {
+            Preset::all()
+        } else {
+            self.presets.clone()
+        }
+    }
+}
+
+// What the app should switch to next
+#[derive(Debug, Clone, PartialEq)]
+pub enum DemoStep {
+    // Move on to this preset
+    Preset(Preset),
+    // Morph into random Particle Life rules
+    Randomize,
+    // Visitors left: start the show over from this preset
+    Restart(Preset),
+}
+
+// The demo's clock and position in the playlist
+#[derive(Debug, Clone, Default)]
+pub struct DemoMode {
+    elapsed: f32,
+    // Seconds since the last input, while a visitor has control
+    idle: Option<f32>,
+    next: usize,
+}
+
+impl DemoMode {
+    // Starts the show; returns the preset to open with
+    pub fn start<R: Rng>(config: &DemoConfig, rng: &mut R) -> (Self, Preset) {
+        let mut demo = Self::default();
+        let preset = demo.next_preset(config, rng);
+        (demo, preset)
+    }
+
+    // A visitor touched something: hold the current preset until they have been idle a while
+    pub fn interact(&mut self) {
+        self.idle = Some(0.0);
+    }
+
+    pub fn is_interrupted(&self) -> bool {
+        self.idle.is_some()
+    }
+
+    // Advances the timers by a frame and says when to switch
+    pub fn update<R: Rng>(&mut self, config: &DemoConfig, dt: f32, rng: &mut R) -> Option<DemoStep> {
+        if let Some(idle) = self.idle.as_mut() {
+            *idle += dt;
+            if *idle < config.idle_timeout {
+                return None;
+            }
+            self.idle = None;
+            self.elapsed = 0.0;
+            self.next = 0;
+            return Some(DemoStep::Restart(self.next_preset(config, rng)));
+        }
+
+        self.elapsed += dt;
+        if self.elapsed < config.preset_duration {
+            return None;
+        }
+        self.elapsed = 0.0;
+        if rng.gen::<f32>() < config.randomize_chance {
+            Some(DemoStep::Randomize)
+        } else {
+            Some(DemoStep::Preset(self.next_preset(config, rng)))
+        }
+    }
+
+    fn next_preset<R: Rng>(&mut self, config: &DemoConfig, rng: &mut R) -> Preset {
+        let playlist = config.playlist();
+        let index = if config.shuffle { rng.gen_range(0..playlist.len()) } else { self.next % playlist.len() };
+        self.next = index + 1;
+        playlist[index].clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_demo_cycles_presets_on_a_timer() {
+        let config = DemoConfig {
+            presets: vec![Preset::Gravity, Preset::Flocking],
+            preset_duration: 10.0,
+            randomize_chance: 0.0,
+            ..DemoConfig::default()
+        };
+        let mut rng = crate::rng::fork();
+        let (mut demo, first) = DemoMode::start(&config, &mut rng);
+        assert_eq!(first, Preset::Gravity);
+        assert_eq!(demo.update(&config, 9.0, &mut rng), None);
+        assert_eq!(demo.update(&config, 1.0, &mut rng), Some(DemoStep::Preset(Preset::Flocking)));
+        assert_eq!(demo.update(&config, 10.0, &mut rng), Some(DemoStep::Preset(Preset::Gravity)));
+
+        let always = DemoConfig { randomize_chance: 1.0, ..config };
+        assert_eq!(demo.update(&always, 10.0, &mut rng), Some(DemoStep::Randomize));
+    }
+
+    #[test]
+    fn test_interaction_holds_the_show_until_idle() {
+        let config = DemoConfig { preset_duration: 5.0, idle_timeout: 20.0, randomize_chance: 0.0, ..DemoConfig::default() };
+        let mut rng = crate::rng::fork();
+        let (mut demo, _) = DemoMode::start(&config, &mut rng);
+        demo.update(&config, 5.0, &mut rng);
+
+        demo.interact();
+        assert_eq!(demo.update(&config, 15.0, &mut rng), None);
+        demo.interact();
+        assert_eq!(demo.update(&config, 15.0, &mut rng), None);
+        assert!(demo.is_interrupted());
+        assert_eq!(demo.update(&config, 5.0, &mut rng), Some(DemoStep::Restart(config.presets[0].clone())));
+        assert!(!demo.is_interrupted());
+    }
+}
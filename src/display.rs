@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_DISPLAY_FILE: &str = "display.json";
+
+// How the main window sits on screen, for running the simulation as an ambient display
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisplayConfig {
+    pub fullscreen: bool,
+    // No title bar or window border
+    pub borderless: bool,
+    pub always_on_top: bool,
+    // Monitor to show the window on, as an index into the monitors the OS reports; None keeps
+    // whichever monitor the window is on
+    pub monitor: Option<usize>,
+    // Control panels in a window of their own, leaving the simulation window clean for capture
+    // or projection
+    #[serde(default)]
+    pub separate_controls: bool,
+}
+
+impl DisplayConfig {
+    pub fn from_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save_to_file(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    // The chosen monitor among `count` connected ones; a monitor that has since been unplugged
+    // falls back to the current one
+    pub fn monitor_index(&self, count: usize) -> Option<usize> {
+        self.monitor.filter(|&index| index < count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_config_round_trip_and_monitor_fallback() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("display.json");
+        let path = path.to_str().unwrap();
+        let display = DisplayConfig { fullscreen: true, borderless: true, always_on_top: false, monitor: Some(1), separate_controls: true };
+        display.save_to_file(path).unwrap();
+        let loaded = DisplayConfig::from_file(path).unwrap();
+        assert_eq!(loaded, display);
+
+        assert_eq!(loaded.monitor_index(2), Some(1));
+        assert_eq!(loaded.monitor_index(1), None);
+        assert_eq!(DisplayConfig::default().monitor_index(3), None);
+    }
+}
@@ -0,0 +1,111 @@
+use crate::config::{Palette, Preset, SimulationConfig};
+
+// Upper bound for `particles=`, so a shared link can't stall the visitor's browser
+const MAX_EMBED_PARTICLES: usize = 5000;
+
+// Settings read from an embedding page's URL, e.g. ?preset=Flocking&particles=800&seed=42&hideui=1&palette=neon.
+// Unknown keys and unparsable values are ignored so a typo never breaks the embed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EmbedOptions {
+    pub preset: Option<Preset>,
+    pub particles: Option<usize>,
+    pub seed: Option<u64>,
+    pub hide_ui: bool,
+    pub palette: Option<Palette>,
+}
+
+impl EmbedOptions {
+    // Accepts `location.search` with or without the leading '?'
+    pub fn from_query(query: &str) -> Self {
+        let mut options = EmbedOptions::default();
+        for pair in query.trim_start_matches('?').split('&') {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = decode(value);
+            match key.to_ascii_lowercase().as_str() {
+                "preset" => options.preset = Preset::from_key(&value),
+                "particles" => options.particles = value.parse().ok().map(|n: usize| n.min(MAX_EMBED_PARTICLES)),
+                "seed" => options.seed = value.parse().ok(),
+                "hideui" => options.hide_ui = matches!(value.as_str(), "" | "1" | "true" | "yes"),
+                "palette" => options.palette = Palette::from_key(&value),
+                _ => {},
+            }
+        }
+        options
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == EmbedOptions::default()
+    }
+
+    // Applied after the preset, since presets reset particle counts and species
+    pub fn apply(&self, config: &mut SimulationConfig) {
+        if let Some(particles) = self.particles {
+            config.particles.initial_particle_count = particles;
+            config.particles.max_particles = config.particles.max_particles.max(particles);
+        }
+        if let Some(palette) = self.palette {
+            config.species.apply_palette(palette);
+        }
+        if self.hide_ui {
+            config.ui.show_ui = false;
+        }
+    }
+}
+
+// Percent-decoding for query values; '+' is a space as in form encoding
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    },
+                    None => decoded.push(b'%'),
+                }
+            },
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigManager;
+
+    #[test]
+    fn test_query_parsing() {
+        let options = EmbedOptions::from_query("?preset=Flocking&particles=800&seed=42&hideui=1&palette=neon");
+        assert_eq!(options.preset, Some(Preset::Flocking));
+        assert_eq!(options.particles, Some(800));
+        assert_eq!(options.seed, Some(42));
+        assert!(options.hide_ui);
+        assert_eq!(options.palette, Some(Palette::Neon));
+
+        let options = EmbedOptions::from_query("preset=particle%5Flife&particles=lots&unknown=1&particles=999999");
+        assert_eq!(options.preset, Some(Preset::ParticleLife));
+        assert_eq!(options.particles, Some(MAX_EMBED_PARTICLES));
+        assert!(EmbedOptions::from_query("").is_empty());
+    }
+
+    #[test]
+    fn test_apply_overrides_preset_config() {
+        let mut manager = ConfigManager::new();
+        manager.apply_preset(Preset::ParticleLife);
+        let mut config = manager.config().clone();
+        EmbedOptions::from_query("particles=123&palette=mono&hideui").apply(&mut config);
+
+        assert_eq!(config.particles.initial_particle_count, 123);
+        assert!(!config.ui.show_ui);
+        assert!(config.species.species.iter().all(|s| s.color == Palette::Mono.color(s.id)));
+    }
+}
@@ -0,0 +1,166 @@
+use glam::Vec2;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+use crate::config::SimulationConfig;
+use crate::noise::CurlNoiseVelocity;
+use crate::particle::ParticleSystem;
+use crate::presets::PresetManager;
+
+// Fires radial bursts of short-lived particles on a fixed clock, like fireworks. Bursts are timed
+// from the simulation clock rather than a running timer, so a resumed run bursts on the same steps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BurstEmitter {
+    pub enabled: bool,
+    // Seconds between bursts
+    pub interval: f32,
+    pub particles_per_burst: usize,
+    pub speed_range: (f32, f32),
+    // Seconds each spark lives, varied by up to ± lifespan_variation of itself
+    pub lifespan: f32,
+    pub lifespan_variation: f32,
+    // Each burst takes the look of one of these species, picked at random
+    pub species: Vec<u32>,
+    // Burst centres are picked uniformly inside this rectangle
+    pub area: (Vec2, Vec2),
+    // Curl-noise flow added to each spark's outward velocity, so a burst curls into swirls
+    // instead of a clean ring
+    #[serde(default)]
+    pub swirl: Option<CurlNoiseVelocity>,
+}
+
+impl Default for BurstEmitter {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: 1.2,
+            particles_per_burst: 120,
+            speed_range: (80.0, 200.0),
+            lifespan: 3.0,
+            lifespan_variation: 0.3,
+            species: vec![0],
+            area: (Vec2::new(-250.0, 0.0), Vec2::new(250.0, 200.0)),
+            swirl: None,
+        }
+    }
+}
+
+impl BurstEmitter {
+    // Bursts due in the span (from, to] of simulated time; one fires at every multiple of the interval
+    pub fn bursts_between(&self, from: f32, to: f32) -> usize {
+        if !self.enabled || self.interval <= 0.0 || to <= from {
+            return 0;
+        }
+        ((to / self.interval).floor() - (from / self.interval).floor()).max(0.0) as usize
+    }
+
+    pub fn update(&self, system: &mut ParticleSystem, config: &SimulationConfig, from: f32, to: f32) {
+        let bursts = self.bursts_between(from, to);
+        if bursts == 0 {
+            return;
+        }
+        let mut rng = crate::rng::fork();
+        for _ in 0..bursts {
+            self.burst(system, config, &mut rng);
+        }
+    }
+
+    pub fn burst<R: Rng>(&self, system: &mut ParticleSystem, config: &SimulationConfig, rng: &mut R) {
+        if self.species.is_empty() || self.particles_per_burst == 0 {
+            return;
+        }
+        let species_id = self.species[rng.gen_range(0..self.species.len())];
+        let (min, max) = self.area;
+        let center = Vec2::new(between(rng, min.x, max.x), between(rng, min.y, max.y));
+        // Each burst reads a different patch of the noise
+        let field_offset = self.swirl.map(|_| CurlNoiseVelocity::random_offset(rng)).unwrap_or(Vec2::ZERO);
+
+        let count = self.particles_per_burst;
+        for index in 0..count {
+            // Jittered but evenly spread angles read as a ring rather than a random spray
+            let angle = (index as f32 + rng.gen::<f32>()) / count as f32 * TAU;
+            let speed = between(rng, self.speed_range.0, self.speed_range.1);
+            let lifespan = self.lifespan * (1.0 + (rng.gen::<f32>() * 2.0 - 1.0) * self.lifespan_variation);
+            let direction = Vec2::from_angle(angle);
+            // Sampled a little way out along the spark's path, so neighbouring sparks curl together
+            let swirl = self.swirl
+                .map(|field| field.sample(center + direction * field.scale * 0.25, field_offset))
+                .unwrap_or(Vec2::ZERO);
+            // Charged like the spawner's particles, jittered by the config's charge_variation
+            let spark = config.species
+                .particle(species_id, center)
+                .with_charge(PresetManager::sample_charge(config, species_id, rng))
+                .with_velocity(direction * speed + swirl)
+                .with_lifespan(lifespan.max(0.0));
+            system.add_particle(spark);
+        }
+    }
+}
+
+// Uniform in [a, b], without gen_range's panic when a UI edit leaves b below a
+fn between<R: Rng>(rng: &mut R, a: f32, b: f32) -> f32 {
+    a + (b - a) * rng.gen::<f32>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bursts_follow_the_clock() {
+        let emitter = BurstEmitter { enabled: true, interval: 0.5, ..BurstEmitter::default() };
+        assert_eq!(emitter.bursts_between(0.0, 0.49), 0);
+        assert_eq!(emitter.bursts_between(0.49, 0.51), 1);
+        assert_eq!(emitter.bursts_between(0.0, 1.6), 3);
+
+        // Stepping in frames fires the same bursts as one long span
+        let dt = 1.0 / 60.0;
+        let stepped: usize = (0..96).map(|step| emitter.bursts_between(step as f32 * dt, (step + 1) as f32 * dt)).sum();
+        assert_eq!(stepped, 3);
+
+        let disabled = BurstEmitter { enabled: false, ..emitter };
+        assert_eq!(disabled.bursts_between(0.0, 10.0), 0);
+    }
+
+    #[test]
+    fn test_sparks_fly_apart_and_burn_out() {
+        let emitter = BurstEmitter { enabled: true, particles_per_burst: 60, ..BurstEmitter::default() };
+        let mut system = ParticleSystem::new(1000);
+        system.spawn_rate = 0.0;
+        let mut rng = crate::rng::fork();
+        emitter.burst(&mut system, &SimulationConfig::default(), &mut rng);
+        assert_eq!(system.particle_count(), 60);
+
+        // A ring of sparks has next to no net momentum
+        let momentum: Vec2 = system.particles.iter().map(|spark| spark.velocity).sum();
+        assert!(momentum.length() < 60.0 * emitter.speed_range.1 * 0.2);
+        assert!(system.particles.iter().all(|spark| spark.lifespan.is_finite()));
+
+        let longest = emitter.lifespan * (1.0 + emitter.lifespan_variation);
+        for _ in 0..(longest * 60.0).ceil() as usize + 1 {
+            system.update(1.0 / 60.0);
+        }
+        assert_eq!(system.particle_count(), 0);
+
+        // Charges vary around the species charge like spawned particles' do
+        let mut config = SimulationConfig::default();
+        config.particles.charge_variation = 0.5;
+        config.particles.species_charges.insert(emitter.species[0], 1.0);
+        emitter.burst(&mut system, &config, &mut rng);
+        let base = config.species_charge(emitter.species[0]);
+        assert!(system.particles.iter().all(|spark| (spark.charge - base).abs() <= base.abs() * 0.25 + 1e-6));
+        assert!(system.particles.iter().any(|spark| spark.charge != base));
+    }
+
+    #[test]
+    fn test_swirl_bends_sparks_off_the_ring() {
+        let swirl = CurlNoiseVelocity { speed: 60.0, ..CurlNoiseVelocity::default() };
+        let emitter = BurstEmitter { enabled: true, particles_per_burst: 40, speed_range: (100.0, 100.0), swirl: Some(swirl), ..BurstEmitter::default() };
+        let mut system = ParticleSystem::new(1000);
+        system.spawn_rate = 0.0;
+        emitter.burst(&mut system, &SimulationConfig::default(), &mut crate::rng::fork());
+        assert_eq!(system.particle_count(), 40);
+        // Without swirl every spark would leave at exactly the burst speed
+        assert!(system.particles.iter().any(|spark| (spark.velocity.length() - 100.0).abs() > 1.0));
+    }
+}
@@ -0,0 +1,392 @@
+use glam::Vec2;
+use rand::Rng;
+use std::collections::HashMap;
+use crate::config::{random_particle_life_rules, Preset, SimulationConfig, SpeciesRegistry};
+use crate::forces::ForceType;
+use crate::particle::Particle;
+use crate::presets::{PresetManager, PresetThumbnail};
+
+pub const THUMBNAIL_SIZE: [usize; 2] = [160, 100];
+// Particles closer than this are counted as part of the same cluster
+const CLUSTER_RADIUS: f32 = 15.0;
+const HALL_OF_FAME_SIZE: usize = 12;
+
+// Scores the state a rule set reaches after a short headless run; higher is better
+pub trait Fitness: Send {
+    fn name(&self) -> &str;
+    fn score(&self, particles: &[Particle]) -> f32;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FitnessKind {
+    // Number of clusters with at least three particles
+    ClusterCount,
+    // Entropy of the cluster size histogram: rewards a mix of small and large structures
+    Diversity,
+    // Entropy of the heading distribution among moving particles
+    MovementEntropy,
+}
+
+impl FitnessKind {
+    pub fn all() -> Vec<FitnessKind> {
+        vec![FitnessKind::ClusterCount, FitnessKind::Diversity, FitnessKind::MovementEntropy]
+    }
+}
+
+impl Fitness for FitnessKind {
+    fn name(&self) -> &str {
+        match self {
+            FitnessKind::ClusterCount => "Cluster Count",
+            FitnessKind::Diversity => "Diversity",
+            FitnessKind::MovementEntropy => "Movement Entropy",
+        }
+    }
+
+    fn score(&self, particles: &[Particle]) -> f32 {
+        match self {
+            FitnessKind::ClusterCount => {
+                cluster_sizes(particles, CLUSTER_RADIUS).into_iter().filter(|&size| size >= 3).count() as f32
+            },
+            FitnessKind::Diversity => {
+                // Sizes binned by powers of two: 1, 2-3, 4-7, ...
+                let mut bins: HashMap<u32, usize> = HashMap::new();
+                let sizes = cluster_sizes(particles, CLUSTER_RADIUS);
+                for &size in &sizes {
+                    *bins.entry(usize::BITS - size.leading_zeros()).or_insert(0) += 1;
+                }
+                entropy(bins.values().copied(), sizes.len())
+            },
+            FitnessKind::MovementEntropy => {
+                const BINS: usize = 16;
+                let mut histogram = [0usize; BINS];
+                let mut moving = 0;
+                for particle in particles {
+                    if particle.velocity.length() < 1.0 {
+                        continue;
+                    }
+                    let angle = particle.velocity.y.atan2(particle.velocity.x) + std::f32::consts::PI;
+                    let bin = ((angle / std::f32::consts::TAU * BINS as f32) as usize).min(BINS - 1);
+                    histogram[bin] += 1;
+                    moving += 1;
+                }
+                let moving_fraction = moving as f32 / particles.len().max(1) as f32;
+                entropy(histogram.into_iter(), moving) * moving_fraction
+            },
+        }
+    }
+}
+
+// Shannon entropy in bits of a histogram with `total` entries
+fn entropy(counts: impl Iterator<Item = usize>, total: usize) -> f32 {
+    if total == 0 {
+        return 0.0;
+    }
+    counts
+        .filter(|&count| count > 0)
+        .map(|count| {
+            let p = count as f32 / total as f32;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+// Sizes of the groups of particles connected by links shorter than `radius`
+pub fn cluster_sizes(particles: &[Particle], radius: f32) -> Vec<usize> {
+    let mut parent: Vec<usize> = (0..particles.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    for i in 0..particles.len() {
+        for j in i + 1..particles.len() {
+            if particles[i].position.distance_squared(particles[j].position) < radius * radius {
+                let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                parent[a] = b;
+            }
+        }
+    }
+
+    let mut sizes: HashMap<usize, usize> = HashMap::new();
+    for i in 0..particles.len() {
+        *sizes.entry(root(&mut parent, i)).or_insert(0) += 1;
+    }
+    sizes.into_values().collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct EvolutionConfig {
+    pub species_count: usize,
+    pub population: usize,
+    // Best candidates carried unchanged into the next generation
+    pub elite: usize,
+    // Chance that each pair rule is perturbed when breeding
+    pub mutation_rate: f32,
+    // Relative size of a perturbation
+    pub mutation_scale: f32,
+    pub particle_count: usize,
+    pub steps: usize,
+    pub fitness: FitnessKind,
+}
+
+impl Default for EvolutionConfig {
+    fn default() -> Self {
+        Self {
+            species_count: 4,
+            population: 12,
+            elite: 3,
+            mutation_rate: 0.3,
+            mutation_scale: 0.3,
+            particle_count: 120,
+            steps: 240,
+            fitness: FitnessKind::ClusterCount,
+        }
+    }
+}
+
+// Particle Life pair rules under evolution
+#[derive(Debug, Clone)]
+pub struct Genome {
+    pub interactions: HashMap<(u32, u32), Vec<ForceType>>,
+}
+
+impl Genome {
+    pub fn random<R: Rng>(species_count: usize, rng: &mut R) -> Self {
+        Self {
+            interactions: random_particle_life_rules(species_count, rng),
+        }
+    }
+
+    // Nudges strengths and ranges; a strength pushed through zero flips attraction and repulsion
+    pub fn mutate<R: Rng>(&self, rate: f32, scale: f32, rng: &mut R) -> Self {
+        let mut interactions = self.interactions.clone();
+        for forces in interactions.values_mut() {
+            for force in forces.iter_mut() {
+                if rng.gen::<f32>() >= rate {
+                    continue;
+                }
+                let (signed, max_distance) = match *force {
+                    ForceType::Attraction { strength, max_distance } => (strength, max_distance),
+                    ForceType::Repulsion { strength, max_distance } => (-strength, max_distance),
+                    _ => continue,
+                };
+                let signed = (signed + rng.gen_range(-1.0..1.0) * scale * 50.0).clamp(-50.0, 50.0);
+                let max_distance = (max_distance * (1.0 + rng.gen_range(-1.0..1.0) * scale)).clamp(10.0, 150.0);
+                *force = if signed >= 0.0 {
+                    ForceType::Attraction { strength: signed, max_distance }
+                } else {
+                    ForceType::Repulsion { strength: -signed, max_distance }
+                };
+            }
+        }
+        Self { interactions }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub genome: Genome,
+    pub score: f32,
+    pub generation: usize,
+    pub thumbnail: PresetThumbnail,
+}
+
+pub struct Evolution {
+    pub config: EvolutionConfig,
+    base: SimulationConfig,
+    fitness: Box<dyn Fitness>,
+    population: Vec<Genome>,
+    pub generation: usize,
+    // Best candidates seen so far, highest score first
+    pub best: Vec<Candidate>,
+}
+
+impl Evolution {
+    pub fn new(config: EvolutionConfig, base: SimulationConfig, fitness: Box<dyn Fitness>) -> Self {
+        let mut rng = rand::thread_rng();
+        let population = (0..config.population.max(2))
+            .map(|_| Genome::random(config.species_count, &mut rng))
+            .collect();
+
+        Self {
+            config,
+            base,
+            fitness,
+            population,
+            generation: 0,
+            best: Vec::new(),
+        }
+    }
+
+    pub fn fitness_name(&self) -> &str {
+        self.fitness.name()
+    }
+
+    // Runs the rule set headless from a fresh Particle Life layout
+    pub fn evaluate(&self, genome: &Genome) -> (f32, Vec<Particle>, Option<(Vec2, Vec2)>) {
+        let mut config = self.base.clone();
+        config.species = SpeciesRegistry::with_count(self.config.species_count);
+        config.particles.species_weights = (0..self.config.species_count as u32)
+            .map(|id| (id, 1.0))
+            .collect();
+        config.particles.initial_particle_count = self.config.particle_count;
+        config.particles.spawn_rate = 0.0;
+        config.forces.species_interactions = genome.interactions.clone();
+        // Genomes hold a rule for each direction of a pair
+        config.forces.asymmetric_interactions = true;
+
+        let system = PresetManager::run_headless(&Preset::ParticleLife, &config, self.config.steps);
+        (self.fitness.score(&system.particles), system.particles, system.bounds)
+    }
+
+    // Scores the current population, records the best, and breeds the next generation
+    pub fn step(&mut self) -> Vec<Candidate> {
+        let mut scored: Vec<Candidate> = self
+            .population
+            .iter()
+            .map(|genome| {
+                let (score, particles, bounds) = self.evaluate(genome);
+                Candidate {
+                    genome: genome.clone(),
+                    score,
+                    generation: self.generation,
+                    thumbnail: PresetManager::render_thumbnail(&particles, bounds, THUMBNAIL_SIZE[0], THUMBNAIL_SIZE[1]),
+                }
+            })
+            .collect();
+        scored.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        self.best.extend(scored.iter().cloned());
+        self.best.sort_by(|a, b| b.score.total_cmp(&a.score));
+        self.best.truncate(HALL_OF_FAME_SIZE);
+
+        // Elites survive as-is; the rest are mutated copies of random elites
+        let mut rng = rand::thread_rng();
+        let elite = self.config.elite.clamp(1, scored.len());
+        let mut next: Vec<Genome> = scored[..elite].iter().map(|c| c.genome.clone()).collect();
+        while next.len() < self.population.len() {
+            let parent = &scored[rng.gen_range(0..elite)].genome;
+            next.push(parent.mutate(self.config.mutation_rate, self.config.mutation_scale, &mut rng));
+        }
+        self.population = next;
+        self.generation += 1;
+
+        scored
+    }
+}
+
+// Runs generations on a worker thread so the UI stays responsive
+pub struct EvolutionRunner {
+    receiver: std::sync::mpsc::Receiver<Vec<Candidate>>,
+    stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    pub generation: usize,
+    pub best: Vec<Candidate>,
+}
+
+impl EvolutionRunner {
+    #[cfg(target_arch = "wasm32")]
+    pub fn start(_evolution: Evolution) -> Result<Self, String> {
+        Err("Evolution search needs threads, which the web build does not have".to_string())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start(mut evolution: Evolution) -> Result<Self, String> {
+        use std::sync::atomic::Ordering;
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let worker_stop = stop.clone();
+        std::thread::Builder::new()
+            .name("evolution".to_string())
+            .spawn(move || {
+                while !worker_stop.load(Ordering::Relaxed) {
+                    evolution.step();
+                    if sender.send(evolution.best.clone()).is_err() {
+                        break;
+                    }
+                }
+            })
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            receiver,
+            stop,
+            generation: 0,
+            best: Vec::new(),
+        })
+    }
+
+    // Collects finished generations; returns true when anything new arrived
+    pub fn poll(&mut self) -> bool {
+        let mut updated = false;
+        while let Ok(best) = self.receiver.try_recv() {
+            self.generation += 1;
+            self.best = best;
+            updated = true;
+        }
+        updated
+    }
+
+    pub fn stop(&self) {
+        self.stop.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+impl Drop for EvolutionRunner {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fitness_functions() {
+        // Two tight groups of three and a loner
+        let positions = [(0.0, 0.0), (5.0, 0.0), (0.0, 5.0), (100.0, 0.0), (105.0, 0.0), (100.0, 5.0), (300.0, 0.0)];
+        let particles: Vec<Particle> = positions.iter().map(|&(x, y)| Particle::new(Vec2::new(x, y))).collect();
+
+        let mut sizes = cluster_sizes(&particles, CLUSTER_RADIUS);
+        sizes.sort();
+        assert_eq!(sizes, vec![1, 3, 3]);
+        assert_eq!(FitnessKind::ClusterCount.score(&particles), 2.0);
+        assert!(FitnessKind::Diversity.score(&particles) > 0.0);
+        assert_eq!(FitnessKind::MovementEntropy.score(&particles), 0.0);
+
+        // Particles heading in four directions carry two bits of heading entropy
+        let moving: Vec<Particle> = [Vec2::X, Vec2::Y, -Vec2::X, -Vec2::Y]
+            .iter()
+            .map(|&direction| Particle::new(Vec2::ZERO).with_velocity(direction * 10.0))
+            .collect();
+        assert!((FitnessKind::MovementEntropy.score(&moving) - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_generation_keeps_best() {
+        let config = EvolutionConfig {
+            species_count: 2,
+            population: 4,
+            elite: 2,
+            particle_count: 20,
+            steps: 5,
+            ..EvolutionConfig::default()
+        };
+        let mut evolution = Evolution::new(config, SimulationConfig::default(), Box::new(FitnessKind::ClusterCount));
+
+        let first = evolution.step();
+        assert_eq!(first.len(), 4);
+        assert!(first.windows(2).all(|w| w[0].score >= w[1].score));
+        assert_eq!(evolution.generation, 1);
+
+        let genome = &first[0].genome;
+        assert_eq!(genome.mutate(1.0, 0.5, &mut rand::thread_rng()).interactions.len(), genome.interactions.len());
+
+        evolution.step();
+        assert!(evolution.best[0].score >= first[0].score);
+    }
+}
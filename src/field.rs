@@ -0,0 +1,170 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use crate::forces::ForceCalculator;
+use crate::particle::Particle;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForceFieldConfig {
+    pub enabled: bool,
+    // Species of the virtual test particle placed at each probe point
+    pub probe_species: u32,
+    // Probe points along the longer side of the view
+    pub resolution: usize,
+    // Screen length of the strongest arrow, in pixels
+    pub arrow_scale: f32,
+    pub show_streamlines: bool,
+    pub streamline_steps: usize,
+}
+
+impl Default for ForceFieldConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            probe_species: 0,
+            resolution: 24,
+            arrow_scale: 25.0,
+            show_streamlines: false,
+            streamline_steps: 40,
+        }
+    }
+}
+
+// Net force sampled on a regular grid covering a region of the world
+#[derive(Debug, Clone)]
+pub struct ForceField {
+    origin: Vec2,
+    spacing: Vec2,
+    columns: usize,
+    rows: usize,
+    forces: Vec<Vec2>,
+    max_magnitude: f32,
+}
+
+impl ForceField {
+    pub fn sample(
+        calculator: &ForceCalculator,
+        particles: &[Particle],
+        probe: Particle,
+        min: Vec2,
+        max: Vec2,
+        resolution: usize,
+    ) -> Self {
+        let extent = (max - min).max(Vec2::splat(1.0));
+        let resolution = resolution.max(2);
+        let cell = extent.max_element() / (resolution - 1) as f32;
+        let columns = (extent.x / cell).round() as usize + 1;
+        let rows = (extent.y / cell).round() as usize + 1;
+        let spacing = Vec2::new(
+            extent.x / (columns - 1).max(1) as f32,
+            extent.y / (rows - 1).max(1) as f32,
+        );
+
+        let mut forces = Vec::with_capacity(columns * rows);
+        for row in 0..rows {
+            for column in 0..columns {
+                let position = min + Vec2::new(column as f32, row as f32) * spacing;
+                let probe = Particle { position, ..probe };
+                forces.push(calculator.probe_force(&probe, particles));
+            }
+        }
+
+        let max_magnitude = forces.iter().map(|f| f.length()).fold(0.0, f32::max);
+
+        Self {
+            origin: min,
+            spacing,
+            columns,
+            rows,
+            forces,
+            max_magnitude,
+        }
+    }
+
+    pub fn max_magnitude(&self) -> f32 {
+        self.max_magnitude
+    }
+
+    pub fn probes(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        self.forces.iter().enumerate().map(move |(i, force)| {
+            let column = i % self.columns;
+            let row = i / self.columns;
+            (self.origin + Vec2::new(column as f32, row as f32) * self.spacing, *force)
+        })
+    }
+
+    // Bilinear interpolation between the surrounding probes; zero outside the sampled region
+    pub fn force_at(&self, position: Vec2) -> Vec2 {
+        let local = (position - self.origin) / self.spacing;
+        if local.x < 0.0 || local.y < 0.0 {
+            return Vec2::ZERO;
+        }
+
+        let column = local.x.floor() as usize;
+        let row = local.y.floor() as usize;
+        if column + 1 >= self.columns || row + 1 >= self.rows {
+            return Vec2::ZERO;
+        }
+
+        let t = local - Vec2::new(column as f32, row as f32);
+        let at = |c: usize, r: usize| self.forces[r * self.columns + c];
+        let bottom = at(column, row).lerp(at(column + 1, row), t.x);
+        let top = at(column, row + 1).lerp(at(column + 1, row + 1), t.x);
+        bottom.lerp(top, t.y)
+    }
+
+    // Follows the field direction from `start` in steps of half a grid cell
+    pub fn streamline(&self, start: Vec2, steps: usize) -> Vec<Vec2> {
+        let step_length = self.spacing.min_element() * 0.5;
+        let mut points = vec![start];
+        let mut position = start;
+
+        for _ in 0..steps {
+            let direction = self.force_at(position).normalize_or_zero();
+            if direction == Vec2::ZERO {
+                break;
+            }
+            position += direction * step_length;
+            points.push(position);
+        }
+
+        points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forces::ForceType;
+
+    #[test]
+    fn test_field_points_towards_attractor() {
+        let mut calculator = ForceCalculator::new();
+        calculator.interaction_matrix.add_interaction(0, 1, ForceType::Attraction { strength: 1.0, max_distance: 100.0 });
+        let particles = vec![Particle::new(Vec2::ZERO).with_species(1)];
+        let probe = Particle::default().with_species(0);
+
+        let field = ForceField::sample(&calculator, &particles, probe, Vec2::splat(-50.0), Vec2::splat(50.0), 11);
+        assert_eq!(field.probes().count(), 121);
+        assert!(field.max_magnitude() > 0.0);
+
+        // Probes left of the attractor are pulled right and vice versa
+        assert!(field.force_at(Vec2::new(-25.0, 0.0)).x > 0.0);
+        assert!(field.force_at(Vec2::new(25.0, 0.0)).x < 0.0);
+        assert_eq!(field.force_at(Vec2::new(500.0, 0.0)), Vec2::ZERO);
+
+        let line = field.streamline(Vec2::new(-40.0, 0.0), 5);
+        assert_eq!(line.len(), 6);
+        assert!(line[5].x > line[0].x);
+    }
+
+    #[test]
+    fn test_probe_species_selects_interactions() {
+        let mut calculator = ForceCalculator::new();
+        calculator.interaction_matrix.add_interaction(0, 1, ForceType::Attraction { strength: 1.0, max_distance: 100.0 });
+        let particles = vec![Particle::new(Vec2::ZERO).with_species(1)];
+        let probe = Particle::default().with_species(2);
+
+        let field = ForceField::sample(&calculator, &particles, probe, Vec2::splat(-50.0), Vec2::splat(50.0), 5);
+        assert_eq!(field.max_magnitude(), 0.0);
+    }
+}
@@ -1,7 +1,16 @@
 use glam::Vec2;
 use crate::particle::{Particle, ParticleSystem};
+use crate::spatial::SpatialPartitioning;
+use crate::steering::SteeringConfig;
+use crate::zone::{apply_zones, Zone};
+use crate::well::{apply_wells, GravityWell};
+use crate::metabolism::MetabolismConfig;
+use crate::noise::{curl2, noise1};
+use crate::profiling::profile_span;
+use crate::rng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ForceType {
@@ -49,12 +58,411 @@ pub enum ForceType {
         alignment_strength: f32,
         cohesion_strength: f32,
     },
+    // Particle i steers toward targets[i]; particles past the end of the list are unaffected
+    SeekTargets {
+        targets: Vec<Vec2>,
+        max_speed: f32,
+        max_force: f32,
+        // Particles slow down linearly inside this distance of their target
+        arrival_radius: f32,
+    },
+    // Steady push along `direction`, with gusts of up to `gustiness` times `strength` either side
+    Wind {
+        direction: Vec2,
+        strength: f32,
+        gustiness: f32,
+    },
+    // Swirling curl-noise flow; `scale` is the size of the eddies in world units and `speed`
+    // how fast the pattern drifts
+    Turbulence {
+        strength: f32,
+        scale: f32,
+        octaves: u32,
+        speed: f32,
+    },
+    // Resistance of the surrounding medium, scaled per species by the calculator's species_drag
+    // and by particle size raised to `size_exponent`
+    Drag {
+        model: DragModel,
+        coefficient: f32,
+        size_exponent: f32,
+    },
+    // Force against distance as a piecewise-linear curve of (distance, force) points sorted by
+    // distance, positive pushing the pair apart. It holds the first point's force below the first
+    // distance and is zero past the last one, e.g. a repulsive core with an attractive shell.
+    Profile {
+        points: Vec<(f32, f32)>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DragModel {
+    // Proportional to speed: slow motion through a viscous fluid (Stokes drag)
+    Linear,
+    // Proportional to speed squared: fast motion through air or water
+    Quadratic,
+}
+
+impl DragModel {
+    pub fn all() -> Vec<DragModel> {
+        vec![DragModel::Linear, DragModel::Quadratic]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            DragModel::Linear => "Linear",
+            DragModel::Quadratic => "Quadratic",
+        }
+    }
+
+    // Physical scaling with particle radius: Stokes drag grows with radius, quadratic drag with cross-section
+    pub fn size_exponent(&self) -> f32 {
+        match self {
+            DragModel::Linear => 1.0,
+            DragModel::Quadratic => 2.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ForceKind {
+    Gravity,
+    ElectroMagnetic,
+    LennardJones,
+    Damping,
+    Brownian,
+    Attraction,
+    Repulsion,
+    Vortex,
+    Spring,
+    Flocking,
+    SeekTargets,
+    Steering,
+    Wind,
+    Turbulence,
+    Drag,
+    Profile,
+}
+
+impl ForceKind {
+    pub fn all() -> Vec<ForceKind> {
+        vec![
+            ForceKind::Gravity,
+            ForceKind::ElectroMagnetic,
+            ForceKind::LennardJones,
+            ForceKind::Damping,
+            ForceKind::Brownian,
+            ForceKind::Attraction,
+            ForceKind::Repulsion,
+            ForceKind::Vortex,
+            ForceKind::Spring,
+            ForceKind::Flocking,
+            ForceKind::SeekTargets,
+            ForceKind::Steering,
+            ForceKind::Wind,
+            ForceKind::Turbulence,
+            ForceKind::Drag,
+            ForceKind::Profile,
+        ]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            ForceKind::Gravity => "Gravity",
+            ForceKind::ElectroMagnetic => "Electromagnetic",
+            ForceKind::LennardJones => "Lennard-Jones",
+            ForceKind::Damping => "Damping",
+            ForceKind::Brownian => "Brownian",
+            ForceKind::Attraction => "Attraction",
+            ForceKind::Repulsion => "Repulsion",
+            ForceKind::Vortex => "Vortex",
+            ForceKind::Spring => "Spring",
+            ForceKind::Flocking => "Flocking",
+            ForceKind::SeekTargets => "Seek Targets",
+            ForceKind::Steering => "Steering",
+            ForceKind::Wind => "Wind",
+            ForceKind::Turbulence => "Turbulence",
+            ForceKind::Drag => "Drag",
+            ForceKind::Profile => "Profile",
+        }
+    }
+
+    // Case-insensitive lookup ignoring separators, e.g. "lennard_jones" or "LennardJones"
+    pub fn from_key(key: &str) -> Option<ForceKind> {
+        let key = normalize_key(key);
+        ForceKind::all()
+            .into_iter()
+            .find(|kind| normalize_key(&format!("{:?}", kind)) == key)
+    }
+}
+
+pub fn normalize_key(key: &str) -> String {
+    key.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+// Force of a Profile curve at `distance`, interpolating linearly between its points
+pub fn profile_force(points: &[(f32, f32)], distance: f32) -> f32 {
+    let Some(next) = points.iter().position(|&(d, _)| d >= distance) else {
+        return 0.0;
+    };
+    if next == 0 {
+        return points[0].1;
+    }
+    let ((d0, f0), (d1, f1)) = (points[next - 1], points[next]);
+    if d1 <= d0 {
+        f1
+    } else {
+        lerp(f0, f1, (distance - d0) / (d1 - d0))
+    }
+}
+
+impl ForceType {
+    pub fn kind(&self) -> ForceKind {
+        match self {
+            ForceType::Gravity { .. } => ForceKind::Gravity,
+            ForceType::ElectroMagnetic { .. } => ForceKind::ElectroMagnetic,
+            ForceType::LennardJones { .. } => ForceKind::LennardJones,
+            ForceType::Damping { .. } => ForceKind::Damping,
+            ForceType::Brownian { .. } => ForceKind::Brownian,
+            ForceType::Attraction { .. } => ForceKind::Attraction,
+            ForceType::Repulsion { .. } => ForceKind::Repulsion,
+            ForceType::Vortex { .. } => ForceKind::Vortex,
+            ForceType::Spring { .. } => ForceKind::Spring,
+            ForceType::Flocking { .. } => ForceKind::Flocking,
+            ForceType::SeekTargets { .. } => ForceKind::SeekTargets,
+            ForceType::Wind { .. } => ForceKind::Wind,
+            ForceType::Turbulence { .. } => ForceKind::Turbulence,
+            ForceType::Drag { .. } => ForceKind::Drag,
+            ForceType::Profile { .. } => ForceKind::Profile,
+        }
+    }
+
+    // Forces of the surroundings rather than the particles; these are set from the config's
+    // global forces, while other global forces (formations) are managed at runtime
+    pub fn is_environmental(&self) -> bool {
+        matches!(self, ForceType::Wind { .. } | ForceType::Turbulence { .. } | ForceType::Drag { .. })
+    }
+
+    // Forces that depend only on the separation between two particles
+    pub fn is_pair_force(&self) -> bool {
+        matches!(
+            self,
+            ForceType::Gravity { .. }
+                | ForceType::ElectroMagnetic { .. }
+                | ForceType::LennardJones { .. }
+                | ForceType::Attraction { .. }
+                | ForceType::Repulsion { .. }
+                | ForceType::Spring { .. }
+                | ForceType::Profile { .. }
+        )
+    }
+
+    // Distance over which the pair force is worth plotting
+    pub fn plot_range(&self) -> f32 {
+        match self {
+            ForceType::Gravity { min_distance, .. } | ForceType::ElectroMagnetic { min_distance, .. } => (min_distance * 20.0).max(10.0),
+            ForceType::LennardJones { sigma, .. } => sigma * 3.0,
+            ForceType::Attraction { max_distance, .. } | ForceType::Repulsion { max_distance, .. } => max_distance * 1.25,
+            ForceType::Spring { rest_length, .. } => (rest_length * 2.0).max(10.0),
+            ForceType::Profile { points } => points.last().map_or(10.0, |&(distance, _)| (distance * 1.25).max(10.0)),
+            _ => 0.0,
+        }
+    }
+
+    // Distance beyond which the pair force is zero; None for forces that reach any distance
+    pub fn cutoff(&self) -> Option<f32> {
+        match self {
+            ForceType::Attraction { max_distance, .. } | ForceType::Repulsion { max_distance, .. } => Some(*max_distance),
+            ForceType::Profile { points } => points.last().map(|&(distance, _)| distance),
+            _ => None,
+        }
+    }
+
+    // Scales the magnitude-like parameters, leaving distances and radii unchanged
+    pub fn scaled(&self, factor: f32) -> ForceType {
+        match self.clone() {
+            ForceType::Gravity { strength, min_distance } => ForceType::Gravity { strength: strength * factor, min_distance },
+            ForceType::ElectroMagnetic { strength, min_distance } => ForceType::ElectroMagnetic { strength: strength * factor, min_distance },
+            ForceType::LennardJones { epsilon, sigma } => ForceType::LennardJones { epsilon: epsilon * factor, sigma },
+            ForceType::Damping { coefficient } => ForceType::Damping { coefficient: coefficient * factor },
+            ForceType::Brownian { intensity } => ForceType::Brownian { intensity: intensity * factor },
+            ForceType::Attraction { strength, max_distance } => ForceType::Attraction { strength: strength * factor, max_distance },
+            ForceType::Repulsion { strength, max_distance } => ForceType::Repulsion { strength: strength * factor, max_distance },
+            ForceType::Vortex { center, strength, max_distance } => ForceType::Vortex { center, strength: strength * factor, max_distance },
+            ForceType::Spring { rest_length, stiffness, damping } => ForceType::Spring { rest_length, stiffness: stiffness * factor, damping: damping * factor },
+            ForceType::Flocking {
+                separation_radius,
+                alignment_radius,
+                cohesion_radius,
+                separation_strength,
+                alignment_strength,
+                cohesion_strength,
+            } => ForceType::Flocking {
+                separation_radius,
+                alignment_radius,
+                cohesion_radius,
+                separation_strength: separation_strength * factor,
+                alignment_strength: alignment_strength * factor,
+                cohesion_strength: cohesion_strength * factor,
+            },
+            ForceType::SeekTargets { targets, max_speed, max_force, arrival_radius } => ForceType::SeekTargets {
+                targets,
+                max_speed,
+                max_force: max_force * factor,
+                arrival_radius,
+            },
+            ForceType::Wind { direction, strength, gustiness } => ForceType::Wind { direction, strength: strength * factor, gustiness },
+            ForceType::Turbulence { strength, scale, octaves, speed } => ForceType::Turbulence { strength: strength * factor, scale, octaves, speed },
+            ForceType::Drag { model, coefficient, size_exponent } => ForceType::Drag { model, coefficient: coefficient * factor, size_exponent },
+            ForceType::Profile { points } => ForceType::Profile {
+                points: points.into_iter().map(|(distance, force)| (distance, force * factor)).collect(),
+            },
+        }
+    }
+
+    // The same force for a scene stretched by `length`: distances scale by it and strengths so
+    // that accelerations do too, so the stretched scene moves exactly like the original
+    pub fn rescaled(&self, length: f32) -> ForceType {
+        let cube = length.powi(3);
+        match self.clone() {
+            ForceType::Gravity { strength, min_distance } => ForceType::Gravity { strength: strength * cube, min_distance: min_distance * length },
+            ForceType::ElectroMagnetic { strength, min_distance } => ForceType::ElectroMagnetic { strength: strength * cube, min_distance: min_distance * length },
+            ForceType::LennardJones { epsilon, sigma } => ForceType::LennardJones { epsilon: epsilon * length * length, sigma: sigma * length },
+            ForceType::Damping { coefficient } => ForceType::Damping { coefficient },
+            ForceType::Brownian { intensity } => ForceType::Brownian { intensity: intensity * length },
+            ForceType::Attraction { strength, max_distance } => ForceType::Attraction { strength: strength * length, max_distance: max_distance * length },
+            ForceType::Repulsion { strength, max_distance } => ForceType::Repulsion { strength: strength * length, max_distance: max_distance * length },
+            ForceType::Vortex { center, strength, max_distance } => ForceType::Vortex { center: center * length, strength: strength * length, max_distance: max_distance * length },
+            ForceType::Spring { rest_length, stiffness, damping } => ForceType::Spring { rest_length: rest_length * length, stiffness, damping },
+            ForceType::Flocking {
+                separation_radius,
+                alignment_radius,
+                cohesion_radius,
+                separation_strength,
+                alignment_strength,
+                cohesion_strength,
+            } => ForceType::Flocking {
+                separation_radius: separation_radius * length,
+                alignment_radius: alignment_radius * length,
+                cohesion_radius: cohesion_radius * length,
+                separation_strength: separation_strength * length,
+                alignment_strength: alignment_strength * length,
+                cohesion_strength: cohesion_strength * length,
+            },
+            ForceType::SeekTargets { targets, max_speed, max_force, arrival_radius } => ForceType::SeekTargets {
+                targets: targets.into_iter().map(|target| target * length).collect(),
+                max_speed: max_speed * length,
+                max_force: max_force * length,
+                arrival_radius: arrival_radius * length,
+            },
+            ForceType::Wind { direction, strength, gustiness } => ForceType::Wind { direction, strength: strength * length, gustiness },
+            ForceType::Turbulence { strength, scale, octaves, speed } => ForceType::Turbulence { strength: strength * length, scale: scale * length, octaves, speed },
+            // Particle sizes stretch too, which the size exponent would otherwise amplify
+            ForceType::Drag { model, coefficient, size_exponent } => {
+                let speed_power = match model {
+                    DragModel::Linear => 0.0,
+                    DragModel::Quadratic => 1.0,
+                };
+                ForceType::Drag { model, coefficient: coefficient / length.powf(speed_power + size_exponent), size_exponent }
+            },
+            ForceType::Profile { points } => ForceType::Profile {
+                points: points.into_iter().map(|(distance, force)| (distance * length, force * length)).collect(),
+            },
+        }
+    }
+
+    // Interpolates every parameter when both forces are the same variant
+    pub fn lerp(&self, other: &ForceType, t: f32) -> Option<ForceType> {
+        match (self, other) {
+            (ForceType::Gravity { strength: s1, min_distance: d1 }, ForceType::Gravity { strength: s2, min_distance: d2 }) => {
+                Some(ForceType::Gravity { strength: lerp(*s1, *s2, t), min_distance: lerp(*d1, *d2, t) })
+            },
+            (ForceType::ElectroMagnetic { strength: s1, min_distance: d1 }, ForceType::ElectroMagnetic { strength: s2, min_distance: d2 }) => {
+                Some(ForceType::ElectroMagnetic { strength: lerp(*s1, *s2, t), min_distance: lerp(*d1, *d2, t) })
+            },
+            (ForceType::LennardJones { epsilon: e1, sigma: s1 }, ForceType::LennardJones { epsilon: e2, sigma: s2 }) => {
+                Some(ForceType::LennardJones { epsilon: lerp(*e1, *e2, t), sigma: lerp(*s1, *s2, t) })
+            },
+            (ForceType::Damping { coefficient: c1 }, ForceType::Damping { coefficient: c2 }) => {
+                Some(ForceType::Damping { coefficient: lerp(*c1, *c2, t) })
+            },
+            (ForceType::Brownian { intensity: i1 }, ForceType::Brownian { intensity: i2 }) => {
+                Some(ForceType::Brownian { intensity: lerp(*i1, *i2, t) })
+            },
+            (ForceType::Attraction { strength: s1, max_distance: d1 }, ForceType::Attraction { strength: s2, max_distance: d2 }) => {
+                Some(ForceType::Attraction { strength: lerp(*s1, *s2, t), max_distance: lerp(*d1, *d2, t) })
+            },
+            (ForceType::Repulsion { strength: s1, max_distance: d1 }, ForceType::Repulsion { strength: s2, max_distance: d2 }) => {
+                Some(ForceType::Repulsion { strength: lerp(*s1, *s2, t), max_distance: lerp(*d1, *d2, t) })
+            },
+            (ForceType::Vortex { center: c1, strength: s1, max_distance: d1 }, ForceType::Vortex { center: c2, strength: s2, max_distance: d2 }) => {
+                Some(ForceType::Vortex { center: c1.lerp(*c2, t), strength: lerp(*s1, *s2, t), max_distance: lerp(*d1, *d2, t) })
+            },
+            (ForceType::Spring { rest_length: r1, stiffness: k1, damping: d1 }, ForceType::Spring { rest_length: r2, stiffness: k2, damping: d2 }) => {
+                Some(ForceType::Spring { rest_length: lerp(*r1, *r2, t), stiffness: lerp(*k1, *k2, t), damping: lerp(*d1, *d2, t) })
+            },
+            (
+                ForceType::Flocking { separation_radius: sr1, alignment_radius: ar1, cohesion_radius: cr1, separation_strength: ss1, alignment_strength: as1, cohesion_strength: cs1 },
+                ForceType::Flocking { separation_radius: sr2, alignment_radius: ar2, cohesion_radius: cr2, separation_strength: ss2, alignment_strength: as2, cohesion_strength: cs2 },
+            ) => Some(ForceType::Flocking {
+                separation_radius: lerp(*sr1, *sr2, t),
+                alignment_radius: lerp(*ar1, *ar2, t),
+                cohesion_radius: lerp(*cr1, *cr2, t),
+                separation_strength: lerp(*ss1, *ss2, t),
+                alignment_strength: lerp(*as1, *as2, t),
+                cohesion_strength: lerp(*cs1, *cs2, t),
+            }),
+            // Targets morph point by point, so both formations need the same number of points
+            (
+                ForceType::SeekTargets { targets: t1, max_speed: v1, max_force: f1, arrival_radius: r1 },
+                ForceType::SeekTargets { targets: t2, max_speed: v2, max_force: f2, arrival_radius: r2 },
+            ) if t1.len() == t2.len() => Some(ForceType::SeekTargets {
+                targets: t1.iter().zip(t2).map(|(a, b)| a.lerp(*b, t)).collect(),
+                max_speed: lerp(*v1, *v2, t),
+                max_force: lerp(*f1, *f2, t),
+                arrival_radius: lerp(*r1, *r2, t),
+            }),
+            (ForceType::Wind { direction: d1, strength: s1, gustiness: g1 }, ForceType::Wind { direction: d2, strength: s2, gustiness: g2 }) => {
+                Some(ForceType::Wind { direction: d1.lerp(*d2, t), strength: lerp(*s1, *s2, t), gustiness: lerp(*g1, *g2, t) })
+            },
+            (
+                ForceType::Turbulence { strength: s1, scale: c1, octaves: o1, speed: v1 },
+                ForceType::Turbulence { strength: s2, scale: c2, octaves: o2, speed: v2 },
+            ) => Some(ForceType::Turbulence {
+                strength: lerp(*s1, *s2, t),
+                scale: lerp(*c1, *c2, t),
+                octaves: if t < 0.5 { *o1 } else { *o2 },
+                speed: lerp(*v1, *v2, t),
+            }),
+            (ForceType::Drag { model: m1, coefficient: c1, size_exponent: e1 }, ForceType::Drag { model: m2, coefficient: c2, size_exponent: e2 }) if m1 == m2 => {
+                Some(ForceType::Drag { model: *m1, coefficient: lerp(*c1, *c2, t), size_exponent: lerp(*e1, *e2, t) })
+            },
+            (ForceType::Profile { points: p1 }, ForceType::Profile { points: p2 }) if p1.len() == p2.len() => Some(ForceType::Profile {
+                points: p1.iter().zip(p2).map(|(a, b)| (lerp(a.0, b.0, t), lerp(a.1, b.1, t))).collect(),
+            }),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InteractionMatrix {
     pub interactions: HashMap<(u32, u32), Vec<ForceType>>,
     pub default_forces: Vec<ForceType>,
+    // Rules for (a, b) and (b, a) are kept apart, so A can chase B while B flees A. A direction
+    // without rules of its own falls back to the other one.
+    #[serde(default)]
+    pub asymmetric: bool,
+    // Limit on the size of a pair's summed pair forces, keyed like `interactions`
+    #[serde(default)]
+    pub max_forces: HashMap<(u32, u32), f32>,
 }
 
 impl Default for InteractionMatrix {
@@ -65,6 +473,8 @@ impl Default for InteractionMatrix {
                 ForceType::Damping { coefficient: 0.01 },
                 ForceType::Brownian { intensity: 0.1 },
             ],
+            asymmetric: false,
+            max_forces: HashMap::new(),
         }
     }
 }
@@ -74,34 +484,114 @@ impl InteractionMatrix {
         Self::default()
     }
 
-    pub fn add_interaction(&mut self, species_a: u32, species_b: u32, force: ForceType) {
-        let key = if species_a <= species_b {
-            (species_a, species_b)
-        } else {
-            (species_b, species_a)
-        };
+    // An empty rule list is kept, so the pair feels nothing rather than the default forces
+    pub fn from_interactions(interactions: &HashMap<(u32, u32), Vec<ForceType>>) -> Self {
+        Self::from_interactions_with_mode(interactions, false)
+    }
+
+    pub fn from_interactions_with_mode(interactions: &HashMap<(u32, u32), Vec<ForceType>>, asymmetric: bool) -> Self {
+        let mut matrix = Self { asymmetric, ..Self::new() };
+        
+        // Merged in key order, so (a, b)'s rules always come before (b, a)'s whatever the map's order
+        let mut pairs: Vec<_> = interactions.iter().collect();
+        pairs.sort_unstable_by_key(|(&pair, _)| pair);
+        for (&(species_a, species_b), forces) in pairs {
+            let key = matrix.key(species_a, species_b);
+            matrix.interactions.entry(key).or_default().extend(forces.iter().cloned());
+        }
         
-        self.interactions.entry(key).or_insert_with(Vec::new).push(force);
+        matrix
     }
 
-    pub fn get_forces(&self, species_a: u32, species_b: u32) -> &[ForceType] {
-        let key = if species_a <= species_b {
+    fn key(&self, species_a: u32, species_b: u32) -> (u32, u32) {
+        if self.asymmetric || species_a <= species_b {
             (species_a, species_b)
         } else {
             (species_b, species_a)
+        }
+    }
+
+    pub fn add_interaction(&mut self, species_a: u32, species_b: u32, force: ForceType) {
+        let key = self.key(species_a, species_b);
+        self.interactions.entry(key).or_default().push(force);
+    }
+
+    // Whether a pair of species feels the same rules in both directions
+    pub fn is_symmetric(&self, species_a: u32, species_b: u32) -> bool {
+        species_a == species_b || std::ptr::eq(self.get_forces(species_a, species_b), self.get_forces(species_b, species_a))
+    }
+
+    pub fn set_max_force(&mut self, species_a: u32, species_b: u32, max_force: Option<f32>) {
+        let key = self.key(species_a, species_b);
+        match max_force {
+            Some(max_force) => self.max_forces.insert(key, max_force),
+            None => self.max_forces.remove(&key),
         };
+    }
+
+    // Cap on the pair force `species_a` feels from `species_b`, mirrored like the rules
+    pub fn max_force(&self, species_a: u32, species_b: u32) -> Option<f32> {
+        let key = self.key(species_a, species_b);
+        self.max_forces
+            .get(&key)
+            .or_else(|| self.max_forces.get(&(key.1, key.0)).filter(|_| self.asymmetric))
+            .copied()
+    }
+
+    // Rules `species_a` follows toward `species_b`
+    pub fn get_forces(&self, species_a: u32, species_b: u32) -> &[ForceType] {
+        let key = self.key(species_a, species_b);
         
         self.interactions
             .get(&key)
+            .or_else(|| self.interactions.get(&(key.1, key.0)).filter(|_| self.asymmetric))
             .map(|v| v.as_slice())
             .unwrap_or(&self.default_forces)
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurvePoint {
+    pub distance: f32,
+    // Radial force, positive when the particles are pushed apart
+    pub force: f32,
+    pub potential: f32,
+}
+
+// Gusts per second, and the distance between gust fronts in world units
+const GUST_FREQUENCY: f32 = 0.5;
+const GUST_WAVELENGTH: f32 = 400.0;
+
+// Work done by apply_forces since the counts were last taken, for the performance window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ForceCounts {
+    // Particle pairs visited for pair forces; each unordered pair once under Newton's third law
+    pub candidate_pairs: usize,
+    // Force terms evaluated, global and pair
+    pub force_terms: usize,
+}
+
+#[derive(Clone)]
 pub struct ForceCalculator {
     pub interaction_matrix: InteractionMatrix,
     pub global_forces: Vec<ForceType>,
     pub dt: f32,
+    // Live multipliers per force kind, used by modulation sources (audio, controllers)
+    pub force_multipliers: HashMap<ForceKind, f32>,
+    pub steering: SteeringConfig,
+    // Evaluate each pair force once and apply it equally and oppositely, conserving momentum
+    pub newtons_third_law: bool,
+    // Seconds over which interactions with a newly spawned particle fade in from zero
+    pub spawn_ramp_time: f32,
+    // Simulated seconds so far; drives wind gusts and drifting turbulence
+    pub time: f32,
+    // Drag multiplier per species ID; unlisted species use 1
+    pub species_drag: HashMap<u32, f32>,
+    // Softening length per inverse-power force, see PhysicsConfig::softening
+    pub softening: HashMap<ForceKind, f32>,
+    // Approximate 1/r² evaluation, see PerformanceConfig::fast_math
+    pub fast_math: FastMath,
+    counts: Cell<ForceCounts>,
 }
 
 impl Default for ForceCalculator {
@@ -110,6 +600,15 @@ impl Default for ForceCalculator {
             interaction_matrix: InteractionMatrix::default(),
             global_forces: Vec::new(),
             dt: 1.0 / 60.0,
+            force_multipliers: HashMap::new(),
+            steering: SteeringConfig::default(),
+            newtons_third_law: true,
+            spawn_ramp_time: 0.0,
+            time: 0.0,
+            species_drag: HashMap::new(),
+            softening: HashMap::new(),
+            fast_math: FastMath::default(),
+            counts: Cell::default(),
         }
     }
 }
@@ -128,35 +627,256 @@ impl ForceCalculator {
         self.global_forces.push(force);
     }
 
+    pub fn force_multiplier(&self, kind: ForceKind) -> f32 {
+        self.force_multipliers.get(&kind).copied().unwrap_or(1.0)
+    }
+
+    pub fn set_force_multiplier(&mut self, kind: ForceKind, multiplier: f32) {
+        self.force_multipliers.insert(kind, multiplier);
+    }
+
+    // Counts accumulated since the last call, which starts them over
+    pub fn take_counts(&self) -> ForceCounts {
+        self.counts.take()
+    }
+
+    fn count(&self, pairs: usize, terms: usize) {
+        let mut counts = self.counts.get();
+        counts.candidate_pairs += pairs;
+        counts.force_terms += terms;
+        self.counts.set(counts);
+    }
+
+    // Forces read the particles as they were when the pass started and only accumulate into the
+    // live accelerations
     pub fn apply_forces(&self, system: &mut ParticleSystem) {
-        let particles_copy = system.particles.clone();
-        
-        for (i, particle) in system.particles.iter_mut().enumerate() {
-            self.apply_global_forces(particle);
+        system.double_buffered(|read, write| {
+            let mut species_counts = BTreeMap::new();
+            for particle in read {
+                *species_counts.entry(particle.species_id).or_insert(0usize) += 1;
+            }
             
-            for (j, other) in particles_copy.iter().enumerate() {
-                if i != j {
-                    self.apply_pair_forces(particle, other);
+            for (i, particle) in write.iter_mut().enumerate() {
+                *particle = read[i];
+                self.apply_global_forces(particle);
+                self.apply_species_rule_forces(particle, &species_counts);
+                
+                if !self.newtons_third_law {
+                    for (j, other) in read.iter().enumerate() {
+                        if i != j {
+                            self.apply_pair_forces(particle, other);
+                        }
+                    }
                 }
+                
+                self.apply_flocking_forces(particle, i, read);
+                self.apply_seek_forces(particle, i);
+                
+                let steering = self.steering.force(particle, i, read);
+                particle.apply_force(steering * self.force_multiplier(ForceKind::Steering));
+                self.count(0, 1);
             }
             
-            self.apply_flocking_forces(particle, i, &particles_copy);
+            if self.newtons_third_law {
+                self.apply_reciprocal_pair_forces(read, write);
+            }
+        });
+    }
+
+    // Net force on a particle that is not part of the system, e.g. a probe for field visualization.
+    // Brownian noise is left out so the result is deterministic.
+    pub fn probe_force(&self, probe: &Particle, particles: &[Particle]) -> Vec2 {
+        let mut total = Vec2::ZERO;
+        
+        for force in &self.global_forces {
+            match force {
+                ForceType::Brownian { .. } | ForceType::SeekTargets { .. } => {},
+                ForceType::Flocking { .. } => {
+                    total += self.calculate_flocking_force(probe, usize::MAX, particles, force)
+                        * self.force_multiplier(ForceKind::Flocking);
+                },
+                _ => total += self.calculate_force(force, probe, None),
+            }
+        }
+        
+        let mut species = Vec::new();
+        for other in particles {
+            let forces = self.interaction_matrix.get_forces(probe.species_id, other.species_id);
+            for force in forces.iter().filter(|force| force.is_pair_force()) {
+                total += self.calculate_force(force, probe, Some(other));
+            }
+            if !species.contains(&other.species_id) {
+                species.push(other.species_id);
+            }
+        }
+        
+        // Like apply_forces, the other rules count once per species present
+        for species_id in species {
+            for force in self.interaction_matrix.get_forces(probe.species_id, species_id) {
+                if !force.is_pair_force() && !matches!(force, ForceType::Brownian { .. }) {
+                    total += self.calculate_force(force, probe, None);
+                }
+            }
+        }
+        
+        total
+    }
+
+    // Radial force between two unit particles at rest, ignoring live multipliers
+    pub fn pair_force_at(&self, force: &ForceType, distance: f32) -> f32 {
+        let particle = Particle::new(Vec2::ZERO).with_charge(1.0);
+        let other = Particle::new(Vec2::new(distance, 0.0)).with_charge(1.0);
+        -self.calculate_base_force(force, &particle, Some(&other)).x
+    }
+
+    // Net radial force between particles of two species `distance` apart, over all their pair rules
+    pub fn species_pair_force(&self, species_a: u32, species_b: u32, distance: f32) -> f32 {
+        let force: f32 = self.interaction_matrix
+            .get_forces(species_a, species_b)
+            .iter()
+            .filter(|force| force.is_pair_force())
+            .map(|force| self.pair_force_at(force, distance))
+            .sum();
+        match self.interaction_matrix.max_force(species_a, species_b) {
+            Some(max_force) => force.clamp(-max_force, max_force),
+            None => force,
         }
     }
 
+    // Samples force and potential over (0, max_distance]. The potential is integrated
+    // inward from max_distance, where it is taken as zero.
+    pub fn pair_curve(&self, force: &ForceType, max_distance: f32, samples: usize) -> Vec<CurvePoint> {
+        if !force.is_pair_force() || max_distance <= 0.0 || samples < 2 {
+            return Vec::new();
+        }
+        
+        let step = max_distance / samples as f32;
+        let mut points: Vec<CurvePoint> = (1..=samples)
+            .map(|i| {
+                let distance = step * i as f32;
+                CurvePoint {
+                    distance,
+                    force: self.pair_force_at(force, distance),
+                    potential: 0.0,
+                }
+            })
+            .collect();
+        
+        // U(r) = integral of F from r to max_distance, using the trapezoid rule
+        for i in (0..points.len() - 1).rev() {
+            let area = (points[i].force + points[i + 1].force) * 0.5 * step;
+            points[i].potential = points[i + 1].potential + area;
+        }
+        
+        points
+    }
+
+    // A Profile tracing a pair force at `samples` evenly spaced distances over its plot range,
+    // for reshaping it by hand
+    pub fn profile_of(&self, force: &ForceType, samples: usize) -> ForceType {
+        let range = force.plot_range();
+        let samples = samples.max(2);
+        let points = (1..=samples)
+            .map(|i| {
+                let distance = range * i as f32 / samples as f32;
+                (distance, self.pair_force_at(force, distance))
+            })
+            .collect();
+        ForceType::Profile { points }
+    }
+
     fn apply_global_forces(&self, particle: &mut Particle) {
         for force in &self.global_forces {
             let force_vec = self.calculate_force(force, particle, None);
             particle.apply_force(force_vec);
         }
+        self.count(0, self.global_forces.len());
     }
 
+    // Pair forces `particle` feels from `other`, when newtons_third_law is off
     fn apply_pair_forces(&self, particle: &mut Particle, other: &Particle) {
-        let forces = self.interaction_matrix.get_forces(particle.species_id, other.species_id);
-        
-        for force_type in forces {
-            let force_vec = self.calculate_force(force_type, particle, Some(other));
-            particle.apply_force(force_vec);
+        self.count(1, 0);
+        let ramp = self.pair_ramp(particle, other);
+        if ramp > 0.0 {
+            let force_vec = self.pair_force(particle, other);
+            particle.apply_force(force_vec * ramp);
+        }
+    }
+    
+    // Rules in the matrix that aren't pair forces (damping, noise) don't depend on the other
+    // particle, so each particle feels them once for every other species present rather than
+    // once per particle of it. `species_counts` counts the particles of each species.
+    fn apply_species_rule_forces(&self, particle: &mut Particle, species_counts: &BTreeMap<u32, usize>) {
+        let ramp = self.spawn_ramp(particle);
+        if ramp <= 0.0 {
+            return;
+        }
+        for (&species_id, &count) in species_counts {
+            let others = if species_id == particle.species_id { count - 1 } else { count };
+            if others == 0 {
+                continue;
+            }
+            let forces = self.interaction_matrix.get_forces(particle.species_id, species_id);
+            for force_type in forces.iter().filter(|force_type| !force_type.is_pair_force()) {
+                let force_vec = self.calculate_force(force_type, particle, None);
+                particle.apply_force(force_vec * ramp);
+                self.count(0, 1);
+            }
+        }
+    }
+
+    // 0 at spawn rising to 1 once the particle is spawn_ramp_time seconds old
+    fn spawn_ramp(&self, particle: &Particle) -> f32 {
+        if self.spawn_ramp_time <= 0.0 {
+            1.0
+        } else {
+            (particle.age / self.spawn_ramp_time).clamp(0.0, 1.0)
+        }
+    }
+
+    // The younger particle of a pair decides, so forces both on and from it are softened
+    fn pair_ramp(&self, a: &Particle, b: &Particle) -> f32 {
+        self.spawn_ramp(a).min(self.spawn_ramp(b))
+    }
+
+    // Each unordered pair is visited once. Pairs under the same rules both ways take equal and
+    // opposite forces; one-sided pairs each take the force of their own side's rules, so chasing
+    // and fleeing keep their full strength.
+    fn apply_reciprocal_pair_forces(&self, read: &[Particle], write: &mut [Particle]) {
+        for i in 0..read.len() {
+            for j in i + 1..read.len() {
+                let (a, b) = (&read[i], &read[j]);
+                self.count(1, 0);
+                let ramp = self.pair_ramp(a, b);
+                if ramp <= 0.0 {
+                    continue;
+                }
+                let on_a = self.pair_force(a, b) * ramp;
+                let on_b = if self.interaction_matrix.is_symmetric(a.species_id, b.species_id) {
+                    -on_a
+                } else {
+                    self.pair_force(b, a) * ramp
+                };
+                write[i].apply_force(on_a);
+                write[j].apply_force(on_b);
+            }
+        }
+    }
+
+    // Summed pair forces, held to the pair's max force if it has one
+    fn pair_force(&self, particle: &Particle, other: &Particle) -> Vec2 {
+        let force: Vec2 = self.interaction_matrix
+            .get_forces(particle.species_id, other.species_id)
+            .iter()
+            .filter(|force_type| force_type.is_pair_force())
+            .map(|force_type| {
+                self.count(0, 1);
+                self.calculate_force(force_type, particle, Some(other))
+            })
+            .sum();
+        match self.interaction_matrix.max_force(particle.species_id, other.species_id) {
+            Some(max_force) => force.clamp_length_max(max_force),
+            None => force,
         }
     }
 
@@ -164,12 +884,29 @@ impl ForceCalculator {
         for force_type in &self.global_forces {
             if let ForceType::Flocking { .. } = force_type {
                 let force_vec = self.calculate_flocking_force(particle, index, all_particles, force_type);
-                particle.apply_force(force_vec);
+                particle.apply_force(force_vec * self.force_multiplier(ForceKind::Flocking) * self.spawn_ramp(particle));
+                self.count(0, 1);
+            }
+        }
+    }
+
+    fn apply_seek_forces(&self, particle: &mut Particle, index: usize) {
+        for force_type in &self.global_forces {
+            if let ForceType::SeekTargets { targets, max_speed, max_force, arrival_radius } = force_type {
+                if let Some(&target) = targets.get(index) {
+                    let force_vec = seek_force(particle, target, *max_speed, *max_force, *arrival_radius);
+                    particle.apply_force(force_vec * self.force_multiplier(ForceKind::SeekTargets));
+                    self.count(0, 1);
+                }
             }
         }
     }
 
     fn calculate_force(&self, force_type: &ForceType, particle: &Particle, other: Option<&Particle>) -> Vec2 {
+        self.calculate_base_force(force_type, particle, other) * self.force_multiplier(force_type.kind())
+    }
+
+    fn calculate_base_force(&self, force_type: &ForceType, particle: &Particle, other: Option<&Particle>) -> Vec2 {
         match force_type {
             ForceType::Gravity { strength, min_distance } => {
                 if let Some(other) = other {
@@ -197,8 +934,8 @@ impl ForceCalculator {
             },
             ForceType::Brownian { intensity } => {
                 Vec2::new(
-                    (rand::random::<f32>() - 0.5) * *intensity,
-                    (rand::random::<f32>() - 0.5) * *intensity,
+                    (rng::random::<f32>() - 0.5) * *intensity,
+                    (rng::random::<f32>() - 0.5) * *intensity,
                 )
             },
             ForceType::Attraction { strength, max_distance } => {
@@ -225,13 +962,70 @@ impl ForceCalculator {
                     Vec2::ZERO
                 }
             },
-            ForceType::Flocking { .. } => Vec2::ZERO, // Handled separately
+            ForceType::Flocking { .. } | ForceType::SeekTargets { .. } => Vec2::ZERO, // Handled separately
+            ForceType::Wind { direction, strength, gustiness } => {
+                self.calculate_wind_force(particle, *direction, *strength, *gustiness)
+            },
+            ForceType::Drag { model, coefficient, size_exponent } => {
+                self.calculate_drag_force(particle, *model, *coefficient, *size_exponent)
+            },
+            ForceType::Turbulence { strength, scale, octaves, speed } => {
+                let drift = Vec2::new(1.0, -0.5) * self.time * *speed;
+                curl2(particle.position / scale.max(f32::EPSILON) + drift, *octaves) * *strength
+            },
+            ForceType::Profile { points } => {
+                if let Some(other) = other {
+                    self.calculate_profile_force(particle, other, points)
+                } else {
+                    Vec2::ZERO
+                }
+            },
+        }
+    }
+
+    fn calculate_drag_force(&self, particle: &Particle, model: DragModel, coefficient: f32, size_exponent: f32) -> Vec2 {
+        let species = self.species_drag.get(&particle.species_id).copied().unwrap_or(1.0);
+        let scale = coefficient * species * particle.size.max(0.0).powf(size_exponent);
+        match model {
+            DragModel::Linear => -particle.velocity * scale,
+            DragModel::Quadratic => -particle.velocity * particle.velocity.length() * scale,
+        }
+    }
+
+    // Gusts travel downwind: a particle feels what the particle upwind of it felt a moment earlier
+    fn calculate_wind_force(&self, particle: &Particle, direction: Vec2, strength: f32, gustiness: f32) -> Vec2 {
+        let direction = direction.normalize_or_zero();
+        let phase = self.time * GUST_FREQUENCY - particle.position.dot(direction) / GUST_WAVELENGTH;
+        direction * strength * (1.0 + gustiness * noise1(phase))
+    }
+
+    // Plummer softening: sqrt(r² + ε²) stands in for r, so the force stays finite as r goes to zero
+    fn softened_distance(&self, kind: ForceKind, distance: f32) -> f32 {
+        match self.softening.get(&kind) {
+            Some(&epsilon) if epsilon > 0.0 => (distance * distance + epsilon * epsilon).sqrt(),
+            _ => distance,
+        }
+    }
+
+    // Inverse-square force along `offset` on the fast path: softening and the minimum distance are
+    // applied to r², and one rsqrt gives both the direction and the falloff
+    fn fast_inverse_square(&self, kind: ForceKind, offset: Vec2, coupling: f32, min_distance: f32) -> Vec2 {
+        let distance_sq = offset.length_squared();
+        if self.fast_math.cutoff_squared(kind).is_some_and(|cutoff_sq| distance_sq > cutoff_sq) || distance_sq == 0.0 {
+            return Vec2::ZERO;
         }
+        let epsilon = self.softening.get(&kind).copied().unwrap_or(0.0).max(0.0);
+        let inv_length = fast_inv_sqrt(distance_sq);
+        let inv_distance = fast_inv_sqrt(distance_sq.max(min_distance * min_distance) + epsilon * epsilon);
+        offset * inv_length * coupling * inv_distance * inv_distance
     }
 
     fn calculate_gravitational_force(&self, particle: &Particle, other: &Particle, strength: f32, min_distance: f32) -> Vec2 {
         let distance_vec = other.position - particle.position;
-        let distance = distance_vec.length().max(min_distance);
+        if self.fast_math.enabled {
+            return self.fast_inverse_square(ForceKind::Gravity, distance_vec, strength * particle.mass * other.mass, min_distance);
+        }
+        let distance = self.softened_distance(ForceKind::Gravity, distance_vec.length().max(min_distance));
         let direction = distance_vec.normalize_or_zero();
         
         let force_magnitude = strength * particle.mass * other.mass / (distance * distance);
@@ -240,7 +1034,10 @@ impl ForceCalculator {
 
     fn calculate_electromagnetic_force(&self, particle: &Particle, other: &Particle, strength: f32, min_distance: f32) -> Vec2 {
         let distance_vec = other.position - particle.position;
-        let distance = distance_vec.length().max(min_distance);
+        if self.fast_math.enabled {
+            return self.fast_inverse_square(ForceKind::ElectroMagnetic, distance_vec, strength * particle.charge * other.charge, min_distance);
+        }
+        let distance = self.softened_distance(ForceKind::ElectroMagnetic, distance_vec.length().max(min_distance));
         let direction = distance_vec.normalize_or_zero();
         
         let force_magnitude = strength * particle.charge * other.charge / (distance * distance);
@@ -256,12 +1053,15 @@ impl ForceCalculator {
         }
         
         let direction = distance_vec.normalize();
+        let distance = self.softened_distance(ForceKind::LennardJones, distance);
         let r_over_sigma = distance / sigma;
         let r6 = r_over_sigma.powi(6);
         let r12 = r6 * r6;
         
+        // Positive inside the equilibrium distance, so the pair is pushed apart there and pulled
+        // together beyond it
         let force_magnitude = 24.0 * epsilon * (2.0 / r12 - 1.0 / r6) / distance;
-        direction * force_magnitude
+        -direction * force_magnitude
     }
 
     fn calculate_attraction_force(&self, particle: &Particle, other: &Particle, strength: f32, max_distance: f32) -> Vec2 {
@@ -290,6 +1090,17 @@ impl ForceCalculator {
         direction * force_magnitude
     }
 
+    fn calculate_profile_force(&self, particle: &Particle, other: &Particle, points: &[(f32, f32)]) -> Vec2 {
+        let distance_vec = other.position - particle.position;
+        let distance = distance_vec.length();
+        
+        if distance == 0.0 {
+            return Vec2::ZERO;
+        }
+        
+        -distance_vec.normalize() * profile_force(points, distance)
+    }
+
     fn calculate_vortex_force(&self, particle: &Particle, center: Vec2, strength: f32, max_distance: f32) -> Vec2 {
         let distance_vec = particle.position - center;
         let distance = distance_vec.length();
@@ -387,6 +1198,65 @@ impl ForceCalculator {
     }
 }
 
+// Limits each particle's accumulated force to `max_force`, returning how many were limited.
+// Zone effects come afterwards, so they still scale the clamped force.
+pub fn clamp_forces(particles: &mut [Particle], max_force: f32) -> usize {
+    if max_force <= 0.0 {
+        return 0;
+    }
+    let mut clamped = 0;
+    for particle in particles.iter_mut().filter(|p| p.mass > 0.0) {
+        let force = particle.acceleration * particle.mass;
+        if force.length_squared() > max_force * max_force {
+            particle.acceleration = force.clamp_length_max(max_force) / particle.mass;
+            clamped += 1;
+        }
+    }
+    clamped
+}
+
+// Reynolds arrival: steer toward the target at up to max_speed, slowing inside arrival_radius
+pub fn seek_force(particle: &Particle, target: Vec2, max_speed: f32, max_force: f32, arrival_radius: f32) -> Vec2 {
+    let offset = target - particle.position;
+    let distance = offset.length();
+    let speed = if distance < arrival_radius {
+        max_speed * distance / arrival_radius
+    } else {
+        max_speed
+    };
+    
+    let desired = offset.normalize_or_zero() * speed;
+    (desired - particle.velocity).clamp_length_max(max_force) * particle.mass
+}
+
+// Pairs each position with the nearest target not yet taken, so a swarm morphs into a new
+// formation without particles crossing the whole shape. Targets are thinned or repeated
+// evenly to match the number of positions.
+pub fn assign_targets(positions: &[Vec2], targets: &[Vec2]) -> Vec<Vec2> {
+    if targets.is_empty() {
+        return Vec::new();
+    }
+    
+    let resampled: Vec<Vec2> = (0..positions.len())
+        .map(|i| targets[i * targets.len() / positions.len().max(1)])
+        .collect();
+    let mut taken = vec![false; resampled.len()];
+    
+    positions
+        .iter()
+        .map(|position| {
+            let (index, target) = resampled
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !taken[*i])
+                .min_by(|a, b| a.1.distance_squared(*position).total_cmp(&b.1.distance_squared(*position)))
+                .expect("one free target per position");
+            taken[index] = true;
+            *target
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PhysicsConfig {
     pub integration_method: IntegrationMethod,
@@ -395,13 +1265,87 @@ pub struct PhysicsConfig {
     pub max_velocity: f32,
     pub enable_collisions: bool,
     pub collision_restitution: f32,
+    // Off for intentionally asymmetric rules such as classic Particle Life
+    #[serde(default = "default_newtons_third_law")]
+    pub newtons_third_law: bool,
+    // Seconds over which a new particle's interactions ramp up to full strength; 0 disables
+    #[serde(default = "default_spawn_ramp_time")]
+    pub spawn_ramp_time: f32,
+    // Softening length for the forces in SOFTENED_FORCES; keeps close encounters finite
+    #[serde(default)]
+    pub softening: HashMap<ForceKind, f32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Forces that diverge as distance goes to zero and so accept a softening length
+pub const SOFTENED_FORCES: [ForceKind; 3] = [ForceKind::Gravity, ForceKind::ElectroMagnetic, ForceKind::LennardJones];
+
+// The 1/r² forces the fast math path approximates
+pub const INVERSE_SQUARE_FORCES: [ForceKind; 2] = [ForceKind::Gravity, ForceKind::ElectroMagnetic];
+
+// Approximate evaluation of the 1/r² forces for very large particle counts: one reciprocal square
+// root estimate stands in for the length, normalize and divide, and pairs beyond a per-kind cutoff
+// distance are skipped outright. Errors stay well under a percent of the force.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FastMath {
+    pub enabled: bool,
+    // Distance past which a force is dropped; missing or 0 keeps its full range
+    #[serde(default)]
+    pub cutoffs: HashMap<ForceKind, f32>,
+}
+
+impl FastMath {
+    fn cutoff_squared(&self, kind: ForceKind) -> Option<f32> {
+        self.cutoffs.get(&kind).filter(|&&cutoff| cutoff > 0.0).map(|cutoff| cutoff * cutoff)
+    }
+}
+
+// 1/sqrt(x) from the bit-level initial guess plus one Newton step; relative error below 0.2%
+pub fn fast_inv_sqrt(x: f32) -> f32 {
+    let estimate = f32::from_bits(0x5f37_59df - (x.to_bits() >> 1));
+    estimate * (1.5 - 0.5 * x * estimate * estimate)
+}
+
+fn default_spawn_ramp_time() -> f32 {
+    0.5
+}
+
+fn default_newtons_third_law() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum IntegrationMethod {
     Euler,
+    #[default]
     Verlet,
     RungeKutta4,
+    // Updates velocity first and moves with the new one; symplectic, so orbits and oscillators
+    // keep their energy over long runs
+    SymplecticEuler,
+    // Kick-drift with velocities held half a step apart from positions; symplectic and second order
+    Leapfrog,
+}
+
+impl IntegrationMethod {
+    pub fn all() -> Vec<IntegrationMethod> {
+        vec![
+            IntegrationMethod::Euler,
+            IntegrationMethod::SymplecticEuler,
+            IntegrationMethod::Leapfrog,
+            IntegrationMethod::Verlet,
+            IntegrationMethod::RungeKutta4,
+        ]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            IntegrationMethod::Euler => "Euler",
+            IntegrationMethod::SymplecticEuler => "Symplectic Euler",
+            IntegrationMethod::Leapfrog => "Leapfrog",
+            IntegrationMethod::Verlet => "Verlet",
+            IntegrationMethod::RungeKutta4 => "Runge-Kutta 4",
+        }
+    }
 }
 
 impl Default for PhysicsConfig {
@@ -413,14 +1357,27 @@ impl Default for PhysicsConfig {
             max_velocity: 100.0,
             enable_collisions: false,
             collision_restitution: 0.8,
+            newtons_third_law: true,
+            spawn_ramp_time: default_spawn_ramp_time(),
+            softening: HashMap::new(),
         }
     }
 }
 
+// Whether `previous_position` holds the particle's position from its last physics step
+fn has_stepped(particle: &Particle) -> bool {
+    particle.age > 0.0 && particle.previous_position.is_finite()
+}
+
 pub struct PhysicsEngine {
     pub config: PhysicsConfig,
     pub force_calculator: ForceCalculator,
-    previous_positions: Vec<Vec2>,
+    pub zones: Vec<Zone>,
+    pub wells: Vec<GravityWell>,
+    // Charges organisms energy for the forces they feel, once those are known
+    pub metabolism: MetabolismConfig,
+    // Particles whose net force hit PhysicsConfig::max_force in the last step
+    pub clamped_forces: usize,
 }
 
 impl PhysicsEngine {
@@ -428,27 +1385,53 @@ impl PhysicsEngine {
         Self {
             force_calculator: ForceCalculator::new().with_dt(config.dt),
             config,
-            previous_positions: Vec::new(),
+            zones: Vec::new(),
+            wells: Vec::new(),
+            metabolism: MetabolismConfig::default(),
+            clamped_forces: 0,
         }
     }
 
     pub fn update(&mut self, system: &mut ParticleSystem) {
+        self.update_with_spatial(system, None);
+    }
+
+    // `spatial`, if given, must be up to date with `system`; zones use it to find their particles
+    pub fn update_with_spatial(&mut self, system: &mut ParticleSystem, spatial: Option<&SpatialPartitioning>) {
         self.force_calculator.dt = self.config.dt;
-        self.force_calculator.apply_forces(system);
+        self.force_calculator.newtons_third_law = self.config.newtons_third_law;
+        self.force_calculator.spawn_ramp_time = self.config.spawn_ramp_time;
+        self.force_calculator.softening.clone_from(&self.config.softening);
+        {
+            profile_span!("force_calculation");
+            self.force_calculator.apply_forces(system);
+            apply_wells(&self.wells, &mut system.particles);
+            self.clamped_forces = clamp_forces(&mut system.particles, self.config.max_force);
+            apply_zones(&self.zones, &mut system.particles, spatial);
+            self.metabolism.burn(&mut system.particles, self.config.dt);
+        }
+        self.force_calculator.time += self.config.dt;
 
-        match self.config.integration_method {
-            IntegrationMethod::Euler => self.euler_integration(system),
-            IntegrationMethod::Verlet => self.verlet_integration(system),
-            IntegrationMethod::RungeKutta4 => self.rk4_integration(system),
+        {
+            profile_span!("integration");
+            match self.config.integration_method {
+                IntegrationMethod::Euler => self.euler_integration(system),
+                IntegrationMethod::SymplecticEuler => self.symplectic_euler_integration(system),
+                IntegrationMethod::Leapfrog => self.leapfrog_integration(system),
+                IntegrationMethod::Verlet => self.verlet_integration(system),
+                IntegrationMethod::RungeKutta4 => self.rk4_integration(system),
+            }
         }
 
         if self.config.enable_collisions {
+            profile_span!("collisions");
             self.handle_collisions(system);
         }
     }
 
     fn euler_integration(&self, system: &mut ParticleSystem) {
         for particle in &mut system.particles {
+            particle.previous_position = particle.position;
             let old_velocity = particle.velocity;
             particle.velocity += particle.acceleration * self.config.dt;
             particle.velocity = particle.velocity.clamp_length_max(self.config.max_velocity);
@@ -459,16 +1442,50 @@ impl PhysicsEngine {
         }
     }
 
-    fn verlet_integration(&mut self, system: &mut ParticleSystem) {
-        if self.previous_positions.len() != system.particles.len() {
-            self.previous_positions = system.particles.iter().map(|p| p.position).collect();
+    fn symplectic_euler_integration(&self, system: &mut ParticleSystem) {
+        for particle in &mut system.particles {
+            particle.previous_position = particle.position;
+            particle.velocity += particle.acceleration * self.config.dt;
+            particle.velocity = particle.velocity.clamp_length_max(self.config.max_velocity);
+            
+            particle.position += particle.velocity * self.config.dt;
+            particle.age += self.config.dt;
+            particle.acceleration = Vec2::ZERO;
         }
+    }
 
-        for (i, particle) in system.particles.iter_mut().enumerate() {
-            let new_position = 2.0 * particle.position - self.previous_positions[i] + 
+    fn leapfrog_integration(&self, system: &mut ParticleSystem) {
+        let dt = self.config.dt;
+        for particle in &mut system.particles {
+            // The last step's displacement is the half-step velocity
+            let half_velocity = if has_stepped(particle) {
+                (particle.position - particle.previous_position) / dt
+            } else {
+                // Seed so the kick below lands half a step ahead of the current velocity
+                particle.velocity - particle.acceleration * dt * 0.5
+            };
+            let half_velocity = (half_velocity + particle.acceleration * dt).clamp_length_max(self.config.max_velocity);
+            particle.previous_position = particle.position;
+            particle.position += half_velocity * dt;
+            // Report the velocity at the new position, finishing the step's kick
+            particle.velocity = (half_velocity + particle.acceleration * dt * 0.5).clamp_length_max(self.config.max_velocity);
+            particle.age += dt;
+            particle.acceleration = Vec2::ZERO;
+        }
+    }
+
+    fn verlet_integration(&self, system: &mut ParticleSystem) {
+        for particle in &mut system.particles {
+            // Seed from the velocity so new particles keep their initial motion
+            let previous_position = if has_stepped(particle) {
+                particle.previous_position
+            } else {
+                particle.position - particle.velocity * self.config.dt
+            };
+            let new_position = 2.0 * particle.position - previous_position + 
                               particle.acceleration * self.config.dt * self.config.dt;
             
-            self.previous_positions[i] = particle.position;
+            particle.previous_position = particle.position;
             particle.velocity = (new_position - particle.position) / self.config.dt;
             particle.velocity = particle.velocity.clamp_length_max(self.config.max_velocity);
             particle.position = new_position;
@@ -479,6 +1496,7 @@ impl PhysicsEngine {
 
     fn rk4_integration(&self, system: &mut ParticleSystem) {
         for particle in &mut system.particles {
+            particle.previous_position = particle.position;
             let dt = self.config.dt;
             let k1_v = particle.acceleration * dt;
             let k1_x = particle.velocity * dt;
@@ -500,41 +1518,51 @@ impl PhysicsEngine {
         }
     }
 
+    // Pushes overlapping pairs apart and bounces them off each other. Every pair is judged from
+    // the state at the start of the pass and both particles take their share, equal and
+    // opposite, so the outcome doesn't depend on which of them comes first. Pairs are visited
+    // with the later particle outermost, so each slot is started from its read particle before
+    // any pair writes to it.
     fn handle_collisions(&self, system: &mut ParticleSystem) {
-        let particles_copy = system.particles.clone();
-        
-        for (i, particle) in system.particles.iter_mut().enumerate() {
-            for (j, other) in particles_copy.iter().enumerate() {
-                if i >= j {
-                    continue;
-                }
-                
-                let distance = particle.distance_to(other);
-                let min_distance = (particle.size + other.size) * 0.5;
-                
-                if distance < min_distance && distance > 0.0 {
-                    let overlap = min_distance - distance;
-                    let direction = (particle.position - other.position) / distance;
+        system.double_buffered(|read, write| {
+            for j in 0..read.len() {
+                write[j] = read[j];
+                for i in 0..j {
+                    let (a, b) = (&read[i], &read[j]);
+                    let distance = a.distance_to(b);
+                    let min_distance = (a.size + b.size) * 0.5;
                     
-                    particle.position += direction * overlap * 0.5;
+                    if distance >= min_distance || distance <= 0.0 {
+                        continue;
+                    }
                     
-                    let relative_velocity = particle.velocity - other.velocity;
-                    let velocity_along_normal = relative_velocity.dot(direction);
+                    let direction = (a.position - b.position) / distance;
+                    let correction = direction * (min_distance - distance) * 0.5;
+                    write[i].position += correction;
+                    write[j].position -= correction;
                     
-                    if velocity_along_normal > 0.0 {
+                    let velocity_along_normal = (a.velocity - b.velocity).dot(direction);
+                    let inverse_masses = inverse_mass(a) + inverse_mass(b);
+                    if velocity_along_normal > 0.0 || inverse_masses <= 0.0 {
                         continue;
                     }
                     
-                    let impulse_magnitude = -(1.0 + self.config.collision_restitution) * velocity_along_normal;
+                    let impulse_magnitude = -(1.0 + self.config.collision_restitution) * velocity_along_normal / inverse_masses;
                     let impulse = direction * impulse_magnitude;
                     
-                    particle.apply_impulse(impulse);
+                    write[i].apply_impulse(impulse);
+                    write[j].apply_impulse(-impulse);
                 }
             }
-        }
+        });
     }
 }
 
+// Massless particles are treated as immovable in collisions, as apply_impulse leaves them be
+fn inverse_mass(particle: &Particle) -> f32 {
+    if particle.mass > 0.0 { 1.0 / particle.mass } else { 0.0 }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -546,6 +1574,284 @@ mod tests {
         
         let forces = matrix.get_forces(0, 1);
         assert_eq!(forces.len(), 1);
+        
+        let interactions = HashMap::from([((1, 0), vec![ForceType::Damping { coefficient: 0.5 }]), ((2, 2), Vec::new())]);
+        let matrix = InteractionMatrix::from_interactions(&interactions);
+        assert_eq!(matrix.get_forces(0, 1).len(), 1);
+        assert!(matrix.get_forces(2, 2).is_empty());
+        assert_eq!(matrix.get_forces(3, 3).len(), matrix.default_forces.len());
+    }
+
+    #[test]
+    fn test_profile_repulsive_core_attractive_shell() {
+        let points = vec![(0.0, 10.0), (10.0, 0.0), (20.0, -5.0), (30.0, 0.0)];
+        assert_eq!(profile_force(&points, 5.0), 5.0);
+        assert_eq!(profile_force(&points, 15.0), -2.5);
+        assert_eq!(profile_force(&points, 40.0), 0.0);
+        assert_eq!(profile_force(&[(5.0, 3.0)], 1.0), 3.0);
+        assert_eq!(profile_force(&[], 1.0), 0.0);
+
+        let calculator = ForceCalculator::new();
+        let profile = ForceType::Profile { points };
+        assert!(profile.is_pair_force());
+        assert_eq!(calculator.pair_force_at(&profile, 5.0), 5.0);
+        assert_eq!(calculator.pair_force_at(&profile, 20.0), -5.0);
+        assert_eq!(profile.plot_range(), 37.5);
+
+        let attraction = ForceType::Attraction { strength: 10.0, max_distance: 40.0 };
+        let ForceType::Profile { points } = calculator.profile_of(&attraction, 10) else {
+            panic!("expected a profile");
+        };
+        assert_eq!(points.len(), 10);
+        assert_eq!(points[1], (10.0, -7.5));
+        assert_eq!(points[9].1, 0.0);
+    }
+
+    #[test]
+    fn test_pair_max_force_caps_summed_pair_forces() {
+        let mut calculator = ForceCalculator::new();
+        calculator.newtons_third_law = false;
+        calculator.interaction_matrix.add_interaction(0, 1, ForceType::Repulsion { strength: 40.0, max_distance: 50.0 });
+        calculator.interaction_matrix.add_interaction(0, 1, ForceType::Repulsion { strength: 40.0, max_distance: 50.0 });
+        calculator.interaction_matrix.set_max_force(1, 0, Some(30.0));
+        assert_eq!(calculator.interaction_matrix.max_force(0, 1), Some(30.0));
+        assert_eq!(calculator.species_pair_force(0, 1, 25.0), 30.0);
+        assert!((calculator.species_pair_force(0, 1, 45.0) - 8.0).abs() < 1e-4);
+
+        let mut system = ParticleSystem::new(2);
+        system.add_particle(Particle::new(Vec2::ZERO).with_species(0));
+        system.add_particle(Particle::new(Vec2::new(25.0, 0.0)).with_species(1));
+        calculator.apply_forces(&mut system);
+        assert!((system.particles[0].acceleration.x + 30.0).abs() < 1.0);
+
+        assert_eq!(ForceType::Repulsion { strength: 1.0, max_distance: 50.0 }.cutoff(), Some(50.0));
+        assert_eq!(ForceType::Gravity { strength: 1.0, min_distance: 1.0 }.cutoff(), None);
+    }
+
+    #[test]
+    fn test_asymmetric_matrix_keeps_directions_apart() {
+        let chase = ForceType::Attraction { strength: 10.0, max_distance: 50.0 };
+        let flee = ForceType::Repulsion { strength: 10.0, max_distance: 50.0 };
+        let interactions = HashMap::from([((0, 1), vec![chase.clone()]), ((1, 0), vec![flee]), ((0, 2), vec![chase])]);
+
+        let symmetric = InteractionMatrix::from_interactions(&interactions);
+        // Merged in key order, whatever order the map iterates in
+        assert!(matches!(symmetric.get_forces(1, 0), [ForceType::Attraction { .. }, ForceType::Repulsion { .. }]));
+        assert!(symmetric.is_symmetric(0, 1));
+
+        let matrix = InteractionMatrix::from_interactions_with_mode(&interactions, true);
+        assert!(matches!(matrix.get_forces(0, 1), [ForceType::Attraction { .. }]));
+        assert!(matches!(matrix.get_forces(1, 0), [ForceType::Repulsion { .. }]));
+        assert!(!matrix.is_symmetric(0, 1));
+        // A direction without its own rules mirrors the other
+        assert!(matches!(matrix.get_forces(2, 0), [ForceType::Attraction { .. }]));
+        assert!(matrix.is_symmetric(0, 2));
+
+        let mut calculator = ForceCalculator::new();
+        calculator.interaction_matrix = matrix;
+        assert!(calculator.species_pair_force(0, 1, 20.0) < 0.0);
+        assert!(calculator.species_pair_force(1, 0, 20.0) > 0.0);
+
+        // Under Newton's third law a one-sided pair still feels each side at full strength
+        calculator.interaction_matrix.default_forces.clear();
+        let mut system = ParticleSystem::new(2);
+        system.add_particle(Particle::new(Vec2::ZERO));
+        system.add_particle(Particle::new(Vec2::new(20.0, 0.0)).with_species(1));
+        calculator.apply_forces(&mut system);
+        let chaser = system.particles[0].acceleration.x;
+        let fleer = system.particles[1].acceleration.x;
+        assert!((chaser + calculator.species_pair_force(0, 1, 20.0)).abs() < 1e-4);
+        assert!((fleer - calculator.species_pair_force(1, 0, 20.0)).abs() < 1e-4);
+
+    }
+
+    #[test]
+    fn test_non_pair_rules_apply_once_per_species() {
+        let mut calculator = ForceCalculator::new();
+        calculator.global_forces.clear();
+        calculator.interaction_matrix.default_forces = vec![ForceType::Damping { coefficient: 0.5 }];
+
+        let acceleration = |others: usize| {
+            let mut system = ParticleSystem::new(20);
+            system.add_particle(Particle::new(Vec2::ZERO).with_velocity(Vec2::new(2.0, 0.0)));
+            for index in 0..others {
+                system.add_particle(Particle::new(Vec2::new(100.0 + index as f32, 0.0)).with_species(1));
+            }
+            calculator.apply_forces(&mut system);
+            system.particles[0].acceleration
+        };
+
+        assert_eq!(acceleration(0), Vec2::ZERO);
+        assert_eq!(acceleration(1), Vec2::new(-1.0, 0.0));
+        assert_eq!(acceleration(10), acceleration(1));
+    }
+
+    #[test]
+    fn test_wind_gusts_vary_over_time() {
+        let mut calculator = ForceCalculator::new();
+        let particle = Particle::new(Vec2::new(10.0, 0.0));
+        let steady = ForceType::Wind { direction: Vec2::new(2.0, 0.0), strength: 5.0, gustiness: 0.0 };
+        let gusty = ForceType::Wind { direction: Vec2::new(2.0, 0.0), strength: 5.0, gustiness: 0.8 };
+
+        let samples: Vec<Vec2> = (0..20)
+            .map(|step| {
+                calculator.time = step as f32 * 0.25;
+                assert_eq!(calculator.calculate_force(&steady, &particle, None), Vec2::new(5.0, 0.0));
+                calculator.calculate_force(&gusty, &particle, None)
+            })
+            .collect();
+        assert!(samples.iter().all(|force| force.y == 0.0 && force.x >= 1.0 && force.x <= 9.0));
+        assert!(samples.iter().any(|force| (force.x - 5.0).abs() > 0.5));
+    }
+
+    #[test]
+    fn test_drag_models() {
+        let mut calculator = ForceCalculator::new();
+        calculator.species_drag.insert(1, 3.0);
+        let linear = ForceType::Drag { model: DragModel::Linear, coefficient: 0.5, size_exponent: 1.0 };
+        let quadratic = ForceType::Drag { model: DragModel::Quadratic, coefficient: 0.5, size_exponent: 0.0 };
+
+        let particle = Particle::new(Vec2::ZERO).with_velocity(Vec2::new(4.0, 0.0)).with_size(2.0);
+        assert_eq!(calculator.calculate_force(&linear, &particle, None), Vec2::new(-4.0, 0.0));
+        assert_eq!(calculator.calculate_force(&quadratic, &particle, None), Vec2::new(-8.0, 0.0));
+
+        // Per-species coefficients multiply in; unlisted species keep the base coefficient
+        let particle = particle.with_species(1);
+        assert_eq!(calculator.calculate_force(&linear, &particle, None), Vec2::new(-12.0, 0.0));
+    }
+
+    #[test]
+    fn test_force_clamping_and_softening() {
+        let mut particles = vec![
+            Particle::new(Vec2::ZERO).with_mass(2.0),
+            Particle::new(Vec2::ZERO).with_mass(2.0),
+        ];
+        particles[0].acceleration = Vec2::new(300.0, 400.0);
+        particles[1].acceleration = Vec2::new(10.0, 0.0);
+        assert_eq!(clamp_forces(&mut particles, 100.0), 1);
+        assert!((particles[0].acceleration - Vec2::new(30.0, 40.0)).length() < 1e-4);
+        assert_eq!(particles[1].acceleration, Vec2::new(10.0, 0.0));
+        assert_eq!(clamp_forces(&mut particles, 0.0), 0);
+
+        // Softening 4 turns a distance of 3 into 5
+        let mut calculator = ForceCalculator::new();
+        calculator.softening.insert(ForceKind::Gravity, 4.0);
+        let gravity = ForceType::Gravity { strength: 25.0, min_distance: 0.0 };
+        let particle = Particle::new(Vec2::ZERO);
+        let other = Particle::new(Vec2::new(3.0, 0.0));
+        assert!((calculator.calculate_force(&gravity, &particle, Some(&other)) - Vec2::new(1.0, 0.0)).length() < 1e-5);
+    }
+
+    #[test]
+    fn test_fast_math_tracks_exact_forces_and_cuts_off() {
+        let mut calculator = ForceCalculator::new();
+        calculator.softening.insert(ForceKind::ElectroMagnetic, 2.0);
+        let particle = Particle::new(Vec2::ZERO).with_mass(2.0).with_charge(1.5);
+        let forces = [
+            ForceType::Gravity { strength: 40.0, min_distance: 5.0 },
+            ForceType::ElectroMagnetic { strength: -30.0, min_distance: 1.0 },
+        ];
+        let offsets = [Vec2::new(1.0, 0.5), Vec2::new(3.0, -4.0), Vec2::new(-70.0, 120.0), Vec2::new(0.01, 900.0)];
+        for force in &forces {
+            for offset in offsets {
+                let other = Particle::new(offset).with_mass(3.0).with_charge(-0.5);
+                calculator.fast_math.enabled = false;
+                let exact = calculator.calculate_force(force, &particle, Some(&other));
+                calculator.fast_math.enabled = true;
+                let fast = calculator.calculate_force(force, &particle, Some(&other));
+                assert!((fast - exact).length() <= exact.length() * 0.01, "{:?} at {:?}: {:?} vs {:?}", force, offset, fast, exact);
+            }
+        }
+
+        calculator.fast_math.cutoffs.insert(ForceKind::Gravity, 100.0);
+        let near = Particle::new(Vec2::new(60.0, 0.0));
+        let far = Particle::new(Vec2::new(60.0, 90.0)).with_charge(1.0);
+        assert!(calculator.calculate_force(&forces[0], &particle, Some(&near)).x > 0.0);
+        assert_eq!(calculator.calculate_force(&forces[0], &particle, Some(&far)), Vec2::ZERO);
+        assert_ne!(calculator.calculate_force(&forces[1], &particle, Some(&far)), Vec2::ZERO);
+    }
+
+    #[test]
+    fn test_symplectic_integrators_conserve_spring_energy() {
+        let (stiffness, rest_length) = (2.0, 100.0);
+        // Two unit masses on a spring oscillate at 2 rad/s; run for about 20 periods
+        let energy_drift = |method: IntegrationMethod| {
+            let mut engine = PhysicsEngine::new(PhysicsConfig { integration_method: method, spawn_ramp_time: 0.0, ..PhysicsConfig::default() });
+            engine.force_calculator.interaction_matrix.default_forces = vec![ForceType::Spring { rest_length, stiffness, damping: 0.0 }];
+            let mut system = ParticleSystem::new(2);
+            system.add_particle(Particle::new(Vec2::new(-60.0, 0.0)));
+            system.add_particle(Particle::new(Vec2::new(60.0, 0.0)));
+
+            let energy = |system: &ParticleSystem| {
+                let stretch = system.particles[0].distance_to(&system.particles[1]) - rest_length;
+                system.total_energy() + 0.5 * stiffness * stretch * stretch
+            };
+            let initial = energy(&system);
+            let mut worst: f32 = 0.0;
+            for _ in 0..3600 {
+                engine.update(&mut system);
+                worst = worst.max((energy(&system) / initial - 1.0).abs());
+            }
+            worst
+        };
+
+        assert!(energy_drift(IntegrationMethod::SymplecticEuler) < 0.05);
+        // Second order, so an order of magnitude tighter
+        assert!(energy_drift(IntegrationMethod::Leapfrog) < 0.005);
+        assert!(energy_drift(IntegrationMethod::Euler) > 0.5);
+    }
+
+    #[test]
+    fn test_verlet_state_follows_particles_through_deaths_and_spawns() {
+        let mut engine = PhysicsEngine::new(PhysicsConfig::default());
+        engine.force_calculator.interaction_matrix.default_forces.clear();
+        engine.wells = vec![GravityWell::new(Vec2::ZERO)];
+        let mut system = ParticleSystem::new(10);
+        for i in 0..4 {
+            system.add_particle(Particle::new(Vec2::new(60.0 + 30.0 * i as f32, 0.0)).with_velocity(Vec2::new(0.0, 20.0)));
+        }
+        let mut undisturbed = system.clone();
+
+        for step in 0..60 {
+            if step == 30 {
+                system.particles.remove(1);
+                system.add_particle(Particle::new(Vec2::new(0.0, 200.0)).with_velocity(Vec2::new(15.0, 0.0)));
+            }
+            engine.update(&mut system);
+            engine.update(&mut undisturbed);
+        }
+
+        // Survivors move exactly as if nothing happened, and the newcomer keeps its initial motion
+        assert_eq!(system.particles[0], undisturbed.particles[0]);
+        assert_eq!(system.particles[1..3], undisturbed.particles[2..4]);
+        assert!(system.particles[3].position.x > 5.0);
+    }
+
+    #[test]
+    fn test_rescaled_forces_keep_the_motion_similar() {
+        let calculator = ForceCalculator::new();
+        let forces = [
+            ForceType::Gravity { strength: 50.0, min_distance: 1.0 },
+            ForceType::LennardJones { epsilon: 2.0, sigma: 10.0 },
+            ForceType::Repulsion { strength: 20.0, max_distance: 30.0 },
+            ForceType::Spring { rest_length: 15.0, stiffness: 0.5, damping: 0.0 },
+        ];
+        // Doubling the scene doubles the distance, so the force at twice the distance should double
+        for force in &forces {
+            let original = calculator.pair_force_at(force, 12.0);
+            let stretched = calculator.pair_force_at(&force.rescaled(2.0), 24.0);
+            assert!((stretched - original * 2.0).abs() < original.abs() * 1e-4 + 1e-6, "{:?}", force);
+        }
+    }
+
+    #[test]
+    fn test_lennard_jones_repels_inside_and_attracts_beyond_equilibrium() {
+        let calculator = ForceCalculator::new();
+        let force = ForceType::LennardJones { epsilon: 1.0, sigma: 10.0 };
+        let equilibrium = 10.0 * 2f32.powf(1.0 / 6.0);
+        assert!(calculator.pair_force_at(&force, 9.0) > 0.0);
+        assert!(calculator.pair_force_at(&force, 15.0) < 0.0);
+        assert!(calculator.pair_force_at(&force, equilibrium).abs() < 1e-4);
     }
 
     #[test]
@@ -559,6 +1865,67 @@ mod tests {
         assert_eq!(force.y, 0.0);
     }
 
+    #[test]
+    fn test_pair_curve() {
+        let calculator = ForceCalculator::new();
+        let repulsion = ForceType::Repulsion { strength: 2.0, max_distance: 10.0 };
+        
+        assert!((calculator.pair_force_at(&repulsion, 5.0) - 1.0).abs() < 1e-5);
+        
+        let curve = calculator.pair_curve(&repulsion, repulsion.plot_range(), 50);
+        assert_eq!(curve.len(), 50);
+        assert_eq!(curve.last().unwrap().potential, 0.0);
+        assert!(curve.iter().filter(|p| p.distance > 10.0).all(|p| p.force == 0.0));
+        assert!(curve[0].potential > curve[20].potential);
+        
+        assert!(calculator.pair_curve(&ForceType::Brownian { intensity: 1.0 }, 10.0, 50).is_empty());
+    }
+
+    #[test]
+    fn test_seek_targets_arrives() {
+        let mut calculator = ForceCalculator::new();
+        calculator.add_global_force(ForceType::SeekTargets {
+            targets: vec![Vec2::new(100.0, 0.0)],
+            max_speed: 50.0,
+            max_force: 100.0,
+            arrival_radius: 20.0,
+        });
+        let mut system = ParticleSystem::new(10);
+        system.add_particle(Particle::new(Vec2::ZERO));
+        system.add_particle(Particle::new(Vec2::ZERO));
+        
+        calculator.apply_forces(&mut system);
+        // Only the first particle has a target; pair forces default to damping and Brownian noise
+        assert!(system.particles[0].acceleration.x > 40.0);
+        assert!(system.particles[1].acceleration.x.abs() < 1.0);
+        
+        // At the target and at rest there is nothing left to steer
+        let arrived = Particle::new(Vec2::new(100.0, 0.0));
+        assert_eq!(seek_force(&arrived, Vec2::new(100.0, 0.0), 50.0, 100.0, 20.0), Vec2::ZERO);
+        
+        let assigned = assign_targets(&[Vec2::new(10.0, 0.0), Vec2::new(-10.0, 0.0)], &[Vec2::new(-12.0, 0.0), Vec2::new(12.0, 0.0)]);
+        assert_eq!(assigned, vec![Vec2::new(12.0, 0.0), Vec2::new(-12.0, 0.0)]);
+    }
+
+    #[test]
+    fn test_force_counts_track_pairs_and_terms() {
+        let mut calculator = ForceCalculator::new();
+        let mut system = ParticleSystem::new(10);
+        for x in [0.0, 10.0, 20.0] {
+            system.add_particle(Particle::new(Vec2::new(x, 0.0)));
+        }
+        
+        calculator.apply_forces(&mut system);
+        let counts = calculator.take_counts();
+        assert_eq!(counts.candidate_pairs, 3);
+        assert!(counts.force_terms >= 3);
+        assert_eq!(calculator.take_counts(), ForceCounts::default());
+        
+        calculator.newtons_third_law = false;
+        calculator.apply_forces(&mut system);
+        assert_eq!(calculator.take_counts().candidate_pairs, 6);
+    }
+
     #[test]
     fn test_physics_engine() {
         let config = PhysicsConfig::default();
@@ -570,4 +1937,73 @@ mod tests {
         
         assert_eq!(system.particle_count(), 1);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_pair_forces_conserve_momentum() {
+        let mut calculator = ForceCalculator::new();
+        calculator.interaction_matrix.default_forces.clear();
+        calculator.interaction_matrix.add_interaction(0, 1, ForceType::Attraction { strength: 5.0, max_distance: 100.0 });
+        calculator.interaction_matrix.add_interaction(0, 0, ForceType::Gravity { strength: 50.0, min_distance: 1.0 });
+
+        let mut system = ParticleSystem::new(10);
+        system.add_particle(Particle::new(Vec2::ZERO).with_mass(2.0));
+        system.add_particle(Particle::new(Vec2::new(30.0, 5.0)).with_mass(0.5));
+        system.add_particle(Particle::new(Vec2::new(-10.0, 20.0)).with_species(1));
+        calculator.apply_forces(&mut system);
+
+        let net: Vec2 = system.particles.iter().map(|p| p.acceleration * p.mass).sum();
+        assert!(net.length() < 1e-4);
+        assert!(system.particles[0].acceleration.length() > 0.0);
+    }
+
+    #[test]
+    fn test_spawn_ramp_softens_new_particles() {
+        let mut calculator = ForceCalculator::new();
+        calculator.interaction_matrix.default_forces.clear();
+        calculator.interaction_matrix.add_interaction(0, 0, ForceType::Repulsion { strength: 10.0, max_distance: 50.0 });
+        calculator.spawn_ramp_time = 1.0;
+
+        let acceleration = |age: f32| {
+            let mut system = ParticleSystem::new(2);
+            let mut newcomer = Particle::new(Vec2::ZERO);
+            newcomer.age = age;
+            let mut settled = Particle::new(Vec2::new(10.0, 0.0));
+            settled.age = 5.0;
+            system.add_particle(newcomer);
+            system.add_particle(settled);
+            calculator.apply_forces(&mut system);
+            system.particles[1].acceleration.length()
+        };
+
+        assert_eq!(acceleration(0.0), 0.0);
+        assert!((acceleration(0.5) * 2.0 - acceleration(2.0)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_collisions_push_both_sides_whatever_the_order() {
+        let engine = PhysicsEngine::new(PhysicsConfig { collision_restitution: 1.0, ..PhysicsConfig::default() });
+        let collide = |particles: [Particle; 2]| {
+            let mut system = ParticleSystem::new(2);
+            for particle in particles {
+                system.add_particle(particle.with_size(10.0));
+            }
+            engine.handle_collisions(&mut system);
+            system.particles
+        };
+        let left = Particle::new(Vec2::ZERO).with_velocity(Vec2::new(5.0, 0.0));
+        let right = Particle::new(Vec2::new(6.0, 0.0)).with_velocity(Vec2::new(-5.0, 0.0)).with_mass(3.0);
+
+        let forward = collide([left, right]);
+        let backward = collide([right, left]);
+        assert_eq!((forward[0].position, forward[1].position), (Vec2::new(-2.0, 0.0), Vec2::new(8.0, 0.0)));
+        assert_eq!((forward[0].position, forward[0].velocity), (backward[1].position, backward[1].velocity));
+        assert_eq!((forward[1].position, forward[1].velocity), (backward[0].position, backward[0].velocity));
+
+        // An elastic bounce keeps momentum and energy: the light particle rebounds and the heavy
+        // one, three times its mass, stops dead
+        let momentum: Vec2 = forward.iter().map(Particle::momentum).sum();
+        assert!((momentum - Vec2::new(-10.0, 0.0)).length() < 1e-4);
+        assert!((forward[0].velocity - Vec2::new(-10.0, 0.0)).length() < 1e-4);
+        assert!(forward[1].velocity.length() < 1e-4);
+    }
+}
@@ -0,0 +1,75 @@
+use glam::Vec2;
+use crate::forces::{assign_targets, ForceType};
+
+// Where the target points of a formation come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormationSource {
+    // The current brush shape, with one point per particle
+    Brush,
+    // The text stamp settings
+    Text,
+    // The image import settings
+    Image,
+}
+
+impl FormationSource {
+    pub fn all() -> Vec<FormationSource> {
+        vec![FormationSource::Brush, FormationSource::Text, FormationSource::Image]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            FormationSource::Brush => "Brush Shape",
+            FormationSource::Text => "Text",
+            FormationSource::Image => "Image",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FormationOptions {
+    pub source: FormationSource,
+    pub max_speed: f32,
+    pub max_force: f32,
+    pub arrival_radius: f32,
+}
+
+impl Default for FormationOptions {
+    fn default() -> Self {
+        Self {
+            source: FormationSource::Brush,
+            max_speed: 80.0,
+            max_force: 200.0,
+            arrival_radius: 40.0,
+        }
+    }
+}
+
+impl FormationOptions {
+    // A SeekTargets force sending each particle to its nearest free target point
+    pub fn seek_force(&self, positions: &[Vec2], targets: &[Vec2]) -> ForceType {
+        ForceType::SeekTargets {
+            targets: assign_targets(positions, targets),
+            max_speed: self.max_speed,
+            max_force: self.max_force,
+            arrival_radius: self.arrival_radius,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seek_force_covers_every_particle() {
+        let positions: Vec<Vec2> = (0..10).map(|i| Vec2::new(i as f32, 0.0)).collect();
+        let targets = vec![Vec2::new(0.0, 50.0), Vec2::new(10.0, 50.0), Vec2::new(20.0, 50.0)];
+
+        let ForceType::SeekTargets { targets: assigned, .. } = FormationOptions::default().seek_force(&positions, &targets) else {
+            panic!("expected a SeekTargets force");
+        };
+        assert_eq!(assigned.len(), positions.len());
+        assert!(assigned.iter().all(|target| targets.contains(target)));
+    }
+}
@@ -0,0 +1,88 @@
+use glam::Vec2;
+use crate::particle::ParticleSystem;
+use crate::pool::ParticleId;
+
+// Spring settings for the grab tool. Stiffness and damping act on acceleration, like a stamp's
+// Hold spring, so light and heavy particles follow the cursor alike.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GrabOptions {
+    pub stiffness: f32,
+    pub damping: f32,
+    // How far from a particle, in points on screen, a click still grabs it
+    pub reach: f32,
+}
+
+impl Default for GrabOptions {
+    fn default() -> Self {
+        Self {
+            stiffness: 20.0,
+            damping: 6.0,
+            reach: 20.0,
+        }
+    }
+}
+
+// A particle held on a temporary spring to the cursor. The particle is tracked by id, so other
+// particles dying or spawning don't move the grab, and it lets go once its own particle is gone.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Grab {
+    pub id: ParticleId,
+    // World position the spring pulls toward
+    pub target: Vec2,
+}
+
+impl Grab {
+    // Grabs the particle nearest `position` within `reach` world units
+    pub fn nearest(system: &ParticleSystem, position: Vec2, reach: f32) -> Option<Self> {
+        system
+            .iter()
+            .filter(|(_, particle)| particle.position.distance(position) <= reach)
+            .min_by(|(_, a), (_, b)| a.position.distance_squared(position).total_cmp(&b.position.distance_squared(position)))
+            .map(|(id, _)| Self { id, target: position })
+    }
+
+    // Pulls the particle toward the target; returns false once it is gone
+    pub fn apply(&self, options: &GrabOptions, system: &mut ParticleSystem) -> bool {
+        let Some(particle) = system.get_mut(self.id) else {
+            return false;
+        };
+        let acceleration = (self.target - particle.position) * options.stiffness - particle.velocity * options.damping;
+        particle.apply_force(acceleration * particle.mass);
+        true
+    }
+
+    // The spring's two ends in world space, for drawing
+    pub fn segment(&self, system: &ParticleSystem) -> Option<(Vec2, Vec2)> {
+        system.get(self.id).map(|particle| (particle.position, self.target))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::particle::Particle;
+
+    #[test]
+    fn test_grab_picks_nearest_and_pulls_toward_cursor() {
+        let mut system = ParticleSystem::new(10);
+        system.add_particle(Particle::new(Vec2::new(0.0, 0.0)));
+        system.add_particle(Particle::new(Vec2::new(8.0, 0.0)).with_mass(4.0));
+        assert_eq!(Grab::nearest(&system, Vec2::new(30.0, 0.0), 10.0), None);
+
+        let mut grab = Grab::nearest(&system, Vec2::new(6.0, 0.0), 10.0).unwrap();
+        assert_eq!(grab.id, system.particles[1].id);
+        grab.target = Vec2::new(8.0, 50.0);
+        assert!(grab.apply(&GrabOptions::default(), &mut system));
+        // Mass doesn't change how hard the spring pulls it along
+        assert_eq!(system.particles[1].acceleration, Vec2::new(0.0, 50.0 * GrabOptions::default().stiffness));
+        assert_eq!(grab.segment(&system), Some((Vec2::new(8.0, 0.0), Vec2::new(8.0, 50.0))));
+
+        // Another particle dying moves the grabbed one into its slot, but the grab follows it
+        let first = system.particles[0].id;
+        system.remove_particle(first);
+        assert_eq!(grab.segment(&system), Some((Vec2::new(8.0, 0.0), Vec2::new(8.0, 50.0))));
+
+        system.remove_particle(grab.id);
+        assert!(!grab.apply(&GrabOptions::default(), &mut system));
+    }
+}
@@ -0,0 +1,175 @@
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::Stream;
+use tonic::{Request, Response, Status};
+use crate::checkpoint::HeadlessRun;
+use crate::config::{ConfigManager, Preset};
+use crate::sweep::apply_parameters;
+
+pub mod proto {
+    tonic::include_proto!("inochi");
+}
+
+use proto::simulation_service_server::{SimulationService, SimulationServiceServer};
+use proto::{Frame, StartSimRequest, StepRequest, StreamFramesRequest, UpdateConfigRequest, UpdateConfigResponse};
+
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:50051";
+// Caps the work a single request can ask for
+const MAX_STEPS_PER_REQUEST: u32 = 10_000;
+pub const MAX_PARTICLES_PER_REQUEST: u32 = 50_000;
+
+// One headless simulation shared by every connected client
+#[derive(Clone, Default)]
+pub struct SimulationServer {
+    run: Arc<Mutex<Option<HeadlessRun>>>,
+}
+
+impl SimulationServer {
+    fn with_run<T>(&self, f: impl FnOnce(&mut HeadlessRun) -> T) -> Result<T, Status> {
+        let mut run = self.run.lock().map_err(|_| Status::internal("Simulation state is poisoned"))?;
+        let run = run
+            .as_mut()
+            .ok_or_else(|| Status::failed_precondition("No simulation running; call StartSim first"))?;
+        Ok(f(run))
+    }
+
+    // Stepping is CPU-bound, so it runs off the async workers
+    async fn advance(&self, steps: u32) -> Result<Frame, Status> {
+        let server = self.clone();
+        let steps = steps.min(MAX_STEPS_PER_REQUEST) as u64;
+        tokio::task::spawn_blocking(move || {
+            server.with_run(|run| {
+                run.advance(steps);
+                frame(run)
+            })
+        })
+        .await
+        .map_err(|e| Status::internal(e.to_string()))?
+    }
+}
+
+fn frame(run: &HeadlessRun) -> Frame {
+    let particles = &run.system.particles;
+    Frame {
+        step: run.step,
+        positions: particles.iter().flat_map(|p| [p.position.x, p.position.y]).collect(),
+        velocities: particles.iter().flat_map(|p| [p.velocity.x, p.velocity.y]).collect(),
+        species: particles.iter().map(|p| p.species_id).collect(),
+    }
+}
+
+#[tonic::async_trait]
+impl SimulationService for SimulationServer {
+    async fn start_sim(&self, request: Request<StartSimRequest>) -> Result<Response<Frame>, Status> {
+        let request = request.into_inner();
+        let preset = Preset::from_key(&request.preset)
+            .ok_or_else(|| Status::invalid_argument(format!("Unknown preset: {}", request.preset)))?;
+
+        if let Some(count) = request.particle_count.filter(|&count| count > MAX_PARTICLES_PER_REQUEST) {
+            return Err(Status::invalid_argument(format!("{} particles requested; at most {} allowed", count, MAX_PARTICLES_PER_REQUEST)));
+        }
+
+        let mut manager = ConfigManager::new();
+        manager.apply_preset(preset.clone());
+        let mut config = manager.config().clone();
+        if let Some(count) = request.particle_count {
+            config.particles.initial_particle_count = count as usize;
+            config.particles.max_particles = config.particles.max_particles.max(count as usize);
+        }
+
+        // Building the preset (and any warm-up steps) is CPU-bound too
+        let seed = request.seed.unwrap_or_else(rand::random);
+        let run = tokio::task::spawn_blocking(move || HeadlessRun::new(preset, config, seed))
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let frame = frame(&run);
+        *self.run.lock().map_err(|_| Status::internal("Simulation state is poisoned"))? = Some(run);
+        Ok(Response::new(frame))
+    }
+
+    async fn step(&self, request: Request<StepRequest>) -> Result<Response<Frame>, Status> {
+        self.advance(request.into_inner().steps).await.map(Response::new)
+    }
+
+    type StreamFramesStream = Pin<Box<dyn Stream<Item = Result<Frame, Status>> + Send>>;
+
+    async fn stream_frames(&self, request: Request<StreamFramesRequest>) -> Result<Response<Self::StreamFramesStream>, Status> {
+        let request = request.into_inner();
+        self.with_run(|_| ())?;
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(4);
+        let server = self.clone();
+        let interval = Duration::from_millis((request.interval_ms as u64).max(1));
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let frame = server.advance(request.steps_per_frame).await;
+                let failed = frame.is_err();
+                // Stops once the client disconnects or the simulation goes away
+                if sender.send(frame).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(ReceiverStream::new(receiver))))
+    }
+
+    async fn update_config(&self, request: Request<UpdateConfigRequest>) -> Result<Response<UpdateConfigResponse>, Status> {
+        let parameters: Vec<(String, f64)> = request
+            .into_inner()
+            .parameters
+            .into_iter()
+            .map(|parameter| (parameter.path, parameter.value))
+            .collect();
+
+        // All or nothing: a bad path leaves the config untouched
+        self.with_run(|run| {
+            run.config = apply_parameters(&run.config, &parameters)?;
+            run.apply_config();
+            Ok(())
+        })?
+        .map_err(Status::invalid_argument)?;
+        Ok(Response::new(UpdateConfigResponse {}))
+    }
+}
+
+pub async fn serve(address: SocketAddr) -> Result<(), String> {
+    tonic::transport::Server::builder()
+        .add_service(SimulationServiceServer::new(SimulationServer::default()))
+        .serve(address)
+        .await
+        .map_err(|e| format!("gRPC server failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proto::ConfigParameter;
+
+    #[tokio::test]
+    async fn test_start_step_and_update_config() {
+        let server = SimulationServer::default();
+        assert!(server.step(Request::new(StepRequest { steps: 1 })).await.is_err());
+
+        let too_many = StartSimRequest { preset: "brownian".to_string(), seed: Some(3), particle_count: Some(MAX_PARTICLES_PER_REQUEST + 1) };
+        assert_eq!(server.start_sim(Request::new(too_many)).await.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+        let start = StartSimRequest { preset: "brownian".to_string(), seed: Some(3), particle_count: Some(20) };
+        let frame = server.start_sim(Request::new(start)).await.unwrap().into_inner();
+        assert_eq!(frame.positions.len(), frame.species.len() * 2);
+
+        let frame = server.step(Request::new(StepRequest { steps: 5 })).await.unwrap().into_inner();
+        assert_eq!(frame.step, 5);
+
+        let update = |path: &str| UpdateConfigRequest {
+            parameters: vec![ConfigParameter { path: path.to_string(), value: 0.01 }],
+        };
+        assert!(server.update_config(Request::new(update("physics.dt"))).await.is_ok());
+        assert!(server.update_config(Request::new(update("physics.no_such_field"))).await.is_err());
+        assert_eq!(server.with_run(|run| run.config.physics.dt).unwrap(), 0.01);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::config::SimulationConfig;
+use crate::forces::{ForceCalculator, ForceType};
+
+pub const DEFAULT_HEATMAP_FILE: &str = "interaction_heatmap.png";
+// Distances sampled per pair when averaging its forces
+const SAMPLES: usize = 32;
+// Pixels per cell in the exported image
+#[cfg(feature = "desktop")]
+const CELL_SIZE: u32 = 32;
+const NEUTRAL: [u8; 3] = [40, 40, 40];
+const ATTRACTION: [u8; 3] = [60, 200, 90];
+const REPULSION: [u8; 3] = [220, 60, 60];
+
+// How strongly each species is pulled toward each other one, averaged over the distances their
+// pair rules reach; positive attracts, negative repels
+#[derive(Debug, Clone, PartialEq)]
+pub struct InteractionHeatmap {
+    pub species: Vec<u32>,
+    pub colors: Vec<[f32; 4]>,
+    // Row-major: values[row * len + column] is the pull species `row` feels toward `column`
+    pub values: Vec<f32>,
+}
+
+impl InteractionHeatmap {
+    pub fn from_config(config: &SimulationConfig) -> Self {
+        let mut calculator = ForceCalculator::new();
+        calculator.interaction_matrix = config.forces.interaction_matrix();
+        calculator.softening.clone_from(&config.physics.softening);
+
+        let species = config.species.ids();
+        let colors = species.iter().map(|&id| config.species.color(id)).collect();
+        let values = species
+            .iter()
+            .flat_map(|&a| species.iter().map(move |&b| (a, b)))
+            .map(|(a, b)| net_attraction(&calculator, a, b))
+            .collect();
+        Self { species, colors, values }
+    }
+
+    pub fn len(&self) -> usize {
+        self.species.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.species.is_empty()
+    }
+
+    pub fn value(&self, row: usize, column: usize) -> f32 {
+        self.values[row * self.len() + column]
+    }
+
+    // Largest pull or push, which the colours are scaled to
+    pub fn max_magnitude(&self) -> f32 {
+        self.values.iter().fold(0.0, |max, value| max.max(value.abs()))
+    }
+
+    // Green for attraction, red for repulsion, fading to grey as the pair gets weaker
+    pub fn color(&self, row: usize, column: usize) -> [u8; 3] {
+        let max = self.max_magnitude();
+        if max <= 0.0 {
+            return NEUTRAL;
+        }
+        let t = self.value(row, column) / max;
+        let target = if t >= 0.0 { ATTRACTION } else { REPULSION };
+        std::array::from_fn(|channel| (NEUTRAL[channel] as f32 + (target[channel] as f32 - NEUTRAL[channel] as f32) * t.abs()).round() as u8)
+    }
+
+    // The matrix with a strip of species colours along the top and left, for documenting rules
+    #[cfg(feature = "desktop")]
+    pub fn to_image(&self) -> image::RgbaImage {
+        let size = (self.len() as u32 + 1) * CELL_SIZE;
+        image::RgbaImage::from_fn(size, size, |x, y| {
+            let (row, column) = ((y / CELL_SIZE) as usize, (x / CELL_SIZE) as usize);
+            let [r, g, b] = match (row, column) {
+                (0, 0) => [0, 0, 0],
+                (0, species) | (species, 0) => {
+                    let color = self.colors[species - 1];
+                    std::array::from_fn(|channel| (color[channel].clamp(0.0, 1.0) * 255.0).round() as u8)
+                },
+                (row, column) => self.color(row - 1, column - 1),
+            };
+            image::Rgba([r, g, b, 255])
+        })
+    }
+
+    #[cfg(feature = "desktop")]
+    pub fn save_png(&self, path: &str) -> Result<(), String> {
+        self.to_image()
+            .save_with_format(path, image::ImageFormat::Png)
+            .map_err(|e| format!("Failed to save {}: {}", path, e))
+    }
+}
+
+// Mean of the radial force on `a` from `b` over the reach of their pair rules, attraction positive
+fn net_attraction(calculator: &ForceCalculator, a: u32, b: u32) -> f32 {
+    let reach = calculator.interaction_matrix
+        .get_forces(a, b)
+        .iter()
+        .filter(|force| force.is_pair_force())
+        .map(ForceType::plot_range)
+        .fold(0.0, f32::max);
+    if reach <= 0.0 {
+        return 0.0;
+    }
+    let total: f32 = (1..=SAMPLES)
+        .map(|i| calculator.species_pair_force(a, b, reach * i as f32 / SAMPLES as f32))
+        .sum();
+    -total / SAMPLES as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigManager;
+
+    #[test]
+    fn test_heatmap_signs_and_image() {
+        let mut manager = ConfigManager::new();
+        manager.randomize_particle_life(2);
+        let config = manager.config_mut();
+        let ids = config.species.ids();
+        let interactions = &mut config.forces.species_interactions;
+        interactions.clear();
+        interactions.insert((ids[0], ids[1]), vec![ForceType::Attraction { strength: 5.0, max_distance: 50.0 }]);
+        interactions.insert((ids[1], ids[1]), vec![ForceType::Repulsion { strength: 5.0, max_distance: 50.0 }]);
+
+        let heatmap = InteractionHeatmap::from_config(manager.config());
+        assert_eq!(heatmap.len(), 2);
+        assert!(heatmap.value(0, 1) > 0.0);
+        assert!(heatmap.value(1, 1) < 0.0);
+        assert_eq!(heatmap.value(0, 0), 0.0);
+        assert_eq!(heatmap.color(0, 0), NEUTRAL);
+        assert!(heatmap.color(0, 1)[1] > heatmap.color(0, 1)[0]);
+        assert!(heatmap.color(1, 1)[0] > heatmap.color(1, 1)[1]);
+
+        #[cfg(feature = "desktop")]
+        {
+            let image = heatmap.to_image();
+            assert_eq!(image.dimensions(), (3 * CELL_SIZE, 3 * CELL_SIZE));
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("heatmap.png");
+            heatmap.save_png(path.to_str().unwrap()).unwrap();
+            assert!(path.exists());
+        }
+    }
+}
@@ -0,0 +1,295 @@
+#[cfg(feature = "checkpoint")]
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use crate::config::SimulationConfig;
+use crate::particle::{Particle, ParticleSystem};
+
+pub const RECORDING_EXTENSION: &str = "inochirec";
+const RECORDING_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryConfig {
+    pub enabled: bool,
+    pub duration_seconds: f32,
+    pub memory_cap_mb: usize,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            duration_seconds: 10.0,
+            memory_cap_mb: 256,
+        }
+    }
+}
+
+struct HistoryFrame {
+    time: f32,
+    system: ParticleSystem,
+    bytes: usize,
+}
+
+// Ring buffer of recent simulation states, recorded after every physics step.
+// The newest frame always matches the live state until the user scrubs back.
+#[derive(Default)]
+pub struct SimulationHistory {
+    frames: VecDeque<HistoryFrame>,
+    total_bytes: usize,
+    cursor: Option<usize>,
+}
+
+impl SimulationHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn estimate_bytes(system: &ParticleSystem) -> usize {
+        std::mem::size_of::<ParticleSystem>() + system.particles.len() * std::mem::size_of::<Particle>()
+    }
+
+    pub fn record(&mut self, system: &ParticleSystem, time: f32, config: &HistoryConfig) {
+        if !config.enabled || self.is_scrubbing() {
+            return;
+        }
+
+        let bytes = Self::estimate_bytes(system);
+        self.frames.push_back(HistoryFrame {
+            time,
+            system: system.clone(),
+            bytes,
+        });
+        self.total_bytes += bytes;
+
+        // Trim by age and memory, always keeping the newest frame
+        let memory_cap = config.memory_cap_mb * 1024 * 1024;
+        while self.frames.len() > 1 {
+            let oldest = &self.frames[0];
+            let too_old = time - oldest.time > config.duration_seconds;
+            if !too_old && self.total_bytes <= memory_cap {
+                break;
+            }
+            self.total_bytes -= oldest.bytes;
+            self.frames.pop_front();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn memory_bytes(&self) -> usize {
+        self.total_bytes
+    }
+
+    pub fn buffered_seconds(&self) -> f32 {
+        match (self.frames.front(), self.frames.back()) {
+            (Some(first), Some(last)) => last.time - first.time,
+            _ => 0.0,
+        }
+    }
+
+    pub fn is_scrubbing(&self) -> bool {
+        self.cursor.is_some()
+    }
+
+    // Index shown on the timeline; the newest frame when not scrubbing
+    pub fn position(&self) -> usize {
+        self.cursor.unwrap_or_else(|| self.frames.len().saturating_sub(1))
+    }
+
+    // Seconds between the given frame and the newest one
+    pub fn offset_seconds(&self, index: usize) -> f32 {
+        match (self.frames.get(index), self.frames.back()) {
+            (Some(frame), Some(last)) => frame.time - last.time,
+            _ => 0.0,
+        }
+    }
+
+    pub fn seek(&mut self, index: usize) -> Option<&ParticleSystem> {
+        if index >= self.frames.len() {
+            return None;
+        }
+        self.cursor = Some(index);
+        self.frames.get(index).map(|frame| &frame.system)
+    }
+
+    pub fn step_back(&mut self) -> Option<&ParticleSystem> {
+        let position = self.position();
+        if position == 0 {
+            return None;
+        }
+        self.seek(position - 1)
+    }
+
+    // Moves forward through buffered frames; None once the newest frame is reached
+    pub fn step_forward(&mut self) -> Option<&ParticleSystem> {
+        let cursor = self.cursor?;
+        if cursor + 1 >= self.frames.len() {
+            return None;
+        }
+        self.seek(cursor + 1)
+    }
+
+    // Resumes recording from the scrubbed frame, discarding everything after it
+    pub fn resume(&mut self) {
+        if let Some(cursor) = self.cursor.take() {
+            while self.frames.len() > cursor + 1 {
+                if let Some(frame) = self.frames.pop_back() {
+                    self.total_bytes -= frame.bytes;
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.total_bytes = 0;
+        self.cursor = None;
+    }
+
+    // Every buffered frame, with times relative to the oldest one
+    pub fn recording(&self, config: &SimulationConfig) -> Recording {
+        let start = self.frames.front().map(|frame| frame.time).unwrap_or(0.0);
+        Recording {
+            version: RECORDING_VERSION,
+            config: config.clone(),
+            frames: self
+                .frames
+                .iter()
+                .map(|frame| RecordedFrame {
+                    time: frame.time - start,
+                    particles: frame.system.particles.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    pub time: f32,
+    pub particles: Vec<Particle>,
+}
+
+// Buffered history exported for sharing, stored as gzipped bincode like checkpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recording {
+    pub version: u32,
+    pub config: SimulationConfig,
+    pub frames: Vec<RecordedFrame>,
+}
+
+#[cfg(feature = "checkpoint")]
+impl Recording {
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        bincode::serialize_into(&mut encoder, self).map_err(|e| format!("Failed to encode recording: {}", e))?;
+        encoder.finish().map_err(|e| format!("Failed to encode recording: {}", e))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let recording: Recording = bincode::deserialize_from(GzDecoder::new(bytes))
+            .map_err(|e| format!("Invalid recording: {}", e))?;
+        if recording.version != RECORDING_VERSION {
+            return Err(format!("Unsupported recording version {}", recording.version));
+        }
+        Ok(recording)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec2;
+
+    fn enabled_config() -> HistoryConfig {
+        HistoryConfig {
+            enabled: true,
+            ..HistoryConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_history_trims_by_duration() {
+        let config = HistoryConfig {
+            duration_seconds: 1.0,
+            ..enabled_config()
+        };
+        let mut history = SimulationHistory::new();
+        let system = ParticleSystem::new(10);
+
+        // Nothing is kept until recording is enabled
+        history.record(&system, 0.0, &HistoryConfig::default());
+        assert!(history.is_empty());
+
+        for i in 0..20 {
+            history.record(&system, i as f32 * 0.25, &config);
+        }
+        assert_eq!(history.buffered_seconds(), 1.0);
+        assert_eq!(history.len(), 5);
+    }
+
+    #[test]
+    #[cfg(feature = "checkpoint")]
+    fn test_recording_round_trip() {
+        let mut history = SimulationHistory::new();
+        let mut system = ParticleSystem::new(10);
+        system.add_particle(Particle::new(Vec2::new(1.0, 2.0)));
+        for i in 0..3 {
+            history.record(&system, 5.0 + i as f32, &enabled_config());
+        }
+
+        let bytes = history.recording(&SimulationConfig::default()).to_bytes().unwrap();
+        let recording = Recording::from_bytes(&bytes).unwrap();
+        assert_eq!(recording.frames.len(), 3);
+        assert_eq!(recording.frames[0].time, 0.0);
+        assert_eq!(recording.frames[2].particles, system.particles);
+        assert!(Recording::from_bytes(b"garbage").is_err());
+    }
+
+    #[test]
+    fn test_history_trims_by_memory() {
+        let config = HistoryConfig {
+            memory_cap_mb: 0,
+            ..enabled_config()
+        };
+        let mut history = SimulationHistory::new();
+        let system = ParticleSystem::new(10);
+
+        for i in 0..5 {
+            history.record(&system, i as f32, &config);
+        }
+        assert_eq!(history.len(), 1);
+    }
+
+    #[test]
+    fn test_scrub_and_resume() {
+        let config = enabled_config();
+        let mut history = SimulationHistory::new();
+        let mut system = ParticleSystem::new(10);
+
+        for i in 0..4 {
+            system.add_particle(Particle::new(Vec2::new(i as f32, 0.0)));
+            history.record(&system, i as f32 * 0.1, &config);
+        }
+
+        assert_eq!(history.step_back().unwrap().particle_count(), 3);
+        assert_eq!(history.step_back().unwrap().particle_count(), 2);
+        assert_eq!(history.step_forward().unwrap().particle_count(), 3);
+
+        // Recording is suspended while scrubbing
+        history.record(&system, 1.0, &config);
+        assert_eq!(history.len(), 4);
+
+        history.resume();
+        assert!(!history.is_scrubbing());
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.seek(2).unwrap().particle_count(), 3);
+    }
+}
@@ -0,0 +1,560 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// UI strings are keyed by their English text; languages without an entry fall back to English
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    Japanese,
+}
+
+impl Language {
+    pub fn all() -> Vec<Language> {
+        vec![Language::English, Language::Japanese]
+    }
+
+    pub fn native_name(&self) -> &str {
+        match self {
+            Language::English => "English",
+            Language::Japanese => "日本語",
+        }
+    }
+
+    // Built on first use; tr runs for every label of every frame
+    fn table(&self) -> Option<&'static HashMap<&'static str, &'static str>> {
+        static JAPANESE_TABLE: OnceLock<HashMap<&str, &str>> = OnceLock::new();
+        match self {
+            Language::English => None,
+            Language::Japanese => Some(JAPANESE_TABLE.get_or_init(|| JAPANESE.iter().copied().collect())),
+        }
+    }
+}
+
+pub fn tr(language: Language, text: &str) -> &str {
+    language
+        .table()
+        .and_then(|table| table.get(text).copied())
+        .unwrap_or(text)
+}
+
+const JAPANESE: &[(&str, &str)] = &[
+    // Main panel
+    ("🎆 Inochi Particle Life", "🎆 Inochi パーティクルライフ"),
+    ("Simulation", "シミュレーション"),
+    ("▶️ Play", "▶️ 再生"),
+    ("⏸️ Pause", "⏸️ 一時停止"),
+    ("🔄 Reset", "🔄 リセット"),
+    ("⏮ Step Back", "⏮ 1ステップ戻る"),
+    ("⏭ Step", "⏭ 1ステップ進む"),
+    ("Speed ×", "速度 ×"),
+    ("Particles", "粒子"),
+    ("📊 Performance", "📊 パフォーマンス"),
+    ("Record History", "履歴を記録"),
+    ("Buffer (s)", "バッファ (秒)"),
+    ("Memory Cap (MB)", "メモリ上限 (MB)"),
+    ("buffered", "記録済み"),
+    (" • press Play to resume here", " • 再生するとここから再開します"),
+    // Presets
+    ("Presets", "プリセット"),
+    ("Current", "現在"),
+    ("Custom", "カスタム"),
+    ("🖼️ Preset Gallery", "🖼️ プリセットギャラリー"),
+    ("Species", "種"),
+    ("🎲 Random Particle Life", "🎲 ランダムパーティクルライフ"),
+    ("🧬 Evolution", "🧬 進化"),
+    ("Fitness", "適応度"),
+    ("Cluster Count", "クラスター数"),
+    ("Diversity", "多様性"),
+    ("Movement Entropy", "運動エントロピー"),
+    ("Population", "個体数"),
+    ("Elite", "エリート"),
+    ("Mutation Rate", "突然変異率"),
+    ("Mutation Scale", "突然変異の大きさ"),
+    ("Steps per Run", "1回あたりのステップ数"),
+    ("⏹ Stop", "⏹ 停止"),
+    ("▶ Start Search", "▶ 探索を開始"),
+    ("Generation", "世代"),
+    ("Custom Presets", "カスタムプリセット"),
+    ("🔄 Refresh Custom Presets", "🔄 カスタムプリセットを再読み込み"),
+    ("Apply", "適用"),
+    // Scenes
+    ("🎬 Scenes", "🎬 シーン"),
+    ("Name", "名前"),
+    ("Notes", "メモ"),
+    ("💾 Save Current Scene", "💾 現在のシーンを保存"),
+    ("🔄 Refresh", "🔄 再読み込み"),
+    ("No saved scenes", "保存されたシーンはありません"),
+    ("Load", "読み込み"),
+    ("Duplicate", "複製"),
+    ("Delete", "削除"),
+    ("Confirm", "確認"),
+    // Particles and forces
+    ("Spawn Rate", "生成レート"),
+    ("Max Particles", "最大粒子数"),
+    ("Default Size", "デフォルトサイズ"),
+    ("Warm-Up Steps", "ウォームアップのステップ数"),
+    ("🎆 Bursts", "🎆 バースト"),
+    ("Interval", "間隔"),
+    ("Particles per Burst", "バーストあたりの粒子数"),
+    ("Min Speed", "最小速度"),
+    ("Lifespan", "寿命"),
+    ("🐜 Agents", "🐜 エージェント"),
+    ("Neighbor Radius", "近傍の半径"),
+    ("🍄 Pheromones", "🍄 フェロモン"),
+    ("Show Trails", "軌跡を表示"),
+    ("Cell Size", "セルサイズ"),
+    ("Deposit", "分泌量"),
+    ("Diffusion", "拡散"),
+    ("Evaporation", "蒸発"),
+    ("Sensor Distance", "センサー距離"),
+    ("Sensor Angle", "センサー角度"),
+    ("Turn Rate", "旋回速度"),
+    ("🧪 Gray-Scott", "🧪 Gray-Scott反応拡散"),
+    ("Reseed", "再シード"),
+    ("Show Field", "場を表示"),
+    ("Feed", "供給率"),
+    ("Kill", "除去率"),
+    ("Diffusion U", "拡散 U"),
+    ("Diffusion V", "拡散 V"),
+    ("Reaction Steps", "反応ステップ数"),
+    ("Push", "押す力"),
+    ("🍽 Metabolism", "🍽 代謝"),
+    ("Initial Energy", "初期エネルギー"),
+    ("Max Energy", "最大エネルギー"),
+    ("Basal Cost", "基礎代謝"),
+    ("Movement Cost", "移動コスト"),
+    ("Force Cost", "力のコスト"),
+    ("Eat Radius", "摂食半径"),
+    ("Food Energy", "餌のエネルギー"),
+    ("Food per Second", "毎秒の餌の数"),
+    ("🧵 Cloth", "🧵 布"),
+    ("Reweave", "織り直す"),
+    ("Show Links", "リンクを表示"),
+    ("Tear Stretch", "破れる伸び率"),
+    ("Shear Links", "せん断リンク"),
+    ("Pin Top Row", "上端の行を固定"),
+    ("Reweaving links the particles where they are now", "織り直すと現在の位置で粒子をつなぎます"),
+    ("Rule Code", "ルールコード"),
+    ("📋 Copy Rule Code", "📋 ルールコードをコピー"),
+    ("Paste a rule code", "ルールコードを貼り付け"),
+    ("📥 Load", "📥 読み込む"),
+    ("🌐 Community", "🌐 コミュニティ"),
+    ("Server", "サーバー"),
+    ("Show", "表示"),
+    ("All", "すべて"),
+    ("Preset", "プリセット"),
+    ("Nothing shared yet", "まだ共有されたものはありません"),
+    ("by", "作者:"),
+    ("Share", "共有"),
+    ("Author", "作者"),
+    ("📤 Share Rule Code", "📤 ルールコードを共有"),
+    ("📤 Share Preset", "📤 プリセットを共有"),
+    ("🖥 Display", "🖥 ディスプレイ"),
+    ("Fullscreen (F11)", "全画面表示 (F11)"),
+    ("Borderless Window", "枠なしウィンドウ"),
+    ("Always on Top", "常に最前面"),
+    ("Controls in Separate Window", "操作パネルを別ウィンドウに表示"),
+    ("Monitor", "モニター"),
+    ("🎞 Demo Mode", "🎞 デモモード"),
+    ("Seconds per Preset", "プリセットごとの秒数"),
+    ("Random Rules Chance", "ランダムルールの確率"),
+    ("Idle Reset (s)", "無操作でリセット (秒)"),
+    ("Shuffle", "シャッフル"),
+    ("Presets (none ticked plays them all)", "プリセット (未選択ならすべて再生)"),
+    ("▶ Start Demo", "▶ デモを開始"),
+    ("Hides the UI; press Esc to leave", "UIを隠します。Escで終了"),
+    ("⏳ Lifespan", "⏳ 寿命"),
+    ("Finite Lifespan", "有限の寿命"),
+    ("Lifespan Variation", "寿命のばらつき"),
+    ("Applies to particles created from now on", "これから生成される粒子に適用されます"),
+    ("⏳ Age Ramp", "⏳ 年齢による変化"),
+    ("Fade with Age", "年齢とともに薄くする"),
+    ("Recolor with Age", "年齢とともに色を変える"),
+    ("Size at End of Life", "寿命の終わりのサイズ"),
+    ("Warming Up", "ウォームアップ中"),
+    ("Skip", "スキップ"),
+    ("🖼 Import Image", "🖼 画像をインポート"),
+    ("File", "ファイル"),
+    ("Threshold On", "しきい値の基準"),
+    ("Alpha", "アルファ"),
+    ("Brightness", "明るさ"),
+    ("Threshold", "しきい値"),
+    ("Pixel Spacing", "ピクセル間隔"),
+    ("Width", "幅"),
+    ("Replace Existing Particles", "既存の粒子を置き換える"),
+    ("📥 Import", "📥 インポート"),
+    ("✏️ Stamp Text", "✏️ テキストをスタンプ"),
+    ("Font", "フォント"),
+    ("Particle Spacing", "粒子の間隔"),
+    ("Free", "自由"),
+    ("Disperse", "拡散"),
+    ("Hold", "保持"),
+    ("Speed", "速度"),
+    ("✏️ Stamp", "✏️ スタンプ"),
+    ("Release", "解放"),
+    ("🖌 Brush", "🖌 ブラシ"),
+    ("Click to paint", "クリックで描画"),
+    ("Shape", "形状"),
+    ("Rectangle", "長方形"),
+    ("Circle", "円"),
+    ("Ring", "リング"),
+    ("Spiral", "渦巻き"),
+    ("Grid", "格子"),
+    ("Gaussian Blob", "ガウス分布"),
+    ("Half Width", "半幅"),
+    ("Half Height", "半高さ"),
+    ("Radius", "半径"),
+    ("Inner Radius", "内半径"),
+    ("Turns", "巻き数"),
+    ("Arms", "腕の数"),
+    ("Spread", "広がり"),
+    ("Density", "密度"),
+    ("🔍 Region Probe", "🔍 領域プローブ"),
+    ("Drag to place probe", "ドラッグしてプローブを配置"),
+    ("Density (per 100×100)", "密度 (100×100 あたり)"),
+    ("Mean Velocity", "平均速度ベクトル"),
+    ("Mean Speed", "平均速さ"),
+    ("🖌 Stamp at View Center", "🖌 画面中央にスタンプ"),
+    ("🎯 Formation", "🎯 フォーメーション"),
+    ("Steer the existing particles into a shape", "既存の粒子を形に誘導します"),
+    ("Targets From", "ターゲットの元"),
+    ("Brush Shape", "ブラシの形状"),
+    ("Text", "テキスト"),
+    ("Image", "画像"),
+    ("Max Speed", "最大速度"),
+    ("Max Force", "最大の力"),
+    ("Arrival Radius", "到着半径"),
+    ("🎯 Seek", "🎯 誘導"),
+    ("Forces", "力"),
+    ("Gravity", "重力"),
+    ("Gravity Strength", "重力の強さ"),
+    ("Damping", "減衰"),
+    ("Brownian Motion", "ブラウン運動"),
+    ("Brownian Intensity", "ブラウン運動の強さ"),
+    ("Wind", "風"),
+    ("Direction (°)", "方向 (°)"),
+    ("Wind Strength", "風の強さ"),
+    ("Gustiness", "突風の強さ"),
+    ("Turbulence", "乱流"),
+    ("Turbulence Strength", "乱流の強さ"),
+    ("Eddy Size", "渦の大きさ"),
+    ("Octaves", "オクターブ数"),
+    ("Drift Speed", "流れの速さ"),
+    ("Drag", "抗力"),
+    ("Profile", "プロファイル"),
+    ("Linear", "線形"),
+    ("Quadratic", "二次"),
+    ("Drag Coefficient", "抗力係数"),
+    ("Size Exponent", "サイズ指数"),
+    ("🧭 Steering", "🧭 ステアリング"),
+    ("🌡 Zones", "🌡 ゾーン"),
+    ("🪐 Gravity Wells", "🪐 重力井戸"),
+    ("Click to place wells", "クリックで井戸を配置"),
+    ("Drag to move, right-click to delete", "ドラッグで移動、右クリックで削除"),
+    ("Softening", "ソフトニング"),
+    ("Electromagnetic", "電磁気"),
+    ("Lennard-Jones", "レナード＝ジョーンズ"),
+    ("Clamped Forces", "制限された力"),
+    ("Reset", "リセット"),
+    ("Forces are hitting Max Force; try more softening or a smaller time step", "力が最大の力に達しています。ソフトニングを増やすか時間ステップを小さくしてください"),
+    ("Units", "単位"),
+    ("Show Physical Units", "物理単位で表示"),
+    ("Pixels per Meter", "1メートルあたりのピクセル"),
+    ("Seconds per Simulated Second", "シミュレーション1秒あたりの秒数"),
+    ("kg per Mass Unit", "質量単位あたりのkg"),
+    ("C per Charge Unit", "電荷単位あたりのC"),
+    ("Apply Physical Constants", "物理定数を適用"),
+    ("Rescale Scene", "シーンを拡大縮小"),
+    ("Integrator", "積分法"),
+    ("Euler", "オイラー法"),
+    ("Verlet", "ベルレ法"),
+    ("Symplectic Euler", "シンプレクティック・オイラー法"),
+    ("Leapfrog", "リープフロッグ法"),
+    ("Runge-Kutta 4", "4次ルンゲ＝クッタ法"),
+    ("Integrator Comparison", "積分法の比較"),
+    ("Compare With", "比較対象"),
+    ("Start", "開始"),
+    ("Restart", "やり直す"),
+    ("Divergence", "乖離"),
+    ("🗑️ Clear Wells", "🗑️ 井戸をすべて削除"),
+    ("➕ Add Zone", "➕ ゾーンを追加"),
+    ("Gravity Well", "重力井戸"),
+    ("Temperature", "温度"),
+    ("Force Multiplier", "力の倍率"),
+    ("Food", "餌"),
+    ("Energy per Second", "毎秒のエネルギー"),
+    ("Amount", "量"),
+    ("Factor", "倍率"),
+    ("Center", "中心"),
+    ("Enabled", "有効"),
+    ("Weight", "重み"),
+    ("➕ Add Behavior", "➕ 行動を追加"),
+    ("Wander", "徘徊"),
+    ("Pursue", "追跡"),
+    ("Evade", "回避"),
+    ("Follow Path", "経路追従"),
+    ("Separate", "分離"),
+    ("Align", "整列"),
+    ("Cohere", "結合"),
+    ("Paths", "経路"),
+    ("points", "点"),
+    ("Closed", "閉じる"),
+    ("Click in the world to add points", "ワールドをクリックして点を追加"),
+    ("✔ Finish Path", "✔ 経路を完了"),
+    ("✏️ Draw New Path", "✏️ 新しい経路を描く"),
+    ("Distance", "距離"),
+    ("📏 Measure", "📏 計測"),
+    ("Drag to measure", "ドラッグして計測"),
+    ("Species A", "種 A"),
+    ("Species B", "種 B"),
+    ("Rate", "速さ"),
+    ("Target Species", "対象の種"),
+    ("Draw a path below first", "先に下で経路を描いてください"),
+    ("Path", "経路"),
+    ("Lookahead", "先読み距離"),
+    ("🔧 Force Editor", "🔧 力エディタ"),
+    ("Interactions", "相互作用"),
+    ("One-Sided Rules", "一方向のルール"),
+    ("Each species reacts to the other by its own rule, so A can chase B while B flees A", "それぞれの種が相手に独自のルールで反応します。AがBを追い、BがAから逃げることもできます"),
+    ("No pair forces in this preset", "このプリセットには粒子間の力がありません"),
+    ("Green attracts, red repels. Click a cell to edit that pair.", "緑は引力、赤は斥力。セルをクリックするとそのペアを編集できます。"),
+    ("🖼 Export Heatmap", "🖼 ヒートマップを書き出す"),
+    ("➰ Convert to Profile", "➰ プロファイルに変換"),
+    ("Replace this force with an editable curve of the same shape", "この力を同じ形の編集可能な曲線に置き換えます"),
+    ("Max Pair Force", "ペアの力の上限"),
+    ("Limit the summed pair forces between these species", "これらの種の間の粒子間力の合計を制限します"),
+    ("Drag points to reshape the curve; double-click to add a point, right-click to remove one.", "点をドラッグして曲線の形を変えます。ダブルクリックで点を追加、右クリックで削除します。"),
+    ("Species Pair", "種のペア"),
+    ("Force", "力"),
+    ("Strength", "強さ"),
+    ("Min Distance", "最小距離"),
+    ("Max Distance", "最大距離"),
+    ("Epsilon", "イプシロン"),
+    ("Sigma", "シグマ"),
+    ("Rest Length", "自然長"),
+    ("Stiffness", "剛性"),
+    ("Force (+ repulsive)", "力 (+ は斥力)"),
+    ("Potential", "ポテンシャル"),
+    ("Mass", "質量"),
+    ("Size", "サイズ"),
+    ("Charge", "電荷"),
+    ("New Species", "新しい種"),
+    ("➕ Add Species", "➕ 種を追加"),
+    ("🗑️ Remove Species", "🗑️ 種を削除"),
+    // Rendering
+    ("Rendering", "描画"),
+    ("Particle Trails", "粒子の軌跡"),
+    ("Trail Length", "軌跡の長さ"),
+    ("Point Size", "点のサイズ"),
+    ("Velocity Vectors", "速度ベクトル"),
+    ("Force Vectors", "力ベクトル"),
+    ("Interaction Radii", "相互作用の半径"),
+    ("Hover over a particle to see how far each of its pair rules reaches", "粒子にカーソルを合わせると、各ペアルールの届く距離が表示されます"),
+    ("Force Field Overlay", "力場オーバーレイ"),
+    ("Probe Species", "プローブの種"),
+    ("Field Resolution", "力場の解像度"),
+    ("Arrow Length", "矢印の長さ"),
+    ("Streamlines", "流線"),
+    ("Color by Velocity", "速度で色分け"),
+    ("Color by Energy", "エネルギーで色分け"),
+    ("Shape per Species", "種ごとに形を変える"),
+    ("Render Mode", "描画モード"),
+    ("Additive Blending (Glow)", "加算合成 (グロー)"),
+    ("Points", "点"),
+    ("Circles", "円"),
+    ("Sprites", "スプライト"),
+    ("Metaballs", "メタボール"),
+    ("Lines", "線"),
+    ("Trails", "軌跡"),
+    ("Motion Stretch", "モーションストレッチ"),
+    ("Stretch per Speed", "速度あたりの伸び"),
+    ("Max Elongation", "最大伸長率"),
+    ("Background", "背景"),
+    ("Solid", "単色"),
+    ("Vertical Gradient", "縦グラデーション"),
+    ("Radial Gradient", "放射グラデーション"),
+    ("Image Path", "画像パス"),
+    ("Parallax", "視差"),
+    ("Species Palette", "種のカラーパレット"),
+    ("Choose...", "選択..."),
+    ("Okabe-Ito (color-blind safe)", "Okabe-Ito (色覚多様性対応)"),
+    ("Tol Bright (color-blind safe)", "Tol Bright (色覚多様性対応)"),
+    ("Neon", "ネオン"),
+    ("Pastel", "パステル"),
+    ("Sunset", "夕焼け"),
+    ("Ocean", "海"),
+    ("Mono", "モノクロ"),
+    ("Auto-fit Camera on Preset", "プリセット切替時にカメラを自動調整"),
+    // Performance stats
+    ("📊 Performance Stats", "📊 パフォーマンス統計"),
+    ("Frame Time", "フレーム時間"),
+    ("Average", "平均"),
+    ("Spatial Index", "空間インデックス"),
+    ("Update Time", "更新時間"),
+    ("Render Time", "描画時間"),
+    ("Active Forces", "有効な力"),
+    ("Candidate Pairs", "候補ペア"),
+    ("Spatial Queries", "空間クエリ"),
+    ("Grid Cells", "グリッドセル"),
+    ("Max Particles/Cell", "セルあたり最大粒子数"),
+    ("QuadTree Nodes", "四分木ノード"),
+    ("Max Depth", "最大深さ"),
+    // Advanced settings
+    ("⚙️ Advanced Settings", "⚙️ 詳細設定"),
+    ("Language", "言語"),
+    ("Appearance", "外観"),
+    ("UI Scale", "UIスケール"),
+    ("Theme", "テーマ"),
+    ("Accent Color", "アクセントカラー"),
+    ("Dark", "ダーク"),
+    ("Light", "ライト"),
+    ("High Contrast", "ハイコントラスト"),
+    ("Physics", "物理"),
+    ("Time Step", "タイムステップ"),
+    ("Max Velocity", "最大速度"),
+    ("Enable Collisions", "衝突を有効化"),
+    ("Equal and Opposite Pair Forces", "ペア力を作用・反作用で適用"),
+    ("Spawn Ramp-In (s)", "生成時の力の立ち上がり (秒)"),
+    ("Preset Transitions", "プリセット遷移"),
+    ("Morph Between Presets", "プリセット間をモーフィング"),
+    ("Transition Duration (s)", "遷移時間 (秒)"),
+    ("Audio", "オーディオ"),
+    ("Audio-Reactive Forces", "音に反応する力"),
+    ("🎤 Listening", "🎤 入力中"),
+    ("🎤 Start Microphone", "🎤 マイクを開始"),
+    ("Gain", "ゲイン"),
+    ("Smoothing", "スムージング"),
+    ("Mappings", "マッピング"),
+    ("Base", "基準値"),
+    ("Depth", "深さ"),
+    ("➕ Add Mapping", "➕ マッピングを追加"),
+    ("MIDI", "MIDI"),
+    ("Connected", "接続済み"),
+    ("🎹 Connect MIDI Input", "🎹 MIDI入力に接続"),
+    ("Last CC", "最後のCC"),
+    ("Bindings", "割り当て"),
+    ("Min", "最小"),
+    ("Max", "最大"),
+    ("Learn", "学習"),
+    ("Move a control to bind", "割り当てるコントロールを動かしてください"),
+    ("Cancel", "キャンセル"),
+    ("💾 Save Bindings", "💾 割り当てを保存"),
+    ("📁 Load Bindings", "📁 割り当てを読み込み"),
+    ("OSC", "OSC"),
+    ("UDP Port: ", "UDPポート: "),
+    ("Listening on", "受信中"),
+    ("Stop", "停止"),
+    ("📡 Start OSC Listener", "📡 OSC受信を開始"),
+    ("Video Output", "映像出力"),
+    ("Resolution", "解像度"),
+    ("Server Name", "サーバー名"),
+    ("Spout Sender", "Spoutセンダー"),
+    ("Syphon Server", "Syphonサーバー"),
+    ("NDI Source", "NDIソース"),
+    ("Performance", "パフォーマンス"),
+    ("Spatial Partitioning", "空間分割"),
+    ("Multithreading", "マルチスレッド"),
+    ("Target FPS", "目標FPS"),
+    ("💾 Save Config", "💾 設定を保存"),
+    ("📁 Load Config", "📁 設定を読み込み"),
+    ("🔄 Reset to Defaults", "🔄 初期設定に戻す"),
+    // Hotkeys
+    ("⌨️ Hotkeys", "⌨️ ショートカットキー"),
+    ("Press a key...", "キーを押してください..."),
+    ("Rebind", "再割り当て"),
+    ("Restore Defaults", "初期設定に戻す"),
+    ("Pause / play", "一時停止 / 再生"),
+    ("Reset simulation", "シミュレーションをリセット"),
+    ("Reset camera", "カメラをリセット"),
+    ("Focus camera on particles", "カメラを粒子に合わせる"),
+    ("Toggle settings panel", "設定パネルの表示切替"),
+    ("Toggle performance stats", "パフォーマンス統計の表示切替"),
+    ("Step forward", "1ステップ進む"),
+    ("Step back", "1ステップ戻る"),
+    ("Halve speed", "速度を半分に"),
+    ("Double speed", "速度を2倍に"),
+    ("Toggle this cheat sheet", "この一覧の表示切替"),
+    ("Toggle fullscreen", "全画面表示の切替"),
+    ("Start / stop demo mode", "デモモードの開始/停止"),
+    ("Initial Velocity", "初速度"),
+    ("Uniform", "一様"),
+    ("Curl Noise", "カールノイズ"),
+    ("Swirl Size", "渦の大きさ"),
+    ("Applies when the simulation is reset", "シミュレーションのリセット時に適用されます"),
+    ("Swirl", "渦"),
+    ("Curl the sparks with a noise flow", "ノイズの流れで火花を渦巻かせます"),
+    ("Rigid", "剛体"),
+    ("✋ Grab", "✋ つかむ"),
+    ("Drag to grab particles", "ドラッグで粒子をつかむ"),
+    ("Reach", "届く範囲"),
+    ("⬚ Select", "⬚ 選択"),
+    ("Drag to select", "ドラッグで選択"),
+    ("Lasso", "投げ縄"),
+    ("Selected", "選択数"),
+    ("📋 Copy", "📋 コピー"),
+    ("Set Species", "種族を設定"),
+    ("Apply Impulse", "力積を加える"),
+    ("🗑 Delete", "🗑 削除"),
+    ("📌 Paste", "📌 貼り付け"),
+    ("Clipboard", "クリップボード"),
+    ("💾 Save Stamp", "💾 スタンプを保存"),
+    ("No saved stamps", "保存されたスタンプはありません"),
+    ("Copy selected particles", "選択した粒子をコピー"),
+    ("Paste particles at cursor", "カーソル位置に粒子を貼り付け"),
+    ("Duplicate selected particles", "選択した粒子を複製"),
+    ("💥 Burst", "💥 一斉生成"),
+    ("Spawn this many particles at once in the spawn area", "生成範囲にこの数の粒子を一度に生成"),
+    ("Fast Math", "高速演算"),
+    ("Approximate 1/r² forces for higher particle counts, at a small cost in accuracy", "精度をわずかに犠牲にして1/r²の力を近似し、より多くの粒子を扱う"),
+    ("Cutoff Distance (0 = none)", "カットオフ距離（0 = なし）"),
+    ("World Streaming", "ワールドストリーミング"),
+    ("Fully simulate only the chunks near the camera or pinned; the rest freeze or tick slowly", "カメラ付近または固定したチャンクだけを完全にシミュレートし、それ以外は停止または低頻度で更新"),
+    ("Chunk Size", "チャンクサイズ"),
+    ("Activation Radius", "アクティブ半径"),
+    ("Dormant Tick Interval (0 = frozen)", "休止チャンクの更新間隔（0 = 停止）"),
+    ("Show Chunks", "チャンクを表示"),
+    ("Dormant Particles", "休止中の粒子"),
+    ("Unpin All Chunks", "すべてのチャンクの固定を解除"),
+    ("Pin / unpin the chunk under the cursor", "カーソル下のチャンクを固定／解除"),
+    ("Prometheus Metrics", "Prometheusメトリクス"),
+    ("HTTP Port: ", "HTTPポート: "),
+    ("Serving on", "配信中"),
+    ("📈 Start Metrics Endpoint", "📈 メトリクスエンドポイントを開始"),
+    ("Restore Previous Session?", "前回のセッションを復元しますか？"),
+    ("Inochi didn't close cleanly last time. Its rules and particles were autosaved.", "前回Inochiは正常に終了しませんでした。ルールと粒子は自動保存されています。"),
+    ("particles", "粒子"),
+    ("minutes ago", "分前"),
+    ("Restore", "復元"),
+    ("Discard", "破棄"),
+    ("Autosave", "自動保存"),
+    ("Autosave Session", "セッションを自動保存"),
+    ("Autosave Interval (s)", "自動保存の間隔（秒）"),
+    ("Crash Report", "クラッシュレポート"),
+    ("Inochi hit an unexpected error. Please include this report when filing an issue.", "Inochiで予期しないエラーが発生しました。問題を報告する際はこのレポートを添えてください。"),
+    ("📋 Copy Report", "📋 レポートをコピー"),
+    ("Dismiss", "閉じる"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_translation_fallback() {
+        assert_eq!(tr(Language::English, "Physics"), "Physics");
+        assert_eq!(tr(Language::Japanese, "Physics"), "物理");
+        assert_eq!(tr(Language::Japanese, "Not a UI string"), "Not a UI string");
+    }
+
+    #[test]
+    fn test_translation_keys_unique() {
+        let mut seen = HashSet::new();
+        for (english, translated) in JAPANESE {
+            assert!(seen.insert(*english), "duplicate key: {}", english);
+            assert!(!translated.is_empty());
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use glam::Vec2;
+use crate::config::SpeciesRegistry;
+use crate::particle::Particle;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThresholdChannel {
+    Alpha,
+    // Luminance multiplied by alpha, so transparent pixels count as dark
+    Brightness,
+}
+
+impl ThresholdChannel {
+    pub fn all() -> Vec<ThresholdChannel> {
+        vec![ThresholdChannel::Brightness, ThresholdChannel::Alpha]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            ThresholdChannel::Alpha => "Alpha",
+            ThresholdChannel::Brightness => "Brightness",
+        }
+    }
+
+    fn value(&self, pixel: &[u8]) -> f32 {
+        let alpha = pixel[3] as f32 / 255.0;
+        match self {
+            ThresholdChannel::Alpha => alpha,
+            ThresholdChannel::Brightness => {
+                let luminance = 0.2126 * pixel[0] as f32 + 0.7152 * pixel[1] as f32 + 0.0722 * pixel[2] as f32;
+                luminance / 255.0 * alpha
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImageImportOptions {
+    pub channel: ThresholdChannel,
+    pub threshold: f32,
+    // Sample every Nth pixel in both directions
+    pub spacing: usize,
+    // Width of the imported layout in world units; height follows the aspect ratio
+    pub world_width: f32,
+    pub center: Vec2,
+    pub max_particles: usize,
+}
+
+impl Default for ImageImportOptions {
+    fn default() -> Self {
+        Self {
+            channel: ThresholdChannel::Brightness,
+            threshold: 0.5,
+            spacing: 4,
+            world_width: 600.0,
+            center: Vec2::ZERO,
+            max_particles: 2000,
+        }
+    }
+}
+
+// Species whose color is closest to the pixel color
+fn nearest_species(species: &SpeciesRegistry, pixel: &[u8]) -> Option<u32> {
+    let color = [pixel[0] as f32 / 255.0, pixel[1] as f32 / 255.0, pixel[2] as f32 / 255.0];
+    species
+        .species
+        .iter()
+        .map(|definition| {
+            let distance: f32 = (0..3).map(|i| (definition.color[i] - color[i]).powi(2)).sum();
+            (definition.id, distance)
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(id, _)| id)
+}
+
+// Converts RGBA8 pixels into particles at the positions of pixels above the threshold
+pub fn particles_from_rgba(
+    pixels: &[u8],
+    width: u32,
+    height: u32,
+    options: &ImageImportOptions,
+    species: &SpeciesRegistry,
+) -> Vec<Particle> {
+    let (width, height) = (width as usize, height as usize);
+    if width == 0 || height == 0 || pixels.len() < width * height * 4 {
+        return Vec::new();
+    }
+
+    let spacing = options.spacing.max(1);
+    let scale = options.world_width / width as f32;
+    let mut candidates = Vec::new();
+
+    for y in (0..height).step_by(spacing) {
+        for x in (0..width).step_by(spacing) {
+            let index = (y * width + x) * 4;
+            let pixel = &pixels[index..index + 4];
+            if options.channel.value(pixel) < options.threshold {
+                continue;
+            }
+
+            // Image rows run top to bottom, world y runs bottom to top
+            let offset = Vec2::new(x as f32 - width as f32 * 0.5, height as f32 * 0.5 - y as f32);
+            candidates.push((options.center + offset * scale, nearest_species(species, pixel)));
+        }
+    }
+
+    // Thin out evenly rather than truncating, which would drop the bottom of the image
+    let max_particles = options.max_particles.max(1);
+    let stride = candidates.len().div_ceil(max_particles).max(1);
+
+    candidates
+        .into_iter()
+        .step_by(stride)
+        .map(|(position, species_id)| match species_id {
+            Some(species_id) => species.particle(species_id, position),
+            None => Particle::new(position),
+        })
+        .collect()
+}
+
+// Decodes a PNG or JPEG file and converts it with `particles_from_rgba`
+#[cfg(feature = "desktop")]
+pub fn particles_from_image(
+    path: impl AsRef<std::path::Path>,
+    options: &ImageImportOptions,
+    species: &SpeciesRegistry,
+) -> Result<Vec<Particle>, Box<dyn std::error::Error>> {
+    let image = image::open(path)?.to_rgba8();
+    Ok(particles_from_rgba(image.as_raw(), image.width(), image.height(), options, species))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2x2 image: red, transparent / black, blue
+    fn test_pixels() -> Vec<u8> {
+        vec![
+            255, 0, 0, 255,   0, 0, 0, 0,
+            0, 0, 0, 255,     0, 0, 255, 255,
+        ]
+    }
+
+    #[test]
+    fn test_threshold_and_species_mapping() {
+        let species = SpeciesRegistry::default();
+        let options = ImageImportOptions {
+            spacing: 1,
+            threshold: 0.05,
+            world_width: 2.0,
+            ..ImageImportOptions::default()
+        };
+
+        let particles = particles_from_rgba(&test_pixels(), 2, 2, &options, &species);
+        assert_eq!(particles.len(), 2);
+
+        let red = species.species.iter().find(|s| s.name == "Red").unwrap().id;
+        let blue = species.species.iter().find(|s| s.name == "Blue").unwrap().id;
+        assert_eq!(particles[0].species_id, red);
+        assert_eq!(particles[0].position, Vec2::new(-1.0, 1.0));
+        assert_eq!(particles[1].species_id, blue);
+        assert_eq!(particles[1].position, Vec2::new(0.0, 0.0));
+
+        let alpha = ImageImportOptions {
+            channel: ThresholdChannel::Alpha,
+            ..options
+        };
+        assert_eq!(particles_from_rgba(&test_pixels(), 2, 2, &alpha, &species).len(), 3);
+    }
+
+    #[test]
+    fn test_max_particles_thins_evenly() {
+        let species = SpeciesRegistry::default();
+        let pixels = vec![255u8; 10 * 10 * 4];
+        let options = ImageImportOptions {
+            spacing: 1,
+            max_particles: 30,
+            ..ImageImportOptions::default()
+        };
+
+        let particles = particles_from_rgba(&pixels, 10, 10, &options, &species);
+        assert!(particles.len() <= 30);
+        assert!(particles.last().unwrap().position.y < 0.0);
+    }
+}
@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+use crate::config::Preset;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    TogglePause,
+    Reset,
+    ResetCamera,
+    FocusCamera,
+    // Index into Preset::all()
+    Preset(usize),
+    ToggleSettings,
+    TogglePerformance,
+    StepForward,
+    StepBackward,
+    SlowDown,
+    SpeedUp,
+    ToggleHotkeys,
+    ToggleFullscreen,
+    ToggleDemo,
+    CopySelection,
+    PasteClipboard,
+    DuplicateSelection,
+    ToggleChunkPin,
+}
+
+impl Action {
+    pub fn all() -> Vec<Action> {
+        let mut actions = vec![
+            Action::TogglePause,
+            Action::Reset,
+            Action::ResetCamera,
+            Action::FocusCamera,
+        ];
+        actions.extend((0..Preset::all().len()).map(Action::Preset));
+        actions.extend([
+            Action::ToggleSettings,
+            Action::TogglePerformance,
+            Action::StepForward,
+            Action::StepBackward,
+            Action::SlowDown,
+            Action::SpeedUp,
+            Action::ToggleHotkeys,
+            Action::ToggleFullscreen,
+            Action::ToggleDemo,
+            Action::CopySelection,
+            Action::PasteClipboard,
+            Action::DuplicateSelection,
+            Action::ToggleChunkPin,
+        ]);
+        actions
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Action::TogglePause => "Pause / play".to_string(),
+            Action::Reset => "Reset simulation".to_string(),
+            Action::ResetCamera => "Reset camera".to_string(),
+            Action::FocusCamera => "Focus camera on particles".to_string(),
+            Action::Preset(index) => match Preset::all().get(*index) {
+                Some(preset) => format!("Preset: {}", preset.name()),
+                None => format!("Preset #{}", index + 1),
+            },
+            Action::ToggleSettings => "Toggle settings panel".to_string(),
+            Action::TogglePerformance => "Toggle performance stats".to_string(),
+            Action::StepForward => "Step forward".to_string(),
+            Action::StepBackward => "Step back".to_string(),
+            Action::SlowDown => "Halve speed".to_string(),
+            Action::SpeedUp => "Double speed".to_string(),
+            Action::ToggleHotkeys => "Toggle this cheat sheet".to_string(),
+            Action::ToggleFullscreen => "Toggle fullscreen".to_string(),
+            Action::ToggleDemo => "Start / stop demo mode".to_string(),
+            Action::CopySelection => "Copy selected particles".to_string(),
+            Action::PasteClipboard => "Paste particles at cursor".to_string(),
+            Action::DuplicateSelection => "Duplicate selected particles".to_string(),
+            Action::ToggleChunkPin => "Pin / unpin the chunk under the cursor".to_string(),
+        }
+    }
+}
+
+// Keys are stored by their nannou `Key` debug name (e.g. "Space", "Key1", "F1")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyBinding {
+    pub key: String,
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyMap {
+    pub bindings: Vec<KeyBinding>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let mut bindings = vec![
+            ("Space", Action::TogglePause),
+            ("R", Action::Reset),
+            ("C", Action::ResetCamera),
+            ("F", Action::FocusCamera),
+            ("F1", Action::ToggleSettings),
+            ("F2", Action::TogglePerformance),
+            ("Period", Action::StepForward),
+            ("Comma", Action::StepBackward),
+            ("LBracket", Action::SlowDown),
+            ("RBracket", Action::SpeedUp),
+            ("H", Action::ToggleHotkeys),
+            ("F11", Action::ToggleFullscreen),
+            ("D", Action::ToggleDemo),
+            ("Y", Action::CopySelection),
+            ("V", Action::PasteClipboard),
+            ("B", Action::DuplicateSelection),
+            ("P", Action::ToggleChunkPin),
+        ];
+        let preset_keys = ["Key1", "Key2", "Key3", "Key4", "Key5", "Key6", "Key7", "Key8", "Key9"];
+        for (index, key) in preset_keys.iter().take(Preset::all().len()).enumerate() {
+            bindings.push((*key, Action::Preset(index)));
+        }
+
+        Self {
+            bindings: bindings
+                .into_iter()
+                .map(|(key, action)| KeyBinding { key: key.to_string(), action })
+                .collect(),
+        }
+    }
+}
+
+impl KeyMap {
+    pub fn action_for(&self, key: &str) -> Option<Action> {
+        self.bindings.iter().find(|b| b.key == key).map(|b| b.action)
+    }
+
+    pub fn key_for(&self, action: Action) -> Option<&str> {
+        self.bindings.iter().find(|b| b.action == action).map(|b| b.key.as_str())
+    }
+
+    // Each key and each action has at most one binding
+    pub fn rebind(&mut self, action: Action, key: &str) {
+        self.bindings.retain(|b| b.action != action && b.key != key);
+        self.bindings.push(KeyBinding { key: key.to_string(), action });
+    }
+
+    pub fn display_key(key: &str) -> String {
+        match key {
+            "Period" => ".".to_string(),
+            "Comma" => ",".to_string(),
+            "LBracket" => "[".to_string(),
+            "RBracket" => "]".to_string(),
+            _ => key.strip_prefix("Key").filter(|k| !k.is_empty()).unwrap_or(key).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_keymap() {
+        let keymap = KeyMap::default();
+        assert_eq!(keymap.action_for("Space"), Some(Action::TogglePause));
+        assert_eq!(keymap.action_for("Key1"), Some(Action::Preset(0)));
+        assert_eq!(keymap.action_for("H"), Some(Action::ToggleHotkeys));
+        assert_eq!(keymap.action_for("Q"), None);
+        assert_eq!(KeyMap::display_key("Key3"), "3");
+        assert_eq!(KeyMap::display_key("LBracket"), "[");
+    }
+
+    #[test]
+    fn test_rebind_replaces_conflicts() {
+        let mut keymap = KeyMap::default();
+        keymap.rebind(Action::Reset, "Space");
+
+        assert_eq!(keymap.action_for("Space"), Some(Action::Reset));
+        assert_eq!(keymap.action_for("R"), None);
+        assert_eq!(keymap.key_for(Action::TogglePause), None);
+        assert_eq!(keymap.key_for(Action::Reset), Some("Space"));
+    }
+}
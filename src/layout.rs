@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const DEFAULT_LAYOUT_FILE: &str = "layout.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowRect {
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+    pub open: bool,
+}
+
+// Last known position, size and visibility of each UI window, keyed by a stable window id
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WindowLayouts {
+    pub windows: HashMap<String, WindowRect>,
+}
+
+impl WindowLayouts {
+    pub fn from_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save_to_file(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&WindowRect> {
+        self.windows.get(id)
+    }
+
+    pub fn is_open(&self, id: &str) -> Option<bool> {
+        self.windows.get(id).map(|rect| rect.open)
+    }
+
+    pub fn update(&mut self, id: &str, position: [f32; 2], size: [f32; 2]) {
+        let rect = self.windows.entry(id.to_string()).or_insert(WindowRect {
+            position,
+            size,
+            open: true,
+        });
+        rect.position = position;
+        rect.size = size;
+        rect.open = true;
+    }
+
+    pub fn set_open(&mut self, id: &str, open: bool) {
+        if let Some(rect) = self.windows.get_mut(id) {
+            rect.open = open;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_round_trip() {
+        let mut layouts = WindowLayouts::default();
+        layouts.update("settings", [10.0, 20.0], [300.0, 400.0]);
+        layouts.set_open("settings", false);
+        layouts.set_open("missing", true);
+
+        let json = serde_json::to_string(&layouts).unwrap();
+        let restored: WindowLayouts = serde_json::from_str(&json).unwrap();
+
+        let rect = restored.get("settings").unwrap();
+        assert_eq!(rect.position, [10.0, 20.0]);
+        assert_eq!(rect.size, [300.0, 400.0]);
+        assert_eq!(restored.is_open("settings"), Some(false));
+        assert_eq!(restored.is_open("missing"), None);
+    }
+}
@@ -1,12 +1,153 @@
 use nannou::prelude::*;
 
 fn main() {
+    inochi::profiling::init();
+    // Release builds abort on panic, so the report is written before anything else can go
+    let crash_dir = inochi::crash::default_dir();
+    inochi::crash::install(move |report| match report.save(&crash_dir) {
+        Ok(path) => {
+            eprintln!("Crash report written to {}", path.display());
+            Some(path)
+        },
+        Err(e) => {
+            eprintln!("Failed to write crash report: {}", e);
+            None
+        },
+    });
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(index) = args.iter().position(|arg| arg == "--sweep") {
+        let Some(path) = args.get(index + 1) else {
+            eprintln!("Usage: inochi --sweep <sweep.toml>");
+            std::process::exit(2);
+        };
+        if let Err(e) = run_sweep(std::path::Path::new(path)) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        inochi::profiling::finish();
+        return;
+    }
+    #[cfg(feature = "grpc")]
+    if let Some(index) = args.iter().position(|arg| arg == "--grpc") {
+        let address = args.get(index + 1).map(String::as_str).unwrap_or(inochi::grpc::DEFAULT_ADDRESS);
+        if let Err(e) = run_grpc(address) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        inochi::profiling::finish();
+        return;
+    }
+    if args.iter().any(|arg| arg == "--headless" || arg == "--resume") {
+        if let Err(e) = run_headless(&args) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        inochi::profiling::finish();
+        return;
+    }
+
     nannou::app(model)
         .update(update)
         .view(view)
+        .exit(exit)
         .run();
 }
 
+// Runs a parameter sweep without opening a window
+fn run_sweep(path: &std::path::Path) -> Result<(), String> {
+    let spec = inochi::sweep::SweepSpec::from_file(path)?;
+    let results = spec.run()?;
+    match &spec.output {
+        Some(output) => {
+            inochi::sweep::write_results(&results, output)?;
+            println!("Wrote {} runs to {}", results.len(), output.display());
+        },
+        None => print!("{}", inochi::sweep::results_csv(&results)),
+    }
+    Ok(())
+}
+
+#[cfg(feature = "grpc")]
+fn run_grpc(address: &str) -> Result<(), String> {
+    let address = address.parse().map_err(|e| format!("Invalid address {}: {}", address, e))?;
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+    println!("Serving SimulationService on {}", address);
+    runtime.block_on(inochi::grpc::serve(address))
+}
+
+fn flag<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    let index = args.iter().position(|arg| arg == name)?;
+    args.get(index + 1).map(String::as_str)
+}
+
+fn parse_flag<T: std::str::FromStr>(args: &[String], name: &str) -> Result<Option<T>, String> {
+    flag(args, name)
+        .map(|value| value.parse().map_err(|_| format!("Invalid value for {}: {}", name, value)))
+        .transpose()
+}
+
+// Long simulations without a window, checkpointed so they can be resumed:
+//   inochi --headless <preset> --steps N [--seed S] [--checkpoint-dir DIR]
+//          [--checkpoint-every N] [--keep N] [--keep-every N] [--metrics PORT]
+//   inochi --resume DIR --steps N [...]
+fn run_headless(args: &[String]) -> Result<(), String> {
+    use inochi::checkpoint::{Checkpoint, CheckpointPolicy, HeadlessRun};
+    use inochi::metrics::{MetricsServer, SimulationMetrics, METRICS_PATH};
+    use inochi::perf;
+
+    let defaults = CheckpointPolicy::default();
+    let resume_dir = flag(args, "--resume");
+    let policy = CheckpointPolicy {
+        dir: flag(args, "--checkpoint-dir").or(resume_dir).map(Into::into).unwrap_or(defaults.dir),
+        interval: parse_flag(args, "--checkpoint-every")?.unwrap_or(defaults.interval),
+        keep_last: parse_flag(args, "--keep")?.unwrap_or(defaults.keep_last),
+        keep_every: parse_flag(args, "--keep-every")?,
+    };
+    let steps: u64 = parse_flag(args, "--steps")?.ok_or("--steps is required")?;
+
+    let mut run = match resume_dir {
+        Some(dir) => {
+            let path = CheckpointPolicy { dir: dir.into(), ..defaults }
+                .latest()
+                .ok_or_else(|| format!("No checkpoints in {}", dir))?;
+            println!("Resuming from {}", path.display());
+            HeadlessRun::resume(Checkpoint::load(&path)?)
+        },
+        None => {
+            let key = flag(args, "--headless").ok_or("--headless needs a preset name")?;
+            let preset = inochi::config::Preset::from_key(key).ok_or_else(|| format!("Unknown preset: {}", key))?;
+            let seed = parse_flag(args, "--seed")?.unwrap_or_else(rand::random);
+            println!("Running {} with seed {}", preset.name(), seed);
+            let mut config = inochi::config::ConfigManager::new();
+            config.apply_preset(preset.clone());
+            HeadlessRun::new(preset, config.config().clone(), seed)
+        },
+    };
+
+    let mut server = MetricsServer::new();
+    if let Some(port) = parse_flag::<u16>(args, "--metrics")? {
+        server.start(port).map_err(|e| format!("Failed to start metrics endpoint: {}", e))?;
+        println!("Serving metrics on http://0.0.0.0:{}{}", port, METRICS_PATH);
+    }
+    let mut metrics = SimulationMetrics::default();
+    let mut last_step = std::time::Instant::now();
+    policy.run_with(&mut run, steps, |run| {
+        if !server.is_serving() {
+            return;
+        }
+        // Each step is a headless run's frame
+        let step_ms = perf::millis(std::mem::replace(&mut last_step, std::time::Instant::now()).elapsed());
+        metrics.update_time_ms = perf::smooth(metrics.update_time_ms, step_ms);
+        metrics.physics_time_ms = metrics.update_time_ms;
+        metrics.frame_time_ms = metrics.update_time_ms;
+        metrics.fps = 1000.0 / metrics.frame_time_ms.max(1e-3);
+        metrics.record(run.preset.name(), &run.system.particles, 1, run.config.physics.dt);
+        server.publish(&metrics);
+    })?;
+    println!("Finished at step {} with {} particles", run.step, run.system.particle_count());
+    Ok(())
+}
+
 fn model(app: &App) -> inochi::App {
     let window_id = app
         .new_window()
@@ -14,19 +155,62 @@ fn model(app: &App) -> inochi::App {
         .size(1200, 800)
         .decorations(true)
         .resizable(true)
+        .key_pressed(key_pressed)
+        .raw_event(raw_window_event)
+        .resized(resized)
         .build()
         .unwrap();
 
-    inochi::App::new(app, window_id)
+    let mut model = inochi::App::new(app, window_id);
+    let (width, height) = app.window(window_id).unwrap().inner_size_points();
+    model.resize_viewport(width as u32, height as u32);
+    
+    // Opening a .inochi file from the OS passes its path as the first argument
+    if let Some(path) = std::env::args().nth(1) {
+        if path.ends_with(&format!(".{}", inochi::scene::SCENE_EXTENSION)) {
+            model.open_scene_file(path);
+        }
+    }
+    // Gallery installations start straight into the unattended demo
+    if std::env::args().any(|arg| arg == "--demo") {
+        model.start_demo();
+    }
+    
+    model
 }
 
 fn update(app: &App, model: &mut inochi::App, update: Update) {
     model.update(app, &update);
 }
 
-fn view(_app: &App, _model: &inochi::App, _frame: Frame) {
-    // Note: This will require updating the view method to take &self instead of &mut self
-    // For now, we'll comment this out to get compilation working
-    // model.view(app, &frame);
+fn key_pressed(_app: &App, model: &mut inochi::App, key: Key) {
+    model.key_pressed(key);
+}
+
+// Feeds egui and the camera/tool controls straight from winit
+fn raw_window_event(app: &App, model: &mut inochi::App, event: &nannou::winit::event::WindowEvent) {
+    model.raw_window_event(app, event);
 }
 
+fn resized(_app: &App, model: &mut inochi::App, size: Vec2) {
+    model.resize_viewport(size.x as u32, size.y as u32);
+}
+
+fn exit(app: &App, mut model: inochi::App) {
+    model.finish_output(app);
+    // A clean exit leaves nothing to restore on the next launch
+    if let Err(e) = model.discard_autosave() {
+        eprintln!("Failed to remove autosave: {}", e);
+    }
+    if let Err(e) = model.save_layout() {
+        eprintln!("Failed to save window layout: {}", e);
+    }
+    if let Err(e) = model.save_display() {
+        eprintln!("Failed to save display settings: {}", e);
+    }
+    inochi::profiling::finish();
+}
+
+fn view(app: &App, model: &inochi::App, frame: Frame) {
+    model.render(app, &frame);
+}
@@ -0,0 +1,50 @@
+use glam::Vec2;
+use crate::forces::ForceCalculator;
+
+// A ruler dragged between two world points. The species pick which interaction rules the force
+// readout uses, as if one particle of each sat at either end.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Measurement {
+    pub start: Vec2,
+    pub end: Vec2,
+    pub species_a: u32,
+    pub species_b: u32,
+}
+
+impl Measurement {
+    pub fn distance(&self) -> f32 {
+        self.start.distance(self.end)
+    }
+
+    // Positive when the two particles would push each other apart
+    pub fn force(&self, calculator: &ForceCalculator) -> f32 {
+        calculator.species_pair_force(self.species_a, self.species_b, self.distance())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forces::ForceType;
+
+    #[test]
+    fn test_measurement_uses_species_rules() {
+        let mut calculator = ForceCalculator::new();
+        calculator.interaction_matrix.add_interaction(0, 1, ForceType::Repulsion { strength: 10.0, max_distance: 50.0 });
+        calculator.interaction_matrix.add_interaction(0, 1, ForceType::Brownian { intensity: 1.0 });
+
+        let mut measurement = Measurement {
+            start: Vec2::new(0.0, 0.0),
+            end: Vec2::new(30.0, 40.0),
+            species_a: 1,
+            species_b: 0,
+        };
+        assert_eq!(measurement.distance(), 50.0);
+
+        measurement.end = Vec2::new(0.0, 20.0);
+        let force = measurement.force(&calculator);
+        assert!(force > 0.0);
+        let repulsion = ForceType::Repulsion { strength: 10.0, max_distance: 50.0 };
+        assert_eq!(force, calculator.pair_force_at(&repulsion, 20.0));
+    }
+}
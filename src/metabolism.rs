@@ -0,0 +1,215 @@
+use glam::Vec2;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::config::SpeciesRegistry;
+use crate::particle::{Particle, ParticleSystem};
+use crate::spatial::SpatialPartitioning;
+use crate::zone::{Zone, ZoneEffect};
+
+// Particles as organisms with an energy store: living, moving and feeling forces use it up, eating
+// food particles or resting in food zones fills it, and a particle that runs out dies. The store
+// is the particle's `energy`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetabolismConfig {
+    pub enabled: bool,
+    // Species that burn energy; empty means every species that isn't food
+    pub species: Vec<u32>,
+    // Species eaten as food. Food never burns energy.
+    pub food_species: Vec<u32>,
+    // Energy a newly created organism starts with, and the most it can store
+    pub initial_energy: f32,
+    pub max_energy: f32,
+    // Energy spent per second just staying alive
+    pub basal_cost: f32,
+    // Energy spent per unit of distance travelled
+    pub movement_cost: f32,
+    // Energy spent per second per unit of acceleration the particle feels
+    pub force_cost: f32,
+    // An organism eats food that comes this close
+    pub eat_radius: f32,
+    // Energy one food particle is worth
+    pub food_energy: f32,
+    // Food particles scattered over the bounds per second of simulated time
+    pub food_spawn_rate: f32,
+}
+
+impl Default for MetabolismConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            species: Vec::new(),
+            food_species: vec![1],
+            initial_energy: 10.0,
+            max_energy: 20.0,
+            basal_cost: 0.5,
+            movement_cost: 0.01,
+            force_cost: 0.0005,
+            eat_radius: 6.0,
+            food_energy: 4.0,
+            food_spawn_rate: 0.0,
+        }
+    }
+}
+
+// What happened to the population in one feeding step
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MetabolismReport {
+    pub eaten: usize,
+    pub starved: usize,
+    pub spawned: usize,
+}
+
+impl MetabolismConfig {
+    pub fn is_food(&self, species_id: u32) -> bool {
+        self.food_species.contains(&species_id)
+    }
+
+    pub fn metabolizes(&self, species_id: u32) -> bool {
+        !self.is_food(species_id) && (self.species.is_empty() || self.species.contains(&species_id))
+    }
+
+    // Charges every organism for one physics step of living, moving at its velocity and feeling
+    // its current acceleration. Runs inside the physics step, after forces and before integration.
+    pub fn burn(&self, particles: &mut [Particle], dt: f32) {
+        if !self.enabled {
+            return;
+        }
+        for particle in particles.iter_mut().filter(|particle| self.metabolizes(particle.species_id)) {
+            let cost = self.basal_cost + self.movement_cost * particle.velocity.length() + self.force_cost * particle.acceleration.length();
+            particle.energy -= cost * dt;
+        }
+    }
+
+    // Food particles due in the span (from, to] of simulated time
+    pub fn food_between(&self, from: f32, to: f32) -> usize {
+        if !self.enabled || self.food_spawn_rate <= 0.0 || to <= from {
+            return 0;
+        }
+        ((to * self.food_spawn_rate).floor() - (from * self.food_spawn_rate).floor()).max(0.0) as usize
+    }
+
+    // Feeds organisms from nearby food and food zones, removes those that ran out of energy and
+    // scatters new food. Food is timed from the simulation clock, like bursts.
+    pub fn feed<R: Rng>(
+        &self,
+        system: &mut ParticleSystem,
+        species: &SpeciesRegistry,
+        zones: &[Zone],
+        spatial: Option<&SpatialPartitioning>,
+        (from, to): (f32, f32),
+        rng: &mut R,
+    ) -> MetabolismReport {
+        let mut report = MetabolismReport::default();
+        if !self.enabled {
+            return report;
+        }
+        let dt = to - from;
+        let particles = &mut system.particles;
+
+        for zone in zones.iter().filter(|zone| zone.enabled) {
+            if let ZoneEffect::Food { rate } = zone.effect {
+                for particle in particles.iter_mut().filter(|p| self.metabolizes(p.species_id) && zone.shape.contains(p.position)) {
+                    particle.energy = (particle.energy + rate * dt).min(self.max_energy);
+                }
+            }
+        }
+
+        // Each food particle goes to the first hungry organism that reaches it
+        let radius_sq = self.eat_radius * self.eat_radius;
+        let mut eaten = vec![false; particles.len()];
+        for index in 0..particles.len() {
+            if !self.metabolizes(particles[index].species_id) {
+                continue;
+            }
+            let position = particles[index].position;
+            let candidates = match spatial {
+                Some(spatial) => spatial.query_neighbors(position, self.eat_radius),
+                None => (0..particles.len()).collect(),
+            };
+            for other in candidates {
+                if particles[index].energy >= self.max_energy {
+                    break;
+                }
+                let Some(food) = particles.get(other) else {
+                    continue;
+                };
+                if eaten[other] || !self.is_food(food.species_id) || food.position.distance_squared(position) > radius_sq {
+                    continue;
+                }
+                eaten[other] = true;
+                report.eaten += 1;
+                let organism = &mut particles[index];
+                organism.energy = (organism.energy + self.food_energy).min(self.max_energy);
+            }
+        }
+
+        let mut index = 0;
+        particles.retain(|particle| {
+            let starved = !eaten[index] && self.metabolizes(particle.species_id) && particle.energy <= 0.0;
+            if starved {
+                report.starved += 1;
+            }
+            let keep = !eaten[index] && !starved;
+            index += 1;
+            keep
+        });
+
+        if let (Some(&food), Some((min, max))) = (self.food_species.first(), system.bounds) {
+            for _ in 0..self.food_between(from, to) {
+                let position = min + (max - min) * Vec2::new(rng.gen::<f32>(), rng.gen::<f32>());
+                let before = system.particle_count();
+                system.add_particle(species.particle(food, position).with_lifespan(f32::INFINITY));
+                report.spawned += system.particle_count() - before;
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zone::ZoneShape;
+
+    #[test]
+    fn test_moving_and_pushing_cost_energy() {
+        let metabolism = MetabolismConfig { enabled: true, ..MetabolismConfig::default() };
+        let mut particles = vec![
+            Particle::new(Vec2::ZERO),
+            Particle::new(Vec2::ZERO).with_velocity(Vec2::new(100.0, 0.0)),
+            Particle::new(Vec2::ZERO).with_species(1),
+        ];
+        particles[1].acceleration = Vec2::new(0.0, 1000.0);
+        for particle in particles.iter_mut() {
+            particle.energy = 10.0;
+        }
+        metabolism.burn(&mut particles, 1.0);
+        assert_eq!(particles[0].energy, 10.0 - metabolism.basal_cost);
+        assert!(particles[1].energy < particles[0].energy - 1.0);
+        // Food doesn't metabolize
+        assert_eq!(particles[2].energy, 10.0);
+    }
+
+    #[test]
+    fn test_organisms_eat_food_and_starve_without_it() {
+        let metabolism = MetabolismConfig { enabled: true, food_spawn_rate: 2.0, ..MetabolismConfig::default() };
+        let mut system = ParticleSystem::new(100);
+        system.set_bounds(Vec2::splat(-100.0), Vec2::splat(100.0));
+        let mut hungry = Particle::new(Vec2::ZERO);
+        hungry.energy = 1.0;
+        let mut starving = Particle::new(Vec2::new(50.0, 0.0));
+        starving.energy = -0.1;
+        system.add_particle(hungry);
+        system.add_particle(starving);
+        system.add_particle(Particle::new(Vec2::new(3.0, 0.0)).with_species(1));
+        system.add_particle(Particle::new(Vec2::new(40.0, 40.0)).with_species(1));
+
+        let zones = [Zone::new("Meadow", ZoneShape::Circle { center: Vec2::ZERO, radius: 10.0 }, ZoneEffect::Food { rate: 1.0 })];
+        let mut rng = crate::rng::fork();
+        let report = metabolism.feed(&mut system, &SpeciesRegistry::default(), &zones, None, (0.0, 1.0), &mut rng);
+        assert_eq!(report, MetabolismReport { eaten: 1, starved: 1, spawned: 2 });
+        // One second in the meadow plus one food
+        assert_eq!(system.particles[0].energy, 1.0 + 1.0 + metabolism.food_energy);
+        assert_eq!(system.particles.iter().filter(|p| metabolism.is_food(p.species_id)).count(), 3);
+    }
+}
@@ -0,0 +1,263 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use crate::particle::Particle;
+
+pub const METRICS_PATH: &str = "/metrics";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 9184,
+        }
+    }
+}
+
+// What a long-running installation reports for monitoring, in Prometheus text format
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimulationMetrics {
+    pub preset: String,
+    pub steps: u64,
+    pub particle_count: usize,
+    pub species_counts: BTreeMap<u32, usize>,
+    pub fps: f32,
+    pub frame_time_ms: f32,
+    pub update_time_ms: f32,
+    pub render_time_ms: f32,
+    pub physics_time_ms: f32,
+    // Simulated seconds each preset has run since launch, and the particle count it last had
+    pub preset_seconds: BTreeMap<String, f64>,
+    pub preset_particles: BTreeMap<String, usize>,
+}
+
+impl SimulationMetrics {
+    // Counts the particles and credits `dt` simulated seconds to `preset`; timings are set by
+    // the caller, which knows how it measures them
+    pub fn record(&mut self, preset: &str, particles: &[Particle], steps: u64, dt: f32) {
+        self.preset = preset.to_string();
+        self.steps += steps;
+        self.particle_count = particles.len();
+        self.species_counts.clear();
+        for particle in particles {
+            *self.species_counts.entry(particle.species_id).or_default() += 1;
+        }
+        *self.preset_seconds.entry(self.preset.clone()).or_default() += dt as f64;
+        self.preset_particles.insert(self.preset.clone(), particles.len());
+    }
+
+    pub fn to_prometheus(&self) -> String {
+        let mut text = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, samples: Vec<(String, f64)>| {
+            let _ = writeln!(text, "# HELP inochi_{} {}", name, help);
+            let _ = writeln!(text, "# TYPE inochi_{} {}", name, kind);
+            for (labels, value) in samples {
+                let _ = writeln!(text, "inochi_{}{} {}", name, labels, value);
+            }
+        };
+        let single = |value: f64| vec![(String::new(), value)];
+
+        metric("particles", "gauge", "Particles alive.", single(self.particle_count as f64));
+        metric(
+            "species_particles",
+            "gauge",
+            "Particles alive per species.",
+            self.species_counts.iter().map(|(id, count)| (format!("{{species=\"{}\"}}", id), *count as f64)).collect(),
+        );
+        metric("steps_total", "counter", "Simulation steps taken.", single(self.steps as f64));
+        metric("fps", "gauge", "Frames (or headless steps) per second.", single(self.fps as f64));
+        metric("frame_time_ms", "gauge", "Average wall time per frame in milliseconds.", single(self.frame_time_ms as f64));
+        metric("update_time_ms", "gauge", "Smoothed simulation update time per frame in milliseconds.", single(self.update_time_ms as f64));
+        metric("physics_time_ms", "gauge", "Smoothed force and integration time per frame in milliseconds.", single(self.physics_time_ms as f64));
+        metric("render_time_ms", "gauge", "Smoothed render time per frame in milliseconds.", single(self.render_time_ms as f64));
+
+        let per_preset = |values: Vec<(&String, f64)>| {
+            values.into_iter().map(|(preset, value)| (format!("{{preset=\"{}\"}}", escape_label(preset)), value)).collect()
+        };
+        metric(
+            "preset_active",
+            "gauge",
+            "1 for the preset running now.",
+            per_preset(self.preset_seconds.keys().map(|preset| (preset, (*preset == self.preset) as u8 as f64)).collect()),
+        );
+        metric(
+            "preset_simulated_seconds",
+            "gauge",
+            "Simulated seconds each preset has run since launch.",
+            per_preset(self.preset_seconds.iter().map(|(preset, seconds)| (preset, *seconds)).collect()),
+        );
+        metric(
+            "preset_particles",
+            "gauge",
+            "Particles each preset had when it last ran.",
+            per_preset(self.preset_particles.iter().map(|(preset, count)| (preset, *count as f64)).collect()),
+        );
+        text
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+// The HTTP response for one request line: metrics on GET /metrics, 404 for anything else
+#[cfg(all(feature = "metrics", not(target_arch = "wasm32")))]
+fn response(request_line: &str, body: &str) -> String {
+    let mut parts = request_line.split_whitespace();
+    let path = parts.next().filter(|&method| method == "GET").and(parts.next());
+    let (status, body) = match path {
+        Some(path) if path.split('?').next() == Some(METRICS_PATH) => ("200 OK", body),
+        _ => ("404 Not Found", "Not found\n"),
+    };
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+#[cfg(all(feature = "metrics", not(target_arch = "wasm32")))]
+struct Serving {
+    body: std::sync::Arc<std::sync::Mutex<String>>,
+    stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
+}
+
+// Serves the latest published metrics over plain HTTP from a background thread, so scrapes never
+// wait on a frame
+#[derive(Default)]
+pub struct MetricsServer {
+    #[cfg(all(feature = "metrics", not(target_arch = "wasm32")))]
+    serving: Option<Serving>,
+}
+
+impl MetricsServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg(all(feature = "metrics", not(target_arch = "wasm32")))]
+    pub fn start(&mut self, port: u16) -> Result<(), String> {
+        use std::io::{BufRead, BufReader, Write as _};
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::{Arc, Mutex};
+
+        self.stop();
+        let listener = std::net::TcpListener::bind(("0.0.0.0", port)).map_err(|e| e.to_string())?;
+        // Polled so the thread notices a stop request between connections
+        listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+        let body = Arc::new(Mutex::new(String::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let serving = Serving { body: body.clone(), stop: stop.clone() };
+
+        std::thread::spawn(move || {
+            while !stop.load(Ordering::Relaxed) {
+                let stream = match listener.accept() {
+                    Ok((stream, _)) => stream,
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                        std::thread::sleep(std::time::Duration::from_millis(50));
+                        continue;
+                    },
+                    Err(e) => {
+                        eprintln!("Metrics endpoint stopped: {}", e);
+                        break;
+                    },
+                };
+                let _ = stream.set_nonblocking(false);
+                let _ = stream.set_read_timeout(Some(std::time::Duration::from_secs(2)));
+                let mut request_line = String::new();
+                let mut reader = BufReader::new(&stream);
+                if reader.read_line(&mut request_line).is_ok() {
+                    let body = body.lock().map(|body| body.clone()).unwrap_or_default();
+                    let _ = (&stream).write_all(response(&request_line, &body).as_bytes());
+                }
+            }
+        });
+        self.serving = Some(serving);
+        Ok(())
+    }
+
+    #[cfg(not(all(feature = "metrics", not(target_arch = "wasm32"))))]
+    pub fn start(&mut self, _port: u16) -> Result<(), String> {
+        Err("The metrics endpoint requires the `metrics` feature on desktop".to_string())
+    }
+
+    pub fn stop(&mut self) {
+        #[cfg(all(feature = "metrics", not(target_arch = "wasm32")))]
+        if let Some(serving) = self.serving.take() {
+            serving.stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+
+    pub fn is_serving(&self) -> bool {
+        #[cfg(all(feature = "metrics", not(target_arch = "wasm32")))]
+        {
+            self.serving.is_some()
+        }
+        #[cfg(not(all(feature = "metrics", not(target_arch = "wasm32"))))]
+        {
+            false
+        }
+    }
+
+    // Replaces what the next scrape sees
+    pub fn publish(&self, _metrics: &SimulationMetrics) {
+        #[cfg(all(feature = "metrics", not(target_arch = "wasm32")))]
+        if let Some(serving) = &self.serving {
+            if let Ok(mut body) = serving.body.lock() {
+                *body = _metrics.to_prometheus();
+            }
+        }
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec2;
+
+    #[test]
+    fn test_prometheus_text_has_counts_and_per_preset_gauges() {
+        let mut metrics = SimulationMetrics::default();
+        let particles = vec![
+            Particle::new(Vec2::ZERO).with_species(0),
+            Particle::new(Vec2::ZERO).with_species(2),
+            Particle::new(Vec2::ZERO).with_species(2),
+        ];
+        metrics.record("Gravity", &particles, 60, 1.0);
+        metrics.record("Flocking \"v2\"", &particles[..1], 30, 0.5);
+        metrics.fps = 59.5;
+
+        let text = metrics.to_prometheus();
+        assert!(text.contains("# TYPE inochi_particles gauge\ninochi_particles 1\n"));
+        assert!(text.contains("inochi_species_particles{species=\"0\"} 1\n"));
+        assert!(!text.contains("species=\"2\""));
+        assert!(text.contains("inochi_steps_total 90\n"));
+        assert!(text.contains("inochi_fps 59.5\n"));
+        assert!(text.contains("inochi_preset_active{preset=\"Gravity\"} 0\n"));
+        assert!(text.contains("inochi_preset_active{preset=\"Flocking \\\"v2\\\"\"} 1\n"));
+        assert!(text.contains("inochi_preset_simulated_seconds{preset=\"Gravity\"} 1\n"));
+        assert!(text.contains("inochi_preset_particles{preset=\"Gravity\"} 3\n"));
+    }
+
+    #[cfg(all(feature = "metrics", not(target_arch = "wasm32")))]
+    #[test]
+    fn test_only_get_metrics_is_served() {
+        assert!(response("GET /metrics HTTP/1.1\r\n", "inochi_fps 60\n").starts_with("HTTP/1.1 200 OK"));
+        assert!(response("GET /metrics?x=1 HTTP/1.1\r\n", "").starts_with("HTTP/1.1 200 OK"));
+        assert!(response("GET / HTTP/1.1\r\n", "").starts_with("HTTP/1.1 404"));
+        assert!(response("POST /metrics HTTP/1.1\r\n", "").starts_with("HTTP/1.1 404"));
+    }
+}
@@ -0,0 +1,235 @@
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use crate::config::SimulationConfig;
+use crate::control::ControlTarget;
+
+pub const DEFAULT_BINDINGS_FILE: &str = "midi_bindings.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ControlChange {
+    pub channel: u8,
+    pub controller: u8,
+    pub value: u8,
+}
+
+impl ControlChange {
+    pub fn parse(message: &[u8]) -> Option<Self> {
+        match message {
+            [status, controller, value, ..] if status & 0xF0 == 0xB0 => Some(Self {
+                channel: status & 0x0F,
+                controller: *controller,
+                value: *value,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn normalized(&self) -> f32 {
+        self.value.min(127) as f32 / 127.0
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MidiBinding {
+    pub channel: u8,
+    pub controller: u8,
+    pub target: ControlTarget,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl MidiBinding {
+    pub fn new(channel: u8, controller: u8, target: ControlTarget) -> Self {
+        let (min, max) = target.default_range();
+        Self {
+            channel,
+            controller,
+            target,
+            min,
+            max,
+        }
+    }
+
+    pub fn matches(&self, change: &ControlChange) -> bool {
+        self.channel == change.channel && self.controller == change.controller
+    }
+
+    pub fn value(&self, change: &ControlChange) -> f32 {
+        self.min + (self.max - self.min) * change.normalized()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MidiBindings {
+    pub bindings: Vec<MidiBinding>,
+}
+
+impl MidiBindings {
+    pub fn from_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save_to_file(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    // A controller drives at most one target, so re-learning replaces the old binding
+    pub fn bind(&mut self, binding: MidiBinding) {
+        self.bindings
+            .retain(|b| !(b.channel == binding.channel && b.controller == binding.controller));
+        self.bindings.push(binding);
+    }
+}
+
+pub struct MidiController {
+    pub bindings: MidiBindings,
+    pub learn_target: Option<ControlTarget>,
+    pub last_change: Option<ControlChange>,
+    pending: Arc<Mutex<Vec<ControlChange>>>,
+    port_name: Option<String>,
+    #[cfg(all(feature = "midi", not(target_arch = "wasm32")))]
+    connection: Option<midir::MidiInputConnection<()>>,
+}
+
+impl Default for MidiController {
+    fn default() -> Self {
+        Self::new(MidiBindings::default())
+    }
+}
+
+impl MidiController {
+    pub fn new(bindings: MidiBindings) -> Self {
+        Self {
+            bindings,
+            learn_target: None,
+            last_change: None,
+            pending: Arc::new(Mutex::new(Vec::new())),
+            port_name: None,
+            #[cfg(all(feature = "midi", not(target_arch = "wasm32")))]
+            connection: None,
+        }
+    }
+
+    pub fn load_default() -> Self {
+        Self::new(MidiBindings::from_file(DEFAULT_BINDINGS_FILE).unwrap_or_default())
+    }
+
+    pub fn port_name(&self) -> Option<&str> {
+        self.port_name.as_deref()
+    }
+
+    // Connects to the first available MIDI input port
+    #[cfg(all(feature = "midi", not(target_arch = "wasm32")))]
+    pub fn connect(&mut self) -> Result<(), String> {
+        let input = midir::MidiInput::new("inochi").map_err(|e| e.to_string())?;
+        let ports = input.ports();
+        let port = ports.first().ok_or_else(|| "No MIDI input ports available".to_string())?;
+        let name = input.port_name(port).map_err(|e| e.to_string())?;
+
+        let pending = Arc::clone(&self.pending);
+        let connection = input
+            .connect(
+                port,
+                "inochi-input",
+                move |_timestamp, message, _| {
+                    if let Some(change) = ControlChange::parse(message) {
+                        if let Ok(mut pending) = pending.lock() {
+                            pending.push(change);
+                        }
+                    }
+                },
+                (),
+            )
+            .map_err(|e| e.to_string())?;
+
+        self.connection = Some(connection);
+        self.port_name = Some(name);
+        Ok(())
+    }
+
+    #[cfg(not(all(feature = "midi", not(target_arch = "wasm32"))))]
+    pub fn connect(&mut self) -> Result<(), String> {
+        Err("MIDI input requires the `midi` feature on desktop".to_string())
+    }
+
+    pub fn handle_message(&self, message: &[u8]) {
+        if let Some(change) = ControlChange::parse(message) {
+            if let Ok(mut pending) = self.pending.lock() {
+                pending.push(change);
+            }
+        }
+    }
+
+    pub fn start_learn(&mut self, target: ControlTarget) {
+        self.learn_target = Some(target);
+    }
+
+    // Drains queued messages; in learn mode the first CC binds to the learn target
+    pub fn process(&mut self, config: &mut SimulationConfig) -> bool {
+        let changes: Vec<ControlChange> = match self.pending.lock() {
+            Ok(mut pending) => pending.drain(..).collect(),
+            Err(_) => return false,
+        };
+
+        let mut applied = false;
+        for change in changes {
+            self.last_change = Some(change);
+
+            if let Some(target) = self.learn_target.take() {
+                self.bindings.bind(MidiBinding::new(change.channel, change.controller, target));
+                continue;
+            }
+
+            for binding in self.bindings.bindings.iter().filter(|b| b.matches(&change)) {
+                binding.target.apply(config, binding.value(&change));
+                applied = true;
+            }
+        }
+        applied
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forces::ForceKind;
+
+    #[test]
+    fn test_parse_control_change() {
+        let change = ControlChange::parse(&[0xB2, 7, 127]).unwrap();
+        assert_eq!(change.channel, 2);
+        assert_eq!(change.controller, 7);
+        assert_eq!(change.normalized(), 1.0);
+
+        // Note-on is ignored
+        assert!(ControlChange::parse(&[0x90, 60, 100]).is_none());
+    }
+
+    #[test]
+    fn test_learn_and_apply() {
+        let mut config = SimulationConfig::default();
+        let mut controller = MidiController::default();
+
+        controller.start_learn(ControlTarget::SpawnRate);
+        controller.handle_message(&[0xB0, 21, 0]);
+        assert!(!controller.process(&mut config));
+        assert_eq!(controller.bindings.bindings.len(), 1);
+        assert!(controller.learn_target.is_none());
+
+        controller.handle_message(&[0xB0, 21, 127]);
+        assert!(controller.process(&mut config));
+        assert_eq!(config.particles.spawn_rate, 100.0);
+
+        // Re-learning the same controller replaces its binding
+        controller.start_learn(ControlTarget::ForceStrength(ForceKind::Gravity));
+        controller.handle_message(&[0xB0, 21, 64]);
+        controller.process(&mut config);
+        assert_eq!(controller.bindings.bindings.len(), 1);
+        assert_eq!(
+            controller.bindings.bindings[0].target,
+            ControlTarget::ForceStrength(ForceKind::Gravity)
+        );
+    }
+}
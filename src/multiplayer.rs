@@ -0,0 +1,195 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::brush::{BrushShape, ShapeSampler};
+use crate::checkpoint::HeadlessRun;
+use crate::rng;
+
+// Limits on what one client message can do to the shared world
+const MAX_SPAWN_PER_MESSAGE: u32 = 50;
+const SPAWN_RADIUS: f32 = 15.0;
+const MAX_ATTRACT_STRENGTH: f32 = 500.0;
+const MAX_ATTRACT_RADIUS: f32 = 300.0;
+// Positions that moved less than this since the last broadcast are left out of deltas
+const DELTA_EPSILON: f32 = 0.05;
+
+pub type ClientId = u64;
+
+// Sent by browsers as JSON, e.g. {"type":"spawn","x":10,"y":20,"species":1,"count":5}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Spawn { x: f32, y: f32, species: u32, count: u32 },
+    // Pulls particles toward the point until released; negative strength pushes them away
+    Attract { x: f32, y: f32, strength: f32, radius: f32 },
+    Release,
+}
+
+// One particle as [x, y, species]
+pub type ParticleState = (f32, f32, u32);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    // Full state, sent when a browser joins
+    Snapshot { step: u64, particles: Vec<ParticleState> },
+    // Particles that changed since the last broadcast, by index. Clients drop anything past `count`.
+    Delta { step: u64, count: usize, changed: Vec<(usize, ParticleState)> },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Attractor {
+    position: Vec2,
+    strength: f32,
+    radius: f32,
+}
+
+// The authoritative simulation: clients send inputs, the server steps and broadcasts deltas
+pub struct SharedSimulation {
+    pub run: HeadlessRun,
+    attractors: HashMap<ClientId, Attractor>,
+    last_sent: Vec<ParticleState>,
+}
+
+impl SharedSimulation {
+    pub fn new(run: HeadlessRun) -> Self {
+        Self {
+            run,
+            attractors: HashMap::new(),
+            last_sent: Vec::new(),
+        }
+    }
+
+    pub fn apply(&mut self, client: ClientId, message: ClientMessage) {
+        match message {
+            ClientMessage::Spawn { x, y, species, count } => {
+                let sampler = ShapeSampler::new(BrushShape::Circle { radius: SPAWN_RADIUS }, Vec2::new(x, y));
+                let mut spawn_rng = rng::fork();
+                for position in sampler.sample(count.min(MAX_SPAWN_PER_MESSAGE) as usize, &mut spawn_rng) {
+                    if !self.run.config.species.can_spawn(species, self.run.system.particle_count()) {
+                        break;
+                    }
+                    let particle = self.run.config.species.particle(species, position);
+                    self.run.system.add_particle(particle);
+                }
+            },
+            ClientMessage::Attract { x, y, strength, radius } => {
+                self.attractors.insert(client, Attractor {
+                    position: Vec2::new(x, y),
+                    strength: strength.clamp(-MAX_ATTRACT_STRENGTH, MAX_ATTRACT_STRENGTH),
+                    radius: radius.clamp(0.0, MAX_ATTRACT_RADIUS),
+                });
+            },
+            ClientMessage::Release => {
+                self.attractors.remove(&client);
+            },
+        }
+    }
+
+    pub fn disconnect(&mut self, client: ClientId) {
+        self.attractors.remove(&client);
+    }
+
+    pub fn step(&mut self, steps: u64) {
+        for _ in 0..steps {
+            for attractor in self.attractors.values() {
+                for particle in &mut self.run.system.particles {
+                    let offset = attractor.position - particle.position;
+                    let distance = offset.length();
+                    if distance > 0.0 && distance < attractor.radius {
+                        let falloff = 1.0 - distance / attractor.radius;
+                        particle.apply_force(offset / distance * attractor.strength * falloff * particle.mass);
+                    }
+                }
+            }
+            self.run.advance(1);
+        }
+    }
+
+    fn states(&self) -> Vec<ParticleState> {
+        self.run
+            .system
+            .particles
+            .iter()
+            .map(|p| (p.position.x, p.position.y, p.species_id))
+            .collect()
+    }
+
+    pub fn snapshot(&self) -> ServerMessage {
+        ServerMessage::Snapshot { step: self.run.step, particles: self.states() }
+    }
+
+    // Changes since the previous delta; the first delta carries every particle
+    pub fn delta(&mut self) -> ServerMessage {
+        let states = self.states();
+        let changed = states
+            .iter()
+            .enumerate()
+            .filter(|(i, state)| match self.last_sent.get(*i) {
+                Some(previous) => {
+                    previous.2 != state.2
+                        || (previous.0 - state.0).abs() > DELTA_EPSILON
+                        || (previous.1 - state.1).abs() > DELTA_EPSILON
+                },
+                None => true,
+            })
+            .map(|(i, state)| (i, *state))
+            .collect::<Vec<_>>();
+
+        // Remember what clients now hold, so small drifts accumulate until they cross the epsilon
+        for &(i, state) in &changed {
+            if i < self.last_sent.len() {
+                self.last_sent[i] = state;
+            } else {
+                self.last_sent.push(state);
+            }
+        }
+        self.last_sent.truncate(states.len());
+
+        ServerMessage::Delta { step: self.run.step, count: states.len(), changed }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ConfigManager, Preset};
+
+    fn simulation() -> SharedSimulation {
+        let mut manager = ConfigManager::new();
+        manager.apply_preset(Preset::Brownian);
+        let mut config = manager.config().clone();
+        config.particles.initial_particle_count = 10;
+        SharedSimulation::new(HeadlessRun::new(Preset::Brownian, config, 5))
+    }
+
+    #[test]
+    fn test_client_messages_parse() {
+        let spawn: ClientMessage = serde_json::from_str(r#"{"type":"spawn","x":1,"y":2,"species":1,"count":3}"#).unwrap();
+        assert_eq!(spawn, ClientMessage::Spawn { x: 1.0, y: 2.0, species: 1, count: 3 });
+        let release: ClientMessage = serde_json::from_str(r#"{"type":"release"}"#).unwrap();
+        assert_eq!(release, ClientMessage::Release);
+    }
+
+    #[test]
+    fn test_deltas_track_client_state() {
+        let mut shared = simulation();
+        let ServerMessage::Delta { count, changed, .. } = shared.delta() else {
+            panic!("expected a delta");
+        };
+        assert_eq!(changed.len(), count);
+
+        // Nothing moved, nothing to send
+        let ServerMessage::Delta { changed, .. } = shared.delta() else {
+            panic!("expected a delta");
+        };
+        assert!(changed.is_empty());
+
+        shared.apply(1, ClientMessage::Spawn { x: 0.0, y: 0.0, species: 2, count: 500 });
+        let ServerMessage::Delta { count: grown, changed, .. } = shared.delta() else {
+            panic!("expected a delta");
+        };
+        assert!(grown > count && grown <= count + MAX_SPAWN_PER_MESSAGE as usize);
+        assert!(changed.iter().filter(|(_, state)| state.2 == 2).count() >= grown - count);
+    }
+}
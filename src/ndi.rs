@@ -0,0 +1,139 @@
+use crate::output::{FrameSink, VideoFrame};
+
+#[cfg(all(feature = "ndi", not(target_arch = "wasm32")))]
+mod ffi {
+    use std::os::raw::{c_char, c_int, c_void};
+
+    pub type SendInstance = *mut c_void;
+
+    #[repr(C)]
+    pub struct SendCreate {
+        pub p_ndi_name: *const c_char,
+        pub p_groups: *const c_char,
+        pub clock_video: bool,
+        pub clock_audio: bool,
+    }
+
+    #[repr(C)]
+    pub struct VideoFrameV2 {
+        pub xres: c_int,
+        pub yres: c_int,
+        pub four_cc: u32,
+        pub frame_rate_n: c_int,
+        pub frame_rate_d: c_int,
+        pub picture_aspect_ratio: f32,
+        pub frame_format_type: c_int,
+        pub timecode: i64,
+        pub p_data: *const u8,
+        pub line_stride_in_bytes: c_int,
+        pub p_metadata: *const c_char,
+        pub timestamp: i64,
+    }
+
+    pub const FOURCC_RGBA: u32 = (b'R' as u32) | (b'G' as u32) << 8 | (b'B' as u32) << 16 | (b'A' as u32) << 24;
+    pub const FRAME_FORMAT_PROGRESSIVE: c_int = 1;
+    pub const TIMECODE_SYNTHESIZE: i64 = i64::MAX;
+
+    #[cfg_attr(target_os = "windows", link(name = "Processing.NDI.Lib.x64"))]
+    #[cfg_attr(not(target_os = "windows"), link(name = "ndi"))]
+    extern "C" {
+        pub fn NDIlib_initialize() -> bool;
+        pub fn NDIlib_send_create(settings: *const SendCreate) -> SendInstance;
+        pub fn NDIlib_send_destroy(instance: SendInstance);
+        pub fn NDIlib_send_send_video_v2(instance: SendInstance, frame: *const VideoFrameV2);
+    }
+}
+
+// Streams frames as an NDI source on the local network
+pub struct NdiSink {
+    name: String,
+    frame_rate: u32,
+    #[cfg(all(feature = "ndi", not(target_arch = "wasm32")))]
+    instance: ffi::SendInstance,
+}
+
+impl NdiSink {
+    pub fn sink_name(source_name: &str) -> String {
+        format!("{} (NDI)", source_name)
+    }
+
+    #[cfg(all(feature = "ndi", not(target_arch = "wasm32")))]
+    pub fn new(source_name: &str, frame_rate: u32) -> Result<Self, String> {
+        let c_name = std::ffi::CString::new(source_name).map_err(|e| e.to_string())?;
+
+        // SAFETY: the settings struct and name outlive the call; NDI copies the name
+        let instance = unsafe {
+            if !ffi::NDIlib_initialize() {
+                return Err("NDI runtime could not be initialized (unsupported CPU?)".to_string());
+            }
+            let settings = ffi::SendCreate {
+                p_ndi_name: c_name.as_ptr(),
+                p_groups: std::ptr::null(),
+                clock_video: false,
+                clock_audio: false,
+            };
+            ffi::NDIlib_send_create(&settings)
+        };
+
+        if instance.is_null() {
+            return Err("Failed to create NDI sender".to_string());
+        }
+
+        Ok(Self {
+            name: Self::sink_name(source_name),
+            frame_rate,
+            instance,
+        })
+    }
+
+    #[cfg(not(all(feature = "ndi", not(target_arch = "wasm32"))))]
+    pub fn new(_source_name: &str, _frame_rate: u32) -> Result<Self, String> {
+        Err("NDI output requires the `ndi` feature and the NDI runtime".to_string())
+    }
+}
+
+impl FrameSink for NdiSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[cfg(all(feature = "ndi", not(target_arch = "wasm32")))]
+    fn publish(&mut self, frame: &VideoFrame) -> Result<(), String> {
+        if frame.pixels.len() != frame.width * frame.height * 4 {
+            return Err("Frame size does not match its dimensions".to_string());
+        }
+
+        let video = ffi::VideoFrameV2 {
+            xres: frame.width as i32,
+            yres: frame.height as i32,
+            four_cc: ffi::FOURCC_RGBA,
+            frame_rate_n: self.frame_rate as i32,
+            frame_rate_d: 1,
+            picture_aspect_ratio: frame.width as f32 / frame.height.max(1) as f32,
+            frame_format_type: ffi::FRAME_FORMAT_PROGRESSIVE,
+            timecode: ffi::TIMECODE_SYNTHESIZE,
+            p_data: frame.pixels.as_ptr(),
+            line_stride_in_bytes: (frame.width * 4) as i32,
+            p_metadata: std::ptr::null(),
+            timestamp: 0,
+        };
+
+        // SAFETY: the synchronous send returns once NDI is done with the pixel buffer
+        unsafe { ffi::NDIlib_send_send_video_v2(self.instance, &video) };
+        Ok(())
+    }
+
+    #[cfg(not(all(feature = "ndi", not(target_arch = "wasm32"))))]
+    fn publish(&mut self, _frame: &VideoFrame) -> Result<(), String> {
+        let _ = self.frame_rate;
+        Err("NDI output is not available in this build".to_string())
+    }
+}
+
+#[cfg(all(feature = "ndi", not(target_arch = "wasm32")))]
+impl Drop for NdiSink {
+    fn drop(&mut self) {
+        // SAFETY: the instance was created by NDIlib_send_create and is destroyed once
+        unsafe { ffi::NDIlib_send_destroy(self.instance) };
+    }
+}
@@ -0,0 +1,183 @@
+use glam::Vec2;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+// Coherent noise for wind gusts and turbulence. Lattice gradients come from an integer hash
+// rather than a permutation table, so every field is deterministic and needs no setup.
+
+// Step for the finite differences in `curl2`, in noise-space units
+const CURL_EPSILON: f32 = 1e-3;
+// Rough mean length of `curl2` over the plane, so a curl-noise flow can be scaled to a speed
+const MEAN_CURL: f32 = 2.0;
+
+fn hash(x: i32, y: i32) -> u32 {
+    let mut h = (x as u32).wrapping_mul(0x8DA6_B343) ^ (y as u32).wrapping_mul(0xD816_3841);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x2C1B_3C6D);
+    h ^= h >> 12;
+    h = h.wrapping_mul(0x297A_2D39);
+    h ^ (h >> 15)
+}
+
+// Hash mapped to [-1, 1]
+fn hash_unit(x: i32, y: i32) -> f32 {
+    hash(x, y) as f32 / u32::MAX as f32 * 2.0 - 1.0
+}
+
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+// 1D gradient noise in [-1, 1]; zero at integers, one smooth bump or dip between them
+pub fn noise1(x: f32) -> f32 {
+    let cell = x.floor();
+    let t = x - cell;
+    let i = cell as i32;
+    let (g0, g1) = (hash_unit(i, 0), hash_unit(i + 1, 0));
+    let (v0, v1) = (g0 * t, g1 * (t - 1.0));
+    (v0 + (v1 - v0) * fade(t)) * 2.0
+}
+
+// 2D simplex noise, roughly in [-1, 1]
+pub fn simplex2(x: f32, y: f32) -> f32 {
+    const F2: f32 = 0.366_025_4; // (sqrt(3) - 1) / 2
+    const G2: f32 = 0.211_324_87; // (3 - sqrt(3)) / 6
+
+    let skew = (x + y) * F2;
+    let (i, j) = ((x + skew).floor(), (y + skew).floor());
+    let unskew = (i + j) * G2;
+    let p0 = Vec2::new(x - (i - unskew), y - (j - unskew));
+
+    // Which of the two triangles of the skewed cell we're in
+    let step = if p0.x > p0.y { Vec2::X } else { Vec2::Y };
+    let p1 = p0 - step + Vec2::splat(G2);
+    let p2 = p0 - Vec2::ONE + Vec2::splat(2.0 * G2);
+
+    let (i, j) = (i as i32, j as i32);
+    let corners = [
+        (p0, hash(i, j)),
+        (p1, hash(i + step.x as i32, j + step.y as i32)),
+        (p2, hash(i + 1, j + 1)),
+    ];
+    let total: f32 = corners
+        .iter()
+        .map(|&(p, h)| {
+            let falloff = 0.5 - p.length_squared();
+            if falloff <= 0.0 {
+                return 0.0;
+            }
+            let angle = h as f32 / u32::MAX as f32 * std::f32::consts::TAU;
+            falloff.powi(4) * Vec2::new(angle.cos(), angle.sin()).dot(p)
+        })
+        .sum();
+    total * 70.0
+}
+
+// Fractal sum of `octaves` simplex layers, each twice the frequency and half the amplitude of the last
+pub fn fbm2(p: Vec2, octaves: u32) -> f32 {
+    let mut total = 0.0;
+    let mut amplitude = 1.0;
+    let mut frequency = 1.0;
+    let mut norm = 0.0;
+    for octave in 0..octaves.max(1) {
+        // Offset each layer so their lattices don't line up at the origin
+        let offset = octave as f32 * 17.31;
+        total += simplex2(p.x * frequency + offset, p.y * frequency - offset) * amplitude;
+        norm += amplitude;
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+    total / norm
+}
+
+// Curl of the fbm2 potential: a swirling, divergence-free flow, so particles stir without clumping
+pub fn curl2(p: Vec2, octaves: u32) -> Vec2 {
+    let dx = fbm2(p + Vec2::new(CURL_EPSILON, 0.0), octaves) - fbm2(p - Vec2::new(CURL_EPSILON, 0.0), octaves);
+    let dy = fbm2(p + Vec2::new(0.0, CURL_EPSILON), octaves) - fbm2(p - Vec2::new(0.0, CURL_EPSILON), octaves);
+    Vec2::new(dy, -dx) / (2.0 * CURL_EPSILON)
+}
+
+// Starting velocities drawn from a curl-noise flow, so neighbouring particles set off together in
+// coherent swirls instead of each in a random direction
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CurlNoiseVelocity {
+    // Size of the swirls in world units
+    pub scale: f32,
+    // Mean speed; the flow is faster in some places than others
+    pub speed: f32,
+    pub octaves: u32,
+}
+
+impl Default for CurlNoiseVelocity {
+    fn default() -> Self {
+        Self { scale: 150.0, speed: 30.0, octaves: 2 }
+    }
+}
+
+impl CurlNoiseVelocity {
+    // `offset` shifts the flow in noise space; particles spawned together should share one
+    pub fn sample(&self, position: Vec2, offset: Vec2) -> Vec2 {
+        curl2(position / self.scale.max(f32::EPSILON) + offset, self.octaves) * (self.speed / MEAN_CURL)
+    }
+
+    // A fresh pattern for each batch of particles
+    pub fn random_offset<R: Rng>(rng: &mut R) -> Vec2 {
+        Vec2::new(rng.gen_range(-1000.0..1000.0), rng.gen_range(-1000.0..1000.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_noise_is_bounded_and_deterministic() {
+        for i in 0..2000 {
+            let x = i as f32 * 0.173 - 150.0;
+            let y = i as f32 * -0.291 + 40.0;
+            assert!(noise1(x).abs() <= 1.0);
+            assert!(simplex2(x, y).abs() <= 1.0);
+            assert_eq!(simplex2(x, y), simplex2(x, y));
+        }
+        assert_eq!(noise1(3.0), 0.0);
+        assert_ne!(noise1(3.5), noise1(4.5));
+    }
+
+    #[test]
+    fn test_curl_has_no_net_flux() {
+        // A divergence-free field carries as much flow into any closed loop as out of it
+        let samples = 512;
+        for center in [Vec2::new(0.3, -1.2), Vec2::new(4.0, 2.5), Vec2::new(-7.5, 0.8)] {
+            let radius = 0.4;
+            let (mut flux, mut total) = (0.0, 0.0);
+            for i in 0..samples {
+                let angle = i as f32 / samples as f32 * std::f32::consts::TAU;
+                let normal = Vec2::new(angle.cos(), angle.sin());
+                let flow = curl2(center + normal * radius, 3);
+                flux += flow.dot(normal);
+                total += flow.length();
+            }
+            assert!(total > 0.0);
+            assert!(flux.abs() < total * 0.01, "flux {} of {} around {:?}", flux, total, center);
+        }
+    }
+
+    #[test]
+    fn test_curl_velocities_are_coherent_and_near_speed() {
+        let field = CurlNoiseVelocity { scale: 100.0, speed: 20.0, octaves: 2 };
+        let offset = Vec2::new(12.5, -3.0);
+        let (mut total, mut near, mut far) = (0.0, 0.0, 0.0);
+        for i in 0..100 {
+            let position = Vec2::new((i % 10) as f32 * 37.0, (i / 10) as f32 * 41.0);
+            let here = field.sample(position, offset);
+            total += here.length();
+            near += (here - field.sample(position + Vec2::new(2.0, 0.0), offset)).length();
+            far += (here - field.sample(position + Vec2::new(500.0, 0.0), offset)).length();
+        }
+        let mean = total / 100.0;
+        assert!(mean > field.speed * 0.5 && mean < field.speed * 2.0, "mean speed {}", mean);
+        // Neighbours move together; distant particles don't
+        assert!(near * 5.0 < far, "near {} far {}", near, far);
+        assert_ne!(field.sample(Vec2::ZERO, offset), field.sample(Vec2::ZERO, Vec2::new(500.0, 500.0)));
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::VecDeque;
+
+const MAX_VISIBLE: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+impl NotificationLevel {
+    pub fn duration(&self) -> f32 {
+        match self {
+            NotificationLevel::Info => 3.0,
+            NotificationLevel::Warning => 5.0,
+            NotificationLevel::Error => 8.0,
+        }
+    }
+
+    pub fn icon(&self) -> &str {
+        match self {
+            NotificationLevel::Info => "ℹ️",
+            NotificationLevel::Warning => "⚠️",
+            NotificationLevel::Error => "❌",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub level: NotificationLevel,
+    pub message: String,
+    pub age: f32,
+}
+
+impl Toast {
+    pub fn remaining(&self) -> f32 {
+        (self.level.duration() - self.age).max(0.0)
+    }
+}
+
+// Queue of short-lived messages shown in the corner of the window
+#[derive(Debug, Default)]
+pub struct Notifications {
+    toasts: VecDeque<Toast>,
+}
+
+impl Notifications {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, level: NotificationLevel, message: impl Into<String>) {
+        let message = message.into();
+
+        // Repeated failures (e.g. every frame) refresh the existing toast instead of stacking
+        if let Some(existing) = self.toasts.iter_mut().find(|t| t.level == level && t.message == message) {
+            existing.age = 0.0;
+            return;
+        }
+
+        self.toasts.push_back(Toast { level, message, age: 0.0 });
+        while self.toasts.len() > MAX_VISIBLE {
+            self.toasts.pop_front();
+        }
+    }
+
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.push(NotificationLevel::Info, message);
+    }
+
+    pub fn warn(&mut self, message: impl Into<String>) {
+        self.push(NotificationLevel::Warning, message);
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        eprintln!("{}", message);
+        self.push(NotificationLevel::Error, message);
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for toast in &mut self.toasts {
+            toast.age += dt;
+        }
+        self.toasts.retain(|toast| toast.remaining() > 0.0);
+    }
+
+    pub fn dismiss(&mut self, index: usize) {
+        self.toasts.remove(index);
+    }
+
+    pub fn toasts(&self) -> impl Iterator<Item = &Toast> {
+        self.toasts.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.toasts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toasts_expire_by_level() {
+        let mut notifications = Notifications::new();
+        notifications.info("saved");
+        notifications.error("failed");
+
+        notifications.update(4.0);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications.toasts().next().unwrap().level, NotificationLevel::Error);
+
+        notifications.update(5.0);
+        assert!(notifications.is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_toasts_are_merged() {
+        let mut notifications = Notifications::new();
+        notifications.warn("no device");
+        notifications.update(2.0);
+        notifications.warn("no device");
+
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications.toasts().next().unwrap().age, 0.0);
+
+        for i in 0..10 {
+            notifications.info(format!("message {}", i));
+        }
+        assert_eq!(notifications.len(), MAX_VISIBLE);
+    }
+}
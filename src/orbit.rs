@@ -0,0 +1,65 @@
+use glam::Vec2;
+use std::f32::consts::TAU;
+
+// Orbital mechanics for setting up gravity scenes. `strength` is the G of ForceType::Gravity and
+// masses are particle masses, so G·M is `strength * central_mass`.
+
+// Fewest integration steps per revolution for a Verlet orbit to keep a steady radius
+pub const MIN_STEPS_PER_ORBIT: f32 = 200.0;
+
+// Speed of a circular orbit `distance` from the central mass
+pub fn circular_orbit_speed(strength: f32, central_mass: f32, distance: f32) -> f32 {
+    if distance <= 0.0 {
+        return 0.0;
+    }
+    (strength * central_mass / distance).max(0.0).sqrt()
+}
+
+// Counter-clockwise circular orbit velocity for a body at `offset` from the central mass
+pub fn circular_orbit_velocity(strength: f32, central_mass: f32, offset: Vec2) -> Vec2 {
+    offset.perp().normalize_or_zero() * circular_orbit_speed(strength, central_mass, offset.length())
+}
+
+pub fn orbital_period(strength: f32, central_mass: f32, distance: f32) -> f32 {
+    let mu = strength * central_mass;
+    if mu <= 0.0 {
+        return f32::INFINITY;
+    }
+    TAU * (distance.powi(3) / mu).sqrt()
+}
+
+// Radius of the region where a body's own gravity dominates the central mass's tides; bodies
+// that pass within it of each other get captured or flung out
+pub fn hill_radius(distance: f32, mass: f32, central_mass: f32) -> f32 {
+    if central_mass <= 0.0 {
+        return f32::INFINITY;
+    }
+    distance * (mass / (3.0 * central_mass)).cbrt()
+}
+
+// Smallest orbit whose period spans `steps_per_orbit` steps of `dt`; tighter orbits precess or
+// decay under the integrator
+pub fn min_stable_radius(strength: f32, central_mass: f32, dt: f32, steps_per_orbit: f32) -> f32 {
+    let mu = (strength * central_mass).max(0.0);
+    let period = steps_per_orbit * dt;
+    (mu * (period / TAU).powi(2)).cbrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orbit_helpers_agree() {
+        let (strength, central_mass) = (50.0, 1000.0);
+        let radius = min_stable_radius(strength, central_mass, 1.0 / 60.0, MIN_STEPS_PER_ORBIT);
+        assert!((orbital_period(strength, central_mass, radius) - MIN_STEPS_PER_ORBIT / 60.0).abs() < 1e-3);
+
+        // Period and speed describe the same circle
+        let speed = circular_orbit_speed(strength, central_mass, 100.0);
+        assert!((speed * orbital_period(strength, central_mass, 100.0) - TAU * 100.0).abs() < 1e-2);
+        assert_eq!(circular_orbit_velocity(strength, central_mass, Vec2::new(100.0, 0.0)), Vec2::new(0.0, speed));
+
+        assert!((hill_radius(100.0, 3.0, 1000.0) - 10.0).abs() < 1e-4);
+    }
+}
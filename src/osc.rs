@@ -0,0 +1,206 @@
+use serde::{Deserialize, Serialize};
+use crate::config::Preset;
+use crate::control::ControlTarget;
+use crate::forces::ForceKind;
+
+pub const ADDRESS_PREFIX: &str = "/inochi";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OscConfig {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for OscConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 9000,
+        }
+    }
+}
+
+// Argument types the endpoint understands, independent of the wire decoder
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscArgument {
+    Float(f32),
+    Int(i32),
+    Str(String),
+    Bool(bool),
+}
+
+impl OscArgument {
+    pub fn as_f32(&self) -> Option<f32> {
+        match self {
+            OscArgument::Float(value) => Some(*value),
+            OscArgument::Int(value) => Some(*value as f32),
+            OscArgument::Bool(value) => Some(if *value { 1.0 } else { 0.0 }),
+            OscArgument::Str(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscCommand {
+    Set(ControlTarget, f32),
+    Preset(String),
+    Pause(bool),
+    Reset,
+}
+
+impl OscCommand {
+    // Supported addresses:
+    //   /inochi/force/<kind>/strength f
+    //   /inochi/spawn_rate f
+    //   /inochi/trail_length f
+    //   /inochi/preset s|i
+    //   /inochi/pause [i]
+    //   /inochi/reset
+    pub fn parse(address: &str, args: &[OscArgument]) -> Option<OscCommand> {
+        let path = address.strip_prefix(ADDRESS_PREFIX)?;
+        let parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+        let first = args.first();
+
+        match parts.as_slice() {
+            ["force", kind, "strength"] => {
+                let kind = ForceKind::from_key(kind)?;
+                Some(OscCommand::Set(ControlTarget::ForceStrength(kind), first?.as_f32()?))
+            },
+            ["spawn_rate"] => Some(OscCommand::Set(ControlTarget::SpawnRate, first?.as_f32()?)),
+            ["trail_length"] => Some(OscCommand::Set(ControlTarget::TrailLength, first?.as_f32()?)),
+            ["preset"] => match first? {
+                OscArgument::Str(name) => Some(OscCommand::Preset(name.clone())),
+                other => {
+                    let index = other.as_f32()? as usize;
+                    let preset = Preset::all().into_iter().nth(index)?;
+                    Some(OscCommand::Preset(format!("{:?}", preset)))
+                },
+            },
+            ["pause"] => Some(OscCommand::Pause(first.and_then(|a| a.as_f32()).is_none_or(|v| v != 0.0))),
+            ["reset"] => Some(OscCommand::Reset),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(all(feature = "osc", not(target_arch = "wasm32")))]
+fn convert_argument(arg: &rosc::OscType) -> Option<OscArgument> {
+    match arg {
+        rosc::OscType::Float(value) => Some(OscArgument::Float(*value)),
+        rosc::OscType::Double(value) => Some(OscArgument::Float(*value as f32)),
+        rosc::OscType::Int(value) => Some(OscArgument::Int(*value)),
+        rosc::OscType::Long(value) => Some(OscArgument::Int(*value as i32)),
+        rosc::OscType::String(value) => Some(OscArgument::Str(value.clone())),
+        rosc::OscType::Bool(value) => Some(OscArgument::Bool(*value)),
+        _ => None,
+    }
+}
+
+#[cfg(all(feature = "osc", not(target_arch = "wasm32")))]
+fn collect_commands(packet: rosc::OscPacket, commands: &mut Vec<OscCommand>) {
+    match packet {
+        rosc::OscPacket::Message(message) => {
+            let args: Vec<OscArgument> = message.args.iter().filter_map(convert_argument).collect();
+            if let Some(command) = OscCommand::parse(&message.addr, &args) {
+                commands.push(command);
+            }
+        },
+        rosc::OscPacket::Bundle(bundle) => {
+            for packet in bundle.content {
+                collect_commands(packet, commands);
+            }
+        },
+    }
+}
+
+#[derive(Default)]
+pub struct OscListener {
+    #[cfg(all(feature = "osc", not(target_arch = "wasm32")))]
+    socket: Option<std::net::UdpSocket>,
+}
+
+impl OscListener {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg(all(feature = "osc", not(target_arch = "wasm32")))]
+    pub fn start(&mut self, port: u16) -> Result<(), String> {
+        let socket = std::net::UdpSocket::bind(("0.0.0.0", port)).map_err(|e| e.to_string())?;
+        socket.set_nonblocking(true).map_err(|e| e.to_string())?;
+        self.socket = Some(socket);
+        Ok(())
+    }
+
+    #[cfg(not(all(feature = "osc", not(target_arch = "wasm32"))))]
+    pub fn start(&mut self, _port: u16) -> Result<(), String> {
+        Err("OSC control requires the `osc` feature on desktop".to_string())
+    }
+
+    pub fn stop(&mut self) {
+        #[cfg(all(feature = "osc", not(target_arch = "wasm32")))]
+        {
+            self.socket = None;
+        }
+    }
+
+    pub fn is_listening(&self) -> bool {
+        #[cfg(all(feature = "osc", not(target_arch = "wasm32")))]
+        {
+            self.socket.is_some()
+        }
+        #[cfg(not(all(feature = "osc", not(target_arch = "wasm32"))))]
+        {
+            false
+        }
+    }
+
+    // Drains every datagram waiting on the socket without blocking the frame
+    pub fn poll(&mut self) -> Vec<OscCommand> {
+        #[allow(unused_mut)]
+        let mut commands = Vec::new();
+
+        #[cfg(all(feature = "osc", not(target_arch = "wasm32")))]
+        if let Some(ref socket) = self.socket {
+            let mut buffer = [0u8; rosc::decoder::MTU];
+            while let Ok(size) = socket.recv(&mut buffer) {
+                match rosc::decoder::decode_udp(&buffer[..size]) {
+                    Ok((_, packet)) => collect_commands(packet, &mut commands),
+                    Err(e) => eprintln!("Invalid OSC packet: {:?}", e),
+                }
+            }
+        }
+
+        commands
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_force_strength() {
+        let command = OscCommand::parse("/inochi/force/gravity/strength", &[OscArgument::Float(2.5)]);
+        assert_eq!(command, Some(OscCommand::Set(ControlTarget::ForceStrength(ForceKind::Gravity), 2.5)));
+
+        let command = OscCommand::parse("/inochi/force/lennard_jones/strength", &[OscArgument::Int(1)]);
+        assert_eq!(command, Some(OscCommand::Set(ControlTarget::ForceStrength(ForceKind::LennardJones), 1.0)));
+
+        assert_eq!(OscCommand::parse("/inochi/force/unknown/strength", &[OscArgument::Float(1.0)]), None);
+        assert_eq!(OscCommand::parse("/other/force/gravity/strength", &[OscArgument::Float(1.0)]), None);
+    }
+
+    #[test]
+    fn test_parse_preset_and_transport() {
+        let by_name = OscCommand::parse("/inochi/preset", &[OscArgument::Str("flocking".to_string())]);
+        assert_eq!(by_name, Some(OscCommand::Preset("flocking".to_string())));
+
+        let by_index = OscCommand::parse("/inochi/preset", &[OscArgument::Int(2)]);
+        assert_eq!(by_index, Some(OscCommand::Preset("Gravity".to_string())));
+
+        assert_eq!(OscCommand::parse("/inochi/pause", &[]), Some(OscCommand::Pause(true)));
+        assert_eq!(OscCommand::parse("/inochi/pause", &[OscArgument::Int(0)]), Some(OscCommand::Pause(false)));
+        assert_eq!(OscCommand::parse("/inochi/reset", &[]), Some(OscCommand::Reset));
+    }
+}
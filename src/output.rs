@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputConfig {
+    pub server_name: String,
+    pub width: usize,
+    pub height: usize,
+    #[serde(default)]
+    pub ndi_enabled: bool,
+    #[serde(default)]
+    pub spout_enabled: bool,
+    #[serde(default)]
+    pub syphon_enabled: bool,
+    #[serde(default = "default_frame_rate")]
+    pub frame_rate: u32,
+}
+
+fn default_frame_rate() -> u32 {
+    60
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            server_name: "Inochi".to_string(),
+            width: 1280,
+            height: 720,
+            ndi_enabled: false,
+            spout_enabled: false,
+            syphon_enabled: false,
+            frame_rate: default_frame_rate(),
+        }
+    }
+}
+
+// RGBA8 frame handed to external outputs, rows top to bottom
+#[derive(Debug, Clone)]
+pub struct VideoFrame {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+pub trait FrameSink {
+    fn name(&self) -> &str;
+    fn publish(&mut self, frame: &VideoFrame) -> Result<(), String>;
+}
+
+// Renders the view offscreen at the output resolution and reads it back for the sinks. Readback
+// completes asynchronously, so a frame reaches the sinks a frame or two after it was drawn.
+#[cfg(feature = "desktop")]
+pub struct FrameCapture {
+    texture: nannou::wgpu::Texture,
+    renderer: nannou::draw::Renderer,
+    capturer: nannou::wgpu::TextureCapturer,
+    sender: std::sync::mpsc::Sender<VideoFrame>,
+    receiver: std::sync::mpsc::Receiver<VideoFrame>,
+}
+
+#[cfg(feature = "desktop")]
+impl FrameCapture {
+    pub fn new(device: &nannou::wgpu::Device, width: usize, height: usize) -> Self {
+        use nannou::wgpu;
+
+        let texture = wgpu::TextureBuilder::new()
+            .size([width.max(1) as u32, height.max(1) as u32])
+            .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING)
+            .sample_count(1)
+            .format(nannou::Frame::TEXTURE_FORMAT)
+            .build(device);
+        let renderer = nannou::draw::RendererBuilder::new().build_from_texture_descriptor(device, texture.descriptor());
+        let (sender, receiver) = std::sync::mpsc::channel();
+        Self {
+            texture,
+            renderer,
+            capturer: wgpu::TextureCapturer::default(),
+            sender,
+            receiver,
+        }
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        let [width, height] = self.texture.size();
+        (width as usize, height as usize)
+    }
+
+    // Renders `draw` into the output texture and starts reading it back
+    pub fn capture(&mut self, device: &nannou::wgpu::Device, queue: &nannou::wgpu::Queue, draw: &nannou::Draw) -> Result<(), String> {
+        let descriptor = nannou::wgpu::CommandEncoderDescriptor { label: Some("video output") };
+        let mut encoder = device.create_command_encoder(&descriptor);
+        self.renderer.render_to_texture(device, &mut encoder, draw, &self.texture);
+        let snapshot = self.capturer.capture(device, &mut encoder, &self.texture);
+        queue.submit(Some(encoder.finish()));
+
+        let sender = self.sender.clone();
+        snapshot
+            .read(move |result| {
+                if let Ok(image) = result {
+                    let image = image.to_owned();
+                    let (width, height) = (image.width() as usize, image.height() as usize);
+                    let _ = sender.send(VideoFrame { width, height, pixels: image.into_raw() });
+                }
+            })
+            .map_err(|e| format!("Failed to read back the output frame: {}", e))
+    }
+
+    // The newest frame read back since the last call, dropping any older ones
+    pub fn latest(&self) -> Option<VideoFrame> {
+        self.receiver.try_iter().last()
+    }
+
+    // Waits for readbacks still in flight, so none outlive the device
+    pub fn finish(&self, device: &nannou::wgpu::Device) {
+        let _ = self.capturer.await_active_snapshots(device);
+    }
+}
+
+#[derive(Default)]
+pub struct OutputManager {
+    sinks: Vec<Box<dyn FrameSink>>,
+    pub last_error: Option<String>,
+}
+
+impl OutputManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_sink(&mut self, sink: Box<dyn FrameSink>) {
+        self.sinks.push(sink);
+    }
+
+    pub fn remove_sink(&mut self, name: &str) {
+        self.sinks.retain(|sink| sink.name() != name);
+    }
+
+    pub fn sink_names(&self) -> Vec<String> {
+        self.sinks.iter().map(|sink| sink.name().to_string()).collect()
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.sinks.is_empty()
+    }
+
+    pub fn publish(&mut self, frame: &VideoFrame) {
+        for sink in &mut self.sinks {
+            if let Err(e) = sink.publish(frame) {
+                self.last_error = Some(format!("{}: {}", sink.name(), e));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NullSink;
+
+    impl FrameSink for NullSink {
+        fn name(&self) -> &str {
+            "null"
+        }
+
+        fn publish(&mut self, _frame: &VideoFrame) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_output_manager_sinks() {
+        let mut manager = OutputManager::new();
+        assert!(!manager.is_active());
+
+        manager.add_sink(Box::new(NullSink));
+        assert!(manager.is_active());
+
+        let frame = VideoFrame { width: 1, height: 1, pixels: vec![0; 4] };
+        manager.publish(&frame);
+        assert!(manager.last_error.is_none());
+
+        manager.remove_sink("null");
+        assert!(!manager.is_active());
+    }
+}
@@ -1,6 +1,10 @@
 use glam::Vec2;
 use serde::{Deserialize, Serialize};
 use bytemuck::{Pod, Zeroable};
+use crate::config::SpawnArea;
+use crate::pool::{ParticleId, ParticlePool};
+use crate::rng;
+use crate::spatial::SpatialPartitioning;
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Pod, Zeroable)]
 #[repr(C)]
@@ -11,12 +15,25 @@ pub struct Particle {
     pub mass: f32,
     pub charge: f32,
     pub age: f32,
+    #[serde(with = "crate::config::lifespan")]
     pub lifespan: f32,
     pub color: [f32; 4],
     pub species_id: u32,
     pub energy: f32,
     pub size: f32,
     pub temperature: f32,
+    // Position before the last physics step. Verlet and leapfrog keep their state here so it
+    // follows the particle through spawns, deaths and reordering; unused until the first step.
+    #[serde(default = "unset_previous_position")]
+    pub previous_position: Vec2,
+    // Assigned by the ParticleSystem the particle is added to; copies must not keep it
+    #[serde(default)]
+    pub id: ParticleId,
+}
+
+// Particles saved before previous_position existed may already have aged, so mark theirs unset
+fn unset_previous_position() -> Vec2 {
+    Vec2::NAN
 }
 
 impl Default for Particle {
@@ -34,6 +51,8 @@ impl Default for Particle {
             energy: 1.0,
             size: 1.0,
             temperature: 1.0,
+            previous_position: Vec2::ZERO,
+            id: ParticleId::default(),
         }
     }
 }
@@ -98,11 +117,6 @@ impl Particle {
         self.position += self.velocity * dt;
         self.age += dt;
         self.acceleration = Vec2::ZERO;
-        
-        self.energy = self.velocity.length_squared() * 0.5 * self.mass;
-        
-        let life_factor = self.life_ratio();
-        self.color[3] = life_factor;
     }
 
     pub fn apply_force(&mut self, force: Vec2) {
@@ -144,6 +158,24 @@ impl Particle {
     }
 }
 
+// Why a particle was taken out through the despawn API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DespawnCause {
+    // remove_particle
+    Removed,
+    // despawn_in_radius
+    Radius,
+    // kill_species
+    Species,
+}
+
+// Something that happened to a particle outside the normal step, queued on the ParticleSystem
+// until the app hands it to its listeners
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ParticleEvent {
+    Despawned { id: ParticleId, species_id: u32, position: Vec2, cause: DespawnCause },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParticleSystem {
     pub particles: Vec<Particle>,
@@ -153,6 +185,16 @@ pub struct ParticleSystem {
     pub bounds: Option<(Vec2, Vec2)>,
     pub wrap_boundaries: bool,
     pub damping: f32,
+    // Generational ids for the particles, so they can be referred to across deaths and spawns
+    #[serde(default)]
+    pub pool: ParticlePool,
+    // Not saved: events belong to the frame they happened in
+    #[serde(skip)]
+    pub events: Vec<ParticleEvent>,
+    // Write side of `double_buffered`, swapped with `particles` after each pass and kept so the
+    // next pass doesn't allocate
+    #[serde(skip)]
+    back_buffer: Vec<Particle>,
 }
 
 impl ParticleSystem {
@@ -165,32 +207,189 @@ impl ParticleSystem {
             bounds: None,
             wrap_boundaries: false,
             damping: 0.99,
+            pool: ParticlePool::default(),
+            events: Vec::new(),
+            back_buffer: Vec::new(),
         }
     }
 
-    pub fn add_particle(&mut self, particle: Particle) {
+    pub fn add_particle(&mut self, mut particle: Particle) {
         if self.particles.len() < self.max_particles {
+            particle.id = self.pool.allocate(self.particles.len());
             self.particles.push(particle);
         }
     }
 
-    pub fn spawn_particle_at(&mut self, position: Vec2) {
-        if self.particles.len() < self.max_particles {
-            self.add_particle(Particle::new(position));
+    // Runs a pass where each particle reads all the others. `pass` reads the particles as they
+    // are and writes the next state into the back buffer, which then swaps in as the live state,
+    // so every read sees the same snapshot however the writes are ordered. The back buffer holds
+    // stale particles, so the pass must start each slot from its read particle before adding to it.
+    pub fn double_buffered(&mut self, pass: impl FnOnce(&[Particle], &mut [Particle])) {
+        self.back_buffer.resize(self.particles.len(), Particle::default());
+        pass(&self.particles, &mut self.back_buffer);
+        std::mem::swap(&mut self.particles, &mut self.back_buffer);
+    }
+
+    // Where the particle with this id is now, if it is still alive. Code that edits `particles`
+    // directly can leave the pool behind until the next sync_ids, so a stale record falls back
+    // to a search.
+    pub fn index_of(&self, id: ParticleId) -> Option<usize> {
+        if !id.is_assigned() {
+            return None;
+        }
+        match self.pool.index_of(id) {
+            Some(index) if self.particles.get(index).is_some_and(|particle| particle.id == id) => Some(index),
+            _ => self.particles.iter().position(|particle| particle.id == id),
         }
     }
 
+    pub fn get(&self, id: ParticleId) -> Option<&Particle> {
+        self.index_of(id).map(|index| &self.particles[index])
+    }
+
+    pub fn get_mut(&mut self, id: ParticleId) -> Option<&mut Particle> {
+        self.index_of(id).map(|index| &mut self.particles[index])
+    }
+
+    // Every particle with its id, in storage order. Ids stay valid as other particles come and
+    // go, unlike the indices of `particles`.
+    pub fn iter(&self) -> impl Iterator<Item = (ParticleId, &Particle)> + '_ {
+        self.particles.iter().map(|particle| (particle.id, particle))
+    }
+
+    pub fn iter_species(&self, species_id: u32) -> impl Iterator<Item = (ParticleId, &Particle)> + '_ {
+        self.iter_filtered(move |particle| particle.species_id == species_id)
+    }
+
+    pub fn iter_filtered<'a>(&'a self, mut filter: impl FnMut(&Particle) -> bool + 'a) -> impl Iterator<Item = (ParticleId, &'a Particle)> + 'a {
+        self.iter().filter(move |(_, particle)| filter(particle))
+    }
+
+    // Particles within `radius` of `center`, in storage order. Candidates come from the spatial
+    // index when there is one; distances are checked against current positions either way.
+    pub fn iter_in_radius<'a>(&'a self, center: Vec2, radius: f32, spatial: Option<&SpatialPartitioning>) -> impl Iterator<Item = (ParticleId, &'a Particle)> + 'a {
+        let mut candidates = match spatial {
+            Some(spatial) => spatial.query_range((center - Vec2::splat(radius), center + Vec2::splat(radius))),
+            None => (0..self.particles.len()).collect(),
+        };
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates
+            .into_iter()
+            .filter_map(|index| self.particles.get(index))
+            .filter(move |particle| particle.position.distance_squared(center) <= radius * radius)
+            .map(|particle| (particle.id, particle))
+    }
+
+    // Brings the pool back in line after `particles` was edited directly: particles pushed
+    // without an id (or duplicating another's) get a fresh one, and slots of particles that are
+    // gone are freed
+    pub fn sync_ids(&mut self) {
+        let mut seen = vec![false; self.pool.capacity()];
+        for index in 0..self.particles.len() {
+            let id = self.particles[index].id;
+            let slot = id.slot as usize;
+            if self.pool.index_of(id).is_some() && !seen[slot] {
+                self.pool.relocate(id, index);
+            } else {
+                let id = self.pool.allocate(index);
+                seen.resize(self.pool.capacity(), false);
+                self.particles[index].id = id;
+            }
+            seen[self.particles[index].id.slot as usize] = true;
+        }
+        let stale: Vec<ParticleId> = (0..self.pool.capacity())
+            .filter(|&slot| !seen[slot])
+            .filter_map(|slot| self.pool.id_of_slot(slot as u32))
+            .collect();
+        for id in stale {
+            self.pool.release(id);
+        }
+    }
+
+    // Removes dead particles by swapping the last one into each gap, so the rest stay where they
+    // are in memory and only the moved particle's pool record changes
+    pub fn remove_dead(&mut self) {
+        self.despawn_where(|particle| !particle.is_alive(), None);
+    }
+
+    // Takes out one particle; None if it is already gone
+    pub fn remove_particle(&mut self, id: ParticleId) -> Option<Particle> {
+        let index = self.index_of(id)?;
+        Some(self.swap_remove(index, Some(DespawnCause::Removed)))
+    }
+
+    // Removes every particle within `radius` of `center`; returns how many went
+    pub fn despawn_in_radius(&mut self, center: Vec2, radius: f32) -> usize {
+        let radius_squared = radius * radius;
+        self.despawn_where(|particle| particle.position.distance_squared(center) <= radius_squared, Some(DespawnCause::Radius))
+    }
+
+    // Removes every particle of a species; returns how many went
+    pub fn kill_species(&mut self, species_id: u32) -> usize {
+        self.despawn_where(|particle| particle.species_id == species_id, Some(DespawnCause::Species))
+    }
+
+    // Events queued since the last call, oldest first
+    pub fn drain_events(&mut self) -> Vec<ParticleEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    // Swap-removes the matching particles, queueing a Despawned event for each when there is a
+    // cause to report
+    fn despawn_where(&mut self, mut doomed: impl FnMut(&Particle) -> bool, cause: Option<DespawnCause>) -> usize {
+        let before = self.particles.len();
+        let mut index = 0;
+        while index < self.particles.len() {
+            if doomed(&self.particles[index]) {
+                self.swap_remove(index, cause);
+            } else {
+                index += 1;
+            }
+        }
+        before - self.particles.len()
+    }
+
+    fn swap_remove(&mut self, index: usize, cause: Option<DespawnCause>) -> Particle {
+        let removed = self.particles.swap_remove(index);
+        self.pool.release(removed.id);
+        if let Some(moved) = self.particles.get(index) {
+            self.pool.relocate(moved.id, index);
+        }
+        if let Some(cause) = cause {
+            self.events.push(ParticleEvent::Despawned {
+                id: removed.id,
+                species_id: removed.species_id,
+                position: removed.position,
+                cause,
+            });
+        }
+        removed
+    }
+
+    // Spawns up to `count` particles at random points in `area`, drawing from the simulation's
+    // random stream; returns how many fit under max_particles
+    pub fn burst(&mut self, count: usize, area: &SpawnArea) -> usize {
+        let count = count.min(self.max_particles.saturating_sub(self.particles.len()));
+        let mut rng = rng::state();
+        for _ in 0..count {
+            self.add_particle(Particle::new(area.sample(&mut rng)));
+        }
+        rng::set_state(rng);
+        count
+    }
+
     pub fn update(&mut self, dt: f32) {
-        self.spawn_timer += dt;
-        
-        if self.spawn_timer >= 1.0 / self.spawn_rate {
-            if let Some((min_bounds, max_bounds)) = self.bounds {
-                let spawn_pos = Vec2::new(
-                    rand::random::<f32>() * (max_bounds.x - min_bounds.x) + min_bounds.x,
-                    rand::random::<f32>() * (max_bounds.y - min_bounds.y) + min_bounds.y,
-                );
-                self.spawn_particle_at(spawn_pos);
-                self.spawn_timer = 0.0;
+        self.sync_ids();
+
+        // Spawns owed since the last frame carry over, so rates above the frame rate still add
+        // `spawn_rate` particles a second; only the fraction of the next one stays in the timer
+        if self.spawn_rate > 0.0 {
+            self.spawn_timer += dt;
+            let due = (self.spawn_timer * self.spawn_rate).floor();
+            self.spawn_timer -= due / self.spawn_rate;
+            if let Some((min, max)) = self.bounds {
+                self.burst(due as usize, &SpawnArea::Rectangle { min, max });
             }
         }
 
@@ -205,7 +404,7 @@ impl ParticleSystem {
             }
         }
 
-        self.particles.retain(|p| p.is_alive());
+        self.remove_dead();
     }
 
     fn apply_boundary_conditions(particle: &mut Particle, min_bounds: Vec2, max_bounds: Vec2, wrap_boundaries: bool) {
@@ -270,7 +469,9 @@ impl ParticleSystem {
     }
 
     pub fn clear(&mut self) {
-        self.particles.clear();
+        for particle in self.particles.drain(..) {
+            self.pool.release(particle.id);
+        }
     }
 
     pub fn set_bounds(&mut self, min: Vec2, max: Vec2) {
@@ -323,4 +524,138 @@ mod tests {
         system.add_particle(Particle::new(Vec2::ZERO));
         assert_eq!(system.particle_count(), 1);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_spawn_rate_is_not_capped_by_frame_rate() {
+        let mut system = ParticleSystem::new(1000);
+        system.bounds = Some((Vec2::ZERO, Vec2::splat(100.0)));
+        system.spawn_rate = 600.0;
+        system.update(0.25);
+        assert_eq!(system.particle_count(), 150);
+
+        // Fractions carry over: two a second at four frames a second is one every other frame
+        let mut system = ParticleSystem::new(1000);
+        system.bounds = Some((Vec2::ZERO, Vec2::splat(100.0)));
+        system.spawn_rate = 2.0;
+        let counts: Vec<usize> = (0..4)
+            .map(|_| {
+                system.update(0.25);
+                system.particle_count()
+            })
+            .collect();
+        assert_eq!(counts, vec![0, 1, 1, 2]);
+    }
+
+    #[test]
+    fn test_burst_fills_area_up_to_the_limit() {
+        let mut system = ParticleSystem::new(50);
+        let area = SpawnArea::Ring { center: Vec2::new(10.0, 0.0), inner_radius: 5.0, outer_radius: 8.0 };
+        assert_eq!(system.burst(40, &area), 40);
+        assert!(system.particles.iter().all(|particle| {
+            let distance = particle.position.distance(Vec2::new(10.0, 0.0));
+            (5.0 - 1e-3..=8.0 + 1e-3).contains(&distance)
+        }));
+        assert_eq!(system.burst(40, &SpawnArea::Point(Vec2::ZERO)), 10);
+        assert_eq!(system.particle_count(), 50);
+    }
+
+    #[test]
+    fn test_ids_follow_particles_through_deaths_and_direct_edits() {
+        let mut system = ParticleSystem::new(10);
+        system.spawn_rate = 0.0;
+        for x in 0..4 {
+            system.add_particle(Particle::new(Vec2::new(x as f32, 0.0)).with_lifespan(if x == 1 { 0.5 } else { f32::INFINITY }));
+        }
+        let ids: Vec<ParticleId> = system.particles.iter().map(|particle| particle.id).collect();
+
+        system.update(1.0);
+        assert_eq!(system.particle_count(), 3);
+        assert_eq!(system.get(ids[1]), None);
+        // The last particle fills the gap rather than everything after it shifting down
+        assert_eq!(system.index_of(ids[3]), Some(1));
+        assert_eq!(system.index_of(ids[2]), Some(2));
+
+        // A recycled slot gets a new generation, so the dead particle's id stays dead
+        system.add_particle(Particle::new(Vec2::ZERO));
+        assert_eq!(system.particles[3].id.slot, ids[1].slot);
+        assert_eq!(system.get(ids[1]), None);
+
+        // Edits that bypass add_particle are picked up on the next sync
+        system.particles.remove(0);
+        system.particles.push(system.particles[0]);
+        system.sync_ids();
+        assert_eq!(system.get(ids[0]), None);
+        assert_eq!(system.index_of(ids[3]), Some(0));
+        assert_ne!(system.particles[3].id, ids[3]);
+        assert_eq!(system.pool.live(), 4);
+    }
+
+    #[test]
+    fn test_despawn_api_removes_and_reports() {
+        let mut system = ParticleSystem::new(10);
+        for x in 0..6 {
+            system.add_particle(Particle::new(Vec2::new(x as f32 * 10.0, 0.0)).with_species(x % 2));
+        }
+        let ids: Vec<ParticleId> = system.particles.iter().map(|particle| particle.id).collect();
+
+        assert_eq!(system.remove_particle(ids[0]).map(|particle| particle.position), Some(Vec2::ZERO));
+        assert_eq!(system.remove_particle(ids[0]), None);
+        assert_eq!(system.despawn_in_radius(Vec2::new(20.0, 0.0), 10.0), 3);
+        assert_eq!(system.kill_species(1), 1);
+        assert_eq!(system.particle_count(), 1);
+        assert!(system.get(ids[4]).is_some());
+
+        let events = system.drain_events();
+        assert_eq!(events.len(), 5);
+        assert_eq!(
+            events[0],
+            ParticleEvent::Despawned { id: ids[0], species_id: 0, position: Vec2::ZERO, cause: DespawnCause::Removed }
+        );
+        assert!(matches!(events[4], ParticleEvent::Despawned { id, cause: DespawnCause::Species, .. } if id == ids[5]));
+        assert!(system.drain_events().is_empty());
+    }
+
+    #[test]
+    fn test_queries_yield_ids_not_indices() {
+        let mut system = ParticleSystem::new(20);
+        for x in 0..10 {
+            system.add_particle(Particle::new(Vec2::new(x as f32 * 10.0, 0.0)).with_species(x % 3));
+        }
+        let mut spatial = SpatialPartitioning::new_grid(15.0, (Vec2::splat(-50.0), Vec2::splat(150.0)));
+        spatial.update(&system.particles);
+
+        let near: Vec<ParticleId> = system.iter_in_radius(Vec2::new(30.0, 0.0), 10.0, Some(&spatial)).map(|(id, _)| id).collect();
+        let ids = |indices: &[usize]| indices.iter().map(|&index| system.particles[index].id).collect::<Vec<_>>();
+        assert_eq!(near, ids(&[2, 3, 4]));
+        assert_eq!(system.iter_in_radius(Vec2::new(30.0, 0.0), 10.0, None).count(), 3);
+
+        let species: Vec<ParticleId> = system.iter_species(1).map(|(id, _)| id).collect();
+        assert_eq!(species, ids(&[1, 4, 7]));
+        assert_eq!(system.iter_filtered(|particle| particle.position.x > 75.0).count(), 2);
+
+        // Ids still find the same particles after others are removed
+        system.remove_particle(species[0]);
+        let remaining: Vec<(ParticleId, f32)> = system.iter_species(1).map(|(id, particle)| (id, particle.position.x)).collect();
+        assert_eq!(remaining, vec![(species[1], 40.0), (species[2], 70.0)]);
+        assert_eq!(system.get(species[2]).map(|particle| particle.position.x), Some(70.0));
+    }
+
+    #[test]
+    fn test_double_buffered_pass_reads_one_snapshot() {
+        let mut system = ParticleSystem::new(10);
+        for x in 0..3 {
+            system.add_particle(Particle::new(Vec2::new(x as f32, 0.0)));
+        }
+        // Each particle moves to the sum of the positions it reads; later ones must not see
+        // earlier writes
+        system.double_buffered(|read, write| {
+            let sum: Vec2 = read.iter().map(|particle| particle.position).sum();
+            for (slot, particle) in write.iter_mut().zip(read) {
+                *slot = *particle;
+                slot.position = sum;
+            }
+        });
+        assert!(system.particles.iter().all(|particle| particle.position == Vec2::new(3.0, 0.0)));
+        assert_eq!(system.index_of(system.particles[2].id), Some(2));
+    }
+}
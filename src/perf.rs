@@ -0,0 +1,128 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+// Seconds of frame timings kept for the performance plots
+pub const HISTORY_SECONDS: f32 = 10.0;
+// Weight of the newest frame in the smoothed timings
+const SMOOTHING: f32 = 0.1;
+
+// Elapsed time in fractional milliseconds
+pub fn millis(elapsed: Duration) -> f32 {
+    elapsed.as_secs_f32() * 1000.0
+}
+
+// Exponential moving average of per-frame timings; the first sample seeds it
+pub fn smooth(average: f32, sample: f32) -> f32 {
+    if average == 0.0 {
+        sample
+    } else {
+        average + (sample - average) * SMOOTHING
+    }
+}
+
+// Timings of one frame, in milliseconds, at `time` seconds on the history's clock
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameSample {
+    pub time: f32,
+    pub frame_ms: f32,
+    pub update_ms: f32,
+    pub render_ms: f32,
+}
+
+// Rolling per-frame timings over the last `HISTORY_SECONDS`, so stutter shows up even when the
+// per-second averages look fine
+#[derive(Debug, Clone, Default)]
+pub struct FrameHistory {
+    samples: VecDeque<FrameSample>,
+    clock: f32,
+}
+
+impl FrameHistory {
+    // Starts a frame that took `dt` seconds, of which `update_ms` went to the simulation
+    pub fn record(&mut self, dt: f32, update_ms: f32) {
+        self.clock += dt;
+        self.samples.push_back(FrameSample {
+            time: self.clock,
+            frame_ms: dt * 1000.0,
+            update_ms,
+            render_ms: 0.0,
+        });
+        while self.samples.front().is_some_and(|sample| sample.time < self.clock - HISTORY_SECONDS) {
+            self.samples.pop_front();
+        }
+    }
+
+    // Render time arrives after the frame was recorded
+    pub fn set_render_time(&mut self, render_ms: f32) {
+        if let Some(sample) = self.samples.back_mut() {
+            sample.render_ms = render_ms;
+        }
+    }
+
+    pub fn samples(&self) -> &VecDeque<FrameSample> {
+        &self.samples
+    }
+
+    // Seconds since the history started; sample times count up to this
+    pub fn now(&self) -> f32 {
+        self.clock
+    }
+
+    // Nearest-rank percentile (0–100) of one timing over the kept frames
+    pub fn percentile(&self, timing: fn(&FrameSample) -> f32, percentile: f32) -> f32 {
+        let mut values: Vec<f32> = self.samples.iter().map(timing).collect();
+        if values.is_empty() {
+            return 0.0;
+        }
+        values.sort_by(f32::total_cmp);
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * values.len() as f32).ceil() as usize;
+        values[rank.clamp(1, values.len()) - 1]
+    }
+
+    pub fn mean(&self, timing: fn(&FrameSample) -> f32) -> f32 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        self.samples.iter().map(timing).sum::<f32>() / self.samples.len() as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_catch_the_slow_frames() {
+        let mut history = FrameHistory::default();
+        for ms in 1..=100 {
+            history.record(0.001, ms as f32);
+        }
+        let update = |sample: &FrameSample| sample.update_ms;
+        assert_eq!(history.percentile(update, 50.0), 50.0);
+        assert_eq!(history.percentile(update, 95.0), 95.0);
+        assert_eq!(history.percentile(update, 99.0), 99.0);
+        assert_eq!(history.percentile(update, 100.0), 100.0);
+        assert_eq!(history.mean(update), 50.5);
+
+        history.set_render_time(7.0);
+        assert_eq!(history.samples().back().unwrap().render_ms, 7.0);
+    }
+
+    #[test]
+    fn test_timings_keep_fractions_and_smooth() {
+        assert_eq!(millis(Duration::from_micros(250)), 0.25);
+        assert_eq!(smooth(0.0, 4.0), 4.0);
+        assert_eq!(smooth(4.0, 14.0), 5.0);
+    }
+
+    #[test]
+    fn test_old_frames_roll_off() {
+        let mut history = FrameHistory::default();
+        for _ in 0..(HISTORY_SECONDS as usize * 2 * 10) {
+            history.record(0.1, 1.0);
+        }
+        let oldest = history.samples().front().unwrap().time;
+        assert!(history.now() - oldest <= HISTORY_SECONDS);
+        assert!(history.samples().len() <= HISTORY_SECONDS as usize * 10 + 1);
+    }
+}
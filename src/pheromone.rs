@@ -0,0 +1,241 @@
+use glam::Vec2;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::particle::{Particle, ParticleSystem};
+use crate::rng;
+
+// Rate at which a trail follower's velocity closes on its chosen heading, per second
+const STEERING_RESPONSE: f32 = 20.0;
+// World used when the particle system has no bounds
+const DEFAULT_BOUNDS: (Vec2, Vec2) = (Vec2::new(-400.0, -300.0), Vec2::new(400.0, 300.0));
+
+// Chemotaxis in the style of Physarum slime mould: particles lay a scent that spreads and fades on
+// a grid, and steer toward whichever of three sensors ahead of them smells strongest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PheromoneConfig {
+    pub enabled: bool,
+    // Species that lay and follow trails; empty means every species
+    pub species: Vec<u32>,
+    // Width of a grid cell in world units
+    pub cell_size: f32,
+    // Trail each particle lays per second
+    pub deposit: f32,
+    // How fast trails blur into neighbouring cells, per second
+    pub diffusion: f32,
+    // Fraction of the trail that fades per second
+    pub evaporation: f32,
+    // Sensors sit this far ahead, one straight on and one to each side
+    pub sensor_distance: f32,
+    pub sensor_angle: f32,
+    // Degrees per second a follower turns toward the strongest sensor
+    pub turn_rate: f32,
+    // Followers keep moving at this speed
+    pub speed: f32,
+    // Draw the trail grid under the particles
+    pub show: bool,
+    pub color: [f32; 4],
+}
+
+impl Default for PheromoneConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            species: Vec::new(),
+            cell_size: 4.0,
+            deposit: 5.0,
+            diffusion: 3.0,
+            evaporation: 0.8,
+            sensor_distance: 14.0,
+            sensor_angle: 45.0,
+            turn_rate: 900.0,
+            speed: 40.0,
+            show: true,
+            color: [1.0, 0.85, 0.3, 0.6],
+        }
+    }
+}
+
+impl PheromoneConfig {
+    pub fn follows(&self, particle: &Particle) -> bool {
+        self.species.is_empty() || self.species.contains(&particle.species_id)
+    }
+}
+
+// Trail strength on a regular grid covering the world
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PheromoneField {
+    origin: Vec2,
+    cell_size: f32,
+    columns: usize,
+    rows: usize,
+    values: Vec<f32>,
+}
+
+impl PheromoneField {
+    pub fn new(bounds: (Vec2, Vec2), cell_size: f32) -> Self {
+        let (cell_size, columns, rows) = Self::dimensions(bounds, cell_size);
+        Self {
+            origin: bounds.0,
+            cell_size,
+            columns,
+            rows,
+            values: vec![0.0; columns * rows],
+        }
+    }
+
+    fn dimensions(bounds: (Vec2, Vec2), cell_size: f32) -> (f32, usize, usize) {
+        let cell_size = cell_size.max(1.0);
+        let extent = (bounds.1 - bounds.0).max(Vec2::splat(cell_size));
+        (cell_size, (extent.x / cell_size).ceil() as usize, (extent.y / cell_size).ceil() as usize)
+    }
+
+    // Whether this grid was built for these bounds and cell size
+    pub fn matches(&self, bounds: (Vec2, Vec2), cell_size: f32) -> bool {
+        self.origin == bounds.0 && (self.cell_size, self.columns, self.rows) == Self::dimensions(bounds, cell_size)
+    }
+
+    fn cell(&self, position: Vec2) -> Option<usize> {
+        let local = (position - self.origin) / self.cell_size;
+        if local.x < 0.0 || local.y < 0.0 {
+            return None;
+        }
+        let (column, row) = (local.x as usize, local.y as usize);
+        (column < self.columns && row < self.rows).then_some(row * self.columns + column)
+    }
+
+    // Trail strength at `position`; nothing outside the grid
+    pub fn sample(&self, position: Vec2) -> f32 {
+        self.cell(position).map_or(0.0, |index| self.values[index])
+    }
+
+    pub fn deposit(&mut self, position: Vec2, amount: f32) {
+        if let Some(index) = self.cell(position) {
+            self.values[index] += amount;
+        }
+    }
+
+    // Blends each cell toward the mean of its 3×3 neighbourhood, then lets everything fade
+    pub fn diffuse(&mut self, diffusion: f32, evaporation: f32, dt: f32) {
+        let blend = (diffusion * dt).clamp(0.0, 1.0);
+        let keep = (1.0 - evaporation * dt).clamp(0.0, 1.0);
+        let (columns, rows) = (self.columns, self.rows);
+        let mut next = vec![0.0; self.values.len()];
+        for row in 0..rows {
+            for column in 0..columns {
+                let (mut total, mut count) = (0.0, 0.0);
+                for r in row.saturating_sub(1)..=(row + 1).min(rows - 1) {
+                    for c in column.saturating_sub(1)..=(column + 1).min(columns - 1) {
+                        total += self.values[r * columns + c];
+                        count += 1.0;
+                    }
+                }
+                let index = row * columns + column;
+                let value = self.values[index];
+                next[index] = (value + (total / count - value) * blend) * keep;
+            }
+        }
+        self.values = next;
+    }
+
+    pub fn total(&self) -> f32 {
+        self.values.iter().sum()
+    }
+
+    pub fn max_value(&self) -> f32 {
+        self.values.iter().copied().fold(0.0, f32::max)
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    // Centre and strength of every cell
+    pub fn cells(&self) -> impl Iterator<Item = (Vec2, f32)> + '_ {
+        self.values.iter().enumerate().map(move |(index, &value)| {
+            let (column, row) = (index % self.columns, index / self.columns);
+            (self.origin + (Vec2::new(column as f32, row as f32) + 0.5) * self.cell_size, value)
+        })
+    }
+
+    // Steers every follower toward the strongest of its sensors, then lays its trail
+    pub fn steer<R: Rng>(&mut self, config: &PheromoneConfig, particles: &mut [Particle], dt: f32, rng: &mut R) {
+        let sensor_angle = config.sensor_angle.to_radians();
+        let turn = config.turn_rate.to_radians() * dt;
+        for particle in particles.iter_mut().filter(|particle| config.follows(particle)) {
+            let heading = particle
+                .velocity
+                .try_normalize()
+                .unwrap_or_else(|| Vec2::from_angle(rng.gen::<f32>() * std::f32::consts::TAU));
+            let smell = |angle: f32| self.sample(particle.position + Vec2::from_angle(angle).rotate(heading) * config.sensor_distance);
+            let (left, front, right) = (smell(sensor_angle), smell(0.0), smell(-sensor_angle));
+
+            let rotation = if front >= left && front >= right {
+                0.0
+            } else if front < left && front < right {
+                // Both sides beat straight on: pick one at random
+                if rng.gen::<bool>() { turn } else { -turn }
+            } else if left > right {
+                turn
+            } else {
+                -turn
+            };
+            let desired = Vec2::from_angle(rotation).rotate(heading) * config.speed;
+            particle.acceleration += (desired - particle.velocity) * STEERING_RESPONSE;
+            self.deposit(particle.position, config.deposit * dt);
+        }
+    }
+}
+
+// Steps the trail subsystem for one physics step, building the grid over the system's bounds and
+// rebuilding it when the cell size changes. Drops the grid when pheromones are off.
+pub fn update_pheromones(field: &mut Option<PheromoneField>, config: &PheromoneConfig, system: &mut ParticleSystem, dt: f32) {
+    if !config.enabled {
+        *field = None;
+        return;
+    }
+    let bounds = system.bounds.unwrap_or(DEFAULT_BOUNDS);
+    if !field.as_ref().is_some_and(|field| field.matches(bounds, config.cell_size)) {
+        *field = Some(PheromoneField::new(bounds, config.cell_size));
+    }
+    if let Some(field) = field.as_mut() {
+        field.steer(config, &mut system.particles, dt, &mut rng::fork());
+        field.diffuse(config.diffusion, config.evaporation, dt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trails_spread_and_fade() {
+        let mut field = PheromoneField::new((Vec2::ZERO, Vec2::splat(40.0)), 4.0);
+        field.deposit(Vec2::splat(20.0), 9.0);
+        assert_eq!(field.sample(Vec2::splat(21.0)), 9.0);
+        assert_eq!(field.sample(Vec2::splat(-5.0)), 0.0);
+
+        // Pure diffusion only moves the trail around
+        field.diffuse(10.0, 0.0, 0.1);
+        assert!((field.total() - 9.0).abs() < 1e-4);
+        assert!(field.sample(Vec2::splat(25.0)) > 0.0);
+        assert!(field.sample(Vec2::splat(21.0)) < 9.0);
+
+        field.diffuse(0.0, 0.5, 0.1);
+        assert!((field.total() - 9.0 * 0.95).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_followers_turn_toward_the_trail() {
+        let config = PheromoneConfig { enabled: true, ..PheromoneConfig::default() };
+        let mut field = PheromoneField::new((Vec2::splat(-100.0), Vec2::splat(100.0)), config.cell_size);
+        // A scent ahead and to the left of a particle heading along +x
+        let left = Vec2::from_angle(config.sensor_angle.to_radians()) * config.sensor_distance;
+        field.deposit(left, 10.0);
+
+        let mut particles = vec![Particle::new(Vec2::ZERO).with_velocity(Vec2::new(config.speed, 0.0))];
+        let mut rng = rng::fork();
+        field.steer(&config, &mut particles, 1.0 / 60.0, &mut rng);
+        assert!(particles[0].acceleration.y > 0.0);
+        assert!(field.sample(Vec2::ZERO) > 0.0);
+    }
+}
@@ -0,0 +1,100 @@
+// Screen-sized buffer of particle indices, the equivalent of an ID render pass. Frames only mark
+// it stale; it is redrawn when something asks what is under the cursor, after which further
+// lookups in the same frame are a single read however many particles there are.
+const EMPTY: u32 = u32::MAX;
+// Small particles still get a clickable area of at least this radius, in pixels
+const MIN_PICK_RADIUS: f32 = 2.0;
+
+#[derive(Debug, Clone, Default)]
+pub struct PickingBuffer {
+    width: u32,
+    height: u32,
+    ids: Vec<u32>,
+    // Drawn since the last invalidate
+    fresh: bool,
+}
+
+impl PickingBuffer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            ids: vec![EMPTY; width as usize * height as usize],
+            fresh: false,
+        }
+    }
+
+    // Clears the buffer for a redraw, reallocating only when the size changed
+    pub fn reset(&mut self, width: u32, height: u32) {
+        if self.width != width || self.height != height {
+            *self = Self::new(width, height);
+        } else {
+            self.ids.fill(EMPTY);
+        }
+        self.fresh = true;
+    }
+
+    // The particles moved or the view changed, so the next pick must redraw first
+    pub fn invalidate(&mut self) {
+        self.fresh = false;
+    }
+
+    pub fn is_stale(&self) -> bool {
+        !self.fresh
+    }
+
+    // Coordinates are pixels from the top-left corner. Later draws cover earlier ones, as on screen.
+    pub fn draw_circle(&mut self, index: usize, x: f32, y: f32, radius: f32) {
+        let radius = radius.max(MIN_PICK_RADIUS);
+        let min_x = (x - radius).floor().max(0.0) as i64;
+        let max_x = ((x + radius).ceil() as i64).min(self.width as i64 - 1);
+        let min_y = (y - radius).floor().max(0.0) as i64;
+        let max_y = ((y + radius).ceil() as i64).min(self.height as i64 - 1);
+
+        for py in min_y..=max_y {
+            let dy = py as f32 + 0.5 - y;
+            for px in min_x..=max_x {
+                let dx = px as f32 + 0.5 - x;
+                if dx * dx + dy * dy <= radius * radius {
+                    self.ids[py as usize * self.width as usize + px as usize] = index as u32;
+                }
+            }
+        }
+    }
+
+    pub fn pick(&self, x: f32, y: f32) -> Option<usize> {
+        if x < 0.0 || y < 0.0 || x >= self.width as f32 || y >= self.height as f32 {
+            return None;
+        }
+        let id = self.ids[y as usize * self.width as usize + x as usize];
+        (id != EMPTY).then_some(id as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick_returns_topmost_particle() {
+        let mut buffer = PickingBuffer::new(100, 50);
+        buffer.draw_circle(3, 20.0, 20.0, 5.0);
+        buffer.draw_circle(7, 24.0, 20.0, 5.0);
+
+        assert_eq!(buffer.pick(16.0, 20.0), Some(3));
+        assert_eq!(buffer.pick(22.0, 20.0), Some(7));
+        assert_eq!(buffer.pick(80.0, 40.0), None);
+        assert_eq!(buffer.pick(-1.0, 20.0), None);
+
+        // Off-screen and tiny particles are clipped and padded rather than panicking or vanishing
+        buffer.draw_circle(9, 99.5, 49.5, 0.1);
+        assert_eq!(buffer.pick(99.0, 49.0), Some(9));
+        buffer.reset(100, 50);
+        assert_eq!(buffer.pick(22.0, 20.0), None);
+
+        assert!(!buffer.is_stale());
+        buffer.invalidate();
+        assert!(buffer.is_stale());
+        assert!(PickingBuffer::default().is_stale());
+    }
+}
@@ -0,0 +1,128 @@
+use bytemuck::{Pod, Zeroable};
+use serde::{Deserialize, Serialize};
+
+// Stable handle to a particle that survives other particles dying, spawning and moving around
+// in the dense array. Slots are reused once their particle dies, and the generation tells the
+// new occupant apart from the old one. Generation 0 means the particle has no id yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, Pod, Zeroable)]
+#[repr(C)]
+pub struct ParticleId {
+    pub slot: u32,
+    pub generation: u32,
+}
+
+impl ParticleId {
+    pub fn is_assigned(&self) -> bool {
+        self.generation != 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct Slot {
+    generation: u32,
+    // Where the slot's particle sits in the dense array; None while the slot is free
+    index: Option<usize>,
+}
+
+// Slot table behind ParticleIds, with a free list so dead particles' slots are handed out again
+// instead of the table growing with every spawn
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParticlePool {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+}
+
+impl ParticlePool {
+    // A fresh id for a particle stored at `index`
+    pub fn allocate(&mut self, index: usize) -> ParticleId {
+        match self.free.pop() {
+            Some(slot) => {
+                let entry = &mut self.slots[slot as usize];
+                entry.generation = entry.generation.wrapping_add(1).max(1);
+                entry.index = Some(index);
+                ParticleId { slot, generation: entry.generation }
+            },
+            None => {
+                self.slots.push(Slot { generation: 1, index: Some(index) });
+                ParticleId { slot: self.slots.len() as u32 - 1, generation: 1 }
+            },
+        }
+    }
+
+    // Frees the id's slot; stale or unknown ids are ignored
+    pub fn release(&mut self, id: ParticleId) {
+        if let Some(entry) = self.live_slot_mut(id) {
+            entry.index = None;
+            self.free.push(id.slot);
+        }
+    }
+
+    // Records that the id's particle moved to `index`
+    pub fn relocate(&mut self, id: ParticleId, index: usize) {
+        if let Some(entry) = self.live_slot_mut(id) {
+            entry.index = Some(index);
+        }
+    }
+
+    // Dense index the id was last recorded at, or None once its particle is gone
+    pub fn index_of(&self, id: ParticleId) -> Option<usize> {
+        self.slots
+            .get(id.slot as usize)
+            .filter(|entry| id.is_assigned() && entry.generation == id.generation)
+            .and_then(|entry| entry.index)
+    }
+
+    // The live id in a slot, if any
+    pub fn id_of_slot(&self, slot: u32) -> Option<ParticleId> {
+        self.slots
+            .get(slot as usize)
+            .filter(|entry| entry.index.is_some())
+            .map(|entry| ParticleId { slot, generation: entry.generation })
+    }
+
+    // Number of particles holding an id
+    pub fn live(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    // Slots ever handed out, live or free
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn live_slot_mut(&mut self, id: ParticleId) -> Option<&mut Slot> {
+        self.slots
+            .get_mut(id.slot as usize)
+            .filter(|entry| id.is_assigned() && entry.generation == id.generation && entry.index.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_released_slots_are_reused_with_a_new_generation() {
+        let mut pool = ParticlePool::default();
+        let a = pool.allocate(0);
+        let b = pool.allocate(1);
+        assert_eq!((pool.index_of(a), pool.index_of(b)), (Some(0), Some(1)));
+
+        pool.release(a);
+        pool.relocate(b, 0);
+        assert_eq!(pool.index_of(a), None);
+        assert_eq!(pool.index_of(b), Some(0));
+
+        let c = pool.allocate(1);
+        assert_eq!(c.slot, a.slot);
+        assert_ne!(c, a);
+        assert_eq!(pool.index_of(a), None);
+        assert_eq!(pool.index_of(c), Some(1));
+        assert_eq!((pool.live(), pool.capacity()), (2, 2));
+
+        // Releasing a stale id doesn't free the slot's new occupant
+        pool.release(a);
+        assert_eq!(pool.index_of(c), Some(1));
+        assert_eq!(pool.index_of(ParticleId::default()), None);
+    }
+}
@@ -1,8 +1,31 @@
 use glam::Vec2;
 use rand::Rng;
+use std::collections::HashMap;
 use crate::particle::{Particle, ParticleSystem};
-use crate::config::{SimulationConfig, Preset, ConfigManager};
-use crate::forces::{ForceType, InteractionMatrix};
+use crate::config::{SimulationConfig, Preset, ConfigManager, SpeciesRegistry};
+use crate::forces::{ForceType, InteractionMatrix, PhysicsEngine};
+use crate::brush::{BrushShape, ShapeSampler};
+use crate::orbit::{self, circular_orbit_velocity, hill_radius, min_stable_radius};
+use crate::cloth::update_cloth;
+use crate::pheromone::update_pheromones;
+use crate::reaction::update_reaction;
+
+// Used when the config has no sun/body gravity rule, e.g. for a hand-edited Gravity config
+const DEFAULT_GRAVITY_STRENGTH: f32 = 50.0;
+// Resamples allowed per body when it lands in another body's Hill sphere
+const HILL_SPACING_ATTEMPTS: usize = 8;
+// Used when the crystal presets' Lennard-Jones rule is missing
+const DEFAULT_LATTICE_SIGMA: f32 = 10.0;
+// Gap between the floor and the lowest grains of the granular preset
+const GRANULAR_DROP_HEIGHT: f32 = 60.0;
+// Lattice spacing of the agent presets, where their repulsion runs out
+const AGENT_SPACING: f32 = 12.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lattice {
+    Hexagonal,
+    Square,
+}
 
 pub struct PresetManager;
 
@@ -12,7 +35,7 @@ impl PresetManager {
         
         // Set system properties
         system.spawn_rate = config.particles.spawn_rate;
-        system.damping = config.forces.damping_coefficient;
+        system.damping = Self::damping(config);
         
         if let Some((min_bounds, max_bounds)) = Self::get_spawn_bounds(config) {
             system.set_bounds(min_bounds, max_bounds);
@@ -26,12 +49,25 @@ impl PresetManager {
             Preset::Electromagnetic => Self::create_electromagnetic_system(&mut system, config),
             Preset::Brownian => Self::create_brownian_system(&mut system, config),
             Preset::ReactionDiffusion => Self::create_reaction_diffusion_system(&mut system, config),
+            Preset::HexCrystal => Self::create_crystal_system(&mut system, config, Lattice::Hexagonal),
+            Preset::SquareCrystal => Self::create_crystal_system(&mut system, config, Lattice::Square),
+            Preset::Granular => Self::create_granular_system(&mut system, config),
+            Preset::Fireworks => Self::create_fireworks_system(&mut system, config),
+            Preset::CyclicAgents | Preset::ExcitableAgents => Self::create_agents_system(&mut system, config),
+            Preset::Physarum => Self::create_physarum_system(&mut system, config),
+            Preset::Metabolism => Self::create_metabolism_system(&mut system, config),
+            Preset::Cloth => Self::create_cloth_system(&mut system, config),
         }
 
         system
     }
 
-    fn get_spawn_bounds(config: &SimulationConfig) -> Option<(Vec2, Vec2)> {
+    // With damping off, particles keep their velocity from frame to frame
+    pub fn damping(config: &SimulationConfig) -> f32 {
+        if config.forces.enable_damping { config.forces.damping_coefficient } else { 1.0 }
+    }
+
+    pub fn get_spawn_bounds(config: &SimulationConfig) -> Option<(Vec2, Vec2)> {
         if config.forces.boundary_forces.enable_boundaries {
             Some(config.forces.boundary_forces.bounds)
         } else {
@@ -42,8 +78,32 @@ impl PresetManager {
         }
     }
 
+    pub fn sample_charge<R: Rng>(config: &SimulationConfig, species_id: u32, rng: &mut R) -> f32 {
+        let base_charge = config.species_charge(species_id);
+        base_charge * (1.0 + (rng.gen::<f32>() - 0.5) * config.particles.charge_variation)
+    }
+
+    pub fn pick_species<R: Rng>(species_weights: &HashMap<u32, f32>, rng: &mut R) -> u32 {
+        // Walked in id order, since a HashMap's order differs between instances and would make
+        // seeded runs pick different species
+        let mut weights: Vec<(u32, f32)> = species_weights.iter().map(|(&id, &weight)| (id, weight)).collect();
+        weights.sort_unstable_by_key(|&(id, _)| id);
+        let total_weight: f32 = weights.iter().map(|&(_, weight)| weight).sum();
+        let target_weight = rng.gen::<f32>() * total_weight;
+        let mut weight_sum = 0.0;
+        
+        for (id, weight) in weights {
+            weight_sum += weight;
+            if target_weight <= weight_sum {
+                return id;
+            }
+        }
+        
+        0
+    }
+
     fn create_particle_life_system(system: &mut ParticleSystem, config: &SimulationConfig) {
-        let mut rng = rand::thread_rng();
+        let mut rng = crate::rng::fork();
         let bounds = Self::get_spawn_bounds(config).unwrap_or((
             Vec2::new(-200.0, -200.0),
             Vec2::new(200.0, 200.0),
@@ -51,66 +111,58 @@ impl PresetManager {
 
         // Create particles with different species
         let species_weights = &config.particles.species_weights;
-        let total_weight: f32 = species_weights.values().sum();
+        let mut species_counts: HashMap<u32, usize> = HashMap::new();
+        let sampler = ShapeSampler::rect(bounds.0, bounds.1);
+        // Shared by every particle so a curl-noise start flows as one field
+        let field_offset = config.particles.initial_velocity.field_offset(&mut rng);
         
-        for _ in 0..config.particles.initial_particle_count {
+        for position in sampler.sample(config.particles.initial_particle_count, &mut rng) {
             // Select species based on weights
-            let mut species_id = 0;
-            let mut weight_sum = 0.0;
-            let target_weight = rng.gen::<f32>() * total_weight;
-            
-            for (&id, &weight) in species_weights {
-                weight_sum += weight;
-                if target_weight <= weight_sum {
-                    species_id = id;
-                    break;
-                }
-            }
+            let species_id = Self::pick_species(species_weights, &mut rng);
 
-            // Random position within bounds
-            let position = Vec2::new(
-                rng.gen_range(bounds.0.x..bounds.1.x),
-                rng.gen_range(bounds.0.y..bounds.1.y),
-            );
+            let velocity = config.particles.sample_velocity(position, field_offset, &mut rng);
 
-            // Random initial velocity
-            let velocity = Vec2::new(
-                rng.gen_range(config.particles.initial_velocity_range.0.x..config.particles.initial_velocity_range.1.x),
-                rng.gen_range(config.particles.initial_velocity_range.0.y..config.particles.initial_velocity_range.1.y),
-            );
+            let count = species_counts.entry(species_id).or_insert(0);
+            if !config.species.can_spawn(species_id, *count) {
+                continue;
+            }
+            *count += 1;
 
-            let color = Self::get_species_color(species_id);
+            let (base_mass, base_size) = config.species
+                .get(species_id)
+                .map(|species| (species.mass, species.size))
+                .unwrap_or((config.particles.default_mass, config.particles.default_size));
+            let color = config.species.color(species_id);
             
             let particle = Particle::new(position)
                 .with_velocity(velocity)
-                .with_mass(config.particles.default_mass * (1.0 + (rng.gen::<f32>() - 0.5) * config.particles.mass_variation))
-                .with_size(config.particles.default_size * (1.0 + (rng.gen::<f32>() - 0.5) * config.particles.size_variation))
+                .with_mass(base_mass * (1.0 + (rng.gen::<f32>() - 0.5) * config.particles.mass_variation))
+                .with_size(base_size * (1.0 + (rng.gen::<f32>() - 0.5) * config.particles.size_variation))
+                .with_charge(Self::sample_charge(config, species_id, &mut rng))
                 .with_species(species_id)
                 .with_color(color)
-                .with_lifespan(config.particles.default_lifespan);
+                .with_lifespan(config.particles.sample_lifespan(&mut rng));
 
             system.add_particle(particle);
         }
     }
 
     fn create_flocking_system(system: &mut ParticleSystem, config: &SimulationConfig) {
-        let mut rng = rand::thread_rng();
+        let mut rng = crate::rng::fork();
         let bounds = Self::get_spawn_bounds(config).unwrap_or((
             Vec2::new(-300.0, -300.0),
             Vec2::new(300.0, 300.0),
         ));
 
+        let sampler = ShapeSampler::rect(bounds.0, bounds.1);
+
         for _ in 0..config.particles.initial_particle_count {
             // Create small clusters of particles
-            let cluster_center = Vec2::new(
-                rng.gen_range(bounds.0.x..bounds.1.x),
-                rng.gen_range(bounds.0.y..bounds.1.y),
-            );
-            
-            let position = cluster_center + Vec2::new(
-                rng.gen_range(-30.0..30.0),
-                rng.gen_range(-30.0..30.0),
+            let cluster = ShapeSampler::new(
+                BrushShape::Rect { half_extents: Vec2::splat(30.0) },
+                sampler.sample_one(&mut rng),
             );
+            let position = cluster.sample_one(&mut rng);
 
             // Initial velocity pointing in a common direction with some variation
             let base_direction = Vec2::new(rng.gen::<f32>() - 0.5, rng.gen::<f32>() - 0.5).normalize_or_zero();
@@ -119,6 +171,7 @@ impl PresetManager {
             let particle = Particle::new(position)
                 .with_velocity(velocity)
                 .with_mass(1.0)
+                .with_charge(Self::sample_charge(config, 0, &mut rng))
                 .with_size(3.0)
                 .with_species(0)
                 .with_color([0.8, 0.8, 1.0, 1.0])
@@ -129,39 +182,51 @@ impl PresetManager {
     }
 
     fn create_gravity_system(system: &mut ParticleSystem, config: &SimulationConfig) {
-        let mut rng = rand::thread_rng();
+        let mut rng = crate::rng::fork();
+        let strength = Self::gravity_strength(config);
+        let central_mass = 1000.0;
         
         // Create a central massive body
-        let central_mass = Particle::new(Vec2::ZERO)
-            .with_mass(100.0)
+        let sun = Particle::new(Vec2::ZERO)
+            .with_mass(central_mass)
+            .with_charge(Self::sample_charge(config, 0, &mut rng))
             .with_size(10.0)
             .with_velocity(Vec2::ZERO)
             .with_species(0)
             .with_color([1.0, 1.0, 0.0, 1.0])
             .with_lifespan(f32::INFINITY);
         
-        system.add_particle(central_mass);
-
-        // Create orbiting bodies
-        for _ in 1..config.particles.initial_particle_count {
-            let distance = rng.gen_range(50.0..300.0);
-            let angle = rng.gen::<f32>() * 2.0 * std::f32::consts::PI;
-            
-            let position = Vec2::new(
-                distance * angle.cos(),
-                distance * angle.sin(),
-            );
+        system.add_particle(sun);
+
+        // Orbits tighter than the integrator can resolve would spiral, so the ring starts outside them
+        let inner_radius = min_stable_radius(strength, central_mass, config.physics.dt, orbit::MIN_STEPS_PER_ORBIT).max(50.0);
+        let orbits = ShapeSampler::new(BrushShape::Ring { inner_radius, outer_radius: inner_radius + 250.0 }, Vec2::ZERO);
+        let orbit_count = config.particles.initial_particle_count.saturating_sub(1);
+        let mut momentum = Vec2::ZERO;
+        for _ in 0..orbit_count {
+            let mass = rng.gen_range(0.5..3.0);
 
-            // Calculate orbital velocity (simplified)
-            let orbital_speed = (100.0 / distance).sqrt() * 20.0; // G*M/r approximation
-            let velocity = Vec2::new(-orbital_speed * angle.sin(), orbital_speed * angle.cos());
+            // Keep bodies out of each other's Hill spheres so they don't capture one another at once
+            let mut position = orbits.sample_one(&mut rng);
+            for _ in 0..HILL_SPACING_ATTEMPTS {
+                let crowded = system.particles.iter().skip(1).any(|other| {
+                    let reach = hill_radius(other.position.length(), other.mass.max(mass), central_mass);
+                    other.position.distance(position) < reach
+                });
+                if !crowded {
+                    break;
+                }
+                position = orbits.sample_one(&mut rng);
+            }
 
-            let mass = rng.gen_range(0.5..3.0);
+            let velocity = circular_orbit_velocity(strength, central_mass, position);
+            momentum += velocity * mass;
             let size = 2.0 + mass;
 
             let particle = Particle::new(position)
                 .with_velocity(velocity)
                 .with_mass(mass)
+                .with_charge(Self::sample_charge(config, 1, &mut rng))
                 .with_size(size)
                 .with_species(1)
                 .with_color([0.8, 0.6, 1.0, 1.0])
@@ -169,30 +234,45 @@ impl PresetManager {
 
             system.add_particle(particle);
         }
+
+        // The sun recoils against the orbiting bodies so the system as a whole stays put
+        if let Some(sun) = system.particles.first_mut() {
+            sun.velocity = -momentum / central_mass;
+        }
+    }
+
+    // G of the sun/body gravity rule, as set by the Gravity preset
+    fn gravity_strength(config: &SimulationConfig) -> f32 {
+        config.forces.species_interactions
+            .get(&(0, 1))
+            .into_iter()
+            .flatten()
+            .find_map(|force| match force {
+                ForceType::Gravity { strength, .. } => Some(*strength),
+                _ => None,
+            })
+            .unwrap_or(DEFAULT_GRAVITY_STRENGTH)
     }
 
     fn create_electromagnetic_system(system: &mut ParticleSystem, config: &SimulationConfig) {
-        let mut rng = rand::thread_rng();
+        let mut rng = crate::rng::fork();
         let bounds = Self::get_spawn_bounds(config).unwrap_or((
             Vec2::new(-200.0, -200.0),
             Vec2::new(200.0, 200.0),
         ));
 
-        for _ in 0..config.particles.initial_particle_count {
-            let position = Vec2::new(
-                rng.gen_range(bounds.0.x..bounds.1.x),
-                rng.gen_range(bounds.0.y..bounds.1.y),
-            );
+        let sampler = ShapeSampler::rect(bounds.0, bounds.1);
 
+        for position in sampler.sample(config.particles.initial_particle_count, &mut rng) {
             let velocity = Vec2::new(
                 rng.gen_range(-20.0..20.0),
                 rng.gen_range(-20.0..20.0),
             );
 
-            // Randomly assign positive or negative charge
-            let charge = if rng.gen::<bool>() { 1.0 } else { -1.0 };
-            let species_id = if charge > 0.0 { 0 } else { 1 };
-            let color = if charge > 0.0 { [1.0, 0.3, 0.3, 1.0] } else { [0.3, 0.3, 1.0, 1.0] };
+            // Randomly assign a species; its charge comes from the per-species mapping
+            let species_id = if rng.gen::<bool>() { 0 } else { 1 };
+            let charge = Self::sample_charge(config, species_id, &mut rng);
+            let color = config.species.color(species_id);
 
             let particle = Particle::new(position)
                 .with_velocity(velocity)
@@ -208,21 +288,19 @@ impl PresetManager {
     }
 
     fn create_brownian_system(system: &mut ParticleSystem, config: &SimulationConfig) {
-        let mut rng = rand::thread_rng();
+        let mut rng = crate::rng::fork();
         let bounds = Self::get_spawn_bounds(config).unwrap_or((
             Vec2::new(-400.0, -300.0),
             Vec2::new(400.0, 300.0),
         ));
 
-        for _ in 0..config.particles.initial_particle_count {
-            let position = Vec2::new(
-                rng.gen_range(bounds.0.x..bounds.1.x),
-                rng.gen_range(bounds.0.y..bounds.1.y),
-            );
+        let sampler = ShapeSampler::rect(bounds.0, bounds.1);
 
+        for position in sampler.sample(config.particles.initial_particle_count, &mut rng) {
             let particle = Particle::new(position)
                 .with_velocity(Vec2::ZERO)
                 .with_mass(1.0)
+                .with_charge(Self::sample_charge(config, 0, &mut rng))
                 .with_size(rng.gen_range(1.0..3.0))
                 .with_species(0)
                 .with_color([
@@ -237,34 +315,130 @@ impl PresetManager {
         }
     }
 
+    fn create_crystal_system(system: &mut ParticleSystem, config: &SimulationConfig, lattice: Lattice) {
+        // Lennard-Jones pairs rest at 2^(1/6) sigma
+        let spacing = Self::lattice_sigma(config) * 2f32.powf(1.0 / 6.0);
+        for position in lattice_positions(lattice, Vec2::ZERO, spacing, config.particles.initial_particle_count) {
+            system.add_particle(config.species.particle(0, position).with_lifespan(f32::INFINITY));
+        }
+    }
+
+    // Sigma of the atom/atom Lennard-Jones rule, as set by the crystal presets
+    fn lattice_sigma(config: &SimulationConfig) -> f32 {
+        config.forces.species_interactions
+            .get(&(0, 0))
+            .into_iter()
+            .flatten()
+            .find_map(|force| match force {
+                ForceType::LennardJones { sigma, .. } => Some(*sigma),
+                _ => None,
+            })
+            .unwrap_or(DEFAULT_LATTICE_SIGMA)
+    }
+
+    fn create_granular_system(system: &mut ParticleSystem, config: &SimulationConfig) {
+        let mut rng = crate::rng::fork();
+        let floor = system.bounds.map_or(-300.0, |(min, _)| min.y);
+        let grain = config.species.particle(0, Vec2::ZERO).with_lifespan(f32::INFINITY);
+        
+        // A loose column of grains above the middle of the floor, jittered so it doesn't fall as
+        // one rigid block
+        let spacing = grain.size * 1.5;
+        let count = config.particles.initial_particle_count;
+        let columns = (count as f32).sqrt().ceil().max(1.0) as usize;
+        let width = (columns - 1) as f32 * spacing;
+        for index in 0..count {
+            let (column, row) = (index % columns, index / columns);
+            let jitter = Vec2::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)) * grain.size * 0.2;
+            let position = Vec2::new(
+                column as f32 * spacing - width * 0.5,
+                floor + GRANULAR_DROP_HEIGHT + row as f32 * spacing,
+            ) + jitter;
+            let mut particle = grain;
+            particle.position = position;
+            system.add_particle(particle);
+        }
+    }
+
+    // Opens on a single burst; the rest come from the config's emitter as the simulation runs
+    fn create_fireworks_system(system: &mut ParticleSystem, config: &SimulationConfig) {
+        let mut rng = crate::rng::fork();
+        config.particles.bursts.burst(system, config, &mut rng);
+    }
+
+    // Agents on a hexagonal lattice, each starting in a state drawn from the species weights
+    fn create_agents_system(system: &mut ParticleSystem, config: &SimulationConfig) {
+        let mut rng = crate::rng::fork();
+        for position in lattice_positions(Lattice::Hexagonal, Vec2::ZERO, AGENT_SPACING, config.particles.initial_particle_count) {
+            let state = Self::pick_species(&config.particles.species_weights, &mut rng);
+            system.add_particle(config.species.particle(state, position).with_lifespan(f32::INFINITY));
+        }
+    }
+
+    // A disc of agents heading off in random directions at the trail-following speed
+    fn create_physarum_system(system: &mut ParticleSystem, config: &SimulationConfig) {
+        let mut rng = crate::rng::fork();
+        let sampler = ShapeSampler::new(BrushShape::Circle { radius: 150.0 }, Vec2::ZERO);
+        for _ in 0..config.particles.initial_particle_count {
+            let heading = Vec2::from_angle(rng.gen_range(0.0..std::f32::consts::TAU));
+            let agent = config.species
+                .particle(0, sampler.sample_one(&mut rng))
+                .with_velocity(heading * config.pheromones.speed)
+                .with_lifespan(f32::INFINITY);
+            system.add_particle(agent);
+        }
+    }
+
+    // Foragers and food scattered over the bounds; foragers start with a full meal's energy
+    fn create_metabolism_system(system: &mut ParticleSystem, config: &SimulationConfig) {
+        let mut rng = crate::rng::fork();
+        let (min, max) = Self::get_spawn_bounds(config).unwrap_or((Vec2::splat(-300.0), Vec2::splat(300.0)));
+        let metabolism = &config.metabolism;
+        for _ in 0..config.particles.initial_particle_count {
+            let species_id = Self::pick_species(&config.particles.species_weights, &mut rng);
+            let position = min + (max - min) * Vec2::new(rng.gen(), rng.gen());
+            let mut particle = config.species.particle(species_id, position).with_lifespan(f32::INFINITY);
+            if metabolism.metabolizes(species_id) {
+                particle.energy = metabolism.initial_energy;
+            }
+            system.add_particle(particle);
+        }
+    }
+
+    // A sheet hanging from the top of the bounds, laid out row by row for the cloth to weave
+    fn create_cloth_system(system: &mut ParticleSystem, config: &SimulationConfig) {
+        let top = Self::get_spawn_bounds(config).map_or(Vec2::new(0.0, 250.0), |(_, max)| Vec2::new(0.0, max.y - 50.0));
+        for position in config.cloth.positions(top) {
+            system.add_particle(config.species.particle(0, position).with_lifespan(f32::INFINITY));
+        }
+    }
+
     fn create_reaction_diffusion_system(system: &mut ParticleSystem, config: &SimulationConfig) {
-        let mut rng = rand::thread_rng();
+        let mut rng = crate::rng::fork();
         let bounds = Self::get_spawn_bounds(config).unwrap_or((
             Vec2::new(-200.0, -200.0),
             Vec2::new(200.0, 200.0),
         ));
 
+        let sampler = ShapeSampler::rect(bounds.0, bounds.1);
+
         // Create activator particles (species 0) in small clusters
         let num_clusters = 5;
         for _ in 0..num_clusters {
-            let cluster_center = Vec2::new(
-                rng.gen_range(bounds.0.x..bounds.1.x),
-                rng.gen_range(bounds.0.y..bounds.1.y),
+            let cluster = ShapeSampler::new(
+                BrushShape::Rect { half_extents: Vec2::splat(20.0) },
+                sampler.sample_one(&mut rng),
             );
 
             let particles_per_cluster = config.particles.initial_particle_count * 6 / (10 * num_clusters);
-            for _ in 0..particles_per_cluster {
-                let position = cluster_center + Vec2::new(
-                    rng.gen_range(-20.0..20.0),
-                    rng.gen_range(-20.0..20.0),
-                );
-
+            for position in cluster.sample(particles_per_cluster, &mut rng) {
                 let particle = Particle::new(position)
                     .with_velocity(Vec2::ZERO)
                     .with_mass(1.0)
+                    .with_charge(Self::sample_charge(config, 0, &mut rng))
                     .with_size(2.5)
                     .with_species(0) // Activator
-                    .with_color([1.0, 0.3, 0.3, 1.0]) // Red
+                    .with_color(config.species.color(0))
                     .with_lifespan(f32::INFINITY);
 
                 system.add_particle(particle);
@@ -273,18 +447,14 @@ impl PresetManager {
 
         // Fill the rest of the space with inhibitor particles (species 1)
         let remaining_particles = config.particles.initial_particle_count - system.particle_count();
-        for _ in 0..remaining_particles {
-            let position = Vec2::new(
-                rng.gen_range(bounds.0.x..bounds.1.x),
-                rng.gen_range(bounds.0.y..bounds.1.y),
-            );
-
+        for position in sampler.sample(remaining_particles, &mut rng) {
             let particle = Particle::new(position)
                 .with_velocity(Vec2::ZERO)
                 .with_mass(1.0)
+                .with_charge(Self::sample_charge(config, 1, &mut rng))
                 .with_size(2.0)
                 .with_species(1) // Inhibitor
-                .with_color([0.3, 0.3, 1.0, 1.0]) // Blue
+                .with_color(config.species.color(1))
                 .with_lifespan(f32::INFINITY);
 
             system.add_particle(particle);
@@ -324,6 +494,37 @@ impl PresetManager {
                 // Positive-Negative: attraction
                 matrix.add_interaction(0, 1, ForceType::ElectroMagnetic { strength: -1000.0, min_distance: 5.0 });
             },
+            Preset::Gravity => {
+                // Sun-Body and Body-Body: Newtonian attraction
+                matrix.add_interaction(0, 1, ForceType::Gravity { strength: DEFAULT_GRAVITY_STRENGTH, min_distance: 5.0 });
+                matrix.add_interaction(1, 1, ForceType::Gravity { strength: DEFAULT_GRAVITY_STRENGTH, min_distance: 5.0 });
+            },
+            Preset::HexCrystal | Preset::SquareCrystal => {
+                // Atom-Atom: Lennard-Jones, resting at the lattice spacing
+                matrix.add_interaction(0, 0, ForceType::LennardJones { epsilon: 0.005, sigma: DEFAULT_LATTICE_SIGMA });
+            },
+            Preset::Granular => {
+                // Grain-Grain: stiff repulsion just beyond contact
+                matrix.add_interaction(0, 0, ForceType::Repulsion { strength: 10_000.0, max_distance: 12.0 });
+            },
+            Preset::Fireworks | Preset::Physarum | Preset::Cloth => {
+                // Sparks, slime agents and cloth pass through each other; cloth is held by its links
+                matrix.default_forces.clear();
+            },
+            Preset::Metabolism => {
+                // Forager-Forager: personal space; Forager-Food: scent
+                matrix.add_interaction(0, 0, ForceType::Repulsion { strength: 60.0, max_distance: 12.0 });
+                matrix.add_interaction(0, 1, ForceType::Attraction { strength: 40.0, max_distance: 120.0 });
+                matrix.interactions.insert((1, 1), Vec::new());
+            },
+            Preset::CyclicAgents | Preset::ExcitableAgents => {
+                // Agents of every state hold each other at the lattice spacing
+                for a in 0..3 {
+                    for b in a..3 {
+                        matrix.add_interaction(a, b, ForceType::Repulsion { strength: 200.0, max_distance: AGENT_SPACING });
+                    }
+                }
+            },
             Preset::ReactionDiffusion => {
                 // Activator-Activator: self-reinforcement
                 matrix.add_interaction(0, 0, ForceType::Attraction { strength: 25.0, max_distance: 40.0 });
@@ -346,17 +547,7 @@ impl PresetManager {
     }
 
     pub fn get_species_color(species_id: u32) -> [f32; 4] {
-        match species_id {
-            0 => [1.0, 0.3, 0.3, 1.0], // Red
-            1 => [0.3, 0.3, 1.0, 1.0], // Blue
-            2 => [0.3, 1.0, 0.3, 1.0], // Green
-            3 => [1.0, 1.0, 0.3, 1.0], // Yellow
-            4 => [1.0, 0.3, 1.0, 1.0], // Magenta
-            5 => [0.3, 1.0, 1.0, 1.0], // Cyan
-            6 => [1.0, 0.6, 0.2, 1.0], // Orange
-            7 => [0.6, 0.2, 1.0, 1.0], // Purple
-            _ => [0.8, 0.8, 0.8, 1.0], // Light gray for unknown species
-        }
+        SpeciesRegistry::default_color(species_id)
     }
 
     pub fn create_test_scenario(scenario_name: &str) -> (ParticleSystem, InteractionMatrix) {
@@ -444,6 +635,91 @@ impl PresetManager {
         (system, matrix)
     }
 
+    pub fn generate_preview(preset: &Preset, steps: usize, width: usize, height: usize) -> PresetThumbnail {
+        let mut manager = ConfigManager::new();
+        manager.apply_preset(preset.clone());
+        
+        // Keep previews cheap: a capped particle count run for a handful of steps
+        let mut config = manager.config().clone();
+        config.particles.initial_particle_count = config.particles.initial_particle_count.min(150);
+        config.particles.spawn_rate = 0.0;
+        
+        let system = Self::run_headless(preset, &config, steps);
+        Self::render_thumbnail(&system.particles, system.bounds, width, height)
+    }
+
+    // Physics set up from the config alone, as used by headless runs
+    pub fn create_physics_engine(config: &SimulationConfig) -> PhysicsEngine {
+        let mut engine = PhysicsEngine::new(config.physics.clone());
+        engine.force_calculator.interaction_matrix = config.forces.interaction_matrix();
+        engine.force_calculator.force_multipliers = config.forces.force_scales.clone();
+        engine.force_calculator.steering = config.forces.steering.clone();
+        engine.force_calculator.species_drag = config.species.drag_coefficients();
+        engine.force_calculator.fast_math = config.performance.fast_math.clone();
+        engine.zones = config.forces.zones.clone();
+        engine.wells = config.forces.wells.clone();
+        engine.metabolism = config.metabolism.clone();
+        engine.force_calculator.global_forces = config.forces.environment_forces();
+        engine
+    }
+
+    // Builds the preset's initial layout and steps it without any rendering
+    pub fn run_headless(preset: &Preset, config: &SimulationConfig, steps: usize) -> ParticleSystem {
+        let mut system = Self::create_particle_system_from_preset(preset, config);
+        let mut engine = Self::create_physics_engine(config);
+        let dt = config.physics.dt;
+        let mut pheromones = None;
+        let mut reaction = None;
+        let mut cloth = None;
+        for step in 0..steps {
+            config.agents.step(&mut system.particles, &config.species, None, &mut crate::rng::fork());
+            update_cloth(&mut cloth, &config.cloth, &mut system);
+            update_pheromones(&mut pheromones, &config.pheromones, &mut system, dt);
+            update_reaction(&mut reaction, &config.reaction, &mut system, dt);
+            engine.update(&mut system);
+            system.update(dt);
+            let time = step as f32 * dt;
+            config.particles.bursts.update(&mut system, config, time, time + dt);
+            config.metabolism.feed(&mut system, &config.species, &config.forces.zones, None, (time, time + dt), &mut crate::rng::fork());
+        }
+        
+        system
+    }
+
+    pub fn render_thumbnail(particles: &[Particle], bounds: Option<(Vec2, Vec2)>, width: usize, height: usize) -> PresetThumbnail {
+        let mut pixels = vec![0u8; width * height * 4];
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&[5, 5, 13, 255]);
+        }
+        
+        let (min, max) = bounds.unwrap_or_else(|| {
+            particles.iter().fold(
+                (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+                |(min, max), p| (min.min(p.position), max.max(p.position)),
+            )
+        });
+        let extent = (max - min).max(Vec2::splat(1.0));
+        
+        for particle in particles {
+            let normalized = (particle.position - min) / extent;
+            if normalized.x < 0.0 || normalized.x > 1.0 || normalized.y < 0.0 || normalized.y > 1.0 {
+                continue;
+            }
+            
+            let x = ((normalized.x * (width - 1) as f32) as usize).min(width - 1);
+            // Image rows run top to bottom, world y runs bottom to top
+            let y = (((1.0 - normalized.y) * (height - 1) as f32) as usize).min(height - 1);
+            let index = (y * width + x) * 4;
+            
+            pixels[index] = (particle.color[0].clamp(0.0, 1.0) * 255.0) as u8;
+            pixels[index + 1] = (particle.color[1].clamp(0.0, 1.0) * 255.0) as u8;
+            pixels[index + 2] = (particle.color[2].clamp(0.0, 1.0) * 255.0) as u8;
+            pixels[index + 3] = 255;
+        }
+        
+        PresetThumbnail { width, height, pixels }
+    }
+
     pub fn save_custom_preset(name: &str, config: &SimulationConfig, system: &ParticleSystem) -> Result<(), Box<dyn std::error::Error>> {
         let filename = format!("presets/{}.json", name);
         std::fs::create_dir_all("presets")?;
@@ -487,6 +763,28 @@ impl PresetManager {
     }
 }
 
+// `count` lattice sites `spacing` apart in a roughly square patch centred on `center`. Hexagonal
+// rows are offset by half a spacing and packed sqrt(3)/2 apart.
+pub fn lattice_positions(lattice: Lattice, center: Vec2, spacing: f32, count: usize) -> Vec<Vec2> {
+    let row_spacing = match lattice {
+        Lattice::Hexagonal => spacing * 3f32.sqrt() * 0.5,
+        Lattice::Square => spacing,
+    };
+    let columns = ((count as f32 * row_spacing / spacing).sqrt().ceil() as usize).max(1);
+    let rows = count.div_ceil(columns);
+    let origin = center - Vec2::new((columns - 1) as f32 * spacing, (rows - 1) as f32 * row_spacing) * 0.5;
+    (0..count)
+        .map(|index| {
+            let (column, row) = (index % columns, index / columns);
+            let offset = match lattice {
+                Lattice::Hexagonal if row % 2 == 1 => spacing * 0.5,
+                _ => 0.0,
+            };
+            origin + Vec2::new(column as f32 * spacing + offset, row as f32 * row_spacing)
+        })
+        .collect()
+}
+
 use serde::{Serialize, Deserialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -497,10 +795,59 @@ pub struct CustomPresetData {
     pub initial_particles: Vec<Particle>,
 }
 
+// A JSON file opened or dropped by the user: a saved custom preset or a bare config
+#[derive(Debug, Clone)]
+pub enum ConfigFile {
+    Preset(CustomPresetData),
+    Config(SimulationConfig),
+}
+
+impl ConfigFile {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let value: serde_json::Value = serde_json::from_str(json).map_err(|e| format!("Not valid JSON: {}", e))?;
+        // Custom presets wrap a config together with their particles
+        if value.get("initial_particles").is_some() {
+            serde_json::from_value(value)
+                .map(ConfigFile::Preset)
+                .map_err(|e| format!("Invalid preset file: {}", e))
+        } else {
+            serde_json::from_value(value)
+                .map(ConfigFile::Config)
+                .map_err(|e| format!("Invalid config file: {}", e))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PresetThumbnail {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_config_file_detects_presets_and_configs() {
+        let config = SimulationConfig::default();
+        let system = PresetManager::create_particle_system_from_preset(&Preset::Brownian, &config);
+        let preset = CustomPresetData {
+            name: "dropped".to_string(),
+            description: String::new(),
+            config: config.clone(),
+            initial_particles: system.particles.clone(),
+        };
+
+        let parsed = ConfigFile::parse(&serde_json::to_string(&preset).unwrap()).unwrap();
+        assert!(matches!(parsed, ConfigFile::Preset(data) if data.initial_particles.len() == system.particle_count()));
+        let parsed = ConfigFile::parse(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert!(matches!(parsed, ConfigFile::Config(_)));
+        assert!(ConfigFile::parse("{\"physics\": 3}").is_err());
+        assert!(ConfigFile::parse("not json").is_err());
+    }
+
     #[test]
     fn test_particle_life_creation() {
         let config = SimulationConfig::default();
@@ -524,6 +871,161 @@ mod tests {
         assert_eq!(blue, [0.3, 0.3, 1.0, 1.0]);
     }
 
+    #[test]
+    fn test_electromagnetic_species_charges() {
+        let mut manager = ConfigManager::new();
+        manager.apply_preset(Preset::Electromagnetic);
+        let system = PresetManager::create_particle_system_from_preset(&Preset::Electromagnetic, manager.config());
+        
+        for particle in &system.particles {
+            if particle.species_id == 0 {
+                assert!(particle.charge > 0.0);
+            } else {
+                assert!(particle.charge < 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gravity_preset_two_body_orbit_is_stable() {
+        let mut manager = ConfigManager::new();
+        manager.apply_preset(Preset::Gravity);
+        manager.config_mut().particles.initial_particle_count = 2;
+        let config = manager.config();
+        let mut system = PresetManager::create_particle_system_from_preset(&Preset::Gravity, config);
+        let mut engine = PresetManager::create_physics_engine(config);
+        assert_eq!(system.particle_count(), 2);
+
+        let radius = system.particles[0].position.distance(system.particles[1].position);
+        for step in 0..10_000 {
+            engine.update(&mut system);
+            let distance = system.particles[0].position.distance(system.particles[1].position);
+            assert!((distance - radius).abs() < radius * 0.05, "radius {} drifted to {} at step {}", radius, distance, step);
+        }
+    }
+
+    #[test]
+    fn test_lattice_positions_are_evenly_spaced() {
+        for lattice in [Lattice::Hexagonal, Lattice::Square] {
+            let positions = lattice_positions(lattice, Vec2::new(50.0, 0.0), 10.0, 40);
+            assert_eq!(positions.len(), 40);
+            for (i, position) in positions.iter().enumerate() {
+                let nearest = positions.iter().enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, other)| position.distance(*other))
+                    .fold(f32::MAX, f32::min);
+                assert!((nearest - 10.0).abs() < 1e-3, "{:?} site {} is {} from its nearest neighbour", lattice, i, nearest);
+            }
+            let center = positions.iter().sum::<Vec2>() / positions.len() as f32;
+            assert!(center.distance(Vec2::new(50.0, 0.0)) < 10.0);
+        }
+    }
+
+    #[test]
+    fn test_cold_crystal_keeps_its_lattice() {
+        let mut manager = ConfigManager::new();
+        manager.apply_preset(Preset::HexCrystal);
+        let config = manager.config();
+        let start = PresetManager::create_particle_system_from_preset(&Preset::HexCrystal, config);
+        let system = PresetManager::run_headless(&Preset::HexCrystal, config, 300);
+        assert_eq!(system.particle_count(), start.particle_count());
+        
+        let spacing = 10.0 * 2f32.powf(1.0 / 6.0);
+        let drift = system.particles.iter().zip(&start.particles)
+            .map(|(p, s)| p.position.distance(s.position))
+            .fold(0.0, f32::max);
+        assert!(drift < spacing * 0.3, "an atom wandered {} from its site", drift);
+    }
+
+    #[test]
+    fn test_fireworks_keep_bursting_and_burning_out() {
+        let mut manager = ConfigManager::new();
+        manager.apply_preset(Preset::Fireworks);
+        let config = manager.config();
+        let per_burst = config.particles.bursts.particles_per_burst;
+        let start = PresetManager::create_particle_system_from_preset(&Preset::Fireworks, config);
+        assert_eq!(start.particle_count(), per_burst);
+
+        // Ten seconds: the opening burst is long gone and later ones keep the sky lit
+        let system = PresetManager::run_headless(&Preset::Fireworks, config, 600);
+        assert!(system.particle_count() > 0 && system.particle_count() < 3 * per_burst);
+        assert!(system.particles.iter().all(|spark| spark.lifespan.is_finite()));
+    }
+
+    #[test]
+    fn test_physarum_agents_lay_a_network() {
+        let mut manager = ConfigManager::new();
+        manager.apply_preset(Preset::Physarum);
+        // Fewer agents keep the pair loop quick
+        let mut config = manager.config().clone();
+        config.particles.initial_particle_count = 300;
+        let config = &config;
+        let mut system = PresetManager::create_particle_system_from_preset(&Preset::Physarum, config);
+        let mut engine = PresetManager::create_physics_engine(config);
+        let mut field = None;
+        for _ in 0..300 {
+            update_pheromones(&mut field, &config.pheromones, &mut system, config.physics.dt);
+            engine.update(&mut system);
+            system.update(config.physics.dt);
+        }
+        // Agents keep their speed, and their trails gather into strands rather than an even wash
+        let speed = config.pheromones.speed;
+        assert!(system.particles.iter().all(|agent| (agent.velocity.length() - speed).abs() < speed * 0.5));
+        let field = field.unwrap();
+        let cells = field.cells().count() as f32;
+        assert!(field.max_value() > 4.0 * field.total() / cells);
+    }
+
+    #[test]
+    fn test_metabolism_foragers_spend_energy() {
+        let mut manager = ConfigManager::new();
+        manager.apply_preset(Preset::Metabolism);
+        let mut config = manager.config().clone();
+        config.particles.initial_particle_count = 100;
+        config.forces.zones.clear();
+        let metabolism = config.metabolism.clone();
+
+        let system = PresetManager::create_particle_system_from_preset(&Preset::Metabolism, &config);
+        assert!(system.particles.iter().any(|p| metabolism.is_food(p.species_id)));
+        assert!(system.particles.iter().filter(|p| metabolism.metabolizes(p.species_id)).all(|p| p.energy == metabolism.initial_energy));
+
+        // With nothing to graze on, foragers that ate nothing are down by at least a second's upkeep
+        let system = PresetManager::run_headless(&Preset::Metabolism, &config, 60);
+        let foragers: Vec<_> = system.particles.iter().filter(|p| metabolism.metabolizes(p.species_id)).collect();
+        assert!(!foragers.is_empty());
+        assert!(foragers.iter().any(|p| p.energy <= metabolism.initial_energy - metabolism.basal_cost));
+    }
+
+    #[test]
+    fn test_cloth_hangs_from_its_pins() {
+        let mut manager = ConfigManager::new();
+        manager.apply_preset(Preset::Cloth);
+        // A smaller sheet keeps the test quick
+        let mut config = manager.config().clone();
+        config.cloth.columns = 12;
+        config.cloth.rows = 10;
+        config.particles.initial_particle_count = config.cloth.particle_count();
+
+        let start = PresetManager::create_particle_system_from_preset(&Preset::Cloth, &config);
+        let system = PresetManager::run_headless(&Preset::Cloth, &config, 240);
+        assert_eq!(system.particle_count(), start.particle_count());
+        // The top row stays put, give or take the step since it was last pinned, while the rest
+        // sags and drifts downwind
+        for column in 0..config.cloth.columns {
+            assert!(system.particles[column].position.distance(start.particles[column].position) < 0.1);
+        }
+        let corner = config.cloth.particle_count() - 1;
+        assert!(system.particles[corner].position.y < start.particles[corner].position.y);
+        assert!(system.particles[corner].position.x > start.particles[corner].position.x);
+    }
+
+    #[test]
+    fn test_preset_preview() {
+        let thumbnail = PresetManager::generate_preview(&Preset::Brownian, 2, 32, 24);
+        assert_eq!(thumbnail.pixels.len(), 32 * 24 * 4);
+        assert!(thumbnail.pixels.chunks_exact(4).any(|p| p != [5, 5, 13, 255]));
+    }
+
     #[test]
     fn test_test_scenarios() {
         let (system, _) = PresetManager::create_test_scenario("chase");
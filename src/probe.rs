@@ -0,0 +1,140 @@
+use glam::Vec2;
+use std::collections::BTreeMap;
+use crate::particle::Particle;
+use crate::spatial::SpatialPartitioning;
+
+// Densities are reported in particles per 100x100 world units, as for brushes
+const DENSITY_AREA: f32 = 100.0 * 100.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProbeShape {
+    Rect { half_extents: Vec2 },
+    Circle { radius: f32 },
+}
+
+// A region of the world whose particles are summarised live
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Probe {
+    pub center: Vec2,
+    pub shape: ProbeShape,
+}
+
+// What a mouse drag on the probe is doing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProbeDrag {
+    // Moving the probe; holds the grab point relative to the centre
+    Move(Vec2),
+    Resize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProbeStats {
+    pub count: usize,
+    pub density: f32,
+    pub mean_velocity: Vec2,
+    pub mean_speed: f32,
+    // Particle count per species ID
+    pub species: BTreeMap<u32, usize>,
+}
+
+impl Default for Probe {
+    fn default() -> Self {
+        Self {
+            center: Vec2::ZERO,
+            shape: ProbeShape::Circle { radius: 100.0 },
+        }
+    }
+}
+
+impl Probe {
+    pub fn contains(&self, position: Vec2) -> bool {
+        let offset = position - self.center;
+        match self.shape {
+            ProbeShape::Rect { half_extents } => offset.x.abs() <= half_extents.x && offset.y.abs() <= half_extents.y,
+            ProbeShape::Circle { radius } => offset.length_squared() <= radius * radius,
+        }
+    }
+
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        let half_extents = match self.shape {
+            ProbeShape::Rect { half_extents } => half_extents,
+            ProbeShape::Circle { radius } => Vec2::splat(radius),
+        };
+        (self.center - half_extents, self.center + half_extents)
+    }
+
+    pub fn area(&self) -> f32 {
+        match self.shape {
+            ProbeShape::Rect { half_extents } => half_extents.x * half_extents.y * 4.0,
+            ProbeShape::Circle { radius } => std::f32::consts::PI * radius * radius,
+        }
+    }
+
+    // Resizes so the edge passes through `point`, keeping the centre
+    pub fn resize_to(&mut self, point: Vec2) {
+        let offset = point - self.center;
+        self.shape = match self.shape {
+            ProbeShape::Rect { .. } => ProbeShape::Rect { half_extents: offset.abs() },
+            ProbeShape::Circle { .. } => ProbeShape::Circle { radius: offset.length() },
+        };
+    }
+
+    // Candidates come from the spatial index when there is one, but membership is checked against
+    // current positions since the index is only rebuilt on simulation steps
+    pub fn stats(&self, particles: &[Particle], spatial: Option<&SpatialPartitioning>) -> ProbeStats {
+        let candidates: Box<dyn Iterator<Item = &Particle>> = match spatial {
+            Some(spatial) => Box::new(spatial.query_range(self.bounds()).into_iter().filter_map(|i| particles.get(i))),
+            None => Box::new(particles.iter()),
+        };
+
+        let mut stats = ProbeStats::default();
+        let mut velocity_sum = Vec2::ZERO;
+        let mut speed_sum = 0.0;
+        for particle in candidates.filter(|p| self.contains(p.position)) {
+            stats.count += 1;
+            velocity_sum += particle.velocity;
+            speed_sum += particle.velocity.length();
+            *stats.species.entry(particle.species_id).or_insert(0) += 1;
+        }
+
+        if stats.count > 0 {
+            stats.mean_velocity = velocity_sum / stats.count as f32;
+            stats.mean_speed = speed_sum / stats.count as f32;
+        }
+        if self.area() > 0.0 {
+            stats.density = stats.count as f32 * DENSITY_AREA / self.area();
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probe_stats_match_with_and_without_index() {
+        let particles = vec![
+            Particle::new(Vec2::new(0.0, 0.0)).with_species(0).with_velocity(Vec2::new(2.0, 0.0)),
+            Particle::new(Vec2::new(30.0, 0.0)).with_species(1).with_velocity(Vec2::new(0.0, 4.0)),
+            Particle::new(Vec2::new(45.0, 45.0)).with_species(1),
+            Particle::new(Vec2::new(300.0, 0.0)).with_species(0),
+        ];
+        let mut spatial = SpatialPartitioning::new_quadtree((Vec2::splat(-500.0), Vec2::splat(500.0)), 2, 6);
+        spatial.update(&particles);
+
+        let circle = Probe { center: Vec2::ZERO, shape: ProbeShape::Circle { radius: 50.0 } };
+        let stats = circle.stats(&particles, Some(&spatial));
+        assert_eq!(stats, circle.stats(&particles, None));
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.mean_velocity, Vec2::new(1.0, 2.0));
+        assert_eq!(stats.mean_speed, 3.0);
+        assert_eq!(stats.species.get(&1), Some(&1));
+
+        // The rectangle reaches the corner the circle misses
+        let rect = Probe { center: Vec2::ZERO, shape: ProbeShape::Rect { half_extents: Vec2::splat(50.0) } };
+        let stats = rect.stats(&particles, Some(&spatial));
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.density, 3.0);
+    }
+}
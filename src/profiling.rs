@@ -0,0 +1,53 @@
+// Tracing spans around the main simulation and render phases, for profiling regressions.
+// Build with `--features profiling` to write a Chrome trace (open it in Perfetto or
+// chrome://tracing), or `--features tracy` to also stream to a running Tracy profiler.
+// Without either feature the spans compile to nothing.
+
+#[cfg(all(feature = "profiling", not(target_arch = "wasm32")))]
+use std::sync::Mutex;
+
+// Where the Chrome trace goes unless INOCHI_TRACE names another file
+pub const DEFAULT_TRACE_FILE: &str = "trace.json";
+
+#[cfg(all(feature = "profiling", not(target_arch = "wasm32")))]
+static CHROME_GUARD: Mutex<Option<tracing_chrome::FlushGuard>> = Mutex::new(None);
+
+// Opens a span named `$name` that lasts until the end of the enclosing block
+macro_rules! profile_span {
+    ($name:literal) => {
+        #[cfg(feature = "profiling")]
+        let _span = tracing::info_span!($name).entered();
+    };
+}
+pub(crate) use profile_span;
+
+// Installs the trace subscriber; does nothing without the profiling feature
+pub fn init() {
+    #[cfg(all(feature = "profiling", not(target_arch = "wasm32")))]
+    {
+        use tracing_subscriber::prelude::*;
+
+        let path = std::env::var("INOCHI_TRACE").unwrap_or_else(|_| DEFAULT_TRACE_FILE.to_string());
+        let (chrome, guard) = tracing_chrome::ChromeLayerBuilder::new().file(&path).build();
+        let registry = tracing_subscriber::registry().with(chrome);
+        #[cfg(feature = "tracy")]
+        let registry = registry.with(tracing_tracy::TracyLayer::default());
+        if let Err(e) = registry.try_init() {
+            eprintln!("Failed to start profiling: {}", e);
+            return;
+        }
+        println!("Writing trace to {}", path);
+        if let Ok(mut slot) = CHROME_GUARD.lock() {
+            *slot = Some(guard);
+        }
+    }
+}
+
+// Writes out the rest of the Chrome trace. Call before exiting, since the desktop event loop
+// never returns to main.
+pub fn finish() {
+    #[cfg(all(feature = "profiling", not(target_arch = "wasm32")))]
+    if let Some(guard) = CHROME_GUARD.lock().ok().and_then(|mut slot| slot.take()) {
+        drop(guard);
+    }
+}
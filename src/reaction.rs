@@ -0,0 +1,272 @@
+use glam::Vec2;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::particle::ParticleSystem;
+use crate::rng;
+
+// World used when the particle system has no bounds
+const DEFAULT_BOUNDS: (Vec2, Vec2) = (Vec2::new(-400.0, -300.0), Vec2::new(400.0, 300.0));
+// Spots of V dropped into a fresh grid to start the reaction, and their radius in cells
+const SEED_SPOTS: usize = 12;
+const SEED_RADIUS: usize = 3;
+
+// Gray-Scott reaction-diffusion on a grid: U is fed in everywhere, V eats U to make more of itself
+// and dies off, and both spread at different rates. Particles can feed U into the grid and be
+// pushed along the slope of V.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReactionConfig {
+    pub enabled: bool,
+    // Rate U is replenished and V removed; together they pick the pattern (spots, stripes, mazes)
+    pub feed: f32,
+    pub kill: f32,
+    pub diffusion_u: f32,
+    pub diffusion_v: f32,
+    // Width of a grid cell in world units
+    pub cell_size: f32,
+    // Reaction steps per physics step; the reaction runs on its own clock of one unit per step
+    pub iterations: usize,
+    // U each coupled particle adds to its cell per second
+    pub deposit: f32,
+    // Acceleration per unit slope of V; negative pushes particles away from V
+    pub push: f32,
+    // Species coupled to the grid; empty means every species
+    pub species: Vec<u32>,
+    // Draw the grid as a texture under the particles, shading from `colors[0]` to `colors[1]` by V
+    pub show: bool,
+    pub colors: [[f32; 4]; 2],
+}
+
+impl Default for ReactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            feed: 0.055,
+            kill: 0.062,
+            diffusion_u: 1.0,
+            diffusion_v: 0.5,
+            cell_size: 4.0,
+            iterations: 8,
+            deposit: 0.0,
+            push: 0.0,
+            species: Vec::new(),
+            show: true,
+            colors: [[0.02, 0.02, 0.08, 1.0], [0.3, 0.9, 0.8, 1.0]],
+        }
+    }
+}
+
+impl ReactionConfig {
+    pub fn couples(&self, species_id: u32) -> bool {
+        self.species.is_empty() || self.species.contains(&species_id)
+    }
+}
+
+// Concentrations of U and V on a regular grid covering the world
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReactionField {
+    origin: Vec2,
+    cell_size: f32,
+    columns: usize,
+    rows: usize,
+    u: Vec<f32>,
+    v: Vec<f32>,
+}
+
+impl ReactionField {
+    // All substrate and no catalyst: nothing happens until V is seeded
+    pub fn new(bounds: (Vec2, Vec2), cell_size: f32) -> Self {
+        let (cell_size, columns, rows) = Self::dimensions(bounds, cell_size);
+        Self {
+            origin: bounds.0,
+            cell_size,
+            columns,
+            rows,
+            u: vec![1.0; columns * rows],
+            v: vec![0.0; columns * rows],
+        }
+    }
+
+    fn dimensions(bounds: (Vec2, Vec2), cell_size: f32) -> (f32, usize, usize) {
+        let cell_size = cell_size.max(1.0);
+        let extent = (bounds.1 - bounds.0).max(Vec2::splat(cell_size));
+        (cell_size, (extent.x / cell_size).ceil() as usize, (extent.y / cell_size).ceil() as usize)
+    }
+
+    // Whether this grid was built for these bounds and cell size
+    pub fn matches(&self, bounds: (Vec2, Vec2), cell_size: f32) -> bool {
+        self.origin == bounds.0 && (self.cell_size, self.columns, self.rows) == Self::dimensions(bounds, cell_size)
+    }
+
+    fn cell(&self, position: Vec2) -> Option<usize> {
+        let local = (position - self.origin) / self.cell_size;
+        if local.x < 0.0 || local.y < 0.0 {
+            return None;
+        }
+        let (column, row) = (local.x as usize, local.y as usize);
+        (column < self.columns && row < self.rows).then_some(row * self.columns + column)
+    }
+
+    // U and V at `position`; outside the grid is untouched substrate
+    pub fn sample(&self, position: Vec2) -> (f32, f32) {
+        self.cell(position).map_or((1.0, 0.0), |index| (self.u[index], self.v[index]))
+    }
+
+    // Slope of V per world unit, by central differences across neighbouring cells
+    pub fn gradient(&self, position: Vec2) -> Vec2 {
+        let h = self.cell_size;
+        let v = |offset: Vec2| self.sample(position + offset).1;
+        Vec2::new(v(Vec2::X * h) - v(Vec2::NEG_X * h), v(Vec2::Y * h) - v(Vec2::NEG_Y * h)) / (2.0 * h)
+    }
+
+    // Sets V to one in a square of cells around `center`, taking the U it would have used up
+    pub fn seed(&mut self, center: Vec2, radius: usize) {
+        let Some(index) = self.cell(center) else {
+            return;
+        };
+        let (column, row) = (index % self.columns, index / self.columns);
+        for r in row.saturating_sub(radius)..=(row + radius).min(self.rows - 1) {
+            for c in column.saturating_sub(radius)..=(column + radius).min(self.columns - 1) {
+                self.u[r * self.columns + c] = 0.5;
+                self.v[r * self.columns + c] = 1.0;
+            }
+        }
+    }
+
+    pub fn seed_randomly<R: Rng>(&mut self, spots: usize, rng: &mut R) {
+        let extent = Vec2::new(self.columns as f32, self.rows as f32) * self.cell_size;
+        for _ in 0..spots {
+            let position = self.origin + Vec2::new(rng.gen::<f32>(), rng.gen::<f32>()) * extent;
+            self.seed(position, SEED_RADIUS);
+        }
+    }
+
+    // Adds U at `position`, never past the fully fed level of one
+    pub fn deposit(&mut self, position: Vec2, amount: f32) {
+        if let Some(index) = self.cell(position) {
+            self.u[index] = (self.u[index] + amount).min(1.0);
+        }
+    }
+
+    // One explicit Gray-Scott step with the usual 3×3 Laplacian (edges 0.2, corners 0.05) and
+    // closed borders
+    pub fn react(&mut self, config: &ReactionConfig) {
+        let (columns, rows) = (self.columns, self.rows);
+        let mut next_u = vec![0.0; self.u.len()];
+        let mut next_v = vec![0.0; self.v.len()];
+        for row in 0..rows {
+            for column in 0..columns {
+                let index = row * columns + column;
+                let (mut laplace_u, mut laplace_v) = (0.0, 0.0);
+                for dr in -1i32..=1 {
+                    for dc in -1i32..=1 {
+                        let weight = match (dr, dc) {
+                            (0, 0) => continue,
+                            (0, _) | (_, 0) => 0.2,
+                            _ => 0.05,
+                        };
+                        let r = (row as i32 + dr).clamp(0, rows as i32 - 1) as usize;
+                        let c = (column as i32 + dc).clamp(0, columns as i32 - 1) as usize;
+                        laplace_u += (self.u[r * columns + c] - self.u[index]) * weight;
+                        laplace_v += (self.v[r * columns + c] - self.v[index]) * weight;
+                    }
+                }
+                let (u, v) = (self.u[index], self.v[index]);
+                let reaction = u * v * v;
+                next_u[index] = (u + config.diffusion_u * laplace_u - reaction + config.feed * (1.0 - u)).clamp(0.0, 1.0);
+                next_v[index] = (v + config.diffusion_v * laplace_v + reaction - (config.kill + config.feed) * v).clamp(0.0, 1.0);
+            }
+        }
+        self.u = next_u;
+        self.v = next_v;
+    }
+
+    pub fn total_v(&self) -> f32 {
+        self.v.iter().sum()
+    }
+
+    // World-space rectangle the grid covers
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        (self.origin, self.origin + Vec2::new(self.columns as f32, self.rows as f32) * self.cell_size)
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (self.columns, self.rows)
+    }
+
+    // RGBA8 pixels shading each cell from `low` to `high` by its V, top row first like an image
+    pub fn pixels(&self, [low, high]: [[f32; 4]; 2]) -> Vec<u8> {
+        let max_v = self.v.iter().copied().fold(0.0, f32::max).max(1e-6);
+        let mut pixels = Vec::with_capacity(self.v.len() * 4);
+        for row in (0..self.rows).rev() {
+            for &v in &self.v[row * self.columns..(row + 1) * self.columns] {
+                let t = v / max_v;
+                pixels.extend((0..4).map(|channel| ((low[channel] + (high[channel] - low[channel]) * t).clamp(0.0, 1.0) * 255.0) as u8));
+            }
+        }
+        pixels
+    }
+}
+
+// Steps the reaction for one physics step: coupled particles feed U into their cells and are
+// pushed along the slope of V, then the grid reacts. The grid is built and seeded over the system's
+// bounds on first use, rebuilt when the cell size changes and dropped when the layer is off.
+pub fn update_reaction(field: &mut Option<ReactionField>, config: &ReactionConfig, system: &mut ParticleSystem, dt: f32) {
+    if !config.enabled {
+        *field = None;
+        return;
+    }
+    let bounds = system.bounds.unwrap_or(DEFAULT_BOUNDS);
+    if !field.as_ref().is_some_and(|field| field.matches(bounds, config.cell_size)) {
+        let mut fresh = ReactionField::new(bounds, config.cell_size);
+        fresh.seed_randomly(SEED_SPOTS, &mut rng::fork());
+        *field = Some(fresh);
+    }
+    let Some(field) = field.as_mut() else {
+        return;
+    };
+    for particle in system.particles.iter_mut().filter(|particle| config.couples(particle.species_id)) {
+        if config.push != 0.0 {
+            particle.acceleration += field.gradient(particle.position) * config.push;
+        }
+        if config.deposit > 0.0 {
+            field.deposit(particle.position, config.deposit * dt);
+        }
+    }
+    for _ in 0..config.iterations {
+        field.react(config);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_spot_grows_into_a_pattern() {
+        let config = ReactionConfig { enabled: true, ..ReactionConfig::default() };
+        let mut field = ReactionField::new((Vec2::ZERO, Vec2::splat(160.0)), 4.0);
+        field.seed(Vec2::splat(80.0), 2);
+        let seeded = field.total_v();
+        for _ in 0..2000 {
+            field.react(&config);
+        }
+        // The spot spread beyond the seed instead of dying out, and left clear substrate behind
+        assert!(field.total_v() > seeded * 2.0);
+        assert!(field.sample(Vec2::splat(2.0)).0 > 0.2);
+        assert_eq!(field.pixels(config.colors).len(), 40 * 40 * 4);
+    }
+
+    #[test]
+    fn test_particles_feed_and_follow_the_field() {
+        let mut field = ReactionField::new((Vec2::ZERO, Vec2::splat(100.0)), 4.0);
+        field.seed(Vec2::splat(50.0), 2);
+        // V falls off to the right of the spot, so its slope points back toward it
+        assert!(field.gradient(Vec2::new(62.0, 50.0)).x < 0.0);
+        assert_eq!(field.gradient(Vec2::splat(10.0)), Vec2::ZERO);
+
+        field.deposit(Vec2::splat(50.0), 0.3);
+        assert_eq!(field.sample(Vec2::splat(50.0)).0, 0.8);
+        field.deposit(Vec2::splat(50.0), 5.0);
+        assert_eq!(field.sample(Vec2::splat(50.0)).0, 1.0);
+    }
+}
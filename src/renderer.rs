@@ -1,8 +1,17 @@
 use nannou::prelude::*;
 use nannou::wgpu;
 use crate::particle::{Particle, ParticleSystem};
-use crate::config::{RenderConfig, ParticleRenderMode};
-use std::collections::VecDeque;
+use crate::config::{Background, RenderConfig, ParticleRenderMode, SpeciesRegistry};
+use crate::field::ForceField;
+use crate::picking::PickingBuffer;
+use crate::probe::{Probe, ProbeShape};
+use crate::zone::{Zone, ZoneShape};
+use crate::well::GravityWell;
+use crate::units::UnitConfig;
+use crate::steering::SteeringPath;
+use crate::pheromone::PheromoneField;
+use crate::reaction::ReactionField;
+use std::collections::{BTreeMap, VecDeque};
 
 // Conversion helpers between glam::Vec2 (0.25) and nannou::geom::Vec2 (0.17)
 fn glam_to_nannou(v: glam::Vec2) -> Vec2 {
@@ -15,14 +24,82 @@ fn nannou_to_glam(v: Vec2) -> glam::Vec2 {
 
 pub struct ParticleRenderer {
     config: RenderConfig,
-    trail_history: Vec<VecDeque<Vec2>>,
+    species: SpeciesRegistry,
+    // Recent positions per pool slot, tagged with the generation of the particle they belong to
+    trail_history: Vec<(u32, VecDeque<Vec2>)>,
+    force_field: Option<ForceField>,
+    cursor_world: Vec2,
+    steering_paths: Vec<SteeringPath>,
+    // Ruler endpoints in world space while the measure tool is in use
+    measurement: Option<(Vec2, Vec2)>,
+    // Grabbed particle and the point it is sprung to, in world space
+    grab: Option<(Vec2, Vec2)>,
+    // Rubber band being dragged, and where the selected particles are, in world space
+    selection_outline: Option<Vec<glam::Vec2>>,
+    selected: Vec<glam::Vec2>,
+    probe: Option<Probe>,
+    // World position of the particle under the cursor, with the reach of each of its pair rules
+    // and the colour of the species it applies to
+    interaction_radii: Option<(Vec2, Vec<(f32, [f32; 4])>)>,
+    zones: Vec<Zone>,
+    wells: Vec<GravityWell>,
+    // World bounds of the streaming chunks that hold particles, and whether each is active
+    chunks: Vec<((glam::Vec2, glam::Vec2), bool)>,
+    // Pheromone trail grid and the colour it is drawn in
+    pheromones: Option<(PheromoneField, [f32; 4])>,
+    // Cloth links as world-space segments, and their colour
+    cloth: Option<(Vec<(glam::Vec2, glam::Vec2)>, [f32; 4])>,
+    // Particles of the integrator comparison run, drawn as outlines over the live ones
+    ghosts: Vec<Particle>,
+    // Labels lengths in physical units when enabled
+    units: UnitConfig,
     vertex_buffer: Option<wgpu::Buffer>,
     index_buffer: Option<wgpu::Buffer>,
     uniform_buffer: Option<wgpu::Buffer>,
     render_pipeline: Option<wgpu::RenderPipeline>,
+    // Path of the loaded background image, with None if it failed to load
+    background_texture: Option<(String, Option<wgpu::Texture>)>,
+    // Gray-Scott grid uploaded as a texture, with the world rectangle it covers
+    reaction_texture: Option<(wgpu::Texture, (glam::Vec2, glam::Vec2))>,
     pub camera: Camera,
 }
 
+// Every GRID_SUBDIVISIONS-th grid line is a major line
+const GRID_SUBDIVISIONS: i64 = 5;
+// Grid lines closer together than this on screen switch to the next coarser level
+const MIN_GRID_PIXELS: f32 = 24.0;
+
+// Segments in the radial gradient's triangle fan
+const RADIAL_GRADIENT_SEGMENTS: usize = 64;
+
+// Shapes cycled through by species ID when `species_shapes` is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpeciesShape {
+    Circle,
+    Square,
+    Triangle,
+    Ring,
+}
+
+impl SpeciesShape {
+    pub fn for_species(species_id: u32) -> Self {
+        match species_id % 4 {
+            0 => SpeciesShape::Circle,
+            1 => SpeciesShape::Square,
+            2 => SpeciesShape::Triangle,
+            _ => SpeciesShape::Ring,
+        }
+    }
+}
+
+// Particles sharing one 8-bit RGBA colour, as (x, y, radius) circles in canvas coordinates
+// (origin top-left, y down), so a 2D canvas can fill the whole group with a single path
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanvasBatch {
+    pub color: [u8; 4],
+    pub circles: Vec<(f32, f32, f32)>,
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 struct ParticleVertex {
@@ -109,6 +186,42 @@ impl Camera {
         unzoomed + self.position
     }
 
+    pub fn fit_bounds(&mut self, min: Vec2, max: Vec2, screen_size: Vec2, margin: f32) {
+        let extent = (max - min).max(Vec2::splat(1.0));
+        let zoom_x = screen_size.x / extent.x;
+        let zoom_y = screen_size.y / extent.y;
+        
+        self.position = (min + max) * 0.5;
+        self.zoom = (zoom_x.min(zoom_y) * (1.0 - margin)).clamp(0.1, 10.0);
+        self.clear_target();
+    }
+
+    // Keeps a bounded world framed across a resize: if it all fit on the old screen, it is
+    // centered and scaled so it fits the new one with the same margin
+    pub fn keep_bounds_in_view(&mut self, min: Vec2, max: Vec2, old_size: Vec2, new_size: Vec2) {
+        let (visible_min, visible_max) = self.visible_bounds(old_size);
+        if visible_min.x > min.x || visible_min.y > min.y || visible_max.x < max.x || visible_max.y < max.y {
+            return;
+        }
+        let extent = (max - min).max(Vec2::splat(1.0));
+        let fit = |size: Vec2| (size.x / extent.x).min(size.y / extent.y);
+        self.zoom = (self.zoom * fit(new_size) / fit(old_size)).clamp(0.1, 10.0);
+        self.position = (min + max) * 0.5;
+        self.clear_target();
+    }
+
+    // World-space box containing the whole screen, rotation included
+    pub fn visible_bounds(&self, screen_size: Vec2) -> (Vec2, Vec2) {
+        let corners = [
+            Vec2::ZERO,
+            Vec2::new(screen_size.x, 0.0),
+            Vec2::new(0.0, screen_size.y),
+            screen_size,
+        ]
+        .map(|corner| self.screen_to_world(corner, screen_size));
+        corners.iter().fold((corners[0], corners[0]), |(min, max), &c| (min.min(c), max.max(c)))
+    }
+
     pub fn get_view_bounds(&self, screen_size: Vec2) -> (Vec2, Vec2) {
         let half_size = screen_size * 0.5 / self.zoom;
         (
@@ -122,22 +235,150 @@ impl ParticleRenderer {
     pub fn new(config: RenderConfig) -> Self {
         Self {
             config,
+            species: SpeciesRegistry::default(),
             trail_history: Vec::new(),
+            force_field: None,
+            cursor_world: Vec2::ZERO,
+            steering_paths: Vec::new(),
+            measurement: None,
+            grab: None,
+            selection_outline: None,
+            selected: Vec::new(),
+            probe: None,
+            interaction_radii: None,
+            zones: Vec::new(),
+            wells: Vec::new(),
+            chunks: Vec::new(),
+            pheromones: None,
+            cloth: None,
+            ghosts: Vec::new(),
+            units: UnitConfig::default(),
             vertex_buffer: None,
             index_buffer: None,
             uniform_buffer: None,
             render_pipeline: None,
+            background_texture: None,
+            reaction_texture: None,
             camera: Camera::new(),
         }
     }
 
     pub fn update_config(&mut self, config: RenderConfig) {
-        self.config = config;
+        // The surface size follows the window, not whichever config is being applied
+        let (window_width, window_height) = (self.config.window_width, self.config.window_height);
+        self.config = RenderConfig { window_width, window_height, ..config };
         // Update camera settings
         self.camera.zoom = self.config.camera_zoom;
         self.camera.position = glam_to_nannou(self.config.camera_position);
     }
 
+    // Logical size of the drawing surface in points; world/screen mapping, culling and grid use it.
+    // A bounded world that was fully in view stays centered and fitted to the new size.
+    pub fn resize(&mut self, width: u32, height: u32, bounds: Option<(glam::Vec2, glam::Vec2)>) {
+        let old_size = self.screen_size();
+        self.config.window_width = width.max(1);
+        self.config.window_height = height.max(1);
+        if let Some((min, max)) = bounds {
+            self.camera.keep_bounds_in_view(glam_to_nannou(min), glam_to_nannou(max), old_size, self.screen_size());
+        }
+    }
+
+    fn screen_size(&self) -> Vec2 {
+        Vec2::new(self.config.window_width as f32, self.config.window_height as f32)
+    }
+
+    // Textures need the app's GPU device, so the image is loaded here rather than in `render`.
+    // A failed path is remembered too, so its error comes back once rather than every frame.
+    pub fn load_background(&mut self, app: &nannou::App) -> Result<(), String> {
+        let Background::Image { path } = &self.config.background else {
+            return Ok(());
+        };
+        if path.is_empty() || self.background_texture.as_ref().is_some_and(|(loaded, _)| loaded == path) {
+            return Ok(());
+        }
+        let (texture, result) = match wgpu::Texture::from_path(app, path) {
+            Ok(texture) => (Some(texture), Ok(())),
+            Err(e) => (None, Err(format!("Failed to load background image {}: {}", path, e))),
+        };
+        self.background_texture = Some((path.clone(), texture));
+        result
+    }
+
+    // Re-uploads the reaction grid each frame it is shown; one texel per cell
+    pub fn load_reaction(&mut self, app: &nannou::App, field: Option<&ReactionField>, colors: [[f32; 4]; 2]) {
+        self.reaction_texture = field.and_then(|field| {
+            let (columns, rows) = field.size();
+            let image = nannou::image::RgbaImage::from_raw(columns as u32, rows as u32, field.pixels(colors))?;
+            let texture = wgpu::Texture::from_image(app, &nannou::image::DynamicImage::ImageRgba8(image));
+            Some((texture, field.bounds()))
+        });
+    }
+
+    pub fn set_trail_length(&mut self, trail_length: usize) {
+        self.config.trail_length = trail_length;
+    }
+
+    pub fn set_species_registry(&mut self, species: SpeciesRegistry) {
+        self.species = species;
+    }
+
+    pub fn set_force_field(&mut self, field: Option<ForceField>) {
+        self.force_field = field;
+    }
+
+    pub fn set_steering_paths(&mut self, paths: Vec<SteeringPath>) {
+        self.steering_paths = paths;
+    }
+
+    pub fn set_measurement(&mut self, measurement: Option<(glam::Vec2, glam::Vec2)>) {
+        self.measurement = measurement.map(|(start, end)| (glam_to_nannou(start), glam_to_nannou(end)));
+    }
+
+    pub fn set_grab(&mut self, grab: Option<(glam::Vec2, glam::Vec2)>) {
+        self.grab = grab.map(|(particle, target)| (glam_to_nannou(particle), glam_to_nannou(target)));
+    }
+
+    pub fn set_selection(&mut self, outline: Option<Vec<glam::Vec2>>, selected: Vec<glam::Vec2>) {
+        self.selection_outline = outline;
+        self.selected = selected;
+    }
+
+    pub fn set_zones(&mut self, zones: Vec<Zone>) {
+        self.zones = zones;
+    }
+
+    pub fn set_chunks(&mut self, chunks: Vec<((glam::Vec2, glam::Vec2), bool)>) {
+        self.chunks = chunks;
+    }
+
+    pub fn set_units(&mut self, units: UnitConfig) {
+        self.units = units;
+    }
+
+    pub fn set_wells(&mut self, wells: Vec<GravityWell>) {
+        self.wells = wells;
+    }
+
+    pub fn set_pheromones(&mut self, field: Option<PheromoneField>, color: [f32; 4]) {
+        self.pheromones = field.map(|field| (field, color));
+    }
+
+    pub fn set_cloth(&mut self, links: Option<Vec<(glam::Vec2, glam::Vec2)>>, color: [f32; 4]) {
+        self.cloth = links.map(|links| (links, color));
+    }
+
+    pub fn set_ghosts(&mut self, ghosts: Vec<Particle>) {
+        self.ghosts = ghosts;
+    }
+
+    pub fn set_probe(&mut self, probe: Option<Probe>) {
+        self.probe = probe;
+    }
+
+    pub fn set_interaction_radii(&mut self, radii: Option<(glam::Vec2, Vec<(f32, [f32; 4])>)>) {
+        self.interaction_radii = radii.map(|(center, radii)| (glam_to_nannou(center), radii));
+    }
+
     pub fn update(&mut self, system: &ParticleSystem, dt: f32) {
         self.camera.update(dt);
         self.update_trails(system);
@@ -149,24 +390,26 @@ impl ParticleRenderer {
             return;
         }
 
-        // Ensure trail history matches particle count
-        while self.trail_history.len() < system.particles.len() {
-            self.trail_history.push(VecDeque::new());
-        }
-        
-        while self.trail_history.len() > system.particles.len() {
-            self.trail_history.pop();
-        }
-
-        // Update trail positions
-        for (i, particle) in system.particles.iter().enumerate() {
-            if let Some(trail) = self.trail_history.get_mut(i) {
-                trail.push_front(glam_to_nannou(particle.position));
-                
-                // Limit trail length
-                while trail.len() > self.config.trail_length {
-                    trail.pop_back();
-                }
+        // Trails are kept per pool slot, so they stay with their particle when others die and
+        // the array is compacted; a slot's new occupant starts a fresh trail
+        for particle in &system.particles {
+            if !particle.id.is_assigned() {
+                continue;
+            }
+            let slot = particle.id.slot as usize;
+            if self.trail_history.len() <= slot {
+                self.trail_history.resize_with(slot + 1, Default::default);
+            }
+            let (generation, trail) = &mut self.trail_history[slot];
+            if *generation != particle.id.generation {
+                *generation = particle.id.generation;
+                trail.clear();
+            }
+            trail.push_front(glam_to_nannou(particle.position));
+            
+            // Limit trail length
+            while trail.len() > self.config.trail_length {
+                trail.pop_back();
             }
         }
     }
@@ -179,19 +422,60 @@ impl ParticleRenderer {
             self.config.background_color[2],
             self.config.background_color[3],
         ));
+        self.draw_background(draw);
+        if let Some((texture, bounds)) = &self.reaction_texture {
+            self.draw_reaction(draw, texture, *bounds);
+        }
 
         // Draw grid if enabled
         if self.config.enable_grid {
             self.draw_grid(draw);
         }
 
+        if !self.zones.is_empty() {
+            self.draw_zones(draw);
+        }
+
+        if !self.chunks.is_empty() {
+            self.draw_chunks(draw);
+        }
+
+        if let Some((field, color)) = &self.pheromones {
+            self.draw_pheromones(draw, field, *color);
+        }
+
+        if let Some((links, color)) = &self.cloth {
+            self.draw_cloth(draw, links, *color);
+        }
+
+        // Draw the force field overlay beneath everything else
+        if let Some(field) = &self.force_field {
+            self.draw_force_field(draw, field);
+        }
+
+        if !self.steering_paths.is_empty() {
+            self.draw_steering_paths(draw);
+        }
+
+        if !self.wells.is_empty() {
+            self.draw_wells(draw);
+        }
+
         // Draw trails first (so particles appear on top)
         if self.config.enable_trails {
             self.draw_trails(draw, system);
         }
 
-        // Draw particles
-        self.draw_particles(draw, system);
+        // Draw particles; additive blending makes dense regions glow instead of occluding
+        if self.config.is_additive() {
+            self.draw_particles(&draw.blend(BLEND_ADD), system);
+        } else {
+            self.draw_particles(draw, system);
+        }
+
+        if !self.ghosts.is_empty() {
+            self.draw_ghosts(draw);
+        }
 
         // Draw velocity vectors if enabled
         if self.config.show_velocity_vectors {
@@ -207,55 +491,101 @@ impl ParticleRenderer {
         if self.config.show_particle_ids {
             self.draw_particle_ids(draw, system);
         }
+
+        if let Some((start, end)) = self.measurement {
+            self.draw_measurement(draw, start, end);
+        }
+
+        self.draw_selection(draw);
+
+        if let Some((particle, target)) = self.grab {
+            self.draw_grab(draw, particle, target);
+        }
+
+        if let Some(probe) = &self.probe {
+            self.draw_probe(draw, probe);
+        }
+
+        if let Some((center, radii)) = &self.interaction_radii {
+            self.draw_interaction_radii(draw, *center, radii);
+        }
     }
 
-    fn draw_grid(&self, draw: &Draw) {
-        let bounds = self.camera.get_view_bounds(Vec2::new(
-            self.config.window_width as f32,
-            self.config.window_height as f32,
-        ));
-        
-        let grid_color = rgba(
-            self.config.grid_color[0],
-            self.config.grid_color[1],
-            self.config.grid_color[2],
-            self.config.grid_color[3],
-        );
+    fn draw_background(&self, draw: &Draw) {
+        let screen_size = self.screen_size();
+        let center = screen_size * 0.5;
+        let shift = parallax_shift(self.camera.position, self.camera.zoom, self.config.background_parallax);
+
+        match &self.config.background {
+            Background::Solid => {},
+            Background::VerticalGradient { top, bottom } => {
+                let (top, bottom) = (rgba(top[0], top[1], top[2], top[3]), rgba(bottom[0], bottom[1], bottom[2], bottom[3]));
+                let (low, high) = (Vec2::ZERO, screen_size);
+                let tris = [
+                    geom::Tri([(low, bottom), (pt2(high.x, low.y), bottom), (high, top)]),
+                    geom::Tri([(low, bottom), (high, top), (pt2(low.x, high.y), top)]),
+                ];
+                draw.mesh().tris_colored(tris);
+            },
+            Background::RadialGradient { inner, outer } => {
+                let (inner, outer) = (rgba(inner[0], inner[1], inner[2], inner[3]), rgba(outer[0], outer[1], outer[2], outer[3]));
+                // Reach the far corner even when the centre has drifted with the camera
+                let origin = center + shift;
+                let radius = center.length() + shift.length();
+                let rim = |i: usize| {
+                    let angle = i as f32 * std::f32::consts::TAU / RADIAL_GRADIENT_SEGMENTS as f32;
+                    origin + Vec2::new(angle.cos(), angle.sin()) * radius
+                };
+                let tris = (0..RADIAL_GRADIENT_SEGMENTS)
+                    .map(|i| geom::Tri([(origin, inner), (rim(i), outer), (rim(i + 1), outer)]));
+                draw.mesh().tris_colored(tris);
+            },
+            Background::Image { .. } => {
+                let Some((_, Some(texture))) = &self.background_texture else {
+                    return;
+                };
+                // The image is stretched to the screen and tiled, so four copies always cover it
+                let offset = wrap_offset(shift, screen_size);
+                for (i, j) in [(-1.0, -1.0), (0.0, -1.0), (-1.0, 0.0), (0.0, 0.0)] {
+                    let tile_center = center + offset + screen_size * Vec2::new(i, j);
+                    draw.texture(texture).xy(tile_center).wh(screen_size);
+                }
+            },
+        }
+    }
 
-        // Vertical lines
-        let start_x = (bounds.0.x / self.config.grid_spacing).floor() * self.config.grid_spacing;
-        let mut x = start_x;
-        while x <= bounds.1.x {
-            let screen_x = self.camera.world_to_screen(
-                Vec2::new(x, 0.0),
-                Vec2::new(self.config.window_width as f32, self.config.window_height as f32)
-            ).x;
-            
+    // Lines are laid out in world space and mapped through the camera, so they follow zoom and rotation
+    fn draw_grid(&self, draw: &Draw) {
+        let screen_size = self.screen_size();
+        let (min, max) = self.camera.visible_bounds(screen_size);
+        let (spacing, minor_fade) = grid_levels(self.config.grid_spacing, self.camera.zoom);
+        let [r, g, b, a] = self.config.grid_color;
+
+        let line = |start: Vec2, end: Vec2, index: i64, axis_color: Rgba| {
+            let (color, weight) = if index == 0 {
+                (axis_color, 2.0)
+            } else if index % GRID_SUBDIVISIONS == 0 {
+                (rgba(r, g, b, a), 1.0)
+            } else {
+                (rgba(r, g, b, a * minor_fade), 1.0)
+            };
             draw.line()
-                .start(pt2(screen_x, 0.0))
-                .end(pt2(screen_x, self.config.window_height as f32))
-                .color(grid_color)
-                .stroke_weight(1.0);
-            
-            x += self.config.grid_spacing;
+                .start(self.camera.world_to_screen(start, screen_size))
+                .end(self.camera.world_to_screen(end, screen_size))
+                .color(color)
+                .stroke_weight(weight);
+        };
+
+        // Vertical lines; x = 0 is the y axis
+        for index in (min.x / spacing).floor() as i64..=(max.x / spacing).ceil() as i64 {
+            let x = index as f32 * spacing;
+            line(Vec2::new(x, min.y), Vec2::new(x, max.y), index, rgba(0.3, 0.8, 0.3, 0.8));
         }
 
-        // Horizontal lines
-        let start_y = (bounds.0.y / self.config.grid_spacing).floor() * self.config.grid_spacing;
-        let mut y = start_y;
-        while y <= bounds.1.y {
-            let screen_y = self.camera.world_to_screen(
-                Vec2::new(0.0, y),
-                Vec2::new(self.config.window_width as f32, self.config.window_height as f32)
-            ).y;
-            
-            draw.line()
-                .start(pt2(0.0, screen_y))
-                .end(pt2(self.config.window_width as f32, screen_y))
-                .color(grid_color)
-                .stroke_weight(1.0);
-            
-            y += self.config.grid_spacing;
+        // Horizontal lines; y = 0 is the x axis
+        for index in (min.y / spacing).floor() as i64..=(max.y / spacing).ceil() as i64 {
+            let y = index as f32 * spacing;
+            line(Vec2::new(min.x, y), Vec2::new(max.x, y), index, rgba(0.8, 0.3, 0.3, 0.8));
         }
     }
 
@@ -275,9 +605,16 @@ impl ParticleRenderer {
             }
 
             let color = self.get_particle_color(particle, system);
-            let size = particle.size * self.camera.zoom * self.config.point_size;
+            let size = self.particle_size(particle) * self.camera.zoom * self.config.point_size;
 
             match self.config.particle_render_mode {
+                ParticleRenderMode::Points | ParticleRenderMode::Circles if self.config.species_shapes => {
+                    let radius = match self.config.particle_render_mode {
+                        ParticleRenderMode::Points => size * 0.5,
+                        _ => size,
+                    };
+                    draw_species_shape(draw, SpeciesShape::for_species(particle.species_id), screen_pos, radius, color);
+                },
                 ParticleRenderMode::Points => {
                     draw.ellipse()
                         .x_y(screen_pos.x, screen_pos.y)
@@ -319,6 +656,16 @@ impl ParticleRenderer {
                         .color(color)
                         .stroke_weight(self.config.line_width);
                 },
+                ParticleRenderMode::Stretched => {
+                    let speed = particle.velocity.length();
+                    let elongation = stretch_elongation(speed, self.config.stretch_factor, self.config.max_elongation);
+                    let angle = particle.velocity.y.atan2(particle.velocity.x);
+                    draw.ellipse()
+                        .x_y(screen_pos.x, screen_pos.y)
+                        .w_h(size * elongation, size)
+                        .rotate(angle)
+                        .color(color);
+                },
                 ParticleRenderMode::Trails => {
                     // This mode is handled by draw_trails
                     draw.ellipse()
@@ -336,8 +683,12 @@ impl ParticleRenderer {
             self.config.window_height as f32,
         );
 
-        for (i, particle) in system.particles.iter().enumerate() {
-            if let Some(trail) = self.trail_history.get(i) {
+        for particle in &system.particles {
+            let trail = self.trail_history
+                .get(particle.id.slot as usize)
+                .filter(|(generation, _)| *generation == particle.id.generation)
+                .map(|(_, trail)| trail);
+            if let Some(trail) = trail {
                 if trail.len() < 2 {
                     continue;
                 }
@@ -435,11 +786,362 @@ impl ParticleRenderer {
         }
     }
 
+    fn draw_steering_paths(&self, draw: &Draw) {
+        let screen_size = Vec2::new(
+            self.config.window_width as f32,
+            self.config.window_height as f32,
+        );
+
+        for path in &self.steering_paths {
+            let mut points: Vec<Point2> = path
+                .points
+                .iter()
+                .map(|p| self.camera.world_to_screen(glam_to_nannou(*p), screen_size))
+                .collect();
+            if path.closed && points.len() > 2 {
+                points.push(points[0]);
+            }
+
+            for point in &points {
+                draw.ellipse().xy(*point).radius(2.5).color(rgba(1.0, 0.8, 0.3, 0.8));
+            }
+            if points.len() > 1 {
+                draw.polyline()
+                    .weight(1.5)
+                    .points(points)
+                    .color(rgba(1.0, 0.8, 0.3, 0.5));
+            }
+        }
+    }
+
+    fn draw_force_field(&self, draw: &Draw, field: &ForceField) {
+        let screen_size = Vec2::new(
+            self.config.window_width as f32,
+            self.config.window_height as f32,
+        );
+        let field_config = &self.config.force_field;
+        let max_magnitude = field.max_magnitude();
+        if max_magnitude <= 0.0 {
+            return;
+        }
+
+        if field_config.show_streamlines {
+            for (position, _) in field.probes() {
+                let points: Vec<Point2> = field
+                    .streamline(position, field_config.streamline_steps)
+                    .into_iter()
+                    .map(|p| self.camera.world_to_screen(glam_to_nannou(p), screen_size))
+                    .collect();
+                if points.len() > 1 {
+                    draw.polyline()
+                        .weight(1.0)
+                        .points(points)
+                        .color(rgba(0.4, 0.8, 1.0, 0.25));
+                }
+            }
+        }
+
+        for (position, force) in field.probes() {
+            let strength = force.length() / max_magnitude;
+            if strength < 0.01 {
+                continue;
+            }
+
+            // Arrow length is relative to the strongest probe so weak regions stay readable
+            let start = self.camera.world_to_screen(glam_to_nannou(position), screen_size);
+            let direction = glam_to_nannou(force.normalize());
+            let end = start + direction * field_config.arrow_scale * strength.sqrt();
+            let color = rgba(0.3 + 0.7 * strength, 0.8 - 0.5 * strength, 1.0 - strength, 0.8);
+
+            draw.arrow()
+                .start(start)
+                .end(end)
+                .weight(1.0)
+                .head_length(4.0)
+                .head_width(2.5)
+                .color(color);
+        }
+    }
+
+    // The grid texture stretched over the world rectangle it simulates
+    fn draw_reaction(&self, draw: &Draw, texture: &wgpu::Texture, (min, max): (glam::Vec2, glam::Vec2)) {
+        let center = self.camera.world_to_screen(glam_to_nannou((min + max) * 0.5), self.screen_size());
+        let size = glam_to_nannou(max - min) * self.camera.zoom;
+        draw.texture(texture)
+            .xy(center)
+            .wh(size)
+            .rotate(self.camera.rotation);
+    }
+
+    // One square per visible cell, more opaque where the trail is stronger
+    fn draw_pheromones(&self, draw: &Draw, field: &PheromoneField, color: [f32; 4]) {
+        let max_value = field.max_value();
+        if max_value <= 0.0 {
+            return;
+        }
+        let screen_size = self.screen_size();
+        let (min, max) = self.view_bounds();
+        let half_cell = field.cell_size() * 0.5;
+        let size = field.cell_size() * self.camera.zoom;
+        for (center, value) in field.cells() {
+            let strength = value / max_value;
+            if strength < 0.02 || center.x + half_cell < min.x || center.x - half_cell > max.x || center.y + half_cell < min.y || center.y - half_cell > max.y {
+                continue;
+            }
+            draw.rect()
+                .xy(self.camera.world_to_screen(glam_to_nannou(center), screen_size))
+                .w_h(size, size)
+                .color(rgba(color[0], color[1], color[2], color[3] * strength));
+        }
+    }
+
+    // A thin line per cloth link
+    fn draw_cloth(&self, draw: &Draw, links: &[(glam::Vec2, glam::Vec2)], color: [f32; 4]) {
+        let screen_size = self.screen_size();
+        let color = rgba(color[0], color[1], color[2], color[3]);
+        for &(start, end) in links {
+            draw.line()
+                .start(self.camera.world_to_screen(glam_to_nannou(start), screen_size))
+                .end(self.camera.world_to_screen(glam_to_nannou(end), screen_size))
+                .color(color)
+                .stroke_weight(1.0);
+        }
+    }
+
+    // Translucent fills tinted by effect; disabled zones only keep a faint outline
+    fn draw_zones(&self, draw: &Draw) {
+        let screen_size = self.screen_size();
+        for zone in &self.zones {
+            let [r, g, b] = zone.effect.color();
+            let fill = rgba(r, g, b, if zone.enabled { 0.12 } else { 0.0 });
+            let stroke = rgba(r, g, b, if zone.enabled { 0.5 } else { 0.2 });
+            match zone.shape {
+                ZoneShape::Circle { center, radius } => {
+                    draw.ellipse()
+                        .xy(self.camera.world_to_screen(glam_to_nannou(center), screen_size))
+                        .radius(radius * self.camera.zoom)
+                        .color(fill)
+                        .stroke(stroke)
+                        .stroke_weight(1.0);
+                },
+                ZoneShape::Rect { min, max } => {
+                    let corners = [Vec2::new(min.x, min.y), Vec2::new(max.x, min.y), Vec2::new(max.x, max.y), Vec2::new(min.x, max.y)]
+                        .map(|corner| self.camera.world_to_screen(corner, screen_size));
+                    draw.polygon()
+                        .color(fill)
+                        .stroke(stroke)
+                        .stroke_weight(1.0)
+                        .points(corners);
+                },
+            }
+        }
+    }
+
+    // Outlines of the occupied streaming chunks: green while active, grey while dormant
+    fn draw_chunks(&self, draw: &Draw) {
+        let screen_size = self.screen_size();
+        for &((min, max), active) in &self.chunks {
+            let color = if active { rgba(0.4, 0.9, 0.5, 0.35) } else { rgba(0.6, 0.6, 0.6, 0.2) };
+            let corners = [min, glam::Vec2::new(max.x, min.y), max, glam::Vec2::new(min.x, max.y), min]
+                .map(|corner| self.camera.world_to_screen(glam_to_nannou(corner), screen_size));
+            draw.polyline().weight(1.0).points(corners).color(color);
+        }
+    }
+
+    // Concentric rings around a cross; attractors are orange, repellers blue, disabled wells faded
+    fn draw_wells(&self, draw: &Draw) {
+        let screen_size = self.screen_size();
+        for well in &self.wells {
+            let center = self.camera.world_to_screen(glam_to_nannou(well.position), screen_size);
+            let alpha = if well.enabled { 0.9 } else { 0.3 };
+            let color = if well.strength >= 0.0 { rgba(1.0, 0.6, 0.2, alpha) } else { rgba(0.3, 0.6, 1.0, alpha) };
+            let radius = well.marker_radius() * self.camera.zoom;
+            for (scale, weight) in [(1.0, 1.5), (2.0, 1.0), (3.0, 0.5)] {
+                draw.ellipse()
+                    .xy(center)
+                    .radius(radius * scale)
+                    .no_fill()
+                    .stroke(color)
+                    .stroke_weight(weight);
+            }
+            let arm = radius * 0.5;
+            draw.line().start(center - Vec2::new(arm, 0.0)).end(center + Vec2::new(arm, 0.0)).color(color).weight(1.5);
+            draw.line().start(center - Vec2::new(0.0, arm)).end(center + Vec2::new(0.0, arm)).color(color).weight(1.5);
+        }
+    }
+
+    fn draw_ghosts(&self, draw: &Draw) {
+        let screen_size = self.screen_size();
+        let color = rgba(1.0, 0.3, 0.9, 0.8);
+        for particle in &self.ghosts {
+            let screen_pos = self.camera.world_to_screen(glam_to_nannou(particle.position), screen_size);
+            if screen_pos.x < -50.0 || screen_pos.x > screen_size.x + 50.0 ||
+               screen_pos.y < -50.0 || screen_pos.y > screen_size.y + 50.0 {
+                continue;
+            }
+            let radius = particle.size * self.camera.zoom * self.config.point_size * 0.5;
+            draw.ellipse()
+                .xy(screen_pos)
+                .radius(radius.max(2.0))
+                .no_fill()
+                .stroke(color)
+                .stroke_weight(1.0);
+        }
+    }
+
+    fn draw_probe(&self, draw: &Draw, probe: &Probe) {
+        let screen_size = self.screen_size();
+        let center = glam_to_nannou(probe.center);
+        let color = rgba(0.4, 0.8, 1.0, 0.8);
+        match probe.shape {
+            ProbeShape::Circle { radius } => {
+                draw.ellipse()
+                    .xy(self.camera.world_to_screen(center, screen_size))
+                    .radius(radius * self.camera.zoom)
+                    .no_fill()
+                    .stroke(color)
+                    .stroke_weight(1.5);
+            },
+            ProbeShape::Rect { half_extents } => {
+                // Corners go through the camera one by one so the box turns with a rotated view
+                let half_extents = glam_to_nannou(half_extents);
+                let corners = [Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0), Vec2::new(-1.0, 1.0), Vec2::new(-1.0, -1.0)]
+                    .map(|corner| self.camera.world_to_screen(center + corner * half_extents, screen_size));
+                draw.polyline().weight(1.5).points(corners).color(color);
+            },
+        }
+    }
+
+    fn draw_measurement(&self, draw: &Draw, start: Vec2, end: Vec2) {
+        let screen_size = self.screen_size();
+        let (a, b) = (
+            self.camera.world_to_screen(start, screen_size),
+            self.camera.world_to_screen(end, screen_size),
+        );
+        let color = rgba(1.0, 1.0, 1.0, 0.9);
+        draw.line().start(a).end(b).color(color).stroke_weight(1.5);
+
+        // Ticks across each end, and the distance beside the middle
+        let normal = (b - a).perp().normalize_or_zero();
+        for point in [a, b] {
+            draw.line().start(point - normal * 6.0).end(point + normal * 6.0).color(color).stroke_weight(1.5);
+        }
+        draw.text(&self.units.format_length(start.distance(end)))
+            .xy((a + b) * 0.5 + normal * 14.0)
+            .color(color)
+            .font_size(14);
+    }
+
+    // A ring around each selected particle, and the rubber band while it is dragged
+    fn draw_selection(&self, draw: &Draw) {
+        let screen_size = self.screen_size();
+        let color = rgba(0.4, 0.8, 1.0, 0.9);
+        for &position in &self.selected {
+            let screen = self.camera.world_to_screen(glam_to_nannou(position), screen_size);
+            draw.ellipse().xy(screen).radius(5.0).no_fill().stroke(color).stroke_weight(1.0);
+        }
+        if let Some(outline) = self.selection_outline.as_ref().filter(|outline| outline.len() > 1) {
+            let points = outline.iter()
+                .chain(outline.first())
+                .map(|&point| self.camera.world_to_screen(glam_to_nannou(point), screen_size));
+            draw.polyline().weight(1.0).points(points).color(color);
+        }
+    }
+
+    // The grab spring as a line from the particle to a ring at the cursor
+    fn draw_grab(&self, draw: &Draw, particle: Vec2, target: Vec2) {
+        let screen_size = self.screen_size();
+        let (a, b) = (
+            self.camera.world_to_screen(particle, screen_size),
+            self.camera.world_to_screen(target, screen_size),
+        );
+        let color = rgba(1.0, 0.85, 0.3, 0.9);
+        draw.line().start(a).end(b).color(color).stroke_weight(1.5);
+        draw.ellipse().xy(b).radius(4.0).no_fill().stroke(color).stroke_weight(1.5);
+    }
+
+    fn draw_interaction_radii(&self, draw: &Draw, center: Vec2, radii: &[(f32, [f32; 4])]) {
+        let screen_size = self.screen_size();
+        let screen_center = self.camera.world_to_screen(center, screen_size);
+        for &(radius, [r, g, b, _]) in radii {
+            let color = rgba(r, g, b, 0.8);
+            draw.ellipse()
+                .xy(screen_center)
+                .radius(radius * self.camera.zoom)
+                .no_fill()
+                .stroke(color)
+                .stroke_weight(1.0);
+            draw.text(&self.units.format_length(radius))
+                .xy(screen_center + Vec2::new(0.0, radius * self.camera.zoom + 8.0))
+                .color(color)
+                .font_size(11);
+        }
+    }
+
     fn draw_particle_ids(&self, _draw: &Draw, _system: &ParticleSystem) {
         // Text rendering would require additional setup with nannou_egui or similar
         // For now, this is a placeholder
     }
 
+    // Where a particle is drawn as (x, y, radius) in canvas coordinates, or None when off screen
+    fn canvas_circle(&self, particle: &Particle, screen_size: Vec2) -> Option<(f32, f32, f32)> {
+        let screen_pos = self.camera.world_to_screen(glam_to_nannou(particle.position), screen_size);
+        let radius = self.particle_size(particle) * self.camera.zoom * self.config.point_size * 0.5;
+        if screen_pos.x < -radius || screen_pos.x > screen_size.x + radius ||
+           screen_pos.y < -radius || screen_pos.y > screen_size.y + radius {
+            return None;
+        }
+        Some((screen_pos.x, screen_size.y - screen_pos.y, radius))
+    }
+
+    fn screen_size(&self) -> Vec2 {
+        Vec2::new(
+            self.config.window_width as f32,
+            self.config.window_height as f32,
+        )
+    }
+
+    // On-screen particles grouped by colour for the Canvas2D fallback
+    pub fn canvas_batches(&self, system: &ParticleSystem) -> Vec<CanvasBatch> {
+        let screen_size = self.screen_size();
+        let mut batches: BTreeMap<[u8; 4], Vec<(f32, f32, f32)>> = BTreeMap::new();
+
+        for particle in &system.particles {
+            let Some(circle) = self.canvas_circle(particle, screen_size) else {
+                continue;
+            };
+            let color = self.get_particle_color(particle, system);
+            let key = [color.red, color.green, color.blue, color.alpha].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+            batches.entry(key).or_default().push(circle);
+        }
+
+        batches
+            .into_iter()
+            .map(|(color, circles)| CanvasBatch { color, circles })
+            .collect()
+    }
+
+    // The grab spring's ends in CSS pixels for the Canvas2D fallback, particle first
+    pub fn canvas_grab(&self) -> Option<((f32, f32), (f32, f32))> {
+        let screen_size = self.screen_size();
+        let to_canvas = |world: Vec2| {
+            let screen = self.camera.world_to_screen(world, screen_size);
+            (screen.x, screen_size.y - screen.y)
+        };
+        self.grab.map(|(particle, target)| (to_canvas(particle), to_canvas(target)))
+    }
+
+    // Redraws particle indices into `picking` at the current view, in draw order
+    pub fn draw_picking(&self, system: &ParticleSystem, picking: &mut PickingBuffer) {
+        let screen_size = self.screen_size();
+        picking.reset(self.config.window_width, self.config.window_height);
+        for (index, particle) in system.particles.iter().enumerate() {
+            if let Some((x, y, radius)) = self.canvas_circle(particle, screen_size) {
+                picking.draw_circle(index, x, y, radius);
+            }
+        }
+    }
+
     fn get_particle_color(&self, particle: &Particle, system: &ParticleSystem) -> Rgba {
         let mut color = rgba(
             particle.color[0],
@@ -470,22 +1172,29 @@ impl ParticleRenderer {
             }
         }
 
-        // Apply species-specific coloring
-        match particle.species_id {
-            0 => rgba(1.0, 0.3, 0.3, color.alpha), // Red
-            1 => rgba(0.3, 0.3, 1.0, color.alpha), // Blue
-            2 => rgba(0.3, 1.0, 0.3, color.alpha), // Green
-            3 => rgba(1.0, 1.0, 0.3, color.alpha), // Yellow
-            4 => rgba(1.0, 0.3, 1.0, color.alpha), // Magenta
-            5 => rgba(0.3, 1.0, 1.0, color.alpha), // Cyan
-            _ => color, // Use original color
-        }
+        // Apply species-specific coloring from the registry
+        let color = match self.species.get(particle.species_id) {
+            Some(species) => [species.color[0], species.color[1], species.color[2], color.alpha],
+            None => [color.red, color.green, color.blue, color.alpha], // Use original color
+        };
+        let [r, g, b, a] = self.config.age_ramp.color(color, particle.life_ratio());
+        rgba(r, g, b, a)
+    }
+
+    // Particle size in world units after the age ramp
+    fn particle_size(&self, particle: &Particle) -> f32 {
+        particle.size * self.config.age_ramp.size_scale(particle.life_ratio())
     }
 
+    // `mouse_pos` is in window points from the top-left corner, y pointing down
     pub fn handle_mouse_input(&mut self, mouse_pos: Vec2, screen_size: Vec2) {
-        let _world_pos = self.camera.screen_to_world(mouse_pos, screen_size);
-        // Could be used for interaction, for now just update camera target
-        // self.camera.set_target(world_pos);
+        let flipped = Vec2::new(mouse_pos.x, screen_size.y - mouse_pos.y);
+        self.cursor_world = self.camera.screen_to_world(flipped, screen_size);
+    }
+
+    // World position under the cursor as of the last mouse move
+    pub fn cursor_world(&self) -> glam::Vec2 {
+        nannou_to_glam(self.cursor_world)
     }
 
     pub fn handle_zoom(&mut self, zoom_delta: f32) {
@@ -504,6 +1213,38 @@ impl ParticleRenderer {
         self.camera.clear_target();
     }
 
+    pub fn fit_to_particles(&mut self, system: &ParticleSystem) {
+        if system.particles.is_empty() {
+            return;
+        }
+        
+        let (min, max) = system.particles.iter().fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), p| {
+                let pos = glam_to_nannou(p.position);
+                (min.min(pos), max.max(pos))
+            },
+        );
+        
+        let screen_size = Vec2::new(
+            self.config.window_width as f32,
+            self.config.window_height as f32,
+        );
+        self.camera.fit_bounds(min, max, screen_size, 0.1);
+    }
+
+    pub fn camera_state(&self) -> (glam::Vec2, f32) {
+        (nannou_to_glam(self.camera.position), self.camera.zoom)
+    }
+
+    pub fn view_bounds(&self) -> (glam::Vec2, glam::Vec2) {
+        let (min, max) = self.camera.get_view_bounds(Vec2::new(
+            self.config.window_width as f32,
+            self.config.window_height as f32,
+        ));
+        (nannou_to_glam(min), nannou_to_glam(max))
+    }
+
     pub fn focus_on_particles(&mut self, system: &ParticleSystem) {
         if !system.particles.is_empty() {
             let center = system.center_of_mass();
@@ -534,10 +1275,147 @@ impl PostProcessor {
     }
 }
 
+// World spacing of minor grid lines at `zoom` - `base` scaled by a power of GRID_SUBDIVISIONS so lines
+// stay at least MIN_GRID_PIXELS apart - and the opacity of those minor lines, fading in as they spread out
+pub fn grid_levels(base: f32, zoom: f32) -> (f32, f32) {
+    let base = base.max(f32::EPSILON);
+    let zoom = zoom.max(f32::EPSILON);
+    let level = ((MIN_GRID_PIXELS / (base * zoom)).ln() / (GRID_SUBDIVISIONS as f32).ln()).ceil();
+    let spacing = base * (GRID_SUBDIVISIONS as f32).powf(level);
+    let fade = (spacing * zoom - MIN_GRID_PIXELS) / (MIN_GRID_PIXELS * (GRID_SUBDIVISIONS - 1) as f32);
+    (spacing, fade.clamp(0.0, 1.0))
+}
+
+// Screen-space scroll of the backdrop for a camera at `position`; the world scrolls the other way
+pub fn parallax_shift(position: Vec2, zoom: f32, parallax: f32) -> Vec2 {
+    -position * zoom * parallax
+}
+
+// Folds an offset into [0, tile) on each axis, for seamlessly repeating tiles
+pub fn wrap_offset(offset: Vec2, tile: Vec2) -> Vec2 {
+    Vec2::new(offset.x.rem_euclid(tile.x.max(1.0)), offset.y.rem_euclid(tile.y.max(1.0)))
+}
+
+// Length / width of a stretched particle moving at `speed`
+pub fn stretch_elongation(speed: f32, stretch_factor: f32, max_elongation: f32) -> f32 {
+    (1.0 + speed * stretch_factor).clamp(1.0, max_elongation.max(1.0))
+}
+
+fn draw_species_shape(draw: &Draw, shape: SpeciesShape, center: Vec2, radius: f32, color: Rgba) {
+    match shape {
+        SpeciesShape::Circle => {
+            draw.ellipse().xy(center).radius(radius).color(color);
+        },
+        SpeciesShape::Square => {
+            draw.rect().xy(center).w_h(radius * 1.8, radius * 1.8).color(color);
+        },
+        SpeciesShape::Triangle => {
+            let points = (0..3).map(|i| {
+                let angle = std::f32::consts::FRAC_PI_2 + i as f32 * std::f32::consts::TAU / 3.0;
+                center + Vec2::new(angle.cos(), angle.sin()) * radius * 1.2
+            });
+            draw.polygon().color(color).points(points);
+        },
+        SpeciesShape::Ring => {
+            draw.ellipse()
+                .xy(center)
+                .radius(radius)
+                .no_fill()
+                .stroke(color)
+                .stroke_weight((radius * 0.4).max(1.0));
+        },
+    }
+}
+
+// Pixel size of a canvas backing store shown at `width` x `height` CSS pixels,
+// so high-DPI screens get one canvas pixel per device pixel
+pub fn backing_size(width: f32, height: f32, pixel_ratio: f32) -> (u32, u32) {
+    let ratio = if pixel_ratio.is_finite() && pixel_ratio > 0.0 { pixel_ratio } else { 1.0 };
+    (
+        (width * ratio).round().max(1.0) as u32,
+        (height * ratio).round().max(1.0) as u32,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_canvas_batches_group_by_color() {
+        let mut config = RenderConfig::default();
+        config.window_width = 800;
+        config.window_height = 600;
+        config.point_size = 2.0;
+        let renderer = ParticleRenderer::new(config);
+
+        let mut system = ParticleSystem::new(10);
+        system.add_particle(Particle::new(glam::Vec2::new(0.0, 100.0)).with_color([1.0, 0.0, 0.0, 1.0]));
+        system.add_particle(Particle::new(glam::Vec2::new(10.0, 0.0)).with_color([1.0, 0.0, 0.0, 1.0]));
+        system.add_particle(Particle::new(glam::Vec2::new(0.0, 0.0)).with_color([0.0, 0.0, 1.0, 1.0]));
+        // Off screen
+        system.add_particle(Particle::new(glam::Vec2::new(5000.0, 0.0)).with_color([0.0, 1.0, 0.0, 1.0]));
+
+        let batches = renderer.canvas_batches(&system);
+        assert_eq!(batches.len(), 2);
+        let red = batches.iter().find(|batch| batch.color == [255, 0, 0, 255]).unwrap();
+        assert_eq!(red.circles.len(), 2);
+        // Canvas y points down, so a particle above the origin lands above the centre
+        assert_eq!((red.circles[0].0, red.circles[0].1), (400.0, 200.0));
+    }
+
+    #[test]
+    fn test_stretch_elongation_is_capped() {
+        assert_eq!(stretch_elongation(0.0, 0.02, 4.0), 1.0);
+        assert_eq!(stretch_elongation(50.0, 0.02, 4.0), 2.0);
+        assert_eq!(stretch_elongation(1000.0, 0.02, 4.0), 4.0);
+        assert_eq!(stretch_elongation(50.0, 0.02, 0.5), 1.0);
+    }
+
+    #[test]
+    fn test_species_shapes_cycle() {
+        let shapes: Vec<SpeciesShape> = (0..5).map(SpeciesShape::for_species).collect();
+        assert_eq!(shapes[..4], [SpeciesShape::Circle, SpeciesShape::Square, SpeciesShape::Triangle, SpeciesShape::Ring]);
+        assert_eq!(shapes[4], SpeciesShape::Circle);
+    }
+
+    #[test]
+    fn test_grid_levels_adapt_to_zoom() {
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-3;
+        assert!(close(grid_levels(50.0, 1.0).0, 50.0));
+        // Zoomed out, lines merge into a coarser level; zoomed in, a sub-grid appears
+        assert!(close(grid_levels(50.0, 0.1).0, 250.0));
+        assert!(close(grid_levels(50.0, 10.0).0, 10.0));
+        assert!(grid_levels(50.0, 0.1).1 < grid_levels(50.0, 0.3).1);
+    }
+
+    #[test]
+    fn test_visible_bounds_include_rotation() {
+        let mut camera = Camera::new();
+        let screen_size = Vec2::new(200.0, 100.0);
+        assert_eq!(camera.visible_bounds(screen_size), camera.get_view_bounds(screen_size));
+
+        camera.rotation = std::f32::consts::FRAC_PI_2;
+        let (min, max) = camera.visible_bounds(screen_size);
+        assert!((max.x - min.x - 100.0).abs() < 1e-3);
+        assert!((max.y - min.y - 200.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_background_parallax_wraps() {
+        let shift = parallax_shift(Vec2::new(100.0, -50.0), 2.0, 0.5);
+        assert_eq!(shift, Vec2::new(-100.0, 50.0));
+        assert_eq!(wrap_offset(shift, Vec2::new(800.0, 600.0)), Vec2::new(700.0, 50.0));
+        assert_eq!(parallax_shift(Vec2::new(100.0, -50.0), 2.0, 0.0), Vec2::ZERO);
+    }
+
+    #[test]
+    fn test_backing_size_honors_pixel_ratio() {
+        assert_eq!(backing_size(800.0, 600.0, 2.0), (1600, 1200));
+        assert_eq!(backing_size(333.3, 100.0, 1.5), (500, 150));
+        assert_eq!(backing_size(0.0, 10.0, f32::NAN), (1, 10));
+    }
+
     #[test]
     fn test_camera_world_to_screen() {
         let camera = Camera::new();
@@ -558,6 +1436,33 @@ mod tests {
         assert_eq!(world_pos, Vec2::new(10.0, 20.0));
     }
 
+    #[test]
+    fn test_camera_fit_bounds() {
+        let mut camera = Camera::new();
+        camera.fit_bounds(Vec2::new(-100.0, -50.0), Vec2::new(300.0, 150.0), Vec2::new(800.0, 600.0), 0.0);
+        
+        assert_eq!(camera.position, Vec2::new(100.0, 50.0));
+        assert_eq!(camera.zoom, 2.0);
+    }
+
+    #[test]
+    fn test_camera_keeps_bounds_in_view_on_resize() {
+        let (min, max) = (Vec2::new(-400.0, -300.0), Vec2::new(400.0, 300.0));
+        let mut camera = Camera::new();
+        camera.position = Vec2::new(5.0, 0.0);
+        camera.zoom = 0.9;
+        camera.keep_bounds_in_view(min, max, Vec2::new(1200.0, 800.0), Vec2::new(600.0, 800.0));
+        assert_eq!(camera.position, Vec2::ZERO);
+        assert!((camera.zoom - 0.9 * 0.75 / (4.0 / 3.0)).abs() < 1e-5);
+        let (visible_min, visible_max) = camera.visible_bounds(Vec2::new(600.0, 800.0));
+        assert!(visible_min.x <= min.x && visible_max.x >= max.x);
+        
+        // Zoomed in past the bounds, the view is left alone
+        camera.zoom = 4.0;
+        camera.keep_bounds_in_view(min, max, Vec2::new(600.0, 800.0), Vec2::new(1200.0, 800.0));
+        assert_eq!(camera.zoom, 4.0);
+    }
+
     #[test]
     fn test_camera_zoom() {
         let mut camera = Camera::new();
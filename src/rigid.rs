@@ -0,0 +1,158 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use crate::particle::{Particle, ParticleSystem};
+use crate::pool::ParticleId;
+
+// A set of particles locked together as one rigid body. Each step the members move under their
+// own forces like any other particle, then are snapped back into shape about their shared centre
+// of mass. The snap keeps the members' total momentum and angular momentum, which amounts to
+// moving the group as a single body under the summed force and torque, so free particles push,
+// pull and spin it. Members are tracked by id, like cloth links.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RigidGroup {
+    members: Vec<ParticleId>,
+    // Where each member sits relative to the centre of mass, in the body's own frame
+    offsets: Vec<Vec2>,
+    // Sum of mass × squared offset, the group's resistance to spinning
+    inertia: f32,
+}
+
+impl RigidGroup {
+    // Locks the given particles into the shape they are in now
+    pub fn new(members: Vec<ParticleId>, system: &ParticleSystem) -> Self {
+        let (members, indices): (Vec<ParticleId>, Vec<usize>) = members.into_iter().filter_map(|id| Some((id, system.index_of(id)?))).unzip();
+        let particles = &system.particles;
+        let center = center_of_mass(&indices, particles);
+        let offsets: Vec<Vec2> = indices.iter().map(|&index| particles[index].position - center).collect();
+        let inertia = indices.iter().zip(&offsets).map(|(&index, offset)| weight(&particles[index]) * offset.length_squared()).sum();
+        Self { members, offsets, inertia }
+    }
+
+    pub fn members(&self) -> &[ParticleId] {
+        &self.members
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    // Pulls the members back into shape after a physics step of `dt`. Returns false once a
+    // member is gone, after which the group should be dropped.
+    pub fn apply(&self, system: &mut ParticleSystem, dt: f32) -> bool {
+        let Some(indices) = self.members.iter().map(|&id| system.index_of(id)).collect::<Option<Vec<usize>>>() else {
+            return false;
+        };
+        if indices.is_empty() {
+            return false;
+        }
+        let particles = &mut system.particles;
+        let mass: f32 = indices.iter().map(|&index| weight(&particles[index])).sum();
+        let center = center_of_mass(&indices, particles);
+        let velocity = indices.iter().map(|&index| particles[index].velocity * weight(&particles[index])).sum::<Vec2>() / mass;
+
+        // Best-fit rotation of the body frame onto where the members drifted to
+        let (mut cross, mut dot, mut momentum) = (0.0, 0.0, 0.0);
+        for (&index, offset) in indices.iter().zip(&self.offsets) {
+            let particle = &particles[index];
+            let relative = particle.position - center;
+            cross += weight(particle) * offset.perp_dot(relative);
+            dot += weight(particle) * offset.dot(relative);
+            momentum += weight(particle) * relative.perp_dot(particle.velocity - velocity);
+        }
+        let rotation = Vec2::from_angle(cross.atan2(dot));
+        let spin = if self.inertia > 0.0 { momentum / self.inertia } else { 0.0 };
+
+        for (&index, offset) in indices.iter().zip(&self.offsets) {
+            let arm = rotation.rotate(*offset);
+            let particle = &mut particles[index];
+            particle.position = center + arm;
+            particle.velocity = velocity + arm.perp() * spin;
+            // Verlet and leapfrog read velocity from the last displacement
+            particle.previous_position = particle.position - particle.velocity * dt;
+        }
+        true
+    }
+}
+
+// Holds every group in shape for one step, after the particles have moved. Groups whose members
+// are gone are dropped.
+pub fn update_rigid_groups(groups: &mut Vec<RigidGroup>, system: &mut ParticleSystem, dt: f32) {
+    groups.retain(|group| group.apply(system, dt));
+}
+
+// Massless particles still count a little, so a group of them keeps its shape
+fn weight(particle: &Particle) -> f32 {
+    particle.mass.max(f32::EPSILON)
+}
+
+fn center_of_mass(indices: &[usize], particles: &[Particle]) -> Vec2 {
+    let mass: f32 = indices.iter().map(|&index| weight(&particles[index])).sum();
+    if mass <= 0.0 {
+        return Vec2::ZERO;
+    }
+    indices.iter().map(|&index| particles[index].position * weight(&particles[index])).sum::<Vec2>() / mass
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> ParticleSystem {
+        let mut system = ParticleSystem::new(10);
+        for (x, y) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+            system.add_particle(Particle::new(Vec2::new(x, y) * 10.0));
+        }
+        system
+    }
+
+    fn ids(system: &ParticleSystem) -> Vec<ParticleId> {
+        system.iter().map(|(id, _)| id).collect()
+    }
+
+    #[test]
+    fn test_off_centre_push_moves_and_spins_the_body() {
+        let mut system = square();
+        let group = RigidGroup::new(ids(&system), &system);
+        let dt = 1.0 / 60.0;
+
+        // A sideways kick to one corner, as its own integration step would leave it
+        system.particles[2].velocity = Vec2::new(40.0, 0.0);
+        for particle in &mut system.particles {
+            particle.position += particle.velocity * dt;
+        }
+        assert!(group.apply(&mut system, dt));
+
+        // The body keeps its shape and the kick's momentum, and turns about its centre
+        let particles = &system.particles;
+        let distance = |a: usize, b: usize| particles[a].position.distance(particles[b].position);
+        assert!((distance(0, 2) - 20.0 * 2f32.sqrt()).abs() < 1e-3);
+        assert!((distance(0, 1) - 20.0).abs() < 1e-3);
+        let momentum: Vec2 = particles.iter().map(|particle| particle.velocity * particle.mass).sum();
+        assert!((momentum - Vec2::new(40.0, 0.0)).length() < 1e-3);
+        // A push along +x on the top-right corner turns the body clockwise
+        assert!(particles[2].velocity.x > particles[0].velocity.x);
+        assert!(particles[2].velocity.y < 0.0 && particles[0].velocity.y > 0.0);
+    }
+
+    #[test]
+    fn test_group_survives_other_deaths_and_drops_with_its_own() {
+        let mut system = square();
+        let members = ids(&system)[1..].to_vec();
+        let mut groups = vec![RigidGroup::new(members.clone(), &system)];
+
+        // The free particle dying swaps a member into its slot; the group keeps its shape
+        let free = system.particles[0].id;
+        system.remove_particle(free);
+        update_rigid_groups(&mut groups, &mut system, 1.0 / 60.0);
+        assert_eq!(groups.len(), 1);
+        assert!(system.get(members[2]).unwrap().position.distance(Vec2::new(-10.0, 10.0)) < 1e-3);
+
+        system.remove_particle(members[0]);
+        update_rigid_groups(&mut groups, &mut system, 1.0 / 60.0);
+        assert!(groups.is_empty());
+    }
+}
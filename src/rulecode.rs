@@ -0,0 +1,136 @@
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::config::{ConfigManager, SimulationConfig};
+use crate::forces::ForceType;
+
+// Marks a rule code and its format version, so older codes can still be told apart later
+const PREFIX: &str = "inochi1:";
+// Particle Life needs at least one species; more than this is not a rule set anyone can read
+const MAX_SPECIES: usize = 64;
+// Largest decoded payload accepted, so a pasted code can't ask for a huge allocation
+const MAX_PAYLOAD: u64 = 64 * 1024;
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+// A Particle Life rule set small enough to paste into a post: the pair rules and each species'
+// colour, packed with varint bincode and written in URL-safe base64 behind `PREFIX`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleCode {
+    pub colors: Vec<[u8; 3]>,
+    // Sorted by pair so the same rules always give the same code
+    pub interactions: Vec<((u32, u32), Vec<ForceType>)>,
+}
+
+impl RuleCode {
+    pub fn from_config(config: &SimulationConfig) -> Self {
+        let colors = config.species.species
+            .iter()
+            .map(|species| std::array::from_fn(|channel| (species.color[channel].clamp(0.0, 1.0) * 255.0).round() as u8))
+            .collect();
+        let mut interactions: Vec<_> = config.forces.species_interactions
+            .iter()
+            .map(|(&pair, forces)| (pair, forces.clone()))
+            .collect();
+        interactions.sort_by_key(|&(pair, _)| pair);
+        Self { colors, interactions }
+    }
+
+    pub fn species_count(&self) -> usize {
+        self.colors.len()
+    }
+
+    pub fn encode(&self) -> String {
+        let bytes = options().serialize(self).unwrap_or_default();
+        format!("{}{}", PREFIX, encode_base64(&bytes))
+    }
+
+    // Accepts a code with surrounding whitespace, as pasted from a post
+    pub fn decode(code: &str) -> Result<Self, String> {
+        let payload = code.trim().strip_prefix(PREFIX).ok_or("Not a rule code")?;
+        let bytes = decode_base64(payload).ok_or("Rule code is damaged")?;
+        let rules: RuleCode = options().deserialize(&bytes).map_err(|_| "Rule code is damaged".to_string())?;
+        let species_count = rules.species_count();
+        if !(1..=MAX_SPECIES).contains(&species_count) {
+            return Err(format!("Rule code has {} species", species_count));
+        }
+        if let Some(&((a, b), _)) = rules.interactions.iter().find(|((a, b), _)| *a as usize >= species_count || *b as usize >= species_count) {
+            return Err(format!("Rule code refers to species {} but only has {}", a.max(b), species_count));
+        }
+        Ok(rules)
+    }
+
+    // Switches to Particle Life with these rules and colours
+    pub fn apply(&self, manager: &mut ConfigManager) {
+        let interactions: HashMap<(u32, u32), Vec<ForceType>> = self.interactions.iter().cloned().collect();
+        manager.apply_particle_life_rules(self.species_count(), interactions);
+        for (species, color) in manager.config_mut().species.species.iter_mut().zip(&self.colors) {
+            species.color = [color[0] as f32 / 255.0, color[1] as f32 / 255.0, color[2] as f32 / 255.0, 1.0];
+        }
+    }
+}
+
+fn options() -> impl Options {
+    bincode::DefaultOptions::new().with_limit(MAX_PAYLOAD)
+}
+
+// Base64 without padding, using '-' and '_' so codes survive URLs and chat apps
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| group | ((byte as u32) << (16 - 8 * i)));
+        for i in 0..=chunk.len() {
+            encoded.push(ALPHABET[((group >> (18 - 6 * i)) & 0x3f) as usize] as char);
+        }
+    }
+    encoded
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let (mut group, mut bits) = (0u32, 0);
+    for character in text.trim_end_matches('=').bytes() {
+        let value = ALPHABET.iter().position(|&symbol| symbol == character)? as u32;
+        group = (group << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((group >> bits) as u8);
+            group &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Preset;
+
+    #[test]
+    fn test_base64_round_trip() {
+        for length in 0..8 {
+            let bytes: Vec<u8> = (0..length).map(|i| (i * 97 + 200) as u8).collect();
+            assert_eq!(decode_base64(&encode_base64(&bytes)), Some(bytes));
+        }
+        assert_eq!(encode_base64(b"Man"), "TWFu");
+        assert_eq!(decode_base64("TWE"), Some(b"Ma".to_vec()));
+        assert_eq!(decode_base64("TW+u"), None);
+    }
+
+    #[test]
+    fn test_rule_code_round_trip() {
+        let mut manager = ConfigManager::new();
+        manager.randomize_particle_life(5);
+        let code = RuleCode::from_config(manager.config()).encode();
+        assert!(code.starts_with(PREFIX));
+
+        let mut other = ConfigManager::new();
+        other.apply_preset(Preset::Gravity);
+        RuleCode::decode(&format!("  {}\n", code)).unwrap().apply(&mut other);
+        assert_eq!(RuleCode::from_config(other.config()).encode(), code);
+        assert_eq!(other.config().species.len(), 5);
+
+        assert!(RuleCode::decode("hello").is_err());
+        assert!(RuleCode::decode(&format!("{}AAAA", PREFIX)).is_err());
+    }
+}
@@ -0,0 +1,239 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use crate::brush::BrushShape;
+use crate::config::SpawnArea;
+use crate::control::ControlTarget;
+use crate::presets::CustomPresetData;
+
+pub const SCENE_EXTENSION: &str = "inochi";
+pub const SCENES_DIR: &str = "scenes";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CameraState {
+    pub position: Vec2,
+    pub zoom: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SceneAction {
+    Set { target: ControlTarget, value: f32 },
+    Pause(bool),
+    // Shown to the viewer as a notification
+    Note(String),
+    // Spawns particles of one species in a brush shape; density is per 100x100 world units
+    Stamp { shape: BrushShape, center: Vec2, density: f32, species_id: u32 },
+    // Spawns `count` default particles at once, anywhere in the area
+    Burst { count: usize, area: SpawnArea },
+}
+
+// An action fired once simulation time passes `time` seconds after the scene is loaded
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SceneEvent {
+    pub time: f32,
+    pub action: SceneAction,
+}
+
+// A custom preset plus everything needed to reproduce a moment: camera, scripted events and notes.
+// Plain preset files load as scenes with no camera, events or notes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scene {
+    // Nested rather than flattened: flattening buffers the input, and the preset's integer-keyed
+    // maps can't be read back from JSON's string keys that way
+    pub preset: CustomPresetData,
+    #[serde(default)]
+    pub camera: Option<CameraState>,
+    #[serde(default)]
+    pub events: Vec<SceneEvent>,
+    #[serde(default)]
+    pub notes: String,
+}
+
+impl Scene {
+    pub fn name(&self) -> &str {
+        &self.preset.name
+    }
+
+    // The name is made safe to use as a file name first, so it can't escape the scenes directory
+    pub fn path_for(name: &str) -> PathBuf {
+        PathBuf::from(SCENES_DIR).join(format!("{}.{}", file_stem_for(name), SCENE_EXTENSION))
+    }
+
+    // A scene, or a plain preset file loaded as one
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json).or_else(|scene_error| {
+            serde_json::from_str::<CustomPresetData>(json).map(Self::from).map_err(|_| scene_error)
+        })
+    }
+
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(Self::from_json(&content)?)
+    }
+
+    pub fn save_to_file(&self, path: impl AsRef<std::path::Path>) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn load(name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::from_file(Self::path_for(name))
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::create_dir_all(SCENES_DIR)?;
+        self.save_to_file(Self::path_for(self.name()))
+    }
+
+    pub fn list() -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(SCENES_DIR)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                if path.extension()?.to_str()? != SCENE_EXTENSION {
+                    return None;
+                }
+                path.file_stem()?.to_str().map(str::to_string)
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    // Copies the scene under the first free "<name> copy", "<name> copy 2", ... name
+    pub fn duplicate(name: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let mut scene = Self::load(name)?;
+        let existing = Self::list();
+        let copy_name = std::iter::once(format!("{} copy", name))
+            .chain((2..).map(|n| format!("{} copy {}", name, n)))
+            .find(|candidate| !existing.contains(candidate))
+            .unwrap_or_default();
+
+        scene.preset.name = copy_name.clone();
+        scene.save()?;
+        Ok(copy_name)
+    }
+
+    pub fn delete(name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::remove_file(Self::path_for(name))?;
+        Ok(())
+    }
+}
+
+impl From<CustomPresetData> for Scene {
+    fn from(preset: CustomPresetData) -> Self {
+        Self {
+            preset,
+            camera: None,
+            events: Vec::new(),
+            notes: String::new(),
+        }
+    }
+}
+
+// Path separators, characters Windows rejects and control characters become '_', and leading or
+// trailing dots and spaces are dropped
+fn file_stem_for(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| if c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '_' } else { c })
+        .collect();
+    let stem = stem.trim_matches(|c| c == '.' || c == ' ');
+    if stem.is_empty() {
+        "untitled".to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
+// Plays back a scene's events against simulation time
+#[derive(Debug, Clone, Default)]
+pub struct SceneScript {
+    events: Vec<SceneEvent>,
+    next: usize,
+    elapsed: f32,
+}
+
+impl SceneScript {
+    pub fn new(mut events: Vec<SceneEvent>) -> Self {
+        events.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self {
+            events,
+            next: 0,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.events.len()
+    }
+
+    pub fn advance(&mut self, dt: f32) -> Vec<SceneAction> {
+        self.elapsed += dt;
+
+        let mut due = Vec::new();
+        while let Some(event) = self.events.get(self.next) {
+            if event.time > self.elapsed {
+                break;
+            }
+            due.push(event.action.clone());
+            self.next += 1;
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SimulationConfig;
+
+    #[test]
+    fn test_preset_data_loads_as_scene() {
+        let mut config = SimulationConfig::default();
+        config.particles.species_weights.insert(1, 0.25);
+        let preset = CustomPresetData {
+            name: "swarm".to_string(),
+            description: String::new(),
+            config,
+            initial_particles: Vec::new(),
+        };
+        let json = serde_json::to_string(&preset).unwrap();
+
+        let scene = Scene::from_json(&json).unwrap();
+        assert_eq!(scene.name(), "swarm");
+        assert!(scene.camera.is_none());
+        assert!(scene.events.is_empty());
+
+        let mut saved = scene.clone();
+        saved.camera = Some(CameraState { position: Vec2::new(1.0, 2.0), zoom: 3.0 });
+        let reloaded = Scene::from_json(&serde_json::to_string(&saved).unwrap()).unwrap();
+        assert_eq!(reloaded.camera, saved.camera);
+        assert_eq!(reloaded.preset.config.particles.species_weights.get(&1), Some(&0.25));
+        assert!(Scene::from_json("{}").is_err());
+    }
+
+    #[test]
+    fn test_scene_names_stay_inside_the_scenes_directory() {
+        assert_eq!(Scene::path_for("swarm"), PathBuf::from("scenes/swarm.inochi"));
+        assert_eq!(Scene::path_for("../../etc/passwd"), PathBuf::from("scenes/_.._etc_passwd.inochi"));
+        assert_eq!(Scene::path_for("a:b?"), PathBuf::from("scenes/a_b_.inochi"));
+        assert_eq!(Scene::path_for(" .. "), PathBuf::from("scenes/untitled.inochi"));
+    }
+
+    #[test]
+    fn test_script_fires_events_in_order() {
+        let mut script = SceneScript::new(vec![
+            SceneEvent { time: 2.0, action: SceneAction::Pause(true) },
+            SceneEvent { time: 0.5, action: SceneAction::Note("hello".to_string()) },
+        ]);
+
+        assert!(script.advance(0.25).is_empty());
+        assert_eq!(script.advance(0.5), vec![SceneAction::Note("hello".to_string())]);
+        assert!(!script.is_finished());
+        assert_eq!(script.advance(5.0), vec![SceneAction::Pause(true)]);
+        assert!(script.is_finished());
+    }
+}
@@ -0,0 +1,254 @@
+use glam::Vec2;
+use crate::config::SpeciesRegistry;
+use crate::particle::{Particle, ParticleSystem};
+use crate::pool::ParticleId;
+use crate::spatial::SpatialPartitioning;
+
+// Lasso points closer together than this, in world units, are dropped while dragging
+const LASSO_SPACING: f32 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SelectionMode {
+    #[default]
+    Rect,
+    Lasso,
+}
+
+impl SelectionMode {
+    pub fn all() -> Vec<SelectionMode> {
+        vec![SelectionMode::Rect, SelectionMode::Lasso]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            SelectionMode::Rect => "Rectangle",
+            SelectionMode::Lasso => "Lasso",
+        }
+    }
+}
+
+// The region a rubber-band drag has covered so far, in world space
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelectionShape {
+    // Opposite corners, in whichever order they were dragged
+    Rect { start: Vec2, end: Vec2 },
+    // Closed polygon through the dragged points
+    Lasso(Vec<Vec2>),
+}
+
+impl SelectionShape {
+    pub fn new(mode: SelectionMode, point: Vec2) -> Self {
+        match mode {
+            SelectionMode::Rect => SelectionShape::Rect { start: point, end: point },
+            SelectionMode::Lasso => SelectionShape::Lasso(vec![point]),
+        }
+    }
+
+    // Follows the cursor during the drag
+    pub fn extend(&mut self, point: Vec2) {
+        match self {
+            SelectionShape::Rect { end, .. } => *end = point,
+            SelectionShape::Lasso(points) => {
+                if !points.last().is_some_and(|last| last.distance(point) < LASSO_SPACING) {
+                    points.push(point);
+                }
+            },
+        }
+    }
+
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        match self {
+            SelectionShape::Rect { start, end } => (start.min(*end), start.max(*end)),
+            SelectionShape::Lasso(points) => points.iter().fold(
+                (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+                |(min, max), point| (min.min(*point), max.max(*point)),
+            ),
+        }
+    }
+
+    pub fn contains(&self, position: Vec2) -> bool {
+        match self {
+            SelectionShape::Rect { .. } => {
+                let (min, max) = self.bounds();
+                position.cmpge(min).all() && position.cmple(max).all()
+            },
+            // Even-odd rule: a point is inside when a ray from it crosses the outline an odd
+            // number of times
+            SelectionShape::Lasso(points) => {
+                let mut inside = false;
+                for (index, a) in points.iter().enumerate() {
+                    let b = points[(index + 1) % points.len()];
+                    if (a.y > position.y) != (b.y > position.y) {
+                        let crossing = a.x + (position.y - a.y) / (b.y - a.y) * (b.x - a.x);
+                        if position.x < crossing {
+                            inside = !inside;
+                        }
+                    }
+                }
+                inside
+            },
+        }
+    }
+
+    // Outline to draw, as a closed loop
+    pub fn outline(&self) -> Vec<Vec2> {
+        match self {
+            SelectionShape::Rect { .. } => {
+                let (min, max) = self.bounds();
+                vec![min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)]
+            },
+            SelectionShape::Lasso(points) => points.clone(),
+        }
+    }
+}
+
+// Ids of the selected particles, in storage order when selected. Ids stay with their particles,
+// so other particles dying or spawning don't change what is selected; selected particles that
+// die simply drop out.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Selection {
+    pub ids: Vec<ParticleId>,
+}
+
+impl Selection {
+    // Particles inside the shape. Candidates come from the spatial index's range query when there
+    // is one, but membership is checked against current positions, as for the region probe.
+    pub fn from_shape(shape: &SelectionShape, system: &ParticleSystem, spatial: Option<&SpatialPartitioning>) -> Self {
+        let candidates = match spatial {
+            Some(spatial) => spatial.query_range(shape.bounds()),
+            None => (0..system.particles.len()).collect(),
+        };
+        let mut indices: Vec<usize> = candidates
+            .into_iter()
+            .filter(|&index| system.particles.get(index).is_some_and(|particle| shape.contains(particle.position)))
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        Self { ids: indices.into_iter().map(|index| system.particles[index].id).collect() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.ids.clear();
+    }
+
+    pub fn positions(&self, system: &ParticleSystem) -> Vec<Vec2> {
+        self.ids.iter().filter_map(|&id| system.get(id)).map(|particle| particle.position).collect()
+    }
+
+    pub fn centroid(&self, system: &ParticleSystem) -> Option<Vec2> {
+        let positions = self.positions(system);
+        (!positions.is_empty()).then(|| positions.iter().sum::<Vec2>() / positions.len() as f32)
+    }
+
+    // Removes the selected particles and empties the selection; returns how many went
+    pub fn delete(&mut self, system: &mut ParticleSystem) -> usize {
+        let removed = self.ids.iter().filter(|&&id| system.remove_particle(id).is_some()).count();
+        self.clear();
+        removed
+    }
+
+    // Gives the selected particles the look and mass of another species, keeping their motion
+    pub fn set_species(&self, system: &mut ParticleSystem, species: &SpeciesRegistry, species_id: u32) {
+        self.for_each(system, |particle| {
+            let template = species.particle(species_id, particle.position);
+            particle.species_id = species_id;
+            particle.color = template.color;
+            particle.mass = template.mass;
+            particle.size = template.size;
+            particle.charge = template.charge;
+        });
+    }
+
+    pub fn stop(&self, system: &mut ParticleSystem) {
+        self.for_each(system, |particle| particle.velocity = Vec2::ZERO);
+    }
+
+    pub fn apply_impulse(&self, system: &mut ParticleSystem, impulse: Vec2) {
+        self.for_each(system, |particle| particle.apply_impulse(impulse));
+    }
+
+    // Edits also reset the integrator history, so Verlet and leapfrog pick up a changed velocity
+    // instead of carrying on with the last step's displacement
+    fn for_each(&self, system: &mut ParticleSystem, mut edit: impl FnMut(&mut Particle)) {
+        for &id in &self.ids {
+            if let Some(particle) = system.get_mut(id) {
+                edit(particle);
+                particle.previous_position = Vec2::NAN;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row() -> ParticleSystem {
+        let mut system = ParticleSystem::new(100);
+        for i in 0..10 {
+            system.add_particle(Particle::new(Vec2::new(i as f32 * 10.0, 0.0)).with_velocity(Vec2::new(1.0, 0.0)));
+        }
+        system
+    }
+
+    fn ids(system: &ParticleSystem, indices: &[usize]) -> Vec<ParticleId> {
+        indices.iter().map(|&index| system.particles[index].id).collect()
+    }
+
+    #[test]
+    fn test_rect_and_lasso_select_with_spatial_index() {
+        let system = row();
+        let mut spatial = SpatialPartitioning::new_grid(25.0, (Vec2::splat(-100.0), Vec2::splat(200.0)));
+        spatial.update(&system.particles);
+
+        let mut rect = SelectionShape::new(SelectionMode::Rect, Vec2::new(35.0, 5.0));
+        rect.extend(Vec2::new(5.0, -5.0));
+        let selection = Selection::from_shape(&rect, &system, Some(&spatial));
+        assert_eq!(selection.ids, ids(&system, &[1, 2, 3]));
+        assert_eq!(selection, Selection::from_shape(&rect, &system, None));
+
+        // A triangle whose tip only reaches past the first few particles
+        let mut lasso = SelectionShape::new(SelectionMode::Lasso, Vec2::new(-5.0, -20.0));
+        lasso.extend(Vec2::new(-5.0, 20.0));
+        lasso.extend(Vec2::new(15.0, 0.0));
+        let selection = Selection::from_shape(&lasso, &system, Some(&spatial));
+        assert_eq!(selection.ids, ids(&system, &[0, 1]));
+        assert_eq!(selection.centroid(&system), Some(Vec2::new(5.0, 0.0)));
+    }
+
+    #[test]
+    fn test_bulk_edits_touch_only_the_selection() {
+        let mut system = row();
+        let mut selection = Selection { ids: ids(&system, &[2, 9]) };
+
+        selection.apply_impulse(&mut system, Vec2::new(0.0, 3.0));
+        assert_eq!(system.particles[2].velocity, Vec2::new(1.0, 3.0));
+        selection.stop(&mut system);
+        assert_eq!(system.particles[9].velocity, Vec2::ZERO);
+        assert_eq!(system.particles[4].velocity, Vec2::new(1.0, 0.0));
+
+        let mut species = SpeciesRegistry::default();
+        let id = species.add_species("Heavy");
+        selection.set_species(&mut system, &species, id);
+        assert_eq!(system.particles[2].species_id, id);
+        assert_eq!(system.particles[3].species_id, 0);
+
+        // A death elsewhere swaps the last, selected particle into its place; the selection follows it
+        let first = system.particles[0].id;
+        system.remove_particle(first);
+        assert_eq!(selection.positions(&system), vec![Vec2::new(20.0, 0.0), Vec2::new(90.0, 0.0)]);
+
+        assert_eq!(selection.delete(&mut system), 2);
+        assert!(selection.is_empty());
+        assert_eq!(system.particle_count(), 7);
+        assert!(system.particles.iter().all(|particle| particle.species_id == 0));
+    }
+}
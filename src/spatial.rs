@@ -1,5 +1,6 @@
 use glam::Vec2;
 use crate::particle::Particle;
+use std::cell::Cell;
 use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
@@ -8,6 +9,8 @@ pub struct SpatialGrid {
     bounds: (Vec2, Vec2),
     grid: HashMap<(i32, i32), Vec<usize>>,
     particle_positions: Vec<Vec2>,
+    // Queries answered since the count was last taken
+    queries: Cell<usize>,
 }
 
 impl SpatialGrid {
@@ -17,6 +20,7 @@ impl SpatialGrid {
             bounds,
             grid: HashMap::new(),
             particle_positions: Vec::new(),
+            queries: Cell::new(0),
         }
     }
 
@@ -33,6 +37,7 @@ impl SpatialGrid {
     }
 
     pub fn query_neighbors(&self, position: Vec2, radius: f32) -> Vec<usize> {
+        self.queries.set(self.queries.get() + 1);
         let mut neighbors = Vec::new();
         let min_cell = self.position_to_cell(position - Vec2::splat(radius));
         let max_cell = self.position_to_cell(position + Vec2::splat(radius));
@@ -64,12 +69,38 @@ impl SpatialGrid {
         }
     }
 
+    pub fn query_range(&self, range: (Vec2, Vec2)) -> Vec<usize> {
+        self.queries.set(self.queries.get() + 1);
+        let mut results = Vec::new();
+        let min_cell = self.position_to_cell(range.0);
+        let max_cell = self.position_to_cell(range.1);
+
+        for x in min_cell.0..=max_cell.0 {
+            for y in min_cell.1..=max_cell.1 {
+                if let Some(indices) = self.grid.get(&(x, y)) {
+                    results.extend(indices.iter().copied().filter(|&index| {
+                        self.particle_positions.get(index).is_some_and(|pos| {
+                            pos.x >= range.0.x && pos.x <= range.1.x && pos.y >= range.0.y && pos.y <= range.1.y
+                        })
+                    }));
+                }
+            }
+        }
+
+        results
+    }
+
     fn position_to_cell(&self, position: Vec2) -> (i32, i32) {
         let x = ((position.x - self.bounds.0.x) / self.cell_size).floor() as i32;
         let y = ((position.y - self.bounds.0.y) / self.cell_size).floor() as i32;
         (x, y)
     }
 
+    // Queries since the last call, which starts the count over
+    pub fn take_queries(&self) -> usize {
+        self.queries.take()
+    }
+
     pub fn get_cell_count(&self) -> usize {
         self.grid.len()
     }
@@ -272,6 +303,8 @@ impl QuadTree {
 pub struct QuadTreeManager {
     quadtree: QuadTree,
     particle_positions: Vec<Vec2>,
+    // Queries answered since the count was last taken
+    queries: Cell<usize>,
 }
 
 impl QuadTreeManager {
@@ -279,6 +312,7 @@ impl QuadTreeManager {
         Self {
             quadtree: QuadTree::new(bounds, max_particles_per_node, max_depth),
             particle_positions: Vec::new(),
+            queries: Cell::new(0),
         }
     }
 
@@ -294,6 +328,7 @@ impl QuadTreeManager {
     }
 
     pub fn query_neighbors(&self, position: Vec2, radius: f32) -> Vec<usize> {
+        self.queries.set(self.queries.get() + 1);
         let mut results = Vec::new();
         self.quadtree.query_radius(position, radius, &mut results, &self.particle_positions);
         results
@@ -309,11 +344,17 @@ impl QuadTreeManager {
     }
 
     pub fn query_range(&self, range: (Vec2, Vec2)) -> Vec<usize> {
+        self.queries.set(self.queries.get() + 1);
         let mut results = Vec::new();
         self.quadtree.query_range(range, &mut results, &self.particle_positions);
         results
     }
 
+    // Queries since the last call, which starts the count over
+    pub fn take_queries(&self) -> usize {
+        self.queries.take()
+    }
+
     pub fn get_statistics(&self) -> SpatialStatistics {
         SpatialStatistics {
             node_count: self.quadtree.get_node_count(),
@@ -364,6 +405,22 @@ impl SpatialPartitioning {
             SpatialPartitioning::QuadTree(quadtree) => quadtree.query_neighbors_for_particle(particle_index, radius),
         }
     }
+
+    // Indices of particles inside the (min, max) box
+    pub fn query_range(&self, range: (Vec2, Vec2)) -> Vec<usize> {
+        match self {
+            SpatialPartitioning::Grid(grid) => grid.query_range(range),
+            SpatialPartitioning::QuadTree(quadtree) => quadtree.query_range(range),
+        }
+    }
+
+    // Neighbour and range queries since the last call, which starts the count over
+    pub fn take_queries(&self) -> usize {
+        match self {
+            SpatialPartitioning::Grid(grid) => grid.take_queries(),
+            SpatialPartitioning::QuadTree(quadtree) => quadtree.take_queries(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -441,5 +498,9 @@ mod tests {
         
         let neighbors = spatial.query_neighbors(Vec2::new(10.0, 10.0), 10.0);
         assert!(!neighbors.is_empty());
+        spatial.query_neighbors_for_particle(0, 10.0);
+        spatial.query_range((Vec2::ZERO, Vec2::splat(20.0)));
+        assert_eq!(spatial.take_queries(), 3);
+        assert_eq!(spatial.take_queries(), 0);
     }
 }
\ No newline at end of file
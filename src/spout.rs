@@ -0,0 +1,83 @@
+use crate::output::{FrameSink, VideoFrame};
+
+// Thin C wrapper around the SpoutDX sender, compiled from native/spout_sender.cpp by build.rs
+#[cfg(all(feature = "spout", target_os = "windows"))]
+mod ffi {
+    use std::os::raw::{c_char, c_void};
+
+    pub type Sender = *mut c_void;
+
+    extern "C" {
+        pub fn inochi_spout_create(name: *const c_char) -> Sender;
+        pub fn inochi_spout_send(sender: Sender, pixels: *const u8, width: u32, height: u32) -> bool;
+        pub fn inochi_spout_destroy(sender: Sender);
+    }
+}
+
+// Shares frames as a Spout texture with other applications on the same Windows machine
+pub struct SpoutSink {
+    name: String,
+    #[cfg(all(feature = "spout", target_os = "windows"))]
+    sender: ffi::Sender,
+}
+
+impl SpoutSink {
+    pub fn sink_name(sender_name: &str) -> String {
+        format!("{} (Spout)", sender_name)
+    }
+
+    #[cfg(all(feature = "spout", target_os = "windows"))]
+    pub fn new(sender_name: &str) -> Result<Self, String> {
+        let c_name = std::ffi::CString::new(sender_name).map_err(|e| e.to_string())?;
+
+        // SAFETY: the name outlives the call; Spout copies it into the sender list
+        let sender = unsafe { ffi::inochi_spout_create(c_name.as_ptr()) };
+        if sender.is_null() {
+            return Err("Failed to create Spout sender (is DirectX 11 available?)".to_string());
+        }
+
+        Ok(Self {
+            name: Self::sink_name(sender_name),
+            sender,
+        })
+    }
+
+    #[cfg(not(all(feature = "spout", target_os = "windows")))]
+    pub fn new(_sender_name: &str) -> Result<Self, String> {
+        Err("Spout output requires Windows and the `spout` feature".to_string())
+    }
+}
+
+impl FrameSink for SpoutSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[cfg(all(feature = "spout", target_os = "windows"))]
+    fn publish(&mut self, frame: &VideoFrame) -> Result<(), String> {
+        if frame.pixels.len() != frame.width * frame.height * 4 {
+            return Err("Frame size does not match its dimensions".to_string());
+        }
+
+        // SAFETY: the pixels are copied into the shared texture before the call returns
+        let sent = unsafe { ffi::inochi_spout_send(self.sender, frame.pixels.as_ptr(), frame.width as u32, frame.height as u32) };
+        if sent {
+            Ok(())
+        } else {
+            Err("Spout sender rejected the frame".to_string())
+        }
+    }
+
+    #[cfg(not(all(feature = "spout", target_os = "windows")))]
+    fn publish(&mut self, _frame: &VideoFrame) -> Result<(), String> {
+        Err("Spout output is not available in this build".to_string())
+    }
+}
+
+#[cfg(all(feature = "spout", target_os = "windows"))]
+impl Drop for SpoutSink {
+    fn drop(&mut self) {
+        // SAFETY: the sender was created by inochi_spout_create and is destroyed once
+        unsafe { ffi::inochi_spout_destroy(self.sender) };
+    }
+}
@@ -0,0 +1,284 @@
+// Text stamping needs ab_glyph and egui's fonts, so it is only built with "desktop"
+#[cfg(feature = "desktop")]
+use ab_glyph::{point, Font, FontVec, PxScale, ScaleFont};
+use glam::Vec2;
+use crate::particle::ParticleSystem;
+use crate::pool::ParticleId;
+
+pub const DEFAULT_FONT: &str = "Ubuntu-Light";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StampMode {
+    // Particles are left to the active forces
+    Free,
+    // Particles start moving outward from the stamp's center
+    Disperse,
+    // Particles are sprung back to where they were placed
+    Hold,
+    // Particles are locked together into one rigid body
+    Rigid,
+}
+
+impl StampMode {
+    pub fn all() -> Vec<StampMode> {
+        vec![StampMode::Free, StampMode::Disperse, StampMode::Hold, StampMode::Rigid]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            StampMode::Free => "Free",
+            StampMode::Disperse => "Disperse",
+            StampMode::Hold => "Hold",
+            StampMode::Rigid => "Rigid",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StampOptions {
+    pub species_id: u32,
+    pub mode: StampMode,
+    // Outward speed for Disperse, spring stiffness for Hold
+    pub strength: f32,
+}
+
+impl Default for StampOptions {
+    fn default() -> Self {
+        Self {
+            species_id: 0,
+            mode: StampMode::Hold,
+            strength: 2.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TextStampOptions {
+    pub text: String,
+    // Name of a built-in UI font, or a path to a .ttf/.otf file
+    pub font: String,
+    // Height of one line in world units
+    pub font_size: f32,
+    // Distance between neighbouring particles in world units
+    pub spacing: f32,
+    pub stamp: StampOptions,
+}
+
+impl Default for TextStampOptions {
+    fn default() -> Self {
+        Self {
+            text: "inochi".to_string(),
+            font: DEFAULT_FONT.to_string(),
+            font_size: 120.0,
+            spacing: 4.0,
+            stamp: StampOptions::default(),
+        }
+    }
+}
+
+// Fonts bundled with egui that can render plain text
+#[cfg(feature = "desktop")]
+pub fn builtin_fonts() -> Vec<String> {
+    egui::FontDefinitions::default()
+        .font_data
+        .keys()
+        .filter(|name| !name.contains("moji"))
+        .cloned()
+        .collect()
+}
+
+#[cfg(feature = "desktop")]
+fn builtin_font_data(font: &str) -> Option<Vec<u8>> {
+    egui::FontDefinitions::default().font_data.get(font).map(|data| data.font.to_vec())
+}
+
+#[cfg(feature = "desktop")]
+pub fn load_font(font: &str) -> Result<FontVec, String> {
+    let bytes = match builtin_font_data(font) {
+        Some(bytes) => bytes,
+        None => std::fs::read(font).map_err(|e| format!("Failed to read font {}: {}", font, e))?,
+    };
+    FontVec::try_from_vec(bytes).map_err(|e| format!("Invalid font {}: {}", font, e))
+}
+
+// Grayscale coverage (0..1) of the rendered text, row-major from the top-left
+pub struct CoverageMask {
+    pub width: usize,
+    pub height: usize,
+    pub coverage: Vec<f32>,
+}
+
+#[cfg(feature = "desktop")]
+pub fn rasterize_text(font: &FontVec, text: &str, pixel_height: f32) -> CoverageMask {
+    let scale = PxScale::from(pixel_height.max(1.0));
+    let scaled = font.as_scaled(scale);
+    let line_height = scaled.height() + scaled.line_gap();
+
+    // Lay out glyphs line by line, applying kerning between neighbours
+    let mut glyphs = Vec::new();
+    let mut width: f32 = 0.0;
+    let mut line_count = 0;
+    for (line_index, line) in text.lines().enumerate() {
+        let baseline = scaled.ascent() + line_index as f32 * line_height;
+        let mut caret = 0.0;
+        let mut previous = None;
+        for character in line.chars() {
+            let id = font.glyph_id(character);
+            if let Some(previous) = previous {
+                caret += scaled.kern(previous, id);
+            }
+            glyphs.push(id.with_scale_and_position(scale, point(caret, baseline)));
+            caret += scaled.h_advance(id);
+            previous = Some(id);
+        }
+        width = width.max(caret);
+        line_count += 1;
+    }
+
+    let width = width.ceil() as usize;
+    let height = (line_count as f32 * line_height).ceil() as usize;
+    let mut coverage = vec![0.0f32; width * height];
+
+    for glyph in glyphs {
+        if let Some(outlined) = font.outline_glyph(glyph) {
+            let bounds = outlined.px_bounds();
+            outlined.draw(|x, y, value| {
+                let px = bounds.min.x as i64 + x as i64;
+                let py = bounds.min.y as i64 + y as i64;
+                if px >= 0 && py >= 0 && (px as usize) < width && (py as usize) < height {
+                    let cell = &mut coverage[py as usize * width + px as usize];
+                    *cell = cell.max(value);
+                }
+            });
+        }
+    }
+
+    CoverageMask { width, height, coverage }
+}
+
+impl CoverageMask {
+    // Coverage from the alpha channel of RGBA8 pixels, e.g. text drawn on a browser canvas
+    pub fn from_rgba_alpha(pixels: &[u8], width: usize, height: usize) -> Self {
+        let coverage = pixels
+            .chunks_exact(4)
+            .take(width * height)
+            .map(|pixel| pixel[3] as f32 / 255.0)
+            .collect::<Vec<_>>();
+        let height = coverage.len().checked_div(width).unwrap_or(0);
+        Self { width, height, coverage }
+    }
+
+    // World positions of covered pixels, one world unit per `scale` pixels, centered on `center`
+    pub fn positions(&self, center: Vec2, scale: f32) -> Vec<Vec2> {
+        let mut positions = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.coverage[y * self.width + x] < 0.5 {
+                    continue;
+                }
+                // Mask rows run top to bottom, world y runs bottom to top
+                let offset = Vec2::new(
+                    x as f32 - self.width as f32 * 0.5,
+                    self.height as f32 * 0.5 - y as f32,
+                );
+                positions.push(center + offset * scale);
+            }
+        }
+        positions
+    }
+}
+
+// Rasterizes at one pixel per particle so that coverage sampling gives the requested spacing
+#[cfg(feature = "desktop")]
+pub fn text_positions(options: &TextStampOptions, center: Vec2) -> Result<Vec<Vec2>, String> {
+    let font = load_font(&options.font)?;
+    let spacing = options.spacing.max(0.1);
+    let mask = rasterize_text(&font, &options.text, options.font_size / spacing);
+    Ok(mask.positions(center, spacing))
+}
+
+// Springs stamped particles back to where they were placed. Particles are tracked by id, so a
+// stamped particle that dies just drops out, and the hold is released once none are left.
+#[derive(Debug, Clone)]
+pub struct ShapeHold {
+    homes: Vec<(ParticleId, Vec2)>,
+    stiffness: f32,
+}
+
+impl ShapeHold {
+    pub fn new(homes: Vec<(ParticleId, Vec2)>, stiffness: f32) -> Self {
+        Self { homes, stiffness }
+    }
+
+    pub fn len(&self) -> usize {
+        self.homes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.homes.is_empty()
+    }
+
+    // Returns false once the held particles are gone
+    pub fn apply(&self, system: &mut ParticleSystem) -> bool {
+        // Slightly underdamped so shapes settle without looking rigid
+        let damping = self.stiffness.sqrt();
+        let mut held = false;
+        for &(id, home) in &self.homes {
+            if let Some(particle) = system.get_mut(id) {
+                let acceleration = (home - particle.position) * self.stiffness - particle.velocity * damping;
+                particle.apply_force(acceleration * particle.mass);
+                held = true;
+            }
+        }
+        held
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::particle::Particle;
+
+    #[test]
+    #[cfg(feature = "desktop")]
+    fn test_text_rasterizes_with_builtin_font() {
+        assert!(builtin_fonts().iter().any(|name| name == DEFAULT_FONT));
+
+        let options = TextStampOptions {
+            text: "Hi".to_string(),
+            font_size: 40.0,
+            spacing: 2.0,
+            ..TextStampOptions::default()
+        };
+        let positions = text_positions(&options, Vec2::ZERO).unwrap();
+        assert!(!positions.is_empty());
+
+        let wide = TextStampOptions {
+            text: "Hi Hi".to_string(),
+            ..options.clone()
+        };
+        assert!(text_positions(&wide, Vec2::ZERO).unwrap().len() > positions.len());
+
+        let missing = TextStampOptions {
+            font: "no/such/font.ttf".to_string(),
+            ..options
+        };
+        assert!(text_positions(&missing, Vec2::ZERO).is_err());
+    }
+
+    #[test]
+    fn test_shape_hold_pulls_home_and_releases() {
+        let mut system = ParticleSystem::new(10);
+        system.add_particle(Particle::new(Vec2::new(-10.0, 0.0)));
+        system.add_particle(Particle::new(Vec2::new(10.0, 0.0)));
+        let hold = ShapeHold::new(vec![(system.particles[1].id, Vec2::ZERO)], 4.0);
+
+        // An unheld particle dying moves the held one into its slot; the hold follows it
+        system.remove_particle(system.particles[0].id);
+        assert!(hold.apply(&mut system));
+        assert!(system.particles[0].acceleration.x < 0.0);
+
+        system.clear();
+        assert!(!hold.apply(&mut system));
+    }
+}
@@ -0,0 +1,315 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::particle::Particle;
+
+// Classic Reynolds steering behaviors. Each one yields a desired change in velocity;
+// a species' behaviors are blended by weight and the result is capped at max_force.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SteeringBehavior {
+    // Smoothly varying random heading, projected `distance` ahead onto a circle of `radius`
+    Wander { radius: f32, distance: f32, rate: f32 },
+    // Head for where the nearest particle of `species` is about to be
+    Pursue { species: u32, radius: f32 },
+    Evade { species: u32, radius: f32 },
+    // Travel along `paths[path]`, looking `lookahead` units ahead of the closest point
+    FollowPath { path: usize, lookahead: f32 },
+    // Flocking rules, applied among particles of the same species
+    Separate { radius: f32 },
+    Align { radius: f32 },
+    Cohere { radius: f32 },
+}
+
+impl SteeringBehavior {
+    pub fn all() -> Vec<SteeringBehavior> {
+        vec![
+            SteeringBehavior::Wander { radius: 20.0, distance: 40.0, rate: 1.0 },
+            SteeringBehavior::Pursue { species: 0, radius: 150.0 },
+            SteeringBehavior::Evade { species: 0, radius: 80.0 },
+            SteeringBehavior::FollowPath { path: 0, lookahead: 30.0 },
+            SteeringBehavior::Separate { radius: 20.0 },
+            SteeringBehavior::Align { radius: 40.0 },
+            SteeringBehavior::Cohere { radius: 60.0 },
+        ]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            SteeringBehavior::Wander { .. } => "Wander",
+            SteeringBehavior::Pursue { .. } => "Pursue",
+            SteeringBehavior::Evade { .. } => "Evade",
+            SteeringBehavior::FollowPath { .. } => "Follow Path",
+            SteeringBehavior::Separate { .. } => "Separate",
+            SteeringBehavior::Align { .. } => "Align",
+            SteeringBehavior::Cohere { .. } => "Cohere",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeightedBehavior {
+    pub behavior: SteeringBehavior,
+    pub weight: f32,
+}
+
+// A polyline drawn in the world; closed paths loop back to their first point
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SteeringPath {
+    pub points: Vec<Vec2>,
+    pub closed: bool,
+}
+
+impl SteeringPath {
+    fn segments(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        let closing = if self.closed && self.points.len() > 2 {
+            Some((self.points[self.points.len() - 1], self.points[0]))
+        } else {
+            None
+        };
+        self.points.windows(2).map(|w| (w[0], w[1])).chain(closing)
+    }
+
+    // Closest point on the path to `position`, moved `lookahead` further along the path
+    pub fn point_ahead(&self, position: Vec2, lookahead: f32) -> Option<Vec2> {
+        let segments: Vec<(Vec2, Vec2)> = self.segments().collect();
+        if segments.is_empty() {
+            return self.points.first().copied();
+        }
+
+        let (mut index, mut along) = (0, 0.0);
+        let mut best = f32::MAX;
+        for (i, (a, b)) in segments.iter().enumerate() {
+            let length = a.distance(*b);
+            let t = if length > 0.0 {
+                ((position - *a).dot(*b - *a) / (length * length)).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let distance = position.distance_squared(a.lerp(*b, t));
+            if distance < best {
+                best = distance;
+                index = i;
+                along = t * length;
+            }
+        }
+
+        // Walk forward, wrapping on closed paths and stopping at the end of open ones
+        let total: f32 = segments.iter().map(|(a, b)| a.distance(*b)).sum();
+        let mut remaining = along + lookahead;
+        loop {
+            let (a, b) = segments[index];
+            let length = a.distance(b);
+            if remaining <= length {
+                return Some(a + (b - a).normalize_or_zero() * remaining);
+            }
+            remaining -= length;
+            index += 1;
+            if index == segments.len() {
+                if !self.closed || total <= 0.0 {
+                    return Some(b);
+                }
+                index = 0;
+                remaining %= total;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SteeringConfig {
+    pub enabled: bool,
+    pub max_speed: f32,
+    pub max_force: f32,
+    pub species: HashMap<u32, Vec<WeightedBehavior>>,
+    pub paths: Vec<SteeringPath>,
+}
+
+impl Default for SteeringConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_speed: 60.0,
+            max_force: 120.0,
+            species: HashMap::new(),
+            paths: Vec::new(),
+        }
+    }
+}
+
+impl SteeringConfig {
+    // Blended steering force on particle `index`
+    pub fn force(&self, particle: &Particle, index: usize, particles: &[Particle]) -> Vec2 {
+        if !self.enabled {
+            return Vec2::ZERO;
+        }
+        let Some(behaviors) = self.species.get(&particle.species_id) else {
+            return Vec2::ZERO;
+        };
+
+        let steering: Vec2 = behaviors
+            .iter()
+            .map(|weighted| self.steer(&weighted.behavior, particle, index, particles) * weighted.weight)
+            .sum();
+        steering.clamp_length_max(self.max_force) * particle.mass
+    }
+
+    fn seek(&self, particle: &Particle, target: Vec2) -> Vec2 {
+        (target - particle.position).normalize_or_zero() * self.max_speed - particle.velocity
+    }
+
+    fn flee(&self, particle: &Particle, threat: Vec2) -> Vec2 {
+        (particle.position - threat).normalize_or_zero() * self.max_speed - particle.velocity
+    }
+
+    fn steer(&self, behavior: &SteeringBehavior, particle: &Particle, index: usize, particles: &[Particle]) -> Vec2 {
+        match *behavior {
+            SteeringBehavior::Wander { radius, distance, rate } => {
+                let heading = particle.velocity.try_normalize().unwrap_or(Vec2::X);
+                let angle = wander_angle(index, particle.age * rate);
+                self.seek(particle, particle.position + heading * distance + Vec2::from_angle(angle) * radius)
+            },
+            SteeringBehavior::Pursue { species, radius } => {
+                match nearest(particle, index, particles, radius, |other| other.species_id == species) {
+                    Some(other) => self.seek(particle, self.predict(particle, other)),
+                    None => Vec2::ZERO,
+                }
+            },
+            SteeringBehavior::Evade { species, radius } => {
+                match nearest(particle, index, particles, radius, |other| other.species_id == species) {
+                    Some(other) => self.flee(particle, self.predict(particle, other)),
+                    None => Vec2::ZERO,
+                }
+            },
+            SteeringBehavior::FollowPath { path, lookahead } => {
+                let target = self.paths.get(path).and_then(|path| path.point_ahead(particle.position, lookahead));
+                match target {
+                    Some(target) => self.seek(particle, target),
+                    None => Vec2::ZERO,
+                }
+            },
+            SteeringBehavior::Separate { radius } => {
+                let mut away = Vec2::ZERO;
+                for_neighbors(particle, index, particles, radius, |other, distance| {
+                    away += (particle.position - other.position) / (distance * distance);
+                });
+                if away == Vec2::ZERO {
+                    Vec2::ZERO
+                } else {
+                    away.normalize() * self.max_speed - particle.velocity
+                }
+            },
+            SteeringBehavior::Align { radius } => {
+                let mut heading = Vec2::ZERO;
+                for_neighbors(particle, index, particles, radius, |other, _| heading += other.velocity);
+                match heading.try_normalize() {
+                    Some(heading) => heading * self.max_speed - particle.velocity,
+                    None => Vec2::ZERO,
+                }
+            },
+            SteeringBehavior::Cohere { radius } => {
+                let (mut sum, mut count) = (Vec2::ZERO, 0);
+                for_neighbors(particle, index, particles, radius, |other, _| {
+                    sum += other.position;
+                    count += 1;
+                });
+                if count == 0 {
+                    Vec2::ZERO
+                } else {
+                    self.seek(particle, sum / count as f32)
+                }
+            },
+        }
+    }
+
+    // Where `other` will be by the time `particle` could reach it
+    fn predict(&self, particle: &Particle, other: &Particle) -> Vec2 {
+        let time = particle.position.distance(other.position) / self.max_speed.max(1.0);
+        other.position + other.velocity * time
+    }
+}
+
+// Smooth pseudo-random angle per particle, so wandering needs no stored state
+fn wander_angle(index: usize, time: f32) -> f32 {
+    let seed = index as f32 * 12.9898;
+    (time * 1.3 + seed).sin() * 2.0 + (time * 0.7 + seed * 1.7).sin() * 1.5
+}
+
+fn nearest<'a>(
+    particle: &Particle,
+    index: usize,
+    particles: &'a [Particle],
+    radius: f32,
+    filter: impl Fn(&Particle) -> bool,
+) -> Option<&'a Particle> {
+    particles
+        .iter()
+        .enumerate()
+        .filter(|(i, other)| *i != index && filter(other))
+        .map(|(_, other)| (other, particle.position.distance_squared(other.position)))
+        .filter(|(_, distance)| *distance <= radius * radius)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(other, _)| other)
+}
+
+fn for_neighbors(particle: &Particle, index: usize, particles: &[Particle], radius: f32, mut visit: impl FnMut(&Particle, f32)) {
+    for (i, other) in particles.iter().enumerate() {
+        if i == index || other.species_id != particle.species_id {
+            continue;
+        }
+        let distance = particle.position.distance(other.position);
+        if distance > 0.0 && distance < radius {
+            visit(other, distance);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(behavior: SteeringBehavior) -> SteeringConfig {
+        let mut config = SteeringConfig {
+            enabled: true,
+            ..SteeringConfig::default()
+        };
+        config.species.insert(0, vec![WeightedBehavior { behavior, weight: 1.0 }]);
+        config
+    }
+
+    #[test]
+    fn test_pursue_and_evade() {
+        let hunter = Particle::new(Vec2::ZERO).with_species(0);
+        let prey = Particle::new(Vec2::new(50.0, 0.0)).with_species(1);
+        let particles = [hunter, prey];
+
+        let pursue = config(SteeringBehavior::Pursue { species: 1, radius: 100.0 });
+        assert!(pursue.force(&hunter, 0, &particles).x > 0.0);
+
+        let evade = config(SteeringBehavior::Evade { species: 1, radius: 100.0 });
+        assert!(evade.force(&hunter, 0, &particles).x < 0.0);
+
+        // Out of range, or for species without behaviors, nothing happens
+        let short = config(SteeringBehavior::Pursue { species: 1, radius: 10.0 });
+        assert_eq!(short.force(&hunter, 0, &particles), Vec2::ZERO);
+        assert_eq!(pursue.force(&prey, 1, &particles), Vec2::ZERO);
+    }
+
+    #[test]
+    fn test_path_following() {
+        let path = SteeringPath {
+            points: vec![Vec2::ZERO, Vec2::new(100.0, 0.0), Vec2::new(100.0, 100.0)],
+            closed: false,
+        };
+        assert_eq!(path.point_ahead(Vec2::new(20.0, 10.0), 30.0), Some(Vec2::new(50.0, 0.0)));
+        assert_eq!(path.point_ahead(Vec2::new(90.0, 0.0), 20.0), Some(Vec2::new(100.0, 10.0)));
+        assert_eq!(path.point_ahead(Vec2::new(100.0, 90.0), 50.0), Some(Vec2::new(100.0, 100.0)));
+
+        let closed = SteeringPath { closed: true, ..path };
+        assert_eq!(closed.point_ahead(Vec2::new(100.0, 90.0), 50.0).map(|p| p.x < 100.0), Some(true));
+
+        let mut follow = config(SteeringBehavior::FollowPath { path: 0, lookahead: 30.0 });
+        follow.paths.push(closed);
+        let particle = Particle::new(Vec2::new(50.0, 10.0));
+        assert!(follow.force(&particle, 0, &[particle]).x > 0.0);
+    }
+}
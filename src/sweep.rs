@@ -0,0 +1,307 @@
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use crate::config::{ConfigManager, Preset, SimulationConfig};
+use crate::evolution::{cluster_sizes, Fitness, FitnessKind};
+use crate::forces::ForceKind;
+use crate::particle::ParticleSystem;
+use crate::presets::PresetManager;
+
+// Particles closer than this are counted as part of the same cluster
+const CLUSTER_RADIUS: f32 = 15.0;
+
+// Config values that can be swept. Force kinds are also scaled with "forces.scale.<kind>",
+// e.g. "forces.scale.lennard_jones".
+pub const SWEEP_PATHS: &[&str] = &[
+    "physics.dt",
+    "physics.max_force",
+    "physics.max_velocity",
+    "physics.collision_restitution",
+    "particles.initial_particle_count",
+    "particles.max_particles",
+    "particles.spawn_rate",
+    "particles.default_mass",
+    "particles.default_charge",
+    "particles.mass_variation",
+    "forces.gravity_strength",
+    "forces.electromagnetic_strength",
+    "forces.damping_coefficient",
+    "forces.brownian_intensity",
+];
+
+// One config value to vary, addressed by one of SWEEP_PATHS
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepParameter {
+    pub path: String,
+    // Explicit values take precedence over the min/max/steps range
+    #[serde(default)]
+    pub values: Vec<f64>,
+    #[serde(default)]
+    pub min: f64,
+    #[serde(default)]
+    pub max: f64,
+    #[serde(default = "default_parameter_steps")]
+    pub steps: usize,
+}
+
+fn default_parameter_steps() -> usize {
+    2
+}
+
+impl SweepParameter {
+    pub fn values(&self) -> Vec<f64> {
+        if !self.values.is_empty() {
+            return self.values.clone();
+        }
+        if self.steps <= 1 {
+            return vec![self.min];
+        }
+        (0..self.steps)
+            .map(|i| self.min + (self.max - self.min) * i as f64 / (self.steps - 1) as f64)
+            .collect()
+    }
+}
+
+// A sweep file, usually loaded from TOML:
+//
+//   preset = "particle_life"
+//   steps = 300
+//   output = "sweep.csv"
+//
+//   [[parameters]]
+//   path = "physics.dt"
+//   min = 0.008
+//   max = 0.032
+//   steps = 4
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepSpec {
+    pub preset: String,
+    pub steps: usize,
+    // Runs per combination, to average out random initial layouts
+    #[serde(default = "default_repeats")]
+    pub repeats: usize,
+    #[serde(default)]
+    pub parallel: bool,
+    // .csv or .json; the format follows the extension
+    #[serde(default)]
+    pub output: Option<PathBuf>,
+    pub parameters: Vec<SweepParameter>,
+}
+
+fn default_repeats() -> usize {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunMetrics {
+    pub particle_count: usize,
+    pub total_energy: f32,
+    pub mean_speed: f32,
+    // Mean distance from the center of mass
+    pub spread: f32,
+    pub cluster_count: usize,
+    pub movement_entropy: f32,
+}
+
+impl RunMetrics {
+    pub fn measure(system: &ParticleSystem) -> Self {
+        let particles = &system.particles;
+        let count = particles.len().max(1) as f32;
+        let center = system.center_of_mass();
+        Self {
+            particle_count: particles.len(),
+            total_energy: system.total_energy(),
+            mean_speed: particles.iter().map(|p| p.velocity.length()).sum::<f32>() / count,
+            spread: particles.iter().map(|p| p.position.distance(center)).sum::<f32>() / count,
+            cluster_count: cluster_sizes(particles, CLUSTER_RADIUS).into_iter().filter(|&size| size >= 3).count(),
+            movement_entropy: FitnessKind::MovementEntropy.score(particles),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunResult {
+    pub parameters: Vec<(String, f64)>,
+    pub repeat: usize,
+    pub metrics: RunMetrics,
+}
+
+impl SweepSpec {
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        toml::from_str(&contents).map_err(|e| format!("Invalid sweep file {}: {}", path.display(), e))
+    }
+
+    pub fn preset(&self) -> Result<Preset, String> {
+        Preset::from_key(&self.preset).ok_or_else(|| format!("Unknown preset: {}", self.preset))
+    }
+
+    // Cartesian product of every parameter's values
+    pub fn combinations(&self) -> Vec<Vec<(String, f64)>> {
+        let mut combinations = vec![Vec::new()];
+        for parameter in &self.parameters {
+            let values = parameter.values();
+            combinations = combinations
+                .into_iter()
+                .flat_map(|combination| {
+                    values.iter().map(move |&value| {
+                        let mut combination = combination.clone();
+                        combination.push((parameter.path.clone(), value));
+                        combination
+                    })
+                })
+                .collect();
+        }
+        combinations
+    }
+
+    pub fn run(&self) -> Result<Vec<RunResult>, String> {
+        let preset = self.preset()?;
+        let mut base = ConfigManager::new();
+        base.apply_preset(preset.clone());
+        let base = base.config().clone();
+
+        // Resolve every config up front so a bad path fails before any simulation runs
+        let mut jobs = Vec::new();
+        for parameters in self.combinations() {
+            let config = apply_parameters(&base, &parameters)?;
+            for repeat in 0..self.repeats.max(1) {
+                jobs.push((parameters.clone(), repeat, config.clone()));
+            }
+        }
+
+        let run = |(parameters, repeat, config): (Vec<(String, f64)>, usize, SimulationConfig)| {
+            let system = PresetManager::run_headless(&preset, &config, self.steps);
+            RunResult { parameters, repeat, metrics: RunMetrics::measure(&system) }
+        };
+        Ok(if self.parallel {
+            jobs.into_par_iter().map(run).collect()
+        } else {
+            jobs.into_iter().map(run).collect()
+        })
+    }
+}
+
+pub fn apply_parameter(config: &mut SimulationConfig, path: &str, value: f64) -> Result<(), String> {
+    let count = value.round().max(0.0) as usize;
+    let value = value as f32;
+    match path {
+        "physics.dt" => config.physics.dt = value,
+        "physics.max_force" => config.physics.max_force = value,
+        "physics.max_velocity" => config.physics.max_velocity = value,
+        "physics.collision_restitution" => config.physics.collision_restitution = value,
+        "particles.initial_particle_count" => config.particles.initial_particle_count = count,
+        "particles.max_particles" => config.particles.max_particles = count,
+        "particles.spawn_rate" => config.particles.spawn_rate = value,
+        "particles.default_mass" => config.particles.default_mass = value,
+        "particles.default_charge" => config.particles.default_charge = value,
+        "particles.mass_variation" => config.particles.mass_variation = value,
+        "forces.gravity_strength" => config.forces.gravity_strength = value,
+        "forces.electromagnetic_strength" => config.forces.electromagnetic_strength = value,
+        "forces.damping_coefficient" => config.forces.damping_coefficient = value,
+        "forces.brownian_intensity" => config.forces.brownian_intensity = value,
+        _ => {
+            let kind = path
+                .strip_prefix("forces.scale.")
+                .and_then(ForceKind::from_key)
+                .ok_or_else(|| format!("Unknown sweep parameter: {}", path))?;
+            config.forces.force_scales.insert(kind, value.max(0.0));
+        },
+    }
+    Ok(())
+}
+
+pub fn apply_parameters(config: &SimulationConfig, parameters: &[(String, f64)]) -> Result<SimulationConfig, String> {
+    let mut config = config.clone();
+    for (path, value) in parameters {
+        apply_parameter(&mut config, path, *value)?;
+    }
+    Ok(config)
+}
+
+pub fn write_results(results: &[RunResult], path: &Path) -> Result<(), String> {
+    let contents = match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => serde_json::to_string_pretty(results).map_err(|e| e.to_string())?,
+        _ => results_csv(results),
+    };
+    std::fs::write(path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+// One row per run: the swept parameters, then the metrics
+pub fn results_csv(results: &[RunResult]) -> String {
+    let mut csv = String::new();
+    if let Some(first) = results.first() {
+        for (path, _) in &first.parameters {
+            csv.push_str(path);
+            csv.push(',');
+        }
+    }
+    csv.push_str("repeat,particle_count,total_energy,mean_speed,spread,cluster_count,movement_entropy\n");
+
+    for result in results {
+        for (_, value) in &result.parameters {
+            csv.push_str(&format!("{},", value));
+        }
+        let m = &result.metrics;
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            result.repeat, m.particle_count, m.total_energy, m.mean_speed, m.spread, m.cluster_count, m.movement_entropy,
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> SweepSpec {
+        toml::from_str(
+            r#"
+            preset = "particle_life"
+            steps = 2
+
+            [[parameters]]
+            path = "physics.dt"
+            min = 0.01
+            max = 0.03
+            steps = 3
+
+            [[parameters]]
+            path = "particles.initial_particle_count"
+            values = [10, 20]
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_combinations_and_parameters() {
+        let spec = spec();
+        let combinations = spec.combinations();
+        assert_eq!(combinations.len(), 6);
+        assert_eq!(combinations[1], vec![("physics.dt".to_string(), 0.01), ("particles.initial_particle_count".to_string(), 20.0)]);
+
+        let config = apply_parameters(&SimulationConfig::default(), &combinations[1]).unwrap();
+        assert_eq!(config.physics.dt, 0.01);
+        assert_eq!(config.particles.initial_particle_count, 20);
+
+        let scaled = apply_parameters(&config, &[("forces.scale.lennard_jones".to_string(), 2.0)]).unwrap();
+        assert_eq!(scaled.forces.force_scale(ForceKind::LennardJones), 2.0);
+
+        let missing = [("physics.no_such_field".to_string(), 1.0)];
+        assert!(apply_parameters(&SimulationConfig::default(), &missing).is_err());
+    }
+
+    #[test]
+    fn test_run_writes_a_row_per_run() {
+        let spec = SweepSpec { repeats: 2, parallel: true, ..spec() };
+        let results = spec.run().unwrap();
+        assert_eq!(results.len(), 12);
+
+        let csv = results_csv(&results);
+        assert!(csv.starts_with("physics.dt,particles.initial_particle_count,repeat,"));
+        assert_eq!(csv.lines().count(), 13);
+    }
+}
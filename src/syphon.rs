@@ -0,0 +1,83 @@
+use crate::output::{FrameSink, VideoFrame};
+
+// Thin C wrapper around SyphonMetalServer, compiled from native/syphon_server.m by build.rs
+#[cfg(all(feature = "syphon", target_os = "macos"))]
+mod ffi {
+    use std::os::raw::{c_char, c_void};
+
+    pub type Server = *mut c_void;
+
+    extern "C" {
+        pub fn inochi_syphon_create(name: *const c_char) -> Server;
+        pub fn inochi_syphon_publish(server: Server, pixels: *const u8, width: u32, height: u32) -> bool;
+        pub fn inochi_syphon_destroy(server: Server);
+    }
+}
+
+// Shares frames as a Syphon server with other applications on the same Mac
+pub struct SyphonSink {
+    name: String,
+    #[cfg(all(feature = "syphon", target_os = "macos"))]
+    server: ffi::Server,
+}
+
+impl SyphonSink {
+    pub fn sink_name(server_name: &str) -> String {
+        format!("{} (Syphon)", server_name)
+    }
+
+    #[cfg(all(feature = "syphon", target_os = "macos"))]
+    pub fn new(server_name: &str) -> Result<Self, String> {
+        let c_name = std::ffi::CString::new(server_name).map_err(|e| e.to_string())?;
+
+        // SAFETY: the name outlives the call; the server keeps its own copy
+        let server = unsafe { ffi::inochi_syphon_create(c_name.as_ptr()) };
+        if server.is_null() {
+            return Err("Failed to create Syphon server (no Metal device?)".to_string());
+        }
+
+        Ok(Self {
+            name: Self::sink_name(server_name),
+            server,
+        })
+    }
+
+    #[cfg(not(all(feature = "syphon", target_os = "macos")))]
+    pub fn new(_server_name: &str) -> Result<Self, String> {
+        Err("Syphon output requires macOS and the `syphon` feature".to_string())
+    }
+}
+
+impl FrameSink for SyphonSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[cfg(all(feature = "syphon", target_os = "macos"))]
+    fn publish(&mut self, frame: &VideoFrame) -> Result<(), String> {
+        if frame.pixels.len() != frame.width * frame.height * 4 {
+            return Err("Frame size does not match its dimensions".to_string());
+        }
+
+        // SAFETY: the pixels are copied into a Metal texture before the call returns
+        let published = unsafe { ffi::inochi_syphon_publish(self.server, frame.pixels.as_ptr(), frame.width as u32, frame.height as u32) };
+        if published {
+            Ok(())
+        } else {
+            Err("Syphon server could not publish the frame".to_string())
+        }
+    }
+
+    #[cfg(not(all(feature = "syphon", target_os = "macos")))]
+    fn publish(&mut self, _frame: &VideoFrame) -> Result<(), String> {
+        Err("Syphon output is not available in this build".to_string())
+    }
+}
+
+#[cfg(all(feature = "syphon", target_os = "macos"))]
+impl Drop for SyphonSink {
+    fn drop(&mut self) {
+        // SAFETY: the server was created by inochi_syphon_create and is destroyed once
+        unsafe { ffi::inochi_syphon_destroy(self.server) };
+    }
+}
@@ -0,0 +1,332 @@
+use glam::Vec2;
+use std::collections::{HashMap, HashSet};
+use crate::config::{Preset, SimulationConfig};
+use crate::forces::{ForceType, InteractionMatrix, PhysicsConfig, PhysicsEngine};
+use crate::particle::ParticleSystem;
+use crate::presets::PresetManager;
+
+// Everything besides the pair rules that a preset sets for the running simulation
+#[derive(Debug, Clone)]
+pub struct MorphSettings {
+    pub physics: PhysicsConfig,
+    // The config's environment_forces, the only global forces the engine takes from it
+    pub global_forces: Vec<ForceType>,
+    pub bounds: Option<(Vec2, Vec2)>,
+    pub damping: f32,
+}
+
+impl MorphSettings {
+    // What a particle system freshly built for `config` would run with
+    pub fn from_config(config: &SimulationConfig) -> Self {
+        Self {
+            physics: config.physics.clone(),
+            global_forces: config.forces.environment_forces(),
+            bounds: PresetManager::get_spawn_bounds(config),
+            damping: PresetManager::damping(config),
+        }
+    }
+
+    // The running simulation's settings, with `config` for the ones the engine rereads each step
+    pub fn current(config: &SimulationConfig, system: &ParticleSystem) -> Self {
+        Self {
+            bounds: system.bounds,
+            damping: system.damping,
+            ..Self::from_config(config)
+        }
+    }
+
+    // Puts these settings into the engine and system, keeping the engine's step size and the
+    // global forces that don't come from the config
+    pub fn apply(&self, engine: &mut PhysicsEngine, system: &mut ParticleSystem) {
+        let dt = engine.config.dt;
+        engine.config.clone_from(&self.physics);
+        engine.config.dt = dt;
+        let global_forces = &mut engine.force_calculator.global_forces;
+        global_forces.retain(|force| !force.is_environmental() && !matches!(force, ForceType::Brownian { .. }));
+        global_forces.extend(self.global_forces.iter().cloned());
+        system.bounds = self.bounds;
+        system.damping = self.damping;
+    }
+
+    // Scalars are interpolated and forces blended like the pair rules; switches, softening and
+    // the integrator take the target's values from the start
+    fn blend(&self, to: &Self, t: f32) -> Self {
+        let lerp = |a: f32, b: f32| a + (b - a) * t;
+        let physics = PhysicsConfig {
+            max_force: lerp(self.physics.max_force, to.physics.max_force),
+            max_velocity: lerp(self.physics.max_velocity, to.physics.max_velocity),
+            collision_restitution: lerp(self.physics.collision_restitution, to.physics.collision_restitution),
+            spawn_ramp_time: lerp(self.physics.spawn_ramp_time, to.physics.spawn_ramp_time),
+            ..to.physics.clone()
+        };
+        let bounds = match (self.bounds, to.bounds) {
+            (Some((from_min, from_max)), Some((to_min, to_max))) => Some((from_min.lerp(to_min, t), from_max.lerp(to_max, t))),
+            _ => to.bounds,
+        };
+        Self {
+            physics,
+            global_forces: PresetTransition::blend_forces(&self.global_forces, &to.global_forces, t),
+            bounds,
+            damping: lerp(self.damping, to.damping),
+        }
+    }
+}
+
+pub struct PresetTransition {
+    pub target: Preset,
+    pub duration: f32,
+    pub elapsed: f32,
+    from_interactions: HashMap<(u32, u32), Vec<ForceType>>,
+    to_interactions: HashMap<(u32, u32), Vec<ForceType>>,
+    // Blend each direction of a pair on its own, as the target's one-sided rules expect
+    asymmetric: bool,
+    // Physics, environmental forces, bounds and damping to ease between alongside the rules
+    settings: Option<(MorphSettings, MorphSettings)>,
+}
+
+impl PresetTransition {
+    pub fn new(
+        target: Preset,
+        from_interactions: HashMap<(u32, u32), Vec<ForceType>>,
+        to_interactions: HashMap<(u32, u32), Vec<ForceType>>,
+        duration: f32,
+    ) -> Self {
+        Self {
+            target,
+            duration: duration.max(0.0),
+            elapsed: 0.0,
+            from_interactions,
+            to_interactions,
+            asymmetric: false,
+            settings: None,
+        }
+    }
+
+    pub fn with_asymmetric(mut self, asymmetric: bool) -> Self {
+        self.asymmetric = asymmetric;
+        self
+    }
+
+    pub fn with_settings(mut self, from: MorphSettings, to: MorphSettings) -> Self {
+        self.settings = Some((from, to));
+        self
+    }
+
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            1.0
+        } else {
+            (self.elapsed / self.duration).clamp(0.0, 1.0)
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.progress() >= 1.0
+    }
+
+    pub fn advance(&mut self, dt: f32) {
+        self.elapsed += dt;
+    }
+
+    // The settings at this point of the morph, if the transition carries any
+    pub fn current_settings(&self) -> Option<MorphSettings> {
+        self.settings.as_ref().map(|(from, to)| from.blend(to, self.progress()))
+    }
+
+    pub fn target_matrix(&self) -> InteractionMatrix {
+        InteractionMatrix::from_interactions_with_mode(&self.to_interactions, self.asymmetric)
+    }
+
+    pub fn current_matrix(&self) -> InteractionMatrix {
+        let t = self.progress();
+        let mut matrix = InteractionMatrix { asymmetric: self.asymmetric, ..InteractionMatrix::new() };
+        
+        let pairs: HashSet<(u32, u32)> = self.from_interactions
+            .keys()
+            .chain(self.to_interactions.keys())
+            .flat_map(|&(a, b)| {
+                if self.asymmetric {
+                    vec![(a, b), (b, a)]
+                } else {
+                    vec![(a.min(b), a.max(b))]
+                }
+            })
+            .collect();
+        
+        for (species_a, species_b) in pairs {
+            let from = self.forces_for(&self.from_interactions, species_a, species_b);
+            let to = self.forces_for(&self.to_interactions, species_a, species_b);
+            
+            for force in Self::blend_forces(&from, &to, t) {
+                matrix.add_interaction(species_a, species_b, force);
+            }
+        }
+        
+        matrix
+    }
+
+    // One-sided, a direction without its own rules takes its mirror's; otherwise both merge
+    fn forces_for(&self, interactions: &HashMap<(u32, u32), Vec<ForceType>>, a: u32, b: u32) -> Vec<ForceType> {
+        if self.asymmetric {
+            return interactions.get(&(a, b)).or_else(|| interactions.get(&(b, a))).cloned().unwrap_or_default();
+        }
+        let mut forces = interactions.get(&(a, b)).cloned().unwrap_or_default();
+        if a != b {
+            forces.extend(interactions.get(&(b, a)).cloned().unwrap_or_default());
+        }
+        forces
+    }
+
+    // Same-variant forces at the same slot are interpolated; everything else cross-fades
+    fn blend_forces(from: &[ForceType], to: &[ForceType], t: f32) -> Vec<ForceType> {
+        let mut blended = Vec::new();
+        
+        for i in 0..from.len().max(to.len()) {
+            match (from.get(i), to.get(i)) {
+                (Some(a), Some(b)) => match a.lerp(b, t) {
+                    Some(force) => blended.push(force),
+                    None => {
+                        blended.push(a.scaled(1.0 - t));
+                        blended.push(b.scaled(t));
+                    }
+                },
+                (Some(a), None) => blended.push(a.scaled(1.0 - t)),
+                (None, Some(b)) => blended.push(b.scaled(t)),
+                (None, None) => {}
+            }
+        }
+        
+        blended
+    }
+}
+
+// Keeps particle positions and velocities but redistributes species by the new preset's weights
+pub fn reassign_species(system: &mut ParticleSystem, config: &SimulationConfig) {
+    let mut rng = crate::rng::fork();
+    
+    for particle in &mut system.particles {
+        let species_id = PresetManager::pick_species(&config.particles.species_weights, &mut rng);
+        let alpha = particle.color[3];
+        
+        particle.species_id = species_id;
+        particle.color = config.species.color(species_id);
+        particle.color[3] = alpha;
+        particle.charge = PresetManager::sample_charge(config, species_id, &mut rng);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interactions(force: ForceType) -> HashMap<(u32, u32), Vec<ForceType>> {
+        let mut map = HashMap::new();
+        map.insert((0, 1), vec![force]);
+        map
+    }
+
+    #[test]
+    fn test_transition_interpolates_same_variant() {
+        let mut transition = PresetTransition::new(
+            Preset::ParticleLife,
+            interactions(ForceType::Attraction { strength: 10.0, max_distance: 50.0 }),
+            interactions(ForceType::Attraction { strength: 30.0, max_distance: 50.0 }),
+            2.0,
+        );
+        
+        transition.advance(1.0);
+        assert_eq!(transition.progress(), 0.5);
+        
+        let matrix = transition.current_matrix();
+        match matrix.get_forces(0, 1) {
+            [ForceType::Attraction { strength, .. }] => assert_eq!(*strength, 20.0),
+            forces => panic!("unexpected forces: {:?}", forces),
+        }
+    }
+
+    #[test]
+    fn test_transition_crossfades_different_variants() {
+        let mut transition = PresetTransition::new(
+            Preset::ParticleLife,
+            interactions(ForceType::Attraction { strength: 10.0, max_distance: 50.0 }),
+            interactions(ForceType::Repulsion { strength: 20.0, max_distance: 50.0 }),
+            1.0,
+        );
+        
+        transition.advance(2.0);
+        assert!(transition.is_finished());
+        assert_eq!(transition.current_matrix().get_forces(0, 1).len(), 2);
+    }
+
+    #[test]
+    fn test_asymmetric_transition_blends_each_direction() {
+        let mut to = interactions(ForceType::Attraction { strength: 30.0, max_distance: 50.0 });
+        to.insert((1, 0), vec![ForceType::Attraction { strength: 50.0, max_distance: 50.0 }]);
+        let mut transition = PresetTransition::new(
+            Preset::ParticleLife,
+            interactions(ForceType::Attraction { strength: 10.0, max_distance: 50.0 }),
+            to,
+            2.0,
+        ).with_asymmetric(true);
+        
+        transition.advance(1.0);
+        let matrix = transition.current_matrix();
+        match (matrix.get_forces(0, 1), matrix.get_forces(1, 0)) {
+            ([ForceType::Attraction { strength: ab, .. }], [ForceType::Attraction { strength: ba, .. }]) => {
+                assert_eq!(*ab, 20.0);
+                assert_eq!(*ba, 30.0);
+            },
+            forces => panic!("unexpected forces: {:?}", forces),
+        }
+    }
+
+    #[test]
+    fn test_settings_ease_into_the_target_preset() {
+        use crate::config::ConfigManager;
+        
+        let mut manager = ConfigManager::new();
+        manager.apply_preset(Preset::ParticleLife);
+        let mut from = MorphSettings::from_config(manager.config());
+        from.physics.max_velocity = 100.0;
+        from.bounds = Some((Vec2::splat(-100.0), Vec2::splat(100.0)));
+        from.global_forces = vec![ForceType::Brownian { intensity: 10.0 }];
+        let mut to = from.clone();
+        to.physics.max_velocity = 300.0;
+        to.bounds = Some((Vec2::splat(-300.0), Vec2::splat(300.0)));
+        to.global_forces = vec![ForceType::Brownian { intensity: 30.0 }];
+        
+        let mut transition = PresetTransition::new(Preset::Granular, HashMap::new(), HashMap::new(), 2.0).with_settings(from, to);
+        transition.advance(1.0);
+        let settings = transition.current_settings().unwrap();
+        assert_eq!(settings.physics.max_velocity, 200.0);
+        assert_eq!(settings.bounds, Some((Vec2::splat(-200.0), Vec2::splat(200.0))));
+        
+        let mut engine = PhysicsEngine::new(manager.config().physics.clone());
+        let mut system = ParticleSystem::new(10);
+        settings.apply(&mut engine, &mut system);
+        assert_eq!(engine.config.max_velocity, 200.0);
+        assert_eq!(system.bounds, settings.bounds);
+        match engine.force_calculator.global_forces.as_slice() {
+            [ForceType::Brownian { intensity }] => assert_eq!(*intensity, 20.0),
+            forces => panic!("unexpected forces: {:?}", forces),
+        }
+    }
+
+    #[test]
+    fn test_reassign_species_keeps_positions() {
+        use crate::config::ConfigManager;
+        use crate::particle::Particle;
+        use glam::Vec2;
+        
+        let mut manager = ConfigManager::new();
+        manager.apply_preset(Preset::ReactionDiffusion);
+        
+        let mut system = ParticleSystem::new(10);
+        system.add_particle(Particle::new(Vec2::new(3.0, 4.0)).with_species(5));
+        
+        reassign_species(&mut system, manager.config());
+        
+        assert_eq!(system.particles[0].position, Vec2::new(3.0, 4.0));
+        assert!(system.particles[0].species_id <= 1);
+    }
+}
@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+use crate::config::SimulationConfig;
+use crate::forces::ForceType;
+use crate::particle::Particle;
+use crate::zone::{ZoneEffect, ZoneShape};
+
+// Physical constants in SI units
+pub const GRAVITATIONAL_CONSTANT: f64 = 6.674_30e-11;
+pub const COULOMB_CONSTANT: f64 = 8.987_551_8e9;
+
+// How world units map onto physical ones. The simulation itself never reads this: it only
+// converts values for display and derives force strengths for semi-realistic scenes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnitConfig {
+    // Show lengths and speeds in physical units in the UI
+    pub enabled: bool,
+    pub pixels_per_meter: f32,
+    // Physical seconds that pass per simulated second
+    pub time_scale: f32,
+    // Physical mass of one unit of particle mass
+    pub kilograms_per_mass: f32,
+    // Physical charge of one unit of particle charge
+    pub coulombs_per_charge: f32,
+}
+
+impl Default for UnitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            pixels_per_meter: 100.0,
+            time_scale: 1.0,
+            kilograms_per_mass: 1.0,
+            coulombs_per_charge: 1.0,
+        }
+    }
+}
+
+impl UnitConfig {
+    pub fn to_meters(&self, world: f32) -> f32 {
+        world / self.pixels_per_meter
+    }
+
+    pub fn to_world(&self, meters: f32) -> f32 {
+        meters * self.pixels_per_meter
+    }
+
+    // World units per simulated second to metres per physical second
+    pub fn speed_to_si(&self, speed: f32) -> f32 {
+        speed / (self.pixels_per_meter * self.time_scale)
+    }
+
+    // Metres per physical second squared to world units per simulated second squared
+    pub fn acceleration_to_world(&self, acceleration: f32) -> f32 {
+        acceleration * self.pixels_per_meter * self.time_scale * self.time_scale
+    }
+
+    // Strength for ForceType::Gravity that reproduces Newtonian gravity at this scale
+    pub fn gravity_strength(&self) -> f32 {
+        (GRAVITATIONAL_CONSTANT * self.kilograms_per_mass as f64 * self.world_factor()) as f32
+    }
+
+    // Strength for ForceType::ElectroMagnetic that reproduces Coulomb's law at this scale
+    pub fn coulomb_strength(&self) -> f32 {
+        let charge = self.coulombs_per_charge as f64;
+        (COULOMB_CONSTANT * charge * charge / self.kilograms_per_mass as f64 * self.world_factor()) as f32
+    }
+
+    // Converts an inverse-square acceleration constant from m³/s² to world³/step-second²
+    fn world_factor(&self) -> f64 {
+        let scale = self.pixels_per_meter as f64;
+        let time = self.time_scale as f64;
+        scale * scale * scale * time * time
+    }
+
+    pub fn format_length(&self, world: f32) -> String {
+        if !self.enabled {
+            return format!("{:.1}", world);
+        }
+        let meters = self.to_meters(world);
+        match meters.abs() {
+            m if m >= 1000.0 => format!("{:.2} km", meters / 1000.0),
+            m if m >= 1.0 || m == 0.0 => format!("{:.2} m", meters),
+            m if m >= 0.01 => format!("{:.1} cm", meters * 100.0),
+            _ => format!("{:.2} mm", meters * 1000.0),
+        }
+    }
+
+    pub fn format_speed(&self, speed: f32) -> String {
+        if self.enabled {
+            format!("{:.3} m/s", self.speed_to_si(speed))
+        } else {
+            format!("{:.2}", speed)
+        }
+    }
+}
+
+// Sets every gravity and electromagnetic rule to the real constants at this scale. Repulsive or
+// attractive signs on electromagnetic rules are kept.
+pub fn apply_physical_constants(config: &mut SimulationConfig) {
+    let gravity = config.units.gravity_strength();
+    let coulomb = config.units.coulomb_strength();
+    let rules = config.forces.species_interactions.values_mut().flatten();
+    for force in rules.chain(config.forces.global_forces.iter_mut()) {
+        match force {
+            ForceType::Gravity { strength, .. } => *strength = gravity,
+            ForceType::ElectroMagnetic { strength, .. } => *strength = coulomb.copysign(*strength),
+            _ => {},
+        }
+    }
+}
+
+// Stretches a scene's geometry by `factor` while keeping its physical meaning: lengths, forces
+// and bounds scale together and pixels_per_meter follows, so the scene still measures the same
+// in metres and moves the same way.
+pub fn rescale_config(config: &mut SimulationConfig, factor: f32) {
+    let forces = &mut config.forces;
+    for rules in forces.species_interactions.values_mut() {
+        for force in rules.iter_mut() {
+            *force = force.rescaled(factor);
+        }
+    }
+    for force in forces.global_forces.iter_mut() {
+        *force = force.rescaled(factor);
+    }
+    forces.brownian_intensity *= factor;
+    forces.boundary_forces.bounds.0 *= factor;
+    forces.boundary_forces.bounds.1 *= factor;
+    for path in forces.steering.paths.iter_mut() {
+        for point in path.points.iter_mut() {
+            *point *= factor;
+        }
+    }
+    for zone in forces.zones.iter_mut() {
+        zone.shape = match zone.shape {
+            ZoneShape::Circle { center, radius } => ZoneShape::Circle { center: center * factor, radius: radius * factor },
+            ZoneShape::Rect { min, max } => ZoneShape::Rect { min: min * factor, max: max * factor },
+        };
+        zone.effect = match zone.effect {
+            ZoneEffect::GravityWell { strength } => ZoneEffect::GravityWell { strength: strength * factor },
+            ZoneEffect::Temperature { temperature } if temperature > 0.0 => ZoneEffect::Temperature { temperature: temperature * factor },
+            effect => effect,
+        };
+    }
+    for well in forces.wells.iter_mut() {
+        well.position *= factor;
+        well.strength *= factor.powi(3);
+        well.softening *= factor;
+    }
+
+    for species in config.species.species.iter_mut() {
+        species.size *= factor;
+    }
+    config.physics.max_velocity *= factor;
+    config.physics.max_force *= factor;
+    for softening in config.physics.softening.values_mut() {
+        *softening *= factor;
+    }
+    for cutoff in config.performance.fast_math.cutoffs.values_mut() {
+        *cutoff *= factor;
+    }
+    config.performance.streaming.chunk_size *= factor;
+    config.performance.streaming.activation_radius *= factor;
+    config.units.pixels_per_meter *= factor;
+}
+
+// Companion to rescale_config for the particles of a running scene
+pub fn rescale_particles(particles: &mut [Particle], factor: f32) {
+    for particle in particles.iter_mut() {
+        particle.position *= factor;
+        particle.previous_position *= factor;
+        particle.velocity *= factor;
+        particle.acceleration *= factor;
+        particle.size *= factor;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_conversions() {
+        let units = UnitConfig { enabled: true, pixels_per_meter: 50.0, time_scale: 2.0, ..UnitConfig::default() };
+        assert_eq!(units.to_meters(125.0), 2.5);
+        assert_eq!(units.format_length(125.0), "2.50 m");
+        assert_eq!(units.format_length(1.0), "2.0 cm");
+        assert_eq!(units.speed_to_si(100.0), 1.0);
+        // 1 m/s² is 50 px per physical second², and a simulated second lasts two physical ones
+        assert_eq!(units.acceleration_to_world(1.0), 200.0);
+
+        // One kilogram a metre away pulls with G m/s²
+        let pull = units.gravity_strength() / units.to_world(1.0).powi(2);
+        assert!((pull / units.acceleration_to_world(GRAVITATIONAL_CONSTANT as f32) - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_rescale_config_keeps_physical_size() {
+        let mut config = SimulationConfig::default();
+        config.forces.global_forces = vec![ForceType::Repulsion { strength: 10.0, max_distance: 40.0 }];
+        let bounds = config.forces.boundary_forces.bounds;
+        let meters = config.units.to_meters(bounds.1.x);
+
+        rescale_config(&mut config, 3.0);
+        assert_eq!(config.forces.boundary_forces.bounds.1, bounds.1 * 3.0);
+        assert!((config.units.to_meters(config.forces.boundary_forces.bounds.1.x) - meters).abs() < 1e-5);
+        assert!(matches!(config.forces.global_forces[0], ForceType::Repulsion { strength, max_distance } if strength == 30.0 && max_distance == 120.0));
+    }
+}
@@ -1,5 +1,11 @@
 use wasm_bindgen::prelude::*;
-use crate::{App, config::Preset};
+use crate::{App, config::{Background, Preset}, embed::EmbedOptions, presets::ConfigFile};
+use crate::history::RECORDING_EXTENSION;
+use crate::renderer::{backing_size, parallax_shift};
+use crate::scene::SCENE_EXTENSION;
+use wasm_bindgen_futures::JsFuture;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::sync::Mutex;
 
 // Console logging for WASM
@@ -15,17 +21,127 @@ macro_rules! console_log {
 
 // Global state for the WebAssembly version
 static mut GLOBAL_APP: Option<Mutex<App>> = None;
+// Read from the page URL when the simulation starts
+static mut EMBED_OPTIONS: Option<EmbedOptions> = None;
+
+fn embed_options_from_url() -> EmbedOptions {
+    let query = web_sys::window()
+        .and_then(|window| window.location().search().ok())
+        .unwrap_or_default();
+    EmbedOptions::from_query(&query)
+}
+
+thread_local! {
+    // Page callback for crash reports, see set_crash_callback
+    static CRASH_CALLBACK: RefCell<Option<js_sys::Function>> = const { RefCell::new(None) };
+}
+
+// JavaScript's stack at the panic, since std can't walk the WASM stack
+fn js_stack() -> Option<String> {
+    js_sys::Reflect::get(&js_sys::Error::new(""), &JsValue::from_str("stack")).ok()?.as_string()
+}
 
 #[wasm_bindgen(start)]
 pub fn wasm_main() {
+    // The report goes to the console and the page first; console_error_panic_hook then logs the
+    // panic itself as before
     console_error_panic_hook::set_once();
+    crate::crash::install(|report| {
+        let mut report = report.clone();
+        report.backtrace = js_stack().unwrap_or(report.backtrace);
+        let text = report.to_text();
+        web_sys::console::error_1(&JsValue::from_str(&text));
+        CRASH_CALLBACK.with(|callback| {
+            if let Some(callback) = callback.borrow().as_ref() {
+                let _ = callback.call1(&JsValue::NULL, &JsValue::from_str(&text));
+            }
+        });
+        None
+    });
     console_log!("WASM module initialized");
 }
 
+// Calls `callback` with the crash report text if the module panics. The simulation is stopped
+// for good after that, so the page should stop calling in and show the report instead.
+#[wasm_bindgen]
+pub fn set_crash_callback(callback: js_sys::Function) {
+    CRASH_CALLBACK.with(|slot| *slot.borrow_mut() = Some(callback));
+}
+
+// What the browser can render with. wgpu needs WebGPU or WebGL2; without them the page
+// draws with render_canvas_2d instead. WebGL1 is reported for diagnostics only.
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct GraphicsCapabilities {
+    pub webgpu: bool,
+    pub webgl2: bool,
+    pub webgl1: bool,
+    pub canvas2d: bool,
+}
+
+impl GraphicsCapabilities {
+    fn detect() -> Self {
+        let window = web_sys::window();
+        let webgpu = window
+            .as_ref()
+            .map(|window| js_sys::Reflect::has(&window.navigator(), &JsValue::from_str("gpu")).unwrap_or(false))
+            .unwrap_or(false);
+        // A throwaway canvas, since a canvas keeps whichever context type it was first asked for
+        let supports = |context: &str| {
+            window
+                .as_ref()
+                .and_then(|window| window.document())
+                .and_then(|document| document.create_element("canvas").ok())
+                .and_then(|element| element.dyn_into::<web_sys::HtmlCanvasElement>().ok())
+                .and_then(|canvas| canvas.get_context(context).ok().flatten())
+                .is_some()
+        };
+        Self {
+            webgpu,
+            webgl2: supports("webgl2"),
+            webgl1: supports("webgl"),
+            canvas2d: supports("2d"),
+        }
+    }
+
+    pub fn renderer(&self) -> Option<&'static str> {
+        if self.webgpu {
+            Some("webgpu")
+        } else if self.webgl2 {
+            Some("webgl2")
+        } else if self.canvas2d {
+            Some("canvas2d")
+        } else {
+            None
+        }
+    }
+}
+
+// JSON report, e.g. {"webgpu":false,"webgl2":true,"webgl1":true,"canvas2d":true,"renderer":"webgl2"}
+#[wasm_bindgen]
+pub fn get_graphics_capabilities() -> String {
+    let capabilities = GraphicsCapabilities::detect();
+    let mut report = serde_json::to_value(capabilities).unwrap_or_default();
+    report["renderer"] = capabilities.renderer().into();
+    report.to_string()
+}
+
 #[wasm_bindgen]
 pub async fn start_simulation() -> Result<(), JsValue> {
     console_log!("Starting simulation in WASM mode...");
     
+    let capabilities = GraphicsCapabilities::detect();
+    console_log!("Graphics capabilities: {:?}", capabilities);
+    match capabilities.renderer() {
+        Some("canvas2d") => console_log!("Neither WebGPU nor WebGL2 is available; falling back to Canvas2D"),
+        Some(_) => {},
+        None => return Err(JsValue::from_str("This browser supports neither WebGPU, WebGL2 nor a 2D canvas")),
+    }
+    
+    let embed = embed_options_from_url();
+    if !embed.is_empty() {
+        console_log!("Embed options: {:?}", embed);
+    }
+    
     // For now, just create a simple test to verify WASM is working
     // The full nannou integration needs more complex setup
     unsafe {
@@ -36,11 +152,35 @@ pub async fn start_simulation() -> Result<(), JsValue> {
             // Full nannou WASM integration would require proper canvas binding
             console_log!("Application ready (test mode)");
         }
+        if let Some(ref app_mutex) = GLOBAL_APP {
+            if let Ok(mut app) = app_mutex.lock() {
+                if !embed.is_empty() {
+                    app.apply_embed_options(&embed);
+                }
+            }
+        }
+        EMBED_OPTIONS = Some(embed.clone());
+    }
+    
+    // Embeds always show what their URL asks for, so only plain visits restore and save settings
+    if embed.is_empty() {
+        if load_from_browser() {
+            console_log!("Restored settings from the browser");
+        }
+        save_on_page_hide();
     }
+    install_drop_handler();
+    install_resize_observer();
     
     Ok(())
 }
 
+// Lets the host page hide its own controls for ?hideui=1
+#[wasm_bindgen]
+pub fn is_ui_hidden() -> bool {
+    unsafe { EMBED_OPTIONS.as_ref() }.is_some_and(|options| options.hide_ui)
+}
+
 #[wasm_bindgen]
 pub fn reset_simulation() {
     if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
@@ -62,14 +202,9 @@ pub fn toggle_pause() {
 
 #[wasm_bindgen]
 pub fn change_preset(preset_name: &str) {
-    let preset = match preset_name {
-        "ParticleLife" => Preset::ParticleLife,
-        "Flocking" => Preset::Flocking,
-        "Gravity" => Preset::Gravity,
-        "Electromagnetic" => Preset::Electromagnetic,
-        "Brownian" => Preset::Brownian,
-        "ReactionDiffusion" => Preset::ReactionDiffusion,
-        _ => return,
+    let preset = match Preset::from_key(preset_name) {
+        Some(preset) => preset,
+        None => return,
     };
     
     if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
@@ -79,6 +214,24 @@ pub fn change_preset(preset_name: &str) {
     }
 }
 
+#[wasm_bindgen]
+pub fn set_time_scale(time_scale: f32) {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            app.set_time_scale(time_scale);
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn step_simulation() {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            app.step_forward();
+        }
+    }
+}
+
 #[wasm_bindgen]
 pub fn get_particle_count() -> usize {
     if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
@@ -153,16 +306,206 @@ pub fn add_particle(x: f32, y: f32, species_id: u32) {
             use crate::particle::Particle;
             use glam::Vec2;
             
-            let particle = Particle::new(Vec2::new(x, y))
+            let species = &app.config_manager.config().species;
+            let current_count = app.particle_system.iter_species(species_id).count();
+            
+            if !species.can_spawn(species_id, current_count) {
+                return;
+            }
+            
+            let mut particle = Particle::new(Vec2::new(x, y))
                 .with_species(species_id)
                 .with_size(2.0 + (species_id as f32 * 0.5))
-                .with_color(crate::presets::PresetManager::get_species_color(species_id));
+                .with_color(species.color(species_id));
+            
+            if let Some(definition) = species.get(species_id) {
+                particle = particle
+                    .with_mass(definition.mass)
+                    .with_size(definition.size)
+                    .with_charge(definition.charge);
+            }
             
             app.particle_system.add_particle(particle);
         }
     }
 }
 
+// Pixels are RGBA8, e.g. from CanvasRenderingContext2D.getImageData
+#[wasm_bindgen]
+pub fn import_image(pixels: &[u8], width: u32, height: u32, threshold: f32, replace: bool) -> usize {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            use crate::image_import::{particles_from_rgba, ImageImportOptions};
+            
+            let config = app.config_manager.config();
+            let options = ImageImportOptions {
+                threshold,
+                center: app.renderer.camera_state().0,
+                max_particles: config.particles.max_particles,
+                ..ImageImportOptions::default()
+            };
+            let particles = particles_from_rgba(pixels, width, height, &options, &config.species);
+            let count = particles.len();
+            app.import_particles(particles, replace);
+            return count;
+        }
+    }
+    0
+}
+
+// Stamps particles wherever the RGBA8 mask is opaque, e.g. text drawn with fillText on a canvas.
+// `mode` is "free", "disperse", "hold" or "rigid".
+#[wasm_bindgen]
+pub fn stamp_mask(pixels: &[u8], width: usize, height: usize, spacing: f32, species_id: u32, mode: &str, strength: f32) -> usize {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            use crate::stamp::{CoverageMask, StampMode, StampOptions};
+            
+            let mode = match mode {
+                "disperse" => StampMode::Disperse,
+                "hold" => StampMode::Hold,
+                "rigid" => StampMode::Rigid,
+                _ => StampMode::Free,
+            };
+            let center = app.renderer.camera_state().0;
+            let positions = CoverageMask::from_rgba_alpha(pixels, width, height).positions(center, spacing);
+            return app.stamp(positions, center, &StampOptions { species_id, mode, strength });
+        }
+    }
+    0
+}
+
+#[wasm_bindgen]
+pub fn stamp_shape(shape: &str, x: f32, y: f32, size: f32, density: f32, species_id: u32) -> usize {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            use crate::brush::{BrushShape, ShapeSampler};
+            use crate::stamp::{StampMode, StampOptions};
+            
+            let Some(shape) = BrushShape::all(size).into_iter().find(|s| s.name().eq_ignore_ascii_case(shape)) else {
+                return 0;
+            };
+            let center = glam::Vec2::new(x, y);
+            let positions = ShapeSampler::new(shape, center).sample_density(density, &mut rand::thread_rng());
+            return app.stamp(positions, center, &StampOptions { species_id, mode: StampMode::Free, strength: 0.0 });
+        }
+    }
+    0
+}
+
+#[wasm_bindgen]
+pub fn add_species(name: &str) -> i32 {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            return app.add_species(name) as i32;
+        }
+    }
+    -1
+}
+
+#[wasm_bindgen]
+pub fn remove_species(species_id: u32) -> bool {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            return app.remove_species(species_id);
+        }
+    }
+    false
+}
+
+// [slot, generation] of the particle at `index`, the handle the despawn calls take, or empty
+#[wasm_bindgen]
+pub fn get_particle_id(index: usize) -> Vec<u32> {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(app) = app_mutex.lock() {
+            if let Some(particle) = app.particle_system.particles.get(index) {
+                return vec![particle.id.slot, particle.id.generation];
+            }
+        }
+    }
+    Vec::new()
+}
+
+#[wasm_bindgen]
+pub fn remove_particle(slot: u32, generation: u32) -> bool {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            let id = crate::pool::ParticleId { slot, generation };
+            return app.particle_system.remove_particle(id).is_some();
+        }
+    }
+    false
+}
+
+// Removes every particle within `radius` of (x, y), in world units
+#[wasm_bindgen]
+pub fn despawn_in_radius(x: f32, y: f32, radius: f32) -> usize {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            return app.particle_system.despawn_in_radius(glam::Vec2::new(x, y), radius);
+        }
+    }
+    0
+}
+
+// Removes a species' particles but keeps the species, unlike remove_species
+#[wasm_bindgen]
+pub fn kill_species(species_id: u32) -> usize {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            return app.particle_system.kill_species(species_id);
+        }
+    }
+    0
+}
+
+// Calls `callback` with each particle event as a JSON string, e.g.
+// {"Despawned":{"id":{"slot":3,"generation":1},"species_id":0,"position":[1.0,2.0],"cause":"Radius"}},
+// once per frame after the step they happened in
+#[wasm_bindgen]
+pub fn set_particle_event_callback(callback: js_sys::Function) {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            app.particle_listeners.clear();
+            app.on_particle_event(move |event| {
+                if let Ok(json) = serde_json::to_string(event) {
+                    let _ = callback.call1(&JsValue::NULL, &JsValue::from_str(&json));
+                }
+            });
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn get_species_info() -> String {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(app) = app_mutex.lock() {
+            return serde_json::to_string(&app.config_manager.config().species)
+                .unwrap_or_else(|_| "{}".to_string());
+        }
+    }
+    "{}".to_string()
+}
+
+#[wasm_bindgen]
+pub fn push_audio_samples(samples: &[f32], sample_rate: f32) {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            let app = &mut *app;
+            app.audio.push_samples(samples, sample_rate, &app.config_manager.config().audio);
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn set_audio_reactive(enabled: bool) {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            app.config_manager.config_mut().audio.enabled = enabled;
+        }
+    }
+}
+
 #[wasm_bindgen]
 pub fn get_particles() -> Vec<f32> {
     if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
@@ -261,6 +604,17 @@ pub fn set_spawn_rate(rate: f32) {
     }
 }
 
+#[wasm_bindgen]
+pub fn burst(count: usize) -> usize {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            let area = app.config_manager.config().particles.spawn_area.clone();
+            return app.burst(count, &area);
+        }
+    }
+    0
+}
+
 #[wasm_bindgen]
 pub fn enable_trails(enable: bool) {
     if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
@@ -289,6 +643,147 @@ pub fn set_background_color(r: f32, g: f32, b: f32, a: f32) {
     }
 }
 
+thread_local! {
+    // CSS colour strings by RGBA, built once instead of formatted every frame
+    static CSS_COLORS: RefCell<HashMap<[u8; 4], JsValue>> = RefCell::new(HashMap::new());
+}
+
+fn to_rgba8(color: [f32; 4]) -> [u8; 4] {
+    color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+}
+
+fn css_color(color: [u8; 4]) -> JsValue {
+    CSS_COLORS.with(|cache| {
+        cache
+            .borrow_mut()
+            .entry(color)
+            .or_insert_with(|| {
+                let [r, g, b, a] = color;
+                JsValue::from_str(&format!("rgba({},{},{},{:.3})", r, g, b, a as f32 / 255.0))
+            })
+            .clone()
+    })
+}
+
+// Canvas2D fallback: one Path2D and one fill per colour group rather than per particle
+#[wasm_bindgen]
+pub fn render_canvas_2d(context: &web_sys::CanvasRenderingContext2d) {
+    let Some(app_mutex) = (unsafe { GLOBAL_APP.as_ref() }) else {
+        return;
+    };
+    let Ok(mut app) = app_mutex.lock() else {
+        return;
+    };
+    let Some(canvas) = context.canvas() else {
+        return;
+    };
+
+    // Batches are in CSS pixels; scale up to the devicePixelRatio-sized backing store
+    let (width, height) = (canvas.client_width().max(1) as f64, canvas.client_height().max(1) as f64);
+    let ratio = canvas.width() as f64 / width;
+    let _ = context.set_transform(ratio, 0.0, 0.0, ratio, 0.0, 0.0);
+
+    let rendering = &app.config_manager.config().rendering;
+    context.set_fill_style(&css_color(to_rgba8(rendering.background_color)));
+    context.fill_rect(0.0, 0.0, width, height);
+    // Images need the GPU renderer; the Canvas2D fallback shows them as the solid colour
+    let shift = parallax_shift(app.renderer.camera.position, app.renderer.camera.zoom, rendering.background_parallax);
+    let gradient = match &rendering.background {
+        Background::VerticalGradient { top, bottom } => Some((context.create_linear_gradient(0.0, 0.0, 0.0, height), *top, *bottom)),
+        Background::RadialGradient { inner, outer } => {
+            // Canvas y points down, so the vertical shift flips
+            let (x, y) = (width / 2.0 + shift.x as f64, height / 2.0 - shift.y as f64);
+            let radius = (width * width + height * height).sqrt() / 2.0 + shift.length() as f64;
+            context.create_radial_gradient(x, y, 0.0, x, y, radius).ok().map(|g| (g, *inner, *outer))
+        },
+        _ => None,
+    };
+    if let Some((gradient, start, end)) = gradient {
+        let _ = gradient.add_color_stop(0.0, &css_color(to_rgba8(start)).as_string().unwrap_or_default());
+        let _ = gradient.add_color_stop(1.0, &css_color(to_rgba8(end)).as_string().unwrap_or_default());
+        context.set_fill_style(&gradient);
+        context.fill_rect(0.0, 0.0, width, height);
+    }
+
+    // "lighter" is Canvas2D's additive blending
+    let additive = app.config_manager.config().rendering.is_additive();
+    let _ = context.set_global_composite_operation(if additive { "lighter" } else { "source-over" });
+    for batch in app.renderer.canvas_batches(&app.particle_system) {
+        let Ok(path) = web_sys::Path2d::new() else {
+            continue;
+        };
+        for (x, y, radius) in batch.circles {
+            let (x, y, radius) = (x as f64, y as f64, radius as f64);
+            path.move_to(x + radius, y);
+            let _ = path.arc(x, y, radius, 0.0, std::f64::consts::TAU);
+        }
+        context.set_fill_style(&css_color(batch.color));
+        context.fill_with_path_2d(&path);
+    }
+    let _ = context.set_global_composite_operation("source-over");
+    if let Some(((x0, y0), (x1, y1))) = app.renderer.canvas_grab() {
+        context.set_stroke_style(&JsValue::from_str("rgba(255,217,77,0.9)"));
+        context.set_line_width(1.5);
+        context.begin_path();
+        context.move_to(x0 as f64, y0 as f64);
+        context.line_to(x1 as f64, y1 as f64);
+        context.stroke();
+    }
+    app.picking.invalidate();
+}
+
+// Index of the particle under (x, y) in CSS pixels from the canvas's top-left, or -1
+#[wasm_bindgen]
+pub fn pick_particle(x: f32, y: f32) -> i32 {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            return app.pick_particle(glam::Vec2::new(x, y)).map_or(-1, |index| index as i32);
+        }
+    }
+    -1
+}
+
+// Hooks the particle under or nearest (x, y), in CSS pixels from the canvas's top-left, onto a
+// spring to the pointer; returns its index, or -1 if nothing is in reach
+#[wasm_bindgen]
+pub fn grab_particle(x: f32, y: f32) -> i32 {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            return app.start_grab(glam::Vec2::new(x, y)).map_or(-1, |index| index as i32);
+        }
+    }
+    -1
+}
+
+// Moves the pointer end of the grab spring, in CSS pixels
+#[wasm_bindgen]
+pub fn move_grab(x: f32, y: f32) {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            app.move_grab(glam::Vec2::new(x, y));
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn release_grab() {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            app.release_grab();
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn set_grab_spring(stiffness: f32, damping: f32) {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            app.ui_state.grab_options.stiffness = stiffness.max(0.0);
+            app.ui_state.grab_options.damping = damping.max(0.0);
+        }
+    }
+}
+
 // Performance monitoring functions
 #[wasm_bindgen]
 pub fn get_performance_stats() -> Vec<f32> {
@@ -300,30 +795,31 @@ pub fn get_performance_stats() -> Vec<f32> {
                 app.performance_stats.update_time_ms,
                 app.performance_stats.render_time_ms,
                 app.performance_stats.particle_count as f32,
+                app.performance_stats.physics_time_ms,
+                app.performance_stats.spatial_time_ms,
+                app.performance_stats.trail_time_ms,
+                app.performance_stats.active_forces as f32,
+                app.performance_stats.candidate_pairs as f32,
+                app.performance_stats.spatial_queries as f32,
             ]
         } else {
-            vec![0.0; 5]
+            vec![0.0; 11]
         }
     } else {
-        vec![0.0; 5]
+        vec![0.0; 11]
     }
 }
 
 // Configuration export/import
+fn current_config_json() -> Option<String> {
+    let app_mutex = unsafe { GLOBAL_APP.as_ref() }?;
+    let app = app_mutex.lock().ok()?;
+    serde_json::to_string_pretty(app.config_manager.config()).ok()
+}
+
 #[wasm_bindgen]
 pub fn export_config() -> String {
-    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
-        if let Ok(app) = app_mutex.lock() {
-            match serde_json::to_string_pretty(app.config_manager.config()) {
-                Ok(json) => json,
-                Err(_) => "{}".to_string(),
-            }
-        } else {
-            "{}".to_string()
-        }
-    } else {
-        "{}".to_string()
-    }
+    current_config_json().unwrap_or_else(|| "{}".to_string())
 }
 
 #[wasm_bindgen]
@@ -346,6 +842,247 @@ pub fn import_config(config_json: &str) -> bool {
     }
 }
 
+// Rule codes: the current pair rules and species colours as a short shareable string
+#[wasm_bindgen]
+pub fn export_rule_code() -> String {
+    unsafe { GLOBAL_APP.as_ref() }
+        .and_then(|app_mutex| app_mutex.lock().ok().map(|app| app.rule_code()))
+        .unwrap_or_default()
+}
+
+// Switches to Particle Life with the code's rules, returning its species count
+#[wasm_bindgen]
+pub fn import_rule_code(code: &str) -> Result<usize, JsValue> {
+    let app_mutex = unsafe { GLOBAL_APP.as_ref() }.ok_or_else(|| JsValue::from_str("Simulation not initialized"))?;
+    let mut app = app_mutex.lock().map_err(|_| JsValue::from_str("Simulation not available"))?;
+    app.load_rule_code(code).map_err(|e| JsValue::from_str(&e))
+}
+
+// Browser persistence: the exported config JSON is kept in localStorage under this key
+const STORAGE_KEY: &str = "inochi.config";
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[wasm_bindgen]
+pub fn save_to_browser() -> bool {
+    match (local_storage(), current_config_json()) {
+        (Some(storage), Some(json)) => storage.set_item(STORAGE_KEY, &json).is_ok(),
+        _ => false,
+    }
+}
+
+// False when nothing is saved or the saved config no longer parses
+#[wasm_bindgen]
+pub fn load_from_browser() -> bool {
+    match local_storage().and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten()) {
+        Some(json) => import_config(&json),
+        None => false,
+    }
+}
+
+// Forgets the saved config and returns to the defaults
+#[wasm_bindgen]
+pub fn reset_settings() {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(STORAGE_KEY);
+    }
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            app.reset_settings();
+        }
+    }
+}
+
+// Saves when the tab is closed or reloaded; pagehide also fires where beforeunload doesn't (mobile)
+fn save_on_page_hide() {
+    if let Some(window) = web_sys::window() {
+        let handler = Closure::<dyn FnMut()>::new(|| {
+            save_to_browser();
+        });
+        let _ = window.add_event_listener_with_callback("pagehide", handler.as_ref().unchecked_ref());
+        handler.forget();
+    }
+}
+
+const CANVAS_ID: &str = "nannou-canvas";
+
+fn canvas_element() -> Option<web_sys::HtmlCanvasElement> {
+    web_sys::window()?
+        .document()?
+        .get_element_by_id(CANVAS_ID)?
+        .dyn_into()
+        .ok()
+}
+
+// Matches the canvas backing store to its displayed size times devicePixelRatio for crisp
+// output, and the renderer's world mapping to the displayed size in CSS pixels
+fn resize_canvas(canvas: &web_sys::HtmlCanvasElement) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let (width, height) = (canvas.client_width().max(1) as u32, canvas.client_height().max(1) as u32);
+    let (pixel_width, pixel_height) = backing_size(width as f32, height as f32, window.device_pixel_ratio() as f32);
+    if canvas.width() != pixel_width || canvas.height() != pixel_height {
+        canvas.set_width(pixel_width);
+        canvas.set_height(pixel_height);
+    }
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            app.resize_viewport(width, height);
+        }
+    }
+}
+
+// ResizeObserver covers layout changes; window resize also catches devicePixelRatio changes from browser zoom
+fn install_resize_observer() {
+    let Some(canvas) = canvas_element() else {
+        return;
+    };
+    resize_canvas(&canvas);
+
+    let observed = canvas.clone();
+    let on_resize = Closure::<dyn FnMut()>::new(move || resize_canvas(&observed));
+    if let Ok(observer) = web_sys::ResizeObserver::new(on_resize.as_ref().unchecked_ref()) {
+        observer.observe(&canvas);
+    }
+    if let Some(window) = web_sys::window() {
+        let _ = window.add_event_listener_with_callback("resize", on_resize.as_ref().unchecked_ref());
+    }
+    on_resize.forget();
+}
+
+// Drag-and-drop of .json config or custom preset files onto the canvas
+const TOAST_DURATION_MS: i32 = 4000;
+
+// Parses and applies a config or custom preset, returning what was loaded
+#[wasm_bindgen]
+pub fn load_config_file(json: &str) -> Result<String, JsValue> {
+    let file = ConfigFile::parse(json).map_err(|e| JsValue::from_str(&e))?;
+    let description = match &file {
+        ConfigFile::Preset(data) => format!("preset \"{}\"", data.name),
+        ConfigFile::Config(_) => "config".to_string(),
+    };
+    let app_mutex = unsafe { GLOBAL_APP.as_ref() }.ok_or_else(|| JsValue::from_str("Simulation not initialized"))?;
+    let mut app = app_mutex.lock().map_err(|_| JsValue::from_str("Simulation not available"))?;
+    app.apply_config_file(file);
+    Ok(description)
+}
+
+// Styled by the page's .toast and .toast.error rules
+fn show_toast(message: &str, error: bool) {
+    console_log!("{}", message);
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        return;
+    };
+    let (Ok(toast), Some(body)) = (document.create_element("div"), document.body()) else {
+        return;
+    };
+    toast.set_class_name(if error { "toast error" } else { "toast" });
+    toast.set_text_content(Some(message));
+    let _ = body.append_child(&toast);
+
+    let remove = Closure::once_into_js(move || toast.remove());
+    if let Some(window) = web_sys::window() {
+        let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(remove.unchecked_ref(), TOAST_DURATION_MS);
+    }
+}
+
+async fn load_dropped_file(file: web_sys::File) {
+    let name = file.name();
+    if !name.to_lowercase().ends_with(".json") {
+        show_toast(&format!("{}: only .json config or preset files can be loaded", name), true);
+        return;
+    }
+    let Some(text) = JsFuture::from(file.text()).await.ok().and_then(|text| text.as_string()) else {
+        show_toast(&format!("Could not read {}", name), true);
+        return;
+    };
+    match load_config_file(&text) {
+        Ok(description) => show_toast(&format!("Loaded {} from {}", description, name), false),
+        Err(e) => show_toast(&format!("{}: {}", name, e.as_string().unwrap_or_default()), true),
+    }
+}
+
+fn install_drop_handler() {
+    let Some(canvas) = canvas_element() else {
+        return;
+    };
+
+    // The browser only fires drop on elements that cancel dragover
+    let drag_over = Closure::<dyn FnMut(web_sys::DragEvent)>::new(|event: web_sys::DragEvent| {
+        event.prevent_default();
+    });
+    let drop = Closure::<dyn FnMut(web_sys::DragEvent)>::new(|event: web_sys::DragEvent| {
+        event.prevent_default();
+        let file = event
+            .data_transfer()
+            .and_then(|transfer| transfer.files())
+            .and_then(|files| files.get(0));
+        if let Some(file) = file {
+            wasm_bindgen_futures::spawn_local(load_dropped_file(file));
+        }
+    });
+    let _ = canvas.add_event_listener_with_callback("dragover", drag_over.as_ref().unchecked_ref());
+    let _ = canvas.add_event_listener_with_callback("drop", drop.as_ref().unchecked_ref());
+    drag_over.forget();
+    drop.forget();
+}
+
+// Downloads for resuming a browser session on desktop or sharing it
+fn download(bytes: &[u8], mime_type: &str, filename: &str) -> Result<(), JsValue> {
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let mut options = web_sys::BlobPropertyBag::new();
+    options.type_(mime_type);
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| JsValue::from_str("No document"))?;
+    let link: web_sys::HtmlAnchorElement = document.create_element("a")?.dyn_into()?;
+    link.set_href(&url);
+    link.set_download(filename);
+    link.click();
+    web_sys::Url::revoke_object_url(&url)
+}
+
+// The full current state as a .inochi scene, which the desktop app opens directly
+#[wasm_bindgen]
+pub fn export_snapshot() -> Result<(), JsValue> {
+    let app_mutex = unsafe { GLOBAL_APP.as_ref() }.ok_or_else(|| JsValue::from_str("Simulation not initialized"))?;
+    let json = {
+        let app = app_mutex.lock().map_err(|_| JsValue::from_str("Simulation not available"))?;
+        let scene = app.capture_scene("Browser Session");
+        serde_json::to_string_pretty(&scene).map_err(|e| JsValue::from_str(&e.to_string()))?
+    };
+    download(json.as_bytes(), "application/json", &format!("inochi-snapshot.{}", SCENE_EXTENSION))
+}
+
+// The buffered history as a compressed binary recording; needs history recording enabled
+#[wasm_bindgen]
+pub fn export_recording() -> Result<(), JsValue> {
+    let app_mutex = unsafe { GLOBAL_APP.as_ref() }.ok_or_else(|| JsValue::from_str("Simulation not initialized"))?;
+    let bytes = {
+        let app = app_mutex.lock().map_err(|_| JsValue::from_str("Simulation not available"))?;
+        if app.history.is_empty() {
+            return Err(JsValue::from_str("Nothing recorded yet; enable history recording first"));
+        }
+        app.history.recording(app.config_manager.config()).to_bytes().map_err(|e| JsValue::from_str(&e))?
+    };
+    download(&bytes, "application/octet-stream", &format!("inochi-recording.{}", RECORDING_EXTENSION))
+}
+
+#[wasm_bindgen]
+pub fn set_history_recording(enabled: bool) {
+    if let Some(ref app_mutex) = unsafe { &GLOBAL_APP } {
+        if let Ok(mut app) = app_mutex.lock() {
+            app.config_manager.config_mut().history.enabled = enabled;
+        }
+    }
+}
+
 // Utility functions for debugging
 #[wasm_bindgen]
 pub fn log_particle_info(index: usize) {
@@ -0,0 +1,86 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use crate::particle::Particle;
+
+// A fixed point mass that pulls on every particle, placed from the UI rather than simulated
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GravityWell {
+    pub enabled: bool,
+    pub position: Vec2,
+    // G·M folded into one number; negative wells repel
+    pub strength: f32,
+    // Plummer softening length, keeps the pull finite for particles passing through the centre
+    pub softening: f32,
+}
+
+impl GravityWell {
+    pub fn new(position: Vec2) -> Self {
+        Self {
+            enabled: true,
+            position,
+            strength: 50_000.0,
+            softening: 10.0,
+        }
+    }
+
+    // Acceleration felt at `position`; independent of the particle's own mass
+    pub fn acceleration_at(&self, position: Vec2) -> Vec2 {
+        let offset = self.position - position;
+        let distance_sq = offset.length_squared() + self.softening * self.softening;
+        if distance_sq <= f32::EPSILON {
+            return Vec2::ZERO;
+        }
+        offset * (self.strength / (distance_sq * distance_sq.sqrt()))
+    }
+
+    // Radius of the marker ring, in world units; grows slowly with strength so heavy wells stand out
+    pub fn marker_radius(&self) -> f32 {
+        self.softening.max(4.0) + self.strength.abs().sqrt() * 0.05
+    }
+}
+
+pub fn apply_wells(wells: &[GravityWell], particles: &mut [Particle]) {
+    for well in wells.iter().filter(|well| well.enabled) {
+        for particle in particles.iter_mut() {
+            particle.acceleration += well.acceleration_at(particle.position);
+        }
+    }
+}
+
+// Index of the well whose marker is under `position`, nearest first
+pub fn well_at(wells: &[GravityWell], position: Vec2) -> Option<usize> {
+    wells
+        .iter()
+        .enumerate()
+        .filter(|(_, well)| well.position.distance(position) <= well.marker_radius())
+        .min_by(|(_, a), (_, b)| a.position.distance_squared(position).total_cmp(&b.position.distance_squared(position)))
+        .map(|(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wells_pull_with_inverse_square() {
+        let mut well = GravityWell::new(Vec2::ZERO);
+        well.strength = 1000.0;
+        well.softening = 0.0;
+        assert_eq!(well.acceleration_at(Vec2::new(10.0, 0.0)), Vec2::new(-10.0, 0.0));
+        assert_eq!(well.acceleration_at(Vec2::new(0.0, -20.0)), Vec2::new(0.0, 2.5));
+        assert_eq!(well.acceleration_at(Vec2::ZERO), Vec2::ZERO);
+
+        let mut particles = vec![Particle::new(Vec2::new(10.0, 0.0))];
+        let disabled = GravityWell { enabled: false, ..well };
+        apply_wells(&[well, disabled], &mut particles);
+        assert_eq!(particles[0].acceleration, Vec2::new(-10.0, 0.0));
+    }
+
+    #[test]
+    fn test_well_picking_prefers_nearest() {
+        let wells = vec![GravityWell::new(Vec2::ZERO), GravityWell::new(Vec2::new(12.0, 0.0))];
+        assert_eq!(well_at(&wells, Vec2::new(2.0, 0.0)), Some(0));
+        assert_eq!(well_at(&wells, Vec2::new(9.0, 0.0)), Some(1));
+        assert_eq!(well_at(&wells, Vec2::new(0.0, 500.0)), None);
+    }
+}
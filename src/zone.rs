@@ -0,0 +1,183 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use crate::particle::Particle;
+use crate::rng;
+use crate::spatial::SpatialPartitioning;
+
+// Random acceleration per unit of temperature in a heated zone
+const THERMAL_ACCELERATION: f32 = 50.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ZoneShape {
+    Circle { center: Vec2, radius: f32 },
+    Rect { min: Vec2, max: Vec2 },
+}
+
+// Effects are accelerations, so they work the same under every integration method
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ZoneEffect {
+    // Extra drag, roughly the fraction of velocity lost per second
+    Damping { amount: f32 },
+    // Pull toward the zone's centre; negative pushes particles out
+    GravityWell { strength: f32 },
+    // Positive heats with random kicks, negative cools by draining speed
+    Temperature { temperature: f32 },
+    // Scales the net force particles feel from everything else
+    ForceMultiplier { factor: f32 },
+    // Energy per second for organisms inside, when metabolism is on; no force
+    Food { rate: f32 },
+}
+
+// A region of the world with its own local physics
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Zone {
+    pub name: String,
+    pub enabled: bool,
+    pub shape: ZoneShape,
+    pub effect: ZoneEffect,
+}
+
+impl ZoneShape {
+    pub fn contains(&self, position: Vec2) -> bool {
+        match *self {
+            ZoneShape::Circle { center, radius } => position.distance_squared(center) <= radius * radius,
+            ZoneShape::Rect { min, max } => position.cmpge(min).all() && position.cmple(max).all(),
+        }
+    }
+
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        match *self {
+            ZoneShape::Circle { center, radius } => (center - Vec2::splat(radius), center + Vec2::splat(radius)),
+            ZoneShape::Rect { min, max } => (min, max),
+        }
+    }
+
+    pub fn center(&self) -> Vec2 {
+        let (min, max) = self.bounds();
+        (min + max) * 0.5
+    }
+}
+
+impl ZoneEffect {
+    pub fn all() -> Vec<ZoneEffect> {
+        vec![
+            ZoneEffect::Damping { amount: 2.0 },
+            ZoneEffect::GravityWell { strength: 100.0 },
+            ZoneEffect::Temperature { temperature: 1.0 },
+            ZoneEffect::ForceMultiplier { factor: 2.0 },
+            ZoneEffect::Food { rate: 2.0 },
+        ]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            ZoneEffect::Damping { .. } => "Damping",
+            ZoneEffect::GravityWell { .. } => "Gravity Well",
+            ZoneEffect::Temperature { .. } => "Temperature",
+            ZoneEffect::ForceMultiplier { .. } => "Force Multiplier",
+            ZoneEffect::Food { .. } => "Food",
+        }
+    }
+
+    // Overlay tint: grey for drag, violet for wells, red/blue for hot/cold, green for multipliers,
+    // yellow for food
+    pub fn color(&self) -> [f32; 3] {
+        match *self {
+            ZoneEffect::Damping { .. } => [0.6, 0.6, 0.7],
+            ZoneEffect::GravityWell { .. } => [0.6, 0.3, 0.9],
+            ZoneEffect::Temperature { temperature } if temperature >= 0.0 => [1.0, 0.35, 0.2],
+            ZoneEffect::Temperature { .. } => [0.25, 0.55, 1.0],
+            ZoneEffect::ForceMultiplier { .. } => [0.3, 0.9, 0.4],
+            ZoneEffect::Food { .. } => [0.95, 0.85, 0.2],
+        }
+    }
+}
+
+impl Zone {
+    pub fn new(name: impl Into<String>, shape: ZoneShape, effect: ZoneEffect) -> Self {
+        Self {
+            name: name.into(),
+            enabled: true,
+            shape,
+            effect,
+        }
+    }
+
+    // Adjusts the acceleration of a particle inside the zone
+    pub fn apply(&self, particle: &mut Particle) {
+        match self.effect {
+            ZoneEffect::Damping { amount } => {
+                particle.acceleration -= particle.velocity * amount;
+            },
+            ZoneEffect::GravityWell { strength } => {
+                particle.acceleration += (self.shape.center() - particle.position).normalize_or_zero() * strength;
+            },
+            ZoneEffect::Temperature { temperature } if temperature >= 0.0 => {
+                let kick = Vec2::new(rng::random::<f32>() - 0.5, rng::random::<f32>() - 0.5) * 2.0;
+                particle.acceleration += kick * temperature * THERMAL_ACCELERATION;
+            },
+            ZoneEffect::Temperature { temperature } => {
+                particle.acceleration += particle.velocity * temperature;
+            },
+            ZoneEffect::ForceMultiplier { factor } => {
+                particle.acceleration *= factor;
+            },
+            // Handled by MetabolismConfig::feed
+            ZoneEffect::Food { .. } => {},
+        }
+    }
+}
+
+// Applies each enabled zone to the particles inside it, in order. With a spatial index only the
+// particles in the zone's bounding box are visited; the index must match `particles`.
+pub fn apply_zones(zones: &[Zone], particles: &mut [Particle], spatial: Option<&SpatialPartitioning>) {
+    for zone in zones.iter().filter(|zone| zone.enabled) {
+        match spatial {
+            Some(spatial) => {
+                for index in spatial.query_range(zone.shape.bounds()) {
+                    if let Some(particle) = particles.get_mut(index) {
+                        if zone.shape.contains(particle.position) {
+                            zone.apply(particle);
+                        }
+                    }
+                }
+            },
+            None => {
+                for particle in particles.iter_mut().filter(|p| zone.shape.contains(p.position)) {
+                    zone.apply(particle);
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zones_only_affect_particles_inside() {
+        let zones = vec![
+            Zone::new("well", ZoneShape::Circle { center: Vec2::ZERO, radius: 50.0 }, ZoneEffect::GravityWell { strength: 10.0 }),
+            Zone::new("drag", ZoneShape::Rect { min: Vec2::new(100.0, -10.0), max: Vec2::new(200.0, 10.0) }, ZoneEffect::Damping { amount: 0.5 }),
+        ];
+        let particles = vec![
+            Particle::new(Vec2::new(20.0, 0.0)),
+            Particle::new(Vec2::new(150.0, 0.0)).with_velocity(Vec2::new(4.0, 0.0)),
+            Particle::new(Vec2::new(-300.0, 0.0)).with_velocity(Vec2::new(4.0, 0.0)),
+        ];
+
+        let mut spatial = SpatialPartitioning::new_grid(25.0, (Vec2::splat(-500.0), Vec2::splat(500.0)));
+        spatial.update(&particles);
+        let mut indexed = particles.clone();
+        apply_zones(&zones, &mut indexed, Some(&spatial));
+        let mut scanned = particles;
+        apply_zones(&zones, &mut scanned, None);
+
+        for particles in [indexed, scanned] {
+            assert_eq!(particles[0].acceleration, Vec2::new(-10.0, 0.0));
+            assert_eq!(particles[1].acceleration, Vec2::new(-2.0, 0.0));
+            assert_eq!(particles[2].acceleration, Vec2::ZERO);
+        }
+    }
+}
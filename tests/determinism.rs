@@ -0,0 +1,121 @@
+// Runs every preset from a fixed seed and compares a hash of the final particle state against
+// recorded golden values, catching accidental nondeterminism and unintended physics changes.
+//
+// Goldens live in tests/golden/determinism.txt and are only written when blessing. After an
+// intended physics change or a new preset, re-record with
+//   INOCHI_BLESS=1 cargo test --test determinism
+// State is rounded to QUANTUM before hashing, so last-bit differences between platforms, libms
+// and fused multiply-adds don't change the hash.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use inochi::checkpoint::HeadlessRun;
+use inochi::config::{ConfigManager, Preset};
+
+const SEED: u64 = 0x1_0C41;
+const STEPS: usize = 120;
+const PARTICLE_CAP: usize = 100;
+// Grid positions and velocities are rounded to before hashing
+const QUANTUM: f32 = 1e-3;
+
+fn golden_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden/determinism.txt")
+}
+
+fn read_golden() -> BTreeMap<String, u64> {
+    let contents = std::fs::read_to_string(golden_path()).unwrap_or_default();
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (key, hash) = line.split_once(char::is_whitespace)?;
+            Some((key.to_string(), u64::from_str_radix(hash.trim(), 16).ok()?))
+        })
+        .collect()
+}
+
+fn write_golden(hashes: &BTreeMap<String, u64>) {
+    let mut contents = format!("# Particle state hashes after {} steps from seed {:#x}, rounded to {}\n", STEPS, SEED, QUANTUM);
+    for (key, hash) in hashes {
+        contents.push_str(&format!("{} {:016x}\n", key, hash));
+    }
+    let path = golden_path();
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+fn quantize(value: f32) -> i64 {
+    (value / QUANTUM).round() as i64
+}
+
+// FNV-1a over the quantized state, so the hash is stable across Rust versions
+fn hash_state(run: &HeadlessRun) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut feed = |value: i64| {
+        for byte in value.to_le_bytes() {
+            hash = (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    };
+    feed(run.system.particles.len() as i64);
+    for particle in &run.system.particles {
+        feed(quantize(particle.position.x));
+        feed(quantize(particle.position.y));
+        feed(quantize(particle.velocity.x));
+        feed(quantize(particle.velocity.y));
+        feed(particle.species_id as i64);
+    }
+    hash
+}
+
+fn simulate(preset: &Preset, seed: u64) -> u64 {
+    let mut manager = ConfigManager::new();
+    manager.apply_preset(preset.clone());
+    let mut config = manager.config().clone();
+    config.particles.initial_particle_count = config.particles.initial_particle_count.min(PARTICLE_CAP);
+
+    let mut run = HeadlessRun::new(preset.clone(), config, seed);
+    for _ in 0..STEPS {
+        run.step();
+    }
+    hash_state(&run)
+}
+
+#[test]
+fn test_presets_match_golden_hashes() {
+    let golden = read_golden();
+    let bless = std::env::var_os("INOCHI_BLESS").is_some();
+    let mut recorded = golden.clone();
+    let mut mismatches = Vec::new();
+
+    for preset in Preset::all() {
+        let key = format!("{:?}", preset);
+        let hash = simulate(&preset, SEED);
+        assert_eq!(hash, simulate(&preset, SEED), "{} differs between two runs with the same seed", preset.name());
+
+        if bless {
+            recorded.insert(key, hash);
+            continue;
+        }
+        match golden.get(&key) {
+            Some(&expected) if expected != hash => {
+                mismatches.push(format!("{}: expected {:016x}, got {:016x}", key, expected, hash));
+            },
+            Some(_) => {},
+            None => mismatches.push(format!("{}: no golden hash recorded", key)),
+        }
+    }
+
+    if bless && recorded != golden {
+        write_golden(&recorded);
+    }
+    assert!(
+        mismatches.is_empty(),
+        "Simulation results changed:\n{}\nRe-run with INOCHI_BLESS=1 if the change is intended",
+        mismatches.join("\n"),
+    );
+}
+
+#[test]
+fn test_seed_changes_outcome() {
+    assert_ne!(simulate(&Preset::ParticleLife, 1), simulate(&Preset::ParticleLife, 2));
+}